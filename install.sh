@@ -36,8 +36,12 @@ if [ $BINARY_EXIST_CHECK_EXIT_CODE -eq 0 ]; then
 
         if [[ "$OS_TYPE" == "Darwin" && "$ARCHITECTURE" == "x86_64" ]]; then
             curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-macos-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
+        elif [[ "$OS_TYPE" == "Darwin" && "$ARCHITECTURE" == "arm64" ]]; then
+            curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-macos-arm64-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
         elif [[ "$OS_TYPE" == "Linux" && "$ARCHITECTURE" == "x86_64" ]]; then
             curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-linux-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
+        elif [[ "$OS_TYPE" == "Linux" && ( "$ARCHITECTURE" == "aarch64" || "$ARCHITECTURE" == "arm64" ) ]]; then
+            curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-linux-aarch64-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
         else
             echo "No binary for ${OS_TYPE}/${ARCHITECTURE}."
             echo "You should clone the repository and build from source."
@@ -55,8 +59,12 @@ else
 
     if [[ "$OS_TYPE" == "Darwin" && "$ARCHITECTURE" == "x86_64" ]]; then
         curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-macos-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
+    elif [[ "$OS_TYPE" == "Darwin" && "$ARCHITECTURE" == "arm64" ]]; then
+        curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-macos-arm64-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
     elif [[ "$OS_TYPE" == "Linux" && "$ARCHITECTURE" == "x86_64" ]]; then
         curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-linux-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
+    elif [[ "$OS_TYPE" == "Linux" && ( "$ARCHITECTURE" == "aarch64" || "$ARCHITECTURE" == "arm64" ) ]]; then
+        curl -s https://github.com/anoma/namada-trusted-setup/releases/download/v${LAST_BINARY_VERSION}/${BINARY_NAME}-linux-aarch64-v${LAST_BINARY_VERSION} -L -o $BINARY_PATH
     else
         echo "No binary for ${OS_TYPE}/${ARCHITECTURE}."
         echo "You should clone the repository and build from source. Check the docs here: https://github.com/anoma/namada-trusted-setup#building-and-contributing-from-source."
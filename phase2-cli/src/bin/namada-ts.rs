@@ -1,10 +1,43 @@
 use phase2_coordinator::{
     authentication::{KeyPair, Production, Signature},
-    commands::{Computation, RandomSource, SEED_LENGTH},
+    changelog::PROTOCOL_VERSION,
+    commands::{verify_link, Computation, ComputationClock, RandomSource, SEED_LENGTH},
+    conversion,
+    environment::TEST_CIRCUITS,
+    hashing::HashAlgorithm,
     io::{self, verify_signature, KeyPairUser},
-    objects::{ContributionFileSignature, ContributionInfo, ContributionState, TrimmedContributionInfo},
-    rest_utils::{ContributorStatus, PostChunkRequest, TOKENS_ZIP_FILE, UPDATE_TIME},
-    storage::Object,
+    objects::{
+        seed_commitment,
+        AnnouncementLevel,
+        ChallengeBundle,
+        ContributionFileSignature,
+        ContributionInfo,
+        ContributionState,
+        DestructionAttestation,
+        DestructionMethod,
+        GenesisSeedStatus,
+        InjectionStatus,
+        ManualContribution,
+        OfflineContributionInfo,
+        ResponseBundle,
+        SeedCommitment,
+        SeedReveal,
+        TrimmedContributionInfo,
+        VerificationStatus,
+        CHECKPOINT_ITERATIONS,
+        GENESIS_SEED_SIZE,
+    },
+    rest_utils::{
+        AttestationPostRequest, ComputationProofRequest, ContributorStatus, DestructionAttestationRequest,
+        ManualContributionApproval, PostChunkRequest, SeedCommitmentRequest, SeedRevealRequest, TOKENS_ZIP_FILE,
+        UPDATE_TIME,
+    },
+    publication::SignedTranscriptDelta,
+    storage::{
+        compare_manifests, find_reused_contributions, find_reused_contributors, hash_chain, Divergence, Object, Reuse,
+        TranscriptFile, ANOMA_BASE_FILE_SIZE, ANOMA_PER_ROUND_FILE_SIZE_INCREASE,
+    },
+    Participant,
 };
 
 use reqwest::{Client, Url};
@@ -19,8 +52,16 @@ use ed25519_compact::{KeyPair as EdKeyPair, Seed};
 use futures_util::StreamExt;
 use phase2_cli::{
     ascii_logo::{ASCII_CONTRIBUTION_DONE, ASCII_LOGO},
+    ceremony_verification::{
+        check_file, round_links, CeremonyAttestation, CeremonyVerificationError, Link, MirrorVerdict,
+        SignedCeremonyAttestation, VerificationProgress, Verdict,
+    },
     keys::{self, EncryptedKeypair, TomlConfig},
-    requests, CeremonyOpt, CoordinatorUrl, Token, VerifySignatureContribution,
+    prevalidation,
+    requests::{self, RequestError},
+    transfer::TransferConfig,
+    CeremonyOpt, CompareCoordinators, ConvertParameters, CoordinatorUrl, DetectReuse, GenesisSeedShare,
+    InjectContribution, MirrorCeremony, Token, VerifyCeremony, VerifySignatureContribution,
 };
 use serde_json;
 use setup_utils::calculate_hash;
@@ -30,6 +71,7 @@ use std::{
     collections::HashMap,
     fs::{self, File, OpenOptions},
     io::Read,
+    path::{Path, PathBuf},
     process,
     sync::Arc,
     time::{Duration, Instant, UNIX_EPOCH},
@@ -39,6 +81,7 @@ use chrono::{DateTime, Utc};
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 
+use rand::{rngs::OsRng, RngCore};
 use regex::Regex;
 
 use tokio::{fs as async_fs, io::AsyncWriteExt, task::JoinHandle, time};
@@ -50,6 +93,15 @@ use bs58;
 
 const OFFLINE_CONTRIBUTION_FILE_NAME: &str = "contribution.params";
 const OFFLINE_CHALLENGE_FILE_NAME: &str = "challenge.params";
+const OFFLINE_CHALLENGE_BUNDLE_FILE_NAME: &str = "challenge_bundle.json";
+const OFFLINE_RESPONSE_BUNDLE_FILE_NAME: &str = "response_bundle.json";
+const VERIFICATION_POLL_TIME: Duration = Duration::from_secs(5);
+/// Time the verification of the contribution is followed for, after which the contributor is left to check its outcome.
+const VERIFICATION_FOLLOW_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Time without any event of the stream of the queue status after which the status is polled.
+const QUEUE_EVENTS_TIMEOUT: Duration = Duration::from_secs(300);
+/// Number of downloads of the files of a round not matching the manifest before the mirror objects to the round.
+const MIRROR_DOWNLOAD_ATTEMPTS: u32 = 3;
 
 const CUSTOM_SEED_MSG_NO: &str = "Enter a variable-length random string to be used as entropy in combination with your OS randomness.\nYou can type frenetically, smash your keyboard, or enter a string representation of your alternative source of entropy.\nThe only limitation is your terminal’s max command length.\nThis string will be hashed together with your OS randomness to produce the seed for ChaCha RNG";
 const CUSTOM_SEED_MSG_YES: &str = "Provide your custom random seed to initialize the ChaCha random number generator.\nYour seed might come from an external source of randomness like atmospheric noise, radioactive elements, lava lite etc. or an airgapped machine.";
@@ -81,16 +133,28 @@ fn initialize_contribution() -> Result<ContributionInfo> {
     )?
     .to_lowercase();
 
-    // Ask for personal info
+    // Ask for personal info, until it can be published as it is
     if anonymous == "n" {
-        contrib_info.full_name = Some(io::get_user_input(
-            "Please enter your full name (first and last name):".bright_yellow(),
-            Some(&Regex::new(r"(.|\s)*\S(.|\s)*")?),
-        )?);
-        contrib_info.email = Some(io::get_user_input(
-            "Please enter your email address:".bright_yellow(),
-            Some(&Regex::new(r".+[@].+[.].+")?),
-        )?);
+        loop {
+            contrib_info.full_name = Some(io::get_user_input(
+                "Please enter your full name (first and last name):".bright_yellow(),
+                Some(&Regex::new(r"(.|\s)*\S(.|\s)*")?),
+            )?);
+            contrib_info.email = Some(io::get_user_input(
+                "Please enter your email address:".bright_yellow(),
+                Some(&Regex::new(r".+[@].+[.].+")?),
+            )?);
+            let language = io::get_user_input(
+                "Please enter the language of your name, e.g. en, ja or ar (leave empty to skip):".bright_yellow(),
+                None,
+            )?;
+            contrib_info.language = Some(language).filter(|language| !language.is_empty());
+
+            match contrib_info.validate() {
+                Ok(()) => break,
+                Err(e) => println!("{}", e.to_string().red().bold()),
+            }
+        }
     }
 
     Ok(contrib_info)
@@ -126,13 +190,14 @@ fn get_progress_bar(len: u64) -> ProgressBar {
 
 /// Contest and offline execution branches
 #[inline(always)]
-fn compute_contribution_offline() -> Result<()> {
+fn compute_contribution_offline(deadline: DateTime<Utc>) -> Result<()> {
     // Print instructions to the user
     let mut msg = format!("{}\n\n", "Instructions".bold().underline().bright_cyan(),);
     msg.push_str(format!("{}",format!(
-        "In the current working directory, you can find the challenge file \"{}\" and contribution file \"{}\".\nTo contribute, you will need both files.\n",
-        OFFLINE_CONTRIBUTION_FILE_NAME,
-        OFFLINE_CHALLENGE_FILE_NAME
+        "In the current working directory, you can find the challenge file \"{}\", the challenge bundle \"{}\" and contribution file \"{}\".\nTo contribute, you will need all these files.\n",
+        OFFLINE_CHALLENGE_FILE_NAME,
+        OFFLINE_CHALLENGE_BUNDLE_FILE_NAME,
+        OFFLINE_CONTRIBUTION_FILE_NAME
     ).as_str().bright_cyan()).as_str());
     msg.push_str(
         format!(
@@ -143,16 +208,17 @@ fn compute_contribution_offline() -> Result<()> {
     );
     msg.push_str(
         format!("{}",format!(
-        "{:4}1) Copy both the challenge file \"{}\" and contribution file \"{}\" in the directory where you will execute the offline command\n",
+        "{:4}1) Copy the challenge file \"{}\", the challenge bundle \"{}\" and contribution file \"{}\" in the directory where you will execute the offline command\n",
         "",
         OFFLINE_CHALLENGE_FILE_NAME,
+        OFFLINE_CHALLENGE_BUNDLE_FILE_NAME,
         OFFLINE_CONTRIBUTION_FILE_NAME
     ).as_str().bright_cyan()).as_str());
     msg.push_str(
         format!(
             "{}",
             format!(
-                "{:4}2) Execute the command \"cargo run --release --bin phase2 --features=cli contribute offline\"\n",
+                "{:4}2) Execute the command \"namada-ts contribute offline\", you will be asked for your mnemonic to sign the response bundle\n",
                 ""
             )
             .as_str()
@@ -164,8 +230,8 @@ fn compute_contribution_offline() -> Result<()> {
         format!(
             "{}",
             format!(
-                "{:4}3) Copy the contribution file \"{}\" back to this directory (by overwriting the previous file)",
-                "", OFFLINE_CONTRIBUTION_FILE_NAME
+                "{:4}3) Copy the contribution file \"{}\" (by overwriting the previous file) and the response bundle \"{}\" back to this directory before {}",
+                "", OFFLINE_CONTRIBUTION_FILE_NAME, OFFLINE_RESPONSE_BUNDLE_FILE_NAME, deadline.to_rfc2822()
             )
             .as_str()
             .bright_cyan()
@@ -175,7 +241,7 @@ fn compute_contribution_offline() -> Result<()> {
     println!("{}", msg.bright_cyan());
 
     // Wait for the contribution file to be updated with randomness
-    // NOTE: we don't actually check for the timeout on the 15 minutes. If the user takes more time than allowed to produce the file we'll keep going on in the contribution, at the following request the Coordinator will reply with an error because the contributor has been dropped out of the ceremony
+    // NOTE: we don't actually check for the deadline. If the user takes more time than allowed to produce the file we'll keep going on in the contribution, at the following request the Coordinator will reply with an error because the contributor has been dropped out of the ceremony
     io::get_user_input(
         "When your contribution file is ready, press enter to upload it".bright_yellow(),
         None,
@@ -210,9 +276,9 @@ fn compute_contribution(custom_seed: bool, challenge: &[u8], filename: &str) ->
 
     let writer = OpenOptions::new().append(true).open(filename)?;
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "rehearsal"))]
     Computation::contribute_test_masp(challenge, writer, &rand_source);
-    #[cfg(not(debug_assertions))]
+    #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
     Computation::contribute_masp(challenge, writer, &rand_source);
 
     println!(
@@ -224,6 +290,33 @@ fn compute_contribution(custom_seed: bool, challenge: &[u8], filename: &str) ->
     Ok(())
 }
 
+/// Signs the contribution computed on the air-gapped machine and writes the response bundle to carry back to the online
+/// machine
+fn sign_response_bundle(challenge_bundle: &ChallengeBundle, challenge_hash: &[u8]) -> Result<()> {
+    let contribution = fs::read(OFFLINE_CONTRIBUTION_FILE_NAME)?;
+    let contribution_state =
+        ContributionState::new(challenge_hash.to_vec(), calculate_hash(&contribution).to_vec(), None)?;
+
+    let keypair = io::keypair_from_user_mnemonic()?;
+    let signature = Production.sign(keypair.sigkey(), &contribution_state.signature_message()?)?;
+    let response_bundle = ResponseBundle::new(
+        challenge_bundle,
+        ContributionFileSignature::new(signature, contribution_state)?,
+    );
+    fs::write(OFFLINE_RESPONSE_BUNDLE_FILE_NAME, serde_json::to_vec(&response_bundle)?)?;
+
+    println!(
+        "{}",
+        format!(
+            "The response bundle has been written to \"{}\". Copy it back to the online machine together with the contribution file \"{}\"",
+            OFFLINE_RESPONSE_BUNDLE_FILE_NAME, OFFLINE_CONTRIBUTION_FILE_NAME
+        )
+        .green()
+        .bold()
+    );
+    Ok(())
+}
+
 /// Performs the contribution sequence. Returns the round height of the contribution.
 #[inline(always)]
 async fn contribute(
@@ -238,28 +331,29 @@ async fn contribute(
     let locked_locators = requests::get_lock_chunk(client, coordinator, keypair).await?;
     contrib_info.timestamps.challenge_locked = Utc::now();
     let end_lock_time = contrib_info.timestamps.challenge_locked + chrono::Duration::minutes(20);
-    println!(
-        "{}",
-        format!("From now on, you will have a maximum of 20 minutes to contribute and upload your contribution after which you will be dropped out of the ceremony!\nYour time starts now on {} and ends in 20 minutes on {}  \nHave fun!",
-        contrib_info.timestamps.challenge_locked.to_rfc2822(),
-        end_lock_time.to_rfc2822()).bright_cyan()
-    );
+    if contrib_info.is_another_machine {
+        println!(
+            "{}",
+            "You will be given the deadline to upload your contribution computed offline together with the challenge bundle".bright_cyan()
+        );
+    } else {
+        println!(
+            "{}",
+            format!("From now on, you will have a maximum of 20 minutes to contribute and upload your contribution after which you will be dropped out of the ceremony!\nYour time starts now on {} and ends in 20 minutes on {}  \nHave fun!",
+            contrib_info.timestamps.challenge_locked.to_rfc2822(),
+            end_lock_time.to_rfc2822()).bright_cyan()
+        );
+    }
+    if !locked_locators.circuits().is_empty() {
+        let circuits: Vec<String> = locked_locators.circuits().iter().map(|c| c.to_string()).collect();
+        println!("Contributing to circuits: {}", circuits.join(", "));
+    }
     let response_locator = locked_locators.next_contribution();
     let round_height = response_locator.round_height();
     contrib_info.ceremony_round = round_height;
 
     let challenge_url = requests::get_challenge_url(client, coordinator, keypair, &round_height).await?;
     println!("{} Getting challenge", "[5/11]".bold().dimmed());
-    let mut challenge_stream = requests::get_challenge(client, challenge_url.as_str()).await?;
-    let progress_bar = get_progress_bar(challenge_stream.1);
-    let mut challenge: Vec<u8> = Vec::new();
-    while let Some(b) = challenge_stream.0.next().await {
-        let b = b?;
-        challenge.extend_from_slice(&b);
-        progress_bar.inc(b.len() as u64);
-    }
-    progress_bar.finish();
-    contrib_info.timestamps.challenge_downloaded = Utc::now();
 
     // Saves the challenge locally, in case the contributor is paranoid and wants to double check himself. It is also used in the offline contrib path
     let challenge_filename = if contrib_info.is_another_machine {
@@ -267,13 +361,63 @@ async fn contribute(
     } else {
         format!("namada_challenge_round_{}.params", round_height)
     };
-    let mut challenge_writer = async_fs::File::create(challenge_filename.as_str()).await?;
-    challenge_writer.write_all(&challenge.as_slice()).await?;
+    // Downloaded in ranges adapted to the bandwidth, resumed on a restart of the client
+    let challenge_size = locked_locators.sizes().map(|sizes| sizes.challenge);
+    let progress_bar = get_progress_bar(challenge_size.unwrap_or_default());
+    requests::download_ranges(
+        client,
+        challenge_url.as_str(),
+        Path::new(&challenge_filename),
+        TransferConfig::default(),
+        |downloaded| progress_bar.set_position(downloaded),
+    )
+    .await?;
+    progress_bar.finish();
+    let mut challenge = async_fs::read(challenge_filename.as_str()).await?;
+    contrib_info.timestamps.challenge_downloaded = Utc::now();
 
     let challenge_hash = calculate_hash(challenge.as_ref());
     debug!("Challenge hash is {}", pretty_hash!(&challenge_hash));
     debug!("Challenge length {}", challenge.len());
 
+    // Detect a corrupted or tampered challenge before spending time on the contribution. Coordinators predating the
+    // transcript manifests don't publish the hashes of the challenges
+    match requests::get_transcript_manifests(client, coordinator, round_height.saturating_sub(1), round_height).await {
+        Ok(manifests) => {
+            let challenge_locator = locked_locators.current_contribution();
+            let expected_circuits = Some(locked_locators.circuits().len()).filter(|circuits| *circuits > 0);
+            let (returned_challenge, validation) = tokio::task::spawn_blocking(move || {
+                let validation =
+                    prevalidation::prevalidate_challenge(&challenge, &challenge_locator, &manifests, expected_circuits);
+                (challenge, validation)
+            })
+            .await?;
+            validation?;
+            challenge = returned_challenge;
+            debug!("Challenge matches the transcript of the coordinator");
+        }
+        Err(e) => debug!("Skipping the validation of the challenge: {}", e),
+    }
+
+    // Switch to the offline flow, which relaxes the timeout, and save the bundle to carry to the air-gapped machine
+    let challenge_bundle = if contrib_info.is_another_machine {
+        let challenge_bundle = requests::get_challenge_bundle(client, coordinator, keypair).await?;
+        if challenge_bundle.challenge_hash != hex::encode(challenge_hash) {
+            return Err(anyhow::anyhow!(
+                "The downloaded challenge doesn't match the challenge bundle"
+            ));
+        }
+        async_fs::write(
+            OFFLINE_CHALLENGE_BUNDLE_FILE_NAME,
+            serde_json::to_vec(&challenge_bundle)?,
+        )
+        .await?;
+        Some(challenge_bundle)
+    } else {
+        None
+    };
+    let challenge_bundle_downloaded = Utc::now();
+
     // Prepare contribution file with the challege hash
     println!("{} Setting up contribution file", "[6/11]".bold().dimmed());
     let contrib_filename = if contrib_info.is_another_machine {
@@ -293,8 +437,10 @@ async fn contribute(
 
     let contrib_filename_copy = contrib_filename.clone();
     contrib_info.timestamps.start_computation = Utc::now();
-    if contrib_info.is_another_machine {
-        tokio::task::spawn_blocking(move || compute_contribution_offline()).await??;
+    let computation_proof = if let Some(challenge_bundle) = &challenge_bundle {
+        let deadline = DateTime::<Utc>::from(std::time::SystemTime::from(challenge_bundle.deadline));
+        tokio::task::spawn_blocking(move || compute_contribution_offline(deadline)).await??;
+        None
     } else {
         let custom_seed = contrib_info.is_own_seed_of_randomness;
         if custom_seed {
@@ -302,11 +448,14 @@ async fn contribute(
         } else {
             println!("{}", CUSTOM_SEED_MSG_NO.bright_cyan());
         }
+        // Chain hashes from the challenge hash while computing, to prove the time the computation took
+        let clock = ComputationClock::start(&challenge_hash, CHECKPOINT_ITERATIONS);
         tokio::task::spawn_blocking(move || {
             compute_contribution(custom_seed, challenge.as_ref(), contrib_filename_copy.as_str())
         })
         .await??;
-    }
+        Some(tokio::task::spawn_blocking(move || clock.stop()).await?)
+    };
     let contrib_filename_copy = contrib_filename.clone();
     let contribution = tokio::task::spawn_blocking(move || {
         get_file_as_byte_vec(
@@ -343,48 +492,96 @@ async fn contribute(
     contrib_info.contribution_hash_signature =
         Production.sign(keypair.sigkey(), contrib_info.contribution_hash.as_str())?;
 
-    // Send contribution to the coordinator
-    let contribution_state = ContributionState::new(challenge_hash.to_vec(), contribution_file_hash.to_vec(), None)?;
-
-    let signature = Production.sign(keypair.sigkey(), &contribution_state.signature_message()?)?;
-    let contribution_file_signature = ContributionFileSignature::new(signature, contribution_state)?;
-
-    let (contribution_url, contribution_signature_url) =
-        requests::get_contribution_url(client, coordinator, keypair, &round_height).await?;
-    println!("{} Uploading contribution", "[9/11]".bold().dimmed());
-    let contrib_file = async_fs::File::open(contrib_filename.as_str()).await?;
-    let contrib_size = async_fs::metadata(contrib_filename.as_str()).await?.len();
-    let mut stream = ReaderStream::new(contrib_file);
-    let pb = get_progress_bar(contrib_size);
-    let pb_clone = pb.clone();
+    // Send contribution to the coordinator. In the offline flow the signature comes from the air-gapped machine
+    let response_bundle = match &challenge_bundle {
+        Some(challenge_bundle) => {
+            let response_bundle: ResponseBundle =
+                serde_json::from_slice(&async_fs::read(OFFLINE_RESPONSE_BUNDLE_FILE_NAME).await?)?;
+            if !response_bundle.answers(challenge_bundle)
+                || response_bundle.contribution_file_signature.get_response_hash()
+                    != contrib_info.contribution_file_hash
+            {
+                return Err(anyhow::anyhow!(
+                    "The response bundle doesn't match the challenge bundle or the contribution file"
+                ));
+            }
 
-    let contrib_stream = try_stream! {
-        while let Some(b) = stream.next().await {
-            let b = b?;
-            pb.inc(b.len() as u64);
-            yield b;
+            contrib_info.offline_contribution = Some(OfflineContributionInfo {
+                challenge_hash: challenge_bundle.challenge_hash.clone(),
+                deadline: DateTime::<Utc>::from(std::time::SystemTime::from(challenge_bundle.deadline)),
+                challenge_bundle_downloaded,
+                response_bundle_received: Utc::now(),
+            });
+            Some(response_bundle)
+        }
+        None => None,
+    };
+    let contribution_file_signature = match &response_bundle {
+        Some(response_bundle) => response_bundle.contribution_file_signature.clone(),
+        None => {
+            let contribution_state =
+                ContributionState::new(challenge_hash.to_vec(), contribution_file_hash.to_vec(), None)?;
+            let signature = Production.sign(keypair.sigkey(), &contribution_state.signature_message()?)?;
+            ContributionFileSignature::new(signature, contribution_state)?
         }
     };
 
-    requests::upload_chunk(
+    // The coordinator fetches from S3 the contributions answering a response bundle
+    println!("{} Uploading contribution", "[9/11]".bold().dimmed());
+    let streamed = upload_contribution(
         client,
-        contribution_url.as_str(),
-        contribution_signature_url.as_str(),
-        contrib_stream,
-        contrib_size,
+        coordinator,
+        keypair,
+        round_height,
+        contrib_filename.as_str(),
         &contribution_file_signature,
+        response_bundle.is_none(),
     )
     .await?;
-    pb_clone.finish();
     contrib_info.timestamps.end_contribution = Utc::now();
 
+    // The proof of computation time is informative: older coordinators don't take it, which mustn't fail the
+    // contribution
+    if let Some(proof) = computation_proof {
+        let request = ComputationProofRequest { round_height, proof };
+        match requests::post_computation_proof(client, coordinator, keypair, &request).await {
+            Ok(iterations) => debug!("The coordinator accepted a proof of {} iterations", iterations),
+            Err(e) => debug!("The coordinator didn't accept the proof of computation time: {}", e),
+        }
+    }
+
     // Compute signature of contributor info
     contrib_info
         .try_sign(keypair)
         .expect(&format!("{}", "Error while signing the contribution info".red().bold()));
 
-    // Write contribution info file and send it to the Coordinator
-    println!("{} Uploading contribution info", "[10/11]".bold().dimmed());
+    // Notify contribution to the coordinator for the verification
+    println!(
+        "{} Notifying the coordinator of your uploaded contribution.\nYour contribution is being processed... This might take a minute...",
+        "[10/11]".bold().dimmed()
+    );
+    match response_bundle {
+        Some(response_bundle) => requests::post_response_bundle(client, coordinator, keypair, &response_bundle).await?,
+        None => {
+            let post_chunk_req = match streamed {
+                true => PostChunkRequest::spooled(
+                    round_height,
+                    locked_locators.next_contribution(),
+                    locked_locators.next_contribution_file_signature(),
+                    contribution_file_signature,
+                ),
+                false => PostChunkRequest::new(
+                    round_height,
+                    locked_locators.next_contribution(),
+                    locked_locators.next_contribution_file_signature(),
+                ),
+            };
+            requests::post_contribute_chunk(client, coordinator, keypair, &post_chunk_req).await?;
+        }
+    }
+
+    // Write contribution info file and send it to the Coordinator, which checks it against the received contribution
+    println!("{} Uploading contribution info", "[11/11]".bold().dimmed());
     async_fs::write(
         format!("namada_contributor_info_round_{}.json", contrib_info.ceremony_round),
         &serde_json::to_vec(&contrib_info)?,
@@ -392,18 +589,6 @@ async fn contribute(
     .await?;
     requests::post_contribution_info(client, coordinator, keypair, &contrib_info).await?;
 
-    // Notify contribution to the coordinator for the verification
-    println!(
-        "{} Notifying the coordinator of your uploaded contribution.\nYour contribution is being processed... This might take a minute...",
-        "[11/11]".bold().dimmed()
-    );
-    let post_chunk_req = PostChunkRequest::new(
-        round_height,
-        locked_locators.next_contribution(),
-        locked_locators.next_contribution_file_signature(),
-    );
-    requests::post_contribute_chunk(client, coordinator, keypair, &post_chunk_req).await?;
-
     // Interrupt heartbeat, to prevent heartbeating during verification
     // NOTE: need to manually cancel the heartbeat task because, by default, async runtimes use detach on drop strategy
     //  (see https://blog.yoshuawuyts.com/async-cancellation-1/#cancelling-tasks), meaning that the task
@@ -413,9 +598,144 @@ async fn contribute(
     //  We don't need to await the hearbeat future
     heartbeat_handle.abort();
 
+    // Follow the verification of the contribution
+    follow_verification(client, coordinator, keypair).await;
+
     Ok(round_height)
 }
 
+/// Uploads the contribution file to the coordinator, in ranges sized to the bandwidth and resumed after a failure, or
+/// the contribution file and its signature to the urls provided by the coordinator when `stream` is false or the
+/// coordinator predates the ranged uploads. Returns whether the contribution was streamed to the coordinator, in which
+/// case its signature is posted to `contribute_chunk`
+async fn upload_contribution(
+    client: &Client,
+    coordinator: &Url,
+    keypair: &KeyPair,
+    round_height: u64,
+    contrib_filename: &str,
+    contribution_file_signature: &ContributionFileSignature,
+    stream: bool,
+) -> Result<bool> {
+    if stream {
+        let contrib_size = async_fs::metadata(contrib_filename).await?.len();
+        let pb = get_progress_bar(contrib_size);
+        match requests::upload_contribution_ranges(
+            client,
+            coordinator,
+            keypair,
+            Path::new(contrib_filename),
+            TransferConfig::default(),
+            |received| pb.set_position(received),
+        )
+        .await
+        {
+            Ok(hash) => {
+                pb.finish();
+                if hash != contribution_file_signature.get_response_hash() {
+                    return Err(anyhow::anyhow!(
+                        "The coordinator received a contribution with hash {} instead of {}",
+                        hash,
+                        contribution_file_signature.get_response_hash()
+                    ));
+                }
+                return Ok(true);
+            }
+            Err(RequestError::NotFound(e)) => {
+                pb.finish_and_clear();
+                debug!("The coordinator doesn't take the contribution in ranges: {}", e);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let (contribution_url, contribution_signature_url) =
+        requests::get_contribution_url(client, coordinator, keypair, &round_height).await?;
+
+    upload_contribution_to(
+        client,
+        &contribution_url,
+        &contribution_signature_url,
+        contrib_filename,
+        contribution_file_signature,
+    )
+    .await?;
+
+    Ok(false)
+}
+
+/// Uploads the contribution file and its signature to the given urls
+async fn upload_contribution_to(
+    client: &Client,
+    contribution_url: &str,
+    contribution_signature_url: &str,
+    contrib_filename: &str,
+    contribution_file_signature: &ContributionFileSignature,
+) -> Result<()> {
+    let contrib_file = async_fs::File::open(contrib_filename).await?;
+    let contrib_size = async_fs::metadata(contrib_filename).await?.len();
+    let mut stream = ReaderStream::new(contrib_file);
+    let pb = get_progress_bar(contrib_size);
+    let pb_clone = pb.clone();
+
+    let contrib_stream = try_stream! {
+        while let Some(b) = stream.next().await {
+            let b = b?;
+            pb.inc(b.len() as u64);
+            yield b;
+        }
+    };
+
+    requests::upload_chunk(
+        client,
+        contribution_url,
+        contribution_signature_url,
+        contrib_stream,
+        contrib_size,
+        contribution_file_signature,
+    )
+    .await?;
+    pb_clone.finish();
+
+    Ok(())
+}
+
+/// Polls the coordinator until the verification of the contribution is over, or for at most
+/// [`VERIFICATION_FOLLOW_TIMEOUT`]. The failures to get the status are retried until then.
+async fn follow_verification(client: &Client, coordinator: &Url, keypair: &KeyPair) {
+    let deadline = Instant::now() + VERIFICATION_FOLLOW_TIMEOUT;
+    loop {
+        match requests::get_verification_status(client, coordinator, keypair).await {
+            Ok(Some(VerificationStatus::Pending)) => debug!("Contribution is waiting to be verified"),
+            Ok(Some(VerificationStatus::Running(phase))) => println!("Verification of your contribution: {}", phase),
+            Ok(Some(VerificationStatus::Succeeded)) => {
+                println!("{}", "Your contribution has been verified".green().bold());
+                return;
+            }
+            Ok(Some(VerificationStatus::Failed(reason))) => {
+                eprintln!(
+                    "{}",
+                    format!("Verification of your contribution failed: {}", reason).red().bold()
+                );
+                return;
+            }
+            // The coordinator forgets the statuses of the verifications when restarted
+            Ok(None) => debug!("The status of the verification of the contribution is unknown"),
+            // The contribution is uploaded already, the failures to follow its verification are only reported
+            Err(e) => debug!("Couldn't get the status of the verification of the contribution: {}", e),
+        }
+
+        if Instant::now() >= deadline {
+            println!(
+                "{}",
+                "The verification of your contribution is still pending, its outcome will be published in the transcript of the ceremony".yellow()
+            );
+            return;
+        }
+        time::sleep(VERIFICATION_POLL_TIME).await;
+    }
+}
+
 /// Waits in line until it's time to contribute
 #[inline(always)]
 async fn contribution_loop(
@@ -427,11 +747,14 @@ async fn contribution_loop(
 ) {
     println!("{} Joining queue", "[3/11]".bold().dimmed());
 
-    let cohort = requests::post_join_queue(&client, &coordinator, &keypair, &token)
+    let joined = requests::post_join_queue(&client, &coordinator, &keypair, &token)
         .await
         .expect(&format!("{}", "Couldn't join the queue".red().bold()));
+    if let Some(warning) = &joined.warning {
+        println!("{}", warning.yellow());
+    }
     contrib_info.timestamps.joined_queue = Utc::now();
-    contrib_info.joined_cohort = cohort;
+    contrib_info.joined_cohort = joined.cohort;
 
     // Spawn heartbeat task to prevent the Coordinator from
     // dropping the contributor out of the ceremony in the middle of a contribution.
@@ -467,6 +790,15 @@ async fn contribution_loop(
         _ => {}
     }
 
+    // Wait for the changes of the status pushed by the coordinator instead of polling, if it streams them
+    let mut queue_events = match requests::get_contributor_queue_events(&client, &coordinator, &keypair).await {
+        Ok(events) => Some(Box::pin(events)),
+        Err(e) => {
+            debug!("Polling the queue status, the coordinator doesn't stream it: {}", e);
+            None
+        }
+    };
+
     loop {
         // Check the contributor's position in the queue
         let queue_status = requests::get_contributor_queue_status(&client, &coordinator, &keypair)
@@ -497,10 +829,38 @@ async fn contribution_loop(
                 );
                 status_count += 1;
             }
-            ContributorStatus::Round => {
-                round_height = contribute(&client, &coordinator, &keypair, contrib_info.clone(), &heartbeat_handle)
-                    .await
-                    .expect(&format!("{}", "Contribution failed".red().bold()));
+            ContributorStatus::Round | ContributorStatus::Restart => {
+                if let ContributorStatus::Restart = queue_status {
+                    println!(
+                        "{}",
+                        "The coordinator reissued the challenge, restarting the contribution"
+                            .yellow()
+                            .bold()
+                    );
+                }
+
+                match contribute(&client, &coordinator, &keypair, contrib_info.clone(), &heartbeat_handle).await {
+                    Ok(height) => round_height = height,
+                    Err(e) => {
+                        // The contribution may have been interrupted by a challenge reissue, in which case it must be restarted
+                        match requests::get_contributor_queue_status(&client, &coordinator, &keypair).await {
+                            Ok(ContributorStatus::Restart) => {
+                                eprintln!("{}: {}", "Contribution interrupted".yellow().bold(), e)
+                            }
+                            // The lock was refused until the verifications catch up
+                            Ok(ContributorStatus::VerificationBacklog(_)) => {}
+                            _ => panic!("{}: {}", "Contribution failed".red().bold(), e),
+                        }
+                    }
+                }
+            }
+            ContributorStatus::VerificationBacklog(backlog) => {
+                println!(
+                    "{} {} {}",
+                    "The coordinator is catching up with the verification of".yellow().bold(),
+                    backlog,
+                    "contributions, your contribution will start as soon as it's done".yellow().bold()
+                );
             }
             ContributorStatus::Finished => {
                 let content = fs::read(&format!("namada_contributor_info_round_{}.json", round_height))
@@ -516,6 +876,15 @@ async fn contribution_loop(
                                 );
                 println!("{}\n", ASCII_CONTRIBUTION_DONE.bright_yellow());
 
+                // Destruction of the toxic waste
+                if let Err(e) = attest_destruction(&client, &coordinator, &keypair, &contrib_info).await {
+                    println!(
+                        "{} {}",
+                        "Couldn't attest the destruction of the randomness of your contribution:".yellow(),
+                        e
+                    );
+                }
+
                 // Attestation
                 if "n"
                     == io::get_user_input(
@@ -534,15 +903,37 @@ async fn contribution_loop(
                         )
                         .unwrap();
                         if Url::parse(attestation_url.as_str()).is_ok() {
-                            // Send attestation to coordinator
-                            requests::post_attestation(
-                                &client,
-                                &coordinator,
-                                &keypair,
-                                &(round_height, attestation_url),
-                            )
-                            .await
-                            .expect(&format!("{}", "Failed attestation upload".red().bold()));
+                            // Have the coordinator archive the content of the post, so that later edits are detectable
+                            let archived = match requests::get_attestation_post_hash(&client, &attestation_url).await {
+                                Ok(content_hash) => requests::post_attestation_post(
+                                    &client,
+                                    &coordinator,
+                                    &keypair,
+                                    &AttestationPostRequest::new(round_height, attestation_url.clone(), content_hash),
+                                )
+                                .await
+                                .map_err(|e| e.to_string()),
+                                Err(e) => Err(e.to_string()),
+                            };
+
+                            if let Err(e) = archived {
+                                println!(
+                                    "{} {}",
+                                    "Couldn't archive the content of the attestation, registering only its url:"
+                                        .yellow(),
+                                    e
+                                );
+
+                                // Send attestation to coordinator
+                                requests::post_attestation(
+                                    &client,
+                                    &coordinator,
+                                    &keypair,
+                                    &(round_height, attestation_url),
+                                )
+                                .await
+                                .expect(&format!("{}", "Failed attestation upload".red().bold()));
+                            }
                             return;
                         }
                     }
@@ -563,11 +954,70 @@ async fn contribution_loop(
             }
         }
 
-        // Get status updates
-        time::sleep(UPDATE_TIME).await;
+        // Get status updates. Without event for a while, the status is polled anyway in case the stream silently broke
+        let streamed = match queue_events.as_mut() {
+            Some(events) => match time::timeout(QUEUE_EVENTS_TIMEOUT, events.next()).await {
+                Ok(Some(Ok(_))) | Err(_) => true,
+                Ok(Some(Err(e))) => {
+                    debug!("The stream of the queue status broke, polling it: {}", e);
+                    false
+                }
+                Ok(None) => false,
+            },
+            None => false,
+        };
+        if !streamed {
+            queue_events = None;
+            time::sleep(UPDATE_TIME).await;
+        }
     }
 }
 
+/// Asks the contributor how the randomness of its contribution, the toxic waste, was destroyed, and sends the signed
+/// declaration to the coordinator
+async fn attest_destruction(
+    client: &Client,
+    coordinator: &Url,
+    keypair: &KeyPair,
+    contrib_info: &ContributionInfo,
+) -> Result<()> {
+    let methods = DestructionMethod::ALL
+        .iter()
+        .map(DestructionMethod::name)
+        .collect::<Vec<_>>()
+        .join("|");
+    let method = io::get_user_input(
+        format!(
+            "How was the randomness of your contribution destroyed? The default contribution, computed in memory, is \
+             destroyed when the process exits [{}]",
+            methods.replace('|', "/")
+        )
+        .bright_yellow(),
+        Some(&Regex::new(&format!("^({})$", methods)).unwrap()),
+    )?;
+    let details = io::get_user_input(
+        "Please describe how the randomness was destroyed (required for other, press enter to skip):".bright_yellow(),
+        None,
+    )?;
+
+    let attestation = DestructionAttestation {
+        round_height: contrib_info.ceremony_round,
+        contribution_hash: contrib_info.contribution_file_hash.clone(),
+        method: method.parse().map_err(anyhow::Error::msg)?,
+        details: if details.is_empty() { None } else { Some(details) },
+    };
+    attestation.validate()?;
+    let signature = Production.sign(keypair.sigkey(), &attestation.message()?)?;
+
+    Ok(requests::post_destruction_attestation(
+        client,
+        coordinator,
+        keypair,
+        &DestructionAttestationRequest { attestation, signature },
+    )
+    .await?)
+}
+
 #[inline(always)]
 async fn close_ceremony(client: &Client, coordinator: &Url, keypair: &KeyPair) {
     match requests::get_stop_coordinator(client, coordinator, keypair).await {
@@ -599,6 +1049,36 @@ async fn get_coordinator_state(coordinator: &Url, secret: &str) {
     }
 }
 
+#[inline(always)]
+async fn get_participants_history(coordinator: &Url, secret: &str) {
+    match requests::get_participants_history(coordinator, secret).await {
+        Ok(history) => {
+            let history_str = std::str::from_utf8(&history).unwrap();
+            println!("Participants history:\n{}", history_str)
+        }
+        Err(e) => eprintln!("{}", e.to_string().red().bold()),
+    }
+}
+
+#[inline(always)]
+async fn get_storage_shards(coordinator: &Url, secret: &str) {
+    match requests::get_storage_shards(coordinator, secret).await {
+        Ok(shards) => {
+            let shards_str = std::str::from_utf8(&shards).unwrap();
+            println!("Storage shards:\n{}", shards_str)
+        }
+        Err(e) => eprintln!("{}", e.to_string().red().bold()),
+    }
+}
+
+#[inline(always)]
+async fn rebalance_storage(coordinator: &Url, secret: &str) {
+    match requests::post_rebalance_storage(coordinator, secret).await {
+        Ok(moved) => println!("{}", format!("Storage rebalanced, {} objects moved", moved).green().bold()),
+        Err(e) => eprintln!("{}", e.to_string().red().bold()),
+    }
+}
+
 #[cfg(debug_assertions)]
 #[inline(always)]
 async fn verify_contributions(client: &Client, coordinator: &Url, keypair: &KeyPair) {
@@ -629,55 +1109,774 @@ async fn update_cohorts(client: &Client, coordinator: &Url, keypair: &KeyPair) {
     }
 }
 
-enum Branch {
-    AnotherMachine,
-    Default(bool),
-}
+/// Approves, as an operator, the injection of a contribution computed out-of-band, uploading it first unless already
+/// uploaded by another operator
+async fn inject_contribution(
+    client: &Client,
+    keypair: &KeyPair,
+    inject: &InjectContribution,
+) -> Result<InjectionStatus> {
+    let contrib_filename = inject.contribution.to_string_lossy().to_string();
+    let contribution_file_signature: ContributionFileSignature =
+        serde_json::from_slice(&async_fs::read(&inject.signature).await?)?;
+
+    if !inject.skip_upload {
+        let (contribution_url, contribution_signature_url) = requests::get_manual_contribution_url(
+            client,
+            &inject.url.coordinator,
+            keypair,
+            &inject.round_height,
+        )
+        .await?;
+        upload_contribution_to(
+            client,
+            &contribution_url,
+            &contribution_signature_url,
+            &contrib_filename,
+            &contribution_file_signature,
+        )
+        .await?;
+    }
 
-/// Performs the entire contribution cycle
-#[inline(always)]
-async fn contribution_prelude(url: CoordinatorUrl, token: String, branch: Branch) {
-    // Check the token info
-    let decoded_bytes = bs58::decode(token.clone()).into_vec();
-    if let Ok(token_bytes) = decoded_bytes {
-        let decoded_token = String::from_utf8(token_bytes).expect("Can't decode the token");
-        let token_data: Token = serde_json::from_str(&decoded_token).expect("Can't deserialize the token.");
-        match token_data.is_valid_cohort() {
-            phase2_cli::TokenCohort::Finished => {
-                println!("Your cohort round is {} and is already completed.", token_data.index);
-                process::exit(0);
-            }
-            phase2_cli::TokenCohort::Pending => {
-                let token_from_datetime = DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(token_data.from));
-                let token_to_datetime = DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(token_data.to));
-                println!(
-                    "Your cohort round is {} and will start at {} and finish at {}.",
-                    token_data.index, token_from_datetime, token_to_datetime
-                );
-                process::exit(0);
-            }
-            _ => (),
-        }
-    } else {
-        eprintln!("{}", "The token provided is not base58 encoded.".red().bold());
-        process::exit(0);
+    let contribution_hash = tokio::task::spawn_blocking(move || -> Result<String> {
+        Ok(hex::encode(calculate_hash(&fs::read(contrib_filename)?)))
+    })
+    .await??;
+    let contribution = ManualContribution {
+        participant: Participant::new_contributor(&inject.participant),
+        round_height: inject.round_height,
+        contribution_hash,
+        reason: inject.reason.clone(),
     };
+    let signature = Production.sign(keypair.sigkey(), &contribution.message()?)?;
+    let approval = ManualContributionApproval { contribution, signature };
 
-    // Check that the passed-in coordinator url is correct
-    let client = Client::new();
-    if requests::ping_coordinator(&client, &url.coordinator)
-        .await.is_err() {
-            eprintln!("{}", "ERROR: could not contact the Coordinator, please check the url you provided".red().bold());
-            process::exit(1);
-        };
+    Ok(requests::post_manual_contribution(client, &inject.url.coordinator, keypair, &approval).await?)
+}
 
-    println!("{}", ASCII_LOGO.bright_yellow());
-    println!("{}", "Welcome to the Namada Trusted Setup Ceremony!".bold());
+/// Draws, as an operator, a random share of the seed of the genesis challenge, saved to the share file to be revealed
+/// later, and commits to it
+async fn commit_genesis_seed(
+    client: &Client,
+    keypair: &KeyPair,
+    share: &GenesisSeedShare,
+) -> Result<GenesisSeedStatus> {
+    let mut seed_share = [0u8; GENESIS_SEED_SIZE];
+    OsRng.fill_bytes(&mut seed_share);
+
+    // Never overwrite a share, which might already be committed
+    let mut share_file = async_fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&share.share)
+        .await?;
+    share_file.write_all(hex::encode(seed_share).as_bytes()).await?;
+    share_file.sync_all().await?;
+
+    let commitment = seed_commitment(&seed_share);
+    let signature = Production.sign(keypair.sigkey(), &SeedCommitment::message(&commitment))?;
+    let request = SeedCommitmentRequest { commitment, signature };
+
+    Ok(requests::post_genesis_seed_commitment(client, &share.url.coordinator, keypair, &request).await?)
+}
 
-    match branch {
-        Branch::AnotherMachine => println!(
-            "{}\n{}",
-            "DISCLAIMER".bright_red().underline().bold(),
+/// Reveals, as an operator, the share of the seed of the genesis challenge saved on commit
+async fn reveal_genesis_seed(
+    client: &Client,
+    keypair: &KeyPair,
+    share: &GenesisSeedShare,
+) -> Result<GenesisSeedStatus> {
+    let seed_share = async_fs::read_to_string(&share.share).await?.trim().to_string();
+    let signature = Production.sign(keypair.sigkey(), &SeedReveal::message(&seed_share))?;
+    let request = SeedRevealRequest {
+        share: seed_share,
+        signature,
+    };
+
+    Ok(requests::post_genesis_seed_reveal(client, &share.url.coordinator, keypair, &request).await?)
+}
+
+/// Prints the state of the collection of the seed of the genesis challenge
+fn print_genesis_seed_status(status: &GenesisSeedStatus) {
+    match status {
+        GenesisSeedStatus::Committing { commitments, required } => println!(
+            "{}",
+            format!(
+                "Share committed, waiting for {} more operators to commit",
+                required - commitments
+            )
+            .yellow()
+            .bold()
+        ),
+        GenesisSeedStatus::Revealing { reveals, required } => println!(
+            "{}",
+            format!(
+                "Waiting for {} more operators to reveal their shares",
+                required - reveals
+            )
+            .yellow()
+            .bold()
+        ),
+        GenesisSeedStatus::Derived { seed } => {
+            println!("{} {}", "Seed of the genesis challenge derived:".green().bold(), seed)
+        }
+    }
+}
+
+/// Compares the transcripts of two coordinators, returning the first diverging artifact, if any
+async fn compare_coordinators(compare: &CompareCoordinators) -> Result<Option<Divergence>> {
+    let client = Client::new();
+    let to_round = match compare.to_round {
+        Some(to_round) => to_round,
+        None => {
+            let left_status = requests::get_ceremony_status(&client, &compare.left).await?;
+            let right_status = requests::get_ceremony_status(&client, &compare.right).await?;
+            if left_status.round_height != right_status.round_height {
+                println!(
+                    "{}",
+                    format!(
+                        "The coordinators are at rounds {} and {}, comparing up to round {}",
+                        left_status.round_height,
+                        right_status.round_height,
+                        left_status.round_height.min(right_status.round_height)
+                    )
+                    .yellow()
+                );
+            }
+            left_status.round_height.min(right_status.round_height)
+        }
+    };
+
+    let left = requests::get_transcript_manifests(&client, &compare.left, compare.from_round, to_round).await?;
+    let right = requests::get_transcript_manifests(&client, &compare.right, compare.from_round, to_round).await?;
+
+    // The heads of the hash chains summarize the compared transcripts
+    for (coordinator, manifests) in [(&compare.left, &left), (&compare.right, &right)].iter() {
+        if let Some((round_height, head)) = hash_chain(manifests).last() {
+            println!("Hash chain of {} at round {}: {}", coordinator, round_height, head);
+        }
+    }
+
+    Ok(compare_manifests(&left, &right))
+}
+
+/// Compares the contributions of two ceremonies, returning the contributions and, unless ignored, the contributors
+/// found in both
+async fn detect_reuse(detect: &DetectReuse) -> Result<Vec<Reuse>> {
+    let client = Client::new();
+    let mut manifests = vec![];
+    let mut contributions = vec![];
+    for coordinator in [&detect.left, &detect.right].iter() {
+        let status = requests::get_ceremony_status(&client, coordinator).await?;
+        manifests.push(requests::get_transcript_manifests(&client, coordinator, 0, status.round_height).await?);
+        contributions.push(requests::get_trimmed_contributions_info(&client, coordinator).await?);
+        println!(
+            "Downloaded {} rounds and {} contributions of {}",
+            status.round_height + 1,
+            contributions.last().unwrap().len(),
+            coordinator
+        );
+    }
+
+    let mut reuses = find_reused_contributions(&manifests[0], &manifests[1]);
+    reuses.extend(
+        find_reused_contributors(&contributions[0], &contributions[1])
+            .into_iter()
+            .filter(|reuse| !(detect.ignore_public_keys && matches!(reuse, Reuse::PublicKey { .. }))),
+    );
+
+    Ok(reuses)
+}
+
+/// Retrieves the signed delta of a round of the published transcript and checks it against the key of the coordinator
+async fn get_verified_delta(
+    client: &Client,
+    transcript_url: &Url,
+    round_height: u64,
+    coordinator_public_key: &str,
+) -> Result<SignedTranscriptDelta> {
+    let delta = requests::get_transcript_delta(client, transcript_url, round_height).await?;
+    if delta.delta.round_height != round_height || !delta.verify(&Production, coordinator_public_key) {
+        return Err(CeremonyVerificationError::InvalidDelta(round_height, coordinator_public_key.to_owned()).into());
+    }
+
+    Ok(delta)
+}
+
+/// Downloads a file of the published transcript, unless already downloaded, and checks it against its manifest
+async fn download_transcript_file(
+    client: &Client,
+    transcript_url: &Url,
+    download_dir: &Path,
+    file: &TranscriptFile,
+) -> Result<PathBuf> {
+    let path = download_dir.join(&file.name);
+    if !path.is_file() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let downloaded = requests::download_transcript_file(client, transcript_url, &file.name, &path).await?;
+        debug!("Downloaded {} bytes of {}", downloaded, file.name);
+    }
+
+    let (checked_path, checked_file) = (path.clone(), file.clone());
+    if let Err(e) = tokio::task::spawn_blocking(move || check_file(&checked_path, &checked_file)).await? {
+        // A corrupted file is downloaded again by the next run
+        fs::remove_file(&path)?;
+        return Err(e.into());
+    }
+
+    Ok(path)
+}
+
+/// Returns the url of the published transcript with a trailing slash, since the file names are joined to the url, which
+/// would replace its last segment otherwise
+fn transcript_directory_url(transcript_url: &Url) -> Url {
+    let mut transcript_url = transcript_url.clone();
+    if !transcript_url.path().ends_with('/') {
+        transcript_url.set_path(&format!("{}/", transcript_url.path()));
+    }
+
+    transcript_url
+}
+
+/// Returns the key of the coordinator expected to sign the transcript: the given one, the one of the saved progress, or
+/// the one which signed the head of the transcript, and records it in the progress
+fn trusted_coordinator_key(
+    coordinator_pubkey: Option<String>,
+    head: &SignedTranscriptDelta,
+    progress: &mut VerificationProgress,
+    download_dir: &Path,
+) -> Result<String> {
+    let coordinator_public_key = match coordinator_pubkey.or_else(|| progress.coordinator_public_key.clone()) {
+        Some(coordinator_public_key) => coordinator_public_key,
+        None => {
+            println!(
+                "{}",
+                format!(
+                    "Trusting the coordinator key {} which signed the transcript, compare it with the one announced by the coordinator",
+                    head.coordinator_public_key
+                )
+                .yellow()
+            );
+            head.coordinator_public_key.clone()
+        }
+    };
+    if let Some(key) = &progress.coordinator_public_key {
+        if *key != coordinator_public_key {
+            return Err(anyhow::anyhow!(
+                "The progress in {} was recorded for the coordinator key {}",
+                download_dir.display(),
+                key
+            ));
+        }
+    }
+    progress.coordinator_public_key = Some(coordinator_public_key.clone());
+
+    Ok(coordinator_public_key)
+}
+
+/// Returns the keypair of the given mnemonic, or a new one, to sign the outcome of a verification
+fn runner_keypair(mnemonic: Option<&Path>) -> Result<KeyPair> {
+    match mnemonic {
+        Some(path) => Ok(KeyPair::try_from_seed(&io::seed_from_string(&fs::read_to_string(
+            path,
+        )?)?)?),
+        None => {
+            let keypair = KeyPair::new();
+            println!("{}", format!("Signing with the new key {}", keypair.pubkey()).yellow());
+            Ok(keypair)
+        }
+    }
+}
+
+/// Downloads and verifies the given links of the contributions of a round, one contribution at a time
+async fn verify_links(
+    client: &Client,
+    transcript_url: &Url,
+    download_dir: &Path,
+    hash_algorithm: HashAlgorithm,
+    links: &[Link],
+) -> Result<()> {
+    let hasher = hash_algorithm.hasher();
+    for link in links.iter() {
+        let challenge = download_transcript_file(client, transcript_url, download_dir, &link.challenge).await?;
+        let response = download_transcript_file(client, transcript_url, download_dir, &link.response).await?;
+        let next_challenge =
+            download_transcript_file(client, transcript_url, download_dir, &link.next_challenge).await?;
+
+        // Only the files of a single contribution are mapped in memory at once
+        let verified_link = link.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let challenge = unsafe { memmap::Mmap::map(&File::open(challenge)?)? };
+            let response = unsafe { memmap::Mmap::map(&File::open(response)?)? };
+            let next_challenge = unsafe { memmap::Mmap::map(&File::open(next_challenge)?)? };
+            verify_link(hasher, &challenge, &response, &next_challenge).map_err(|e| {
+                CeremonyVerificationError::InvalidLink {
+                    challenge: verified_link.challenge.name.clone(),
+                    response: verified_link.response.name.clone(),
+                    error: e.to_string(),
+                }
+            })?;
+
+            Ok(())
+        })
+        .await??;
+        println!("    {} {}", link.response.name, "verified".green());
+    }
+
+    Ok(())
+}
+
+/// Deletes the downloaded files of a verified round
+fn remove_round_files(download_dir: &Path, round_height: u64) -> Result<()> {
+    let round_dir = download_dir.join(format!("round_{}", round_height));
+    if round_dir.is_dir() {
+        fs::remove_dir_all(round_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Verifies the rounds of the published transcript following the last verified one, and signs the attestation of the
+/// verification of the whole transcript
+async fn verify_ceremony(verify: &VerifyCeremony) -> Result<SignedCeremonyAttestation> {
+    let client = Client::new();
+    let transcript_url = transcript_directory_url(&verify.transcript_url);
+    fs::create_dir_all(&verify.download_dir)?;
+    let mut progress = VerificationProgress::load(&verify.download_dir)?;
+
+    let head = requests::get_transcript_head(&client, &transcript_url)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No round of the transcript has been published yet"))?;
+    let coordinator_public_key = trusted_coordinator_key(
+        verify.coordinator_pubkey.clone(),
+        &head,
+        &mut progress,
+        &verify.download_dir,
+    )?;
+
+    // The challenges derived from the last contributions of a round are published with the next round, so the last
+    // published round is verified once the next one is published
+    let last_round = head.delta.round_height;
+    if progress.next_round > 0 {
+        println!("Resuming the verification from round {}", progress.next_round);
+    }
+    let mut next_delta = None;
+    while progress.next_round < last_round {
+        let round_height = progress.next_round;
+        let delta = match next_delta.take() {
+            Some(delta) => delta,
+            None => get_verified_delta(&client, &transcript_url, round_height, &coordinator_public_key).await?,
+        };
+        if delta.delta.previous_head != progress.head {
+            return Err(CeremonyVerificationError::BrokenChain {
+                round: round_height,
+                expected: progress.head.clone(),
+            }
+            .into());
+        }
+        let next = get_verified_delta(&client, &transcript_url, round_height + 1, &coordinator_public_key).await?;
+
+        let links = round_links(&delta.delta.manifest, &next.delta.manifest)?;
+        println!(
+            "[{}/{}] Verifying the {} contributions of round {}",
+            round_height + 1,
+            last_round,
+            links.len(),
+            round_height
+        );
+        verify_links(
+            &client,
+            &transcript_url,
+            &verify.download_dir,
+            verify.hash_algorithm,
+            &links,
+        )
+        .await?;
+
+        progress.head = delta.delta.head.clone();
+        progress.next_round += 1;
+        progress.verified_links += links.len() as u64;
+        progress.save(&verify.download_dir)?;
+        if !verify.keep_files {
+            remove_round_files(&verify.download_dir, round_height)?;
+        }
+        next_delta = Some(next);
+    }
+    if progress.next_round == 0 {
+        return Err(anyhow::anyhow!(
+            "Round 0 can only be verified once round 1 is published"
+        ));
+    }
+
+    let keypair = runner_keypair(verify.mnemonic.as_deref())?;
+    let attestation = CeremonyAttestation {
+        transcript_url: transcript_url.to_string(),
+        coordinator_public_key,
+        rounds: progress.next_round,
+        head: progress.head.clone(),
+        verified_links: progress.verified_links,
+        cli_version: env!("CARGO_PKG_VERSION").to_owned(),
+        verified_at: Utc::now().timestamp(),
+    }
+    .sign(keypair.pubkey().to_owned(), keypair.sigkey())?;
+    fs::write(&verify.attestation, serde_json::to_vec_pretty(&attestation)?)?;
+
+    Ok(attestation)
+}
+
+/// Verifies the rounds of the published transcript as they are published, and publishes the signed verdict of the
+/// mirror on each of them to its feed. Returns only once a fault of the transcript breaks its hash chain
+async fn mirror_ceremony(mirror: &MirrorCeremony) -> Result<()> {
+    let client = Client::new();
+    let transcript_url = transcript_directory_url(&mirror.transcript_url);
+    fs::create_dir_all(&mirror.download_dir)?;
+    fs::create_dir_all(&mirror.feed_dir)?;
+    let mut progress = VerificationProgress::load(&mirror.download_dir)?;
+    let keypair = runner_keypair(mirror.mnemonic.as_deref())?;
+    println!(
+        "Publishing the verdicts signed by {} to {}",
+        keypair.pubkey(),
+        mirror.feed_dir.display()
+    );
+
+    loop {
+        if let Err(e) = mirror_rounds(&client, &transcript_url, mirror, &keypair, &mut progress).await {
+            match e.downcast_ref::<CeremonyVerificationError>() {
+                // The following rounds can't be verified without trusting the coordinator
+                Some(fault) if fault.breaks_chain() => {
+                    let verdict = Verdict::Object {
+                        reason: fault.to_string(),
+                    };
+                    publish_verdict(&transcript_url, mirror, &keypair, &progress, verdict)?;
+                    return Err(e);
+                }
+                // Retried at the next check, e.g. if the transcript is unreachable
+                _ => eprintln!("{}", format!("Error while mirroring the transcript: {}", e).yellow()),
+            }
+        }
+
+        time::sleep(Duration::from_secs(mirror.interval)).await;
+    }
+}
+
+/// Verifies the published rounds following the last verified one, and publishes the verdict of the mirror on each of
+/// them
+async fn mirror_rounds(
+    client: &Client,
+    transcript_url: &Url,
+    mirror: &MirrorCeremony,
+    keypair: &KeyPair,
+    progress: &mut VerificationProgress,
+) -> Result<()> {
+    let head = match requests::get_transcript_head(client, transcript_url).await? {
+        Some(head) => head,
+        None => return Ok(()),
+    };
+    let coordinator_public_key =
+        trusted_coordinator_key(mirror.coordinator_pubkey.clone(), &head, progress, &mirror.download_dir)?;
+
+    // The last published round is verified once the next one is published
+    while progress.next_round < head.delta.round_height {
+        let round_height = progress.next_round;
+        let delta = get_verified_delta(client, transcript_url, round_height, &coordinator_public_key).await?;
+        if delta.delta.previous_head != progress.head {
+            return Err(CeremonyVerificationError::BrokenChain {
+                round: round_height,
+                expected: progress.head.clone(),
+            }
+            .into());
+        }
+        let next = get_verified_delta(client, transcript_url, round_height + 1, &coordinator_public_key).await?;
+
+        println!("Verifying round {}", round_height);
+        let mut attempt = 1;
+        let verdict = loop {
+            let verified = match round_links(&delta.delta.manifest, &next.delta.manifest) {
+                Ok(links) => verify_links(
+                    client,
+                    transcript_url,
+                    &mirror.download_dir,
+                    mirror.hash_algorithm,
+                    &links,
+                )
+                .await
+                .map(|()| links.len() as u64),
+                Err(e) => Err(e.into()),
+            };
+            let fault = match verified {
+                Ok(verified_links) => {
+                    break Verdict::Concur {
+                        head: delta.delta.head.clone(),
+                        verified_links,
+                    };
+                }
+                Err(e) => match e.downcast::<CeremonyVerificationError>() {
+                    Ok(fault) if fault.is_transcript_fault() => fault,
+                    Ok(fault) => return Err(fault.into()),
+                    Err(e) => return Err(e),
+                },
+            };
+            // The files not matching the manifest are downloaded again, in case the download was interrupted
+            if fault.is_mismatch() && attempt < MIRROR_DOWNLOAD_ATTEMPTS {
+                attempt += 1;
+                continue;
+            }
+
+            break Verdict::Object {
+                reason: fault.to_string(),
+            };
+        };
+        match &verdict {
+            Verdict::Concur { verified_links, .. } => {
+                println!("{}", format!("Concurring with round {}", round_height).green());
+                progress.verified_links += verified_links;
+            }
+            Verdict::Object { reason } => {
+                eprintln!(
+                    "{}",
+                    format!("Objecting to round {}: {}", round_height, reason).red().bold()
+                )
+            }
+        }
+        publish_verdict(transcript_url, mirror, keypair, progress, verdict)?;
+
+        progress.head = delta.delta.head.clone();
+        progress.next_round += 1;
+        progress.save(&mirror.download_dir)?;
+        if !mirror.keep_files {
+            remove_round_files(&mirror.download_dir, round_height)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Signs the verdict of the mirror on the next round of the progress and publishes it to the feed
+fn publish_verdict(
+    transcript_url: &Url,
+    mirror: &MirrorCeremony,
+    keypair: &KeyPair,
+    progress: &VerificationProgress,
+    verdict: Verdict,
+) -> Result<()> {
+    let coordinator_public_key = progress
+        .coordinator_public_key
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("The key of the coordinator is not known yet"))?;
+    MirrorVerdict {
+        transcript_url: transcript_url.to_string(),
+        coordinator_public_key,
+        round_height: progress.next_round,
+        previous_head: progress.head.clone(),
+        verdict,
+        cli_version: env!("CARGO_PKG_VERSION").to_owned(),
+        verified_at: Utc::now().timestamp(),
+    }
+    .sign(keypair.pubkey().to_owned(), keypair.sigkey())?
+    .publish(&mirror.feed_dir)?;
+
+    Ok(())
+}
+
+/// Converts the parameters of each circuit in the contribution file to the given formats
+fn convert_parameters(convert: ConvertParameters) -> Result<()> {
+    let contribution = fs::read(&convert.path)?;
+    let parameters = conversion::read_parameters(&contribution)?;
+    let stem = convert
+        .path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("parameters");
+    fs::create_dir_all(&convert.output_dir)?;
+
+    for (position, circuit_parameters) in parameters.iter().enumerate() {
+        for format in &convert.formats {
+            let bytes = conversion::serialize_parameters(circuit_parameters, *format)?;
+            let path = convert
+                .output_dir
+                .join(format!("{}_circuit_{}.{}", stem, position, format.extension()));
+            fs::write(&path, &bytes)?;
+            println!(
+                "{} ({}): {}",
+                path.display(),
+                format,
+                hex::encode(calculate_hash(&bytes))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+enum Branch {
+    AnotherMachine,
+    Default(bool),
+}
+
+/// Performs the entire contribution cycle
+#[inline(always)]
+/// Warns the user if the coordinator speaks a newer version of the protocol, or plans to remove some of the endpoints
+async fn check_api_changelog(client: &Client, coordinator: &Url) {
+    // Coordinators predating the changelog don't serve it
+    let signed = match requests::get_api_changelog(client, coordinator).await {
+        Ok(signed) => signed,
+        Err(_) => return,
+    };
+
+    if !signed.verify(&Production, &signed.coordinator_public_key) {
+        eprintln!("{}", "WARNING: the changelog of the Coordinator has an invalid signature".yellow().bold());
+        return;
+    }
+
+    if signed.changelog.protocol_version > PROTOCOL_VERSION {
+        eprintln!(
+            "{}",
+            format!(
+                "WARNING: the Coordinator runs version {} of the protocol while this client supports version {}, please update the client",
+                signed.changelog.protocol_version, PROTOCOL_VERSION
+            )
+            .yellow()
+            .bold()
+        );
+    }
+    for deprecation in signed.changelog.deprecations {
+        eprintln!(
+            "{}",
+            format!(
+                "WARNING: the endpoint {} is deprecated and may be removed after {}",
+                deprecation.endpoint, deprecation.deadline
+            )
+            .yellow()
+        );
+    }
+}
+
+/// Exits if the client is built for other parameters than the ones of the ceremony, before any contribution
+async fn check_ceremony_parameters(client: &Client, coordinator: &Url) {
+    // Coordinators predating the parameters don't serve them
+    let parameters = match requests::get_ceremony_parameters(client, coordinator).await {
+        Ok(parameters) => parameters,
+        Err(_) => return,
+    };
+
+    let mismatches = parameters.mismatches(ANOMA_BASE_FILE_SIZE, ANOMA_PER_ROUND_FILE_SIZE_INCREASE);
+    if !mismatches.is_empty() {
+        eprintln!(
+            "{}",
+            "ERROR: this client is built for other parameters than the ones of the ceremony, please use the client of the ceremony"
+                .red()
+                .bold()
+        );
+        for mismatch in mismatches {
+            eprintln!("{}", format!("- {}", mismatch).red());
+        }
+        process::exit(1);
+    }
+}
+
+/// Welcomes the user with the identity of the ceremony, if served and signed by the coordinator
+async fn print_ceremony_welcome(client: &Client, coordinator: &Url) {
+    // Coordinators predating the metadata don't serve it
+    let metadata = match requests::get_ceremony_metadata(client, coordinator).await {
+        Ok(signed) if signed.verify(&Production, &signed.coordinator_public_key) => signed.metadata,
+        _ => {
+            println!("{}", "Welcome to the Namada Trusted Setup Ceremony!".bold());
+            return;
+        }
+    };
+
+    println!("{}", format!("Welcome to the {}!", metadata.name).bold());
+    println!("{}", metadata.description);
+    if metadata.rehearsal {
+        println!(
+            "{}",
+            "This is a REHEARSAL: its parameters are thrown away and your contribution won't be part of the ceremony."
+                .red()
+                .bold()
+        );
+        // The contributions of a rehearsal are computed on the test circuits
+        if !TEST_CIRCUITS {
+            println!(
+                "{}",
+                "This client can't contribute to a rehearsal, rebuild it with the rehearsal feature.".yellow()
+            );
+        }
+    }
+    if !metadata.circuits.is_empty() {
+        let circuits: Vec<String> = metadata.circuits.iter().map(|c| c.id.to_string()).collect();
+        println!("Circuits: {}", circuits.join(", "));
+    }
+    for organizer in metadata.organizers {
+        let contacts: Vec<String> = organizer.email.into_iter().chain(organizer.url).collect();
+        match contacts.is_empty() {
+            true => println!("Organized by {}", organizer.name),
+            false => println!("Organized by {} ({})", organizer.name, contacts.join(", ")),
+        }
+    }
+    for link in metadata.links {
+        println!("{}: {}", link.title, link.url);
+    }
+    println!();
+}
+
+/// Prints the announcements of the coordinator, e.g. the approaching end of the ceremony
+async fn print_announcements(client: &Client, coordinator: &Url) {
+    // Coordinators predating the announcements don't serve them
+    for announcement in requests::get_announcements(client, coordinator).await.unwrap_or_default() {
+        match announcement.level {
+            AnnouncementLevel::Info => println!("{}", announcement.message.bold()),
+            AnnouncementLevel::Warning => println!("{}", announcement.message.yellow().bold()),
+        }
+    }
+}
+
+async fn contribution_prelude(url: CoordinatorUrl, token: String, branch: Branch) {
+    // Check the token info
+    let decoded_bytes = bs58::decode(token.clone()).into_vec();
+    if let Ok(token_bytes) = decoded_bytes {
+        let decoded_token = String::from_utf8(token_bytes).expect("Can't decode the token");
+        let token_data: Token = serde_json::from_str(&decoded_token).expect("Can't deserialize the token.");
+        match token_data.is_valid_cohort() {
+            phase2_cli::TokenCohort::Finished => {
+                println!("Your cohort round is {} and is already completed.", token_data.index);
+                process::exit(0);
+            }
+            phase2_cli::TokenCohort::Pending => {
+                let token_from_datetime = DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(token_data.from));
+                let token_to_datetime = DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(token_data.to));
+                println!(
+                    "Your cohort round is {} and will start at {} and finish at {}.",
+                    token_data.index, token_from_datetime, token_to_datetime
+                );
+                process::exit(0);
+            }
+            _ => (),
+        }
+    } else {
+        eprintln!("{}", "The token provided is not base58 encoded.".red().bold());
+        process::exit(0);
+    };
+
+    // Check that the passed-in coordinator url is correct
+    let client = Client::new();
+    if requests::ping_coordinator(&client, &url.coordinator)
+        .await.is_err() {
+            eprintln!("{}", "ERROR: could not contact the Coordinator, please check the url you provided".red().bold());
+            process::exit(1);
+        };
+    check_api_changelog(&client, &url.coordinator).await;
+    check_ceremony_parameters(&client, &url.coordinator).await;
+
+    println!("{}", ASCII_LOGO.bright_yellow());
+    print_ceremony_welcome(&client, &url.coordinator).await;
+    print_announcements(&client, &url.coordinator).await;
+
+    match branch {
+        Branch::AnotherMachine => println!(
+            "{}\n{}",
+            "DISCLAIMER".bright_red().underline().bold(),
             "The \"--another-machine\" flag is active.\nThis feature is designed for advanced users that want to run the computation of the parameters on another machine.\n".bright_red()
         ),
         Branch::Default(custom_seed) if custom_seed => println!(
@@ -721,6 +1920,169 @@ async fn contribution_prelude(url: CoordinatorUrl, token: String, branch: Branch
     .await;
 }
 
+/// Resumes a contribution interrupted by a crash of the client, possibly on another machine: the contribution file
+/// computed before the crash must be in the current working directory and the keypair is regenerated from the mnemonic
+async fn resume_contribution(url: CoordinatorUrl) -> Result<u64> {
+    let client = Client::new();
+    let coordinator = url.coordinator;
+    check_api_changelog(&client, &coordinator).await;
+    check_ceremony_parameters(&client, &coordinator).await;
+
+    println!("{} Restoring keypair", "[1/5]".bold().dimmed());
+    let keypair = tokio::task::spawn_blocking(io::keypair_from_user_mnemonic).await??;
+
+    println!("{} Resuming session", "[2/5]".bold().dimmed());
+    let session = requests::post_resume_session(&client, &coordinator, &keypair).await?;
+    let round_height = session.round_height;
+    let deadline = DateTime::<Utc>::from(std::time::SystemTime::from(session.deadline));
+    println!(
+        "{}",
+        format!(
+            "Resumed the contribution to round {}, which must be uploaded before {}",
+            round_height,
+            deadline.to_rfc2822()
+        )
+        .bright_cyan()
+    );
+
+    // Keep the session alive until the contribution is notified
+    let keypair = Arc::new(keypair);
+    let (client_cnt, coordinator_cnt, keypair_cnt) = (client.clone(), coordinator.clone(), keypair.clone());
+    let heartbeat_handle = tokio::task::spawn(async move {
+        loop {
+            if let Err(e) = requests::post_heartbeat(&client_cnt, &coordinator_cnt, &keypair_cnt).await {
+                eprintln!(
+                    "{}",
+                    format!("{}: {}", "Heartbeat error".red().bold(), e.to_string().red().bold())
+                );
+            }
+            time::sleep(UPDATE_TIME).await;
+        }
+    });
+
+    println!("{} Reading contribution", "[3/5]".bold().dimmed());
+    let contrib_filename = match session.challenge_bundle {
+        Some(_) => OFFLINE_CONTRIBUTION_FILE_NAME.to_string(),
+        None => format!(
+            "namada_contribution_round_{}_public_key_{}.params",
+            round_height,
+            keypair.pubkey()
+        ),
+    };
+    let contribution = get_file_as_byte_vec(
+        contrib_filename.as_str(),
+        round_height,
+        session.locked_locators.next_contribution().contribution_id(),
+    )?;
+
+    // The contribution file starts with the hash of the challenge it has been computed from
+    let contribution_file_hash = calculate_hash(contribution.as_ref());
+    let challenge_hash_len = contribution_file_hash.len();
+    let challenge_hash = &contribution[..challenge_hash_len];
+    let response_bundle = match &session.challenge_bundle {
+        Some(challenge_bundle) => {
+            let response_bundle: ResponseBundle =
+                serde_json::from_slice(&async_fs::read(OFFLINE_RESPONSE_BUNDLE_FILE_NAME).await?)?;
+            if !response_bundle.answers(challenge_bundle)
+                || response_bundle.contribution_file_signature.get_response_hash()
+                    != hex::encode(contribution_file_hash)
+            {
+                return Err(anyhow::anyhow!(
+                    "The response bundle doesn't match the challenge bundle or the contribution file"
+                ));
+            }
+            Some(response_bundle)
+        }
+        None => None,
+    };
+    let contribution_file_signature = match &response_bundle {
+        Some(response_bundle) => response_bundle.contribution_file_signature.clone(),
+        None => {
+            let contribution_state =
+                ContributionState::new(challenge_hash.to_vec(), contribution_file_hash.to_vec(), None)?;
+            let signature = Production.sign(keypair.sigkey(), &contribution_state.signature_message()?)?;
+            ContributionFileSignature::new(signature, contribution_state)?
+        }
+    };
+
+    // A contribution streamed to the coordinator resumes from the bytes it received
+    let streamed = if session.contribution_uploaded && session.signature_uploaded {
+        println!("{} Contribution already uploaded", "[4/5]".bold().dimmed());
+        false
+    } else {
+        println!("{} Uploading contribution", "[4/5]".bold().dimmed());
+        upload_contribution(
+            &client,
+            &coordinator,
+            &keypair,
+            round_height,
+            contrib_filename.as_str(),
+            &contribution_file_signature,
+            response_bundle.is_none(),
+        )
+        .await?
+    };
+
+    // The info collected before the crash is lost, ask for it again
+    let mut contrib_info = tokio::task::spawn_blocking(initialize_contribution).await??;
+    contrib_info.public_key = keypair.pubkey().to_string();
+    contrib_info.ceremony_round = round_height;
+    contrib_info.contribution_file_hash = hex::encode(contribution_file_hash);
+    contrib_info.contribution_file_signature =
+        Production.sign(keypair.sigkey(), contrib_info.contribution_file_hash.as_str())?;
+    contrib_info.contribution_hash = hex::encode(calculate_hash(&contribution[challenge_hash_len..]));
+    contrib_info.contribution_hash_signature =
+        Production.sign(keypair.sigkey(), contrib_info.contribution_hash.as_str())?;
+    if let Some(challenge_bundle) = &session.challenge_bundle {
+        contrib_info.is_another_machine = true;
+        contrib_info.offline_contribution = Some(OfflineContributionInfo {
+            challenge_hash: challenge_bundle.challenge_hash.clone(),
+            deadline,
+            challenge_bundle_downloaded: Utc::now(),
+            response_bundle_received: Utc::now(),
+        });
+    }
+    contrib_info.timestamps.end_contribution = Utc::now();
+    contrib_info.try_sign(&keypair)?;
+
+    println!(
+        "{} Notifying the coordinator of your uploaded contribution.\nYour contribution is being processed... This might take a minute...",
+        "[5/5]".bold().dimmed()
+    );
+    match response_bundle {
+        Some(response_bundle) => {
+            requests::post_response_bundle(&client, &coordinator, &keypair, &response_bundle).await?
+        }
+        None => {
+            let post_chunk_req = match streamed {
+                true => PostChunkRequest::spooled(
+                    round_height,
+                    session.locked_locators.next_contribution(),
+                    session.locked_locators.next_contribution_file_signature(),
+                    contribution_file_signature,
+                ),
+                false => PostChunkRequest::new(
+                    round_height,
+                    session.locked_locators.next_contribution(),
+                    session.locked_locators.next_contribution_file_signature(),
+                ),
+            };
+            requests::post_contribute_chunk(&client, &coordinator, &keypair, &post_chunk_req).await?;
+        }
+    }
+    async_fs::write(
+        format!("namada_contributor_info_round_{}.json", round_height),
+        &serde_json::to_vec(&contrib_info)?,
+    )
+    .await?;
+    requests::post_contribution_info(&client, &coordinator, &keypair, &contrib_info).await?;
+    heartbeat_handle.abort();
+
+    follow_verification(&client, &coordinator, &keypair).await;
+
+    Ok(round_height)
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
@@ -743,13 +2105,27 @@ async fn main() {
                     "The \"--custom-seed\" flag is active.\nThis feature is designed for advanced users that want to give a custom random seed for the ChaCha RNG.\n".bright_red()
                 );
                     }
-                    // Only compute randomness. It expects a file called challenge.params to be available in the cwd and already filled with the challenge bytes
-                    println!("{} Reading challenge", "[1/2]".bold().dimmed());
+                    // Only compute randomness. It expects the files challenge.params and challenge_bundle.json to be available in the cwd, the former already filled with the challenge bytes
+                    println!("{} Reading challenge", "[1/3]".bold().dimmed());
                     let challenge = async_fs::read(OFFLINE_CHALLENGE_FILE_NAME)
                         .await
                         .expect(&format!("{}", "Couldn't read the challenge file".red().bold()));
+                    let challenge_bundle: ChallengeBundle = serde_json::from_slice(
+                        &async_fs::read(OFFLINE_CHALLENGE_BUNDLE_FILE_NAME)
+                            .await
+                            .expect(&format!("{}", "Couldn't read the challenge bundle".red().bold())),
+                    )
+                    .expect(&format!("{}", "Couldn't deserialize the challenge bundle".red().bold()));
+                    let challenge_hash = calculate_hash(&challenge);
+                    if challenge_bundle.challenge_hash != hex::encode(challenge_hash) {
+                        eprintln!(
+                            "{}",
+                            "The challenge file doesn't match the challenge bundle".red().bold()
+                        );
+                        process::exit(1);
+                    }
 
-                    println!("{} Computing contribution", "[2/2]".bold().dimmed());
+                    println!("{} Computing contribution", "[2/3]".bold().dimmed());
 
                     if custom_seed {
                         println!("{}", CUSTOM_SEED_MSG_YES.bright_cyan());
@@ -762,9 +2138,34 @@ async fn main() {
                     .await
                     .unwrap()
                     .expect(&format!("{}", "Error in computing randomness".red().bold()));
+
+                    println!("{} Signing the response bundle", "[3/3]".bold().dimmed());
+                    tokio::task::spawn_blocking(move || {
+                        sign_response_bundle(&challenge_bundle, challenge_hash.as_slice())
+                    })
+                    .await
+                    .unwrap()
+                    .expect(&format!("{}", "Error while signing the response bundle".red().bold()));
                 }
             }
         }
+        CeremonyOpt::ResumeContribution(url) => {
+            let round_height = resume_contribution(url)
+                .await
+                .expect(&format!("{}", "Couldn't resume the contribution".red().bold()));
+            println!(
+                "{}",
+                format!("Done! Your contribution to round {} has been received", round_height)
+                    .green()
+                    .bold()
+            );
+        }
+        CeremonyOpt::Convert(convert) => {
+            tokio::task::spawn_blocking(move || convert_parameters(convert))
+                .await
+                .unwrap()
+                .expect(&format!("{}", "Error while converting the parameters".red().bold()));
+        }
         CeremonyOpt::CloseCeremony(url) => {
             let keypair = tokio::task::spawn_blocking(|| io::keypair_from_mnemonic())
                 .await
@@ -840,6 +2241,118 @@ async fn main() {
             let secret = state.token.as_str();
             get_coordinator_state(&state.url.coordinator, secret).await;
         }
+        CeremonyOpt::GetParticipantsHistory(history) => {
+            let secret = history.token.as_str();
+            get_participants_history(&history.url.coordinator, secret).await;
+        }
+        CeremonyOpt::GetStorageShards(request) => {
+            let secret = request.token.as_str();
+            get_storage_shards(&request.url.coordinator, secret).await;
+        }
+        CeremonyOpt::RebalanceStorage(request) => {
+            let secret = request.token.as_str();
+            rebalance_storage(&request.url.coordinator, secret).await;
+        }
+        CeremonyOpt::CompareCoordinators(compare) => {
+            match compare_coordinators(&compare)
+                .await
+                .expect(&format!("{}", "Error while comparing the coordinators".red().bold()))
+            {
+                None => println!("{}", "The transcripts of the coordinators match".green().bold()),
+                Some(divergence) => {
+                    eprintln!("{}", divergence.to_string().red().bold());
+                    process::exit(1);
+                }
+            }
+        }
+        CeremonyOpt::DetectReuse(detect) => {
+            let reuses = detect_reuse(&detect)
+                .await
+                .expect(&format!("{}", "Error while comparing the ceremonies".red().bold()));
+            if reuses.is_empty() {
+                println!("{}", "No contribution is shared by the ceremonies".green().bold());
+            } else {
+                for reuse in &reuses {
+                    eprintln!("{}", reuse.to_string().red().bold());
+                }
+                process::exit(1);
+            }
+        }
+        CeremonyOpt::VerifyCeremony(verify) => match verify_ceremony(&verify).await {
+            Ok(signed) => println!(
+                "{}",
+                format!(
+                    "Verified the {} contributions of rounds 0 to {}, up to the head {} of the transcript. Attestation written to {}",
+                    signed.attestation.verified_links,
+                    signed.attestation.rounds - 1,
+                    signed.attestation.head,
+                    verify.attestation.display()
+                )
+                .green()
+                .bold()
+            ),
+            Err(e) => {
+                eprintln!("{}", e.to_string().red().bold());
+                process::exit(1);
+            }
+        },
+        CeremonyOpt::MirrorCeremony(mirror) => {
+            if let Err(e) = mirror_ceremony(&mirror).await {
+                eprintln!("{}", e.to_string().red().bold());
+                process::exit(1);
+            }
+        }
+        CeremonyOpt::InjectContribution(inject) => {
+            let keypair = tokio::task::spawn_blocking(|| io::keypair_from_mnemonic())
+                .await
+                .unwrap()
+                .expect(&format!("{}", "Error while generating the keypair".red().bold()));
+
+            let client = Client::new();
+            match inject_contribution(&client, &keypair, &inject).await {
+                Ok(InjectionStatus::Pending { approvals, required }) => println!(
+                    "{}",
+                    format!("Injection approved, waiting for {} more operators", required - approvals)
+                        .yellow()
+                        .bold()
+                ),
+                Ok(_) => println!("{}", "Contribution injected".green().bold()),
+                Err(e) => {
+                    eprintln!("{}", e.to_string().red().bold());
+                    process::exit(1);
+                }
+            }
+        }
+        CeremonyOpt::CommitGenesisSeed(share) => {
+            let keypair = tokio::task::spawn_blocking(|| io::keypair_from_mnemonic())
+                .await
+                .unwrap()
+                .expect(&format!("{}", "Error while generating the keypair".red().bold()));
+
+            let client = Client::new();
+            match commit_genesis_seed(&client, &keypair, &share).await {
+                Ok(status) => print_genesis_seed_status(&status),
+                Err(e) => {
+                    eprintln!("{}", e.to_string().red().bold());
+                    process::exit(1);
+                }
+            }
+        }
+        CeremonyOpt::RevealGenesisSeed(share) => {
+            let keypair = tokio::task::spawn_blocking(|| io::keypair_from_mnemonic())
+                .await
+                .unwrap()
+                .expect(&format!("{}", "Error while generating the keypair".red().bold()));
+
+            let client = Client::new();
+            match reveal_genesis_seed(&client, &keypair, &share).await {
+                Ok(status) => print_genesis_seed_status(&status),
+                Err(e) => {
+                    eprintln!("{}", e.to_string().red().bold());
+                    process::exit(1);
+                }
+            }
+        }
         CeremonyOpt::UpdateCohorts(url) => {
             let keypair = tokio::task::spawn_blocking(|| io::keypair_from_mnemonic())
                 .await
@@ -0,0 +1,364 @@
+//! Independent verification of the whole ceremony from the transcript published by the coordinator, see
+//! [`phase2_coordinator::publication`].
+//!
+//! The rounds are verified in order: the signed delta of each round is checked against the key of the coordinator and
+//! the head of the hash chain of the previous round, then every contribution of the round is verified on top of its
+//! challenge, and the challenge derived from it is checked. The progress is saved after each round, so that an
+//! interrupted verification resumes from the last verified round. Once done, the runner signs a
+//! [`CeremonyAttestation`] with the head of the hash chain it verified.
+//!
+//! A mirror runs the same verification continuously, as the rounds are published, and publishes to its feed a
+//! [`MirrorVerdict`] on each round, concurring with the head of the hash chain of the round or objecting to it.
+
+use crate::prevalidation::transcript_name;
+
+use phase2_coordinator::{
+    authentication::{Production, Signature},
+    storage::{ContributionLocator, RoundManifest, TranscriptFile},
+};
+use serde::{Deserialize, Serialize};
+use setup_utils::calculate_hash;
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::Path,
+};
+use thiserror::Error;
+
+/// The file of the progress of the verification, in the download directory.
+pub const PROGRESS_FILE: &str = "verify-ceremony.json";
+/// The file of the verdict on the last verified round, in the feed directory of a mirror.
+pub const FEED_HEAD_FILE: &str = "head.json";
+
+#[derive(Debug, Error)]
+pub enum CeremonyVerificationError {
+    #[error("The delta of round {0} is not signed by the coordinator key {1}")]
+    InvalidDelta(u64, String),
+    #[error("The delta of round {round} doesn't follow the head {expected} of the previous round")]
+    BrokenChain { round: u64, expected: String },
+    #[error("The file {0} is missing from the transcript")]
+    MissingFile(String),
+    #[error("The file {name} is {found} bytes long instead of {expected}")]
+    SizeMismatch { name: String, expected: u64, found: u64 },
+    #[error("The hash {found} of the file {name} doesn't match the published one {expected}")]
+    HashMismatch {
+        name: String,
+        expected: String,
+        found: String,
+    },
+    #[error("The contribution {response} on top of {challenge} is invalid: {error}")]
+    InvalidLink {
+        challenge: String,
+        response: String,
+        error: String,
+    },
+    #[error("Error while reading or writing a file: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("Error while encoding or decoding: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl CeremonyVerificationError {
+    /// Returns `true` if the error is a fault of the published transcript, rather than of the runner of the
+    /// verification.
+    pub fn is_transcript_fault(&self) -> bool {
+        !matches!(
+            self,
+            CeremonyVerificationError::IO(_) | CeremonyVerificationError::Json(_)
+        )
+    }
+
+    /// Returns `true` if the fault breaks the hash chain of the transcript, so that the following rounds can't be
+    /// verified.
+    pub fn breaks_chain(&self) -> bool {
+        matches!(
+            self,
+            CeremonyVerificationError::InvalidDelta(..) | CeremonyVerificationError::BrokenChain { .. }
+        )
+    }
+
+    /// Returns `true` if a downloaded file doesn't match the manifest, which may also be caused by an interrupted
+    /// download.
+    pub fn is_mismatch(&self) -> bool {
+        matches!(
+            self,
+            CeremonyVerificationError::SizeMismatch { .. } | CeremonyVerificationError::HashMismatch { .. }
+        )
+    }
+}
+
+/// A link of the chain of contributions: a response computed on top of a challenge, and the challenge derived from
+/// the response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link {
+    pub challenge: TranscriptFile,
+    pub response: TranscriptFile,
+    pub next_challenge: TranscriptFile,
+}
+
+/// Returns the file of the manifests with the given name.
+fn find_file(manifests: &[&RoundManifest], name: String) -> Result<TranscriptFile, CeremonyVerificationError> {
+    manifests
+        .iter()
+        .flat_map(|manifest| manifest.files.iter())
+        .find(|file| file.name == name)
+        .cloned()
+        .ok_or(CeremonyVerificationError::MissingFile(name))
+}
+
+///
+/// Returns the links of the contributions of a round, by chunk. The challenge derived from the last contribution of a
+/// chunk is the first challenge of the chunk in the next round, so the links of a round are only known once the next
+/// round is published.
+///
+pub fn round_links(
+    manifest: &RoundManifest,
+    next_manifest: &RoundManifest,
+) -> Result<Vec<Link>, CeremonyVerificationError> {
+    let round_height = manifest.round_height;
+    let prefix = format!("round_{}/chunk_", round_height);
+
+    // The ids of the responses of each chunk
+    let mut responses: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+    for file in &manifest.files {
+        let parsed = file.name.strip_prefix(&prefix).and_then(|name| {
+            let (chunk_id, contribution) = name.split_once("/contribution_")?;
+            let contribution_id = contribution.strip_suffix(".unverified")?;
+            Some((chunk_id.parse::<u64>().ok()?, contribution_id.parse::<u64>().ok()?))
+        });
+        if let Some((chunk_id, contribution_id)) = parsed {
+            responses.entry(chunk_id).or_default().push(contribution_id);
+        }
+    }
+
+    let manifests = [manifest, next_manifest];
+    let mut links = vec![];
+    for (chunk_id, mut contribution_ids) in responses {
+        contribution_ids.sort_unstable();
+        let last_contribution_id = contribution_ids.last().copied();
+
+        for contribution_id in contribution_ids.into_iter().filter(|id| *id > 0) {
+            let challenge = ContributionLocator::new(round_height, chunk_id, contribution_id - 1, true);
+            let response = ContributionLocator::new(round_height, chunk_id, contribution_id, false);
+            let next_challenge = match Some(contribution_id) == last_contribution_id {
+                true => ContributionLocator::new(round_height + 1, chunk_id, 0, true),
+                false => ContributionLocator::new(round_height, chunk_id, contribution_id, true),
+            };
+            links.push(Link {
+                challenge: find_file(&manifests, transcript_name(&challenge))?,
+                response: find_file(&manifests, transcript_name(&response))?,
+                next_challenge: find_file(&manifests, transcript_name(&next_challenge))?,
+            });
+        }
+    }
+
+    Ok(links)
+}
+
+/// Checks the size of a downloaded file and, if published, its hash, reading it without loading it in memory.
+pub fn check_file(path: &Path, file: &TranscriptFile) -> Result<(), CeremonyVerificationError> {
+    let size = fs::metadata(path)?.len();
+    if size != file.size {
+        return Err(CeremonyVerificationError::SizeMismatch {
+            name: file.name.clone(),
+            expected: file.size,
+            found: size,
+        });
+    }
+
+    if let Some(expected) = &file.hash {
+        let content = unsafe { memmap::Mmap::map(&File::open(path)?)? };
+        let hash = hex::encode(calculate_hash(&content));
+        if &hash != expected {
+            return Err(CeremonyVerificationError::HashMismatch {
+                name: file.name.clone(),
+                expected: expected.clone(),
+                found: hash,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the file through a temporary file, so that an interruption keeps the previous content.
+fn write_atomically(path: &Path, content: &[u8]) -> Result<(), CeremonyVerificationError> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(tmp_path, path)?;
+
+    Ok(())
+}
+
+/// The progress of the verification of a ceremony, saved after each verified round.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationProgress {
+    /// The key of the coordinator which signed the verified rounds.
+    pub coordinator_public_key: Option<String>,
+    /// The next round to verify.
+    pub next_round: u64,
+    /// The head of the hash chain of the transcript up to the last verified round, in hex, empty before round 0.
+    pub head: String,
+    /// The number of contributions verified so far.
+    pub verified_links: u64,
+}
+
+impl VerificationProgress {
+    /// Loads the progress saved in the given directory, or starts from round 0.
+    pub fn load(directory: &Path) -> Result<Self, CeremonyVerificationError> {
+        let path = directory.join(PROGRESS_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Saves the progress to the given directory, through a temporary file so that an interruption keeps the previous
+    /// progress.
+    pub fn save(&self, directory: &Path) -> Result<(), CeremonyVerificationError> {
+        write_atomically(&directory.join(PROGRESS_FILE), &serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// The outcome of the verification of a ceremony, attested by the runner of the verification.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyAttestation {
+    /// The url of the published transcript.
+    pub transcript_url: String,
+    /// The key of the coordinator which signed the transcript.
+    pub coordinator_public_key: String,
+    /// The number of verified rounds, from round 0.
+    pub rounds: u64,
+    /// The head of the hash chain of the transcript up to the last verified round, in hex.
+    pub head: String,
+    /// The number of verified contributions.
+    pub verified_links: u64,
+    /// The version of the CLI which ran the verification.
+    pub cli_version: String,
+    /// The time of the end of the verification, as a unix timestamp.
+    pub verified_at: i64,
+}
+
+impl CeremonyAttestation {
+    /// Returns the message signed by the runner: the json encoding of the attestation, with the fields sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the attestation with the given key of the runner.
+    pub fn sign(
+        self,
+        runner_public_key: String,
+        runner_signing_key: &str,
+    ) -> anyhow::Result<SignedCeremonyAttestation> {
+        let signature = Production.sign(runner_signing_key, &self.message()?)?;
+
+        Ok(SignedCeremonyAttestation {
+            attestation: self,
+            runner_public_key,
+            signature,
+        })
+    }
+}
+
+/// A [`CeremonyAttestation`] signed by the runner of the verification.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedCeremonyAttestation {
+    pub attestation: CeremonyAttestation,
+    pub runner_public_key: String,
+    pub signature: String,
+}
+
+impl SignedCeremonyAttestation {
+    /// Returns `true` if the attestation is signed by the runner with the given public key.
+    pub fn verify(&self, runner_public_key: &str) -> bool {
+        match self.attestation.message() {
+            Ok(message) => {
+                self.runner_public_key == runner_public_key
+                    && Production.verify(runner_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// The verdict of a mirror on a round of the transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Verdict {
+    /// Every contribution of the round was verified, up to the given head of the hash chain of the transcript.
+    Concur { head: String, verified_links: u64 },
+    /// The round is invalid for the given reason.
+    Object { reason: String },
+}
+
+/// The verdict of a mirror on a round of the transcript, published to its feed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorVerdict {
+    /// The url of the published transcript.
+    pub transcript_url: String,
+    /// The key of the coordinator which signed the transcript.
+    pub coordinator_public_key: String,
+    pub round_height: u64,
+    /// The head of the hash chain of the transcript up to the previous round, in hex, empty for round 0.
+    pub previous_head: String,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+    /// The version of the CLI which ran the mirror.
+    pub cli_version: String,
+    /// The time of the verdict, as a unix timestamp.
+    pub verified_at: i64,
+}
+
+impl MirrorVerdict {
+    /// Returns the message signed by the mirror: the json encoding of the verdict, with the fields sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the verdict with the given key of the mirror.
+    pub fn sign(self, mirror_public_key: String, mirror_signing_key: &str) -> anyhow::Result<SignedMirrorVerdict> {
+        let signature = Production.sign(mirror_signing_key, &self.message()?)?;
+
+        Ok(SignedMirrorVerdict {
+            verdict: self,
+            mirror_public_key,
+            signature,
+        })
+    }
+}
+
+/// A [`MirrorVerdict`] signed by the mirror.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedMirrorVerdict {
+    pub verdict: MirrorVerdict,
+    pub mirror_public_key: String,
+    pub signature: String,
+}
+
+impl SignedMirrorVerdict {
+    /// Returns `true` if the verdict is signed by the mirror with the given public key.
+    pub fn verify(&self, mirror_public_key: &str) -> bool {
+        match self.verdict.message() {
+            Ok(message) => {
+                self.mirror_public_key == mirror_public_key
+                    && Production.verify(mirror_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Publishes the verdict to the feed in the given directory, as `round_<height>.json` and as the [`FEED_HEAD_FILE`]
+    /// once written.
+    pub fn publish(&self, feed_dir: &Path) -> Result<(), CeremonyVerificationError> {
+        let content = serde_json::to_vec_pretty(self)?;
+        write_atomically(
+            &feed_dir.join(format!("round_{}.json", self.verdict.round_height)),
+            &content,
+        )?;
+        write_atomically(&feed_dir.join(FEED_HEAD_FILE), &content)
+    }
+}
@@ -4,10 +4,15 @@
 use std::path::PathBuf;
 
 pub mod ascii_logo;
+pub mod ceremony_verification;
 pub mod keys;
+pub mod prevalidation;
 pub mod requests;
+pub mod transfer;
 
 use phase2_coordinator::{
+    conversion::ParameterFormat,
+    hashing::HashAlgorithm,
     objects::round::LockedLocators,
     rest_utils::{ContributorStatus, PostChunkRequest},
 };
@@ -126,11 +131,205 @@ pub struct VerifySignatureContribution {
     pub parameter_path: Option<PathBuf>
 }
 
+#[derive(Debug, StructOpt)]
+pub struct ConvertParameters {
+    #[structopt(help = "The path to the contribution file", required = true, parse(from_os_str))]
+    pub path: PathBuf,
+    #[structopt(
+        help = "The comma separated list of output formats (raw, arkworks, snarkvm)",
+        long,
+        use_delimiter = true,
+        default_value = "raw,arkworks,snarkvm"
+    )]
+    pub formats: Vec<ParameterFormat>,
+    #[structopt(
+        help = "The directory where to write the converted parameters",
+        long,
+        default_value = ".",
+        parse(from_os_str)
+    )]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct CompareCoordinators {
+    #[structopt(help = "The address of the first coordinator", required = true, parse(try_from_str))]
+    pub left: Url,
+    #[structopt(help = "The address of the second coordinator", required = true, parse(try_from_str))]
+    pub right: Url,
+    #[structopt(help = "The first round to compare", long, default_value = "0")]
+    pub from_round: u64,
+    #[structopt(
+        help = "The last round to compare, defaults to the last round reached by both coordinators",
+        long
+    )]
+    pub to_round: Option<u64>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct DetectReuse {
+    #[structopt(
+        help = "The address of the coordinator of the first ceremony",
+        required = true,
+        parse(try_from_str)
+    )]
+    pub left: Url,
+    #[structopt(
+        help = "The address of the coordinator of the second ceremony",
+        required = true,
+        parse(try_from_str)
+    )]
+    pub right: Url,
+    #[structopt(help = "Don't report the contributors who contributed to both ceremonies", long)]
+    pub ignore_public_keys: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct VerifyCeremony {
+    #[structopt(
+        help = "The url of the transcript published by the coordinator",
+        required = true,
+        parse(try_from_str)
+    )]
+    pub transcript_url: Url,
+    #[structopt(
+        help = "The public key of the coordinator, defaults to the key which signed the first round",
+        long,
+        env = "NAMADA_COORDINATOR_PUBKEY"
+    )]
+    pub coordinator_pubkey: Option<String>,
+    #[structopt(
+        help = "The directory of the downloaded files and of the progress of the verification",
+        long,
+        default_value = "ceremony",
+        parse(from_os_str)
+    )]
+    pub download_dir: PathBuf,
+    #[structopt(
+        help = "The path to the mnemonic signing the attestation, defaults to a new key",
+        long,
+        env = "NAMADA_VERIFIER_MNEMONIC_FILE",
+        parse(from_os_str)
+    )]
+    pub mnemonic: Option<PathBuf>,
+    #[structopt(
+        help = "The path of the signed attestation of the verification",
+        long,
+        default_value = "ceremony-attestation.json",
+        parse(from_os_str)
+    )]
+    pub attestation: PathBuf,
+    #[structopt(
+        help = "The hash function of the contribution files, as recorded in the ceremony metadata",
+        long,
+        default_value = "blake2b-512",
+        parse(try_from_str)
+    )]
+    pub hash_algorithm: HashAlgorithm,
+    #[structopt(help = "Keep the files of the verified rounds instead of deleting them", long)]
+    pub keep_files: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct MirrorCeremony {
+    #[structopt(
+        help = "The url of the transcript published by the primary coordinator",
+        required = true,
+        parse(try_from_str)
+    )]
+    pub transcript_url: Url,
+    #[structopt(
+        help = "The public key of the coordinator, defaults to the key which signed the first round",
+        long,
+        env = "NAMADA_COORDINATOR_PUBKEY"
+    )]
+    pub coordinator_pubkey: Option<String>,
+    #[structopt(
+        help = "The directory of the downloaded files and of the progress of the mirror",
+        long,
+        default_value = "mirror",
+        parse(from_os_str)
+    )]
+    pub download_dir: PathBuf,
+    #[structopt(
+        help = "The directory of the feed of the signed verdicts of the mirror, to be served publicly",
+        long,
+        default_value = "mirror-feed",
+        parse(from_os_str)
+    )]
+    pub feed_dir: PathBuf,
+    #[structopt(
+        help = "The path to the mnemonic signing the verdicts, defaults to a new key",
+        long,
+        env = "NAMADA_VERIFIER_MNEMONIC_FILE",
+        parse(from_os_str)
+    )]
+    pub mnemonic: Option<PathBuf>,
+    #[structopt(
+        help = "The interval in seconds between two checks of the published transcript",
+        long,
+        default_value = "300"
+    )]
+    pub interval: u64,
+    #[structopt(
+        help = "The hash function of the contribution files, as recorded in the ceremony metadata",
+        long,
+        default_value = "blake2b-512",
+        parse(try_from_str)
+    )]
+    pub hash_algorithm: HashAlgorithm,
+    #[structopt(help = "Keep the files of the verified rounds instead of deleting them", long)]
+    pub keep_files: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct InjectContribution {
+    #[structopt(flatten)]
+    pub url: CoordinatorUrl,
+    #[structopt(
+        help = "The public key of the contributor on behalf of which the contribution is injected",
+        required = true
+    )]
+    pub participant: String,
+    #[structopt(help = "The round of the contribution", required = true)]
+    pub round_height: u64,
+    #[structopt(help = "The path to the contribution file", required = true, parse(from_os_str))]
+    pub contribution: PathBuf,
+    #[structopt(
+        help = "The path to the json file with the signature of the contribution file",
+        required = true,
+        parse(from_os_str)
+    )]
+    pub signature: PathBuf,
+    #[structopt(help = "Why the contribution is injected manually, published in the transcript", long)]
+    pub reason: String,
+    #[structopt(help = "Skip the upload of the files, already uploaded by another operator", long)]
+    pub skip_upload: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct GenesisSeedShare {
+    #[structopt(flatten)]
+    pub url: CoordinatorUrl,
+    #[structopt(
+        help = "The path to the file of the share of the seed of the genesis challenge, drawn on commit",
+        required = true,
+        parse(from_os_str)
+    )]
+    pub share: PathBuf,
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "namada-ts", about = "Namada CLI for trusted setup.")]
 pub enum CeremonyOpt {
     #[structopt(about = "Contribute to the ceremony")]
     Contribute(Branches),
+    #[structopt(
+        about = "Resume a contribution interrupted by a crash of the client, from the contribution file in the current directory"
+    )]
+    ResumeContribution(CoordinatorUrl),
+    #[structopt(about = "Convert the parameters of a contribution file to other serialization formats")]
+    Convert(ConvertParameters),
     #[structopt(about = "Stop the coordinator and close the ceremony")]
     CloseCeremony(CoordinatorUrl),
     #[structopt(about = "Generate a Namada keypair from a mnemonic")]
@@ -142,6 +341,30 @@ pub enum CeremonyOpt {
     GetContributions(CoordinatorUrl),
     #[structopt(about = "Get the state of the coordinator")]
     GetState(RequestWithToken),
+    #[structopt(about = "Get the history of all the participants of the ceremony")]
+    GetParticipantsHistory(RequestWithToken),
+    #[structopt(about = "Get the usage of the storage shards of the coordinator")]
+    GetStorageShards(RequestWithToken),
+    #[structopt(about = "Move the objects in storage to their assigned shard")]
+    RebalanceStorage(RequestWithToken),
+    #[structopt(about = "Compare the transcripts of two coordinators and report the first diverging file")]
+    CompareCoordinators(CompareCoordinators),
+    #[structopt(about = "Compare two ceremonies and report the contributions and the contributors found in both")]
+    DetectReuse(DetectReuse),
+    #[structopt(
+        about = "Verify every contribution of the transcript published by the coordinator and sign an attestation of the result"
+    )]
+    VerifyCeremony(VerifyCeremony),
+    #[structopt(
+        about = "Verify the rounds of the transcript published by the coordinator as they come, and publish a signed concurrence or objection for each of them"
+    )]
+    MirrorCeremony(MirrorCeremony),
+    #[structopt(about = "Approve the injection of a contribution computed out-of-band on behalf of a contributor")]
+    InjectContribution(InjectContribution),
+    #[structopt(about = "Commit to a random share of the seed of the genesis challenge, saved to be revealed later")]
+    CommitGenesisSeed(GenesisSeedShare),
+    #[structopt(about = "Reveal the share of the seed of the genesis challenge, once every operator committed")]
+    RevealGenesisSeed(GenesisSeedShare),
     #[cfg(debug_assertions)]
     #[structopt(about = "Verify the pending contributions")]
     VerifyContributions(CoordinatorUrl),
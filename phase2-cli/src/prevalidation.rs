@@ -0,0 +1,143 @@
+//! Validation of a downloaded challenge before computing the contribution, so that a corrupted or tampered download is
+//! detected before spending hours on it and wasting the contribution slot.
+
+use phase2_coordinator::{
+    conversion,
+    storage::{ContributionLocator, Object, RoundManifest},
+};
+use setup_utils::calculate_hash;
+use thiserror::Error;
+
+/// The size of the header of a challenge: the hash of the response it was verified from.
+const HEADER_SIZE: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum ChallengeError {
+    #[error("The challenge of {size} bytes is too short to contain its header")]
+    MissingHeader { size: usize },
+    #[error("The challenge is {found} bytes long instead of {expected}")]
+    SizeMismatch { expected: u64, found: u64 },
+    #[error("The coordinator didn't publish the hash of {0} in the transcript")]
+    NotPublished(String),
+    #[error("The hash of the challenge {found} doesn't match the one published by the coordinator {expected}")]
+    HashMismatch { expected: String, found: String },
+    #[error("The header of the challenge {found} doesn't match the hash {expected} of the response {response}")]
+    HeaderMismatch {
+        response: String,
+        expected: String,
+        found: String,
+    },
+    #[error("The parameters of the challenge are malformed: {0}")]
+    MalformedParameters(String),
+    #[error("The challenge contains the parameters of {found} circuits instead of {expected}")]
+    CircuitCountMismatch { expected: usize, found: usize },
+}
+
+/// Returns the name in the transcript of the contribution file at the given locator.
+pub(crate) fn transcript_name(locator: &ContributionLocator) -> String {
+    format!(
+        "round_{}/chunk_{}/contribution_{}.{}",
+        locator.round_height(),
+        locator.chunk_id(),
+        locator.contribution_id(),
+        if locator.is_verified() { "verified" } else { "unverified" }
+    )
+}
+
+/// Returns the hash published in the given manifests for the transcript file with the given name.
+fn published_hash<'a>(manifests: &'a [RoundManifest], name: &str) -> Option<&'a str> {
+    manifests
+        .iter()
+        .flat_map(|manifest| manifest.files.iter())
+        .find(|file| file.name == name)
+        .and_then(|file| file.hash.as_deref())
+}
+
+/// Returns the name of the response the challenge at the given locator was verified from: the previous contribution of
+/// the same round, or the last one of the previous round for the first challenge of a round. The first challenge of
+/// the ceremony is not derived from any response.
+fn previous_response(locator: &ContributionLocator, manifests: &[RoundManifest]) -> Option<String> {
+    if locator.contribution_id() > 0 {
+        return Some(transcript_name(&ContributionLocator::new(
+            locator.round_height(),
+            locator.chunk_id(),
+            locator.contribution_id(),
+            false,
+        )));
+    }
+
+    let previous_round = locator.round_height().checked_sub(1)?;
+    let prefix = format!("round_{}/chunk_{}/contribution_", previous_round, locator.chunk_id());
+    manifests
+        .iter()
+        .filter(|manifest| manifest.round_height == previous_round)
+        .flat_map(|manifest| manifest.files.iter())
+        .filter_map(|file| {
+            let contribution_id = file.name.strip_prefix(&prefix)?.strip_suffix(".unverified")?;
+            Some((contribution_id.parse::<u64>().ok()?, file.name.clone()))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+///
+/// Validates a downloaded challenge against the transcript published by the coordinator, before computing the
+/// contribution. The manifests must include the round of the challenge and, for the first challenge of a round, the
+/// previous round. Checks, in order:
+///
+/// 1. The header and the size of the challenge
+/// 2. The hash of the challenge against the one published in the transcript
+/// 3. The header of the challenge against the hash of the response it was verified from
+/// 4. The parameters of the circuits, and their number if the expected one is given
+///
+pub fn prevalidate_challenge(
+    challenge: &[u8],
+    locator: &ContributionLocator,
+    manifests: &[RoundManifest],
+    expected_circuits: Option<usize>,
+) -> Result<(), ChallengeError> {
+    if challenge.len() < HEADER_SIZE {
+        return Err(ChallengeError::MissingHeader { size: challenge.len() });
+    }
+
+    let expected_size = Object::anoma_contribution_file_size(locator.round_height(), locator.contribution_id());
+    if challenge.len() as u64 != expected_size {
+        return Err(ChallengeError::SizeMismatch {
+            expected: expected_size,
+            found: challenge.len() as u64,
+        });
+    }
+
+    let name = transcript_name(locator);
+    let expected_hash = published_hash(manifests, &name).ok_or(ChallengeError::NotPublished(name))?;
+    let challenge_hash = hex::encode(calculate_hash(challenge));
+    if challenge_hash != expected_hash {
+        return Err(ChallengeError::HashMismatch {
+            expected: expected_hash.to_owned(),
+            found: challenge_hash,
+        });
+    }
+
+    if let Some(response) = previous_response(locator, manifests) {
+        let response_hash =
+            published_hash(manifests, &response).ok_or_else(|| ChallengeError::NotPublished(response.clone()))?;
+        let header = hex::encode(&challenge[..HEADER_SIZE]);
+        if header != response_hash {
+            return Err(ChallengeError::HeaderMismatch {
+                response,
+                expected: response_hash.to_owned(),
+                found: header,
+            });
+        }
+    }
+
+    let parameters =
+        conversion::read_parameters(challenge).map_err(|e| ChallengeError::MalformedParameters(e.to_string()))?;
+    match expected_circuits {
+        Some(expected) if expected != parameters.len() => Err(ChallengeError::CircuitCountMismatch {
+            expected,
+            found: parameters.len(),
+        }),
+        _ => Ok(()),
+    }
+}
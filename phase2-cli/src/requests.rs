@@ -1,27 +1,49 @@
 //! Requests sent to the [Coordinator](`phase2-coordinator::Coordinator`) server.
 
 use bytes::Bytes;
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use phase2_coordinator::{
     authentication::{KeyPair, Production, Signature},
-    objects::ContributionInfo,
+    capability_probe::{CapabilityProbe, CapabilityProof},
+    changelog::SignedChangelog,
+    objects::{
+        Announcement, CeremonyParameters, ChallengeBundle, ContributionInfo, ContributionSession, GenesisSeedStatus,
+        InjectionStatus, ResponseBundle, SignedCeremonyMetadata, TrimmedContributionInfo, VerificationStatus,
+    },
+    pow::PowPuzzle,
+    publication::{SignedTranscriptDelta, DELTAS_DIRECTORY, HEAD_FILE},
     rest_utils::{
-        RequestContent, SignatureHeaders, ACCESS_SECRET_HEADER, BODY_DIGEST_HEADER, CONTENT_LENGTH_HEADER,
-        PUBKEY_HEADER, SIGNATURE_HEADER,
+        self, AttestationPostRequest, CeremonyStatus, ComputationProofRequest, DestructionAttestationRequest, ErrorBody,
+        JoinQueueRequest, JoinQueueResponse, ManualContributionApproval, QueueEvent, RequestContent,
+        SeedCommitmentRequest, SeedRevealRequest, SignatureHeaders, UploadProgress,
+        ACCESS_SECRET_HEADER, BODY_DIGEST_HEADER, CONTENT_LENGTH_HEADER, PUBKEY_HEADER, SIGNATURE_HEADER,
     },
-    ContributionFileSignature,
+    storage::RoundManifest,
+    ContributionFileSignature, Participant,
 };
 use reqwest::{
-    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
-    Client, RequestBuilder, Response, Url,
+    header::{HeaderMap, HeaderValue, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_RANGE, RANGE},
+    Client, RequestBuilder, Response, StatusCode, Url,
 };
 use serde::Serialize;
 use sha2::{Digest, Sha256};
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    io::SeekFrom,
+    path::Path,
+    time::Instant,
+};
 use thiserror::Error;
+use tokio::{
+    fs as async_fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
 use tracing::debug;
 
-use crate::{ContributorStatus, LockedLocators, PostChunkRequest};
+use crate::{
+    transfer::{RangeSizer, TransferConfig},
+    ContributorStatus, LockedLocators, PostChunkRequest,
+};
 
 /// Error returned from a request.
 #[derive(Debug, Error)]
@@ -30,10 +52,14 @@ pub enum RequestError {
     AddressParseError,
     #[error("Client-side error: {0}")]
     Client(String),
+    #[error("Error while writing the downloaded file: {0}")]
+    IO(#[from] std::io::Error),
     #[error("Invalid header value: {0}")]
     InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
     #[error("Json serialization of body failed")]
     JsonError(#[from] serde_json::Error),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("CDN Error: {0}")]
     Proxy(String),
     #[error("Request error: {0}")]
@@ -81,7 +107,7 @@ trait Sign {
 
 impl Sign for SignatureHeaders<'_> {
     fn try_sign(&mut self, sigkey: &str) -> Result<()> {
-        let msg = self.to_string();
+        let msg = self.message();
         self.signature = Some(
             Production
                 .sign(sigkey, &msg)
@@ -119,7 +145,7 @@ where
         Request::Get => client.get(address),
         Request::Post(body) => match body {
             Some(b) => {
-                let json_body = serde_json::to_vec(b)?;
+                let json_body = rest_utils::to_canonical_json(b)?;
 
                 let mut hasher = Sha256::new();
                 hasher.update(&json_body);
@@ -167,35 +193,137 @@ async fn decapsulate_response(response: Response) -> Result<Response> {
 
     if status.is_success() {
         Ok(response)
+    } else if status == StatusCode::NOT_FOUND {
+        Err(RequestError::NotFound(error_message(response).await?))
     } else if status.is_client_error() {
-        Err(RequestError::Client(response.text().await?))
+        Err(RequestError::Client(error_message(response).await?))
     } else {
         if status.as_u16() == reqwest::StatusCode::GATEWAY_TIMEOUT.as_u16() {
             Err(RequestError::Proxy(response.text().await?))
         } else {
-            Err(RequestError::Server(response.text().await?))
+            Err(RequestError::Server(error_message(response).await?))
         }
     }
 }
 
-/// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to join the queue of contributors.
+/// Returns the message of the [`ErrorBody`] of the response with the id of the request, to be reported to the
+/// operators, or the body as it is if it's not structured, e.g. when returned by a proxy.
+async fn error_message(response: Response) -> Result<String> {
+    let body = response.text().await?;
+
+    Ok(match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(error) => format!("{} (request id {})", error.message, error.request_id),
+        Err(_) => body,
+    })
+}
+
+/// Get the proof-of-work puzzle to solve before joining the queue, if required by the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn get_pow_puzzle(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<Option<PowPuzzle>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "contributor/pow_puzzle",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json::<Option<PowPuzzle>>().await?)
+}
+
+/// Get the capability probe to complete before joining the queue, if required by the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn get_capability_probe(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<Option<CapabilityProbe>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "contributor/capability_probe",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json::<Option<CapabilityProbe>>().await?)
+}
+
+/// Download the blob of the capability probe issued by the [Coordinator](`phase2-coordinator::Coordinator`), hashing it
+/// on the fly, and return the proof of the probe.
+pub async fn get_capability_probe_blob(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    probe: &CapabilityProbe,
+) -> Result<CapabilityProof> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "contributor/capability_probe/blob",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    let mut hasher = Sha256::new();
+    let mut stream = response.bytes_stream();
+    while let Some(bytes) = stream.next().await {
+        hasher.update(&bytes?);
+    }
+
+    Ok(CapabilityProof {
+        seed: probe.seed.clone(),
+        hash: hex::encode(hasher.finalize()),
+    })
+}
+
+/// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to join the queue of contributors. The request
+/// can be retried safely with the same token.
 pub async fn post_join_queue(
     client: &Client,
     coordinator_address: &Url,
     keypair: &KeyPair,
     token: &String,
-) -> Result<u64> {
-    let response = submit_request::<String>(
+) -> Result<JoinQueueResponse> {
+    // Solve the proof-of-work puzzle, if required by the coordinator
+    let pow_solution = match get_pow_puzzle(client, coordinator_address, keypair).await? {
+        Some(puzzle) => {
+            let participant = Participant::new_contributor(keypair.pubkey());
+            let solution = tokio::task::spawn_blocking(move || puzzle.solve(&participant))
+                .await
+                .map_err(|e| RequestError::Client(e.to_string()))?;
+            Some(solution)
+        }
+        None => None,
+    };
+
+    // Download the blob of the capability probe, if required by the coordinator, right before joining
+    let mut request = JoinQueueRequest::new(token.clone(), pow_solution);
+    if let Some(probe) = get_capability_probe(client, coordinator_address, keypair).await? {
+        debug!("Downloading the {} bytes of the capability probe", probe.size);
+        let proof = get_capability_probe_blob(client, coordinator_address, keypair, &probe).await?;
+        request = request.with_capability_proof(proof);
+    }
+
+    let response = submit_request::<JoinQueueRequest>(
         client,
         coordinator_address,
         "contributor/join_queue",
         Some(keypair),
         None,
-        Request::Post(Some(token)),
+        Request::Post(Some(&request)),
     )
     .await?;
 
-    Ok(response.json::<u64>().await?)
+    Ok(response.json::<JoinQueueResponse>().await?)
 }
 
 /// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to lock the next [Chunk](`phase2-coordinator::objects::Chunk`).
@@ -213,6 +341,46 @@ pub async fn get_lock_chunk(client: &Client, coordinator_address: &Url, keypair:
     Ok(response.json::<LockedLocators>().await?)
 }
 
+/// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to switch to the offline flow and get the
+/// bundle of the challenge to compute on an air-gapped machine.
+pub async fn get_challenge_bundle(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<ChallengeBundle> {
+    let response = submit_request::<String>(
+        client,
+        coordinator_address,
+        "contributor/offline/challenge_bundle",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json::<ChallengeBundle>().await?)
+}
+
+/// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to resume the contribution session of a
+/// contributor whose client crashed while holding the lock.
+pub async fn post_resume_session(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<ContributionSession> {
+    let response = submit_request::<String>(
+        client,
+        coordinator_address,
+        "contributor/resume_session",
+        Some(keypair),
+        None,
+        Request::Post(None),
+    )
+    .await?;
+
+    Ok(response.json::<ContributionSession>().await?)
+}
+
 /// Send a request to the [Coordinator](`phase2-coordinator::Coordinator`) to get the next challenge's key.
 pub async fn get_challenge_url(
     client: &Client,
@@ -233,14 +401,16 @@ pub async fn get_challenge_url(
     Ok(response.json().await?)
 }
 
-/// Send a request to Amazon S3 to download the next challenge.
+/// Send a request to Amazon S3 to download the next challenge. Returns the stream of the challenge with its length, taken
+/// from the response or, if missing, from the expected size reported by the coordinator, and zero if both are unknown.
 pub async fn get_challenge(
     client: &Client,
     challenge_url: &str,
+    expected_size: Option<u64>,
 ) -> Result<(impl Stream<Item = reqwest::Result<Bytes>>, u64)> {
     let req = client.get(challenge_url);
     let response = req.send().await?;
-    let stream_len = response.content_length().unwrap();
+    let stream_len = response.content_length().or(expected_size).unwrap_or_default();
 
     Ok((decapsulate_response(response).await?.bytes_stream(), stream_len))
 }
@@ -265,6 +435,86 @@ pub async fn get_contribution_url(
     Ok(response.json().await?)
 }
 
+/// Request the urls where an operator uploads a contribution to inject manually and its signature.
+pub async fn get_manual_contribution_url(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    round_height: &u64,
+) -> Result<(String, String)> {
+    let response = submit_request::<u64>(
+        client,
+        coordinator_address,
+        "operator/upload/chunk",
+        Some(keypair),
+        None,
+        Request::Post(Some(round_height)),
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
+/// Send the approval of an operator on the manual injection of a contribution to the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn post_manual_contribution(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    approval: &ManualContributionApproval,
+) -> Result<InjectionStatus> {
+    let response = submit_request(
+        client,
+        coordinator_address,
+        "operator/manual_contribution",
+        Some(keypair),
+        None,
+        Request::Post(Some(approval)),
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
+/// Send the commitment of an operator to its share of the seed of the genesis challenge to the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn post_genesis_seed_commitment(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    commitment: &SeedCommitmentRequest,
+) -> Result<GenesisSeedStatus> {
+    let response = submit_request(
+        client,
+        coordinator_address,
+        "operator/genesis_seed/commit",
+        Some(keypair),
+        None,
+        Request::Post(Some(commitment)),
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
+/// Send the reveal of the share of an operator of the seed of the genesis challenge to the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn post_genesis_seed_reveal(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    reveal: &SeedRevealRequest,
+) -> Result<GenesisSeedStatus> {
+    let response = submit_request(
+        client,
+        coordinator_address,
+        "operator/genesis_seed/reveal",
+        Some(keypair),
+        None,
+        Request::Post(Some(reveal)),
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
 /// Upload a gneric object to S3.
 async fn upload_object(req: RequestBuilder) -> Result<()> {
     let response = req.send().await?;
@@ -301,6 +551,41 @@ where
     Ok(())
 }
 
+/// Stream a contribution to the [Coordinator](`phase2-coordinator::Coordinator`) instead of uploading it to Amazon S3.
+/// The request is signed on the length and the sha256 digest of the contribution. Returns the hex encoded hash of the
+/// contribution received by the coordinator.
+pub async fn upload_contribution<S>(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    contribution_stream: S,
+    contribution_len: usize,
+    contribution_digest: &[u8],
+) -> Result<String>
+where
+    S: Stream<Item = std::result::Result<Bytes, std::io::Error>> + std::marker::Send + std::marker::Sync + 'static,
+{
+    let address = coordinator_address
+        .join("contributor/upload/contribution")
+        .map_err(|_| RequestError::AddressParseError)?;
+
+    let content = RequestContent::new(contribution_len, contribution_digest);
+    let mut headers = SignatureHeaders::new(keypair.pubkey(), Some(content), None);
+    headers.try_sign(keypair.sigkey())?;
+    let header_map: HeaderWrap = headers.try_into()?;
+
+    // The stream can't be cloned, so the request is not resubmitted on a timeout of the CDN
+    let response = client
+        .post(address)
+        .headers(header_map.into())
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(reqwest::Body::wrap_stream(contribution_stream))
+        .send()
+        .await?;
+
+    Ok(decapsulate_response(response).await?.json().await?)
+}
+
 /// Send a request to notify the [Coordinator](`phase2-coordinator::Coordinator`) of an uploaded contribution.
 pub async fn post_contribute_chunk(
     client: &Client,
@@ -321,6 +606,47 @@ pub async fn post_contribute_chunk(
     Ok(())
 }
 
+/// Send the proof of the computation time of the contribution to the [Coordinator](`phase2-coordinator::Coordinator`).
+/// Returns the number of iterations of the proof accepted by the coordinator.
+pub async fn post_computation_proof(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    request_body: &ComputationProofRequest,
+) -> Result<u64> {
+    let response = submit_request(
+        client,
+        coordinator_address,
+        "contributor/computation_proof",
+        Some(keypair),
+        None,
+        Request::Post(Some(request_body)),
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
+/// Send the bundle of a contribution computed on an air-gapped machine to the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn post_response_bundle(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    response_bundle: &ResponseBundle,
+) -> Result<()> {
+    submit_request(
+        client,
+        coordinator_address,
+        "contributor/offline/response_bundle",
+        Some(keypair),
+        None,
+        Request::Post(Some(response_bundle)),
+    )
+    .await?;
+
+    Ok(())
+}
+
 /// Let the [Coordinator](`phase2-coordinator::Coordinator`) know that the contributor is still alive.
 pub async fn post_heartbeat(client: &Client, coordinator_address: &Url, keypair: &KeyPair) -> Result<()> {
     submit_request::<String>(
@@ -394,6 +720,83 @@ pub async fn get_contributor_queue_status(
     Ok(response.json::<ContributorStatus>().await?)
 }
 
+/// Get the stream of the changes of the queue status of the contributor, pushed by the
+/// [Coordinator](`phase2-coordinator::Coordinator`) as Server-Sent Events.
+pub async fn get_contributor_queue_events(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<impl Stream<Item = Result<QueueEvent>>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "contributor/queue_events",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    let state = (Box::pin(response.bytes_stream()), Vec::new());
+    Ok(futures_util::stream::unfold(state, |(bytes, buffer)| next_queue_event(bytes, buffer)))
+}
+
+/// Reads the next [`QueueEvent`] from the given stream of Server-Sent Events, buffering the bytes of the events not
+/// read yet.
+async fn next_queue_event<S>(mut bytes: S, mut buffer: Vec<u8>) -> Option<(Result<QueueEvent>, (S, Vec<u8>))>
+where
+    S: Stream<Item = reqwest::Result<Bytes>> + Unpin,
+{
+    loop {
+        // The events are separated by an empty line
+        if let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+            let block: Vec<u8> = buffer.drain(..end + 2).collect();
+            let data = String::from_utf8_lossy(&block)
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect::<Vec<_>>()
+                .join("\n");
+            // The blocks without data are the heartbeats of the stream
+            if data.is_empty() {
+                continue;
+            }
+            let event = serde_json::from_str(&data).map_err(RequestError::from);
+            return Some((event, (bytes, buffer)));
+        }
+
+        match bytes.next().await {
+            Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+            Some(Err(e)) => return Some((Err(e.into()), (bytes, buffer))),
+            None => return None,
+        }
+    }
+}
+
+/// Get the verification status of the latest contribution, if known by the coordinator, which forgets the statuses
+/// when restarted.
+pub async fn get_verification_status(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<Option<VerificationStatus>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "contributor/verification_status",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await;
+
+    match response {
+        Ok(response) => Ok(Some(response.json::<VerificationStatus>().await?)),
+        Err(RequestError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 /// Send [`ContributionInfo`] to the Coordinator.
 pub async fn post_contribution_info(
     client: &Client,
@@ -434,6 +837,60 @@ pub async fn post_attestation(
     Ok(())
 }
 
+/// Register the public post attesting the contribution, archived by the Coordinator with the hash of its content.
+pub async fn post_attestation_post(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    request_body: &AttestationPostRequest,
+) -> Result<()> {
+    submit_request::<AttestationPostRequest>(
+        client,
+        coordinator_address,
+        "/contributor/attestation_post",
+        Some(keypair),
+        None,
+        Request::Post(Some(request_body)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Send the signed attestation of the destruction of the toxic waste of the contribution to the Coordinator.
+pub async fn post_destruction_attestation(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    request_body: &DestructionAttestationRequest,
+) -> Result<()> {
+    submit_request::<DestructionAttestationRequest>(
+        client,
+        coordinator_address,
+        "/contributor/destruction_attestation",
+        Some(keypair),
+        None,
+        Request::Post(Some(request_body)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch the public post attesting the contribution and compute the hex encoded sha256 hash of its content, as the
+/// Coordinator does when archiving it.
+pub async fn get_attestation_post_hash(client: &Client, post_url: &str) -> Result<String> {
+    let content = client
+        .get(post_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(hex::encode(Sha256::digest(&content)))
+}
+
 /// Query health endpoint of the Coordinator to check the connection
 pub async fn ping_coordinator(client: &Client, coordinator_address: &Url) -> Result<()> {
     submit_request::<()>(client, coordinator_address, "/healthcheck", None, None, Request::Get).await?;
@@ -441,6 +898,37 @@ pub async fn ping_coordinator(client: &Client, coordinator_address: &Url) -> Res
     Ok(())
 }
 
+/// Retrieve the changelog of the REST API, signed by the [Coordinator](`phase2-coordinator::Coordinator`)
+pub async fn get_api_changelog(client: &Client, coordinator_address: &Url) -> Result<SignedChangelog> {
+    let response = submit_request::<()>(client, coordinator_address, "/api/changelog", None, None, Request::Get).await?;
+
+    Ok(response.json::<SignedChangelog>().await?)
+}
+
+/// Retrieve the identity of the ceremony, signed by the [Coordinator](`phase2-coordinator::Coordinator`)
+pub async fn get_ceremony_metadata(client: &Client, coordinator_address: &Url) -> Result<SignedCeremonyMetadata> {
+    let response =
+        submit_request::<()>(client, coordinator_address, "/ceremony/metadata", None, None, Request::Get).await?;
+
+    Ok(response.json::<SignedCeremonyMetadata>().await?)
+}
+
+/// Retrieve the parameters of the ceremony, with the expected sizes of the files of the current round
+pub async fn get_ceremony_parameters(client: &Client, coordinator_address: &Url) -> Result<CeremonyParameters> {
+    let response =
+        submit_request::<()>(client, coordinator_address, "/ceremony/parameters", None, None, Request::Get).await?;
+
+    Ok(response.json::<CeremonyParameters>().await?)
+}
+
+/// Retrieve the announcements of the coordinator, e.g. the approaching end of the ceremony.
+pub async fn get_announcements(client: &Client, coordinator_address: &Url) -> Result<Vec<Announcement>> {
+    let response =
+        submit_request::<()>(client, coordinator_address, "/ceremony/announcements", None, None, Request::Get).await?;
+
+    Ok(response.json::<Vec<Announcement>>().await?)
+}
+
 /// Retrieve the list of contributions, json encoded
 #[cfg(debug_assertions)]
 pub async fn get_contributions_info(coordinator_address: &Url) -> Result<Vec<u8>> {
@@ -459,6 +947,24 @@ pub async fn get_contributions_info(coordinator_address: &Url) -> Result<Vec<u8>
     Ok(response.bytes().await?.to_vec())
 }
 
+/// Retrieve the public info of the contributions received by the coordinator
+pub async fn get_trimmed_contributions_info(
+    client: &Client,
+    coordinator_address: &Url,
+) -> Result<Vec<TrimmedContributionInfo>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        "/contribution_info",
+        None,
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json::<Vec<TrimmedContributionInfo>>().await?)
+}
+
 /// Retrieve the state of the coordinator, json encoded. Needs to provide a secret access token to the endpoint
 pub async fn get_coordinator_state(coordinator_address: &Url, access_secret: &str) -> Result<Vec<u8>> {
     let client = Client::builder().brotli(true).build()?;
@@ -478,6 +984,377 @@ pub async fn get_coordinator_state(coordinator_address: &Url, access_secret: &st
     Ok(response.bytes().await?.to_vec())
 }
 
+/// Retrieve the history of all the participants ever seen by the coordinator, json encoded. Needs to provide a secret
+/// access token to the endpoint
+pub async fn get_participants_history(coordinator_address: &Url, access_secret: &str) -> Result<Vec<u8>> {
+    let client = Client::new();
+    let mut header = HeaderMap::new();
+    header.insert(ACCESS_SECRET_HEADER, HeaderValue::from_str(access_secret)?);
+
+    let response = submit_request::<()>(
+        &client,
+        coordinator_address,
+        "/participants_history",
+        None,
+        Some(header),
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Retrieve the number of objects and the space used on each storage shard of the coordinator, json encoded. Needs to
+/// provide a secret access token to the endpoint
+pub async fn get_storage_shards(coordinator_address: &Url, access_secret: &str) -> Result<Vec<u8>> {
+    let client = Client::new();
+    let mut header = HeaderMap::new();
+    header.insert(ACCESS_SECRET_HEADER, HeaderValue::from_str(access_secret)?);
+
+    let response = submit_request::<()>(
+        &client,
+        coordinator_address,
+        "/storage/shards",
+        None,
+        Some(header),
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Move the objects in storage to their assigned shard, returning the number of moved objects. Needs to provide a
+/// secret access token to the endpoint
+pub async fn post_rebalance_storage(coordinator_address: &Url, access_secret: &str) -> Result<usize> {
+    let client = Client::new();
+    let mut header = HeaderMap::new();
+    header.insert(ACCESS_SECRET_HEADER, HeaderValue::from_str(access_secret)?);
+
+    let response = submit_request::<()>(
+        &client,
+        coordinator_address,
+        "/storage/rebalance",
+        None,
+        Some(header),
+        Request::Post(None),
+    )
+    .await?;
+
+    Ok(response.json::<usize>().await?)
+}
+
+/// Retrieve the public status of the ceremony
+pub async fn get_ceremony_status(client: &Client, coordinator_address: &Url) -> Result<CeremonyStatus> {
+    let response = submit_request::<()>(client, coordinator_address, "/ceremony_status", None, None, Request::Get).await?;
+
+    Ok(response.json::<CeremonyStatus>().await?)
+}
+
+/// Retrieve the manifests of the transcript of the rounds in the range `from_round..=to_round`
+pub async fn get_transcript_manifests(
+    client: &Client,
+    coordinator_address: &Url,
+    from_round: u64,
+    to_round: u64,
+) -> Result<Vec<RoundManifest>> {
+    let response = submit_request::<()>(
+        client,
+        coordinator_address,
+        &format!("/transcript/manifests?from_round={}&to_round={}", from_round, to_round),
+        None,
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json::<Vec<RoundManifest>>().await?)
+}
+
+/// Retrieve the signed delta of the last round of the transcript published at the given url, if any
+pub async fn get_transcript_head(client: &Client, transcript_url: &Url) -> Result<Option<SignedTranscriptDelta>> {
+    let address = transcript_url
+        .join(HEAD_FILE)
+        .map_err(|_| RequestError::AddressParseError)?;
+    let response = client.get(address).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    Ok(Some(decapsulate_response(response).await?.json().await?))
+}
+
+/// Retrieve the signed delta of the given round of the transcript published at the given url
+pub async fn get_transcript_delta(
+    client: &Client,
+    transcript_url: &Url,
+    round_height: u64,
+) -> Result<SignedTranscriptDelta> {
+    let address = transcript_url
+        .join(&format!("{}/round_{}.json", DELTAS_DIRECTORY, round_height))
+        .map_err(|_| RequestError::AddressParseError)?;
+    let response = client.get(address).send().await?;
+
+    Ok(decapsulate_response(response).await?.json().await?)
+}
+
+/// Download a file of the transcript published at the given url to the given path with [`download_ranges`]. Returns
+/// the number of downloaded bytes.
+pub async fn download_transcript_file(client: &Client, transcript_url: &Url, name: &str, path: &Path) -> Result<u64> {
+    let address = transcript_url
+        .join(name)
+        .map_err(|_| RequestError::AddressParseError)?;
+
+    download_ranges(client, address.as_str(), path, TransferConfig::default(), |_| ()).await
+}
+
+/// The response to the request of a range of a file.
+enum RangeResponse {
+    /// The range, with the total length of the file and its entity tag, if sent.
+    Partial {
+        bytes: Bytes,
+        total: u64,
+        etag: Option<String>,
+    },
+    /// The whole file, from a server ignoring the ranges.
+    Full(Bytes),
+    /// The range starts after the end of the file.
+    NotSatisfiable,
+}
+
+/// Download the range of the given length starting at the given offset of the file at the given url. With the entity
+/// tag of the previous ranges, the server sends the whole file instead if it changed since.
+async fn download_range(
+    client: &Client,
+    url: &str,
+    offset: u64,
+    len: u64,
+    etag: Option<&str>,
+) -> Result<RangeResponse> {
+    let mut request = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", offset, offset + len - 1));
+    if let Some(etag) = etag {
+        request = request.header(IF_RANGE, etag);
+    }
+    let response = request.send().await?;
+
+    match response.status() {
+        StatusCode::RANGE_NOT_SATISFIABLE => Ok(RangeResponse::NotSatisfiable),
+        StatusCode::PARTIAL_CONTENT => {
+            // Content-Range: bytes <first>-<last>/<total>
+            let total = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit('/').next())
+                .and_then(|total| total.parse().ok())
+                .ok_or_else(|| RequestError::Server(String::from("Missing length of the ranged file")))?;
+            let etag = response
+                .headers()
+                .get(ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+
+            Ok(RangeResponse::Partial {
+                bytes: response.bytes().await?,
+                total,
+                etag,
+            })
+        }
+        _ => Ok(RangeResponse::Full(decapsulate_response(response).await?.bytes().await?)),
+    }
+}
+
+/// Download the file at the given url to the given path in ranges sized to the bandwidth by a [`RangeSizer`], retrying
+/// the failed ranges within the budget of the given [`TransferConfig`]. The file is downloaded to a `.part` file first,
+/// whose download resumes where it stopped if interrupted, also by a restart of the client. The ranges after the first
+/// one are requested with its `ETag` in `If-Range`, so that a file changed during the download is sent again in full
+/// rather than spliced. Servers ignoring the ranges send the whole file at once. Calls `on_progress` with the number of bytes of the file downloaded so far, and returns
+/// the number of bytes downloaded by this call.
+pub async fn download_ranges(
+    client: &Client,
+    url: &str,
+    path: &Path,
+    config: TransferConfig,
+    mut on_progress: impl FnMut(u64),
+) -> Result<u64> {
+    let part_path = path.with_file_name(format!(
+        "{}.part",
+        path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+    ));
+    let mut file = async_fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(&part_path)
+        .await?;
+    let mut offset = file.metadata().await?.len();
+    file.seek(SeekFrom::Start(offset)).await?;
+    on_progress(offset);
+
+    let mut sizer = RangeSizer::new(config);
+    let mut total = None;
+    let mut etag = None;
+    let mut downloaded = 0;
+    while total.map_or(true, |total| offset < total) {
+        let len = sizer.next_range(total.map_or(u64::MAX, |total| total - offset));
+        let started = Instant::now();
+
+        // The ranges are written once received in full, so that the part file only holds complete ones
+        match download_range(client, url, offset, len, etag.as_deref()).await {
+            Ok(RangeResponse::Partial {
+                bytes,
+                total: length,
+                etag: range_etag,
+            }) => {
+                etag = etag.or(range_etag);
+                file.write_all(&bytes).await?;
+                sizer.record_success(bytes.len() as u64, started.elapsed());
+                offset += bytes.len() as u64;
+                downloaded += bytes.len() as u64;
+                total = Some(length);
+            }
+            Ok(RangeResponse::Full(bytes)) => {
+                file.set_len(0).await?;
+                file.seek(SeekFrom::Start(0)).await?;
+                file.write_all(&bytes).await?;
+                offset = bytes.len() as u64;
+                downloaded = offset;
+                total = Some(offset);
+            }
+            // The part file is already complete if no byte is left after it
+            Ok(RangeResponse::NotSatisfiable) => total = Some(offset),
+            Err(e) => match sizer.record_failure() {
+                Some(delay) => {
+                    debug!("Retrying the range of {} at {} in {:?}: {}", url, offset, delay, e);
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(e),
+            },
+        }
+        on_progress(offset);
+    }
+    file.flush().await?;
+    drop(file);
+    async_fs::rename(&part_path, path).await?;
+
+    Ok(downloaded)
+}
+
+/// Get the progress of the contribution uploaded in ranges to the [Coordinator](`phase2-coordinator::Coordinator`).
+pub async fn get_contribution_upload_progress(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+) -> Result<UploadProgress> {
+    let response = submit_request::<String>(
+        client,
+        coordinator_address,
+        "contributor/upload/contribution/range",
+        Some(keypair),
+        None,
+        Request::Get,
+    )
+    .await?;
+
+    Ok(response.json().await?)
+}
+
+/// Upload a range of the contribution, starting at the given offset, to the [Coordinator](`phase2-coordinator::Coordinator`).
+/// The range at offset zero starts a new upload session, the next ones must carry its id.
+async fn upload_contribution_range(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    offset: u64,
+    session: Option<&str>,
+    range: Vec<u8>,
+) -> Result<UploadProgress> {
+    let mut address = coordinator_address
+        .join("contributor/upload/contribution/range")
+        .map_err(|_| RequestError::AddressParseError)?;
+    address.query_pairs_mut().append_pair("offset", &offset.to_string());
+    if let Some(session) = session {
+        address.query_pairs_mut().append_pair("session", session);
+    }
+
+    let content = RequestContent::new(range.len(), Sha256::digest(&range));
+    let mut headers = SignatureHeaders::new(keypair.pubkey(), Some(content), None);
+    headers.try_sign(keypair.sigkey())?;
+    let header_map: HeaderWrap = headers.try_into()?;
+
+    let response = client
+        .post(address)
+        .headers(header_map.into())
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(range)
+        .send()
+        .await?;
+
+    Ok(decapsulate_response(response).await?.json().await?)
+}
+
+/// Stream the contribution at the given path to the [Coordinator](`phase2-coordinator::Coordinator`) like
+/// [`upload_contribution`], in ranges sized to the bandwidth by a [`RangeSizer`] and retrying the failed ranges within
+/// the budget of the given [`TransferConfig`]. The upload resumes from the bytes already received by the coordinator,
+/// also after a restart of the client. Calls `on_progress` with the number of bytes received so far by the
+/// coordinator, and returns the hex encoded hash of the contribution it received.
+pub async fn upload_contribution_ranges(
+    client: &Client,
+    coordinator_address: &Url,
+    keypair: &KeyPair,
+    path: &Path,
+    config: TransferConfig,
+    mut on_progress: impl FnMut(u64),
+) -> Result<String> {
+    let mut file = async_fs::File::open(path).await?;
+    let size = file.metadata().await?.len();
+    let mut progress = get_contribution_upload_progress(client, coordinator_address, keypair).await?;
+    if progress.size != size {
+        return Err(RequestError::Client(format!(
+            "The contribution is {} bytes long instead of {}",
+            size, progress.size
+        )));
+    }
+
+    let mut sizer = RangeSizer::new(config);
+    loop {
+        on_progress(progress.received);
+        if let Some(hash) = progress.hash {
+            return Ok(hash);
+        }
+
+        // Without a session, the bytes received so far can't be resumed
+        let (offset, session) = match progress.session.as_deref() {
+            Some(session) => (progress.received, Some(session)),
+            None => (0, None),
+        };
+        let mut range = vec![0; sizer.next_range(size - offset) as usize];
+        file.seek(SeekFrom::Start(offset)).await?;
+        file.read_exact(&mut range).await?;
+        let len = range.len() as u64;
+        let started = Instant::now();
+
+        match upload_contribution_range(client, coordinator_address, keypair, offset, session, range).await {
+            Ok(received) => {
+                sizer.record_success(len, started.elapsed());
+                progress = received;
+            }
+            Err(e) => match sizer.record_failure() {
+                Some(delay) => {
+                    debug!("Retrying the range of the contribution at {} in {:?}: {}", offset, delay, e);
+                    tokio::time::sleep(delay).await;
+                    // The coordinator may have received the range before the failure
+                    if let Ok(received) = get_contribution_upload_progress(client, coordinator_address, keypair).await {
+                        progress = received;
+                    }
+                }
+                None => return Err(e),
+            },
+        }
+    }
+}
+
 /// Updates the cohort. [`tokens`] parameter must be the content of the tokens.zip file
 pub async fn post_update_cohorts(
     client: &Client,
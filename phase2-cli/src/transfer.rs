@@ -0,0 +1,100 @@
+//! Sizing of the ranges of the transfers split in ranges, so that the challenge and the contribution complete on
+//! unstable connections.
+//!
+//! A transfer starts with small ranges and measures how long each range takes: the ranges grow while they complete
+//! well within the target duration, to amortize the latency of the requests on fast connections, and shrink when they
+//! are slow or fail, so that a range lost to a dropped connection costs little to send again. A failed range is
+//! retried a few times, and the whole transfer gives up after a budget of failures across its ranges.
+
+use std::time::Duration;
+
+/// The parameters of a transfer split in ranges.
+#[derive(Clone, Copy, Debug)]
+pub struct TransferConfig {
+    /// The size of the first range, in bytes.
+    pub initial_range: u64,
+    pub min_range: u64,
+    pub max_range: u64,
+    /// The duration a range should take: the ranges are sized to the measured throughput to last that long.
+    pub target_duration: Duration,
+    /// The number of times a failed range is retried before giving up.
+    pub range_retries: u32,
+    /// The number of failed ranges, across the transfer, after which it gives up.
+    pub transfer_retries: u32,
+    /// The delay before retrying a failed range, multiplied by the number of attempts of the range.
+    pub retry_delay: Duration,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            initial_range: 256 * 1024,
+            min_range: 64 * 1024,
+            max_range: 64 * 1024 * 1024,
+            target_duration: Duration::from_secs(5),
+            range_retries: 5,
+            transfer_retries: 20,
+            retry_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Sizes the ranges of a transfer to the throughput measured on the previous ones.
+#[derive(Clone, Debug)]
+pub struct RangeSizer {
+    config: TransferConfig,
+    range: u64,
+    /// The number of failures of the current range.
+    range_failures: u32,
+    /// The number of failures since the start of the transfer.
+    transfer_failures: u32,
+}
+
+impl RangeSizer {
+    pub fn new(config: TransferConfig) -> Self {
+        Self {
+            range: config.initial_range.max(config.min_range).min(config.max_range),
+            config,
+            range_failures: 0,
+            transfer_failures: 0,
+        }
+    }
+
+    /// Returns the size of the next range, given the number of bytes left to transfer.
+    pub fn next_range(&self, remaining: u64) -> u64 {
+        self.range.min(remaining)
+    }
+
+    /// Records a range of the given length transferred in the given duration, and resizes the next ones to the
+    /// throughput. The size at most doubles or halves from one range to the next, so that a single outlier doesn't
+    /// swing it.
+    pub fn record_success(&mut self, len: u64, elapsed: Duration) {
+        self.range_failures = 0;
+        // A short final range says little about the throughput
+        if len < self.range / 2 {
+            return;
+        }
+
+        let throughput = len as f64 / elapsed.as_secs_f64().max(0.001);
+        let target = (throughput * self.config.target_duration.as_secs_f64()) as u64;
+        self.range = target
+            .max(self.range / 2)
+            .min(self.range.saturating_mul(2))
+            .max(self.config.min_range)
+            .min(self.config.max_range);
+    }
+
+    /// Records a failed range and halves the next ones. Returns the delay to wait before retrying it, or [`None`] if
+    /// the range or the transfer ran out of retries.
+    pub fn record_failure(&mut self) -> Option<Duration> {
+        self.range_failures += 1;
+        self.transfer_failures += 1;
+        self.range = (self.range / 2).max(self.config.min_range);
+
+        if self.range_failures > self.config.range_retries || self.transfer_failures > self.config.transfer_retries {
+            return None;
+        }
+
+        Some(self.config.retry_delay * self.range_failures)
+    }
+}
@@ -0,0 +1,107 @@
+//! A small ceremony run against the coordinator binary: honest contributions, a tampered one which is rejected and
+//! recovered from, and the audit of the resulting transcript.
+
+use crate::harness::{CoordinatorProcess, TestContributor};
+
+use phase2_coordinator::{
+    authentication::Production,
+    objects::VerificationStatus,
+    rest_utils::ContributorStatus,
+    storage::{hash_chain, RoundManifest},
+};
+use phase2_cli::requests;
+use reqwest::Client;
+use rocket::tokio;
+
+/// Returns the hash of the response of the first contribution of the given round in the manifests.
+fn contribution_hash(manifests: &[RoundManifest], round_height: u64) -> Option<String> {
+    let name = format!("round_{}/chunk_0/contribution_1.unverified", round_height);
+    manifests
+        .iter()
+        .find(|manifest| manifest.round_height == round_height)?
+        .files
+        .iter()
+        .find(|file| file.name.ends_with(&name))?
+        .hash
+        .clone()
+}
+
+#[tokio::test]
+#[ignore = "requires the coordinator binary and the AWS credentials of a test bucket"]
+async fn mini_ceremony() {
+    let honest: Vec<TestContributor> = (0..3).map(|_| TestContributor::default()).collect();
+    let cheater = TestContributor::default();
+    let tokens: Vec<String> = honest
+        .iter()
+        .chain(std::iter::once(&cheater))
+        .map(|contributor| contributor.token().to_owned())
+        .collect();
+
+    let coordinator = CoordinatorProcess::launch(&tokens).await;
+    let client = Client::new();
+    let mut contributions = Vec::new();
+
+    // Round 1: an honest contribution
+    honest[0].join_queue(&client, &coordinator).await;
+    honest[0].wait_for_turn(&client, &coordinator).await;
+    contributions.push(honest[0].contribute(&client, &coordinator, false).await);
+    assert!(matches!(
+        honest[0].wait_for_verification(&client, &coordinator).await,
+        VerificationStatus::Succeeded
+    ));
+
+    // Round 2: a tampered contribution is rejected, its contributor banned and the round taken over by the next one
+    cheater.join_queue(&client, &coordinator).await;
+    honest[1].join_queue(&client, &coordinator).await;
+    cheater.wait_for_turn(&client, &coordinator).await;
+    let (_, cheater_hash) = cheater.contribute(&client, &coordinator, true).await;
+    assert!(matches!(
+        cheater.wait_for_verification(&client, &coordinator).await,
+        VerificationStatus::Failed(_)
+    ));
+    assert!(matches!(cheater.status(&client, &coordinator).await, ContributorStatus::Banned));
+
+    honest[1].wait_for_turn(&client, &coordinator).await;
+    contributions.push(honest[1].contribute(&client, &coordinator, false).await);
+    assert!(matches!(
+        honest[1].wait_for_verification(&client, &coordinator).await,
+        VerificationStatus::Succeeded
+    ));
+
+    // Round 3: another honest contribution, aggregated by the last update
+    honest[2].join_queue(&client, &coordinator).await;
+    honest[2].wait_for_turn(&client, &coordinator).await;
+    contributions.push(honest[2].contribute(&client, &coordinator, false).await);
+    assert!(matches!(
+        honest[2].wait_for_verification(&client, &coordinator).await,
+        VerificationStatus::Succeeded
+    ));
+    coordinator.update(&client).await;
+
+    // Audit of the transcript
+    let manifests = requests::get_transcript_manifests(&client, &coordinator.url, 0, 3)
+        .await
+        .unwrap();
+    assert_eq!(manifests.len(), 4);
+    assert_eq!(hash_chain(&manifests).len(), 4);
+
+    for (round_height, hash) in &contributions {
+        assert_eq!(contribution_hash(&manifests, *round_height).as_ref(), Some(hash));
+
+        let manifest = manifests
+            .iter()
+            .find(|manifest| manifest.round_height == *round_height)
+            .unwrap();
+        let attestation = manifest
+            .attestations
+            .iter()
+            .find(|attestation| attestation.contribution_id == 1)
+            .expect("Missing attestation of the verifier");
+        assert_eq!(attestation.verifier, coordinator.keypair.pubkey());
+        assert!(attestation.verify(&Production));
+    }
+    assert!(manifests
+        .iter()
+        .flat_map(|manifest| manifest.files.iter())
+        .all(|file| file.hash.as_ref() != Some(&cheater_hash)));
+}
@@ -0,0 +1,332 @@
+//! Harness running the coordinator binary, as it is deployed, in a child process with a temporary storage root, and
+//! driving it through its REST API with the client library, like `namada-ts` does.
+//!
+//! The binary is built separately, with the endpoints reserved to debugging:
+//!
+//! ```text
+//! cargo build --bin phase2-coordinator --features parallel
+//! ```
+//!
+//! and found at `PHASE2_COORDINATOR_BIN`, or in the debug target of the workspace by default. Like the in-process
+//! tests, it needs the AWS credentials of a test bucket, see `test.sh`: the harness seeds the tokens of the ceremony
+//! in the bucket with the `aws` cli before launching the coordinator.
+
+use std::{
+    fs,
+    io::Write,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command},
+};
+
+use bytes::Bytes;
+use futures_util::StreamExt;
+use phase2_cli::requests;
+use phase2_coordinator::{
+    authentication::{KeyPair, Production, Signature},
+    commands::{Computation, RandomSource},
+    io,
+    objects::{ContributionInfo, LockedLocators, VerificationStatus},
+    rest_utils::{ContributorStatus, PostChunkRequest, TOKENS_ZIP_FILE},
+    storage::Object,
+    ContributionFileSignature, ContributionState,
+};
+use rand::Rng;
+use reqwest::{Client, Url};
+use rocket::tokio::time::{self, Duration};
+use setup_utils::calculate_hash;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+use zip::write::FileOptions;
+
+/// The prefix of the files of the tokens of the cohorts.
+const TOKENS_FILE_PREFIX: &str = "namada_tokens_cohort";
+
+/// The number of attempts, one second apart, to wait for the coordinator.
+const MAX_ATTEMPTS: usize = 120;
+
+/// Returns the path of the coordinator binary.
+fn coordinator_binary() -> PathBuf {
+    let binary = match std::env::var("PHASE2_COORDINATOR_BIN") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/debug/phase2-coordinator"),
+    };
+    assert!(
+        binary.exists(),
+        "Coordinator binary not found at {}, build it or set PHASE2_COORDINATOR_BIN",
+        binary.display()
+    );
+
+    binary
+}
+
+/// Returns a port on which nothing is listening.
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Uploads the tokens of the first cohort to the bucket, where the coordinator downloads them from at startup.
+fn seed_tokens(storage: &Path, tokens: &[String]) {
+    let zip_path = storage.join("seed").join(TOKENS_ZIP_FILE);
+    fs::create_dir_all(zip_path.parent().unwrap()).unwrap();
+
+    let mut zip_writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    zip_writer
+        .start_file(format!("{}_1.json", TOKENS_FILE_PREFIX), FileOptions::default())
+        .unwrap();
+    zip_writer.write_all(&serde_json::to_vec(tokens).unwrap()).unwrap();
+    zip_writer.finish().unwrap();
+
+    let bucket = std::env::var("AWS_S3_BUCKET").expect("AWS_S3_BUCKET must be set");
+    let status = Command::new("aws")
+        .args(["s3", "cp"])
+        .arg(&zip_path)
+        .arg(format!("s3://{}/master/{}", bucket, TOKENS_ZIP_FILE))
+        .status()
+        .expect("Couldn't run the aws cli");
+    assert!(status.success(), "Couldn't upload the tokens to the bucket");
+}
+
+/// Returns the given message of failure with the log of the coordinator running in the given storage root.
+fn failure(storage: &Path, message: &str) -> String {
+    let log = fs::read_to_string(storage.join("coordinator.log")).unwrap_or_default();
+    format!("{}, log of the coordinator:\n{}", message, log)
+}
+
+/// The coordinator binary running in a child process, killed on drop together with its storage.
+pub struct CoordinatorProcess {
+    child: Child,
+    pub url: Url,
+    /// The keypair of the coordinator, which is also the verifier of the ceremony.
+    pub keypair: KeyPair,
+    storage: TempDir,
+}
+
+impl CoordinatorProcess {
+    /// Launches the coordinator in a temporary storage root and waits until it serves requests. The given tokens are
+    /// the ones of the first cohort.
+    pub async fn launch(tokens: &[String]) -> Self {
+        let storage = tempfile::tempdir().unwrap();
+
+        // The parameters of the powers of tau are read from the working directory
+        for entry in fs::read_dir(env!("CARGO_MANIFEST_DIR")).unwrap() {
+            let entry = entry.unwrap();
+            if entry.file_name().to_string_lossy().starts_with("phase1radix") {
+                std::os::unix::fs::symlink(entry.path(), storage.path().join(entry.file_name())).unwrap();
+            }
+        }
+        let health_path = storage.path().join("status.json");
+        fs::write(&health_path, "{}").unwrap();
+        seed_tokens(storage.path(), tokens);
+
+        let port = free_port();
+        let log = fs::File::create(storage.path().join("coordinator.log")).unwrap();
+        let mut child = Command::new(coordinator_binary())
+            .current_dir(storage.path())
+            .env("ROCKET_CONFIG", storage.path().join("Rocket.toml"))
+            .env("ROCKET_ADDRESS", "127.0.0.1")
+            .env("ROCKET_PORT", port.to_string())
+            .env("HEALTH_PATH", &health_path)
+            .env("NAMADA_TOKENS_PATH", storage.path().join("tokens"))
+            .env("TOKENS_FILE_PREFIX", TOKENS_FILE_PREFIX)
+            .env_remove("AWS_S3_PROD")
+            .env_remove("NAMADA_MPC_PRESET")
+            .stdout(log.try_clone().unwrap())
+            .stderr(log)
+            .spawn()
+            .expect("Couldn't launch the coordinator");
+
+        let url = Url::parse(&format!("http://127.0.0.1:{}", port)).unwrap();
+        let client = Client::new();
+        for _ in 0..MAX_ATTEMPTS {
+            if requests::ping_coordinator(&client, &url).await.is_ok() {
+                // The mnemonic of the coordinator is written before it starts serving
+                let mnemonic = fs::read_to_string(storage.path().join("coordinator.mnemonic")).unwrap();
+                let seed = io::seed_from_string(&mnemonic).unwrap();
+
+                return Self {
+                    child,
+                    url,
+                    keypair: KeyPair::try_from_seed(&seed).unwrap(),
+                    storage,
+                };
+            }
+
+            if let Some(status) = child.try_wait().unwrap() {
+                panic!("{}", failure(storage.path(), &format!("Coordinator exited with {}", status)));
+            }
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
+        child.kill().ok();
+        panic!("{}", failure(storage.path(), "Coordinator didn't start"))
+    }
+
+    /// Returns the given message of failure with the log of the coordinator.
+    pub fn failure(&self, message: &str) -> String {
+        failure(self.storage.path(), message)
+    }
+
+    /// Updates the coordinator, advancing the ceremony to the next round when possible.
+    pub async fn update(&self, client: &Client) {
+        requests::get_update(client, &self.url, &self.keypair)
+            .await
+            .unwrap();
+    }
+
+    /// Verifies the pending contributions.
+    pub async fn verify(&self, client: &Client) {
+        requests::get_verify_chunks(client, &self.url, &self.keypair)
+            .await
+            .unwrap();
+    }
+}
+
+impl Drop for CoordinatorProcess {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+/// A contributor of the ceremony, driven through the client library.
+pub struct TestContributor {
+    pub keypair: KeyPair,
+    token: String,
+}
+
+impl Default for TestContributor {
+    fn default() -> Self {
+        Self {
+            keypair: KeyPair::new(),
+            token: hex::encode(rand::thread_rng().gen::<[u8; 32]>()),
+        }
+    }
+}
+
+impl TestContributor {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub async fn join_queue(&self, client: &Client, coordinator: &CoordinatorProcess) {
+        requests::post_join_queue(client, &coordinator.url, &self.keypair, &self.token)
+            .await
+            .unwrap();
+    }
+
+    pub async fn status(&self, client: &Client, coordinator: &CoordinatorProcess) -> ContributorStatus {
+        requests::get_contributor_queue_status(client, &coordinator.url, &self.keypair)
+            .await
+            .unwrap()
+    }
+
+    /// Updates the coordinator until the contributor is assigned to the current round.
+    pub async fn wait_for_turn(&self, client: &Client, coordinator: &CoordinatorProcess) {
+        for _ in 0..MAX_ATTEMPTS {
+            coordinator.update(client).await;
+            if let ContributorStatus::Round = self.status(client, coordinator).await {
+                return;
+            }
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
+        panic!("{}", coordinator.failure("Contributor wasn't assigned to a round"))
+    }
+
+    ///
+    /// Computes a contribution on the challenge of the current round and streams it to the coordinator. A tampered
+    /// contribution claims to be computed on another challenge and fails verification.
+    ///
+    /// Returns the round of the contribution and its hex encoded hash.
+    ///
+    pub async fn contribute(&self, client: &Client, coordinator: &CoordinatorProcess, tampered: bool) -> (u64, String) {
+        let locked_locators: LockedLocators = requests::get_lock_chunk(client, &coordinator.url, &self.keypair)
+            .await
+            .unwrap();
+        let round_height = locked_locators.current_contribution().round_height();
+
+        let challenge_url = requests::get_challenge_url(client, &coordinator.url, &self.keypair, &round_height)
+            .await
+            .unwrap();
+        let challenge_size = locked_locators.sizes().map(|sizes| sizes.challenge);
+        let (mut challenge_stream, _) = requests::get_challenge(client, &challenge_url, challenge_size)
+            .await
+            .unwrap();
+        let mut challenge = Vec::new();
+        while let Some(bytes) = challenge_stream.next().await {
+            challenge.extend_from_slice(&bytes.unwrap());
+        }
+
+        let challenge_hash = calculate_hash(&challenge);
+        let mut contribution = challenge_hash.to_vec();
+        let seed = RandomSource::Seed(rand::thread_rng().gen::<[u8; 32]>());
+        Computation::contribute_test_masp(&challenge, &mut contribution, &seed);
+        let next_contribution = locked_locators.next_contribution();
+        contribution.resize(
+            Object::anoma_contribution_file_size(round_height, next_contribution.contribution_id()) as usize,
+            0,
+        );
+        if tampered {
+            contribution[0] ^= 1;
+        }
+
+        let response_hash = calculate_hash(&contribution);
+        let contribution_state = ContributionState::new(challenge_hash.to_vec(), response_hash.to_vec(), None).unwrap();
+        let signature = Production
+            .sign(self.keypair.sigkey(), &contribution_state.signature_message().unwrap())
+            .unwrap();
+        let contribution_file_signature = ContributionFileSignature::new(signature, contribution_state).unwrap();
+
+        let mut contribution_info = ContributionInfo::default();
+        contribution_info.public_key = self.keypair.pubkey().to_owned();
+        contribution_info.ceremony_round = round_height;
+        contribution_info.try_sign(&self.keypair).unwrap();
+        requests::post_contribution_info(client, &coordinator.url, &self.keypair, &contribution_info)
+            .await
+            .unwrap();
+
+        let digest = Sha256::digest(&contribution);
+        let contribution_len = contribution.len();
+        let stream = futures_util::stream::iter(vec![Ok::<_, std::io::Error>(Bytes::from(contribution))]);
+        let hash = requests::upload_contribution(
+            client,
+            &coordinator.url,
+            &self.keypair,
+            stream,
+            contribution_len,
+            &digest,
+        )
+        .await
+        .unwrap();
+        assert_eq!(hex::encode(response_hash), hash);
+
+        let request = PostChunkRequest::spooled(
+            round_height,
+            next_contribution,
+            locked_locators.next_contribution_file_signature(),
+            contribution_file_signature,
+        );
+        requests::post_contribute_chunk(client, &coordinator.url, &self.keypair, &request)
+            .await
+            .unwrap();
+
+        (round_height, hash)
+    }
+
+    /// Verifies the pending contributions until the one of the contributor completes, and returns its status.
+    pub async fn wait_for_verification(&self, client: &Client, coordinator: &CoordinatorProcess) -> VerificationStatus {
+        for _ in 0..MAX_ATTEMPTS {
+            coordinator.verify(client).await;
+            let status = requests::get_verification_status(client, &coordinator.url, &self.keypair)
+                .await
+                .unwrap();
+            if status.is_completed() {
+                return status;
+            }
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
+        panic!("{}", coordinator.failure("Verification didn't complete"))
+    }
+}
@@ -3,15 +3,23 @@
 //	It could be possible to define a separate location (base_dir) for every test
 //	but it's simpler to just run the tests sequentially.
 //  NOTE: these tests require the phase1radix files to be placed in the phase2-cli folder
+//  NOTE: the tests of the `ceremony` module run the coordinator binary instead of an in-process server, see `harness`
+
+mod ceremony;
+mod harness;
 
 use std::{io::Write, net::IpAddr, sync::Arc};
 
 use phase2_coordinator::{
     authentication::{KeyPair, Production, Signature},
+    capability_probe::CapabilityProber,
     commands::{Computation, RandomSource},
     coordinator_state::CoordinatorState,
     environment::Testing,
+    human_verification::HumanVerifier,
+    identity::IdentityVerifier,
     objects::{ContributionInfo, LockedLocators, TrimmedContributionInfo},
+    pow::ProofOfWork,
     rest,
     rest_utils::{self, PostChunkRequest, TOKENS_ZIP_FILE},
     storage::{ContributionLocator, ContributionSignatureLocator, Object},
@@ -33,7 +41,10 @@ use async_stream::try_stream;
 use futures_util::StreamExt;
 use toml::Value;
 
-use phase2_cli::requests;
+use phase2_cli::{
+    prevalidation::{self, ChallengeError},
+    requests,
+};
 use reqwest::{Client, Url};
 use zip::write::FileOptions;
 
@@ -138,9 +149,16 @@ async fn test_prelude() -> (TestCtx, JoinHandle<Result<Rocket<Ignite>, Error>>)
         .mount(
             "/",
             routes![
+                rest::get_pow_puzzle,
+                rest::get_capability_probe,
+                rest::get_capability_probe_blob,
                 rest::join_queue,
                 rest::lock_chunk,
                 rest::contribute_chunk,
+                rest::get_challenge_bundle,
+                rest::post_response_bundle,
+                rest::get_pending_verifications,
+                rest::post_verification_vote,
                 rest::update_coordinator,
                 rest::heartbeat,
                 rest::stop_coordinator,
@@ -152,11 +170,17 @@ async fn test_prelude() -> (TestCtx, JoinHandle<Result<Rocket<Ignite>, Error>>)
                 rest::get_contribution_url,
                 rest::get_challenge_url,
                 rest::get_coordinator_state,
+                rest::get_transcript_manifests,
                 rest::update_cohorts,
-                rest::post_attestation
+                rest::post_attestation,
+                rest::post_attestation_post
             ],
         )
         .manage(coordinator)
+        .manage(ProofOfWork::new(None))
+        .manage(CapabilityProber::new(None, Duration::from_secs(300)))
+        .manage(HumanVerifier::default())
+        .manage(IdentityVerifier::default())
         .register(
             "/",
             catchers![
@@ -166,7 +190,9 @@ async fn test_prelude() -> (TestCtx, JoinHandle<Result<Rocket<Ignite>, Error>>)
                 rest_utils::io_error,
                 rest_utils::unprocessable_entity,
                 rest_utils::mismatching_checksum,
-                rest_utils::invalid_header
+                rest_utils::invalid_header,
+                rest_utils::schema_violation,
+                rest_utils::invalid_lifecycle_phase
             ],
         );
 
@@ -556,6 +582,7 @@ async fn wrong_post_contribution_info() {
 ///
 /// - get_challenge_url
 /// - get_challenge
+/// - get_transcript_manifests and prevalidate_challenge
 /// - get_contribution_url
 /// - upload_chunk
 /// - post_contributor_info
@@ -594,13 +621,43 @@ async fn contribution() {
         .unwrap();
 
     // Get challenge
-    let mut challenge_stream = requests::get_challenge(&client, challenge_url.as_str()).await.unwrap();
+    let challenge_size = ctx.contributors[0]
+        .locked_locators
+        .as_ref()
+        .and_then(|locked_locators| locked_locators.sizes())
+        .map(|sizes| sizes.challenge);
+    let mut challenge_stream = requests::get_challenge(&client, challenge_url.as_str(), challenge_size)
+        .await
+        .unwrap();
 
     let mut challenge: Vec<u8> = Vec::new();
     while let Some(b) = challenge_stream.0.next().await {
         let b = b.unwrap();
         challenge.extend_from_slice(&b);
     }
+    assert_eq!(challenge.len() as u64, challenge_stream.1);
+    assert_eq!(Some(challenge_stream.1), challenge_size);
+
+    // Validate the challenge against the transcript of the coordinator
+    let manifests = requests::get_transcript_manifests(&client, &url, ROUND_HEIGHT - 1, ROUND_HEIGHT)
+        .await
+        .unwrap();
+    let challenge_locator = ctx.contributors[0]
+        .locked_locators
+        .as_ref()
+        .unwrap()
+        .current_contribution();
+    prevalidation::prevalidate_challenge(&challenge, &challenge_locator, &manifests, Some(1)).unwrap();
+    let mut tampered_challenge = challenge.clone();
+    *tampered_challenge.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        prevalidation::prevalidate_challenge(&tampered_challenge, &challenge_locator, &manifests, Some(1)),
+        Err(ChallengeError::HashMismatch { .. })
+    ));
+    assert!(matches!(
+        prevalidation::prevalidate_challenge(&challenge[..32], &challenge_locator, &manifests, Some(1)),
+        Err(ChallengeError::MissingHeader { size: 32 })
+    ));
 
     // Get contribution url
     let (chunk_url, sig_url) =
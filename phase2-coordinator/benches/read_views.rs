@@ -0,0 +1,85 @@
+use phase2_coordinator::{
+    authentication::Dummy,
+    testing::{clear_test_storage, TEST_ENVIRONMENT_3},
+    Coordinator, Participant,
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::sync::Arc;
+
+/// The number of contributors waiting in the queue.
+const QUEUE_SIZE: usize = 1000;
+
+/// Returns a coordinator in its first round, with one current contributor and a full queue.
+fn coordinator() -> (Coordinator, Participant, Participant) {
+    clear_test_storage(&TEST_ENVIRONMENT_3);
+    let mut coordinator = Coordinator::new(TEST_ENVIRONMENT_3.clone(), Arc::new(Dummy)).unwrap();
+    coordinator.initialize().unwrap();
+
+    for i in 0..=QUEUE_SIZE {
+        let contributor = Participant::new_contributor(&format!("contributor_{}", i));
+        coordinator
+            .add_to_queue(contributor, None, format!("token_{}", i), 10)
+            .unwrap();
+    }
+    coordinator.update().unwrap();
+
+    let current = coordinator.current_contributors()[0].0.clone();
+    let queued = Participant::new_contributor(&format!("contributor_{}", QUEUE_SIZE));
+    assert!(coordinator.queue_position(&queued).is_some());
+
+    (coordinator, current, queued)
+}
+
+// This was the previous implementation, reading the round from storage before checking the state, we keep it here to
+// compare performance against the check of the state first
+fn is_current_contributor_round_first(coordinator: &Coordinator, participant: &Participant) -> bool {
+    match coordinator.current_round() {
+        Ok(round) => round.is_contributor(participant) && coordinator.state().is_current_contributor(participant),
+        Err(_) => false,
+    }
+}
+
+/// Benchmark of the checks run on every request of a contributor, by the request guards and by the polling of the
+/// status of the queue. Most of the requests come from the contributors waiting in the queue.
+fn contributor_status(c: &mut Criterion) {
+    let (coordinator, current, queued) = coordinator();
+
+    let mut group = c.benchmark_group("is_current_contributor");
+    for (name, participant) in &[("queued", &queued), ("current", &current)] {
+        group.bench_function(format!("round_first_{}", name), |b| {
+            b.iter(|| is_current_contributor_round_first(&coordinator, participant))
+        });
+        group.bench_function(format!("state_first_{}", name), |b| {
+            b.iter(|| coordinator.is_current_contributor(participant))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("contribution_in_progress");
+    group.bench_function("copied_contributors", |b| b.iter(|| !coordinator.current_contributors().is_empty()));
+    group.bench_function("number_of_contributors", |b| b.iter(|| coordinator.number_of_current_contributors() > 0));
+    group.finish();
+
+    let mut group = c.benchmark_group("current_contributor");
+    group.bench_function("copied_contributors", |b| {
+        b.iter(|| {
+            coordinator
+                .current_contributors()
+                .into_iter()
+                .next()
+                .map(|(participant, _)| participant.address())
+        })
+    });
+    group.bench_function("contributor_ids", |b| {
+        b.iter(|| coordinator.current_contributor_ids().next().map(Participant::address))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("queue_position");
+    group.bench_function("queued", |b| b.iter(|| coordinator.queue_position(&queued)));
+    group.finish();
+}
+
+criterion_group!(benches, contributor_status);
+criterion_main!(benches);
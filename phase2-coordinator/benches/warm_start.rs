@@ -0,0 +1,37 @@
+use phase2_coordinator::{
+    authentication::Dummy,
+    testing::{clear_test_storage, TEST_ENVIRONMENT_3},
+    Coordinator,
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::sync::Arc;
+
+/// Returns a coordinator restarted on the storage of an initialized ceremony.
+fn warm_start() -> Coordinator {
+    let mut coordinator = Coordinator::new(TEST_ENVIRONMENT_3.clone(), Arc::new(Dummy)).unwrap();
+    coordinator.initialize().unwrap();
+
+    coordinator
+}
+
+/// Benchmark of the restart of the coordinator on an existing ceremony, the storage of which is loaded and checked
+/// against its metadata before serving any request.
+fn startup(c: &mut Criterion) {
+    clear_test_storage(&TEST_ENVIRONMENT_3);
+    let coordinator = warm_start();
+    println!("{}", coordinator.startup_profile().summary());
+
+    let mut group = c.benchmark_group("startup");
+    group.sample_size(10);
+    group.bench_function("warm_start", |b| b.iter(warm_start));
+
+    let mut coordinator = coordinator;
+    group.bench_function("storage_consistency", |b| {
+        b.iter(|| coordinator.check_storage_consistency(false).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, startup);
+criterion_main!(benches);
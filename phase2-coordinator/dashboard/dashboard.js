@@ -0,0 +1,84 @@
+// Polls the public endpoints of the coordinator and renders the status of the ceremony.
+
+const REFRESH_INTERVAL_MS = 10000;
+const RECENT_CONTRIBUTIONS = 10;
+
+async function fetchJson(path) {
+  const response = await fetch(path, { headers: { Accept: "application/json" } });
+  if (!response.ok) {
+    throw new Error(`${path} answered ${response.status}`);
+  }
+
+  return response.json();
+}
+
+function setText(id, text) {
+  document.getElementById(id).textContent = text;
+}
+
+function shortKey(key) {
+  return key.length > 16 ? `${key.slice(0, 8)}...${key.slice(-8)}` : key;
+}
+
+function renderStatus(status) {
+  setText("round-height", status.round_height);
+  setText("queue-size", status.queue_size);
+  setText("contribution-in-progress", status.contribution_in_progress ? "Yes" : "No");
+  setText("update-interval", `${status.update_interval}s`);
+}
+
+function renderCircuits(registry) {
+  setText("circuits", registry.circuits.length);
+}
+
+function renderContributions(contributions) {
+  const body = document.getElementById("contributions");
+  const recent = contributions
+    .slice()
+    .sort((a, b) => b.ceremony_round - a.ceremony_round)
+    .slice(0, RECENT_CONTRIBUTIONS);
+
+  if (recent.length === 0) {
+    return;
+  }
+
+  body.replaceChildren(
+    ...recent.map((contribution) => {
+      const row = document.createElement("tr");
+      const cells = [
+        contribution.ceremony_round,
+        contribution.full_name || "Anonymous",
+        shortKey(contribution.public_key),
+        new Date(contribution.timestamps.end_contribution).toLocaleString(),
+      ];
+
+      for (const value of cells) {
+        const cell = document.createElement("td");
+        cell.textContent = value;
+        row.appendChild(cell);
+      }
+
+      return row;
+    })
+  );
+}
+
+async function refresh() {
+  const results = await Promise.allSettled([
+    fetchJson("/ceremony_status").then(renderStatus),
+    fetchJson("/circuits").then(renderCircuits),
+    fetchJson("/contribution_info").then(renderContributions),
+  ]);
+
+  const failed = results.filter((result) => result.status === "rejected");
+  failed.forEach((result) => console.error(result.reason));
+  setText(
+    "updated",
+    failed.length === 0
+      ? `Updated at ${new Date().toLocaleTimeString()}`
+      : `Partially updated at ${new Date().toLocaleTimeString()}`
+  );
+}
+
+refresh();
+setInterval(refresh, REFRESH_INTERVAL_MS);
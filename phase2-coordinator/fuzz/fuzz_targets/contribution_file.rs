@@ -0,0 +1,11 @@
+//! The contribution files uploaded by the contributors: the hash of the previous contribution at their head, then
+//! the parameters of the circuits read before the verification.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phase2_coordinator::conversion;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = conversion::read_parameters(data);
+});
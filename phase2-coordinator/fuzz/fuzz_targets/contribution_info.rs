@@ -0,0 +1,19 @@
+//! The summary of a contribution posted by the contributors, whose texts are published in the transcript of the
+//! ceremony.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phase2_coordinator::{objects::ContributionInfo, rest_utils};
+
+fuzz_target!(|data: &[u8]| {
+    let info = match rest_utils::parse_json_body::<ContributionInfo>(data) {
+        Ok(info) => info,
+        Err(_) => return,
+    };
+    let _ = info.validate();
+
+    let canonical = rest_utils::to_canonical_json(&info).expect("accepted info failed to serialize");
+    let decoded = rest_utils::parse_json_body::<ContributionInfo>(&canonical).expect("canonical info rejected");
+    assert_eq!(canonical, rest_utils::to_canonical_json(&decoded).unwrap());
+});
@@ -0,0 +1,19 @@
+//! The body of the requests posting a contribution, whose locators and signature decide what the coordinator reads
+//! and writes in its storage.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phase2_coordinator::rest_utils::{self, PostChunkRequest};
+
+fuzz_target!(|data: &[u8]| {
+    let request = match rest_utils::parse_json_body::<PostChunkRequest>(data) {
+        Ok(request) => request,
+        Err(_) => return,
+    };
+
+    // The canonical encoding, signed by the contributors, must decode to the same request
+    let canonical = rest_utils::to_canonical_json(&request).expect("accepted request failed to serialize");
+    let decoded = rest_utils::parse_json_body::<PostChunkRequest>(&canonical).expect("canonical request rejected");
+    assert_eq!(canonical, rest_utils::to_canonical_json(&decoded).unwrap());
+});
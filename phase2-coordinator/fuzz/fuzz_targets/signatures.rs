@@ -0,0 +1,41 @@
+//! The signatures supplied by the participants: the signatures of the contribution files, and the keys and the
+//! signatures of the requests in their native, OpenSSH and post-quantum formats.
+//!
+//! The input is the public key, the message and the signature on three lines, also parsed as a whole.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phase2_coordinator::{
+    authentication::{
+        ssh::{self, SshPublicKey, SshSignature},
+        Production,
+        Signature,
+    },
+    objects::{ContributionFileSignature, SignedVerifierDelegation},
+};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = ContributionFileSignature::from_slice(data, &Production.name());
+
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let _ = SignedVerifierDelegation::from_header(text);
+
+    let mut lines = text.splitn(3, '\n');
+    let (public_key, message, signature) = match (lines.next(), lines.next(), lines.next()) {
+        (Some(public_key), Some(message), Some(signature)) => (public_key, message, signature),
+        _ => return,
+    };
+
+    if ssh::is_ssh_public_key(public_key) {
+        let _ = SshPublicKey::parse(public_key);
+        let _ = ssh::canonical_public_key(public_key);
+    }
+    if SshSignature::is_ssh_signature(signature) {
+        let _ = SshSignature::parse(signature);
+    }
+    let _ = Production.verify(public_key, message, signature);
+});
@@ -0,0 +1,94 @@
+//! The envelope of a signed request: the headers of the signature and of the digest of the body, then the body
+//! decompressed and deserialized in the order of [`LazyJson`](`phase2_coordinator::rest_utils::LazyJson`).
+//!
+//! The input is the text of the headers, one `Name: value` per line, followed by an empty line and the body.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phase2_coordinator::{
+    authentication::{Production, Signature},
+    compression::{ContentEncoding, CONTENT_ENCODING_HEADER},
+    objects::SignedVerifierDelegation,
+    rest_utils::{
+        self,
+        PostChunkRequest,
+        RequestContent,
+        SignatureHeaders,
+        BODY_DIGEST_HEADER,
+        CONTENT_LENGTH_HEADER,
+        DELEGATION_HEADER,
+        PUBKEY_HEADER,
+        SIGNATURE_HEADER,
+    },
+};
+
+/// The limit of the size of the bodies, far below the one of the server so that the runs stay fast.
+const LIMIT: u64 = 1024 * 1024;
+
+fn split_request(data: &[u8]) -> Option<(Vec<(&str, &str)>, &[u8])> {
+    let end = data.windows(2).position(|window| window == b"\n\n")?;
+    let headers = std::str::from_utf8(&data[..end])
+        .ok()?
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+
+    Some((headers, &data[end + 2..]))
+}
+
+fn header<'a>(headers: &[(&'a str, &'a str)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (headers, body) = match split_request(data) {
+        Some(request) => request,
+        None => return,
+    };
+
+    if let Some(delegation) = header(&headers, DELEGATION_HEADER) {
+        let _ = SignedVerifierDelegation::from_header(delegation);
+    }
+
+    let (pubkey, signature) = match (header(&headers, PUBKEY_HEADER), header(&headers, SIGNATURE_HEADER)) {
+        (Some(pubkey), Some(signature)) => (pubkey, signature),
+        _ => return,
+    };
+    let len = header(&headers, CONTENT_LENGTH_HEADER);
+    let content = match (len, header(&headers, BODY_DIGEST_HEADER)) {
+        (Some(len), Some(digest)) => match RequestContent::try_from_header(len, digest) {
+            Ok(content) => Some(content),
+            Err(_) => return,
+        },
+        _ => None,
+    };
+    let content_length = content.as_ref().map(|content| content.content_length());
+
+    let signature_headers = SignatureHeaders::new(pubkey, content, None);
+    let _ = Production.verify(pubkey, &signature_headers.message(), signature);
+    let _ = Production.verify(pubkey, &signature_headers.legacy_message(), signature);
+
+    // The digest of the body is not checked, for the inputs to reach the decompression and the deserialization. The
+    // body is read up to the announced length, which can't exceed the limit
+    let len = match content_length {
+        Some(len) if len as u64 <= LIMIT => len.min(body.len()),
+        _ => return,
+    };
+    let encoding = match header(&headers, CONTENT_ENCODING_HEADER).map(str::parse::<ContentEncoding>) {
+        Some(Ok(encoding)) => encoding,
+        Some(Err(_)) => return,
+        None => ContentEncoding::Identity,
+    };
+    let body = match encoding.decompress(&body[..len], LIMIT) {
+        Ok(body) => body,
+        Err(_) => return,
+    };
+    assert!(body.len() as u64 <= LIMIT, "decompressed body exceeds the limit");
+
+    let _ = rest_utils::parse_json_body::<PostChunkRequest>(&body);
+});
@@ -4,5 +4,9 @@ pub use dummy::*;
 pub mod production;
 pub use production::*;
 
+pub mod post_quantum;
+
 pub mod signature;
 pub use signature::*;
+
+pub mod ssh;
@@ -0,0 +1,249 @@
+//! Post-quantum signatures, for the participants who want their requests and contributions to resist the claims of
+//! forgery of a future quantum computer, behind the `post-quantum` feature.
+//!
+//! A participant chooses its scheme with its key: the public keys, signing keys and signatures of the post-quantum
+//! schemes are the [`hex`] encoding of their bytes prefixed with the name of the scheme, e.g. `dilithium3:<hex>`.
+//! [`Production`](super::Production) verifies them next to the ed25519 ones, and the scheme is named in the signature
+//! of each contribution, published with the transcript. Without the feature, the post-quantum keys and signatures are
+//! recognized but never verify.
+
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[cfg(feature = "post-quantum")]
+use pqcrypto_dilithium::dilithium3;
+#[cfg(feature = "post-quantum")]
+use pqcrypto_sphincsplus::sphincssha2256ssimple as sphincs;
+#[cfg(feature = "post-quantum")]
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
+
+/// The separator of the name of the scheme and of the encoded key or signature.
+const SEPARATOR: char = ':';
+
+#[derive(Debug, Error)]
+pub enum PostQuantumError {
+    #[error("Invalid hex encoding: {0}")]
+    InvalidEncoding(#[from] hex::FromHexError),
+    #[error("Invalid {0}")]
+    InvalidKey(&'static str),
+    #[error("Not a post-quantum {0}")]
+    NotPostQuantum(&'static str),
+    #[error("Post-quantum signatures require the post-quantum feature")]
+    Unsupported,
+}
+
+/// A post-quantum signature scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostQuantumScheme {
+    /// CRYSTALS-Dilithium at the NIST security level 3.
+    Dilithium3,
+    /// SPHINCS+ with SHA2 at the NIST security level 5, with small signatures. Its security relies on the hash
+    /// function only.
+    SphincsSha2256s,
+}
+
+impl PostQuantumScheme {
+    pub const ALL: [PostQuantumScheme; 2] = [PostQuantumScheme::Dilithium3, PostQuantumScheme::SphincsSha2256s];
+
+    /// Returns the name of the scheme, which prefixes its keys and signatures.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PostQuantumScheme::Dilithium3 => "dilithium3",
+            PostQuantumScheme::SphincsSha2256s => "sphincs-sha2-256s",
+        }
+    }
+
+    /// Returns the length, in bytes, of the signatures of the scheme.
+    pub fn signature_length(&self) -> usize {
+        match self {
+            PostQuantumScheme::Dilithium3 => 3293,
+            PostQuantumScheme::SphincsSha2256s => 29792,
+        }
+    }
+
+    /// Splits a prefixed key or signature into its scheme and its encoded bytes, or returns [`None`] if it isn't
+    /// post-quantum.
+    pub fn split(value: &str) -> Option<(Self, &str)> {
+        let (name, encoded) = value.trim().split_once(SEPARATOR)?;
+
+        Some((name.parse().ok()?, encoded))
+    }
+
+    /// Prefixes the [`hex`] encoding of the bytes with the name of the scheme.
+    fn encode(&self, bytes: &[u8]) -> String {
+        format!("{}{}{}", self.name(), SEPARATOR, hex::encode(bytes))
+    }
+
+    /// Generates a random key pair of the scheme, returned as the prefixed public and signing keys.
+    #[cfg(feature = "post-quantum")]
+    pub fn keypair(&self) -> (String, String) {
+        match self {
+            PostQuantumScheme::Dilithium3 => {
+                let (public_key, signing_key) = dilithium3::keypair();
+                (self.encode(public_key.as_bytes()), self.encode(signing_key.as_bytes()))
+            }
+            PostQuantumScheme::SphincsSha2256s => {
+                let (public_key, signing_key) = sphincs::keypair();
+                (self.encode(public_key.as_bytes()), self.encode(signing_key.as_bytes()))
+            }
+        }
+    }
+
+    #[cfg(feature = "post-quantum")]
+    fn sign_bytes(&self, signing_key: &[u8], message: &[u8]) -> Result<Vec<u8>, PostQuantumError> {
+        let invalid_key = |_| PostQuantumError::InvalidKey("signing key");
+
+        Ok(match self {
+            PostQuantumScheme::Dilithium3 => {
+                let signing_key = dilithium3::SecretKey::from_bytes(signing_key).map_err(invalid_key)?;
+                dilithium3::detached_sign(message, &signing_key).as_bytes().to_vec()
+            }
+            PostQuantumScheme::SphincsSha2256s => {
+                let signing_key = sphincs::SecretKey::from_bytes(signing_key).map_err(invalid_key)?;
+                sphincs::detached_sign(message, &signing_key).as_bytes().to_vec()
+            }
+        })
+    }
+
+    #[cfg(not(feature = "post-quantum"))]
+    fn sign_bytes(&self, _signing_key: &[u8], _message: &[u8]) -> Result<Vec<u8>, PostQuantumError> {
+        Err(PostQuantumError::Unsupported)
+    }
+
+    #[cfg(feature = "post-quantum")]
+    fn verify_bytes(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            PostQuantumScheme::Dilithium3 => {
+                match (
+                    dilithium3::PublicKey::from_bytes(public_key),
+                    dilithium3::DetachedSignature::from_bytes(signature),
+                ) {
+                    (Ok(public_key), Ok(signature)) => {
+                        dilithium3::verify_detached_signature(&signature, message, &public_key).is_ok()
+                    }
+                    _ => false,
+                }
+            }
+            PostQuantumScheme::SphincsSha2256s => {
+                match (
+                    sphincs::PublicKey::from_bytes(public_key),
+                    sphincs::DetachedSignature::from_bytes(signature),
+                ) {
+                    (Ok(public_key), Ok(signature)) => {
+                        sphincs::verify_detached_signature(&signature, message, &public_key).is_ok()
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "post-quantum"))]
+    fn verify_bytes(&self, _public_key: &[u8], _message: &[u8], _signature: &[u8]) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for PostQuantumScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for PostQuantumScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PostQuantumScheme::ALL
+            .iter()
+            .find(|scheme| scheme.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown post-quantum signature scheme {}", s))
+    }
+}
+
+/// Returns `true` if the key or the signature is prefixed with the name of a post-quantum scheme.
+pub fn is_post_quantum(value: &str) -> bool {
+    PostQuantumScheme::split(value).is_some()
+}
+
+/// Signs the message with a prefixed signing key, and returns the prefixed signature.
+pub fn sign(signing_key: &str, message: &[u8]) -> Result<String, PostQuantumError> {
+    let (scheme, encoded) = PostQuantumScheme::split(signing_key).ok_or(PostQuantumError::NotPostQuantum("key"))?;
+    let signature = scheme.sign_bytes(&hex::decode(encoded)?, message)?;
+
+    Ok(scheme.encode(&signature))
+}
+
+/// Verifies the prefixed signature of the message with a prefixed public key of the same scheme.
+pub fn verify(public_key: &str, message: &[u8], signature: &str) -> bool {
+    match (
+        PostQuantumScheme::split(public_key),
+        PostQuantumScheme::split(signature),
+    ) {
+        (Some((key_scheme, public_key)), Some((signature_scheme, signature))) if key_scheme == signature_scheme => {
+            match (hex::decode(public_key), hex::decode(signature)) {
+                (Ok(public_key), Ok(signature)) => key_scheme.verify_bytes(&public_key, message, &signature),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_quantum_scheme() {
+        for scheme in &PostQuantumScheme::ALL {
+            assert_eq!(scheme.to_string().parse::<PostQuantumScheme>().unwrap(), *scheme);
+
+            let value = scheme.encode(&[1, 2]);
+            assert!(is_post_quantum(&value));
+            assert_eq!(PostQuantumScheme::split(&value), Some((*scheme, "0102")));
+        }
+
+        // Native and OpenSSH keys aren't post-quantum
+        assert!(!is_post_quantum(&hex::encode([1u8; 32])));
+        assert!(!is_post_quantum(
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOydlGgcNTiGOqqCDTcTthFVPdS27gEGCZUZVrrEfoIV"
+        ));
+        assert!(!is_post_quantum("falcon512:0102"));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_sign_and_verify() {
+        let message = b"This is the message to sign";
+
+        for scheme in &PostQuantumScheme::ALL {
+            let (public_key, signing_key) = scheme.keypair();
+            let signature = sign(&signing_key, message).unwrap();
+            let (_, encoded) = PostQuantumScheme::split(&signature).unwrap();
+            assert_eq!(hex::decode(encoded).unwrap().len(), scheme.signature_length());
+
+            assert!(verify(&public_key, message, &signature));
+            assert!(!verify(&public_key, b"another message", &signature));
+            let (other_key, _) = scheme.keypair();
+            assert!(!verify(&other_key, message, &signature));
+        }
+
+        // The schemes of the key and of the signature must match
+        let (public_key, _) = PostQuantumScheme::Dilithium3.keypair();
+        let (_, signing_key) = PostQuantumScheme::SphincsSha2256s.keypair();
+        assert!(!verify(&public_key, message, &sign(&signing_key, message).unwrap()));
+    }
+
+    #[cfg(not(feature = "post-quantum"))]
+    #[test]
+    fn test_unsupported() {
+        let signing_key = PostQuantumScheme::Dilithium3.encode(&[1; 32]);
+        assert!(matches!(
+            sign(&signing_key, b"message"),
+            Err(PostQuantumError::Unsupported)
+        ));
+        assert!(!verify(&signing_key, b"message", &signing_key));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::authentication::Signature as SigTrait;
+use crate::authentication::{post_quantum, ssh, Signature as SigTrait};
 use ed25519_compact::{Error, KeyPair as EdKeyPair, Noise, PublicKey, SecretKey, Seed, Signature};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
@@ -32,6 +32,14 @@ impl KeyPair {
         }
     }
 
+    /// Generate a random keypair of a post-quantum signature scheme
+    #[cfg(feature = "post-quantum")]
+    pub fn new_post_quantum(scheme: post_quantum::PostQuantumScheme) -> Self {
+        let (pubkey, sigkey) = scheme.keypair();
+
+        KeyPair { pubkey, sigkey }
+    }
+
     /// Custom keypair available only in test
     #[cfg(debug_assertions)]
     pub fn custom_new(sigkey: String, pubkey: String) -> Self {
@@ -67,8 +75,12 @@ impl SigTrait for Production {
 
     /// Signs the given message using the given signing key,
     /// and returns the signature as a [`hex`] encoded string.
-    /// Signing key is expected to be [`hex`] encoded.
+    /// Signing key is expected to be [`hex`] encoded, or prefixed with a post-quantum scheme (see [`post_quantum`]).
     fn sign(&self, signing_key: &str, message: &str) -> anyhow::Result<String> {
+        if post_quantum::is_post_quantum(signing_key) {
+            return Ok(post_quantum::sign(signing_key, message.as_bytes())?);
+        }
+
         let signing_key_bytes = hex::decode(signing_key)?;
         let signing_key = SecretKey::from_slice(signing_key_bytes.as_ref())?;
 
@@ -79,13 +91,25 @@ impl SigTrait for Production {
 
     /// Verifies the given signature for the given message and public key,
     /// and returns `true` if the signature is valid.
-    /// Public key and signature are expected to be [`hex`] encoded.
+    /// Public key and signature are expected to be [`hex`] encoded, in the OpenSSH format (see [`ssh`]) or prefixed
+    /// with a post-quantum scheme (see [`post_quantum`]).
     fn verify(&self, public_key: &str, message: &str, signature: &str) -> bool {
-        let public_key_bytes = hex::decode(public_key).expect("Invalid public key encoding");
-        let public_key = PublicKey::from_slice(public_key_bytes.as_ref()).expect("Invalid public key");
+        if post_quantum::is_post_quantum(public_key) || post_quantum::is_post_quantum(signature) {
+            return post_quantum::verify(public_key, message.as_bytes(), signature);
+        }
+        if ssh::is_ssh_public_key(public_key) || ssh::SshSignature::is_ssh_signature(signature) {
+            return ssh::verify(public_key, message.as_bytes(), signature);
+        }
 
-        let signature_bytes = hex::decode(signature).expect("Invalid signature encoding");
-        let signature = Signature::from_slice(signature_bytes.as_ref()).expect("Invalid signature");
+        // Malformed keys and signatures come from the headers of the requests: they don't verify rather than panic
+        let public_key = match hex::decode(public_key).map(|bytes| PublicKey::from_slice(&bytes)) {
+            Ok(Ok(public_key)) => public_key,
+            _ => return false,
+        };
+        let signature = match hex::decode(signature).map(|bytes| Signature::from_slice(&bytes)) {
+            Ok(Ok(signature)) => signature,
+            _ => return false,
+        };
 
         public_key.verify(message, &signature).is_ok()
     }
@@ -103,5 +127,20 @@ mod tests {
         let signature = sig_scheme.sign(keypair.sigkey(), msg).unwrap();
 
         assert!(sig_scheme.verify(keypair.pubkey(), msg, signature.as_ref()));
+        assert!(!sig_scheme.verify("not hex", msg, signature.as_ref()));
+        assert!(!sig_scheme.verify(keypair.pubkey(), msg, "00ff"));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn sign_and_verify_post_quantum() {
+        let sig_scheme = Production;
+        let keypair = KeyPair::new_post_quantum(post_quantum::PostQuantumScheme::Dilithium3);
+        let msg = "This is the message to sign";
+        let signature = sig_scheme.sign(keypair.sigkey(), msg).unwrap();
+
+        assert!(sig_scheme.verify(keypair.pubkey(), msg, signature.as_ref()));
+        // Mixed with an ed25519 key
+        assert!(!sig_scheme.verify(KeyPair::new().pubkey(), msg, signature.as_ref()));
     }
 }
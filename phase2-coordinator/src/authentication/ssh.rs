@@ -0,0 +1,363 @@
+//! Support of OpenSSH ed25519 keys, to let the contributors sign with the SSH keys they already own.
+//!
+//! Public keys are accepted in the `authorized_keys` format (`ssh-ed25519 AAAA... [comment]`) and signatures in the
+//! `SSHSIG` format produced by `ssh-keygen -Y sign -n namada-trusted-setup`, either armored or as a single line of
+//! [`base64`]. The identity of a participant is the [`hex`] encoding of the raw ed25519 key, the same of the native
+//! keypairs.
+
+use ed25519_compact::{Noise, PublicKey, SecretKey, Signature};
+use sha2::{Digest, Sha256, Sha512};
+use std::{borrow::Cow, convert::TryInto, fmt};
+use thiserror::Error;
+
+/// The key type of the ed25519 keys of OpenSSH.
+pub const SSH_ED25519_KEY_TYPE: &str = "ssh-ed25519";
+/// The namespace of the signatures of the ceremony, preventing the reuse of signatures made for other purposes.
+pub const SSH_SIGNATURE_NAMESPACE: &str = "namada-trusted-setup";
+
+const SSHSIG_MAGIC: &[u8] = b"SSHSIG";
+const SSHSIG_VERSION: u32 = 1;
+const SSHSIG_HASH_ALGORITHM: &str = "sha512";
+const ARMOR_BEGIN: &str = "-----BEGIN SSH SIGNATURE-----";
+const ARMOR_END: &str = "-----END SSH SIGNATURE-----";
+
+#[derive(Debug, Error)]
+pub enum SshError {
+    #[error("Invalid base64 encoding: {0}")]
+    InvalidEncoding(#[from] base64::DecodeError),
+    #[error("Invalid key: {0}")]
+    InvalidKey(#[from] ed25519_compact::Error),
+    #[error("Malformed {0}")]
+    Malformed(&'static str),
+    #[error("Unsupported hash algorithm {0}")]
+    UnsupportedHashAlgorithm(String),
+    #[error("Unsupported key type {0}")]
+    UnsupportedKeyType(String),
+}
+
+/// Reader of the fields of the SSH wire format.
+struct WireReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> WireReader<'a> {
+    fn take(&mut self, len: usize, field: &'static str) -> Result<&'a [u8], SshError> {
+        if self.data.len() < len {
+            return Err(SshError::Malformed(field));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+
+        Ok(head)
+    }
+
+    fn read_u32(&mut self, field: &'static str) -> Result<u32, SshError> {
+        let bytes = self.take(4, field)?;
+        Ok(u32::from_be_bytes(bytes.try_into().expect("Length was checked")))
+    }
+
+    fn read_string(&mut self, field: &'static str) -> Result<&'a [u8], SshError> {
+        let len = self.read_u32(field)? as usize;
+        self.take(len, field)
+    }
+
+    fn finish(&self, field: &'static str) -> Result<(), SshError> {
+        match self.data.is_empty() {
+            true => Ok(()),
+            false => Err(SshError::Malformed(field)),
+        }
+    }
+}
+
+fn write_string(buffer: &mut Vec<u8>, string: &[u8]) {
+    buffer.extend_from_slice(&(string.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(string);
+}
+
+fn check_key_type(key_type: &[u8]) -> Result<(), SshError> {
+    match key_type == SSH_ED25519_KEY_TYPE.as_bytes() {
+        true => Ok(()),
+        false => Err(SshError::UnsupportedKeyType(
+            String::from_utf8_lossy(key_type).into_owned(),
+        )),
+    }
+}
+
+/// An ed25519 public key of OpenSSH.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SshPublicKey(PublicKey);
+
+impl SshPublicKey {
+    /// Parses a public key in the `authorized_keys` format. The comment, if any, is ignored.
+    pub fn parse(public_key: &str) -> Result<Self, SshError> {
+        let mut fields = public_key.split_whitespace();
+        let key_type = fields.next().ok_or(SshError::Malformed("public key"))?;
+        check_key_type(key_type.as_bytes())?;
+
+        let blob = base64::decode(fields.next().ok_or(SshError::Malformed("public key"))?)?;
+        Self::from_blob(&blob)
+    }
+
+    fn from_blob(blob: &[u8]) -> Result<Self, SshError> {
+        let mut reader = WireReader { data: blob };
+        check_key_type(reader.read_string("public key")?)?;
+        let key = PublicKey::from_slice(reader.read_string("public key")?)?;
+        reader.finish("public key")?;
+
+        Ok(Self(key))
+    }
+
+    fn to_blob(&self) -> Vec<u8> {
+        let mut blob = vec![];
+        write_string(&mut blob, SSH_ED25519_KEY_TYPE.as_bytes());
+        write_string(&mut blob, &self.0[..]);
+
+        blob
+    }
+
+    /// Returns the [`hex`] encoding of the raw ed25519 key, used as the identity of the participant.
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0[..])
+    }
+}
+
+impl From<PublicKey> for SshPublicKey {
+    fn from(public_key: PublicKey) -> Self {
+        Self(public_key)
+    }
+}
+
+impl fmt::Display for SshPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", SSH_ED25519_KEY_TYPE, base64::encode(self.to_blob()))
+    }
+}
+
+/// A signature in the `SSHSIG` format of OpenSSH.
+#[derive(Clone, Debug)]
+pub struct SshSignature {
+    public_key: SshPublicKey,
+    namespace: String,
+    hash_algorithm: String,
+    signature: Signature,
+}
+
+impl SshSignature {
+    /// Returns `true` if the signature looks like an `SSHSIG`, armored or not.
+    pub fn is_ssh_signature(signature: &str) -> bool {
+        let signature = signature.trim_start();
+        signature.starts_with(ARMOR_BEGIN) || signature.starts_with(&base64::encode(SSHSIG_MAGIC))
+    }
+
+    /// Parses an armored or a single line [`base64`] encoded signature.
+    pub fn parse(signature: &str) -> Result<Self, SshError> {
+        let encoded: String = signature
+            .trim()
+            .trim_start_matches(ARMOR_BEGIN)
+            .trim_end_matches(ARMOR_END)
+            .split_whitespace()
+            .collect();
+        let blob = base64::decode(encoded)?;
+
+        let mut reader = WireReader { data: &blob };
+        if reader.take(SSHSIG_MAGIC.len(), "signature")? != SSHSIG_MAGIC
+            || reader.read_u32("signature")? != SSHSIG_VERSION
+        {
+            return Err(SshError::Malformed("signature"));
+        }
+        let public_key = SshPublicKey::from_blob(reader.read_string("signature")?)?;
+        let namespace = String::from_utf8_lossy(reader.read_string("signature")?).into_owned();
+        reader.read_string("signature")?;
+        let hash_algorithm = String::from_utf8_lossy(reader.read_string("signature")?).into_owned();
+
+        let mut signature_reader = WireReader {
+            data: reader.read_string("signature")?,
+        };
+        reader.finish("signature")?;
+        check_key_type(signature_reader.read_string("signature")?)?;
+        let signature = Signature::from_slice(signature_reader.read_string("signature")?)?;
+        signature_reader.finish("signature")?;
+
+        Ok(Self {
+            public_key,
+            namespace,
+            hash_algorithm,
+            signature,
+        })
+    }
+
+    /// Signs the message in the namespace of the ceremony.
+    pub fn sign(secret_key: &SecretKey, message: &[u8]) -> Self {
+        let hash_algorithm = SSHSIG_HASH_ALGORITHM.to_string();
+        let signed_data =
+            signed_data(SSH_SIGNATURE_NAMESPACE, &hash_algorithm, message).expect("The hash algorithm is supported");
+
+        Self {
+            public_key: SshPublicKey(secret_key.public_key()),
+            namespace: SSH_SIGNATURE_NAMESPACE.to_string(),
+            hash_algorithm,
+            signature: secret_key.sign(signed_data, Some(Noise::generate())),
+        }
+    }
+
+    /// Returns the public key which produced the signature.
+    pub fn public_key(&self) -> &SshPublicKey {
+        &self.public_key
+    }
+
+    /// Verifies the signature of the message, which must have been made in the namespace of the ceremony.
+    pub fn verify(&self, message: &[u8]) -> bool {
+        if self.namespace != SSH_SIGNATURE_NAMESPACE {
+            return false;
+        }
+
+        match signed_data(&self.namespace, &self.hash_algorithm, message) {
+            Ok(signed_data) => self.public_key.0.verify(signed_data, &self.signature).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for SshSignature {
+    /// Formats the signature as a single line of [`base64`], which can be sent in a header.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut signature = vec![];
+        write_string(&mut signature, SSH_ED25519_KEY_TYPE.as_bytes());
+        write_string(&mut signature, &self.signature[..]);
+
+        let mut blob = SSHSIG_MAGIC.to_vec();
+        blob.extend_from_slice(&SSHSIG_VERSION.to_be_bytes());
+        write_string(&mut blob, &self.public_key.to_blob());
+        write_string(&mut blob, self.namespace.as_bytes());
+        write_string(&mut blob, &[]);
+        write_string(&mut blob, self.hash_algorithm.as_bytes());
+        write_string(&mut blob, &signature);
+
+        write!(f, "{}", base64::encode(blob))
+    }
+}
+
+/// Returns the data actually signed by an `SSHSIG`.
+fn signed_data(namespace: &str, hash_algorithm: &str, message: &[u8]) -> Result<Vec<u8>, SshError> {
+    let hash = match hash_algorithm {
+        "sha256" => Sha256::digest(message).to_vec(),
+        "sha512" => Sha512::digest(message).to_vec(),
+        _ => return Err(SshError::UnsupportedHashAlgorithm(hash_algorithm.to_string())),
+    };
+
+    let mut data = SSHSIG_MAGIC.to_vec();
+    write_string(&mut data, namespace.as_bytes());
+    write_string(&mut data, &[]);
+    write_string(&mut data, hash_algorithm.as_bytes());
+    write_string(&mut data, &hash);
+
+    Ok(data)
+}
+
+/// Returns `true` if the public key is in the `authorized_keys` format of OpenSSH.
+pub fn is_ssh_public_key(public_key: &str) -> bool {
+    public_key.trim_start().starts_with(SSH_ED25519_KEY_TYPE)
+}
+
+/// Returns the identity of the given public key: OpenSSH keys are converted to the [`hex`] encoding of the raw ed25519
+/// key, other keys are returned unchanged.
+pub fn canonical_public_key(public_key: &str) -> Cow<'_, str> {
+    if is_ssh_public_key(public_key) {
+        if let Ok(key) = SshPublicKey::parse(public_key) {
+            return key.to_hex().into();
+        }
+    }
+
+    public_key.into()
+}
+
+/// Verifies the signature of the message where either the public key or the signature, or both, are in the format of
+/// OpenSSH. Native keys and signatures are expected to be [`hex`] encoded.
+pub fn verify(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let public_key = match is_ssh_public_key(public_key) {
+        true => SshPublicKey::parse(public_key).ok(),
+        false => hex::decode(public_key)
+            .ok()
+            .and_then(|bytes| PublicKey::from_slice(&bytes).ok())
+            .map(SshPublicKey),
+    };
+    let public_key = match public_key {
+        Some(public_key) => public_key,
+        None => return false,
+    };
+
+    if SshSignature::is_ssh_signature(signature) {
+        return match SshSignature::parse(signature) {
+            Ok(signature) => signature.public_key == public_key && signature.verify(message),
+            Err(_) => false,
+        };
+    }
+
+    match hex::decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    {
+        Some(signature) => public_key.0.verify(message, &signature).is_ok(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_compact::KeyPair;
+
+    // Produced with `ssh-keygen -Y sign -n namada-trusted-setup` on a file containing `MESSAGE`
+    const PUBLIC_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOydlGgcNTiGOqqCDTcTthFVPdS27gEGCZUZVrrEfoIV contributor@example";
+    const SIGNATURE: &str = "-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAg7J2UaBw1OIY6qoINNxO2EVU91L
+buAQYJlRlWusR+ghUAAAAUbmFtYWRhLXRydXN0ZWQtc2V0dXAAAAAAAAAABnNoYTUxMgAA
+AFMAAAALc3NoLWVkMjU1MTkAAABAQYsQdmerDk/0Az+GceR8k9aoU9pfvJtt/zrRqQd8X3
+tEwD3CFWESnC/gWZR93jsclnnR+SywsDmglJ9Ewo83Ag==
+-----END SSH SIGNATURE-----";
+    const MESSAGE: &str = "message to sign";
+
+    #[test]
+    fn test_openssh_signature() {
+        let public_key = SshPublicKey::parse(PUBLIC_KEY).unwrap();
+        assert_eq!(
+            public_key.to_string(),
+            PUBLIC_KEY.trim_end_matches(" contributor@example")
+        );
+
+        let signature = SshSignature::parse(SIGNATURE).unwrap();
+        assert_eq!(signature.public_key(), &public_key);
+        assert!(signature.verify(MESSAGE.as_bytes()));
+        assert!(!signature.verify(b"another message"));
+
+        // Armored, single line and hex identity are all accepted
+        assert!(verify(PUBLIC_KEY, MESSAGE.as_bytes(), SIGNATURE));
+        assert!(verify(PUBLIC_KEY, MESSAGE.as_bytes(), &signature.to_string()));
+        assert!(verify(&public_key.to_hex(), MESSAGE.as_bytes(), SIGNATURE));
+        assert_eq!(canonical_public_key(PUBLIC_KEY), public_key.to_hex());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = KeyPair::generate();
+        let public_key = SshPublicKey::from(keypair.pk).to_string();
+        let message = b"This is the message to sign";
+
+        let signature = SshSignature::sign(&keypair.sk, message).to_string();
+        assert!(SshSignature::is_ssh_signature(&signature));
+        assert!(verify(&public_key, message, &signature));
+
+        // Signature of another key
+        let other = KeyPair::generate();
+        assert!(!verify(
+            &public_key,
+            message,
+            &SshSignature::sign(&other.sk, message).to_string()
+        ));
+
+        // Native signature with an OpenSSH public key
+        let native_signature = hex::encode(keypair.sk.sign(message, None));
+        assert!(verify(&public_key, message, &native_signature));
+    }
+}
@@ -0,0 +1,592 @@
+//! Scheduled and encrypted backups of the state of the coordinator to a secondary backend.
+//!
+//! A backup is a [`Snapshot`] of the state of the coordinator, of the state and the transcript manifest of every round
+//! and of the audit log of the participants. Contribution files are not included: their checksums, listed in the
+//! manifests, allow to check the copies restored from the transcript. Backups are enabled by setting the `BACKUP_DIR`
+//! env variable to the directory where to store them, usually on another disk or a network mount, and are configured
+//! through the following env variables:
+//!
+//! - `BACKUP_KEY`: hex encoded 32 bytes key used to encrypt the backups with ChaCha20-Poly1305, required
+//! - `BACKUP_SCHEDULE`: cron expression, in UTC, of the times of the backups (defaults to `0 3 * * *`, every night at
+//!   03:00)
+//! - `BACKUP_KEEP`: number of most recent backups to keep (defaults to 7)
+//! - `BACKUP_MAX_AGE_DAYS`: age, in days, after which a backup is removed, the most recent one excluded
+//!
+//! The coordinator restores the most recent backup, or the given one, when started with
+//! `restore --from-backup [name]`.
+
+use crate::{
+    objects::{ParticipantRecord, Round},
+    storage::RoundManifest,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305,
+    Key,
+    Nonce,
+};
+use lazy_static::lazy_static;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::PathBuf, str::FromStr, sync::Arc};
+use thiserror::Error;
+use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset};
+
+/// Default cron expression of the backups: every night at 03:00 UTC.
+const DEFAULT_SCHEDULE: &str = "0 3 * * *";
+const DEFAULT_KEEP: usize = 7;
+
+/// Size of the random nonce prepended to the encrypted backups.
+const NONCE_SIZE: usize = 12;
+const BACKUP_PREFIX: &str = "backup_";
+const BACKUP_EXTENSION: &str = ".json.enc";
+
+lazy_static! {
+    pub static ref BACKUP_DIR: Option<PathBuf> = std::env::var("BACKUP_DIR").ok().map(PathBuf::from);
+    pub static ref BACKUP_SCHEDULE: String = std::env::var("BACKUP_SCHEDULE").unwrap_or(DEFAULT_SCHEDULE.to_string());
+    pub static ref BACKUP_KEEP: usize = std::env::var("BACKUP_KEEP")
+        .map(|keep| keep.parse().expect("BACKUP_KEEP must be a number"))
+        .unwrap_or(DEFAULT_KEEP);
+    pub static ref BACKUP_MAX_AGE_DAYS: Option<i64> = std::env::var("BACKUP_MAX_AGE_DAYS")
+        .ok()
+        .map(|days| days.parse().expect("BACKUP_MAX_AGE_DAYS must be a number"));
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Backup {0} is corrupted or encrypted with another key")]
+    Decryption(String),
+    #[error("Encryption of the backup failed")]
+    Encryption,
+    #[error("Error in IO: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("Invalid backup key, expected 32 hex encoded bytes")]
+    InvalidKey,
+    #[error("Invalid backup schedule {0}, expected <minute> <hour> <day of month> <month> <day of week>")]
+    InvalidSchedule(String),
+    #[error("Error in json: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("The backup key must be set with the BACKUP_KEY env variable")]
+    MissingKey,
+    #[error("Backup {0} not found")]
+    NotFound(String),
+}
+
+type Result<T> = std::result::Result<T, BackupError>;
+
+/// The state of the coordinator saved in a backup.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The time of the snapshot.
+    #[serde(with = "time::serde::timestamp")]
+    pub created_at: OffsetDateTime,
+    pub round_height: u64,
+    /// The json encoded [`CoordinatorState`](crate::CoordinatorState).
+    pub coordinator_state: serde_json::Value,
+    /// The state of the rounds, from the oldest.
+    pub rounds: Vec<Round>,
+    /// The transcript manifests of the rounds, from the oldest.
+    pub manifests: Vec<RoundManifest>,
+    /// The audit log of the participants.
+    pub participants: Vec<ParticipantRecord>,
+}
+
+/// A field of a cron expression, as the set of the values it matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CronField(u64);
+
+impl CronField {
+    /// Parses a field made of comma separated `*`, `<value>` or `<first>-<last>` ranges, each optionally followed by
+    /// a `/<step>`.
+    fn parse(field: &str, min: u8, max: u8) -> Option<Self> {
+        let mut values = 0u64;
+
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u8>().ok().filter(|step| *step > 0)?),
+                None => (item, 1),
+            };
+            let (first, last) = match range {
+                "*" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((first, last)) => (first.parse().ok()?, last.parse().ok()?),
+                    None => {
+                        let value = range.parse().ok()?;
+                        (value, value)
+                    }
+                },
+            };
+            if first < min || last > max || first > last {
+                return None;
+            }
+
+            for value in (first..=last).step_by(step as usize) {
+                values |= 1 << value;
+            }
+        }
+
+        Some(Self(values))
+    }
+
+    fn contains(&self, value: u8) -> bool {
+        self.0 & (1 << value) != 0
+    }
+
+    fn is_any(&self, min: u8, max: u8) -> bool {
+        (min..=max).all(|value| self.contains(value))
+    }
+}
+
+/// The times of the backups, as a cron expression in UTC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupSchedule {
+    expression: String,
+    minutes: CronField,
+    hours: CronField,
+    days_of_month: CronField,
+    months: CronField,
+    days_of_week: CronField,
+}
+
+impl FromStr for BackupSchedule {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || BackupError::InvalidSchedule(s.to_owned());
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid());
+        }
+
+        let mut days_of_week = CronField::parse(fields[4], 0, 7).ok_or_else(invalid)?;
+        // Both 0 and 7 stand for Sunday
+        if days_of_week.contains(7) {
+            days_of_week.0 |= 1;
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: CronField::parse(fields[0], 0, 59).ok_or_else(invalid)?,
+            hours: CronField::parse(fields[1], 0, 23).ok_or_else(invalid)?,
+            days_of_month: CronField::parse(fields[2], 1, 31).ok_or_else(invalid)?,
+            months: CronField::parse(fields[3], 1, 12).ok_or_else(invalid)?,
+            days_of_week,
+        })
+    }
+}
+
+impl fmt::Display for BackupSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl BackupSchedule {
+    /// Returns the first time of the schedule strictly after the given one, or [`None`] if the schedule never matches
+    /// in the next years (e.g. on February 30th).
+    pub fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let after = after.to_offset(UtcOffset::UTC);
+        let mut next = after.replace_time(Time::from_hms(after.hour(), after.minute(), 0).ok()?) + Duration::minutes(1);
+        let limit = next + Duration::days(5 * 366);
+
+        while next < limit {
+            if !self.months.contains(u8::from(next.month())) {
+                let (year, month) = match next.month() {
+                    Month::December => (next.year() + 1, Month::January),
+                    month => (next.year(), month.next()),
+                };
+                next = Date::from_calendar_date(year, month, 1).ok()?.midnight().assume_utc();
+            } else if !self.matches_day(next.date()) {
+                next = next.date().next_day()?.midnight().assume_utc();
+            } else if !self.hours.contains(next.hour()) {
+                next = next.replace_time(Time::from_hms(next.hour(), 0, 0).ok()?) + Duration::hours(1);
+            } else if !self.minutes.contains(next.minute()) {
+                next += Duration::minutes(1);
+            } else {
+                return Some(next);
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if the schedule matches the given day. As in cron, when both the day of the month and the day of
+    /// the week are restricted, matching either of them is enough.
+    fn matches_day(&self, date: Date) -> bool {
+        let day_of_month = self.days_of_month.contains(date.day());
+        let day_of_week = self.days_of_week.contains(date.weekday().number_days_from_sunday());
+
+        match (self.days_of_month.is_any(1, 31), self.days_of_week.is_any(0, 6)) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+/// The rules deciding which backups are removed after a new one is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The number of most recent backups to keep.
+    pub keep: usize,
+    /// The age after which a backup is removed. The most recent backup is never removed.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for RetentionPolicy {
+    /// Generates a [`RetentionPolicy`] instance from the env.
+    fn default() -> Self {
+        Self {
+            keep: *BACKUP_KEEP,
+            max_age: BACKUP_MAX_AGE_DAYS.map(Duration::days),
+        }
+    }
+}
+
+/// The key encrypting the backups.
+#[derive(Clone)]
+pub struct BackupKey(Key);
+
+impl FromStr for BackupKey {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = hex::decode(s.trim()).map_err(|_| BackupError::InvalidKey)?;
+        if bytes.len() != 32 {
+            return Err(BackupError::InvalidKey);
+        }
+
+        Ok(Self(Key::clone_from_slice(&bytes)))
+    }
+}
+
+impl fmt::Debug for BackupKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BackupKey([REDACTED])")
+    }
+}
+
+impl BackupKey {
+    /// Encrypts the given content, prepending the random nonce to the result.
+    pub fn encrypt(&self, content: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = ChaCha20Poly1305::new(&self.0)
+            .encrypt(Nonce::from_slice(&nonce), content)
+            .map_err(|_| BackupError::Encryption)?;
+
+        Ok(nonce.iter().copied().chain(ciphertext).collect())
+    }
+
+    /// Decrypts and authenticates the content of the given backup.
+    pub fn decrypt(&self, name: &str, content: &[u8]) -> Result<Vec<u8>> {
+        if content.len() < NONCE_SIZE {
+            return Err(BackupError::Decryption(name.to_owned()));
+        }
+
+        let (nonce, ciphertext) = content.split_at(NONCE_SIZE);
+        ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| BackupError::Decryption(name.to_owned()))
+    }
+}
+
+/// A secondary storage of the backups.
+pub trait BackupBackend: Send + Sync {
+    /// Stores a backup with the given name, replacing any previous one.
+    fn put(&self, name: &str, content: &[u8]) -> Result<()>;
+
+    /// Returns the content of the backup with the given name.
+    fn get(&self, name: &str) -> Result<Vec<u8>>;
+
+    /// Returns the names of all the stored backups.
+    fn list(&self) -> Result<Vec<String>>;
+
+    /// Removes the backup with the given name.
+    fn remove(&self, name: &str) -> Result<()>;
+}
+
+/// Stores the backups as files of a directory, e.g. on another disk or on a network mount.
+#[derive(Clone, Debug)]
+pub struct DirectoryBackend {
+    root: PathBuf,
+}
+
+impl DirectoryBackend {
+    pub fn new(root: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&root)?;
+
+        Ok(Self { root })
+    }
+}
+
+impl BackupBackend for DirectoryBackend {
+    fn put(&self, name: &str, content: &[u8]) -> Result<()> {
+        // Write to a temporary file first, so that an interrupted backup never replaces a complete one
+        let tmp_path = self.root.join(format!("{}.tmp", name));
+        fs::write(&tmp_path, content)?;
+        fs::rename(tmp_path, self.root.join(name))?;
+
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<Vec<u8>> {
+        let path = self.root.join(name);
+        if !path.is_file() {
+            return Err(BackupError::NotFound(name.to_owned()));
+        }
+
+        Ok(fs::read(path)?)
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        let mut names = vec![];
+        for entry in fs::read_dir(&self.root)? {
+            if let Some(name) = entry?.file_name().to_str() {
+                names.push(name.to_owned());
+            }
+        }
+
+        Ok(names)
+    }
+
+    fn remove(&self, name: &str) -> Result<()> {
+        Ok(fs::remove_file(self.root.join(name))?)
+    }
+}
+
+/// The backups of the coordinator, stored encrypted on a backend.
+#[derive(Clone)]
+pub struct Backups {
+    backend: Arc<dyn BackupBackend>,
+    key: BackupKey,
+    schedule: BackupSchedule,
+    retention: RetentionPolicy,
+}
+
+impl Backups {
+    pub fn new(
+        backend: Arc<dyn BackupBackend>,
+        key: BackupKey,
+        schedule: BackupSchedule,
+        retention: RetentionPolicy,
+    ) -> Self {
+        Self {
+            backend,
+            key,
+            schedule,
+            retention,
+        }
+    }
+
+    /// Returns the backups configured in the env, or [`None`] if they are disabled.
+    pub fn from_env() -> Result<Option<Self>> {
+        let root = match BACKUP_DIR.clone() {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+        let key = std::env::var("BACKUP_KEY").map_err(|_| BackupError::MissingKey)?.parse()?;
+
+        Ok(Some(Self::new(
+            Arc::new(DirectoryBackend::new(root)?),
+            key,
+            BACKUP_SCHEDULE.parse()?,
+            RetentionPolicy::default(),
+        )))
+    }
+
+    /// Returns the times of the backups.
+    pub fn schedule(&self) -> &BackupSchedule {
+        &self.schedule
+    }
+
+    /// Returns the names of the stored backups, from the oldest.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut backups: Vec<(i64, String)> = self
+            .backend
+            .list()?
+            .into_iter()
+            .filter_map(|name| backup_timestamp(&name).map(|timestamp| (timestamp, name)))
+            .collect();
+        backups.sort();
+
+        Ok(backups.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Encrypts and stores the given snapshot, then removes the backups exceeding the retention policy. Returns the
+    /// name of the new backup.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<String> {
+        let name = format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            snapshot.created_at.unix_timestamp(),
+            BACKUP_EXTENSION
+        );
+        self.backend.put(&name, &self.key.encrypt(&serde_json::to_vec(snapshot)?)?)?;
+        self.prune(snapshot.created_at)?;
+
+        Ok(name)
+    }
+
+    /// Loads the backup with the given name, or the most recent one.
+    pub fn load(&self, name: Option<&str>) -> Result<Snapshot> {
+        let name = match name {
+            Some(name) => name.to_owned(),
+            None => self
+                .list()?
+                .pop()
+                .ok_or_else(|| BackupError::NotFound("latest".to_owned()))?,
+        };
+        let content = self.key.decrypt(&name, &self.backend.get(&name)?)?;
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Removes the backups exceeding the retention policy at the given time. Returns the names of the removed backups.
+    pub fn prune(&self, now: OffsetDateTime) -> Result<Vec<String>> {
+        let backups = self.list()?;
+        let newest = backups.len().saturating_sub(1);
+        let first_kept = backups.len().saturating_sub(self.retention.keep.max(1));
+
+        let mut removed = vec![];
+        for (index, name) in backups.into_iter().enumerate() {
+            let expired = match (self.retention.max_age, backup_timestamp(&name)) {
+                (Some(max_age), Some(timestamp)) => now.unix_timestamp() - timestamp > max_age.whole_seconds(),
+                _ => false,
+            };
+
+            if index < first_kept || (expired && index != newest) {
+                self.backend.remove(&name)?;
+                removed.push(name);
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Returns the unix timestamp of the backup with the given name, or [`None`] if it's not a backup.
+fn backup_timestamp(name: &str) -> Option<i64> {
+    name.strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_EXTENSION)?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn snapshot(created_at: OffsetDateTime) -> Snapshot {
+        Snapshot {
+            created_at,
+            round_height: 1,
+            coordinator_state: serde_json::json!({ "currentRoundHeight": 1 }),
+            rounds: vec![],
+            manifests: vec![],
+            participants: vec![],
+        }
+    }
+
+    fn key() -> BackupKey {
+        "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_schedule() {
+        let nightly: BackupSchedule = "0 3 * * *".parse().unwrap();
+        assert_eq!(
+            nightly.next_after(datetime!(2022-11-20 02:59:30 UTC)),
+            Some(datetime!(2022-11-20 03:00 UTC))
+        );
+        assert_eq!(
+            nightly.next_after(datetime!(2022-11-20 03:00 UTC)),
+            Some(datetime!(2022-11-21 03:00 UTC))
+        );
+        assert_eq!(
+            nightly.next_after(datetime!(2022-12-31 04:00 UTC)),
+            Some(datetime!(2023-01-01 03:00 UTC))
+        );
+
+        // Every 15 minutes during the working hours of Mondays
+        let schedule: BackupSchedule = "*/15 9-17 * * 1".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(datetime!(2022-11-20 12:00 UTC)),
+            Some(datetime!(2022-11-21 09:00 UTC))
+        );
+        assert_eq!(
+            schedule.next_after(datetime!(2022-11-21 09:07 UTC)),
+            Some(datetime!(2022-11-21 09:15 UTC))
+        );
+
+        // Either the 1st of the month or Sundays
+        let schedule: BackupSchedule = "30 0 1 * 7".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(datetime!(2022-11-21 00:00 UTC)),
+            Some(datetime!(2022-11-27 00:30 UTC))
+        );
+        assert_eq!(
+            schedule.next_after(datetime!(2022-11-28 00:00 UTC)),
+            Some(datetime!(2022-12-01 00:30 UTC))
+        );
+
+        let never: BackupSchedule = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(never.next_after(datetime!(2022-11-20 00:00 UTC)), None);
+        assert!("0 3 * *".parse::<BackupSchedule>().is_err());
+        assert!("60 3 * * *".parse::<BackupSchedule>().is_err());
+        assert!("0 3 * * */0".parse::<BackupSchedule>().is_err());
+    }
+
+    #[test]
+    fn test_encryption() {
+        let content = b"coordinator state";
+        let encrypted = key().encrypt(content).unwrap();
+        assert_ne!(&encrypted[NONCE_SIZE..NONCE_SIZE + content.len()], content);
+        assert_eq!(key().decrypt("backup", &encrypted).unwrap(), content);
+
+        // Another key, or a tampered backup, is rejected
+        let other_key: BackupKey = "ff".repeat(32).parse().unwrap();
+        assert!(other_key.decrypt("backup", &encrypted).is_err());
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(key().decrypt("backup", &tampered).is_err());
+
+        assert!("0011".parse::<BackupKey>().is_err());
+    }
+
+    #[test]
+    fn test_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = Backups::new(
+            Arc::new(DirectoryBackend::new(dir.path().to_owned()).unwrap()),
+            key(),
+            DEFAULT_SCHEDULE.parse().unwrap(),
+            RetentionPolicy {
+                keep: 3,
+                max_age: Some(Duration::days(3)),
+            },
+        );
+        assert!(backups.load(None).is_err());
+
+        let start = datetime!(2022-11-20 03:00 UTC);
+        let name = |day: i64| format!("backup_{}.json.enc", (start + Duration::days(day)).unix_timestamp());
+        for day in 0..4 {
+            assert_eq!(backups.save(&snapshot(start + Duration::days(day))).unwrap(), name(day));
+        }
+
+        // Only the 3 most recent backups are kept
+        assert_eq!(backups.list().unwrap(), vec![name(1), name(2), name(3)]);
+        assert_eq!(backups.load(None).unwrap().created_at, start + Duration::days(3));
+        assert_eq!(backups.load(Some(name(1).as_str())).unwrap().created_at, start + Duration::days(1));
+
+        // Expired backups are removed, but the most recent one
+        assert_eq!(backups.prune(start + Duration::days(5)).unwrap(), vec![name(1)]);
+        assert_eq!(backups.prune(start + Duration::days(30)).unwrap(), vec![name(2)]);
+        assert_eq!(backups.list().unwrap(), vec![name(3)]);
+    }
+}
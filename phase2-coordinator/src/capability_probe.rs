@@ -0,0 +1,326 @@
+//! Optional capability probe at queue join, to filter out the contributors who can't physically complete a round.
+//!
+//! Before joining the queue, the contributor downloads a test blob sized like the challenge of the current round and
+//! echoes its hash in the request to join. The blob is generated from a random seed per probe, so that it can't be
+//! cached, and the join must happen within a time bound of the issuance of the probe. The probe is enabled by setting
+//! the `CAPABILITY_PROBE_POLICY` env variable to `warn`, to let the contributors failing it join with a warning, or
+//! `reject`, to keep them out of the queue. The time bound is set by `CAPABILITY_PROBE_TIMEOUT_SECONDS`.
+
+use crate::objects::Participant;
+
+use lazy_static::lazy_static;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+lazy_static! {
+    pub static ref CAPABILITY_PROBE_POLICY: Option<ProbePolicy> = std::env::var("CAPABILITY_PROBE_POLICY")
+        .ok()
+        .map(|policy| policy.parse().expect("CAPABILITY_PROBE_POLICY must be warn or reject"));
+    pub static ref CAPABILITY_PROBE_TIMEOUT: Duration = std::env::var("CAPABILITY_PROBE_TIMEOUT_SECONDS")
+        .ok()
+        .map(|seconds| seconds.parse().expect("Invalid CAPABILITY_PROBE_TIMEOUT_SECONDS"))
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_PROBE_TIMEOUT);
+}
+
+/// Default time allowed between the issuance of a probe and the request to join the queue.
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Size of the chunks in which the blob is generated and streamed.
+const BLOB_CHUNK_SIZE: usize = 1 << 20;
+
+/// What happens to the contributors failing the probe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProbePolicy {
+    /// The contributor joins the queue, with a warning in the response.
+    Warn,
+    /// The contributor is refused in the queue.
+    Reject,
+}
+
+impl FromStr for ProbePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(ProbePolicy::Warn),
+            "reject" => Ok(ProbePolicy::Reject),
+            _ => Err(format!("Unknown capability probe policy {}", s)),
+        }
+    }
+}
+
+/// A capability probe issued to a contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityProbe {
+    /// Hex encoded random seed of the blob.
+    pub seed: String,
+    /// Size in bytes of the blob.
+    pub size: u64,
+    /// Time in seconds allowed to download the blob and join the queue.
+    pub timeout_seconds: u64,
+}
+
+impl CapabilityProbe {
+    /// Returns the content of the blob of the probe, one chunk at a time.
+    pub fn blob(&self) -> ProbeBlob {
+        let mut seed = [0u8; 32];
+        // Seeds which aren't issued by the coordinator produce a blob that matches no probe
+        if let Ok(bytes) = hex::decode(&self.seed) {
+            let length = bytes.len().min(seed.len());
+            seed[..length].copy_from_slice(&bytes[..length]);
+        }
+
+        ProbeBlob {
+            rng: ChaChaRng::from_seed(seed),
+            remaining: self.size,
+        }
+    }
+
+    /// Returns the hex encoded SHA-256 hash of the blob of the probe.
+    pub fn blob_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for chunk in self.blob() {
+            hasher.update(&chunk);
+        }
+
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// The content of the blob of a [`CapabilityProbe`], generated from its seed.
+pub struct ProbeBlob {
+    rng: ChaChaRng,
+    remaining: u64,
+}
+
+impl Iterator for ProbeBlob {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let mut chunk = vec![0u8; self.remaining.min(BLOB_CHUNK_SIZE as u64) as usize];
+        self.rng.fill_bytes(&mut chunk);
+        self.remaining -= chunk.len() as u64;
+
+        Some(chunk)
+    }
+}
+
+/// The proof that a [`CapabilityProbe`] was completed, included in the signed request to join the queue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityProof {
+    pub seed: String,
+    /// Hex encoded SHA-256 hash of the downloaded blob.
+    pub hash: String,
+}
+
+struct IssuedProbe {
+    probe: CapabilityProbe,
+    issued: Instant,
+    /// The hash of the blob, recorded once it has been fully served.
+    served_hash: Option<String>,
+}
+
+/// Issues the capability probes and checks their proofs. Disabled if no policy is set.
+#[derive(Clone)]
+pub struct CapabilityProber {
+    policy: Option<ProbePolicy>,
+    timeout: Duration,
+    probes: Arc<Mutex<HashMap<Participant, IssuedProbe>>>,
+}
+
+impl Default for CapabilityProber {
+    /// Generates a [`CapabilityProber`] instance with the policy and the time bound configured in the env.
+    fn default() -> Self {
+        Self::new(*CAPABILITY_PROBE_POLICY, *CAPABILITY_PROBE_TIMEOUT)
+    }
+}
+
+impl CapabilityProber {
+    pub fn new(policy: Option<ProbePolicy>, timeout: Duration) -> Self {
+        Self {
+            policy,
+            timeout,
+            probes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns `true` if a probe is required to join the queue.
+    pub fn is_enabled(&self) -> bool {
+        self.policy.is_some()
+    }
+
+    /// Issues a new probe with a blob of the given size for the given participant, replacing any previous one. Returns
+    /// [`None`] if the probe is disabled.
+    pub fn issue(&self, participant: &Participant, size: u64) -> Option<CapabilityProbe> {
+        self.policy?;
+        let mut seed = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut seed);
+
+        let probe = CapabilityProbe {
+            seed: hex::encode(seed),
+            size,
+            timeout_seconds: self.timeout.as_secs(),
+        };
+
+        let mut probes = self.probes.lock().expect("Capability probe lock poisoned");
+        probes.retain(|_, issued| issued.issued.elapsed() < self.timeout);
+        probes.insert(
+            participant.clone(),
+            IssuedProbe {
+                probe: probe.clone(),
+                issued: Instant::now(),
+                served_hash: None,
+            },
+        );
+
+        Some(probe)
+    }
+
+    /// Returns the probe issued to the participant, if it hasn't expired.
+    pub fn issued(&self, participant: &Participant) -> Option<CapabilityProbe> {
+        let probes = self.probes.lock().expect("Capability probe lock poisoned");
+
+        probes
+            .get(participant)
+            .filter(|issued| issued.issued.elapsed() < self.timeout)
+            .map(|issued| issued.probe.clone())
+    }
+
+    /// Records that the blob of the probe with the given seed has been fully served to the participant, with its hash.
+    pub fn record_served(&self, participant: &Participant, seed: &str, hash: String) {
+        let mut probes = self.probes.lock().expect("Capability probe lock poisoned");
+
+        if let Some(issued) = probes.get_mut(participant).filter(|issued| issued.probe.seed == seed) {
+            issued.served_hash = Some(hash);
+        }
+    }
+
+    /// Checks the proof of the probe issued to the participant. The probe can only be used once. Returns a warning if
+    /// the check fails under the [`ProbePolicy::Warn`] policy, and always succeeds if the probe is disabled.
+    pub fn check(&self, participant: &Participant, proof: Option<&CapabilityProof>) -> Result<Option<String>, String> {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return Ok(None),
+        };
+
+        match (self.verify(participant, proof), policy) {
+            (Ok(()), _) => Ok(None),
+            (Err(e), ProbePolicy::Warn) => Ok(Some(format!(
+                "The capability probe failed ({}), the contributor may not be able to complete a round in time",
+                e
+            ))),
+            (Err(e), ProbePolicy::Reject) => Err(e),
+        }
+    }
+
+    fn verify(&self, participant: &Participant, proof: Option<&CapabilityProof>) -> Result<(), String> {
+        let proof = proof.ok_or_else(|| String::from("missing proof"))?;
+        let issued = self
+            .probes
+            .lock()
+            .expect("Capability probe lock poisoned")
+            .remove(participant)
+            .ok_or_else(|| String::from("no probe was issued to the participant"))?;
+
+        if issued.issued.elapsed() >= self.timeout {
+            return Err(format!(
+                "the probe wasn't completed within {} seconds",
+                self.timeout.as_secs()
+            ));
+        }
+        if issued.probe.seed != proof.seed {
+            return Err(String::from("the proof doesn't match the probe"));
+        }
+        match issued.served_hash {
+            Some(hash) if hash == proof.hash => Ok(()),
+            Some(_) => Err(String::from("invalid hash of the blob")),
+            None => Err(String::from("the blob wasn't downloaded")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete(prober: &CapabilityProber, participant: &Participant, size: u64) -> CapabilityProof {
+        let probe = prober.issue(participant, size).unwrap();
+        let hash = probe.blob_hash();
+        prober.record_served(participant, &probe.seed, hash.clone());
+
+        CapabilityProof { seed: probe.seed, hash }
+    }
+
+    #[test]
+    fn test_probe_blob() {
+        let prober = CapabilityProber::new(Some(ProbePolicy::Reject), DEFAULT_PROBE_TIMEOUT);
+        let participant = Participant::new_contributor("contributor");
+
+        let probe = prober.issue(&participant, 3 * BLOB_CHUNK_SIZE as u64 + 5).unwrap();
+        let sizes: Vec<usize> = probe.blob().map(|chunk| chunk.len()).collect();
+        assert_eq!(sizes, vec![BLOB_CHUNK_SIZE, BLOB_CHUNK_SIZE, BLOB_CHUNK_SIZE, 5]);
+        assert_eq!(probe.blob().collect::<Vec<_>>(), probe.blob().collect::<Vec<_>>());
+
+        // The blobs of distinct probes differ
+        let other = prober.issue(&participant, probe.size).unwrap();
+        assert_ne!(probe.blob_hash(), other.blob_hash());
+        assert_eq!(prober.issued(&participant), Some(other));
+    }
+
+    #[test]
+    fn test_probe_policy() {
+        let participant = Participant::new_contributor("contributor");
+        assert!(CapabilityProber::new(None, DEFAULT_PROBE_TIMEOUT)
+            .check(&participant, None)
+            .unwrap()
+            .is_none());
+
+        let prober = CapabilityProber::new(Some(ProbePolicy::Reject), DEFAULT_PROBE_TIMEOUT);
+        assert!(prober.check(&participant, None).is_err());
+
+        // The blob must be served before the proof is accepted, and the probe is single use
+        let probe = prober.issue(&participant, 100).unwrap();
+        let proof = CapabilityProof {
+            seed: probe.seed.clone(),
+            hash: probe.blob_hash(),
+        };
+        assert!(prober.check(&participant, Some(&proof)).is_err());
+        let proof = complete(&prober, &participant, 100);
+        assert!(prober
+            .check(
+                &participant,
+                Some(&CapabilityProof {
+                    hash: String::from("wrong"),
+                    ..proof.clone()
+                })
+            )
+            .is_err());
+        let proof = complete(&prober, &participant, 100);
+        assert_eq!(prober.check(&participant, Some(&proof)), Ok(None));
+        assert!(prober.check(&participant, Some(&proof)).is_err());
+
+        // Late proofs are rejected
+        let prober = CapabilityProber::new(Some(ProbePolicy::Reject), Duration::from_millis(1));
+        let proof = complete(&prober, &participant, 100);
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(prober.check(&participant, Some(&proof)).is_err());
+
+        // Or only warned about
+        let prober = CapabilityProber::new(Some(ProbePolicy::Warn), DEFAULT_PROBE_TIMEOUT);
+        assert!(prober.check(&participant, None).unwrap().is_some());
+    }
+}
@@ -0,0 +1,320 @@
+//! Machine-readable changelog of the REST API, so that long-running client deployments can detect the upcoming
+//! incompatibilities with the coordinator.
+//!
+//! The versions of the protocol are part of the code, while the deprecations, whose deadlines depend on the schedule
+//! of the ceremony, are configured by the operator with the `NAMADA_MPC_API_DEPRECATIONS` env variable. The changelog
+//! is signed by the coordinator, so that clients can trust it even when served through a proxy or a cache.
+
+use crate::authentication::Signature;
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// The current version of the protocol spoken by the REST API, bumped on every change which requires an update of the
+/// clients.
+pub const PROTOCOL_VERSION: u64 = 2;
+
+#[derive(Debug, Error)]
+pub enum ChangelogError {
+    #[error("Invalid deprecation {0}, expected <endpoint>=<unix timestamp of the deadline>")]
+    InvalidDeprecation(String),
+}
+
+/// The changes of the REST API introduced by a version of the protocol.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiVersion {
+    /// The version of the protocol.
+    pub version: u64,
+    /// The endpoints added in this version.
+    pub added: Vec<String>,
+    /// The endpoints removed in this version.
+    pub removed: Vec<String>,
+    /// The changes of the behavior of the existing endpoints which require an update of the clients.
+    pub changes: Vec<String>,
+}
+
+/// An endpoint which is going to be removed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deprecation {
+    /// The deprecated endpoint.
+    pub endpoint: String,
+    /// The time after which the endpoint can be removed.
+    #[serde(with = "time::serde::timestamp")]
+    pub deadline: OffsetDateTime,
+}
+
+impl FromStr for Deprecation {
+    type Err = ChangelogError;
+
+    /// Parses a deprecation in the `<endpoint>=<unix timestamp of the deadline>` format.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (endpoint, deadline) = s
+            .split_once('=')
+            .ok_or_else(|| ChangelogError::InvalidDeprecation(s.to_owned()))?;
+        let deadline = deadline
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+            .ok_or_else(|| ChangelogError::InvalidDeprecation(s.to_owned()))?;
+
+        Ok(Self {
+            endpoint: endpoint.trim().to_owned(),
+            deadline,
+        })
+    }
+}
+
+/// The changelog of the REST API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changelog {
+    /// The current version of the protocol.
+    pub protocol_version: u64,
+    /// The changes of every version of the protocol, from the oldest.
+    pub versions: Vec<ApiVersion>,
+    /// The endpoints which are going to be removed.
+    pub deprecations: Vec<Deprecation>,
+}
+
+impl Changelog {
+    /// Returns the changelog of the current version of the protocol, with the given deprecations.
+    pub fn new(deprecations: Vec<Deprecation>) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            versions: history(),
+            deprecations,
+        }
+    }
+
+    /// Returns the message signed by the coordinator: the json encoding of the changelog, with the fields of the objects
+    /// sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the changelog with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedChangelog> {
+        let changelog_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedChangelog {
+            changelog: self,
+            coordinator_public_key,
+            signature: changelog_signature,
+        })
+    }
+}
+
+/// A [`Changelog`] signed by the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedChangelog {
+    pub changelog: Changelog,
+    /// The public key of the coordinator. Clients should check it against the key they expect rather than trust it.
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedChangelog {
+    /// Returns `true` if the changelog is signed by the coordinator with the given public key.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        match self.changelog.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Returns the changes of every version of the protocol, from the oldest.
+fn history() -> Vec<ApiVersion> {
+    let endpoints = |endpoints: &[&str]| endpoints.iter().map(|e| e.to_string()).collect();
+
+    vec![
+        ApiVersion {
+            version: 1,
+            added: endpoints(&[
+                "/contributor/join_queue",
+                "/contributor/lock_chunk",
+                "/contributor/challenge",
+                "/upload/chunk",
+                "/contributor/heartbeat",
+                "/contributor/queue_status",
+                "/contributor/contribution_info",
+                "/contributor/attestation",
+                "/contribution_info",
+                "/coordinator_status",
+                "/update_cohorts",
+                "/healthcheck",
+                "/update",
+                "/stop",
+                "/verify",
+            ]),
+            removed: vec![],
+            changes: vec![],
+        },
+        ApiVersion {
+            version: 2,
+            added: endpoints(&[
+                "/contributor/pow_puzzle",
+                "/contributor/capability_probe",
+                "/contributor/capability_probe/blob",
+                "/contributor/verification_status",
+                "/contributor/queue_events",
+                "/contributor/offline/challenge_bundle",
+                "/contributor/offline/response_bundle",
+                "/contributor/challenge/stream",
+                "/contributor/resume_session",
+                "/contributor/upload/contribution",
+                "/contributor/upload/contribution/range",
+                "/contributor/computation_proof",
+                "/contributor/practice/lock",
+                "/contributor/practice/challenge",
+                "/contributor/practice/contribution",
+                "/verifier/pending_verifications",
+                "/verifier/vote",
+                "/operator/upload/chunk",
+                "/operator/manual_contribution",
+                "/operator/genesis_seed/commit",
+                "/operator/genesis_seed/reveal",
+                "/reissue_challenge",
+                "/participants_history",
+                "/storage/shards",
+                "/storage/rebalance",
+                "/admin/reverify",
+                "/admin/delegate_verifier",
+                "/admin/lock_metrics",
+                "/admin/startup_profile",
+                "/admin/webhooks",
+                "/admin/control_keys",
+                "/admin/control_keys/add",
+                "/admin/control_keys/remove",
+                "/admin/verification_quarantine",
+                "/admin/verification_quarantine/reject",
+                "/admin/verification_quarantine/verify",
+                "/admin/prelaunch/snapshot",
+                "/admin/prelaunch/restore",
+                "/admin/export",
+                "/transcript",
+                "/transcript/manifests",
+                "/ceremony_status",
+                "/ceremony/status",
+                "/circuits",
+                "/ceremony/metadata",
+                "/badge/<pubkey>.json",
+                "/badge/<pubkey>.svg",
+                "/ceremony/parameters",
+                "/ceremony/announcements",
+                "/ceremony/genesis_seed",
+                "/ceremony/computation_stats",
+                "/queue/shuffles",
+                "/api/changelog",
+                "/messages",
+                "/contributor/attestation_post",
+                "/contributor/destruction_attestation",
+            ]),
+            removed: vec![],
+            changes: vec![
+                "Signed request bodies with unknown fields are rejected".to_string(),
+                "/contributor/join_queue requires the solution of the proof-of-work puzzle when enabled".to_string(),
+                "/contributor/join_queue requires the token of a human verification challenge when enabled".to_string(),
+                "/contributor/join_queue requires the hash of the blob of the capability probe when enabled, and may \
+                 return a warning if the probe failed"
+                    .to_string(),
+                "/contributor/join_queue accepts an identity claimed on top of the public key, a DID or a verifiable \
+                 credential, required when enabled"
+                    .to_string(),
+                "/contributor/join_queue returns the cohort and the position in the queue, the same for a contributor \
+                 retrying its request with the same token"
+                    .to_string(),
+                "/contributor/contribution_info must be posted after the upload of the contribution, the hash of \
+                 which it must contain"
+                    .to_string(),
+                "/contributor/contribute_chunk accepts the signature of a contribution streamed to \
+                 /contributor/upload/contribution"
+                    .to_string(),
+                "/verifier/pending_verifications and /verifier/vote accept the verifiers presenting a delegation of \
+                 the coordinator in the ATS-Delegation header"
+                    .to_string(),
+                "The privileged endpoints accept the requests signed with any of the control keys of the coordinator"
+                    .to_string(),
+                "The endpoints called outside of the lifecycle phases in which they are available return a 409 \
+                 Conflict with the current phase"
+                    .to_string(),
+                "/contribution_info publishes the attestations of the contributors that the toxic waste of their \
+                 contribution was destroyed"
+                    .to_string(),
+                "A contribution crashing the verifier is verified again, up to a maximum number of retries after \
+                 which it's quarantined until the operators reject it or verify it once more"
+                    .to_string(),
+                "The queue may be shuffled each round by a seed derived from the response of the previous round, in \
+                 which case /contributor/queue_status reports the position in order of joining"
+                    .to_string(),
+                "/contributor/lock_chunk returns a 503 Service Unavailable while the backlog of verifications exceeds \
+                 its threshold, in which case /contributor/queue_status reports the size of the backlog"
+                    .to_string(),
+                "/contributor/join_queue returns a 403 Forbidden when the current phase of the ceremony, reported by \
+                 /ceremony_status, doesn't admit the contributor"
+                    .to_string(),
+                "/contributor/upload/contribution/range starts an upload session at offset zero, whose id the next \
+                 ranges must carry in the session parameter"
+                    .to_string(),
+                "The requests are signed over the hex encoded SHA-256 hash of their public key, length and digest, \
+                 the concatenation of them being accepted until the deadline set by the operator"
+                    .to_string(),
+                "The responses carry the code of their status or error in the ATS-Message-Code header, and the text \
+                 of the errors is localized in the language of the Accept-Language header"
+                    .to_string(),
+                "The errors return a json body with their code, message and request id, the conflicts with the \
+                 lifecycle of the ceremony being detailed under lifecycle"
+                    .to_string(),
+                "Round 1 may wait for the operators to derive the seed of the genesis challenge, published in the \
+                 manifest of round 0"
+                    .to_string(),
+            ],
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn sign_and_verify() {
+        let keypair = KeyPair::new();
+        let deprecations = vec!["/verify=1672531200".parse().unwrap()];
+        let signed = Changelog::new(deprecations)
+            .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+            .unwrap();
+
+        assert_eq!(signed.changelog.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(signed.changelog.versions.last().unwrap().version, PROTOCOL_VERSION);
+        assert!(signed.verify(&Production, keypair.pubkey()));
+
+        // Another key, or a tampered changelog, is rejected
+        assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
+        let mut tampered = signed.clone();
+        tampered.changelog.deprecations.clear();
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+    }
+
+    #[test]
+    fn parse_deprecation() {
+        let deprecation: Deprecation = "/contributor/challenge = 1672531200".parse().unwrap();
+        assert_eq!(deprecation.endpoint, "/contributor/challenge");
+        assert_eq!(deprecation.deadline.unix_timestamp(), 1672531200);
+
+        assert!("/contributor/challenge".parse::<Deprecation>().is_err());
+        assert!("/contributor/challenge=tomorrow".parse::<Deprecation>().is_err());
+    }
+}
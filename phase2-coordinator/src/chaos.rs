@@ -0,0 +1,210 @@
+//! Chaos mode of the coordinator, available only in debug builds, to let client authors harden their retry logic
+//! against a misbehaving coordinator.
+//!
+//! When enabled, the coordinator delays its responses by a random latency, answers some requests with a spurious
+//! `503 Service Unavailable` without processing them and fails some verifications of valid contributions. The rate of
+//! each fault is configured through the following env variables, all disabled by default:
+//!
+//! - `CHAOS_LATENCY_RATE`: probability, in `[0, 1]`, that a request is delayed
+//! - `CHAOS_MAX_LATENCY_MS`: upper bound of the injected latency, in milliseconds (defaults to 5000)
+//! - `CHAOS_UNAVAILABLE_RATE`: probability that a request is answered with a 503
+//! - `CHAOS_VERIFICATION_FAILURE_RATE`: probability that the verification of a contribution fails
+//!
+//! An injected verification failure resets the round like a real one, but the contributor is not banned and can join
+//! the queue again.
+
+use lazy_static::lazy_static;
+use rand::Rng;
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{uri::Origin, Method, Status},
+    Data,
+    Request,
+    Response,
+};
+#[cfg(feature = "server")]
+use std::io::Cursor;
+#[cfg(feature = "server")]
+use tracing::debug;
+
+/// Default upper bound of the injected latency, in milliseconds.
+const DEFAULT_MAX_LATENCY_MS: u64 = 5000;
+
+/// Path, not served by any route, to which the requests failed by the chaos mode are redirected.
+#[cfg(feature = "server")]
+const UNAVAILABLE_PATH: &str = "/chaos/unavailable";
+
+fn rate_from_env(var: &str) -> f64 {
+    match std::env::var(var) {
+        Ok(rate) => {
+            let rate: f64 = rate.parse().unwrap_or_else(|_| panic!("{} must be a number", var));
+            if !(0.0..=1.0).contains(&rate) {
+                panic!("{} must be in the range [0, 1]", var);
+            }
+            rate
+        }
+        Err(_) => 0.0,
+    }
+}
+
+lazy_static! {
+    pub static ref CHAOS_LATENCY_RATE: f64 = rate_from_env("CHAOS_LATENCY_RATE");
+    pub static ref CHAOS_MAX_LATENCY: Duration = Duration::from_millis(match std::env::var("CHAOS_MAX_LATENCY_MS") {
+        Ok(latency) => latency
+            .parse()
+            .expect("CHAOS_MAX_LATENCY_MS must be a number of milliseconds"),
+        Err(_) => DEFAULT_MAX_LATENCY_MS,
+    });
+    pub static ref CHAOS_UNAVAILABLE_RATE: f64 = rate_from_env("CHAOS_UNAVAILABLE_RATE");
+    pub static ref CHAOS_VERIFICATION_FAILURE_RATE: f64 = rate_from_env("CHAOS_VERIFICATION_FAILURE_RATE");
+}
+
+/// The rates of the faults injected by the chaos mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChaosConfig {
+    /// Probability that a request is delayed.
+    pub latency_rate: f64,
+    /// Upper bound of the injected latency.
+    pub max_latency: Duration,
+    /// Probability that a request is answered with a spurious 503.
+    pub unavailable_rate: f64,
+    /// Probability that the verification of a contribution fails.
+    pub verification_failure_rate: f64,
+}
+
+impl Default for ChaosConfig {
+    /// Generates a [`ChaosConfig`] instance with the rates configured in the env.
+    fn default() -> Self {
+        Self {
+            latency_rate: *CHAOS_LATENCY_RATE,
+            max_latency: *CHAOS_MAX_LATENCY,
+            unavailable_rate: *CHAOS_UNAVAILABLE_RATE,
+            verification_failure_rate: *CHAOS_VERIFICATION_FAILURE_RATE,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Returns a configuration injecting no faults.
+    pub fn disabled() -> Self {
+        Self {
+            latency_rate: 0.0,
+            max_latency: Duration::from_millis(DEFAULT_MAX_LATENCY_MS),
+            unavailable_rate: 0.0,
+            verification_failure_rate: 0.0,
+        }
+    }
+
+    /// Returns `true` if any fault is injected.
+    pub fn is_enabled(&self) -> bool {
+        self.latency_rate > 0.0 || self.unavailable_rate > 0.0 || self.verification_failure_rate > 0.0
+    }
+
+    /// Returns the latency to inject in a request, if any.
+    pub fn sample_latency(&self) -> Option<Duration> {
+        let mut rng = rand::thread_rng();
+        match rng.gen_bool(self.latency_rate) {
+            true => Some(self.max_latency.mul_f64(rng.gen::<f64>())),
+            false => None,
+        }
+    }
+
+    /// Returns `true` if a request must be answered with a spurious 503.
+    pub fn sample_unavailable(&self) -> bool {
+        rand::thread_rng().gen_bool(self.unavailable_rate)
+    }
+
+    /// Returns `true` if the verification of a contribution must fail.
+    pub fn sample_verification_failure(&self) -> bool {
+        rand::thread_rng().gen_bool(self.verification_failure_rate)
+    }
+}
+
+/// Marks a request failed by the chaos mode.
+#[cfg(feature = "server")]
+struct Unavailable(bool);
+
+/// Fairing injecting latencies and spurious 503s in the requests to the server.
+#[cfg(feature = "server")]
+#[derive(Clone, Debug, Default)]
+pub struct Chaos {
+    config: ChaosConfig,
+}
+
+#[cfg(feature = "server")]
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[cfg(feature = "server")]
+#[rocket::async_trait]
+impl Fairing for Chaos {
+    fn info(&self) -> Info {
+        Info {
+            name: "Chaos mode",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        // Leave the CORS preflight requests alone, browsers don't retry them
+        if request.method() == Method::Options {
+            return;
+        }
+
+        if let Some(latency) = self.config.sample_latency() {
+            debug!("Chaos: delaying {} by {:?}", request.uri(), latency);
+            rocket::tokio::time::sleep(latency).await;
+        }
+
+        if self.config.sample_unavailable() {
+            debug!("Chaos: failing {}", request.uri());
+            request.local_cache(|| Unavailable(true));
+            // Route the request nowhere so that no handler processes it
+            request.set_uri(Origin::parse(UNAVAILABLE_PATH).expect("Invalid chaos path"));
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if request.local_cache(|| Unavailable(false)).0 {
+            let body = "Service unavailable (chaos mode)";
+            response.set_status(Status::ServiceUnavailable);
+            response.set_sized_body(body.len(), Cursor::new(body));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled() {
+        let config = ChaosConfig::disabled();
+
+        assert!(!config.is_enabled());
+        assert_eq!(config.sample_latency(), None);
+        assert!(!config.sample_unavailable());
+        assert!(!config.sample_verification_failure());
+    }
+
+    #[test]
+    fn test_always() {
+        let config = ChaosConfig {
+            latency_rate: 1.0,
+            max_latency: Duration::from_millis(100),
+            unavailable_rate: 1.0,
+            verification_failure_rate: 1.0,
+        };
+
+        assert!(config.is_enabled());
+        assert!(config.sample_latency().unwrap() <= Duration::from_millis(100));
+        assert!(config.sample_unavailable());
+        assert!(config.sample_verification_failure());
+    }
+}
@@ -41,6 +41,9 @@ impl Aggregation {
         let readers = Self::readers(environment, storage, round)?;
         let _contribution_readers: Vec<_> = readers.iter().map(|r| (r.as_ref(), compressed_output)).collect();
 
+        // Record the round file in the metadata.
+        storage.commit(&round_locator)?;
+
         let elapsed = Instant::now().duration_since(start);
         debug!("Completed aggregation on round {} in {:?}", round_height, elapsed);
         Ok(())
@@ -2,14 +2,22 @@ use crate::{
     authentication::Signature,
     commands::SigningKey,
     environment::Environment,
+    hashing::ContributionHasher,
+    objects::{chain_start, iterate_hash, ComputationCheckpoint, ComputationProof, MAX_CHECKPOINTS},
     storage::{Disk, Locator, StorageLocator, StorageObject},
     CoordinatorError,
 };
 use phase2::helpers::CurveKind;
 
-use setup_utils::calculate_hash;
-
-use std::{io::Write, sync::Arc, time::Instant};
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
 use tracing::{debug, error, info, trace};
 
 pub const SEED_LENGTH: usize = 32;
@@ -68,16 +76,20 @@ impl Computation {
         // Run computation on chunk.
         let settings = environment.parameters();
         let curve = settings.curve();
+        let hasher = environment.contribution_hasher();
         if let Err(error) = match curve {
             CurveKind::Bls12_381 => Self::contribute(
+                hasher,
                 storage.reader(challenge_locator)?.as_ref(),
                 storage.writer(response_locator)?.as_mut(),
             ),
             CurveKind::Bls12_377 => Self::contribute(
+                hasher,
                 storage.reader(challenge_locator)?.as_ref(),
                 storage.writer(response_locator)?.as_mut(),
             ),
             CurveKind::BW6 => Self::contribute(
+                hasher,
                 storage.reader(challenge_locator)?.as_ref(),
                 storage.writer(response_locator)?.as_mut(),
             ),
@@ -86,9 +98,12 @@ impl Computation {
             return Err(CoordinatorError::ComputationFailed.into());
         }
 
+        // Record the written response in the metadata.
+        storage.commit(response_locator)?;
+
         // Load a contribution response reader.
         let reader = storage.reader(response_locator)?;
-        let contribution_hash = calculate_hash(reader.as_ref());
+        let contribution_hash = hasher.hash(reader.as_ref());
         drop(reader);
         debug!("Response hash is {}", pretty_hash!(&contribution_hash));
 
@@ -103,6 +118,7 @@ impl Computation {
             storage,
             signature,
             contributor_signing_key,
+            hasher,
             challenge_locator,
             response_locator,
             None,
@@ -123,10 +139,14 @@ impl Computation {
         Ok(())
     }
 
-    fn contribute(challenge_reader: &[u8], mut response_writer: &mut [u8]) -> Result<(), CoordinatorError> {
+    fn contribute(
+        hasher: &dyn ContributionHasher,
+        challenge_reader: &[u8],
+        mut response_writer: &mut [u8],
+    ) -> Result<(), CoordinatorError> {
         trace!("Calculating previous contribution hash and writing it to the response");
 
-        let challenge_hash = calculate_hash(&challenge_reader);
+        let challenge_hash = hasher.hash(&challenge_reader);
         debug!("Challenge hash is {}", pretty_hash!(&challenge_hash));
 
         response_writer.write_all(&challenge_hash.as_slice())?;
@@ -146,10 +166,10 @@ impl Computation {
 
         // Contribute to the MASP circuit
         let rand_source = RandomSource::Entropy(String::from("entropy"));
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "rehearsal"))]
         Self::contribute_test_masp(&challenge_reader, &mut response_writer, &rand_source);
 
-        #[cfg(not(debug_assertions))]
+        #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
         Self::contribute_masp(&challenge_reader, &mut response_writer, &rand_source);
 
         trace!("Finishing writing your contribution to response file");
@@ -161,7 +181,7 @@ impl Computation {
     // | previous_contribution_file_hash (64 bytes) |
     // | masp_mpc_new_parameters_contribution |
     // The masp-mpc commands are executed at offset 64 of the [`ContributionFile`]
-    #[cfg(not(debug_assertions))]
+    #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
     pub fn contribute_masp<W: Write>(challenge_reader: &[u8], mut response_writer: W, rand_source: &RandomSource) {
         // Create an RNG as following:
         //  - if the user provides a seed, create the rng from that seed
@@ -261,7 +281,7 @@ impl Computation {
         response_writer.flush().unwrap();
     }
 
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "rehearsal"))]
     pub fn contribute_test_masp<W: Write>(challenge_reader: &[u8], mut response_writer: W, rand_source: &RandomSource) {
         // Create an RNG as following:
         //  - if the user provides a seed, create the rng from that seed
@@ -316,11 +336,64 @@ impl Computation {
     }
 }
 
+///
+/// Runs the hash chain of a proof of computation time on a thread of its own, from the hash of the challenge, while
+/// the contribution is computed, emitting a checkpoint every given number of iterations until stopped or until the
+/// proof has [`MAX_CHECKPOINTS`] checkpoints.
+///
+pub struct ComputationClock {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<ComputationProof>,
+}
+
+impl ComputationClock {
+    /// Starts the chain from the given hash of the challenge.
+    pub fn start(challenge_hash: &[u8], iterations_per_checkpoint: u64) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let challenge_hash = challenge_hash.to_vec();
+
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            let mut hash = chain_start(&challenge_hash);
+            let mut checkpoints = Vec::new();
+            // The proof has at least one checkpoint, however early the clock is stopped
+            loop {
+                hash = iterate_hash(hash, iterations_per_checkpoint);
+                checkpoints.push(ComputationCheckpoint {
+                    hash: hex::encode(hash),
+                    elapsed_ms: start.elapsed().as_millis() as u64,
+                });
+                if stopped.load(Ordering::Relaxed) || checkpoints.len() >= MAX_CHECKPOINTS {
+                    break;
+                }
+            }
+
+            ComputationProof {
+                challenge_hash: hex::encode(&challenge_hash),
+                iterations_per_checkpoint,
+                checkpoints,
+            }
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stops the chain at the next checkpoint and returns the proof.
+    pub fn stop(self) -> ComputationProof {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .join()
+            .expect("the thread of the computation clock panicked")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         authentication::{Dummy, Signature},
-        commands::{Computation, Initialization, Seed, SEED_LENGTH},
+        commands::{Computation, ComputationClock, Initialization, Seed, SEED_LENGTH},
+        objects::MIN_CHECKPOINT_ITERATIONS,
         storage::{ContributionLocator, ContributionSignatureLocator, Locator, Object, StorageObject},
         testing::prelude::*,
     };
@@ -417,4 +490,18 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_computation_clock() {
+        let challenge_hash = [7u8; 64];
+        let clock = ComputationClock::start(&challenge_hash, MIN_CHECKPOINT_ITERATIONS);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let proof = clock.stop();
+
+        assert!(!proof.checkpoints.is_empty());
+        assert_eq!(
+            proof.verify(&hex::encode(challenge_hash)),
+            Ok(proof.checkpoints.len() as u64 * MIN_CHECKPOINT_ITERATIONS)
+        );
+    }
 }
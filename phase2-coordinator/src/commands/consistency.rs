@@ -0,0 +1,39 @@
+use crate::storage::{Disk, Inconsistency};
+
+use std::time::Instant;
+use tracing::{debug, error, info};
+
+pub(crate) struct Consistency;
+
+impl Consistency {
+    ///
+    /// Runs the consistency check between the metadata and the objects in storage.
+    ///
+    /// If `reconcile` is set, the inconsistencies which can be safely resolved (e.g. after
+    /// a crash of the coordinator) are fixed in the metadata.
+    ///
+    /// On success, this function returns the unresolved inconsistencies.
+    ///
+    #[inline]
+    pub(crate) fn run(storage: &mut Disk, reconcile: bool) -> anyhow::Result<Vec<Inconsistency>> {
+        let start = Instant::now();
+        debug!("Starting storage consistency check");
+
+        let inconsistencies = match reconcile {
+            true => storage.reconcile()?,
+            false => storage.check_consistency()?,
+        };
+
+        for inconsistency in &inconsistencies {
+            error!("Storage inconsistency found: {:?}", inconsistency);
+        }
+
+        let elapsed = Instant::now().duration_since(start);
+        info!(
+            "Completed storage consistency check in {:?} with {} unresolved inconsistencies",
+            elapsed,
+            inconsistencies.len()
+        );
+        Ok(inconsistencies)
+    }
+}
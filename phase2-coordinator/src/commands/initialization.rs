@@ -1,11 +1,12 @@
 use crate::{
     environment::Environment,
+    hashing::ContributionHasher,
     storage::{ContributionLocator, Disk, Locator, Object, StorageObject},
     CoordinatorError,
 };
 
 use phase2::helpers::CurveKind;
-use setup_utils::{blank_hash, calculate_hash};
+use setup_utils::blank_hash;
 
 use std::{io::Write, time::Instant};
 use tracing::{debug, error, info, trace};
@@ -15,8 +16,8 @@ use masp_phase2::MPCParameters;
 use bellman::{Circuit, ConstraintSystem, SynthesisError};
 use bls12_381::Scalar;
 
-struct TestCircuit {
-    x: Option<Scalar>,
+pub(crate) struct TestCircuit {
+    pub(crate) x: Option<Scalar>,
 }
 impl Circuit<Scalar> for TestCircuit {
     fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
@@ -67,6 +68,9 @@ impl Initialization {
             return Err(CoordinatorError::InitializationFailed.into());
         }
 
+        // Record the written challenge in the metadata.
+        storage.commit(&contribution_locator)?;
+
         // Copy the current transcript to the next transcript.
         // This operation will *overwrite* the contents of `next_transcript`.
         let next_contribution_locator =
@@ -76,7 +80,8 @@ impl Initialization {
         }
 
         // Check that the current and next contribution hash match.
-        let hash = Self::check_hash(storage, &contribution_locator, &next_contribution_locator)?;
+        let hasher = environment.contribution_hasher();
+        let hash = Self::check_hash(hasher, storage, &contribution_locator, &next_contribution_locator)?;
         debug!("The challenge hash of Chunk {} is {}", chunk_id, pretty_hash!(&hash));
 
         let elapsed = Instant::now().duration_since(start);
@@ -84,6 +89,43 @@ impl Initialization {
         Ok(hash)
     }
 
+    ///
+    /// Writes the seed of the genesis challenge in place of the blank hash at the start of the initial challenge of
+    /// the given round and of the next round, computed by the first contributor.
+    ///
+    /// Returns the hash of the seeded challenge.
+    ///
+    pub(crate) fn seed(
+        environment: &Environment,
+        storage: &mut Disk,
+        round_height: u64,
+        chunk_id: u64,
+        seed: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let contribution_locator = Locator::ContributionFile(ContributionLocator::new(round_height, chunk_id, 0, true));
+        let next_contribution_locator =
+            Locator::ContributionFile(ContributionLocator::new(round_height + 1, chunk_id, 0, true));
+
+        let mut challenge = storage.reader(&contribution_locator)?.to_vec();
+        match challenge.get_mut(0..seed.len()) {
+            Some(hash) => hash.copy_from_slice(seed),
+            None => return Err(CoordinatorError::InitializationFailed.into()),
+        }
+        storage.update(&contribution_locator, Object::ContributionFile(challenge.clone()))?;
+        storage.update(&next_contribution_locator, Object::ContributionFile(challenge))?;
+
+        // Check that the current and next contribution hash match.
+        let hasher = environment.contribution_hasher();
+        let hash = Self::check_hash(hasher, storage, &contribution_locator, &next_contribution_locator)?;
+        debug!(
+            "The seeded challenge hash of Chunk {} is {}",
+            chunk_id,
+            pretty_hash!(&hash)
+        );
+
+        Ok(hash)
+    }
+
     /// Runs Phase 2 initialization on the given parameters.
     #[inline]
     fn initialization(mut writer: &mut [u8]) -> Result<(), CoordinatorError> {
@@ -102,10 +144,10 @@ impl Initialization {
         //
         // NOTE: Add your MPC Parameters initialization function below
         //
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "rehearsal"))]
         Self::initialize_test_masp(&mut writer);
 
-        #[cfg(not(debug_assertions))]
+        #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
         Self::initialize_masp(&mut writer);
 
         trace!("Completed Phase 2 initialization operation");
@@ -116,6 +158,7 @@ impl Initialization {
     /// Compute both contribution hashes and check for equivalence.
     #[inline]
     fn check_hash(
+        hasher: &dyn ContributionHasher,
         storage: &Disk,
         contribution_locator: &Locator,
         next_contribution_locator: &Locator,
@@ -124,8 +167,8 @@ impl Initialization {
         let next = storage.reader(next_contribution_locator)?;
 
         // Compare the contribution hashes of both files to ensure the copy succeeded.
-        let contribution_hash_0 = calculate_hash(current.as_ref());
-        let contribution_hash_1 = calculate_hash(next.as_ref());
+        let contribution_hash_0 = hasher.hash(current.as_ref());
+        let contribution_hash_1 = hasher.hash(next.as_ref());
         if contribution_hash_0 != contribution_hash_1 {
             return Err(CoordinatorError::InitializationTranscriptsDiffer.into());
         }
@@ -134,7 +177,7 @@ impl Initialization {
     }
 
     #[inline]
-    #[cfg(not(debug_assertions))]
+    #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
     fn initialize_masp(mut writer: &mut [u8]) {
         //
         // MASP spend circuit
@@ -207,7 +250,7 @@ impl Initialization {
     }
 
     #[inline]
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "rehearsal"))]
     fn initialize_test_masp(mut writer: &mut [u8]) {
         // MASP Test circuit
         trace!("Creating initial parameters for MASP Test Circuit...");
@@ -1,6 +1,9 @@
 pub(crate) mod aggregation;
 pub(crate) use aggregation::*;
 
+pub(crate) mod consistency;
+pub(crate) use consistency::*;
+
 #[cfg(any(test, feature = "operator"))]
 pub mod computation;
 #[cfg(any(test, feature = "operator"))]
@@ -9,22 +12,28 @@ pub use computation::*;
 pub(crate) mod initialization;
 pub(crate) use initialization::*;
 
+pub(crate) mod rebalance;
+pub(crate) use rebalance::*;
+
+pub(crate) mod restore;
+pub(crate) use restore::*;
+
 #[cfg(any(test, feature = "operator"))]
 pub(crate) mod verification;
 #[cfg(any(test, feature = "operator"))]
+pub use verification::verify_link;
+#[cfg(any(test, feature = "operator"))]
 pub(crate) use verification::*;
 
 #[cfg(any(test, feature = "operator"))]
 use crate::{
     authentication::Signature,
+    hashing::ContributionHasher,
     objects::{ContributionFileSignature, ContributionState},
     storage::{Disk, Locator, StorageLocator, StorageObject},
     CoordinatorError,
 };
 
-#[cfg(any(test, feature = "operator"))]
-use setup_utils::calculate_hash;
-
 #[cfg(any(test, feature = "operator"))]
 use std::{io::Write, sync::Arc};
 
@@ -35,7 +44,7 @@ pub type SigningKey = String;
 /// Writes the contribution file signature to a given `contribution_file_signature` locator.
 ///
 /// This function constructs a contribution file signature with an empty signature and the
-/// hashes of the following files:
+/// hashes, computed with the given hasher, of the following files:
 /// 1. Challenge file
 /// 2. Response file
 /// 3. Next challenge file (for verifiers)
@@ -50,6 +59,7 @@ pub(crate) fn write_contribution_file_signature(
     storage: &mut Disk,
     signature: Arc<dyn Signature>,
     signing_key: &SigningKey,
+    hasher: &dyn ContributionHasher,
     challenge_locator: &Locator,
     response_locator: &Locator,
     next_challenge_locator: Option<&Locator>,
@@ -59,17 +69,17 @@ pub(crate) fn write_contribution_file_signature(
 
     // Calculate the challenge hash.
     let challenge_reader = storage.reader(challenge_locator)?;
-    let challenge_hash = calculate_hash(challenge_reader.as_ref()).to_vec();
+    let challenge_hash = hasher.hash(challenge_reader.as_ref()).to_vec();
 
     // Calculate the response hash.
     let response_reader = storage.reader(response_locator)?;
-    let response_hash = calculate_hash(response_reader.as_ref()).to_vec();
+    let response_hash = hasher.hash(response_reader.as_ref()).to_vec();
 
     // Calculate the next challenge hash.
     let next_challenge_hash = match next_challenge_locator {
         Some(next_challenge_locator) => {
             let next_challenge_reader = storage.reader(next_challenge_locator)?;
-            let next_challenge_hash = calculate_hash(next_challenge_reader.as_ref()).to_vec();
+            let next_challenge_hash = hasher.hash(next_challenge_reader.as_ref()).to_vec();
 
             Some(next_challenge_hash)
         }
@@ -98,6 +108,10 @@ pub(crate) fn write_contribution_file_signature(
         .as_mut()
         .write_all(&contribution_file_signature_bytes[..])?;
     contribution_file_signature_writer.flush()?;
+    drop(contribution_file_signature_writer);
+
+    // Record the written signature in the metadata.
+    storage.commit(contribution_file_signature_locator)?;
 
     Ok(())
 }
@@ -0,0 +1,29 @@
+use crate::storage::Disk;
+
+use std::time::Instant;
+use tracing::{debug, info};
+
+pub(crate) struct Rebalance;
+
+impl Rebalance {
+    ///
+    /// Moves the objects in storage to their assigned shard, e.g. after a shard
+    /// has been added to or removed from the configuration of the coordinator.
+    ///
+    /// On success, this function returns the number of moved objects.
+    ///
+    #[inline]
+    pub(crate) fn run(storage: &mut Disk) -> anyhow::Result<usize> {
+        let start = Instant::now();
+        debug!("Starting storage rebalance");
+
+        let moved = storage.rebalance()?;
+
+        let elapsed = Instant::now().duration_since(start);
+        info!(
+            "Completed storage rebalance in {:?} with {} moved objects",
+            elapsed, moved
+        );
+        Ok(moved)
+    }
+}
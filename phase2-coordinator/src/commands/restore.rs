@@ -0,0 +1,68 @@
+use crate::{
+    backup::Snapshot,
+    coordinator_state::CoordinatorState,
+    storage::{Disk, Locator, Object},
+    CoordinatorError,
+};
+
+use std::{collections::HashMap, time::Instant};
+use tracing::{debug, info, warn};
+
+pub(crate) struct Restore;
+
+impl Restore {
+    ///
+    /// Replaces the state of the coordinator, the round height and the state of
+    /// the rounds in storage with the ones of the given backup snapshot.
+    ///
+    /// The contribution files are not part of a backup, so this function checks
+    /// the files listed in the manifests of the snapshot against the storage.
+    ///
+    /// On success, this function returns the names of the files of the manifests
+    /// which are missing from the storage or have a different size.
+    ///
+    #[inline]
+    pub(crate) fn run(storage: &mut Disk, snapshot: &Snapshot) -> anyhow::Result<Vec<String>> {
+        let start = Instant::now();
+        debug!("Starting restore of the backup of {}", snapshot.created_at);
+
+        let state: CoordinatorState = serde_json::from_value(snapshot.coordinator_state.clone())?;
+
+        let mut objects: Vec<(Locator, Object)> = snapshot
+            .rounds
+            .iter()
+            .map(|round| {
+                let round_height = round.round_height();
+                (Locator::RoundState { round_height }, Object::RoundState(round.clone()))
+            })
+            .collect();
+        objects.push((Locator::RoundHeight, Object::RoundHeight(snapshot.round_height)));
+        objects.push((Locator::CoordinatorState, Object::CoordinatorState(state)));
+        storage.write_all_or_nothing(&objects)?;
+
+        let mut mismatches = vec![];
+        for manifest in &snapshot.manifests {
+            let stored: HashMap<String, u64> = match storage.round_manifest(manifest.round_height) {
+                Ok(stored) => stored.files.into_iter().map(|file| (file.name, file.size)).collect(),
+                Err(CoordinatorError::RoundDoesNotExist) => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+
+            for file in &manifest.files {
+                if stored.get(&file.name) != Some(&file.size) {
+                    warn!("File {} of the backup does not match the storage", file.name);
+                    mismatches.push(file.name.clone());
+                }
+            }
+        }
+
+        let elapsed = Instant::now().duration_since(start);
+        info!(
+            "Completed restore of the backup of {} in {:?} with {} mismatching files",
+            snapshot.created_at,
+            elapsed,
+            mismatches.len()
+        );
+        Ok(mismatches)
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
     authentication::Signature,
     commands::SigningKey,
-    environment::Environment,
+    environment::{ComputeBackend, Environment},
+    hashing::ContributionHasher,
+    memory, sandbox,
     storage::{
         ContributionLocator, ContributionSignatureLocator, Disk, Locator, Object, StorageLocator, StorageObject,
     },
     CoordinatorError,
 };
 use phase2::helpers::CurveKind;
-use setup_utils::{calculate_hash, GenericArray, U64};
+use setup_utils::{GenericArray, U64};
 
 use std::{io::Write, sync::Arc, time::Instant};
 use tracing::{debug, error, info, trace};
@@ -133,6 +135,7 @@ impl Verification {
             storage,
             signature,
             signing_key,
+            environment.contribution_hasher(),
             &challenge_locator,
             &response_locator,
             Some(&next_challenge_locator),
@@ -168,24 +171,41 @@ impl Verification {
             return Err(CoordinatorError::ContributionLocatorMissing);
         }
 
+        // Abort before loading the files if the verification would exceed the memory budget.
+        let required_memory =
+            memory::verification_memory(storage.size(&challenge_locator)?, storage.size(&response_locator)?);
+        memory::check_budget(environment.verification_memory_budget(), required_memory)?;
+
         // Execute ceremony verification on chunk.
         let settings = environment.parameters();
-        let result = match settings.curve() {
-            CurveKind::Bls12_381 => Self::transform_pok_and_correctness(
+        let hasher = environment.contribution_hasher();
+        let result = match (environment.compute_backend(), settings.curve()) {
+            (ComputeBackend::Sandboxed(limits), _) => sandbox::verify(
+                limits,
+                environment.hash_algorithm(),
+                storage.reader(&challenge_locator)?.as_ref(),
+                storage.reader(&response_locator)?.as_ref(),
+            ),
+            (ComputeBackend::InProcess, CurveKind::Bls12_381) => Self::transform_pok_and_correctness(
+                hasher,
                 storage.reader(&challenge_locator)?.as_ref(),
                 storage.reader(&response_locator)?.as_ref(),
             ),
-            CurveKind::Bls12_377 => Self::transform_pok_and_correctness(
+            (ComputeBackend::InProcess, CurveKind::Bls12_377) => Self::transform_pok_and_correctness(
+                hasher,
                 storage.reader(&challenge_locator)?.as_ref(),
                 storage.reader(&response_locator)?.as_ref(),
             ),
-            CurveKind::BW6 => Self::transform_pok_and_correctness(
+            (ComputeBackend::InProcess, CurveKind::BW6) => Self::transform_pok_and_correctness(
+                hasher,
                 storage.reader(&challenge_locator)?.as_ref(),
                 storage.reader(&response_locator)?.as_ref(),
             ),
         };
         let response_hash = match result {
             Ok(response_hash) => response_hash,
+            // Not a verdict on the contribution, retried by the coordinator
+            Err(CoordinatorError::VerifierCrashed) => return Err(CoordinatorError::VerifierCrashed),
             Err(error) => {
                 error!("Verification failed with {}", error);
                 return Err(CoordinatorError::VerificationFailed.into());
@@ -205,7 +225,7 @@ impl Verification {
             trace!("Copying decompressed response file without the public key");
             storage.copy(&response_locator, &next_challenge_locator)?;
 
-            calculate_hash(&storage.reader(&next_challenge_locator)?)
+            hasher.hash(&storage.reader(&next_challenge_locator)?)
         } else {
             trace!("Starting decompression of the response file for the next challenge file");
 
@@ -235,7 +255,10 @@ impl Verification {
                 )?,
             };
 
-            calculate_hash(storage.reader(&next_challenge_locator)?.as_ref())
+            // Record the written next challenge in the metadata.
+            storage.commit(&next_challenge_locator)?;
+
+            hasher.hash(storage.reader(&next_challenge_locator)?.as_ref())
         };
 
         debug!("The next challenge hash is {}", pretty_hash!(&next_challenge_hash));
@@ -262,8 +285,94 @@ impl Verification {
         Ok(())
     }
 
+    ///
+    /// Verifies again the contribution of a completed round, and checks that it is the one the next challenge was
+    /// derived from. Nothing is written to storage, so that the progression of the ceremony is left untouched.
+    ///
+    /// Returns the hash of the response file.
+    ///
+    pub(crate) fn reverify(
+        environment: &Environment,
+        storage: &Disk,
+        round_height: u64,
+        chunk_id: u64,
+        contribution_id: u64,
+        is_final_contribution: bool,
+    ) -> Result<GenericArray<u8, U64>, CoordinatorError> {
+        info!(
+            "Starting re-verification of round {} chunk {} contribution {}",
+            round_height, chunk_id, contribution_id
+        );
+        if contribution_id == 0 {
+            return Err(CoordinatorError::VerificationOnContributionIdZero);
+        }
+
+        let challenge_locator = Locator::ContributionFile(ContributionLocator::new(
+            round_height,
+            chunk_id,
+            contribution_id - 1,
+            true,
+        ));
+        let response_locator =
+            Locator::ContributionFile(ContributionLocator::new(round_height, chunk_id, contribution_id, false));
+        let next_challenge_locator = match is_final_contribution {
+            true => Locator::ContributionFile(ContributionLocator::new(round_height + 1, chunk_id, 0, true)),
+            false => Locator::ContributionFile(ContributionLocator::new(round_height, chunk_id, contribution_id, true)),
+        };
+        if !storage.exists(&challenge_locator)
+            || !storage.exists(&response_locator)
+            || !storage.exists(&next_challenge_locator)
+        {
+            return Err(CoordinatorError::ContributionLocatorMissing);
+        }
+
+        let required_memory =
+            memory::verification_memory(storage.size(&challenge_locator)?, storage.size(&response_locator)?);
+        memory::check_budget(environment.verification_memory_budget(), required_memory)?;
+
+        let challenge = storage.reader(&challenge_locator)?;
+        let response = storage.reader(&response_locator)?;
+        let (challenge, response) = (challenge.as_ref(), response.as_ref());
+
+        // The verification of the transformation panics on an invalid contribution
+        let hasher = environment.contribution_hasher();
+        let verified = match environment.compute_backend() {
+            ComputeBackend::InProcess => {
+                std::panic::catch_unwind(|| Self::transform_pok_and_correctness(hasher, challenge, response))
+            }
+            ComputeBackend::Sandboxed(limits) => Ok(sandbox::verify(
+                limits,
+                environment.hash_algorithm(),
+                challenge,
+                response,
+            )),
+        };
+        let response_hash = match verified {
+            Ok(result) => result?,
+            Err(_) => {
+                error!("Re-verification of the transformation failed");
+                return Err(CoordinatorError::VerificationFailed);
+            }
+        };
+
+        // The next challenge is the hash of the response followed by its parameters
+        let next_challenge = storage.reader(&next_challenge_locator)?;
+        if next_challenge.get(0..64) != Some(response_hash.as_slice()) || next_challenge.get(64..) != response.get(64..)
+        {
+            error!("The next challenge wasn't derived from the re-verified response");
+            return Err(CoordinatorError::ContributionHashMismatch);
+        }
+
+        info!(
+            "Completed re-verification of round {} chunk {} contribution {}",
+            round_height, chunk_id, contribution_id
+        );
+        Ok(response_hash)
+    }
+
     #[inline]
-    fn transform_pok_and_correctness(
+    pub(crate) fn transform_pok_and_correctness(
+        hasher: &dyn ContributionHasher,
         challenge_reader: &[u8],
         response_reader: &[u8],
     ) -> Result<GenericArray<u8, U64>, CoordinatorError> {
@@ -272,7 +381,7 @@ impl Verification {
         // Check that the challenge hashes match.
         let _challenge_hash = {
             // Compute the challenge hash using the challenge file.
-            let challenge_hash = calculate_hash(challenge_reader.as_ref());
+            let challenge_hash = hasher.hash(challenge_reader.as_ref());
 
             // Fetch the challenge hash from the response file.
             let saved_challenge_hash = &response_reader
@@ -292,7 +401,7 @@ impl Verification {
         };
 
         // Compute the response hash using the response file.
-        let response_hash = calculate_hash(response_reader);
+        let response_hash = hasher.hash(response_reader);
         debug!("Response Reader hash is {}", pretty_hash!(&response_hash));
         debug!("Challenge Reader is {}", pretty_hash!(&challenge_reader[0..256]));
         debug!("Response Reader is {}", pretty_hash!(&response_reader[0..256]));
@@ -303,10 +412,10 @@ impl Verification {
 
         trace!("Starting verification");
 
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "rehearsal"))]
         Self::verify_test_masp(&challenge_reader, &response_reader);
 
-        #[cfg(not(debug_assertions))]
+        #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
         Self::verify_masp(&challenge_reader, &response_reader);
 
         trace!("Completed verification");
@@ -315,7 +424,7 @@ impl Verification {
     }
 
     #[inline]
-    #[cfg(not(debug_assertions))]
+    #[cfg(not(any(debug_assertions, feature = "rehearsal")))]
     fn verify_masp(challenge_reader: &[u8], response_reader: &[u8]) {
         trace!("Reading MASP Spend old parameters...");
         let mut masp_challenge_reader = &challenge_reader[64..];
@@ -376,7 +485,7 @@ impl Verification {
     }
 
     #[inline]
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "rehearsal"))]
     fn verify_test_masp(challenge_reader: &[u8], response_reader: &[u8]) {
         let masp_test =
             MPCParameters::read(&challenge_reader[64..], false).expect("couldn't deserialize MASP Test params");
@@ -410,6 +519,32 @@ impl Verification {
     }
 }
 
+///
+/// Verifies a link of the chain of contributions, e.g. to verify a downloaded transcript: the response is a valid
+/// contribution on top of the challenge, and the next challenge is the hash of the response followed by its
+/// parameters, hashed with the given function of the ceremony. Returns the hash of the response.
+///
+pub fn verify_link(
+    hasher: &dyn ContributionHasher,
+    challenge: &[u8],
+    response: &[u8],
+    next_challenge: &[u8],
+) -> Result<GenericArray<u8, U64>, CoordinatorError> {
+    // The verification of the transformation panics on an invalid contribution
+    let verified =
+        std::panic::catch_unwind(|| Verification::transform_pok_and_correctness(hasher, challenge, response));
+    let response_hash = match verified {
+        Ok(result) => result?,
+        Err(_) => return Err(CoordinatorError::VerificationFailed),
+    };
+
+    if next_challenge.get(0..64) != Some(response_hash.as_slice()) || next_challenge.get(64..) != response.get(64..) {
+        return Err(CoordinatorError::ContributionHashMismatch);
+    }
+
+    Ok(response_hash)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -0,0 +1,268 @@
+//! Compression of the bodies exchanged with the REST API, negotiated with the clients.
+//!
+//! Responses with a known size of at least `COMPRESSION_MIN_SIZE` bytes (defaults to 1024) are compressed with zstd or
+//! gzip if the client accepts it through the `Accept-Encoding` header. Already compressed content types and streamed
+//! responses are sent as they are.
+//!
+//! Requests can send a compressed json body, declaring its encoding in the `Content-Encoding` header. The
+//! `Content-Length` and `Digest` headers, and so the signature of the request, refer to the body as sent, which is
+//! decompressed once its digest has been checked. The size limit of the json bodies applies to the decompressed body.
+
+use crate::{cors::VARY_HEADER, rest_utils::ResponseError};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression as GzipLevel};
+use lazy_static::lazy_static;
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{ContentType, Header, Method},
+    Request,
+    Response,
+};
+use std::{
+    io::{self, Cursor, Read, Write},
+    str::FromStr,
+};
+use tracing::error;
+
+pub const ACCEPT_ENCODING_HEADER: &str = "Accept-Encoding";
+pub const CONTENT_ENCODING_HEADER: &str = "Content-Encoding";
+
+const DEFAULT_MIN_SIZE: usize = 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Responses larger than this are not compressed, to bound the memory used by the compression.
+const MAX_COMPRESSED_RESPONSE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Subtypes of the content which is already compressed.
+const COMPRESSED_SUBTYPES: [&str; 5] = ["zip", "gzip", "x-gzip", "zstd", "x-tar+gzip"];
+
+lazy_static! {
+    pub static ref COMPRESSION_MIN_SIZE: usize = match std::env::var("COMPRESSION_MIN_SIZE") {
+        Ok(size) => size.parse().expect("COMPRESSION_MIN_SIZE must be a number of bytes"),
+        Err(_) => DEFAULT_MIN_SIZE,
+    };
+}
+
+/// The encodings of the bodies supported by the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Identity => "identity",
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the encoding with the highest quality in the given `Accept-Encoding` header, preferring zstd on ties.
+    pub fn negotiate(accept_encoding: &str) -> Self {
+        let quality = |encoding: ContentEncoding| {
+            let mut wildcard = 0.0;
+            for entry in accept_encoding.split(',') {
+                let mut parameters = entry.split(';');
+                let name = parameters.next().unwrap_or_default().trim();
+                let quality = parameters
+                    .find_map(|parameter| parameter.trim().strip_prefix("q="))
+                    .map_or(1.0, |quality| quality.trim().parse::<f32>().unwrap_or(0.0));
+
+                if name.eq_ignore_ascii_case(encoding.as_str()) {
+                    return quality;
+                } else if name == "*" {
+                    wildcard = quality;
+                }
+            }
+
+            wildcard
+        };
+
+        let mut negotiated = (ContentEncoding::Identity, 0.0);
+        for encoding in [ContentEncoding::Zstd, ContentEncoding::Gzip].iter() {
+            let quality = quality(*encoding);
+            if quality > negotiated.1 {
+                negotiated = (*encoding, quality);
+            }
+        }
+
+        negotiated.0
+    }
+
+    pub fn compress(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Identity => Ok(body.to_vec()),
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentEncoding::Zstd => zstd::stream::encode_all(body, ZSTD_LEVEL),
+        }
+    }
+
+    /// Decompresses the body, failing if the decompressed body exceeds `limit` bytes.
+    pub fn decompress(&self, body: &[u8], limit: u64) -> Result<Vec<u8>, ResponseError> {
+        let decoder: Box<dyn Read + '_> = match self {
+            ContentEncoding::Identity => Box::new(body),
+            ContentEncoding::Gzip => Box::new(GzDecoder::new(body)),
+            ContentEncoding::Zstd => {
+                Box::new(zstd::stream::read::Decoder::new(body).map_err(|e| ResponseError::IoError(e.to_string()))?)
+            }
+        };
+
+        // Read one byte more than the limit to detect oversized bodies without decompressing them entirely
+        let mut decompressed = vec![];
+        decoder
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut decompressed)
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+        if decompressed.len() as u64 > limit {
+            return Err(ResponseError::PayloadTooLarge(limit));
+        }
+
+        Ok(decompressed)
+    }
+}
+
+impl FromStr for ContentEncoding {
+    type Err = ResponseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "identity" => Ok(ContentEncoding::Identity),
+            "gzip" | "x-gzip" => Ok(ContentEncoding::Gzip),
+            "zstd" => Ok(ContentEncoding::Zstd),
+            _ => Err(ResponseError::UnsupportedEncoding(s.to_string())),
+        }
+    }
+}
+
+/// Returns `true` if content of the given type is worth compressing.
+fn is_compressible(content_type: Option<&ContentType>) -> bool {
+    match content_type {
+        Some(content_type) => {
+            let top = content_type.top().as_str();
+            let sub = content_type.sub().as_str();
+
+            !(top.eq_ignore_ascii_case("image")
+                || top.eq_ignore_ascii_case("video")
+                || COMPRESSED_SUBTYPES.iter().any(|subtype| sub.eq_ignore_ascii_case(subtype)))
+        }
+        None => true,
+    }
+}
+
+/// Fairing compressing the responses of the server with the encoding accepted by the client.
+#[derive(Clone, Debug)]
+pub struct Compression {
+    min_size: usize,
+}
+
+impl Default for Compression {
+    /// Generates a [`Compression`] instance with the minimum size configured in the env.
+    fn default() -> Self {
+        Self::new(*COMPRESSION_MIN_SIZE)
+    }
+}
+
+impl Compression {
+    pub fn new(min_size: usize) -> Self {
+        Self { min_size }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Compression {
+    fn info(&self) -> Info {
+        Info {
+            name: "Response compression",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if request.method() == Method::Head
+            || response.headers().contains(CONTENT_ENCODING_HEADER)
+            || !is_compressible(response.content_type().as_ref())
+        {
+            return;
+        }
+
+        match response.body().preset_size() {
+            Some(size) if size >= self.min_size && size <= MAX_COMPRESSED_RESPONSE_SIZE => (),
+            _ => return,
+        }
+
+        // The response depends on the encodings accepted by the client
+        response.adjoin_header(Header::new(VARY_HEADER, ACCEPT_ENCODING_HEADER));
+
+        let accept_encoding = request.headers().get(ACCEPT_ENCODING_HEADER).collect::<Vec<_>>().join(",");
+        let encoding = ContentEncoding::negotiate(&accept_encoding);
+        if encoding == ContentEncoding::Identity {
+            return;
+        }
+
+        let body = match response.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Error while reading the response body to compress: {}", e);
+                return;
+            }
+        };
+
+        match encoding.compress(&body) {
+            Ok(compressed) => {
+                response.set_header(Header::new(CONTENT_ENCODING_HEADER, encoding.as_str()));
+                response.set_sized_body(compressed.len(), Cursor::new(compressed));
+            }
+            Err(e) => {
+                error!("Error while compressing the response with {}: {}", encoding.as_str(), e);
+                response.set_sized_body(body.len(), Cursor::new(body));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(ContentEncoding::negotiate(""), ContentEncoding::Identity);
+        assert_eq!(ContentEncoding::negotiate("br"), ContentEncoding::Identity);
+        assert_eq!(ContentEncoding::negotiate("gzip, deflate, br"), ContentEncoding::Gzip);
+        assert_eq!(ContentEncoding::negotiate("gzip, zstd"), ContentEncoding::Zstd);
+        assert_eq!(ContentEncoding::negotiate("zstd;q=0.5, gzip"), ContentEncoding::Gzip);
+        assert_eq!(ContentEncoding::negotiate("*"), ContentEncoding::Zstd);
+        assert_eq!(ContentEncoding::negotiate("*, zstd;q=0"), ContentEncoding::Gzip);
+    }
+
+    #[test]
+    fn test_compress_decompress() {
+        let body = b"{\"round_height\":1}".repeat(100);
+
+        for encoding in [ContentEncoding::Identity, ContentEncoding::Gzip, ContentEncoding::Zstd].iter() {
+            let compressed = encoding.compress(&body).unwrap();
+            assert_eq!(encoding.decompress(&compressed, body.len() as u64).unwrap(), body);
+            assert!(matches!(
+                encoding.decompress(&compressed, body.len() as u64 - 1),
+                Err(ResponseError::PayloadTooLarge(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_content_encoding_from_str() {
+        assert_eq!("GZIP".parse::<ContentEncoding>().unwrap(), ContentEncoding::Gzip);
+        assert!(matches!(
+            "br".parse::<ContentEncoding>(),
+            Err(ResponseError::UnsupportedEncoding(_))
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Conversion of the final parameters of the ceremony to the serialization formats of downstream consumers.
+//!
+//! The contribution files contain, after the 64 bytes hash of the previous contribution, the phase 2 parameters of
+//! each circuit one after the other. The Groth16 parameters of each circuit can be exported as:
+//!
+//! - [`ParameterFormat::Raw`]: the uncompressed encoding of `bellman`, read by the MASP prover
+//! - [`ParameterFormat::Arkworks`]: the canonical compressed encoding of an `ark-groth16` proving key on the
+//!   `ark-bls12-381` curve
+//! - [`ParameterFormat::Snarkvm`]: the uncompressed encoding of a `snarkvm` Groth16 proving key, with little endian
+//!   coordinates
+//!
+//! In every format the queries are laid out as in the `bellman` parameters, where the points at infinity of the A and B
+//! queries are filtered out.
+//!
+//! The formats produced at the end of the ceremony are configured through the `NAMADA_MPC_OUTPUT_FORMATS` env
+//! variable (comma separated list), all of them by default.
+
+use crate::CoordinatorError;
+
+use bellman::groth16::Parameters;
+use bls12_381::{Bls12, G1Affine, G2Affine};
+use lazy_static::lazy_static;
+use masp_phase2::MPCParameters;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+lazy_static! {
+    pub static ref OUTPUT_FORMATS: Vec<ParameterFormat> = match std::env::var("NAMADA_MPC_OUTPUT_FORMATS") {
+        Ok(formats) => formats
+            .split(',')
+            .map(str::trim)
+            .filter(|format| !format.is_empty())
+            .map(|format| format.parse().expect("Invalid format in NAMADA_MPC_OUTPUT_FORMATS"))
+            .collect(),
+        Err(_) => ParameterFormat::ALL.to_vec(),
+    };
+}
+
+/// Size of the hash of the previous contribution at the head of a contribution file.
+const CONTRIBUTION_HASH_SIZE: usize = 64;
+
+/// Size of a coordinate of the base field of BLS12-381.
+const FQ_SIZE: usize = 48;
+
+/// Bits of the most significant byte holding the flags of the zcash encoding of a point.
+const ZCASH_FLAGS_MASK: u8 = 0b1110_0000;
+
+/// Flag of a point at infinity in the last byte of the snarkvm encoding.
+const SNARKVM_INFINITY_FLAG: u8 = 0b0100_0000;
+
+/// A serialization format of the final parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterFormat {
+    Raw,
+    Arkworks,
+    Snarkvm,
+}
+
+impl ParameterFormat {
+    pub const ALL: [ParameterFormat; 3] = [ParameterFormat::Raw, ParameterFormat::Arkworks, ParameterFormat::Snarkvm];
+
+    /// Returns the extension of the files in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ParameterFormat::Raw => "params",
+            ParameterFormat::Arkworks => "ark",
+            ParameterFormat::Snarkvm => "snarkvm",
+        }
+    }
+}
+
+impl fmt::Display for ParameterFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterFormat::Raw => write!(f, "raw"),
+            ParameterFormat::Arkworks => write!(f, "arkworks"),
+            ParameterFormat::Snarkvm => write!(f, "snarkvm"),
+        }
+    }
+}
+
+impl FromStr for ParameterFormat {
+    type Err = CoordinatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ParameterFormat::ALL
+            .iter()
+            .find(|format| format.to_string() == s)
+            .copied()
+            .ok_or_else(|| CoordinatorError::ParameterFormatInvalid(s.to_string()))
+    }
+}
+
+/// Reads the Groth16 parameters of all the circuits contained in a contribution file.
+pub fn read_parameters(contribution: &[u8]) -> Result<Vec<Parameters<Bls12>>, CoordinatorError> {
+    let mut reader = contribution
+        .get(CONTRIBUTION_HASH_SIZE..)
+        .ok_or(CoordinatorError::StorageReaderFailed)?;
+
+    // Contribution files may be padded with zeros to their expected size
+    let mut parameters = vec![];
+    while reader.iter().any(|byte| *byte != 0) {
+        parameters.push(MPCParameters::read(&mut reader, false)?.get_params().clone());
+    }
+
+    Ok(parameters)
+}
+
+/// Writes the parameters in the given format.
+pub fn write_parameters<W: Write>(
+    parameters: &Parameters<Bls12>,
+    format: ParameterFormat,
+    writer: W,
+) -> io::Result<()> {
+    match format {
+        ParameterFormat::Raw => parameters.write(writer),
+        ParameterFormat::Arkworks => write_proving_key(parameters, writer, &ArkworksEncoding),
+        ParameterFormat::Snarkvm => write_proving_key(parameters, writer, &SnarkvmEncoding),
+    }
+}
+
+/// Returns the parameters serialized in the given format.
+pub fn serialize_parameters(parameters: &Parameters<Bls12>, format: ParameterFormat) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    write_parameters(parameters, format, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Encoding of the curve points in a Groth16 proving key.
+trait PointEncoding {
+    fn write_g1<W: Write>(&self, writer: &mut W, point: &G1Affine) -> io::Result<()>;
+    fn write_g2<W: Write>(&self, writer: &mut W, point: &G2Affine) -> io::Result<()>;
+}
+
+/// Since version 0.4 `ark-bls12-381` encodes its points like zcash, which is the encoding of `bls12_381`.
+struct ArkworksEncoding;
+
+impl PointEncoding for ArkworksEncoding {
+    fn write_g1<W: Write>(&self, writer: &mut W, point: &G1Affine) -> io::Result<()> {
+        writer.write_all(&point.to_compressed())
+    }
+
+    fn write_g2<W: Write>(&self, writer: &mut W, point: &G2Affine) -> io::Result<()> {
+        writer.write_all(&point.to_compressed())
+    }
+}
+
+/// `snarkvm` encodes the coordinates in little endian, `c0` before `c1` for the quadratic extension, with the infinity
+/// flag in the last byte.
+struct SnarkvmEncoding;
+
+impl SnarkvmEncoding {
+    /// Converts the zcash uncompressed encoding of a point, whose big endian coordinates are listed in `order`.
+    fn write<W: Write>(writer: &mut W, uncompressed: &[u8], is_identity: bool, order: &[usize]) -> io::Result<()> {
+        let mut bytes = vec![0u8; uncompressed.len()];
+
+        if is_identity {
+            bytes[uncompressed.len() - 1] |= SNARKVM_INFINITY_FLAG;
+        } else {
+            let mut big_endian = uncompressed.to_vec();
+            big_endian[0] &= !ZCASH_FLAGS_MASK;
+
+            for (coordinate, index) in bytes.chunks_mut(FQ_SIZE).zip(order) {
+                coordinate.copy_from_slice(&big_endian[index * FQ_SIZE..(index + 1) * FQ_SIZE]);
+                coordinate.reverse();
+            }
+        }
+
+        writer.write_all(&bytes)
+    }
+}
+
+impl PointEncoding for SnarkvmEncoding {
+    fn write_g1<W: Write>(&self, writer: &mut W, point: &G1Affine) -> io::Result<()> {
+        // x, y
+        Self::write(writer, &point.to_uncompressed(), point.is_identity().into(), &[0, 1])
+    }
+
+    fn write_g2<W: Write>(&self, writer: &mut W, point: &G2Affine) -> io::Result<()> {
+        // x.c1, x.c0, y.c1, y.c0
+        Self::write(writer, &point.to_uncompressed(), point.is_identity().into(), &[1, 0, 3, 2])
+    }
+}
+
+/// Writes the parameters with the layout of the Groth16 proving key shared by arkworks and snarkvm, where vectors are
+/// prefixed by their length as a little endian u64.
+fn write_proving_key<W: Write, E: PointEncoding>(
+    parameters: &Parameters<Bls12>,
+    mut writer: W,
+    encoding: &E,
+) -> io::Result<()> {
+    fn write_vec<W: Write, T>(
+        writer: &mut W,
+        points: &[T],
+        mut write_point: impl FnMut(&mut W, &T) -> io::Result<()>,
+    ) -> io::Result<()> {
+        writer.write_all(&(points.len() as u64).to_le_bytes())?;
+        points.iter().try_for_each(|point| write_point(writer, point))
+    }
+
+    let vk = &parameters.vk;
+    encoding.write_g1(&mut writer, &vk.alpha_g1)?;
+    encoding.write_g2(&mut writer, &vk.beta_g2)?;
+    encoding.write_g2(&mut writer, &vk.gamma_g2)?;
+    encoding.write_g2(&mut writer, &vk.delta_g2)?;
+    write_vec(&mut writer, &vk.ic[..], |w, p| encoding.write_g1(w, p))?;
+
+    encoding.write_g1(&mut writer, &vk.beta_g1)?;
+    encoding.write_g1(&mut writer, &vk.delta_g1)?;
+    write_vec(&mut writer, &parameters.a[..], |w, p| encoding.write_g1(w, p))?;
+    write_vec(&mut writer, &parameters.b_g1[..], |w, p| encoding.write_g1(w, p))?;
+    write_vec(&mut writer, &parameters.b_g2[..], |w, p| encoding.write_g2(w, p))?;
+    write_vec(&mut writer, &parameters.h[..], |w, p| encoding.write_g1(w, p))?;
+    write_vec(&mut writer, &parameters.l[..], |w, p| encoding.write_g1(w, p))?;
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_format() {
+        for format in ParameterFormat::ALL.iter() {
+            assert_eq!(format.to_string().parse::<ParameterFormat>().unwrap(), *format);
+        }
+        assert!("bincode".parse::<ParameterFormat>().is_err());
+    }
+
+    #[test]
+    fn test_snarkvm_encoding() {
+        let mut bytes = vec![];
+        SnarkvmEncoding.write_g1(&mut bytes, &G1Affine::generator()).unwrap();
+        let uncompressed = G1Affine::generator().to_uncompressed();
+        let mut x = uncompressed[..FQ_SIZE].to_vec();
+        x.reverse();
+        assert_eq!(&bytes[..FQ_SIZE], &x[..]);
+        let mut y = uncompressed[FQ_SIZE..].to_vec();
+        y.reverse();
+        assert_eq!(&bytes[FQ_SIZE..], &y[..]);
+
+        let mut bytes = vec![];
+        SnarkvmEncoding.write_g2(&mut bytes, &G2Affine::identity()).unwrap();
+        assert_eq!(bytes.len(), 4 * FQ_SIZE);
+        assert_eq!(bytes[4 * FQ_SIZE - 1], SNARKVM_INFINITY_FLAG);
+        assert!(bytes[..4 * FQ_SIZE - 1].iter().all(|byte| *byte == 0));
+    }
+}
@@ -4,28 +4,47 @@
 
 use crate::{
     authentication::Signature,
-    commands::{Aggregation, Initialization},
+    backup::Snapshot,
+    changelog::{Changelog, SignedChangelog},
+    commands::{Aggregation, Consistency, Initialization, Rebalance, Restore},
+    conversion::{self, ParameterFormat},
     coordinator_state::{
         CeremonyStorageAction, CoordinatorState, DropParticipant, ParticipantInfo, ResetCurrentRoundStorageAction,
-        RoundMetrics, IP_BAN, TOKEN_BLACKLIST,
+        RoundMetrics, IP_BAN,
     },
     environment::{Deployment, Environment},
+    events::CeremonyEvent,
+    ip_retention::IpRetention,
     objects::{
-        participant::*, task::TaskInitializationError, ContributionFileSignature, ContributionInfo, LockedLocators,
-        Round, Task, TrimmedContributionInfo,
+        ceremony_phase, participant::*, task::TaskInitializationError, Announcement, ArtifactSizes, CeremonyMetadata,
+        CeremonyParameters, CeremonyPhase, ChallengeBundle, CircuitRegistry, ComputationProof, ComputationProofRecord,
+        ComputationStats, ContributionFileSignature, ContributionInfo, ContributionSession, DestructionAttestation,
+        GenesisSeed, GenesisSeedStatus, HashAlgorithms, HumanVerification, InjectionStatus, LifecyclePhase,
+        LockedLocators, ManualContribution, ManualInjection, OperatorApproval, ParticipantEvent, ParticipantRecord,
+        ParticipationBadge, QueueInfo, QueueShuffle, QuorumStatus, Round, SeedCommitment, SeedReveal,
+        SignedCeremonyMetadata, SignedDestructionAttestation, SignedParticipationBadge, SignedVerifierDelegation, Task,
+        TrimmedContributionInfo, VerificationPhase, VerificationQuorum, VerificationStatus, VerificationVote,
+        VerifiedIdentity, VerifierAttestation, VerifierDelegation, GENESIS_SEED_SIZE,
     },
+    prelaunch::{PrelaunchRestore, PrelaunchSnapshot, PrelaunchSummary},
+    scheduler::{UpdateLoad, UpdateScheduler},
+    startup::StartupProfile,
     storage::{
-        ContributionLocator, ContributionSignatureLocator, Disk, Locator, LocatorPath, Object, StorageAction,
-        StorageLocator, StorageObject, UpdateAction,
+        ContributionLocator, ContributionSignatureLocator, Disk, Inconsistency, Locator, LocatorPath, Object,
+        ParametersFile, RoundManifest, ShardUsage, StorageAction, StorageLocator, StorageObject, UpdateAction,
+        ANOMA_BASE_FILE_SIZE, ANOMA_PER_ROUND_FILE_SIZE_INCREASE,
     },
 };
 use setup_utils::calculate_hash;
 
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
+    convert::TryFrom,
     fmt,
     net::IpAddr,
+    path::PathBuf,
     sync::{Arc, RwLock},
+    time::Instant,
 };
 use time::OffsetDateTime;
 use tracing::*;
@@ -33,11 +52,18 @@ use tracing::*;
 #[cfg(any(test, feature = "operator"))]
 use std::collections::HashMap;
 
+#[cfg(debug_assertions)]
+use crate::chaos::ChaosConfig;
+
 #[derive(Debug)]
 pub enum CoordinatorError {
     AggregateContributionFileSizeMismatch,
+    CeremonyAlreadyLaunched,
     CeremonyIsOver,
+    CeremonyPhasesInvalid(String),
     ChallengeHashSizeInvalid,
+    ChallengeRegenerationFailed,
+    ChaosVerificationFailure,
     ChunkAlreadyComplete,
     ChunkAlreadyVerified,
     ChunkIdAlreadyAdded,
@@ -50,7 +76,9 @@ pub enum CoordinatorError {
     ChunkMissingVerification,
     ChunkCannotLockZeroContributions { chunk_id: u64 },
     ChunkNotLockedOrByWrongParticipant,
+    CircuitRegistryInvalid,
     ComputationFailed,
+    ComputationProofInvalid(String),
     CompressedContributionHashingUnsupported,
     ContributorPendingTasksCannotBeEmpty(Participant),
     ContributionAlreadyAssignedVerifiedLocator,
@@ -58,6 +86,7 @@ pub enum CoordinatorError {
     ContributionAlreadyVerified,
     ContributionFailed,
     ContributionFileSignatureLocatorAlreadyExists,
+    ContributionFileSignatureMalformed(String),
     ContributionFileSizeMismatch,
     ContributionHashMismatch,
     ContributionIdIsNonzero,
@@ -77,6 +106,8 @@ pub enum CoordinatorError {
     ContributorAlreadyContributed,
     ContributorSignatureInvalid,
     ContributorsMissing,
+    ControlKeyLast,
+    ControlKeyMissing,
     CoordinatorContributorMissing,
     CoordinatorStateNotInitialized,
     CurrentRoundAggregating,
@@ -84,10 +115,19 @@ pub enum CoordinatorError {
     CurrentRoundFinished,
     CurrentRoundNotAggregated,
     CurrentRoundNotFinished,
+    DestructionAttestationSignatureInvalid,
     DropParticipantFailed,
     ExpectedContributor,
     ExpectedVerifier,
     Error(anyhow::Error),
+    GenesisSeedAlreadyCommitted,
+    GenesisSeedAlreadyRevealed,
+    GenesisSeedCeremonyDisabled,
+    GenesisSeedCommitmentsMissing,
+    GenesisSeedInvalid,
+    GenesisSeedOperatorsMissing,
+    HashAlgorithmMismatch,
+    IdentityAlreadyUsed,
     InitializationFailed,
     InitializationTranscriptsDiffer,
     Integer(std::num::ParseIntError),
@@ -103,6 +143,8 @@ pub enum CoordinatorError {
     LocatorFileNotOpen,
     LocatorFileShouldBeOpen,
     LocatorSerializationFailed,
+    ManualContributionAlreadyInjected,
+    ManualContributionNotApproved,
     NextChallengeHashAlreadyExists,
     NextChallengeHashSizeInvalid,
     NextChallengeHashMissing,
@@ -110,9 +152,14 @@ pub enum CoordinatorError {
     NextRoundShouldBeEmpty,
     NumberOfChunksInvalid,
     NumberOfContributionsDiffer,
+    OperatorAlreadyApproved,
+    OperatorSignatureInvalid,
+    OperatorUnauthorized,
+    ParameterFormatInvalid(String),
     ParticipantAlreadyAdded,
     ParticipantAlreadyAddedChunk,
     ParticipantAlreadyBanned,
+    ParticipantAlreadyContributed { round_height: u64 },
     ParticipantAlreadyDropped,
     ParticipantAlreadyFinished,
     ParticipantAlreadyFinishedChunk { chunk_id: u64 },
@@ -136,6 +183,7 @@ pub enum CoordinatorError {
     ParticipantMissing,
     ParticipantMissingDisposingTask,
     ParticipantMissingPendingTask { pending_task: Task },
+    ParticipantNotAdmittedInPhase(String),
     ParticipantNotFound(Participant),
     ParticipantNotReady,
     ParticipantRoundHeightInvalid,
@@ -151,6 +199,7 @@ pub enum CoordinatorError {
     ParticipantWasDropped,
     PendingTasksMustContainResponseTask { response_task: Task },
     Phase2Setup(setup_utils::Error),
+    PrelaunchSnapshotMissing,
     QueueIsEmpty,
     QueueWaitTimeIncomplete,
     ResponseHashSizeInvalid,
@@ -191,6 +240,7 @@ pub enum CoordinatorError {
     StorageLocatorMissing,
     StorageLocatorNotOpen,
     StorageLockFailed,
+    StoragePathNotPortable(String),
     StorageReaderFailed,
     StorageSizeLookupFailed,
     StorageUpdateFailed,
@@ -199,8 +249,17 @@ pub enum CoordinatorError {
     TryFromSliceError(std::array::TryFromSliceError),
     UnauthorizedChunkContributor,
     UnauthorizedChunkVerifier,
+    VerificationBacklog { backlog: usize, threshold: usize },
     VerificationFailed,
+    VerificationMemoryBudgetExceeded { required: u64, available: u64 },
     VerificationOnContributionIdZero,
+    VerificationNotPending,
+    VerificationNotQuarantined,
+    VerifierAlreadyVoted,
+    VerifierCrashed,
+    VerifierDelegationExpired,
+    VerifierDelegationInvalid,
+    VerifierDelegationOutOfRounds,
     VerifierMissing,
     VerifierSignatureInvalid,
     VerifiersMissing,
@@ -266,7 +325,8 @@ impl From<CoordinatorError> for anyhow::Error {
         error!("{}", error);
         match error {
             CoordinatorError::Error(anyhow_error) => anyhow_error,
-            _ => Self::msg(error.to_string()),
+            // Kept as it is for the callers to downcast it
+            _ => Self::msg(error),
         }
     }
 }
@@ -319,6 +379,11 @@ impl MockTimeSource {
     pub fn update<F: Fn(OffsetDateTime) -> OffsetDateTime>(&self, f: F) {
         self.set_time(f(self.time()))
     }
+
+    /// Moves the current time forward by the given duration.
+    pub fn advance(&self, duration: time::Duration) {
+        self.update(|time| time + duration)
+    }
 }
 
 impl TimeSource for MockTimeSource {
@@ -337,12 +402,29 @@ pub struct Coordinator {
     signature: Arc<dyn Signature>,
     /// The storage of contributions and rounds for this coordinator.
     storage: Disk,
+    /// The circuits targeted by the contributions of the ceremony.
+    circuit_registry: CircuitRegistry,
+    /// The identity of the ceremony, displayed by the clients.
+    ceremony_metadata: CeremonyMetadata,
     /// The current round and participant self.
     state: CoordinatorState,
     /// The source of time, allows mocking system time for testing.
     time: Arc<dyn TimeSource>,
     /// Callback to call after aggregation is done
     aggregation_callback: Arc<dyn Fn(Vec<Participant>) -> () + Send + Sync>,
+    /// Callback to call on each progress of the verification of a contribution
+    verification_callback: Arc<dyn Fn(&Participant, VerificationStatus) -> () + Send + Sync>,
+    /// Callback to call on each event of the ceremony, with the time of the event
+    event_callback: Arc<dyn Fn(OffsetDateTime, CeremonyEvent) -> () + Send + Sync>,
+    /// The scheduler of the periodic updates of the coordinator
+    update_scheduler: UpdateScheduler,
+    /// The time spent in each phase of the startup of the coordinator
+    startup_profile: StartupProfile,
+    /// The time at which the coordinator booted up, to report its uptime
+    booted_at: OffsetDateTime,
+    /// The faults injected in the verification of the contributions
+    #[cfg(debug_assertions)]
+    chaos: ChaosConfig,
 }
 
 impl Coordinator {
@@ -365,21 +447,60 @@ impl Coordinator {
         signature: Arc<dyn Signature>,
         time: Arc<dyn TimeSource>,
     ) -> Result<Self, CoordinatorError> {
+        let mut startup_profile = StartupProfile::default();
         // Load an instance of storage.
-        let storage = environment.storage()?;
+        let mut storage = startup_profile.time("storage_load", || environment.storage())?;
         // Load an instance of coordinator self.
-        let state = match storage.get(&Locator::CoordinatorState)? {
+        let mut state = match startup_profile.time("state_load", || storage.get(&Locator::CoordinatorState))? {
             Object::CoordinatorState(state) => state,
             _ => return Err(CoordinatorError::StorageFailed),
         };
+        // Redact the IPs stored under another retention policy.
+        startup_profile.time("ip_redaction", || -> Result<(), CoordinatorError> {
+            let redacted_ips = state.redact_ips(environment.ip_retention());
+            if redacted_ips > 0 {
+                info!("Redacted {} IPs stored under a previous retention policy", redacted_ips);
+                state.save(&mut storage)?;
+            }
+            Ok(())
+        })?;
+        // Load the registry of the ceremony circuits.
+        let circuit_registry = startup_profile.time("circuit_registry", CircuitRegistry::load)?;
+        circuit_registry.check_chunks(environment.number_of_chunks())?;
+        // Load the identity of the ceremony from its genesis config.
+        let mut ceremony_metadata =
+            startup_profile.time("ceremony_metadata", || CeremonyMetadata::load(&circuit_registry))?;
+        if environment.is_rehearsal() {
+            ceremony_metadata.mark_rehearsal();
+        }
+        ceremony_metadata.record_hash_algorithm(environment.hash_algorithm())?;
+        // No IP is kept if they aren't banned.
+        ceremony_metadata.ip_retention = match *IP_BAN && !environment.is_rehearsal() {
+            true => Some(environment.ip_retention()),
+            false => Some(IpRetention::Discard),
+        };
+        // Initialize the scheduler of the updates with the bounds of the environment.
+        let update_scheduler = UpdateScheduler::new(
+            std::time::Duration::try_from(environment.minimum_update_interval()).unwrap_or_default(),
+            std::time::Duration::try_from(environment.maximum_update_interval()).unwrap_or_default(),
+        );
 
         Ok(Self {
             environment: environment.clone(),
             signature,
             storage,
+            circuit_registry,
+            ceremony_metadata,
             state,
+            booted_at: time.now_utc(),
             time,
             aggregation_callback: Arc::new(|_| ()),
+            verification_callback: Arc::new(|_, _| ()),
+            event_callback: Arc::new(|_, _| ()),
+            update_scheduler,
+            startup_profile,
+            #[cfg(debug_assertions)]
+            chaos: ChaosConfig::default(),
         })
     }
 
@@ -391,6 +512,42 @@ impl Coordinator {
     pub fn set_aggregation_callback(&mut self, callback: Arc<dyn Fn(Vec<Participant>) -> () + Send + Sync>) {
         self.aggregation_callback = callback;
     }
+
+    ///
+    /// Set a callback which will be called on each progress of the verification
+    /// of a contribution. The contributor who produced the contribution and the
+    /// new status of the verification will be passed to the callback as arguments
+    ///
+    pub fn set_verification_callback(
+        &mut self,
+        callback: Arc<dyn Fn(&Participant, VerificationStatus) -> () + Send + Sync>,
+    ) {
+        self.verification_callback = callback;
+    }
+
+    ///
+    /// Set a callback which will be called on each event of the ceremony: a
+    /// contributor joining the queue, starting and uploading its contribution,
+    /// the completion of a verification and the start of a new round. The
+    /// time and the event will be passed to the callback as arguments
+    ///
+    pub fn set_event_callback(&mut self, callback: Arc<dyn Fn(OffsetDateTime, CeremonyEvent) -> () + Send + Sync>) {
+        self.event_callback = callback;
+    }
+
+    /// Reports the given event of the ceremony to the event callback.
+    fn emit_event(&self, event: CeremonyEvent) {
+        (self.event_callback)(self.time.now_utc(), event);
+    }
+
+    ///
+    /// Set the rates of the faults injected by the chaos mode. Defaults to the
+    /// rates configured in the env.
+    ///
+    #[cfg(debug_assertions)]
+    pub fn set_chaos_config(&mut self, chaos: ChaosConfig) {
+        self.chaos = chaos;
+    }
 }
 
 impl Coordinator {
@@ -399,39 +556,235 @@ impl Coordinator {
     ///
     #[inline]
     pub fn initialize(&mut self) -> Result<(), CoordinatorError> {
-        // Check if the deployment is in production, that the signature scheme is secure.
-        if *self.environment.deployment() == Deployment::Production && !self.signature.is_secure() {
+        // Check if the deployment is in production, or a public rehearsal, that the signature scheme is secure.
+        let deployment = self.environment.deployment();
+        if matches!(deployment, Deployment::Production | Deployment::Rehearsal) && !self.signature.is_secure() {
             return Err(CoordinatorError::SignatureSchemeIsInsecure);
         }
 
+        // Check that the operators who derive the seed of the genesis challenge are set, if required.
+        if self.environment.genesis_seed_ceremony() && self.environment.operators().is_empty() {
+            return Err(CoordinatorError::GenesisSeedOperatorsMissing);
+        }
+
         info!("Coordinator is booting up");
         info!("{:#?}", self.environment.parameters());
 
+        // Reconcile the storage metadata with the stored objects, in case of a previous crash.
+        let start = Instant::now();
+        let unresolved = self.check_storage_consistency(true)?;
+        self.startup_profile.record("storage_consistency", start.elapsed());
+        if !unresolved.is_empty() {
+            warn!(
+                "Storage has {} inconsistencies which require manual intervention",
+                unresolved.len()
+            );
+        }
+
         // Ensure the ceremony is initialized, if it has not started yet.
         {
             // Check if the ceremony has been initialized yet.
             if Self::load_current_round_height(&self.storage).is_err() {
                 info!("Initializing ceremony");
+                let start = Instant::now();
                 let round_height = self.run_initialization(self.time.now_utc())?;
                 info!("Initialized ceremony");
 
                 // Initialize the coordinator state to round 0.
                 self.state.initialize(round_height);
                 self.save_state()?;
+                self.startup_profile.record("initialization", start.elapsed());
             }
         }
 
         // Fetch the current round height from storage. As a sanity check,
         // this call will fail if the ceremony was not initialized.
+        let start = Instant::now();
         let current_round_height = self.current_round_height()?;
+        let current_round = self.current_round()?;
+        self.startup_profile.record("round_load", start.elapsed());
+        self.startup_profile.round_height = Some(current_round_height);
 
         info!("Current round height is {}", current_round_height);
-        info!("{}", serde_json::to_string_pretty(&self.current_round()?)?);
+        info!("{}", serde_json::to_string_pretty(&current_round)?);
+        info!("{}", self.startup_profile.summary());
+        if !self.startup_profile.is_within_target() {
+            warn!(
+                "Coordinator took {} ms to boot up, above the target of {} ms",
+                self.startup_profile.total_ms, self.startup_profile.target_ms
+            );
+        }
         info!("Coordinator has booted up");
 
         Ok(())
     }
 
+    ///
+    /// Checks the consistency between the storage metadata and the stored objects,
+    /// reconciling them if `reconcile` is set.
+    ///
+    /// On success, returns the inconsistencies which could not be resolved.
+    ///
+    pub fn check_storage_consistency(&mut self, reconcile: bool) -> Result<Vec<Inconsistency>, CoordinatorError> {
+        Ok(Consistency::run(&mut self.storage, reconcile)?)
+    }
+
+    /// Returns the time spent in each phase of the startup of the coordinator.
+    pub fn startup_profile(&self) -> &StartupProfile {
+        &self.startup_profile
+    }
+
+    /// Returns the number of objects and the space used on each storage shard.
+    pub fn storage_shards(&self) -> Result<Vec<ShardUsage>, CoordinatorError> {
+        self.storage.shard_usage()
+    }
+
+    ///
+    /// Moves the objects in storage to their assigned shard.
+    ///
+    /// On success, returns the number of moved objects.
+    ///
+    pub fn rebalance_storage(&mut self) -> Result<usize, CoordinatorError> {
+        Ok(Rebalance::run(&mut self.storage)?)
+    }
+
+    ///
+    /// Returns a snapshot of the state of the coordinator, of the rounds in
+    /// storage, of their manifests and of the records of the participants,
+    /// to be saved as a backup.
+    ///
+    pub fn backup_snapshot(&self) -> Result<Snapshot, CoordinatorError> {
+        let round_height = self.current_round_height()?;
+
+        let mut rounds = vec![];
+        let mut manifests = vec![];
+        for height in 0..=round_height {
+            if self.storage.exists(&Locator::RoundState { round_height: height }) {
+                rounds.push(self.get_round(height)?);
+            }
+            match self.round_manifest(height) {
+                Ok(manifest) => manifests.push(manifest),
+                Err(CoordinatorError::RoundDoesNotExist) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Snapshot {
+            created_at: self.time.now_utc(),
+            round_height,
+            coordinator_state: serde_json::to_value(&self.state)?,
+            rounds,
+            manifests,
+            participants: self.participant_records(),
+        })
+    }
+
+    ///
+    /// Restores the state of the coordinator and of the rounds from the given
+    /// backup snapshot, before the coordinator is loaded from storage.
+    ///
+    /// On success, returns the names of the files of the manifests of the
+    /// snapshot which are missing from storage or have a different size.
+    ///
+    pub fn restore_backup(environment: &Environment, snapshot: &Snapshot) -> Result<Vec<String>, CoordinatorError> {
+        let mut storage = environment.storage()?;
+        Ok(Restore::run(&mut storage, snapshot)?)
+    }
+
+    ///
+    /// Saves a snapshot of the state of the coordinator before the launch of the
+    /// ceremony, with its configuration, the allowlist of tokens and the
+    /// reservations and registrations of the contributors in the queue, to be
+    /// restored after a dress rehearsal of the launch. Replaces the previous
+    /// pre-launch snapshot, if any.
+    ///
+    /// Returns an error if a contributor has already been assigned to a round.
+    ///
+    pub fn snapshot_prelaunch(&self) -> Result<PrelaunchSummary, CoordinatorError> {
+        if !self.state.is_prelaunch() {
+            return Err(CoordinatorError::CeremonyAlreadyLaunched);
+        }
+
+        let prelaunch = PrelaunchSnapshot {
+            snapshot: self.backup_snapshot()?,
+            allowlist: self.state.get_tokens().clone(),
+            reserved_tokens: self.state.get_current_tokens().clone(),
+            reserved_ips: self.state.get_current_ips().clone(),
+        };
+        self.storage.save_prelaunch_snapshot(&prelaunch)?;
+        info!("Saved the pre-launch snapshot at round {}", prelaunch.snapshot.round_height);
+
+        Ok(PrelaunchSummary {
+            created_at: prelaunch.snapshot.created_at.unix_timestamp(),
+            round_height: prelaunch.snapshot.round_height,
+            registrations: self.state.number_of_queue_contributors(),
+            allowlist: prelaunch.allowlist.iter().map(HashSet::len).sum(),
+        })
+    }
+
+    ///
+    /// Restores the pre-launch snapshot after a dress rehearsal of the launch.
+    ///
+    /// The rounds of the rehearsal are removed from storage, as well as the
+    /// contributions to the round of the snapshot. The initial challenges of
+    /// this round, and of the first round if produced by the initialization,
+    /// are kept. The state of the coordinator and of the rounds, the allowlist
+    /// of tokens and the reservations are then restored, and the human
+    /// verifications of the contributors recorded during the rehearsal are kept
+    /// along with their registrations.
+    ///
+    pub fn restore_prelaunch(&mut self) -> Result<PrelaunchRestore, CoordinatorError> {
+        let prelaunch = self
+            .storage
+            .prelaunch_snapshot()?
+            .ok_or(CoordinatorError::PrelaunchSnapshotMissing)?;
+        let prelaunch_round_height = prelaunch.snapshot.round_height;
+        let current_round_height = self.current_round_height()?;
+        warn!(
+            "Restoring the pre-launch snapshot of round {} at round {}",
+            prelaunch_round_height, current_round_height
+        );
+
+        // Wipe the rounds from the most recent, the next round holding the challenges produced by the verifications.
+        let mut removed_rounds = vec![];
+        let mut cleared_rounds = vec![];
+        for round_height in (prelaunch_round_height..=current_round_height + 1).rev() {
+            let keep_initial_challenges =
+                round_height == prelaunch_round_height || (prelaunch_round_height == 0 && round_height == 1);
+            if self.storage.wipe_round(round_height, keep_initial_challenges)? {
+                match keep_initial_challenges {
+                    true => cleared_rounds.push(round_height),
+                    false => removed_rounds.push(round_height),
+                }
+            }
+        }
+
+        let mismatches = Restore::run(&mut self.storage, &prelaunch.snapshot)?;
+
+        let mut state = match self.storage.get(&Locator::CoordinatorState)? {
+            Object::CoordinatorState(state) => state,
+            _ => return Err(CoordinatorError::StorageFailed),
+        };
+        state.restore_prelaunch_runtime(&prelaunch);
+        for (participant, verification) in self.state.human_verifications() {
+            state.record_human_verification(participant.clone(), verification.clone());
+        }
+        for (participant, identity) in self.state.identities() {
+            state.record_identity(participant.clone(), identity.clone());
+        }
+        self.state = state;
+        self.save_state()?;
+
+        warn!("Restored the pre-launch snapshot, removed rounds {:?}", removed_rounds);
+
+        Ok(PrelaunchRestore {
+            round_height: prelaunch_round_height,
+            removed_rounds,
+            cleared_rounds,
+            mismatches,
+        })
+    }
+
     /// Save the current state of the coordinator to storage.
     pub fn save_state(&mut self) -> Result<(), CoordinatorError> {
         self.state.save(&mut self.storage)
@@ -442,6 +795,9 @@ impl Coordinator {
     /// newly finished, dropped, or banned participants.
     ///
     pub fn update(&mut self) -> Result<(), CoordinatorError> {
+        // Apply the rules of the phase of the ceremony which started since the last update, if any.
+        self.update_ceremony_phase()?;
+
         // Process ceremony updates for the current round and queue.
         let (is_current_round_finished, is_current_round_aggregated) = {
             // Acquire the state write lock.
@@ -469,10 +825,14 @@ impl Coordinator {
             self.state.update_banned_participants()?;
             self.save_state()?;
 
-            // Update the state of the queue.
+            // Update the state of the queue, shuffled once the previous round is verified if enabled.
+            self.prepare_queue_shuffle()?;
             self.state.update_queue()?;
             self.save_state()?;
 
+            // Remind the contributors who haven't attested the destruction of their toxic waste yet.
+            self.remind_destruction_attestations()?;
+
             // Check if the current round is finished and if the current round is aggregated.
             (
                 self.state.is_current_round_finished(),
@@ -519,6 +879,25 @@ impl Coordinator {
             }
         }
 
+        // Keep the ceremony in round 0 until the operators derived the seed of the genesis challenge, if required.
+        if self.is_awaiting_genesis_seed()? {
+            info!("Waiting for the operators to reveal the seed of the genesis challenge");
+            return Ok(());
+        }
+
+        // Once the ceremony reached its maximum length, let the current contribution
+        // complete and finalize the ceremony instead of advancing to the next round.
+        if self.is_draining()? {
+            if self.number_of_current_contributors() == 0 && self.get_pending_verifications().is_empty() {
+                info!("The ceremony reached its maximum length");
+                // Return an error to force the calling task to request a graceful shutdown of the server
+                return Err(CoordinatorError::CeremonyIsOver);
+            }
+
+            info!("The ceremony reached its maximum length, waiting for the current contribution");
+            return Ok(());
+        }
+
         // Try advancing to the next round if the current round is finished,
         // the current round has been aggregated, and the precommit for
         // the next round is now ready.
@@ -535,7 +914,7 @@ impl Coordinator {
         }
 
         // If cohorts are over, shut the coordinator down
-        if self.state.get_current_cohort_index() >= self.state.get_number_of_cohorts() {
+        if self.current_cohort_index() >= self.state.get_number_of_cohorts() {
             info!("Completed all the scheduled cohorts");
             // Return an error to force the calling task to request a graceful shutdown of the server
             return Err(CoordinatorError::CeremonyIsOver);
@@ -563,6 +942,106 @@ impl Coordinator {
         Ok(())
     }
 
+    ///
+    /// Exports the final parameters of each circuit of the ceremony in the
+    /// given formats, to be called once the ceremony is over.
+    ///
+    /// The final parameters are those of the last verified contribution, which
+    /// is the challenge of the round following the current one if it has not
+    /// been started yet. The exported files are stored in the directory of that
+    /// round and listed, with their checksums, in its transcript manifest.
+    ///
+    pub fn export_parameters(&mut self, formats: &[ParameterFormat]) -> Result<Vec<ParametersFile>, CoordinatorError> {
+        let current_round_height = self.current_round_height()?;
+        let final_challenge = |round_height: u64, chunk_id: u64| {
+            Locator::ContributionFile(ContributionLocator::new(round_height, chunk_id, 0, true))
+        };
+        let round_height = match self.storage.exists(&final_challenge(current_round_height + 1, 0)) {
+            true => current_round_height + 1,
+            false => current_round_height,
+        };
+        info!("Exporting the final parameters of round {} as {:?}", round_height, formats);
+
+        let mut chunk_ids: Vec<u64> = self.circuit_registry.circuits().iter().map(|c| c.chunk_id).collect();
+        chunk_ids.dedup();
+
+        for chunk_id in chunk_ids {
+            let contribution = self.storage.reader(&final_challenge(round_height, chunk_id))?;
+            let parameters = conversion::read_parameters(contribution.as_ref())?;
+
+            for circuit in self.circuit_registry.circuits().iter().filter(|c| c.chunk_id == chunk_id) {
+                let circuit_parameters = parameters.get(circuit.position as usize).ok_or_else(|| {
+                    error!("Parameters of circuit {} are missing", circuit.id);
+                    CoordinatorError::CircuitRegistryInvalid
+                })?;
+
+                for format in formats {
+                    let locator = Locator::ParametersFile {
+                        round_height,
+                        chunk_id,
+                        position: circuit.position,
+                        format: *format,
+                    };
+                    let object = Object::ParametersFile(conversion::serialize_parameters(circuit_parameters, *format)?);
+
+                    match self.storage.exists(&locator) {
+                        true => self.storage.update(&locator, object)?,
+                        false => self.storage.insert(locator, object)?,
+                    }
+                    debug!("Exported the parameters of circuit {} as {}", circuit.id, format);
+                }
+            }
+        }
+
+        self.exported_parameters(round_height)
+    }
+
+    ///
+    /// Returns the final parameters exported in the given round, if any.
+    ///
+    pub fn exported_parameters(&self, round_height: u64) -> Result<Vec<ParametersFile>, CoordinatorError> {
+        let mut parameters = vec![];
+        for circuit in self.circuit_registry.circuits() {
+            for format in ParameterFormat::ALL.iter() {
+                let locator = Locator::ParametersFile {
+                    round_height,
+                    chunk_id: circuit.chunk_id,
+                    position: circuit.position,
+                    format: *format,
+                };
+
+                if self.storage.exists(&locator) {
+                    parameters.push(ParametersFile {
+                        circuit: circuit.id.clone(),
+                        format: *format,
+                        file: self.storage.transcript_file(&locator)?,
+                    });
+                }
+            }
+        }
+
+        Ok(parameters)
+    }
+
+    ///
+    /// Returns the manifest of the transcript of the given round, with the
+    /// votes of the verifiers and the final parameters exported in the round.
+    ///
+    pub fn round_manifest(&self, round_height: u64) -> Result<RoundManifest, CoordinatorError> {
+        let mut manifest = self.storage.round_manifest(round_height)?;
+        manifest.verifications = self.state.verification_quorums(round_height);
+        manifest.injections = self.state.manual_injections(round_height);
+        manifest.attestations = self.state.verifier_attestations(round_height);
+        manifest.computation_proofs = self.state.computation_proofs(round_height);
+        manifest.parameters = self.exported_parameters(round_height)?;
+        manifest.rehearsal = self.environment.is_rehearsal();
+        if round_height == 0 && self.state.genesis_seed().seed.is_some() {
+            manifest.genesis_seed = Some(self.state.genesis_seed().clone());
+        }
+
+        Ok(manifest)
+    }
+
     ///
     /// Updates the set of tokens for the ceremony
     ///
@@ -586,11 +1065,38 @@ impl Coordinator {
         self.state.number_of_queue_contributors()
     }
 
+    ///
+    /// Returns the position of a queued contributor and the number of
+    /// contributors in the queue, in constant time.
+    ///
+    #[inline]
+    pub fn queue_position(&self, participant: &Participant) -> Option<(u64, u64)> {
+        self.state.queue_position(participant)
+    }
+
+    ///
+    /// Returns the earliest time at which the given participant, either in
+    /// the round or in the queue, exceeds one of its timeouts.
+    ///
+    #[inline]
+    pub fn participant_timeout(&self, participant: &Participant) -> Option<OffsetDateTime> {
+        self.state.participant_timeout(participant)
+    }
+
+    ///
+    /// Returns the number of contributions completed since the start of
+    /// the ceremony.
+    ///
+    #[inline]
+    pub fn number_of_finished_contributors(&self) -> usize {
+        self.state.number_of_finished_contributors()
+    }
+
     ///
     /// Returns a list of the contributors currently in the queue.
     ///
     #[inline]
-    pub fn queue_contributors(&self) -> Vec<(Participant, (u8, Option<u64>, OffsetDateTime, OffsetDateTime))> {
+    pub fn queue_contributors(&self) -> Vec<(Participant, QueueInfo)> {
         self.state.queue_contributors()
     }
 
@@ -602,6 +1108,24 @@ impl Coordinator {
         self.state.current_contributors()
     }
 
+    ///
+    /// Returns the contributors currently in the round, without copying
+    /// them.
+    ///
+    #[inline]
+    pub fn current_contributor_ids(&self) -> impl Iterator<Item = &Participant> {
+        self.state.current_contributor_ids()
+    }
+
+    ///
+    /// Returns the number of contributors currently in the round, without
+    /// copying them.
+    ///
+    #[inline]
+    pub fn number_of_current_contributors(&self) -> usize {
+        self.state.number_of_current_contributors()
+    }
+
     ///
     /// Returns a list of participants that were dropped from the current round.
     ///
@@ -610,6 +1134,14 @@ impl Coordinator {
         self.state.dropped_participants()
     }
 
+    ///
+    /// Returns the records of all the participants ever seen by the coordinator, with their final disposition.
+    ///
+    #[inline]
+    pub fn participant_records(&self) -> Vec<ParticipantRecord> {
+        self.state.participant_records()
+    }
+
     ///
     /// Returns the metrics for the current round and current round participants.
     ///
@@ -618,6 +1150,37 @@ impl Coordinator {
         self.state.current_round_metrics()
     }
 
+    ///
+    /// Computes the interval to wait before the next periodic update of the
+    /// coordinator, given the contributions pending verification and the
+    /// time left before the next participant times out.
+    ///
+    pub fn schedule_next_update(&mut self) -> std::time::Duration {
+        let now = self.time.now_utc();
+        let load = UpdateLoad {
+            pending_verifications: self.state.get_pending_verifications().len(),
+            // Expired timeouts yield a zero duration, to be checked as soon as possible
+            next_timeout: self
+                .state
+                .next_timeout()
+                .into_iter()
+                .chain(self.next_ceremony_phase().map(|phase| phase.start))
+                .min()
+                .map(|timeout| std::time::Duration::try_from(timeout - now).unwrap_or_default()),
+        };
+
+        self.update_scheduler.schedule(&load)
+    }
+
+    ///
+    /// Returns the interval between the periodic updates of the coordinator
+    /// last computed by [Coordinator::schedule_next_update].
+    ///
+    #[inline]
+    pub fn update_interval(&self) -> std::time::Duration {
+        self.update_scheduler.interval()
+    }
+
     ///
     /// Adds the given participant to the queue if they are permitted to participate.
     ///
@@ -629,9 +1192,28 @@ impl Coordinator {
         token: String,
         reliability_score: u8,
     ) -> Result<(), CoordinatorError> {
+        // Stop accepting contributors once the ceremony reached its maximum length.
+        if self.is_draining()? {
+            return Err(CoordinatorError::CeremonyIsOver);
+        }
+
+        // A contributor retrying its request to join the queue, e.g. after a timeout, is left at its position. Requests
+        // racing with the first one pass the checks of the rest api before the contributor is queued.
+        if self.state.is_queue_retry(&participant, &token) {
+            return Ok(());
+        }
+        if self.state.is_queue_contributor(&participant) {
+            return Err(CoordinatorError::ParticipantAlreadyAdded);
+        }
+        if let Some(phase) = self.ceremony_phase() {
+            if !phase.admits(&participant.address()) {
+                return Err(CoordinatorError::ParticipantNotAdmittedInPhase(phase.name.clone()));
+            }
+        }
+
         // Attempt to add the participant to the next round.
         self.state.add_to_queue(
-            participant,
+            participant.clone(),
             participant_ip,
             token,
             reliability_score,
@@ -641,76 +1223,245 @@ impl Coordinator {
         // Save the coordinator state in storage.
         self.save_state()?;
 
+        self.emit_event(CeremonyEvent::Joined { participant });
+
         Ok(())
     }
 
     ///
-    /// Removes the given participant from the queue if they are in the queue.
+    /// Records the outcome of the human verification of a participant joining the queue, to be kept in its
+    /// participant info once assigned to a round.
     ///
-    #[inline]
-    pub fn remove_from_queue(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
-        // Attempt to remove the participant from the next round.
-        self.state.remove_from_queue(participant)?;
+    pub fn record_human_verification(
+        &mut self,
+        participant: Participant,
+        verification: HumanVerification,
+    ) -> Result<(), CoordinatorError> {
+        self.state.record_human_verification(participant, verification);
 
         // Save the coordinator state in storage.
-        self.save_state()?;
-
-        Ok(())
+        self.save_state()
     }
 
     ///
-    /// Drops the given participant from the ceremony.
+    /// Records the identity validated for a participant joining the queue, to be kept in its participant info once
+    /// assigned to a round.
     ///
-    #[tracing::instrument(
-        skip(self, participant),
-        fields(participant = %participant)
-    )]
-    pub fn drop_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
-        // Drop the participant from the ceremony.
-        let drop = self.state.drop_participant(participant, self.time.as_ref())?;
-
-        // Update the round to reflect the coordinator state change.
-        self.drop_participant_from_storage(&drop)?;
+    pub fn record_identity(
+        &mut self,
+        participant: Participant,
+        identity: VerifiedIdentity,
+    ) -> Result<(), CoordinatorError> {
+        self.state.record_identity(participant, identity);
 
         // Save the coordinator state in storage.
-        self.save_state()?;
+        self.save_state()
+    }
 
-        Ok(())
+    ///
+    /// Returns the time at which the ceremony reaches its maximum duration,
+    /// or [`None`] if its duration is unlimited.
+    ///
+    pub fn ceremony_deadline(&self) -> Option<OffsetDateTime> {
+        self.environment
+            .max_ceremony_duration()
+            .map(|duration| self.state.ceremony_start_time() + duration)
     }
 
     ///
-    /// Bans the given participant from the ceremony.
+    /// Returns `true` if the ceremony reached its maximum number of rounds or
+    /// its maximum duration. The coordinator then stops accepting contributors
+    /// and finalizes the ceremony once the current contribution completes.
     ///
-    #[inline]
-    pub fn ban_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
-        // Ban the participant from the ceremony.
-        let drop = self.state.ban_participant(participant, self.time.as_ref())?;
+    pub fn is_draining(&self) -> Result<bool, CoordinatorError> {
+        if let Some(max_rounds) = self.environment.max_rounds() {
+            if self.current_round_height()? >= max_rounds {
+                return Ok(true);
+            }
+        }
 
-        // Update the round on disk to reflect the coordinator state change.
-        self.drop_participant_from_storage(&drop)?;
+        Ok(matches!(self.ceremony_deadline(), Some(deadline) if self.time.now_utc() >= deadline))
+    }
 
-        // Save the coordinator state in storage.
-        self.save_state()?;
+    ///
+    /// Returns the phase of the lifecycle of the ceremony. The ceremony is over
+    /// once all the cohorts are completed, or once the current contribution
+    /// completed after it reached its maximum length.
+    ///
+    pub fn lifecycle_phase(&self) -> Result<LifecyclePhase, CoordinatorError> {
+        if self.current_cohort_index() >= self.state.get_number_of_cohorts() {
+            return Ok(LifecyclePhase::Over);
+        }
+        if self.is_draining()? {
+            return match self.number_of_current_contributors() == 0 && self.get_pending_verifications().is_empty() {
+                true => Ok(LifecyclePhase::Over),
+                false => Ok(LifecyclePhase::Draining),
+            };
+        }
 
-        Ok(())
+        match self.state.is_prelaunch() {
+            true => Ok(LifecyclePhase::Prelaunch),
+            false => Ok(LifecyclePhase::Running),
+        }
     }
 
     ///
-    /// Unbans the given participant from joining the queue.
+    /// Returns the current phase of the schedule of the ceremony, if any.
     ///
-    #[inline]
-    pub fn unban_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
-        // Unban the participant from the ceremony.
-        self.state.unban_participant(participant);
+    pub fn ceremony_phase(&self) -> Option<&CeremonyPhase> {
+        let index = ceremony_phase::active_phase(&self.ceremony_metadata.phases, self.time.now_utc())?;
 
-        // Save the coordinator state in storage.
-        self.save_state()?;
+        Some(&self.ceremony_metadata.phases[index])
+    }
 
-        Ok(())
+    ///
+    /// Returns the next phase of the schedule of the ceremony, if any.
+    ///
+    fn next_ceremony_phase(&self) -> Option<&CeremonyPhase> {
+        let now = self.time.now_utc();
+
+        self.ceremony_metadata.phases.iter().find(|phase| phase.start > now)
     }
 
     ///
-    /// Returns `true` if the manual lock for transitioning to the next round is enabled.
+    /// Switches to the phase of the schedule started since the last update,
+    /// applying its timeouts to the participants and announcing it.
+    ///
+    fn update_ceremony_phase(&mut self) -> Result<(), CoordinatorError> {
+        let (name, timeouts) = match self.ceremony_phase() {
+            Some(phase) if self.state.ceremony_phase() != Some(phase.name.as_str()) => {
+                (phase.name.clone(), phase.timeouts.clone())
+            }
+            _ => return Ok(()),
+        };
+
+        info!("Ceremony phase {} started", name);
+        self.state.set_ceremony_phase(name.clone(), timeouts);
+        self.save_state()?;
+        self.emit_event(CeremonyEvent::PhaseStarted { phase: name });
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the announcements of the coordinator to the participants: the
+    /// current phase of the ceremony and the upcoming one, the approaching end
+    /// of the ceremony during its final window, and its finalization once it
+    /// reached its maximum length.
+    ///
+    pub fn announcements(&self) -> Result<Vec<Announcement>, CoordinatorError> {
+        if self.is_draining()? {
+            return Ok(vec![Announcement::warning(
+                "The ceremony reached its maximum length and is being finalized, no more contributors are accepted",
+            )]);
+        }
+
+        let mut announcements = Vec::new();
+        if let Some(announcement) = self.ceremony_phase().and_then(|phase| phase.announcement.as_ref()) {
+            announcements.push(Announcement::info(announcement.clone()));
+        }
+        if let Some(phase) = self.next_ceremony_phase() {
+            if phase.start - self.time.now_utc() <= self.environment.final_window() {
+                announcements.push(Announcement::info(format!(
+                    "The {} phase of the ceremony starts at {}",
+                    phase.name, phase.start
+                )));
+            }
+        }
+        if let Some(deadline) = self.ceremony_deadline() {
+            if deadline - self.time.now_utc() <= self.environment.final_window() {
+                announcements.push(Announcement::warning(format!(
+                    "The ceremony ends at {}, the contributors still in the queue won't be able to contribute",
+                    deadline
+                )));
+            }
+        }
+        if let Some(max_rounds) = self.environment.max_rounds() {
+            let remaining_rounds = max_rounds - self.current_round_height()?;
+            if remaining_rounds <= self.number_of_queue_contributors() as u64 {
+                announcements.push(Announcement::warning(format!(
+                    "The ceremony ends after round {}, only the first {} contributors in the queue will contribute",
+                    max_rounds, remaining_rounds
+                )));
+            }
+        }
+
+        Ok(announcements)
+    }
+
+    ///
+    /// Removes the given participant from the queue if they are in the queue.
+    ///
+    #[inline]
+    pub fn remove_from_queue(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
+        // Attempt to remove the participant from the next round.
+        self.state.remove_from_queue(participant)?;
+        self.state
+            .record_participant_event(participant, ParticipantEvent::Withdrew, self.time.as_ref());
+
+        // Save the coordinator state in storage.
+        self.save_state()?;
+
+        Ok(())
+    }
+
+    ///
+    /// Drops the given participant from the ceremony.
+    ///
+    #[tracing::instrument(
+        skip(self, participant),
+        fields(participant = %participant)
+    )]
+    pub fn drop_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
+        // Drop the participant from the ceremony.
+        let drop = self.state.drop_participant(participant, self.time.as_ref())?;
+        self.state
+            .record_participant_event(participant, ParticipantEvent::Dropped, self.time.as_ref());
+
+        // Update the round to reflect the coordinator state change.
+        self.drop_participant_from_storage(&drop)?;
+
+        // Save the coordinator state in storage.
+        self.save_state()?;
+
+        Ok(())
+    }
+
+    ///
+    /// Bans the given participant from the ceremony.
+    ///
+    #[inline]
+    pub fn ban_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
+        // Ban the participant from the ceremony.
+        let drop = self.state.ban_participant(participant, self.time.as_ref())?;
+
+        // Update the round on disk to reflect the coordinator state change.
+        self.drop_participant_from_storage(&drop)?;
+
+        // Save the coordinator state in storage.
+        self.save_state()?;
+
+        Ok(())
+    }
+
+    ///
+    /// Unbans the given participant from joining the queue.
+    ///
+    #[inline]
+    pub fn unban_participant(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
+        // Unban the participant from the ceremony.
+        self.state.unban_participant(participant);
+        self.state
+            .record_participant_event(participant, ParticipantEvent::Unbanned, self.time.as_ref());
+
+        // Save the coordinator state in storage.
+        self.save_state()?;
+
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if the manual lock for transitioning to the next round is enabled.
     ///
     #[inline]
     pub fn is_manual_lock_enabled(&self) -> bool {
@@ -755,6 +1506,12 @@ impl Coordinator {
     ///
     #[inline]
     pub fn is_current_contributor(&self, participant: &Participant) -> bool {
+        // Check that the participant is a current contributor. Checked first, in memory, so that the round is only
+        // read from storage for the few current contributors.
+        if !self.state.is_current_contributor(participant) {
+            return false;
+        }
+
         // Fetch the current round from storage.
         let round = match Self::load_current_round(&self.storage) {
             // Case 1 - This is a typical round of the ceremony.
@@ -764,12 +1521,7 @@ impl Coordinator {
         };
 
         // Check that the participant is a contributor for the given round height.
-        if !round.is_contributor(participant) {
-            return false;
-        }
-
-        // Check that the participant is a current contributor.
-        self.state.is_current_contributor(participant)
+        round.is_contributor(participant)
     }
 
     ///
@@ -899,6 +1651,178 @@ impl Coordinator {
         self.state.heartbeat(participant, self.time.as_ref())
     }
 
+    ///
+    /// Switches the given contributor, which must hold a lock, to the offline
+    /// flow with relaxed timeouts and returns the bundle describing the challenge
+    /// to compute on its air-gapped machine.
+    ///
+    pub fn start_offline_contribution(
+        &mut self,
+        participant: &Participant,
+    ) -> Result<ChallengeBundle, CoordinatorError> {
+        self.state.start_offline_contribution(participant, self.time.as_ref())?;
+        self.save_state()?;
+
+        self.challenge_bundle(participant)
+    }
+
+    ///
+    /// Returns the challenge bundle of the given contributor computing offline.
+    ///
+    pub fn challenge_bundle(&self, participant: &Participant) -> Result<ChallengeBundle, CoordinatorError> {
+        let (chunk_id, deadline) = self
+            .state
+            .offline_contribution_lock(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+
+        let round = self.current_round()?;
+        let chunk = round.chunk(chunk_id)?;
+        let challenge_locator = Locator::ContributionFile(ContributionLocator::new(
+            round.round_height(),
+            chunk_id,
+            chunk.current_contribution_id(),
+            true,
+        ));
+        let challenge_reader = self.storage.reader(&challenge_locator)?;
+        let challenge_hash = self.environment.contribution_hasher().hash(challenge_reader.as_ref());
+
+        Ok(ChallengeBundle {
+            round_height: round.round_height(),
+            chunk_id,
+            contribution_id: chunk.next_contribution_id(round.expected_number_of_contributions())?,
+            challenge_hash: hex::encode(challenge_hash),
+            deadline,
+        })
+    }
+
+    ///
+    /// Returns the locator of the challenge of the chunk locked by the given
+    /// contributor, checking that the requested round is the current one.
+    /// Contributors can only download the challenge of the chunk they locked.
+    ///
+    pub fn locked_challenge(
+        &self,
+        participant: &Participant,
+        round_height: u64,
+    ) -> Result<ContributionLocator, CoordinatorError> {
+        let (chunk_id, _) = self
+            .state
+            .contribution_lock(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+
+        let round = self.current_round()?;
+        if round.round_height() != round_height {
+            warn!(
+                "{} requested the challenge of round {} during round {}",
+                participant,
+                round_height,
+                round.round_height()
+            );
+            return Err(CoordinatorError::RoundHeightMismatch);
+        }
+
+        Ok(round.locked_locators(chunk_id, participant)?.current_contribution())
+    }
+
+    ///
+    /// Returns the hex encoded hash of the challenge of the chunk locked by
+    /// the given contributor, from which its proof of computation time starts.
+    ///
+    pub fn locked_challenge_hash(
+        &self,
+        participant: &Participant,
+        round_height: u64,
+    ) -> Result<String, CoordinatorError> {
+        let challenge_locator = self.locked_challenge(participant, round_height)?;
+        let challenge_reader = self.storage.reader(&Locator::ContributionFile(challenge_locator))?;
+
+        Ok(hex::encode(
+            self.environment.contribution_hasher().hash(challenge_reader.as_ref()),
+        ))
+    }
+
+    ///
+    /// Records the proof of computation time of the contribution of the given
+    /// contributor, already checked against the challenge of
+    /// [`Coordinator::locked_challenge_hash`], to be published in the
+    /// manifest of the round. The contributor must still hold its lock.
+    ///
+    pub fn add_computation_proof(
+        &mut self,
+        participant: &Participant,
+        round_height: u64,
+        proof: ComputationProof,
+    ) -> Result<(), CoordinatorError> {
+        self.locked_challenge(participant, round_height)?;
+
+        info!(
+            "{} proved {} iterations of computation in round {}",
+            participant,
+            proof.iterations(),
+            round_height
+        );
+        self.state.add_computation_proof(ComputationProofRecord {
+            round_height,
+            contributor: participant.address(),
+            proof,
+            received_at: self.time.now_utc(),
+        });
+        self.save_state()
+    }
+
+    ///
+    /// Returns the statistics of the time spent computing the contributions,
+    /// flagging the anomalously fast ones.
+    ///
+    #[inline]
+    pub fn computation_stats(&self) -> ComputationStats {
+        self.state.computation_stats()
+    }
+
+    ///
+    /// Resumes the contribution of the given contributor, which must still hold
+    /// its lock, after a crash of its client. The contributor is marked as seen
+    /// and the returned session describes the locked files, so that the
+    /// contribution can be completed from another machine with the same keypair.
+    ///
+    pub fn resume_session(&mut self, participant: &Participant) -> Result<ContributionSession, CoordinatorError> {
+        let (chunk_id, deadline) = self
+            .state
+            .contribution_lock(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+
+        let round = self.current_round()?;
+        let locked_locators = round
+            .locked_locators(chunk_id, participant)?
+            .with_circuits(self.circuit_registry.circuits_for_chunk(chunk_id));
+        let locked_locators = self.with_artifact_sizes(locked_locators)?;
+        let challenge_bundle = match self.is_offline_contributor(participant) {
+            true => Some(self.challenge_bundle(participant)?),
+            false => None,
+        };
+
+        self.heartbeat(participant)?;
+        self.save_state()?;
+
+        info!("{} resumed the contribution to chunk {}", participant, chunk_id);
+        Ok(ContributionSession {
+            round_height: round.round_height(),
+            chunk_id,
+            locked_locators,
+            deadline,
+            challenge_bundle,
+            contribution_uploaded: false,
+            signature_uploaded: false,
+        })
+    }
+
+    ///
+    /// Returns `true` if the given current contributor is computing its contribution offline.
+    ///
+    pub fn is_offline_contributor(&self, participant: &Participant) -> bool {
+        self.state.is_offline_contributor(participant)
+    }
+
     ///
     /// Attempts to acquire the lock to a chunk for the given participant.
     ///
@@ -920,6 +1844,12 @@ impl Coordinator {
             return Err(CoordinatorError::ParticipantUnauthorized);
         }
 
+        // Check that neither the participant nor its identity contributed in another round since it joined the queue.
+        self.state.unique_contribution_checks(
+            participant,
+            self.state.identity(participant).map(|identity| &identity.claim),
+        )?;
+
         // Check that the current round is not yet finished.
         if self.state.is_current_round_finished() {
             return Err(CoordinatorError::CurrentRoundFinished);
@@ -935,6 +1865,14 @@ impl Coordinator {
             return Err(CoordinatorError::CurrentRoundAggregated);
         }
 
+        // Check that the verifications keep up with the contributions, no new chunk is locked until they catch up.
+        if let Some(threshold) = self.environment.verification_backlog_threshold() {
+            let backlog = self.verification_backlog();
+            if backlog > threshold {
+                return Err(CoordinatorError::VerificationBacklog { backlog, threshold });
+            }
+        }
+
         // Attempt to fetch the next chunk ID and contribution ID for the given participant.
         let current_task = self.state.fetch_task(participant, self.time.as_ref())?;
         trace!("Fetched task {} for {}", current_task, participant);
@@ -955,9 +1893,18 @@ impl Coordinator {
                 self.state
                     .acquired_lock(participant, current_task.chunk_id(), self.time.as_ref())?;
 
+                // The contributor restarted its contribution on the reissued challenge.
+                self.state.restarted_contribution(participant);
+
                 // Save the coordinator state in storage.
                 self.save_state()?;
 
+                self.emit_event(CeremonyEvent::TurnStarted {
+                    participant: participant.clone(),
+                    round_height: round.round_height(),
+                    chunk_id: current_task.chunk_id(),
+                });
+
                 info!("Acquired lock on chunk {} for {}", current_task.chunk_id(), participant);
                 Ok((current_task.chunk_id(), locked_locators))
             }
@@ -1076,11 +2023,11 @@ impl Coordinator {
             self.storage.remove(&Locator::ContributionFile(response.clone()))?;
 
             // Blacklist participant's token and ip
-            if *TOKEN_BLACKLIST {
+            if self.state.token_blacklist() {
                 self.state.blacklist_participant_token(participant)?;
             }
 
-            if *IP_BAN {
+            if self.state.ip_ban() {
                 self.state.blacklist_participant_ip(participant)?;
             }
 
@@ -1120,17 +2067,24 @@ impl Coordinator {
                         .completed_task(participant, &completed_task, self.time.as_ref())?;
 
                     // Blacklist participant's token and ip
-                    if *TOKEN_BLACKLIST {
+                    if self.state.token_blacklist() {
                         self.state.blacklist_participant_token(participant)?;
                     }
 
-                    if *IP_BAN {
+                    if self.state.ip_ban() {
                         self.state.blacklist_participant_ip(participant)?;
                     }
 
                     // Save the coordinator state in storage.
                     self.save_state()?;
 
+                    (self.verification_callback)(participant, VerificationStatus::Pending);
+                    self.emit_event(CeremonyEvent::Uploaded {
+                        participant: participant.clone(),
+                        round_height,
+                        chunk_id,
+                    });
+
                     info!("Added contribution");
                     return Ok(locator);
                 }
@@ -1481,12 +2435,31 @@ impl Coordinator {
         ) {
             Ok(_) => {
                 debug!("{} acquired lock on chunk {}", participant, chunk_id);
-                Ok(locked_locators)
+                let circuits = self.circuit_registry.circuits_for_chunk(chunk_id);
+                self.with_artifact_sizes(locked_locators.with_circuits(circuits))
             }
             _ => Err(CoordinatorError::StorageUpdateFailed),
         }
     }
 
+    /// Sets the size of the challenge in storage and the expected sizes of the contribution and its signature in the
+    /// given locked locators.
+    fn with_artifact_sizes(&self, locked_locators: LockedLocators) -> Result<LockedLocators, CoordinatorError> {
+        let next_contribution = locked_locators.next_contribution();
+        let sizes = ArtifactSizes {
+            challenge: self
+                .storage
+                .size(&Locator::ContributionFile(locked_locators.current_contribution()))?,
+            contribution: Object::anoma_contribution_file_size(
+                next_contribution.round_height(),
+                next_contribution.contribution_id(),
+            ),
+            contribution_file_signature: Object::contribution_file_signature_size(false),
+        };
+
+        Ok(locked_locators.with_sizes(sizes))
+    }
+
     ///
     /// Attempts to add a contribution for a given chunk ID from a given participant.
     ///
@@ -1555,7 +2528,7 @@ impl Coordinator {
         let (challenge_hash, response_hash) = {
             // Compute the challenge hash using the challenge file.
             let challenge_reader = self.storage.reader(&challenge_file_locator)?;
-            let challenge_hash = calculate_hash(challenge_reader.as_ref());
+            let challenge_hash = self.environment.contribution_hasher().hash(challenge_reader.as_ref());
             info!(
                 "Challenge is located in {}",
                 self.storage.to_path(&challenge_file_locator)?
@@ -1566,7 +2539,7 @@ impl Coordinator {
 
             // Compute the response hash.
             let response_reader = self.storage.reader(&Locator::ContributionFile(response_file_locator))?;
-            let response_hash = calculate_hash(response_reader.as_ref());
+            let response_hash = self.environment.contribution_hasher().hash(response_reader.as_ref());
             info!(
                 "Response is located in {}",
                 self.storage
@@ -1659,6 +2632,8 @@ impl Coordinator {
         }
     }
 
+    /// Reads the given challenge while borrowing the coordinator, unlike [`Coordinator::open_challenge`].
+    #[cfg(test)]
     #[inline]
     pub(crate) fn get_challenge(
         &self,
@@ -1667,36 +2642,128 @@ impl Coordinator {
         contribution_id: u64,
         is_verified: bool,
     ) -> Result<Vec<u8>, CoordinatorError> {
-        // Fetch the challenge, response, and contribution file signature locators.
-        let challenge_file_locator = Locator::ContributionFile(ContributionLocator::new(
-            round_height,
-            chunk_id,
-            contribution_id,
-            is_verified,
-        ));
-        // Get the challenge from the challenge file locator
-        let challenge_reader = self.storage.reader(&challenge_file_locator)?;
+        let challenge_locator = ContributionLocator::new(round_height, chunk_id, contribution_id, is_verified);
+        let (_, file) = self.open_challenge(&challenge_locator)?;
+
+        Disk::read_contribution_file(file, &challenge_locator)
+    }
 
-        Ok(challenge_reader.to_vec())
+    /// Opens the given challenge in storage, with its path, to read it with [`Disk::read_contribution_file`] once the
+    /// lock on the coordinator is released, so that serving a challenge of several gigabytes doesn't block the
+    /// updates of the state. A challenge reissued or regenerated in the meantime is moved in place of the opened one,
+    /// which is read as it was when opened.
+    pub(crate) fn open_challenge(
+        &self,
+        challenge_locator: &ContributionLocator,
+    ) -> Result<(PathBuf, std::fs::File), CoordinatorError> {
+        self.storage.open_object(&Locator::ContributionFile(*challenge_locator))
     }
 
-    /// Writes the bytes of a contribution to storage at the appropriate file
-    /// locator.
+    /// Writes the bytes of a contribution and its file signature to storage at
+    /// the appropriate locators, in a single transaction. Signature of a contribution
+    /// is computed client-side, so there's no way to use the provided
+    /// write_contribution_file_signature function.
     pub(crate) fn write_contribution<T>(
         &mut self,
         contribution_locator: ContributionLocator,
         contribution: T,
+        signature_locator: ContributionSignatureLocator,
+        contribution_file_signature: ContributionFileSignature,
     ) -> Result<(), CoordinatorError>
     where
         T: Into<Vec<u8>>,
     {
-        // Can use update instead of insert because the path is already initialized by other functions
-        self.storage.update(
+        contribution_file_signature.validate(&self.signature.name())?;
+        // Record the post-quantum scheme of the signature in the transcript
+        let contribution_file_signature = contribution_file_signature.with_post_quantum_scheme();
+
+        // The paths are already initialized by other functions
+        self.storage.write_all_or_nothing(&[
+            (
+                Locator::ContributionFile(contribution_locator),
+                Object::ContributionFile(contribution.into()),
+            ),
+            (
+                Locator::ContributionFileSignature(signature_locator),
+                Object::ContributionFileSignature(contribution_file_signature),
+            ),
+        ])
+    }
+
+    /// Opens the challenge locked by the given contributor in the given round, as [`Coordinator::locked_challenge`], to
+    /// stream it from storage.
+    pub(crate) fn open_locked_challenge(
+        &self,
+        participant: &Participant,
+        round_height: u64,
+    ) -> Result<(PathBuf, std::fs::File), CoordinatorError> {
+        let challenge_locator = self.locked_challenge(participant, round_height)?;
+
+        self.open_challenge(&challenge_locator)
+    }
+
+    /// Returns the locator of the next contribution of the given current contributor, with the file to which the
+    /// contribution can be spooled while streamed from a request.
+    pub(crate) fn contribution_spool(
+        &self,
+        participant: &Participant,
+    ) -> Result<(ContributionLocator, PathBuf), CoordinatorError> {
+        let (chunk_id, _) = self
+            .state
+            .contribution_lock(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+        let contribution_locator = self
+            .current_round()?
+            .locked_locators(chunk_id, participant)?
+            .next_contribution();
+        let spool_path = self
+            .storage
+            .spool_path(&Locator::ContributionFile(contribution_locator))?;
+
+        Ok((contribution_locator, spool_path))
+    }
+
+    /// Moves the contribution spooled at [`Coordinator::contribution_spool`] in place and writes
+    /// its file signature to storage, in a single transaction.
+    pub(crate) fn write_spooled_contribution(
+        &mut self,
+        contribution_locator: ContributionLocator,
+        signature_locator: ContributionSignatureLocator,
+        contribution_file_signature: ContributionFileSignature,
+    ) -> Result<(), CoordinatorError> {
+        contribution_file_signature.validate(&self.signature.name())?;
+        // Record the post-quantum scheme of the signature in the transcript
+        let contribution_file_signature = contribution_file_signature.with_post_quantum_scheme();
+
+        self.storage.write_spooled(
             &Locator::ContributionFile(contribution_locator),
-            Object::ContributionFile(contribution.into()),
+            &[(
+                Locator::ContributionFileSignature(signature_locator),
+                Object::ContributionFileSignature(contribution_file_signature),
+            )],
         )
     }
 
+    ///
+    /// Returns the hex encoded hashes of the response files received from the
+    /// given contributor in the current round. Returns an empty list if the
+    /// coordinator didn't receive any contribution from the participant.
+    ///
+    pub fn contribution_file_hashes(&self, participant: &Participant) -> Result<Vec<String>, CoordinatorError> {
+        let hasher = self.environment.contribution_hasher();
+        self.current_round()?
+            .chunks()
+            .iter()
+            .flat_map(|chunk| chunk.get_contributions().values())
+            .filter(|contribution| contribution.get_contributor().as_ref() == Some(participant))
+            .filter_map(|contribution| contribution.get_contributed_location().as_ref())
+            .map(|path| {
+                let locator = self.storage.to_locator(path)?;
+                Ok(hex::encode(hasher.hash(self.storage.reader(&locator)?.as_ref())))
+            })
+            .collect()
+    }
+
     /// Writes the contribution metadata to storage at the appropriate locator.
     pub(crate) fn write_contribution_info(
         &mut self,
@@ -1710,24 +2777,37 @@ impl Coordinator {
         )
     }
 
-    /// Updates the contribution attestation and summary to storage at the appropriate locator.
+    /// Updates the contribution attestation, with the hash of its content if archived, and summary to storage at the
+    /// appropriate locator.
     pub(crate) fn update_contribution_info_attestation(
         &mut self,
         round: u64,
         attestation: String,
+        attestation_hash: Option<String>,
     ) -> Result<(), CoordinatorError> {
         // Retrieve current file to update
-        let updated_info = match self
+        let updated_info = ContributionInfo {
+            attestation: Some(attestation),
+            attestation_hash,
+            ..self.contribution_info(round)?
+        };
+
+        self.update_contribution_info(round, updated_info)
+    }
+
+    /// Reads the contribution info of the given round from storage.
+    fn contribution_info(&self, round: u64) -> Result<ContributionInfo, CoordinatorError> {
+        match self
             .storage
             .get(&Locator::ContributionInfoFile { round_height: round })?
         {
-            Object::ContributionInfoFile(info) => ContributionInfo {
-                attestation: Some(attestation),
-                ..info
-            },
-            _ => return Err(CoordinatorError::StorageFailed),
-        };
+            Object::ContributionInfoFile(info) => Ok(info),
+            _ => Err(CoordinatorError::StorageFailed),
+        }
+    }
 
+    /// Replaces the contribution info of the given round and its entry in the summary.
+    fn update_contribution_info(&mut self, round: u64, updated_info: ContributionInfo) -> Result<(), CoordinatorError> {
         // Persist update to storage
         self.storage.update(
             &Locator::ContributionInfoFile { round_height: round },
@@ -1752,34 +2832,190 @@ impl Coordinator {
         )
     }
 
-    /// Appends current round summary to storage at the appropriate locator.
-    pub(crate) fn update_contribution_summary(
+    ///
+    /// Archives the public post attesting the contribution of the given round: its url and the hash of its content
+    /// are recorded in the contribution info and in the audit log of the participants, so that later edits or
+    /// deletions of the post are detectable.
+    ///
+    pub(crate) fn record_attestation_post(
         &mut self,
-        contribution_summary: TrimmedContributionInfo,
+        participant: &Participant,
+        round_height: u64,
+        url: String,
+        content_hash: String,
     ) -> Result<(), CoordinatorError> {
-        let mut summary = match self.storage.get(&Locator::ContributionsInfoSummary)? {
-            Object::ContributionsInfoSummary(summary) => summary,
-            _ => return Err(CoordinatorError::StorageFailed),
-        };
-        summary.push(contribution_summary);
-
-        self.storage.update(
-            &Locator::ContributionsInfoSummary,
-            Object::ContributionsInfoSummary(summary),
-        )
+        self.update_contribution_info_attestation(round_height, url.clone(), Some(content_hash.clone()))?;
+        self.state.record_participant_event(
+            participant,
+            ParticipantEvent::AttestationPosted {
+                round_height,
+                url,
+                content_hash,
+            },
+            self.time.as_ref(),
+        );
+
+        // Save the coordinator state in storage.
+        self.save_state()
     }
 
-    /// Writes the bytes of a contribution file signature to storage at the appropriate  
-    /// locator. Signature of a contribution is computed client-side, so there's no way to use the provided
-    /// write_contribution_file_signature function.
-    pub(crate) fn write_contribution_file_signature(
+    ///
+    /// Expects the given contributor, which posted the info of its contribution in
+    /// the given round, to attest the destruction of its toxic waste. It is reminded
+    /// through the events of the ceremony until the deadline set in the environment.
+    ///
+    pub(crate) fn expect_destruction_attestation(
         &mut self,
-        locator: ContributionSignatureLocator,
-        contribution_file_signature: ContributionFileSignature,
+        participant: &Participant,
+        round_height: u64,
+    ) -> Result<(), CoordinatorError> {
+        let deadline = self.time.now_utc() + self.environment.destruction_attestation_deadline();
+        self.state
+            .expect_destruction_attestation(participant, round_height, deadline, self.time.as_ref());
+
+        // Save the coordinator state in storage.
+        self.save_state()
+    }
+
+    ///
+    /// Records the attestation of the given contributor that the toxic waste of
+    /// its contribution was destroyed. The attestation is signed by the contributor
+    /// over [DestructionAttestation::message] and must refer to the hash of the
+    /// contribution file of its info. It is published in the contribution info and
+    /// in the summary, and stops the reminders.
+    ///
+    pub(crate) fn record_destruction_attestation(
+        &mut self,
+        participant: &Participant,
+        attestation: DestructionAttestation,
+        signature: String,
+    ) -> Result<(), CoordinatorError> {
+        if !self
+            .signature
+            .verify(&participant.address(), &attestation.message()?, &signature)
+        {
+            return Err(CoordinatorError::DestructionAttestationSignatureInvalid);
+        }
+
+        let round_height = attestation.round_height;
+        let info = self.contribution_info(round_height)?;
+        if !info
+            .contribution_file_hash
+            .eq_ignore_ascii_case(&attestation.contribution_hash)
+        {
+            return Err(CoordinatorError::ContributionHashMismatch);
+        }
+
+        let method = attestation.method;
+        let destruction_attestation = SignedDestructionAttestation {
+            attestation,
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        self.update_contribution_info(
+            round_height,
+            ContributionInfo {
+                destruction_attestation: Some(destruction_attestation),
+                ..info
+            },
+        )?;
+
+        self.state.complete_destruction_attestation(participant, round_height);
+        self.state.record_participant_event(
+            participant,
+            ParticipantEvent::DestructionAttested { round_height, method },
+            self.time.as_ref(),
+        );
+        info!(
+            "{} attested the destruction of its toxic waste of round {} ({})",
+            participant, round_height, method
+        );
+
+        // Save the coordinator state in storage.
+        self.save_state()
+    }
+
+    ///
+    /// Prepares the shuffle of the queue for the next round, if enabled, once
+    /// the final contribution of the current round is verified: its response
+    /// hash, signed with the challenge of the next round, seeds the shuffle.
+    ///
+    fn prepare_queue_shuffle(&mut self) -> Result<(), CoordinatorError> {
+        if !self.environment.queue_shuffle() {
+            return Ok(());
+        }
+
+        let next_round_height = self.current_round_height()? + 1;
+        if self.state.queue_shuffle(next_round_height).is_some() {
+            return Ok(());
+        }
+
+        // The ceremony has a single chunk
+        let signature_locator =
+            Locator::ContributionFileSignature(ContributionSignatureLocator::new(next_round_height, 0, 0, true));
+        if !self.storage.exists(&signature_locator) {
+            return Ok(());
+        }
+        let signature: ContributionFileSignature = serde_json::from_slice(&*self.storage.reader(&signature_locator)?)?;
+
+        let shuffle = QueueShuffle::new(next_round_height, signature.get_response_hash())?;
+        info!("Shuffling the queue of round {} with seed {}", next_round_height, shuffle.seed);
+        self.state.prepare_queue_shuffle(shuffle);
+
+        Ok(())
+    }
+
+    ///
+    /// Reminds the contributors who haven't attested the destruction of their
+    /// toxic waste yet, once per [DESTRUCTION_REMINDER_INTERVAL](crate::objects::DESTRUCTION_REMINDER_INTERVAL),
+    /// and stops expecting the attestation of the ones past their deadline.
+    ///
+    fn remind_destruction_attestations(&mut self) -> Result<(), CoordinatorError> {
+        let (reminders, missed) = self.state.update_destruction_reminders(self.time.as_ref());
+        if reminders.is_empty() && missed.is_empty() {
+            return Ok(());
+        }
+
+        for (participant, pending) in reminders {
+            self.emit_event(CeremonyEvent::DestructionAttestationReminder {
+                participant,
+                round_height: pending.round_height,
+                deadline: pending.deadline,
+            });
+        }
+
+        for (participant, pending) in missed {
+            warn!(
+                "{} didn't attest the destruction of its toxic waste of round {} before the deadline",
+                participant, pending.round_height
+            );
+            self.state.record_participant_event(
+                &participant,
+                ParticipantEvent::DestructionAttestationMissed {
+                    round_height: pending.round_height,
+                },
+                self.time.as_ref(),
+            );
+        }
+
+        // Save the coordinator state in storage.
+        self.save_state()
+    }
+
+    /// Appends current round summary to storage at the appropriate locator.
+    pub(crate) fn update_contribution_summary(
+        &mut self,
+        contribution_summary: TrimmedContributionInfo,
     ) -> Result<(), CoordinatorError> {
+        let mut summary = match self.storage.get(&Locator::ContributionsInfoSummary)? {
+            Object::ContributionsInfoSummary(summary) => summary,
+            _ => return Err(CoordinatorError::StorageFailed),
+        };
+        summary.push(contribution_summary);
+
         self.storage.update(
-            &Locator::ContributionFileSignature(locator),
-            Object::ContributionFileSignature(contribution_file_signature),
+            &Locator::ContributionsInfoSummary,
+            Object::ContributionsInfoSummary(summary),
         )
     }
 
@@ -1876,10 +3112,11 @@ impl Coordinator {
         };
 
         // Check the challenge-response hash chain.
+        let hasher = self.environment.contribution_hasher();
         let (challenge_hash, response_hash) = {
             // Compute the challenge hash using the challenge file.
             let challenge_reader = self.storage.reader(&challenge_file_locator)?;
-            let challenge_hash = calculate_hash(challenge_reader.as_ref());
+            let challenge_hash = hasher.hash(challenge_reader.as_ref());
             trace!(
                 "Challenge is located in {}",
                 self.storage.to_path(&challenge_file_locator)?
@@ -1888,7 +3125,7 @@ impl Coordinator {
 
             // Compute the response hash.
             let response_reader = self.storage.reader(&response_file_locator)?;
-            let response_hash = calculate_hash(response_reader.as_ref());
+            let response_hash = hasher.hash(response_reader.as_ref());
             trace!(
                 "Response is located in {}",
                 self.storage.to_path(&response_file_locator)?
@@ -1916,7 +3153,7 @@ impl Coordinator {
         let next_challenge_hash = {
             // Compute the next challenge hash.
             let next_challenge_reader = self.storage.reader(&next_challenge_locator)?;
-            let next_challenge_hash = calculate_hash(next_challenge_reader.as_ref());
+            let next_challenge_hash = hasher.hash(next_challenge_reader.as_ref());
             trace!(
                 "Next challenge is located in {}",
                 self.storage.to_path(&next_challenge_locator)?
@@ -2000,6 +3237,7 @@ impl Coordinator {
             participant.clone(),
             self.storage.to_path(&next_challenge_locator)?,
             self.storage.to_path(&contribution_file_signature_locator)?,
+            self.time.now_utc(),
         )?;
 
         // Add the updated round to storage.
@@ -2208,17 +3446,20 @@ impl Coordinator {
         #[cfg(test)]
         trace!("{:#?}", &new_round);
 
-        // Insert the new round into storage.
-        self.storage.insert(
-            Locator::RoundState {
-                round_height: new_height,
-            },
-            Object::RoundState(new_round),
-        )?;
+        // Insert the new round into storage and update the round height to reflect it, together.
+        self.storage.write_all_or_nothing(&[
+            (
+                Locator::RoundState {
+                    round_height: new_height,
+                },
+                Object::RoundState(new_round),
+            ),
+            (Locator::RoundHeight, Object::RoundHeight(new_height)),
+        ])?;
 
-        // Next, update the round height to reflect the new round.
-        self.storage
-            .update(&Locator::RoundHeight, Object::RoundHeight(new_height))?;
+        self.emit_event(CeremonyEvent::RoundAdvanced {
+            round_height: new_height,
+        });
 
         debug!("Added round {} to storage", current_round_height);
         info!("Transitioned from round {} to {}", current_round_height, new_height);
@@ -2317,13 +3558,11 @@ impl Coordinator {
         // Set the finished time for round 0.
         round.try_finish(self.time.now_utc());
 
-        // Add the new round to storage.
-        self.storage
-            .insert(Locator::RoundState { round_height }, Object::RoundState(round))?;
-
-        // Next, add the round height to storage.
-        self.storage
-            .insert(Locator::RoundHeight, Object::RoundHeight(round_height))?;
+        // Add the new round and the round height to storage, together.
+        self.storage.write_all_or_nothing(&[
+            (Locator::RoundState { round_height }, Object::RoundState(round)),
+            (Locator::RoundHeight, Object::RoundHeight(round_height)),
+        ])?;
 
         info!("Completed initialization of round {}", round_height);
 
@@ -2458,7 +3697,7 @@ impl Coordinator {
     /// Returns a reference to the instantiation of `Signature` that this
     /// coordinator is using.
     ///
-    #[cfg(test)]
+    #[cfg(any(test, feature = "server"))]
     #[inline]
     pub(super) fn signature(&self) -> Arc<dyn Signature> {
         self.signature.clone()
@@ -2478,6 +3717,142 @@ impl Coordinator {
         Ok(())
     }
 
+    ///
+    /// Reissues the challenges of the current round, e.g. after the storage has
+    /// been restored from a backup. Each challenge is revalidated against the hash
+    /// signed at verification time and regenerated from the last verified
+    /// contribution of the previous round if missing or corrupted.
+    ///
+    /// The chunks locked by contributors are unlocked, so that they can be locked
+    /// again on the reissued challenge. On success, returns the contributors which
+    /// must restart their contribution.
+    ///
+    pub fn reissue_challenge(&mut self) -> Result<Vec<Participant>, CoordinatorError> {
+        let current_round_height = self.current_round_height()?;
+        if current_round_height == 0 {
+            return Err(CoordinatorError::RoundHeightIsZero);
+        }
+        warn!("Reissuing the challenges of round {}", current_round_height);
+
+        // Release the locks held by contributors on the current challenges.
+        let round = self.current_round()?;
+        let mut restarting_contributors = vec![];
+        for chunk_id in 0..self.environment.number_of_chunks() {
+            if let Some(holder) = round.chunk(chunk_id)?.lock_holder() {
+                if !holder.is_contributor() {
+                    continue;
+                }
+
+                let task = self
+                    .state
+                    .lookup_pending_task(holder, chunk_id)?
+                    .copied()
+                    .ok_or(CoordinatorError::ParticipantDidntLockChunkId)?;
+                debug!("Releasing the lock of {} on chunk {}", holder, chunk_id);
+                self.rollback_locked_task(holder, task)?;
+                restarting_contributors.push(holder.clone());
+            }
+        }
+
+        for chunk_id in 0..self.environment.number_of_chunks() {
+            self.regenerate_challenge(current_round_height, chunk_id)?;
+        }
+
+        self.state.add_restarting_contributors(&restarting_contributors);
+        self.save_state()?;
+
+        warn!("Reissued the challenges of round {}", current_round_height);
+
+        Ok(restarting_contributors)
+    }
+
+    ///
+    /// Returns `true` if the given contributor lost its lock because of a
+    /// challenge reissue and has not restarted its contribution yet.
+    ///
+    #[inline]
+    pub fn must_restart(&self, participant: &Participant) -> bool {
+        self.state.must_restart(participant)
+    }
+
+    /// Checks the challenge of the given chunk of the current round against its expected
+    /// hash and regenerates it from the last verified contribution if it doesn't match.
+    /// Returns `true` if the challenge has been regenerated.
+    fn regenerate_challenge(&mut self, round_height: u64, chunk_id: u64) -> Result<bool, CoordinatorError> {
+        let challenge_locator = Locator::ContributionFile(ContributionLocator::new(round_height, chunk_id, 0, true));
+        let signature_locator =
+            Locator::ContributionFileSignature(ContributionSignatureLocator::new(round_height, chunk_id, 0, true));
+
+        // The challenge of the first round is a copy of the initialization, while the following
+        // ones derive from the final contribution of the previous round.
+        let source_locator = match round_height {
+            1 => Locator::ContributionFile(ContributionLocator::new(0, chunk_id, 0, true)),
+            _ => {
+                let previous_round = Self::load_round(&self.storage, round_height - 1)?;
+                let contribution_id = previous_round.chunk(chunk_id)?.current_contribution_id();
+                Locator::ContributionFile(ContributionLocator::new(
+                    round_height - 1,
+                    chunk_id,
+                    contribution_id,
+                    false,
+                ))
+            }
+        };
+
+        // Fetch the hash signed when the challenge was produced, if any.
+        let hasher = self.environment.contribution_hasher();
+        let expected_hash = match self.storage.exists(&signature_locator) {
+            true => {
+                let signature: ContributionFileSignature =
+                    serde_json::from_slice(&*self.storage.reader(&signature_locator)?)?;
+                let next_challenge_hash = signature
+                    .get_next_challenge_hash()
+                    .as_ref()
+                    .ok_or(CoordinatorError::NextChallengeHashMissing)?;
+                hex::decode(next_challenge_hash)?
+            }
+            false => hasher.hash(self.storage.reader(&source_locator)?.as_ref()).to_vec(),
+        };
+
+        if self.storage.exists(&challenge_locator) {
+            let challenge_hash = hasher.hash(self.storage.reader(&challenge_locator)?.as_ref());
+            if challenge_hash.as_slice() == expected_hash.as_slice() {
+                debug!("The challenge of chunk {} is valid", chunk_id);
+                return Ok(false);
+            }
+
+            warn!("The challenge of chunk {} doesn't match the expected hash", chunk_id);
+            self.storage.remove(&challenge_locator)?;
+        }
+
+        // Only uncompressed challenges can be rebuilt by copying the previous response.
+        if self.environment.compressed_outputs() != self.environment.compressed_inputs() {
+            error!(
+                "Cannot regenerate the challenge of chunk {} from a compressed response",
+                chunk_id
+            );
+            return Err(CoordinatorError::ChallengeRegenerationFailed);
+        }
+
+        info!(
+            "Regenerating the challenge of chunk {} from {}",
+            chunk_id,
+            self.storage.to_path(&source_locator)?
+        );
+        self.storage.copy(&source_locator, &challenge_locator)?;
+
+        let challenge_hash = hasher.hash(self.storage.reader(&challenge_locator)?.as_ref());
+        if challenge_hash.as_slice() != expected_hash.as_slice() {
+            error!(
+                "The regenerated challenge of chunk {} doesn't match the expected hash",
+                chunk_id
+            );
+            return Err(CoordinatorError::ChallengeRegenerationFailed);
+        }
+
+        Ok(true)
+    }
+
     /// Reset the current round in storage.
     ///
     /// + `remove_participants` is a list of participants that will
@@ -2524,7 +3899,14 @@ impl Coordinator {
 }
 
 #[cfg(any(test, feature = "operator"))]
-use crate::commands::{Computation, Seed, SigningKey, Verification};
+use crate::{
+    commands::{Computation, Seed, SigningKey, Verification},
+    objects::{
+        QuarantineDecision, ReverificationReport, ReverificationStatus, ReverificationTask, VerificationRetries,
+    },
+};
+#[cfg(any(test, feature = "operator"))]
+use setup_utils::{GenericArray, U64};
 
 #[cfg(any(test, feature = "operator"))]
 impl Coordinator {
@@ -2534,99 +3916,996 @@ impl Coordinator {
     )]
     pub fn contribute(
         &mut self,
-        contributor: &Participant,
-        contributor_signing_key: &SigningKey,
-        contributor_seed: &Seed,
+        contributor: &Participant,
+        contributor_signing_key: &SigningKey,
+        contributor_seed: &Seed,
+    ) -> Result<(), CoordinatorError> {
+        let (_chunk_id, locked_locators) = self.try_lock(contributor)?;
+        let response_locator = locked_locators.next_contribution();
+        let round_height = response_locator.round_height();
+        let chunk_id = response_locator.chunk_id();
+        let contribution_id = response_locator.contribution_id();
+
+        // check that the response matches a pending task
+        {
+            let response_task = Task::new(chunk_id, contribution_id);
+            let info = self.state.current_participant_info(contributor).unwrap().clone();
+
+            if info.pending_tasks().is_empty() {
+                return Err(CoordinatorError::ContributorPendingTasksCannotBeEmpty(
+                    contributor.clone(),
+                ));
+            }
+
+            if !info
+                .pending_tasks()
+                .iter()
+                .find(|pending_task| pending_task == &&response_task)
+                .is_some()
+            {
+                return Err(CoordinatorError::PendingTasksMustContainResponseTask { response_task });
+            }
+        }
+
+        debug!("Computing contributions for round {} chunk {}", round_height, chunk_id);
+        self.run_computation(
+            round_height,
+            chunk_id,
+            contribution_id,
+            contributor,
+            contributor_signing_key,
+            contributor_seed,
+        )?;
+        let _response = self.try_contribute(contributor, chunk_id)?;
+        debug!(
+            "Successfully computed contribution for round {} chunk {}",
+            round_height, chunk_id
+        );
+        Ok(())
+    }
+
+    pub fn get_pending_verifications(&self) -> &HashMap<Task, Participant> {
+        self.state.get_pending_verifications()
+    }
+
+    ///
+    /// Returns the number of contributions waiting to be verified: the
+    /// pending verifications of the current round and the queued
+    /// re-verifications.
+    ///
+    pub fn verification_backlog(&self) -> usize {
+        self.state.verification_backlog()
+    }
+
+    ///
+    /// Returns true if the given current contributor holds no lock and
+    /// waits for the backlog of verifications to drop back to its
+    /// threshold before locking a chunk.
+    ///
+    pub fn is_paused_by_verification_backlog(&self, participant: &Participant) -> bool {
+        match self.environment.verification_backlog_threshold() {
+            Some(threshold) => {
+                self.state.is_current_contributor(participant)
+                    && self.state.contribution_lock(participant).is_none()
+                    && self.verification_backlog() > threshold
+            }
+            None => false,
+        }
+    }
+
+    /// Verify a contribution using the coordinator's default verifier.
+    /// This is just an interface to [`verify`]
+    ///
+    /// # Error
+    /// This function assumes that the given task has been indeed assigned to the
+    /// default verifier.
+    pub fn default_verify(&mut self, task: &Task) -> anyhow::Result<()> {
+        let verifier = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or_else(|| CoordinatorError::VerifierMissing)?
+            .clone();
+        let sigkey = self.environment.default_verifier_signing_key();
+
+        self.verify(&verifier, &sigkey, task)
+    }
+
+    ///
+    /// Verifies all the contributions pending verification with the coordinator's
+    /// default verifier.
+    ///
+    /// An invalid contribution resets the round, to prevent a stall of the coordinator,
+    /// and bans the contributor who produced it. A contribution which crashes the
+    /// verifier is retried on the next call, up to the maximum number of retries of the
+    /// environment, after which it's quarantined until a decision of the operators. On
+    /// success, this function returns the updated summary of the contributions, to be
+    /// published by the frontend.
+    ///
+    pub fn verify_pending_contributions(&mut self) -> Result<Vec<u8>, CoordinatorError> {
+        // Since we don't chunk contributions and we only have one contribution per round, we will always get one
+        // pending verification at max.
+        for (task, _) in self.get_pending_verifications().to_owned() {
+            if self.state.is_verification_quarantined(&task) {
+                continue;
+            }
+
+            self.verify_pending_contribution(&task)?;
+        }
+
+        self.storage.get_contributions_summary()
+    }
+
+    ///
+    /// Verifies the contribution of the given task with the coordinator's default
+    /// verifier, counting the attempts which crash the verifier.
+    ///
+    fn verify_pending_contribution(&mut self, task: &Task) -> Result<(), CoordinatorError> {
+        let round_height = self.current_round_height()?;
+        let error = match self.default_verify(task) {
+            Ok(()) => {
+                if self.state.clear_verification_retries(task).is_some() {
+                    self.save_state()?;
+                }
+                return Ok(());
+            }
+            Err(error) => error,
+        };
+
+        // A crash of the verifier isn't a verdict on the contribution
+        if matches!(
+            error.downcast_ref::<CoordinatorError>(),
+            Some(CoordinatorError::VerifierCrashed)
+        ) {
+            let max_retries = self.environment.max_verification_retries();
+            let retries = self
+                .state
+                .record_verification_crash(task, error.to_string(), max_retries, self.time.as_ref())?;
+            self.save_state()?;
+
+            if retries.is_quarantined() {
+                error!(
+                    "The verification of the contribution {} of round {} crashed the verifier {} times, \
+                    quarantining it until a decision of the operators",
+                    task, round_height, retries.attempts
+                );
+            } else {
+                warn!(
+                    "The verification of the contribution {} of round {} crashed the verifier ({} of {} attempts), \
+                    retrying it on the next verification",
+                    task,
+                    round_height,
+                    retries.attempts,
+                    max_retries + 1
+                );
+            }
+            return Ok(());
+        }
+
+        warn!("Error while verifying a contribution: {}. Restarting the round...", error);
+        // FIXME: the verify_masp function may panic but the program doesn't shut down because we are executing it on a separate thread. It would be better though to make that function return a Result instead of panicking. Revert of round should be moved inside default_verify
+
+        // The failures injected by the chaos mode or caused by the memory budget of the coordinator are not the
+        // fault of the contributor
+        let no_fault = matches!(
+            error.downcast_ref::<CoordinatorError>(),
+            Some(CoordinatorError::ChaosVerificationFailure)
+                | Some(CoordinatorError::VerificationMemoryBudgetExceeded { .. })
+        );
+        self.reject_pending_contribution(!no_fault)
+    }
+
+    ///
+    /// Rejects the contribution pending verification in the current round: the round is
+    /// reset, and the contributor who produced it is banned if the contribution is at fault.
+    ///
+    fn reject_pending_contribution(&mut self, ban: bool) -> Result<(), CoordinatorError> {
+        // Get the participant who produced the contribution
+        let finished_contributor = self
+            .state
+            .current_round_finished_contributors()?
+            .first()
+            .cloned()
+            .ok_or(CoordinatorError::RoundContributorMissing)?;
+
+        // Reset the round to prevent a coordinator stall (the corrupted contribution is not automatically dropped)
+        self.reset_round()?;
+
+        // Ban the participant who produced the invalid contribution. Must be banned after the reset beacuse one can't ban a finished contributor
+        if ban {
+            self.ban_participant(&finished_contributor)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the attempts of the verification of the contributions of the current
+    /// round which crashed the verifier, among which the quarantined ones.
+    ///
+    pub fn verification_retries(&self) -> Vec<VerificationRetries> {
+        self.state.verification_retries().to_vec()
+    }
+
+    ///
+    /// Returns the shuffles of the queue of the rounds, with their seeds, to
+    /// recompute the contributors assigned to each round.
+    ///
+    pub fn queue_shuffles(&self) -> Vec<QueueShuffle> {
+        self.state.queue_shuffles().to_vec()
+    }
+
+    ///
+    /// Applies the decision of the operators on the quarantined contribution of the given
+    /// task of the current round. A rejected contribution is treated as an invalid one,
+    /// and a contribution to verify is verified once more regardless of its attempts,
+    /// staying quarantined if it crashes the verifier again.
+    ///
+    pub fn resolve_quarantined_verification(
+        &mut self,
+        task: &Task,
+        decision: QuarantineDecision,
+    ) -> Result<(), CoordinatorError> {
+        if !self.state.is_verification_quarantined(task) {
+            return Err(CoordinatorError::VerificationNotQuarantined);
+        }
+
+        let round_height = self.current_round_height()?;
+        match decision {
+            QuarantineDecision::Reject => {
+                warn!(
+                    "Rejecting the quarantined contribution {} of round {} on the decision of the operators",
+                    task, round_height
+                );
+                let contributor = self
+                    .current_round()?
+                    .chunk(task.chunk_id())?
+                    .get_contribution(task.contribution_id())?
+                    .get_contributor()
+                    .clone();
+                if let Some(contributor) = &contributor {
+                    (self.verification_callback)(
+                        contributor,
+                        VerificationStatus::Failed("Rejected by the operators".to_string()),
+                    );
+                }
+
+                self.reject_pending_contribution(true)
+            }
+            QuarantineDecision::Verify => {
+                info!(
+                    "Verifying the quarantined contribution {} of round {} on the decision of the operators",
+                    task, round_height
+                );
+                self.verify_pending_contribution(task)
+            }
+        }
+    }
+
+    #[tracing::instrument(
+        skip(self, verifier, verifier_signing_key),
+        fields(verifier = %verifier),
+    )]
+    pub fn verify(
+        &mut self,
+        verifier: &Participant,
+        verifier_signing_key: &SigningKey,
+        task: &Task,
+    ) -> anyhow::Result<()> {
+        let round_height = self.current_round_height()?;
+        debug!(
+            "Running verification for round {} chunk {}",
+            round_height,
+            task.chunk_id()
+        );
+
+        // Fetch the contributor of the contribution to report the progress of the verification
+        let contributor = self
+            .current_round()?
+            .chunk(task.chunk_id())?
+            .get_contribution(task.contribution_id())?
+            .get_contributor()
+            .clone();
+        let callback = self.verification_callback.clone();
+        let event_callback = self.event_callback.clone();
+        let time = self.time.clone();
+        let report = |status: VerificationStatus| {
+            if let Some(contributor) = &contributor {
+                if status.is_completed() {
+                    let event = CeremonyEvent::Verified {
+                        participant: contributor.clone(),
+                        round_height,
+                        succeeded: matches!(status, VerificationStatus::Succeeded),
+                    };
+                    event_callback(time.now_utc(), event);
+                }
+                callback(contributor, status);
+            }
+        };
+
+        // In quorum mode the contribution waits for the votes of the other verifiers once verified.
+        let quorum = self.environment.verification_quorum();
+        if quorum > 1
+            && self
+                .state
+                .verification_quorum(round_height, task)
+                .map_or(false, |votes| votes.has_voted(verifier))
+        {
+            return Ok(());
+        }
+
+        #[cfg(debug_assertions)]
+        if self.chaos.sample_verification_failure() {
+            warn!("Chaos: failing the verification for round {} chunk {}", round_height, task.chunk_id());
+            report(VerificationStatus::Failed("Injected verification failure (chaos mode)".to_string()));
+            return Err(CoordinatorError::ChaosVerificationFailure.into());
+        }
+
+        report(VerificationStatus::Running(VerificationPhase::Verifying));
+        let verified_path = match self.run_verification(round_height, task, verifier, verifier_signing_key) {
+            Ok(verified_path) => verified_path,
+            // A crash of the verifier is retried, the contributor keeps waiting for the verification
+            Err(CoordinatorError::VerifierCrashed) => return Err(CoordinatorError::VerifierCrashed.into()),
+            Err(e) => {
+                report(VerificationStatus::Failed(e.to_string()));
+                return Err(e.into());
+            }
+        };
+        // Dropped with the round if the contribution is rejected later on
+        self.attest_verification(round_height, task, verifier, verifier_signing_key, &verified_path)?;
+
+        if quorum > 1 {
+            report(VerificationStatus::Running(VerificationPhase::Voting));
+            let status = self.vote_verification(round_height, task, verifier, verifier_signing_key, &verified_path)?;
+            self.conclude_verification_vote(round_height, task, status)?;
+            return Ok(());
+        }
+
+        report(VerificationStatus::Running(VerificationPhase::Finalizing));
+        if let Err(e) = self.try_verify(verifier, task) {
+            report(VerificationStatus::Failed(e.to_string()));
+            return Err(e.into());
+        }
+
+        report(VerificationStatus::Succeeded);
+        debug!(
+            "Successful verification for round {} chunk {}",
+            round_height,
+            task.chunk_id()
+        );
+        Ok(())
+    }
+
+    ///
+    /// Records the vote of an external verifier on the verification of a task pending
+    /// verification in the current round, and verifies the contribution once the quorum
+    /// of verifiers agrees on its next challenge.
+    ///
+    /// The vote is signed by the verifier over [VerificationVote::message].
+    ///
+    pub fn add_verification_vote(
+        &mut self,
+        verifier: &Participant,
+        task: &Task,
+        next_challenge_hash: String,
+        signature: String,
+    ) -> Result<QuorumStatus, CoordinatorError> {
+        if !verifier.is_verifier() {
+            return Err(CoordinatorError::ExpectedVerifier);
+        }
+
+        let round_height = self.current_round_height()?;
+        let message = VerificationVote::message(round_height, task, &next_challenge_hash);
+        if !self.signature.verify(&verifier.address(), &message, &signature) {
+            return Err(CoordinatorError::VerifierSignatureInvalid);
+        }
+
+        let vote = VerificationVote {
+            verifier: verifier.clone(),
+            next_challenge_hash,
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        let status = self.state.add_verification_vote(task, vote)?;
+        self.save_state()?;
+
+        self.conclude_verification_vote(round_height, task, status)
+    }
+
+    ///
+    /// Returns the votes collected on each task pending verification in the current round.
+    ///
+    pub fn pending_verification_quorums(&self) -> Result<Vec<VerificationQuorum>, CoordinatorError> {
+        let round_height = self.current_round_height()?;
+
+        Ok(self
+            .state
+            .get_pending_verifications()
+            .keys()
+            .map(|task| {
+                self.state
+                    .verification_quorum(round_height, task)
+                    .cloned()
+                    .unwrap_or_else(|| VerificationQuorum::new(round_height, task))
+            })
+            .collect())
+    }
+
+    ///
+    /// Issues a delegation, signed with the key of the coordinator, allowing an external verifier which is not one
+    /// of the quorum verifiers to call the verification endpoints for the rounds `from_round..=to_round`, during the
+    /// given duration.
+    ///
+    pub fn delegate_verifier(
+        &self,
+        verifier: &Participant,
+        from_round: u64,
+        to_round: u64,
+        duration: time::Duration,
+    ) -> Result<SignedVerifierDelegation, CoordinatorError> {
+        if !verifier.is_verifier() {
+            return Err(CoordinatorError::ExpectedVerifier);
+        }
+        if from_round > to_round || to_round < self.current_round_height()? || !duration.is_positive() {
+            return Err(CoordinatorError::VerifierDelegationInvalid);
+        }
+
+        let coordinator = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or(CoordinatorError::VerifierMissing)?;
+        let issued_at = self.time.now_utc();
+        let delegation = VerifierDelegation {
+            verifier: verifier.address(),
+            from_round,
+            to_round,
+            issued_at,
+            expires_at: issued_at + duration,
+        };
+        info!(
+            "Delegating the verification of rounds {} to {} to {} until {}",
+            from_round, to_round, verifier, delegation.expires_at
+        );
+
+        Ok(delegation.sign(
+            self.signature.as_ref(),
+            coordinator.address(),
+            &self.environment.default_verifier_signing_key(),
+        )?)
+    }
+
+    ///
+    /// Checks that the given delegation is signed by the coordinator and allows the verifier to verify the current
+    /// round.
+    ///
+    pub fn check_verifier_delegation(
+        &self,
+        verifier: &Participant,
+        delegation: &SignedVerifierDelegation,
+    ) -> Result<(), CoordinatorError> {
+        let coordinator = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or(CoordinatorError::VerifierMissing)?;
+        if delegation.delegation.verifier != verifier.address()
+            || !delegation.verify(self.signature.as_ref(), &coordinator.address())
+        {
+            return Err(CoordinatorError::VerifierDelegationInvalid);
+        }
+
+        let now = self.time.now_utc();
+        if now >= delegation.delegation.expires_at {
+            return Err(CoordinatorError::VerifierDelegationExpired);
+        }
+        if !delegation.delegation.covers(self.current_round_height()?, now) {
+            return Err(CoordinatorError::VerifierDelegationOutOfRounds);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the public keys allowed to sign the privileged requests of the coordinator.
+    ///
+    pub fn control_keys(&self) -> BTreeSet<String> {
+        self.state.control_keys()
+    }
+
+    ///
+    /// Returns true if the given public key is allowed to sign the privileged requests of the coordinator.
+    ///
+    pub fn is_control_key(&self, pubkey: &str) -> bool {
+        self.state.control_keys().contains(pubkey)
+    }
+
+    ///
+    /// Adds a public key allowed to sign the privileged requests of the coordinator, e.g. to run a redundant control
+    /// plane or before rotating a key, and returns the control keys.
+    ///
+    pub fn add_control_key(&mut self, pubkey: &str) -> Result<BTreeSet<String>, CoordinatorError> {
+        self.state.add_control_key(pubkey);
+        self.save_state()?;
+        info!("Added the control key {}", pubkey);
+
+        Ok(self.state.control_keys())
+    }
+
+    ///
+    /// Revokes a public key allowed to sign the privileged requests of the coordinator, and returns the control keys.
+    ///
+    pub fn remove_control_key(&mut self, pubkey: &str) -> Result<BTreeSet<String>, CoordinatorError> {
+        self.state.revoke_control_key(pubkey)?;
+        self.save_state()?;
+        info!("Revoked the control key {}", pubkey);
+
+        Ok(self.state.control_keys())
+    }
+
+    ///
+    /// Records the approval of an operator on the manual injection of a contribution
+    /// computed out-of-band on behalf of a contributor of the current round. The
+    /// contribution can be injected once enough distinct operators approved it.
+    ///
+    /// The approval is signed by the operator over [ManualContribution::message].
+    ///
+    pub fn approve_manual_contribution(
+        &mut self,
+        operator: &str,
+        contribution: ManualContribution,
+        signature: String,
+    ) -> Result<InjectionStatus, CoordinatorError> {
+        if !self.environment.operators().iter().any(|pubkey| pubkey == operator) {
+            return Err(CoordinatorError::OperatorUnauthorized);
+        }
+
+        if !contribution.participant.is_contributor() {
+            return Err(CoordinatorError::ExpectedContributor);
+        }
+
+        if !self.signature.verify(operator, &contribution.message()?, &signature) {
+            return Err(CoordinatorError::OperatorSignatureInvalid);
+        }
+
+        let approval = OperatorApproval {
+            operator: operator.to_owned(),
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        let status = self.state.approve_manual_contribution(contribution, approval)?;
+        self.save_state()?;
+
+        Ok(status)
+    }
+
+    ///
+    /// Injects an approved manual contribution in place of the contribution of the participant
+    /// holding the lock, and releases the lock as [`Coordinator::try_contribute`] does. The
+    /// contribution is marked as injected in the manifest of the round.
+    ///
+    pub fn inject_manual_contribution(
+        &mut self,
+        contribution: &ManualContribution,
+        contribution_file: Vec<u8>,
+        contribution_file_signature: ContributionFileSignature,
+    ) -> Result<ContributionLocator, CoordinatorError> {
+        match self.state.manual_injection(contribution).map(ManualInjection::status) {
+            Some(InjectionStatus::Approved) => (),
+            Some(InjectionStatus::Injected) => return Err(CoordinatorError::ManualContributionAlreadyInjected),
+            _ => return Err(CoordinatorError::ManualContributionNotApproved),
+        }
+
+        let contribution_hash = self.environment.contribution_hasher().hash(&contribution_file);
+        if hex::encode(contribution_hash) != contribution.contribution_hash {
+            return Err(CoordinatorError::ContributionHashMismatch);
+        }
+
+        let participant = &contribution.participant;
+        let (chunk_id, _) = self
+            .state
+            .contribution_lock(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+        let locked_locators = self.current_round()?.locked_locators(chunk_id, participant)?;
+
+        self.write_contribution(
+            locked_locators.next_contribution(),
+            contribution_file,
+            locked_locators.next_contribution_file_signature(),
+            contribution_file_signature,
+        )?;
+        let locator = self.try_contribute(participant, chunk_id)?;
+
+        self.state
+            .set_manual_contribution_injected(contribution, self.time.as_ref())?;
+        self.save_state()?;
+
+        warn!(
+            "Injected the contribution of {} approved by the operators: {}",
+            participant, contribution.reason
+        );
+        Ok(locator)
+    }
+
+    ///
+    /// Returns the commit-reveal of the seed of the genesis challenge by the
+    /// operators, and its state.
+    ///
+    pub fn genesis_seed(&self) -> (GenesisSeed, GenesisSeedStatus) {
+        let genesis_seed = self.state.genesis_seed().clone();
+        let status = genesis_seed.status(self.environment.operators().len());
+
+        (genesis_seed, status)
+    }
+
+    ///
+    /// Records the commitment of an operator to its share of the seed of the
+    /// genesis challenge. The shares are revealed once every operator
+    /// committed to its share.
+    ///
+    /// The commitment is the lowercase hex encoded Blake2b-512 hash of the
+    /// share, signed by the operator over [SeedCommitment::message].
+    ///
+    pub fn commit_genesis_seed(
+        &mut self,
+        operator: &str,
+        commitment: String,
+        signature: String,
+    ) -> Result<GenesisSeedStatus, CoordinatorError> {
+        self.check_genesis_seed_operator(operator)?;
+
+        let is_hash = hex::decode(&commitment).map_or(false, |hash| hash.len() == GENESIS_SEED_SIZE);
+        if !is_hash || commitment != commitment.to_lowercase() {
+            return Err(CoordinatorError::GenesisSeedInvalid);
+        }
+
+        if !self
+            .signature
+            .verify(operator, &SeedCommitment::message(&commitment), &signature)
+        {
+            return Err(CoordinatorError::OperatorSignatureInvalid);
+        }
+
+        let commitment = SeedCommitment {
+            operator: operator.to_owned(),
+            commitment,
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        let status = self
+            .state
+            .commit_genesis_seed(commitment, self.environment.operators().len())?;
+        self.save_state()?;
+
+        info!("Operator {} committed to its share of the genesis seed", operator);
+        Ok(status)
+    }
+
+    ///
+    /// Records the reveal of the share of the seed of the genesis challenge an
+    /// operator committed to. Once every operator revealed its share, derives
+    /// the seed and writes it at the start of the genesis challenge, after
+    /// which round 1 can start.
+    ///
+    /// The hex encoded share is signed by the operator over [SeedReveal::message].
+    ///
+    pub fn reveal_genesis_seed(
+        &mut self,
+        operator: &str,
+        share: String,
+        signature: String,
+    ) -> Result<GenesisSeedStatus, CoordinatorError> {
+        self.check_genesis_seed_operator(operator)?;
+
+        if !self
+            .signature
+            .verify(operator, &SeedReveal::message(&share), &signature)
+        {
+            return Err(CoordinatorError::OperatorSignatureInvalid);
+        }
+
+        let reveal = SeedReveal {
+            operator: operator.to_owned(),
+            share,
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        let required = self.environment.operators().len();
+        let status = self.state.reveal_genesis_seed(reveal, required)?;
+        self.save_state()?;
+        info!("Operator {} revealed its share of the genesis seed", operator);
+
+        if self.state.genesis_seed().reveals.len() < required {
+            return Ok(status);
+        }
+
+        let seed = self
+            .state
+            .genesis_seed()
+            .derive()
+            .map_err(|_| CoordinatorError::GenesisSeedInvalid)?;
+        for chunk_id in 0..self.environment.number_of_chunks() {
+            Initialization::seed(&self.environment, &mut self.storage, 0, chunk_id, &seed)?;
+        }
+        self.state.set_genesis_seed(&seed);
+        self.save_state()?;
+
+        info!("Derived the seed of the genesis challenge {}", hex::encode(&seed));
+        Ok(self.state.genesis_seed().status(required))
+    }
+
+    /// Checks that the given operator may take part in the derivation of the seed of the genesis challenge.
+    fn check_genesis_seed_operator(&self, operator: &str) -> Result<(), CoordinatorError> {
+        if !self.environment.genesis_seed_ceremony() {
+            return Err(CoordinatorError::GenesisSeedCeremonyDisabled);
+        }
+
+        if !self.environment.operators().iter().any(|pubkey| pubkey == operator) {
+            return Err(CoordinatorError::OperatorUnauthorized);
+        }
+
+        // The seed is written in the genesis challenge before the first contribution
+        if self.current_round_height()? != 0 {
+            return Err(CoordinatorError::CeremonyAlreadyLaunched);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if round 1 waits for the operators to derive the seed of the genesis challenge.
+    fn is_awaiting_genesis_seed(&self) -> Result<bool, CoordinatorError> {
+        Ok(self.environment.genesis_seed_ceremony()
+            && self.state.genesis_seed().seed.is_none()
+            && self.current_round_height()? == 0)
+    }
+
+    /// Signs and records the vote of the given verifier on the next challenge it produced.
+    fn vote_verification(
+        &mut self,
+        round_height: u64,
+        task: &Task,
+        verifier: &Participant,
+        verifier_signing_key: &SigningKey,
+        verified_path: &LocatorPath,
+    ) -> Result<QuorumStatus, CoordinatorError> {
+        let verified_locator = self.storage.to_locator(verified_path)?;
+        let verified_reader = self.storage.reader(&verified_locator)?;
+        let next_challenge_hash = hex::encode(self.environment.contribution_hasher().hash(verified_reader.as_ref()));
+        let signature = self.signature.sign(
+            verifier_signing_key,
+            &VerificationVote::message(round_height, task, &next_challenge_hash),
+        )?;
+
+        let vote = VerificationVote {
+            verifier: verifier.clone(),
+            next_challenge_hash,
+            signature,
+            timestamp: self.time.now_utc(),
+        };
+        let status = self.state.add_verification_vote(task, vote)?;
+        self.save_state()?;
+
+        Ok(status)
+    }
+
+    /// Signs and records the attestation of the given verifier on the contribution it verified, with the hash of the
+    /// file signature it produced for the next challenge as the verification report.
+    fn attest_verification(
+        &mut self,
+        round_height: u64,
+        task: &Task,
+        verifier: &Participant,
+        verifier_signing_key: &SigningKey,
+        verified_path: &LocatorPath,
     ) -> Result<(), CoordinatorError> {
-        let (_chunk_id, locked_locators) = self.try_lock(contributor)?;
-        let response_locator = locked_locators.next_contribution();
-        let round_height = response_locator.round_height();
-        let chunk_id = response_locator.chunk_id();
-        let contribution_id = response_locator.contribution_id();
+        let verified_locator = match self.storage.to_locator(verified_path)? {
+            Locator::ContributionFile(locator) => locator,
+            _ => return Err(CoordinatorError::StorageLocatorFormatIncorrect),
+        };
+        let report_locator = Locator::ContributionFileSignature(ContributionSignatureLocator::new(
+            verified_locator.round_height(),
+            verified_locator.chunk_id(),
+            verified_locator.contribution_id(),
+            true,
+        ));
+        let report_hash = hex::encode(calculate_hash(self.storage.reader(&report_locator)?.as_ref()));
 
-        // check that the response matches a pending task
-        {
-            let response_task = Task::new(chunk_id, contribution_id);
-            let info = self.state.current_participant_info(contributor).unwrap().clone();
+        let attestation = VerifierAttestation::sign(
+            self.signature.as_ref(),
+            round_height,
+            task,
+            verifier.address(),
+            verifier_signing_key,
+            report_hash,
+        )?;
+        self.state.add_verifier_attestation(attestation);
+        self.save_state()
+    }
 
-            if info.pending_tasks().is_empty() {
-                return Err(CoordinatorError::ContributorPendingTasksCannotBeEmpty(
-                    contributor.clone(),
-                ));
-            }
+    ///
+    /// Queues the verified contributions of the completed rounds, from the given one onwards, to be verified again by
+    /// the default verifier. The progression of the ceremony and the transcript are left untouched.
+    ///
+    /// On success, returns the newly queued contributions.
+    ///
+    pub fn queue_reverification(&mut self, from_round: u64) -> Result<Vec<ReverificationTask>, CoordinatorError> {
+        let current_round_height = self.current_round_height()?;
+        if from_round >= current_round_height {
+            return Err(CoordinatorError::RoundNotComplete);
+        }
 
-            if !info
-                .pending_tasks()
-                .iter()
-                .find(|pending_task| pending_task == &&response_task)
-                .is_some()
-            {
-                return Err(CoordinatorError::PendingTasksMustContainResponseTask { response_task });
+        let mut tasks = Vec::new();
+        for round_height in from_round..current_round_height {
+            let round = Self::load_round(&self.storage, round_height)?;
+            for chunk in round.chunks() {
+                for (&contribution_id, contribution) in chunk.get_contributions() {
+                    if contribution_id > 0 && contribution.is_verified() {
+                        let task = Task::new(chunk.chunk_id(), contribution_id);
+                        tasks.push(ReverificationTask::new(round_height, &task));
+                    }
+                }
             }
         }
 
-        debug!("Computing contributions for round {} chunk {}", round_height, chunk_id);
-        self.run_computation(
-            round_height,
-            chunk_id,
-            contribution_id,
-            contributor,
-            contributor_signing_key,
-            contributor_seed,
-        )?;
-        let _response = self.try_contribute(contributor, chunk_id)?;
-        debug!(
-            "Successfully computed contribution for round {} chunk {}",
-            round_height, chunk_id
-        );
-        Ok(())
-    }
+        let queued = self.state.queue_reverifications(tasks);
+        info!("Queued {} contributions for re-verification", queued.len());
+        self.save_state()?;
 
-    pub fn get_pending_verifications(&self) -> &HashMap<Task, Participant> {
-        self.state.get_pending_verifications()
+        Ok(queued)
     }
 
-    /// Verify a contribution using the coordinator's default verifier.
-    /// This is just an interface to [`verify`]
     ///
-    /// # Error
-    /// This function assumes that the given task has been indeed assigned to the
-    /// default verifier.
-    pub fn default_verify(&mut self, task: &Task) -> anyhow::Result<()> {
+    /// Verifies again the next queued contribution of a completed round with the default verifier, and records the
+    /// signed report of the outcome. A failed re-verification only shows in its report.
+    ///
+    /// On success, returns the report, or `None` if no contribution is queued.
+    ///
+    pub fn reverify_next(&mut self) -> Result<Option<ReverificationReport>, CoordinatorError> {
+        let task = match self.state.next_reverification() {
+            Some(task) => task,
+            None => return Ok(None),
+        };
         let verifier = self
             .environment
             .coordinator_verifiers()
             .first()
-            .ok_or_else(|| CoordinatorError::VerifierMissing)?
+            .ok_or(CoordinatorError::VerifierMissing)?
             .clone();
         let sigkey = self.environment.default_verifier_signing_key();
 
-        self.verify(&verifier, &sigkey, task)
+        let outcome = self.reverify(&task).map(hex::encode).map_err(|e| {
+            warn!(
+                "Re-verification of round {} chunk {} contribution {} failed: {:?}",
+                task.round_height, task.chunk_id, task.contribution_id, e
+            );
+            format!("{:?}", e)
+        });
+        let report = ReverificationReport::sign(
+            self.signature.as_ref(),
+            &task,
+            verifier.address(),
+            &sigkey,
+            outcome,
+            self.time.now_utc().unix_timestamp(),
+        )?;
+        self.state.complete_reverification(report.clone());
+        self.save_state()?;
+
+        Ok(Some(report))
     }
 
-    #[tracing::instrument(
-        skip(self, verifier, verifier_signing_key),
-        fields(verifier = %verifier),
-    )]
-    pub fn verify(
+    ///
+    /// Returns the contributions waiting to be verified again and the reports of their latest re-verification.
+    ///
+    pub fn reverification_status(&self) -> ReverificationStatus {
+        ReverificationStatus {
+            queue: self.state.reverification_queue(),
+            reports: self.state.reverification_reports().to_vec(),
+        }
+    }
+
+    /// Verifies again the given contribution of a completed round, returning the hash of its response.
+    fn reverify(&self, task: &ReverificationTask) -> Result<GenericArray<u8, U64>, CoordinatorError> {
+        let round = Self::load_round(&self.storage, task.round_height)?;
+        let chunk = round.chunk(task.chunk_id)?;
+        let is_final_contribution = chunk.get_contributions().keys().next_back() == Some(&task.contribution_id);
+
+        Verification::reverify(
+            &self.environment,
+            &self.storage,
+            task.round_height,
+            task.chunk_id,
+            task.contribution_id,
+            is_final_contribution,
+        )
+    }
+
+    ///
+    /// Acts on the outcome of the votes on the given task. The contribution is verified
+    /// once the quorum agrees and the verifier assigned to the task, which produces the
+    /// next challenge, has voted. A disagreement between the verifiers is reported as a
+    /// failed verification and holds the task until the operator resets the round.
+    ///
+    fn conclude_verification_vote(
         &mut self,
-        verifier: &Participant,
-        verifier_signing_key: &SigningKey,
+        round_height: u64,
         task: &Task,
-    ) -> anyhow::Result<()> {
-        let round_height = self.current_round_height()?;
-        debug!(
-            "Running verification for round {} chunk {}",
-            round_height,
-            task.chunk_id()
-        );
-        let _next_challenge = self.run_verification(round_height, task, verifier, verifier_signing_key)?;
-        self.try_verify(verifier, task)?;
-        debug!(
-            "Successful verification for round {} chunk {}",
-            round_height,
-            task.chunk_id()
-        );
+        status: QuorumStatus,
+    ) -> Result<QuorumStatus, CoordinatorError> {
+        match &status {
+            QuorumStatus::Pending { votes, quorum } => {
+                debug!(
+                    "Verification of round {} chunk {} contribution {} has {} of {} votes",
+                    round_height,
+                    task.chunk_id(),
+                    task.contribution_id(),
+                    votes,
+                    quorum
+                );
+            }
+            QuorumStatus::Agreed { next_challenge_hash } => {
+                let verifier = self
+                    .state
+                    .get_pending_verifications()
+                    .get(task)
+                    .cloned()
+                    .ok_or(CoordinatorError::VerificationNotPending)?;
+                if self
+                    .state
+                    .verification_quorum(round_height, task)
+                    .map_or(false, |votes| votes.has_voted(&verifier))
+                {
+                    info!(
+                        "Verifiers agree on next challenge {} for round {} chunk {} contribution {}",
+                        next_challenge_hash,
+                        round_height,
+                        task.chunk_id(),
+                        task.contribution_id()
+                    );
+                    if let Err(e) = self.try_verify(&verifier, task) {
+                        self.report_verification(task, VerificationStatus::Failed(e.to_string()))?;
+                        return Err(e);
+                    }
+                    self.report_verification(task, VerificationStatus::Succeeded)?;
+                }
+            }
+            QuorumStatus::Disagreement { next_challenge_hashes } => {
+                error!(
+                    "Verifiers disagree on round {} chunk {} contribution {}: next challenges {}",
+                    round_height,
+                    task.chunk_id(),
+                    task.contribution_id(),
+                    next_challenge_hashes.join(", ")
+                );
+                self.report_verification(
+                    task,
+                    VerificationStatus::Failed("The verifiers disagree on the verification".to_string()),
+                )?;
+            }
+        }
+
+        Ok(status)
+    }
+
+    /// Reports the progress of the verification of the given task to its contributor.
+    fn report_verification(&self, task: &Task, status: VerificationStatus) -> Result<(), CoordinatorError> {
+        let contributor = self
+            .current_round()?
+            .chunk(task.chunk_id())?
+            .get_contribution(task.contribution_id())?
+            .get_contributor()
+            .clone();
+        if let Some(contributor) = contributor {
+            if status.is_completed() {
+                self.emit_event(CeremonyEvent::Verified {
+                    participant: contributor.clone(),
+                    round_height: self.current_round_height()?,
+                    succeeded: matches!(status, VerificationStatus::Succeeded),
+                });
+            }
+            (self.verification_callback)(&contributor, status);
+        }
+
         Ok(())
     }
 
@@ -2829,6 +5108,31 @@ impl Coordinator {
         &self.state
     }
 
+    ///
+    /// Returns the current time of the source of time of the coordinator. Frontends
+    /// should use it rather than the system time, so that tests can control it.
+    ///
+    #[inline]
+    pub fn now_utc(&self) -> OffsetDateTime {
+        self.time.now_utc()
+    }
+
+    ///
+    /// Returns the time elapsed since the coordinator booted up.
+    ///
+    #[inline]
+    pub fn uptime(&self) -> time::Duration {
+        self.now_utc() - self.booted_at
+    }
+
+    ///
+    /// Returns the index of the current cohort, starting from 0.
+    ///
+    #[inline]
+    pub fn current_cohort_index(&self) -> usize {
+        self.state.get_current_cohort_index(&*self.time)
+    }
+
     ///
     /// Returns a reference to the instantiation of `Environment` that this
     /// coordinator is using.
@@ -2838,6 +5142,110 @@ impl Coordinator {
         &self.environment
     }
 
+    ///
+    /// Returns a reference to the registry of the circuits targeted by the ceremony.
+    ///
+    #[inline]
+    pub fn circuit_registry(&self) -> &CircuitRegistry {
+        &self.circuit_registry
+    }
+
+    ///
+    /// Returns the parameters of the ceremony, with the expected sizes of the challenge and the response of the
+    /// current round.
+    ///
+    pub fn ceremony_parameters(&self) -> Result<CeremonyParameters, CoordinatorError> {
+        let settings = self.environment.parameters();
+        let round_height = self.current_round_height()?;
+
+        Ok(CeremonyParameters {
+            curve: settings.curve(),
+            proving_system: settings.proving_system(),
+            power: settings.power(),
+            batch_size: settings.batch_size(),
+            chunk_size: settings.chunk_size(),
+            number_of_chunks: self.environment.number_of_chunks(),
+            base_file_size: ANOMA_BASE_FILE_SIZE,
+            contribution_size_increase: ANOMA_PER_ROUND_FILE_SIZE_INCREASE,
+            round_height,
+            // Each round has a single contribution on top of its challenge
+            challenge_size: Object::anoma_contribution_file_size(round_height, 0),
+            response_size: Object::anoma_contribution_file_size(round_height, 1),
+            response_signature_size: Object::contribution_file_signature_size(false),
+            hash_algorithms: HashAlgorithms {
+                contribution: self.environment.hash_algorithm().to_string(),
+                ..Default::default()
+            },
+        })
+    }
+
+    ///
+    /// Returns the changelog of the REST API, signed with the key of the coordinator.
+    ///
+    pub fn signed_changelog(&self) -> Result<SignedChangelog, CoordinatorError> {
+        let coordinator = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or(CoordinatorError::VerifierMissing)?;
+
+        Ok(Changelog::new(self.environment.api_deprecations().clone()).sign(
+            self.signature.as_ref(),
+            coordinator.address(),
+            &self.environment.default_verifier_signing_key(),
+        )?)
+    }
+
+    ///
+    /// Returns the identity of the ceremony, signed with the key of the coordinator.
+    ///
+    pub fn signed_ceremony_metadata(&self) -> Result<SignedCeremonyMetadata, CoordinatorError> {
+        let coordinator = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or(CoordinatorError::VerifierMissing)?;
+
+        Ok(self.ceremony_metadata.clone().sign(
+            self.signature.as_ref(),
+            coordinator.address(),
+            &self.environment.default_verifier_signing_key(),
+        )?)
+    }
+
+    ///
+    /// Returns the statement of the participation of the contributor with the given public key, its latest contribution
+    /// if any, signed with the key of the coordinator.
+    ///
+    pub fn participation_badge(&self, public_key: &str) -> Result<SignedParticipationBadge, CoordinatorError> {
+        let coordinator = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or(CoordinatorError::VerifierMissing)?;
+
+        let summary = match self.storage.get(&Locator::ContributionsInfoSummary)? {
+            Object::ContributionsInfoSummary(summary) => summary,
+            _ => return Err(CoordinatorError::StorageFailed),
+        };
+        let contribution = summary.iter().rev().find(|info| info.public_key() == public_key);
+
+        let badge = ParticipationBadge {
+            ceremony: self.ceremony_metadata.name.clone(),
+            public_key: public_key.to_string(),
+            contributed: contribution.is_some(),
+            round_height: contribution.map(|info| info.ceremony_round()),
+            contribution_hash: contribution.map(|info| info.contribution_hash().to_string()),
+            issued_at: self.time.now_utc(),
+        };
+
+        Ok(badge.sign(
+            self.signature.as_ref(),
+            coordinator.address(),
+            &self.environment.default_verifier_signing_key(),
+        )?)
+    }
+
     ///
     /// Rollback a task which was locked by a contributor. Should be used to unlock
     /// chunks which become stuck during the ceremony.
@@ -2864,9 +5272,11 @@ mod tests {
         authentication::Dummy,
         commands::{Seed, SigningKey, SEED_LENGTH},
         environment::*,
-        objects::{Participant, Task},
+        objects::{Participant, ReverificationReport, ReverificationTask, Task},
+        storage::{ContributionLocator, Locator, Object},
         testing::prelude::*,
         Coordinator,
+        CoordinatorError,
     };
 
     use once_cell::sync::Lazy;
@@ -3617,6 +6027,170 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[serial]
+    fn coordinator_reissue_challenge() -> anyhow::Result<()> {
+        initialize_test_environment(&TEST_ENVIRONMENT_ANOMA);
+
+        let contributor = Lazy::force(&TEST_CONTRIBUTOR_ID).clone();
+
+        let mut coordinator = Coordinator::new(TEST_ENVIRONMENT_ANOMA.clone(), Arc::new(Dummy))?;
+        initialize_coordinator_single_contributor(&mut coordinator)?;
+
+        let (chunk_id, _) = coordinator.try_lock(&contributor)?;
+        let challenge = coordinator.get_challenge(1, chunk_id, 0, true)?;
+
+        // Simulate the loss of the challenge after a storage restore.
+        let challenge_locator = Locator::ContributionFile(ContributionLocator::new(1, chunk_id, 0, true));
+        coordinator.storage.remove(&challenge_locator)?;
+
+        // Reissue the challenge and check that the lock holder must restart.
+        assert_eq!(vec![contributor.clone()], coordinator.reissue_challenge()?);
+        assert!(coordinator.must_restart(&contributor));
+        assert!(coordinator.current_round()?.chunk(chunk_id)?.is_unlocked());
+
+        // The contributors who must restart survive a restart of the coordinator.
+        match coordinator.storage.get(&Locator::CoordinatorState)? {
+            Object::CoordinatorState(state) => assert!(state.must_restart(&contributor)),
+            _ => panic!("Expected the coordinator state"),
+        }
+        assert_eq!(challenge, coordinator.get_challenge(1, chunk_id, 0, true)?);
+
+        // Reissuing a valid challenge leaves it untouched.
+        assert!(coordinator.reissue_challenge()?.is_empty());
+        assert_eq!(challenge, coordinator.get_challenge(1, chunk_id, 0, true)?);
+
+        // Lock the reissued challenge.
+        assert_eq!(chunk_id, coordinator.try_lock(&contributor)?.0);
+        assert!(!coordinator.must_restart(&contributor));
+
+        // The locked challenge is read without borrowing the coordinator.
+        let challenge_locator = coordinator.locked_challenge(&contributor, 1)?;
+        let (_, file) = coordinator.open_challenge(&challenge_locator)?;
+        drop(coordinator);
+        assert_eq!(challenge, Disk::read_contribution_file(file, &challenge_locator)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn coordinator_challenge_rewritten_during_download() -> anyhow::Result<()> {
+        initialize_test_environment(&TEST_ENVIRONMENT_ANOMA);
+
+        let contributor = Lazy::force(&TEST_CONTRIBUTOR_ID).clone();
+
+        let mut coordinator = Coordinator::new(TEST_ENVIRONMENT_ANOMA.clone(), Arc::new(Dummy))?;
+        initialize_coordinator_single_contributor(&mut coordinator)?;
+
+        let (chunk_id, _) = coordinator.try_lock(&contributor)?;
+        let challenge_locator = coordinator.locked_challenge(&contributor, 1)?;
+        let challenge = coordinator.get_challenge(1, chunk_id, 0, true)?;
+        let mut corrupted = challenge.clone();
+        corrupted[0] ^= 0xff;
+
+        // A download started before the challenge is rewritten reads the challenge as it was.
+        let (_, file) = coordinator.open_locked_challenge(&contributor, 1)?;
+        coordinator.storage.update(
+            &Locator::ContributionFile(challenge_locator),
+            Object::ContributionFile(corrupted.clone()),
+        )?;
+        assert_eq!(corrupted, coordinator.get_challenge(1, chunk_id, 0, true)?);
+        assert_eq!(challenge, Disk::read_contribution_file(file, &challenge_locator)?);
+
+        // A download started before the challenge is reissued reads the corrupted challenge in full.
+        let (_, file) = coordinator.open_locked_challenge(&contributor, 1)?;
+        assert_eq!(vec![contributor.clone()], coordinator.reissue_challenge()?);
+        assert_eq!(challenge, coordinator.get_challenge(1, chunk_id, 0, true)?);
+        assert_eq!(corrupted, Disk::read_contribution_file(file, &challenge_locator)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn coordinator_locked_challenge() -> anyhow::Result<()> {
+        initialize_test_environment(&TEST_ENVIRONMENT_ANOMA);
+
+        let contributor = Lazy::force(&TEST_CONTRIBUTOR_ID).clone();
+        let other_contributor = Participant::new_contributor("other-contributor");
+
+        let mut coordinator = Coordinator::new(TEST_ENVIRONMENT_ANOMA.clone(), Arc::new(Dummy))?;
+        initialize_coordinator_single_contributor(&mut coordinator)?;
+
+        // The challenge is only served once the chunk is locked.
+        assert!(coordinator.locked_challenge(&contributor, 1).is_err());
+
+        let (chunk_id, locked_locators) = coordinator.try_lock(&contributor)?;
+        let challenge = coordinator.locked_challenge(&contributor, 1)?;
+        assert_eq!(challenge, locked_locators.current_contribution());
+        assert_eq!(challenge, ContributionLocator::new(1, chunk_id, 0, true));
+
+        // The sizes of the files are reported with the locators.
+        let sizes = locked_locators.sizes().unwrap();
+        let challenge_file = coordinator.get_challenge(1, chunk_id, 0, true)?;
+        assert_eq!(sizes.challenge, challenge_file.len() as u64);
+        let contribution_id = locked_locators.next_contribution().contribution_id();
+        assert_eq!(sizes.contribution, Object::anoma_contribution_file_size(1, contribution_id));
+        assert_eq!(sizes.contribution_file_signature, Object::contribution_file_signature_size(false));
+
+        // Other rounds and other contributors are rejected.
+        assert!(matches!(
+            coordinator.locked_challenge(&contributor, 0),
+            Err(CoordinatorError::RoundHeightMismatch)
+        ));
+        assert!(matches!(
+            coordinator.locked_challenge(&other_contributor, 1),
+            Err(CoordinatorError::ParticipantUnauthorized)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn coordinator_verification_backlog() -> anyhow::Result<()> {
+        let environment: Environment = Testing::from(Parameters::TestCustom {
+            number_of_chunks: 1,
+            power: 8,
+            batch_size: 128,
+        })
+        .verification_backlog_threshold(0)
+        .into();
+        initialize_test_environment(&environment);
+
+        let contributor = Lazy::force(&TEST_CONTRIBUTOR_ID).clone();
+
+        let mut coordinator = Coordinator::new(environment, Arc::new(Dummy))?;
+        initialize_coordinator_single_contributor(&mut coordinator)?;
+        assert!(!coordinator.is_paused_by_verification_backlog(&contributor));
+
+        // A contribution waiting to be verified pauses the new locks.
+        let task = ReverificationTask::new(0, &Task::new(0, 1));
+        coordinator.state.queue_reverifications(vec![task]);
+        assert_eq!(1, coordinator.verification_backlog());
+        assert!(coordinator.is_paused_by_verification_backlog(&contributor));
+        assert!(matches!(
+            coordinator.try_lock(&contributor),
+            Err(CoordinatorError::VerificationBacklog {
+                backlog: 1,
+                threshold: 0
+            })
+        ));
+
+        // The locks resume once the backlog is verified.
+        let report = ReverificationReport::sign(&Dummy, &task, "verifier".to_string(), "key", Ok(String::new()), 0)?;
+        coordinator.state.complete_reverification(report);
+        assert!(!coordinator.is_paused_by_verification_backlog(&contributor));
+        coordinator.try_lock(&contributor)?;
+
+        // The contributor holding a lock isn't paused.
+        coordinator.state.queue_reverifications(vec![task]);
+        assert!(!coordinator.is_paused_by_verification_backlog(&contributor));
+
+        Ok(())
+    }
+
     #[test]
     #[serial]
     #[ignore]
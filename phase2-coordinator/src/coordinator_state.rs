@@ -1,9 +1,18 @@
 use crate::{
+    authentication::ssh,
     environment::Environment,
+    ip_retention::{IpRetention, RetainedIp},
     objects::{
         participant::*,
         task::{initialize_tasks, Task},
+        seed_commitment, ComputationProofRecord, ComputationStats, ContributionComputation, ContributorQueue,
+        GenesisSeed, GenesisSeedStatus, HumanVerification, IdentityClaim, InjectionStatus, ManualContribution,
+        ManualInjection, OperatorApproval, ParticipantEvent, ParticipantHistory, ParticipantRecord,
+        PendingDestructionAttestation, PhaseTimeouts, QueueInfo, QueueShuffle, QuorumStatus, ReverificationReport,
+        ReverificationTask, SeedCommitment, SeedReveal, VerificationQuorum, VerificationRetries, VerificationVote,
+        VerifiedIdentity, VerifierAttestation,
     },
+    prelaunch::PrelaunchSnapshot,
     storage::{Disk, Locator, Object},
     CoordinatorError, TimeSource,
 };
@@ -13,7 +22,7 @@ use lazy_static::lazy_static;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet, LinkedList},
+    collections::{BTreeSet, HashMap, HashSet, LinkedList, VecDeque},
     iter::FromIterator,
     net::IpAddr,
 };
@@ -105,6 +114,15 @@ pub struct ParticipantInfo {
     disposing_tasks: LinkedList<Task>,
     /// The list of (chunk ID, contribution ID) tasks that are disposed of while computing.
     disposed_tasks: LinkedList<Task>,
+    /// The timestamp when this participant downloaded the challenge bundle to contribute from an air-gapped machine.
+    #[serde(default)]
+    offline_since: Option<OffsetDateTime>,
+    /// The outcome of the human verification of this participant when joining the queue, if required.
+    #[serde(default)]
+    human_verification: Option<HumanVerification>,
+    /// The identity claimed by this participant on top of its public key when joining the queue, if any.
+    #[serde(default)]
+    identity: Option<VerifiedIdentity>,
 }
 
 impl PartialEq for ParticipantInfo {
@@ -140,6 +158,9 @@ impl ParticipantInfo {
             completed_tasks: LinkedList::new(),
             disposing_tasks: LinkedList::new(),
             disposed_tasks: LinkedList::new(),
+            offline_since: None,
+            human_verification: None,
+            identity: None,
         }
     }
 
@@ -192,6 +213,27 @@ impl ParticipantInfo {
         &self.disposed_tasks
     }
 
+    ///
+    /// Returns the timestamp when this participant started contributing from an air-gapped machine, if any.
+    ///
+    pub fn offline_since(&self) -> Option<OffsetDateTime> {
+        self.offline_since
+    }
+
+    ///
+    /// Returns the outcome of the human verification of this participant when joining the queue, if any.
+    ///
+    pub fn human_verification(&self) -> Option<&HumanVerification> {
+        self.human_verification.as_ref()
+    }
+
+    ///
+    /// Returns the identity claimed by this participant when joining the queue, if any.
+    ///
+    pub fn identity(&self) -> Option<&VerifiedIdentity> {
+        self.identity.as_ref()
+    }
+
     ///
     /// Returns `true` if the participant is dropped from the current round.
     ///
@@ -975,9 +1017,9 @@ pub struct CoordinatorState {
     environment: Environment,
     /// The current status of the coordinator.
     status: CoordinatorStatus,
-    /// The map of queue participants with a reliability score, an assigned future
+    /// The queue of participants with a reliability score, an assigned future
     /// round, a last seen timestamp, and their time of joining.
-    queue: HashMap<Participant, (u8, Option<u64>, OffsetDateTime, OffsetDateTime)>,
+    queue: ContributorQueue,
     /// The map of unique participants for the next round.
     next: HashMap<Participant, ParticipantInfo>,
     /// The metrics for the current round of the ceremony.
@@ -986,8 +1028,9 @@ pub struct CoordinatorState {
     current_round_height: Option<u64>,
     /// The map of unique contributors for the current round.
     current_contributors: HashMap<Participant, ParticipantInfo>,
-    /// The map of contributors' IPs
-    blacklisted_ips: HashMap<IpAddr, Participant>,
+    /// The map of the IPs of the contributors who have finished, or of their salted hashes, depending on the
+    /// retention policy
+    blacklisted_ips: HashMap<String, Participant>,
     /// The map of unique verifiers for the current round.
     current_verifiers: HashMap<Participant, ParticipantInfo>,
     /// The map of tasks pending verification in the current round.
@@ -1008,6 +1051,68 @@ pub struct CoordinatorState {
     cohort_duration: u64,
     /// Map of tokens which have been used in the ceremony
     blacklisted_tokens: HashMap<String, Participant>,
+    /// The audit log of the events of all the participants ever seen.
+    #[serde(default)]
+    participant_history: ParticipantHistory,
+    /// The votes of the verifiers on the contributions of the current and past rounds.
+    #[serde(default)]
+    verification_quorums: Vec<VerificationQuorum>,
+    /// The contributions injected manually by the operators, or waiting for their approval.
+    #[serde(default)]
+    manual_injections: Vec<ManualInjection>,
+    /// The attestations of the verifiers on the contributions of the current and past rounds.
+    #[serde(default)]
+    verifier_attestations: Vec<VerifierAttestation>,
+    /// The contributions of completed rounds waiting to be verified again, in order.
+    #[serde(default)]
+    reverification_queue: VecDeque<ReverificationTask>,
+    /// The reports of the latest re-verification of the contributions of completed rounds.
+    #[serde(default)]
+    reverification_reports: Vec<ReverificationReport>,
+    /// The outcomes of the human verification of the contributors who joined the queue from a browser.
+    #[serde(default)]
+    human_verifications: HashMap<Participant, HumanVerification>,
+    /// The identities claimed by the contributors on top of their public keys when joining the queue.
+    #[serde(default)]
+    identities: HashMap<Participant, VerifiedIdentity>,
+    /// The retention policy under which the IPs of the contributors who have finished are stored.
+    #[serde(default)]
+    ip_retention: IpRetention,
+    /// The ASNs and countries of the IPs of the contributors who have finished, under the location retention policy.
+    #[serde(default)]
+    ip_locations: HashMap<Participant, String>,
+    /// The control keys added at runtime, on top of the ones of the environment.
+    #[serde(default)]
+    added_control_keys: BTreeSet<String>,
+    /// The control keys revoked at runtime.
+    #[serde(default)]
+    revoked_control_keys: BTreeSet<String>,
+    /// The contributors who haven't attested the destruction of the toxic waste of their contribution yet.
+    #[serde(default)]
+    pending_destruction_attestations: HashMap<Participant, PendingDestructionAttestation>,
+    /// The attempts of the verification of the contributions of the current round which crashed the verifier.
+    #[serde(default)]
+    verification_retries: Vec<VerificationRetries>,
+    /// The contributors whose lock was released by a reissue of the challenges of the current round,
+    /// and who must restart their contribution.
+    #[serde(default)]
+    restarting_contributors: HashSet<Participant>,
+    /// The shuffles of the queue, published so that anyone can recompute the contributors assigned to each round.
+    #[serde(default)]
+    queue_shuffles: Vec<QueueShuffle>,
+    /// The commit-reveal of the seed of the genesis challenge by the operators.
+    #[serde(default)]
+    genesis_seed: GenesisSeed,
+    /// The proofs of computation time of the contributions of the current and past rounds.
+    #[serde(default)]
+    computation_proofs: Vec<ComputationProofRecord>,
+    /// The name of the current phase of the schedule of the ceremony, if any.
+    #[serde(default)]
+    ceremony_phase: Option<String>,
+    /// The timeouts of the participants set by the current phase of the schedule, overriding the ones of the
+    /// environment.
+    #[serde(default)]
+    phase_timeouts: PhaseTimeouts,
     /// Temporary runtime state, should not be persisted to storage to reset it in case of restart
     #[serde(skip)]
     runtime_state: RuntimeState,
@@ -1090,11 +1195,12 @@ impl CoordinatorState {
         };
 
         let ceremony_start_time = CoordinatorState::get_ceremony_start_time();
+        let ip_retention = environment.ip_retention();
 
         Self {
             environment,
             status: CoordinatorStatus::Initializing,
-            queue: HashMap::default(),
+            queue: ContributorQueue::default(),
             next: HashMap::default(),
             current_metrics: None,
             current_round_height: None,
@@ -1110,6 +1216,26 @@ impl CoordinatorState {
             ceremony_start_time,
             cohort_duration,
             blacklisted_tokens: HashMap::default(),
+            participant_history: ParticipantHistory::default(),
+            verification_quorums: Vec::new(),
+            manual_injections: Vec::new(),
+            verifier_attestations: Vec::new(),
+            reverification_queue: VecDeque::new(),
+            reverification_reports: Vec::new(),
+            human_verifications: HashMap::default(),
+            identities: HashMap::default(),
+            ip_retention,
+            ip_locations: HashMap::default(),
+            added_control_keys: BTreeSet::new(),
+            revoked_control_keys: BTreeSet::new(),
+            pending_destruction_attestations: HashMap::default(),
+            verification_retries: Vec::new(),
+            restarting_contributors: HashSet::new(),
+            queue_shuffles: Vec::new(),
+            genesis_seed: GenesisSeed::default(),
+            computation_proofs: Vec::new(),
+            ceremony_phase: None,
+            phase_timeouts: PhaseTimeouts::default(),
             runtime_state: RuntimeState::default(),
         }
     }
@@ -1222,6 +1348,24 @@ impl CoordinatorState {
                 queue,
                 banned: std::mem::take(&mut self.banned),
                 blacklisted_tokens: std::mem::take(&mut self.blacklisted_tokens),
+                participant_history: std::mem::take(&mut self.participant_history),
+                verification_quorums: self.take_verification_quorums_before(current_round_height),
+                manual_injections: self.take_manual_injections_before(current_round_height),
+                verifier_attestations: self.take_verifier_attestations_before(current_round_height),
+                reverification_queue: std::mem::take(&mut self.reverification_queue),
+                reverification_reports: std::mem::take(&mut self.reverification_reports),
+                human_verifications: std::mem::take(&mut self.human_verifications),
+                identities: std::mem::take(&mut self.identities),
+                ip_retention: self.ip_retention,
+                ip_locations: std::mem::take(&mut self.ip_locations),
+                added_control_keys: std::mem::take(&mut self.added_control_keys),
+                revoked_control_keys: std::mem::take(&mut self.revoked_control_keys),
+                pending_destruction_attestations: std::mem::take(&mut self.pending_destruction_attestations),
+                queue_shuffles: std::mem::take(&mut self.queue_shuffles),
+                genesis_seed: std::mem::take(&mut self.genesis_seed),
+                computation_proofs: self.take_computation_proofs_before(current_round_height),
+                ceremony_phase: std::mem::take(&mut self.ceremony_phase),
+                phase_timeouts: std::mem::take(&mut self.phase_timeouts),
                 runtime_state: std::mem::take(&mut self.runtime_state),
                 ..Self::new(self.environment.clone())
             };
@@ -1268,6 +1412,24 @@ impl CoordinatorState {
                 banned: std::mem::take(&mut self.banned),
                 dropped: std::mem::take(&mut self.dropped),
                 blacklisted_tokens: std::mem::take(&mut self.blacklisted_tokens),
+                participant_history: std::mem::take(&mut self.participant_history),
+                verification_quorums: self.take_verification_quorums_before(current_round_height),
+                manual_injections: self.take_manual_injections_before(current_round_height),
+                verifier_attestations: self.take_verifier_attestations_before(current_round_height),
+                reverification_queue: std::mem::take(&mut self.reverification_queue),
+                reverification_reports: std::mem::take(&mut self.reverification_reports),
+                human_verifications: std::mem::take(&mut self.human_verifications),
+                identities: std::mem::take(&mut self.identities),
+                ip_retention: self.ip_retention,
+                ip_locations: std::mem::take(&mut self.ip_locations),
+                added_control_keys: std::mem::take(&mut self.added_control_keys),
+                revoked_control_keys: std::mem::take(&mut self.revoked_control_keys),
+                pending_destruction_attestations: std::mem::take(&mut self.pending_destruction_attestations),
+                queue_shuffles: std::mem::take(&mut self.queue_shuffles),
+                genesis_seed: std::mem::take(&mut self.genesis_seed),
+                computation_proofs: self.take_computation_proofs_before(current_round_height),
+                ceremony_phase: std::mem::take(&mut self.ceremony_phase),
+                phase_timeouts: std::mem::take(&mut self.phase_timeouts),
                 runtime_state: std::mem::take(&mut self.runtime_state),
                 ..Self::new(self.environment.clone())
             };
@@ -1320,6 +1482,16 @@ impl CoordinatorState {
         participant.is_contributor() && self.queue.contains_key(participant)
     }
 
+    ///
+    /// Returns `true` if the given contributor is already in the queue with the given token, i.e. its request to join
+    /// the queue is a retry. The token is only checked if the tokens in use are tracked.
+    ///
+    #[inline]
+    pub(crate) fn is_queue_retry(&self, participant: &Participant, token: &str) -> bool {
+        self.is_queue_contributor(participant)
+            && (!self.token_blacklist() || self.runtime_state.tokens_in_use.get(token) == Some(participant))
+    }
+
     ///
     /// Returns `true` if the given participant is an authorized contributor in the ceremony.
     ///
@@ -1421,28 +1593,50 @@ impl CoordinatorState {
     ///
     #[inline]
     pub fn number_of_queue_contributors(&self) -> usize {
-        self.queue.par_iter().filter(|(p, _)| p.is_contributor()).count()
+        self.queue.number_of_contributors()
+    }
+
+    ///
+    /// Returns the number of contributors which finished their
+    /// contribution, in all the rounds.
+    ///
+    pub fn number_of_finished_contributors(&self) -> usize {
+        self.finished_contributors.values().map(HashMap::len).sum()
     }
 
     ///
     /// Returns the information of a queued contributor.
     ///
-    pub fn queue_contributor_info(
-        &self,
-        participant: &Participant,
-    ) -> Option<&(u8, Option<u64>, OffsetDateTime, OffsetDateTime)> {
+    pub fn queue_contributor_info(&self, participant: &Participant) -> Option<&QueueInfo> {
         self.queue.get(participant)
     }
 
     ///
-    /// Returns a list of the contributors currently in the queue.
+    /// Returns the position of a queued contributor and the number of
+    /// contributors in the queue.
+    ///
+    /// The position is the number of rounds until the round the contributor
+    /// is assigned to, or its position in the queue if it is not assigned yet.
+    ///
+    pub fn queue_position(&self, participant: &Participant) -> Option<(u64, u64)> {
+        let position = self.queue.position(participant)?;
+        let position = match self.queue.get(participant)? {
+            (_, Some(round), _, _) => round.saturating_sub(self.current_round_height()),
+            (_, None, _, _) => position as u64 + 1,
+        };
+
+        Some((position, self.number_of_queue_contributors() as u64))
+    }
+
+    ///
+    /// Returns a list of the contributors currently in the queue, in order.
     ///
     #[inline]
-    pub fn queue_contributors(&self) -> Vec<(Participant, (u8, Option<u64>, OffsetDateTime, OffsetDateTime))> {
+    pub fn queue_contributors(&self) -> Vec<(Participant, QueueInfo)> {
         self.queue
-            .clone()
-            .into_par_iter()
+            .iter()
             .filter(|(p, _)| p.is_contributor())
+            .map(|(p, info)| (p.clone(), *info))
             .collect()
     }
 
@@ -1451,7 +1645,25 @@ impl CoordinatorState {
     ///
     #[inline]
     pub fn current_contributors(&self) -> Vec<(Participant, ParticipantInfo)> {
-        self.current_contributors.clone().into_iter().collect()
+        self.current_contributors
+            .iter()
+            .map(|(participant, info)| (participant.clone(), info.clone()))
+            .collect()
+    }
+
+    ///
+    /// Returns the contributors currently in the round, without copying them.
+    ///
+    #[inline]
+    pub fn current_contributor_ids(&self) -> impl Iterator<Item = &Participant> {
+        self.current_contributors.keys()
+    }
+
+    ///
+    /// Returns the number of contributors currently in the round.
+    ///
+    pub fn number_of_current_contributors(&self) -> usize {
+        self.current_contributors.len()
     }
 
     /// Gets reference to the [ParticipantInfo] for a participant
@@ -1480,6 +1692,181 @@ impl CoordinatorState {
         self.dropped.clone()
     }
 
+    ///
+    /// Returns the records of all the participants ever seen by the coordinator, with their final disposition.
+    ///
+    #[inline]
+    pub fn participant_records(&self) -> Vec<ParticipantRecord> {
+        self.participant_history.records()
+    }
+
+    ///
+    /// Appends an event of the given participant to the audit log of the participants.
+    ///
+    #[inline]
+    pub(super) fn record_participant_event(
+        &mut self,
+        participant: &Participant,
+        event: ParticipantEvent,
+        time: &dyn TimeSource,
+    ) {
+        self.participant_history.record(participant, event, time.now_utc());
+    }
+
+    ///
+    /// Marks the given current contributor, which must hold a lock, as computing
+    /// its contribution on an air-gapped machine. From now on its lock and liveness
+    /// are checked against [crate::environment::Environment]'s
+    /// `offline_contribution_timeout`.
+    ///
+    pub(super) fn start_offline_contribution(
+        &mut self,
+        participant: &Participant,
+        time: &dyn TimeSource,
+    ) -> Result<(), CoordinatorError> {
+        let participant_info = self
+            .current_contributors
+            .get_mut(participant)
+            .ok_or(CoordinatorError::ParticipantUnauthorized)?;
+
+        if participant_info.locked_chunks.is_empty() {
+            return Err(CoordinatorError::ParticipantDidntLockChunkId);
+        }
+
+        if participant_info.offline_since.is_none() {
+            participant_info.offline_since = Some(time.now_utc());
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the ID of the chunk locked by the given contributor computing
+    /// offline, and the time after which it will be dropped from the round.
+    ///
+    pub fn offline_contribution_lock(&self, participant: &Participant) -> Option<(u64, OffsetDateTime)> {
+        let participant_info = self.current_contributors.get(participant)?;
+        participant_info.offline_since?;
+
+        participant_info.locked_chunks.values().next().map(|lock| {
+            (
+                lock.chunk_id,
+                lock.lock_time + self.environment.offline_contribution_timeout(),
+            )
+        })
+    }
+
+    ///
+    /// Returns the ID of the chunk locked by the given current contributor,
+    /// and the time after which it will be dropped from the round for holding
+    /// the lock too long.
+    ///
+    pub fn contribution_lock(&self, participant: &Participant) -> Option<(u64, OffsetDateTime)> {
+        let participant_info = self.current_contributors.get(participant)?;
+        let lock_timeout = match participant_info.offline_since {
+            Some(_) => self.environment.offline_contribution_timeout(),
+            None => self.participant_lock_timeout(),
+        };
+
+        participant_info.locked_chunks.values().next().map(|lock| {
+            let lock_deadline = lock.lock_time + lock_timeout;
+            let deadline = match participant_info.started_at {
+                Some(started_at) => lock_deadline.min(started_at + lock_timeout),
+                None => lock_deadline,
+            };
+
+            (lock.chunk_id, deadline)
+        })
+    }
+
+    ///
+    /// Returns `true` if the given current contributor is computing its contribution on an air-gapped machine.
+    ///
+    pub fn is_offline_contributor(&self, participant: &Participant) -> bool {
+        self.current_contributors
+            .get(participant)
+            .map_or(false, |participant_info| participant_info.offline_since.is_some())
+    }
+
+    ///
+    /// Returns the earliest time at which a participant, either in the round
+    /// or in the queue, exceeds one of the timeouts of
+    /// [crate::environment::Environment]. Returns [`None`] if no participant
+    /// can time out.
+    ///
+    pub fn next_timeout(&self) -> Option<OffsetDateTime> {
+        let participant_timeouts = self
+            .current_contributors
+            .iter()
+            .chain(self.current_verifiers.iter())
+            .filter(|(participant, _)| !self.is_coordinator_contributor(participant))
+            .flat_map(|(participant, participant_info)| self.participant_timeouts(participant, participant_info));
+
+        let queue_seen_timeout = self.queue_seen_timeout();
+        let queue_timeouts = self
+            .queue
+            .values()
+            .map(|(_, _, last_seen, _)| *last_seen + queue_seen_timeout);
+
+        participant_timeouts.chain(queue_timeouts).min()
+    }
+
+    ///
+    /// Returns the earliest time at which the given participant, either in
+    /// the round or in the queue, exceeds one of the timeouts of
+    /// [crate::environment::Environment]. Returns [`None`] if the participant
+    /// can't time out.
+    ///
+    pub fn participant_timeout(&self, participant: &Participant) -> Option<OffsetDateTime> {
+        if self.is_coordinator_contributor(participant) {
+            return None;
+        }
+
+        let participant_info = self
+            .current_contributors
+            .get(participant)
+            .or_else(|| self.current_verifiers.get(participant));
+        match participant_info {
+            Some(participant_info) => self.participant_timeouts(participant, participant_info).min(),
+            None => self
+                .queue
+                .get(participant)
+                .map(|(_, _, last_seen, _)| *last_seen + self.queue_seen_timeout()),
+        }
+    }
+
+    /// Returns the times at which the given participant of the round exceeds each of its timeouts.
+    fn participant_timeouts<'a>(
+        &'a self,
+        participant: &Participant,
+        participant_info: &'a ParticipantInfo,
+    ) -> impl Iterator<Item = OffsetDateTime> + 'a {
+        let (seen_timeout, lock_timeout) = match participant_info.offline_since {
+            Some(_) => (
+                self.environment.offline_contribution_timeout(),
+                self.environment.offline_contribution_timeout(),
+            ),
+            None => (self.contributor_seen_timeout(), self.participant_lock_timeout()),
+        };
+
+        // Verifiers are only dropped for holding a lock too long
+        let (last_seen, started_at) = match participant {
+            Participant::Contributor(_) => (Some(participant_info.last_seen), participant_info.started_at),
+            Participant::Verifier(_) => (None, None),
+        };
+
+        last_seen
+            .map(|last_seen| last_seen + seen_timeout)
+            .into_iter()
+            .chain(started_at.map(|started_at| started_at + lock_timeout))
+            .chain(
+                participant_info
+                    .locked_chunks
+                    .values()
+                    .map(move |lock| lock.lock_time + lock_timeout),
+            )
+    }
+
     ///
     /// Returns the current round height stored in the coordinator state.
     ///
@@ -1501,10 +1888,11 @@ impl CoordinatorState {
     ///
     /// Computes the current ceremony cohort, starting from 0, depending on the cohort duration.
     ///
-    pub fn get_current_cohort_index(&self) -> usize {
+    pub fn get_current_cohort_index(&self, time: &dyn TimeSource) -> usize {
         let ceremony_start_time = self.ceremony_start_time;
-        let now = OffsetDateTime::now_utc();
-        let timestamp_diff = (now.unix_timestamp() - ceremony_start_time.unix_timestamp()) as u64;
+        let now = time.now_utc();
+        // Before the start of the ceremony, the first cohort is current
+        let timestamp_diff = (now.unix_timestamp() - ceremony_start_time.unix_timestamp()).max(0) as u64;
 
         (timestamp_diff / self.cohort_duration) as usize
     }
@@ -1567,7 +1955,8 @@ impl CoordinatorState {
     }
 
     ///
-    /// Moves the ip address from the list of currently in use to the black list
+    /// Moves the ip address from the list of currently in use to the black list, in the form kept by the retention
+    /// policy
     ///
     pub fn blacklist_participant_ip(&mut self, participant: &Participant) -> Result<(), CoordinatorError> {
         if let Some(target_ip) = self
@@ -1580,13 +1969,20 @@ impl CoordinatorState {
             // Safe to unwrap here
             let (ip, part) = self.runtime_state.current_ips.remove_entry(&target_ip).unwrap();
 
-            match self.blacklisted_ips.insert(ip, part) {
-                Some(part) => Err(CoordinatorError::Error(anyhow!(
-                    "Ip {} was already blacklisted for participant {}!",
-                    target_ip,
-                    part
-                ))),
-                None => Ok(()),
+            match self.ip_retention.retain(&ip) {
+                RetainedIp::Banned(key) => match self.blacklisted_ips.insert(key, part) {
+                    Some(part) => Err(CoordinatorError::Error(anyhow!(
+                        "Ip {} was already blacklisted for participant {}!",
+                        target_ip,
+                        part
+                    ))),
+                    None => Ok(()),
+                },
+                RetainedIp::Located(location) => {
+                    self.ip_locations.insert(part, location);
+                    Ok(())
+                }
+                RetainedIp::Discarded => Ok(()),
             }
         } else {
             Ok(())
@@ -1594,117 +1990,534 @@ impl CoordinatorState {
     }
 
     ///
-    /// Returns true if the token is currently in use
+    /// Applies the given retention policy to the IPs stored under the previous one. Returns the number of redacted IPs.
     ///
-    pub fn is_token_in_use(&self, token: &str) -> bool {
-        self.runtime_state.tokens_in_use.contains_key(token)
+    pub(super) fn redact_ips(&mut self, ip_retention: IpRetention) -> usize {
+        self.ip_retention = ip_retention;
+
+        let mut redacted = 0;
+        for (key, participant) in std::mem::take(&mut self.blacklisted_ips) {
+            match ip_retention.redact(&key) {
+                RetainedIp::Banned(retained_key) => {
+                    if retained_key != key {
+                        redacted += 1;
+                    }
+                    self.blacklisted_ips.insert(retained_key, participant);
+                }
+                RetainedIp::Located(location) => {
+                    redacted += 1;
+                    self.ip_locations.insert(participant, location);
+                }
+                RetainedIp::Discarded => redacted += 1,
+            }
+        }
+        if ip_retention == IpRetention::Discard {
+            redacted += self.ip_locations.len();
+            self.ip_locations.clear();
+        }
+
+        redacted
     }
 
     ///
-    /// Returns true if the token has been blacklisted
+    /// Returns the ASNs and countries of the IPs of the contributors who have finished, under the location retention
+    /// policy.
     ///
-    pub fn is_token_blacklisted(&self, token: &str) -> bool {
-        self.blacklisted_tokens.contains_key(token)
+    #[inline]
+    pub fn ip_locations(&self) -> &HashMap<Participant, String> {
+        &self.ip_locations
     }
 
     ///
-    /// Returns `true` if all participants in the current round have no more pending chunks.
+    /// Returns the public keys allowed to sign the privileged requests: the key of
+    /// the coordinator's verifier and the control keys of the environment, with the
+    /// keys added and without the keys revoked at runtime.
     ///
-    #[inline]
-    pub fn is_current_round_finished(&self) -> bool {
-        // Check that all contributions have undergone verification.
-        self.pending_verification.is_empty()
-            // Check that all current contributors are finished.
-            && self.current_contributors.is_empty()
+    pub fn control_keys(&self) -> BTreeSet<String> {
+        let configured = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .map(Participant::address)
+            .into_iter()
+            .chain(self.environment.control_keys().iter().cloned());
+
+        configured
+            .map(|key| ssh::canonical_public_key(&key).into_owned())
+            .chain(self.added_control_keys.iter().cloned())
+            .filter(|key| !self.revoked_control_keys.contains(key))
+            .collect()
     }
 
     ///
-    /// Returns `true` if the current round is currently being aggregated.
+    /// Adds a control key, which may have been revoked before.
     ///
-    #[inline]
-    pub fn is_current_round_aggregating(&self) -> bool {
-        match &self.current_metrics {
-            Some(metrics) => {
-                !metrics.is_round_aggregated
-                    && metrics.started_aggregation_at.is_some()
-                    && metrics.finished_aggregation_at.is_none()
-            }
-            None => false,
+    pub(super) fn add_control_key(&mut self, key: &str) {
+        let key = ssh::canonical_public_key(key).into_owned();
+        self.revoked_control_keys.remove(&key);
+        self.added_control_keys.insert(key);
+    }
+
+    ///
+    /// Revokes a control key. The last control key can't be revoked, for the
+    /// coordinator to stay under control.
+    ///
+    pub(super) fn revoke_control_key(&mut self, key: &str) -> Result<(), CoordinatorError> {
+        let key = ssh::canonical_public_key(key).into_owned();
+        let control_keys = self.control_keys();
+        if !control_keys.contains(&key) {
+            return Err(CoordinatorError::ControlKeyMissing);
         }
+        if control_keys.len() == 1 {
+            return Err(CoordinatorError::ControlKeyLast);
+        }
+
+        self.added_control_keys.remove(&key);
+        self.revoked_control_keys.insert(key);
+
+        Ok(())
     }
 
     ///
-    /// Returns `true` if the current round has been aggregated.
+    /// Returns the contributors who haven't attested the destruction of the
+    /// toxic waste of their contribution yet.
     ///
     #[inline]
-    pub fn is_current_round_aggregated(&self) -> bool {
-        match &self.current_metrics {
-            Some(metrics) => {
-                metrics.is_round_aggregated
-                    && metrics.started_aggregation_at.is_some()
-                    && metrics.finished_aggregation_at.is_some()
-            }
-            None => false,
-        }
+    pub fn pending_destruction_attestations(&self) -> &HashMap<Participant, PendingDestructionAttestation> {
+        &self.pending_destruction_attestations
     }
 
     ///
-    /// Returns `true` if the precommit for the next round is ready.
+    /// Expects the given contributor to attest the destruction of the toxic
+    /// waste of its contribution in the given round, and reminds it until
+    /// the deadline.
     ///
-    /// This function checks that the requisite number of contributors and verifiers are
-    /// assigned for the next round.
+    pub(super) fn expect_destruction_attestation(
+        &mut self,
+        participant: &Participant,
+        round_height: u64,
+        deadline: OffsetDateTime,
+        time: &dyn TimeSource,
+    ) {
+        self.pending_destruction_attestations.insert(
+            participant.clone(),
+            PendingDestructionAttestation {
+                round_height,
+                deadline,
+                reminded_at: time.now_utc(),
+            },
+        );
+    }
+
     ///
-    /// Note that this function does not check for banned participants, which is checked
-    /// during the precommit phase for the next round.
+    /// Stops expecting the attestation of the destruction of the toxic waste
+    /// of the given contributor in the given round, once received.
     ///
-    #[inline]
-    pub(super) fn is_precommit_next_round_ready(&self, time: &dyn TimeSource) -> bool {
-        // Check that the coordinator is initialized and is not already in a precommit stage.
-        if self.status == CoordinatorStatus::Initializing || self.status == CoordinatorStatus::Precommit {
-            return false;
+    pub(super) fn complete_destruction_attestation(&mut self, participant: &Participant, round_height: u64) {
+        if let Some(pending) = self.pending_destruction_attestations.get(participant) {
+            if pending.round_height == round_height {
+                self.pending_destruction_attestations.remove(participant);
+            }
         }
+    }
 
-        // Check that the queue contains participants.
-        if self.queue.is_empty() {
-            trace!("Queue is currently empty");
-            return false;
+    ///
+    /// Returns the contributors to remind of the attestation of the destruction
+    /// of their toxic waste, marked as reminded, and drops the ones past their
+    /// deadline, returned as missed.
+    ///
+    pub(super) fn update_destruction_reminders(
+        &mut self,
+        time: &dyn TimeSource,
+    ) -> (
+        Vec<(Participant, PendingDestructionAttestation)>,
+        Vec<(Participant, PendingDestructionAttestation)>,
+    ) {
+        let now = time.now_utc();
+        let (missed, pending): (HashMap<_, _>, HashMap<_, _>) =
+            std::mem::take(&mut self.pending_destruction_attestations)
+                .into_iter()
+                .partition(|(_, pending)| now >= pending.deadline);
+        self.pending_destruction_attestations = pending;
+
+        let mut reminders = vec![];
+        for (participant, pending) in self.pending_destruction_attestations.iter_mut() {
+            if pending.is_reminder_due(now) {
+                pending.reminded_at = now;
+                reminders.push((participant.clone(), pending.clone()));
+            }
         }
 
-        // Check that the current round height is set.
-        if self.current_round_height.is_none() {
-            warn!("Current round height is not set in the coordinator state");
-            return false;
-        }
+        (reminders, missed.into_iter().collect())
+    }
 
-        // Check that the current round has been aggregated.
-        if self.current_round_height() > 0 && !self.is_current_round_aggregated() {
-            trace!("Current round has not been aggregated");
-            return false;
-        }
+    ///
+    /// Returns the attempts of the verification of the contributions of the
+    /// current round which crashed the verifier, quarantined or not.
+    ///
+    #[inline]
+    pub fn verification_retries(&self) -> &[VerificationRetries] {
+        &self.verification_retries
+    }
 
-        // Check that the time to trigger the next round has been reached.
-        if let Some(metrics) = &self.current_metrics {
-            if let Some(next_round_after) = metrics.next_round_after {
-                if time.now_utc() < next_round_after {
-                    trace!("Required queue wait time has not been reached yet");
-                    return false;
-                }
-            } else {
-                trace!("Required queue wait time has not been set yet");
-                return false;
+    ///
+    /// Returns true if the verification of the given task of the current
+    /// round is quarantined, waiting for a decision of the operators.
+    ///
+    pub fn is_verification_quarantined(&self, task: &Task) -> bool {
+        self.verification_retries.iter().any(|retries| {
+            Some(retries.round_height) == self.current_round_height && retries.task == *task && retries.is_quarantined()
+        })
+    }
+
+    ///
+    /// Records an attempt of the verification of the given task which crashed
+    /// the verifier, and quarantines the task once its attempts exceed the
+    /// maximum number of retries. Returns the updated attempts of the task.
+    ///
+    pub(super) fn record_verification_crash(
+        &mut self,
+        task: &Task,
+        error: String,
+        max_retries: u32,
+        time: &dyn TimeSource,
+    ) -> Result<VerificationRetries, CoordinatorError> {
+        let round_height = self.current_round_height.ok_or(CoordinatorError::RoundHeightNotSet)?;
+        let now = time.now_utc();
+
+        // The tasks are numbered the same way in every round
+        self.verification_retries
+            .retain(|retries| retries.round_height == round_height);
+        let index = match self.verification_retries.iter().position(|retries| retries.task == *task) {
+            Some(index) => index,
+            None => {
+                self.verification_retries
+                    .push(VerificationRetries::new(round_height, *task, now));
+                self.verification_retries.len() - 1
             }
-        }
+        };
+        let retries = &mut self.verification_retries[index];
+        retries.record_crash(error, max_retries, now);
 
-        // Fetch the next round height.
-        let next_round_height = self.current_round_height.unwrap_or_default() + 1;
+        Ok(retries.clone())
+    }
 
-        // Fetch the state of assigned contributors for the next round in the queue.
-        let minimum_contributors = self.environment.minimum_contributors_per_round();
+    ///
+    /// Drops the attempts of the verification of the given task of the
+    /// current round, once verified or rejected. Returns them, if any.
+    ///
+    pub(super) fn clear_verification_retries(&mut self, task: &Task) -> Option<VerificationRetries> {
+        let round_height = self.current_round_height?;
+        let index = self
+            .verification_retries
+            .iter()
+            .position(|retries| retries.round_height == round_height && retries.task == *task)?;
+
+        Some(self.verification_retries.remove(index))
+    }
+
+    ///
+    /// Returns `true` if the given contributor lost its lock because of a
+    /// reissue of the challenges and has not restarted its contribution yet.
+    ///
+    #[inline]
+    pub fn must_restart(&self, participant: &Participant) -> bool {
+        self.restarting_contributors.contains(participant)
+    }
+
+    ///
+    /// Records the contributors whose lock was released by a reissue of the
+    /// challenges of the current round.
+    ///
+    pub(super) fn add_restarting_contributors(&mut self, participants: &[Participant]) {
+        self.restarting_contributors.extend(participants.iter().cloned());
+    }
+
+    ///
+    /// Records that the given contributor restarted its contribution on the
+    /// reissued challenge.
+    ///
+    pub(super) fn restarted_contribution(&mut self, participant: &Participant) {
+        self.restarting_contributors.remove(participant);
+    }
+
+    ///
+    /// Returns the shuffles of the queue, one per round drawn from a shuffled
+    /// queue, with the order of the queue they were applied to.
+    ///
+    #[inline]
+    pub fn queue_shuffles(&self) -> &[QueueShuffle] {
+        &self.queue_shuffles
+    }
+
+    ///
+    /// Returns the shuffle of the queue for the given round, if any.
+    ///
+    #[inline]
+    pub fn queue_shuffle(&self, round_height: u64) -> Option<&QueueShuffle> {
+        self.queue_shuffles
+            .iter()
+            .find(|shuffle| shuffle.round_height == round_height)
+    }
+
+    ///
+    /// Sets the shuffle of the queue for its round, applied by the next
+    /// updates of the queue until the round starts.
+    ///
+    pub(super) fn prepare_queue_shuffle(&mut self, shuffle: QueueShuffle) {
+        self.queue_shuffles
+            .retain(|existing| existing.round_height != shuffle.round_height);
+        self.queue_shuffles.push(shuffle);
+    }
+
+    ///
+    /// Returns the commit-reveal of the seed of the genesis challenge.
+    ///
+    #[inline]
+    pub fn genesis_seed(&self) -> &GenesisSeed {
+        &self.genesis_seed
+    }
+
+    ///
+    /// Records the commitment of an operator to its share of the seed of the
+    /// genesis challenge, and returns the state of the collection given the
+    /// number of operators.
+    ///
+    pub(super) fn commit_genesis_seed(
+        &mut self,
+        commitment: SeedCommitment,
+        required: usize,
+    ) -> Result<GenesisSeedStatus, CoordinatorError> {
+        if self.genesis_seed.commitment(&commitment.operator).is_some() {
+            return Err(CoordinatorError::GenesisSeedAlreadyCommitted);
+        }
+
+        self.genesis_seed.commitments.push(commitment);
+        Ok(self.genesis_seed.status(required))
+    }
+
+    ///
+    /// Records the reveal of the share of an operator, once every operator
+    /// committed to its share, and returns the state of the collection given
+    /// the number of operators.
+    ///
+    pub(super) fn reveal_genesis_seed(
+        &mut self,
+        reveal: SeedReveal,
+        required: usize,
+    ) -> Result<GenesisSeedStatus, CoordinatorError> {
+        match self.genesis_seed.status(required) {
+            GenesisSeedStatus::Committing { .. } => return Err(CoordinatorError::GenesisSeedCommitmentsMissing),
+            GenesisSeedStatus::Derived { .. } => return Err(CoordinatorError::GenesisSeedAlreadyRevealed),
+            GenesisSeedStatus::Revealing { .. } => (),
+        }
+
+        let commitment = self
+            .genesis_seed
+            .commitment(&reveal.operator)
+            .ok_or(CoordinatorError::GenesisSeedCommitmentsMissing)?;
+        let share = hex::decode(&reveal.share).map_err(|_| CoordinatorError::GenesisSeedInvalid)?;
+        if seed_commitment(&share) != commitment.commitment {
+            return Err(CoordinatorError::GenesisSeedInvalid);
+        }
+
+        if self.genesis_seed.has_revealed(&reveal.operator) {
+            // An operator revealing again once every share is revealed retries the derivation of the seed
+            if self.genesis_seed.reveals.len() < required {
+                return Err(CoordinatorError::GenesisSeedAlreadyRevealed);
+            }
+        } else {
+            self.genesis_seed.reveals.push(reveal);
+        }
+
+        Ok(self.genesis_seed.status(required))
+    }
+
+    ///
+    /// Sets the seed of the genesis challenge, once derived from the shares
+    /// of every operator and written in the genesis challenge.
+    ///
+    pub(super) fn set_genesis_seed(&mut self, seed: &[u8]) {
+        self.genesis_seed.seed = Some(hex::encode(seed));
+    }
+
+    ///
+    /// Returns the name of the current phase of the schedule of the
+    /// ceremony, if any.
+    ///
+    #[inline]
+    pub fn ceremony_phase(&self) -> Option<&str> {
+        self.ceremony_phase.as_deref()
+    }
+
+    ///
+    /// Sets the current phase of the schedule of the ceremony, whose
+    /// timeouts apply to the participants from now on.
+    ///
+    pub(super) fn set_ceremony_phase(&mut self, name: String, timeouts: PhaseTimeouts) {
+        self.ceremony_phase = Some(name);
+        self.phase_timeouts = timeouts;
+    }
+
+    ///
+    /// Returns the timeout of the contributors since they were last seen,
+    /// from the current phase or the environment.
+    ///
+    fn contributor_seen_timeout(&self) -> Duration {
+        match self.phase_timeouts.contributor_seen {
+            Some(secs) => Duration::seconds(secs as i64),
+            None => self.environment.contributor_seen_timeout(),
+        }
+    }
+
+    ///
+    /// Returns the timeout of the participants holding a lock, from the
+    /// current phase or the environment.
+    ///
+    fn participant_lock_timeout(&self) -> Duration {
+        match self.phase_timeouts.participant_lock {
+            Some(secs) => Duration::seconds(secs as i64),
+            None => self.environment.participant_lock_timeout(),
+        }
+    }
+
+    ///
+    /// Returns the timeout of the contributors in the queue since they were
+    /// last seen, from the current phase or the environment.
+    ///
+    fn queue_seen_timeout(&self) -> Duration {
+        match self.phase_timeouts.queue_seen {
+            Some(secs) => Duration::seconds(secs as i64),
+            None => self.environment.queue_seen_timeout(),
+        }
+    }
+
+    ///
+    /// Returns true if the tokens of the contributors are blacklisted once used, as set in the env. The identity
+    /// requirements are relaxed in rehearsals, in which the same contributors take several turns.
+    ///
+    pub(crate) fn token_blacklist(&self) -> bool {
+        *TOKEN_BLACKLIST && !self.environment.is_rehearsal()
+    }
+
+    ///
+    /// Returns true if the IPs of the contributors are banned once used, as set in the env. Never in rehearsals.
+    ///
+    pub(crate) fn ip_ban(&self) -> bool {
+        *IP_BAN && !self.environment.is_rehearsal()
+    }
+
+    ///
+    /// Returns true if the token is currently in use
+    ///
+    pub fn is_token_in_use(&self, token: &str) -> bool {
+        self.runtime_state.tokens_in_use.contains_key(token)
+    }
+
+    ///
+    /// Returns true if the token has been blacklisted
+    ///
+    pub fn is_token_blacklisted(&self, token: &str) -> bool {
+        self.blacklisted_tokens.contains_key(token)
+    }
+
+    ///
+    /// Returns `true` if all participants in the current round have no more pending chunks.
+    ///
+    #[inline]
+    pub fn is_current_round_finished(&self) -> bool {
+        // Check that all contributions have undergone verification.
+        self.pending_verification.is_empty()
+            // Check that all current contributors are finished.
+            && self.current_contributors.is_empty()
+    }
+
+    ///
+    /// Returns `true` if the current round is currently being aggregated.
+    ///
+    #[inline]
+    pub fn is_current_round_aggregating(&self) -> bool {
+        match &self.current_metrics {
+            Some(metrics) => {
+                !metrics.is_round_aggregated
+                    && metrics.started_aggregation_at.is_some()
+                    && metrics.finished_aggregation_at.is_none()
+            }
+            None => false,
+        }
+    }
+
+    ///
+    /// Returns `true` if the current round has been aggregated.
+    ///
+    #[inline]
+    pub fn is_current_round_aggregated(&self) -> bool {
+        match &self.current_metrics {
+            Some(metrics) => {
+                metrics.is_round_aggregated
+                    && metrics.started_aggregation_at.is_some()
+                    && metrics.finished_aggregation_at.is_some()
+            }
+            None => false,
+        }
+    }
+
+    ///
+    /// Returns `true` if the precommit for the next round is ready.
+    ///
+    /// This function checks that the requisite number of contributors and verifiers are
+    /// assigned for the next round.
+    ///
+    /// Note that this function does not check for banned participants, which is checked
+    /// during the precommit phase for the next round.
+    ///
+    #[inline]
+    pub(super) fn is_precommit_next_round_ready(&self, time: &dyn TimeSource) -> bool {
+        // Check that the coordinator is initialized and is not already in a precommit stage.
+        if self.status == CoordinatorStatus::Initializing || self.status == CoordinatorStatus::Precommit {
+            return false;
+        }
+
+        // Check that the queue contains participants.
+        if self.queue.is_empty() {
+            trace!("Queue is currently empty");
+            return false;
+        }
+
+        // Check that the current round height is set.
+        if self.current_round_height.is_none() {
+            warn!("Current round height is not set in the coordinator state");
+            return false;
+        }
+
+        // Check that the current round has been aggregated.
+        if self.current_round_height() > 0 && !self.is_current_round_aggregated() {
+            trace!("Current round has not been aggregated");
+            return false;
+        }
+
+        // Check that the time to trigger the next round has been reached.
+        if let Some(metrics) = &self.current_metrics {
+            if let Some(next_round_after) = metrics.next_round_after {
+                if time.now_utc() < next_round_after {
+                    trace!("Required queue wait time has not been reached yet");
+                    return false;
+                }
+            } else {
+                trace!("Required queue wait time has not been set yet");
+                return false;
+            }
+        }
+
+        // Fetch the next round height.
+        let next_round_height = self.current_round_height.unwrap_or_default() + 1;
+
+        // Fetch the state of assigned contributors for the next round in the queue.
+        let minimum_contributors = self.environment.minimum_contributors_per_round();
         let maximum_contributors = self.environment.maximum_contributors_per_round();
         let number_of_assigned_contributors = self
             .queue
-            .clone()
-            .into_par_iter()
+            .iter()
             .filter(|(p, (_, rh, _, _))| p.is_contributor() && rh.unwrap_or_default() == next_round_height)
             .count();
 
@@ -1726,6 +2539,14 @@ impl CoordinatorState {
         true
     }
 
+    ///
+    /// Returns the network of the given IP, as bucketed by the prefixes of the
+    /// environment, under which it is checked and banned.
+    ///
+    fn ip_bucket(&self, ip: &IpAddr) -> IpAddr {
+        self.environment.ip_prefixes().bucket(ip)
+    }
+
     ///
     /// Safety checks performed before adding a new contributor to the queue.
     ///
@@ -1736,7 +2557,13 @@ impl CoordinatorState {
     ) -> Result<(), CoordinatorError> {
         // Check that the pariticipant IP is not known.
         if let Some(ip) = participant_ip {
-            if *IP_BAN && (self.blacklisted_ips.contains_key(ip) || self.runtime_state.current_ips.contains_key(ip)) {
+            let ip = self.ip_bucket(ip);
+            let blacklisted_ip = self
+                .ip_retention
+                .ban_key(&ip)
+                .map_or(false, |key| self.blacklisted_ips.contains_key(&key));
+            let known_ip = blacklisted_ip || self.runtime_state.current_ips.contains_key(&ip);
+            if self.ip_ban() && known_ip {
                 return Err(CoordinatorError::ParticipantIpAlreadyAdded);
             }
         }
@@ -1756,12 +2583,8 @@ impl CoordinatorState {
             return Err(CoordinatorError::ParticipantAlreadyAdded);
         }
 
-        // Check that the participant hasn't been already seen in the past.
-        for (_, inner) in &self.finished_contributors {
-            if inner.contains_key(participant) {
-                return Err(CoordinatorError::ParticipantAlreadyAdded);
-            }
-        }
+        // Check that the participant didn't contribute already.
+        self.unique_contribution_checks(participant, None)?;
 
         match participant {
             Participant::Contributor(_) => {
@@ -1785,6 +2608,98 @@ impl CoordinatorState {
         Ok(())
     }
 
+    ///
+    /// Returns the height of the round in which the given participant contributed, if any.
+    ///
+    #[inline]
+    pub fn contributed_round(&self, participant: &Participant) -> Option<u64> {
+        self.finished_contributors
+            .iter()
+            .find(|(_, contributors)| contributors.contains_key(participant))
+            .map(|(round_height, _)| *round_height)
+    }
+
+    ///
+    /// Returns true if the given participant is exempt from the limit of one contribution per public key and per
+    /// identity, as set in the environment.
+    ///
+    #[inline]
+    pub(crate) fn is_contribution_limit_exempt(&self, participant: &Participant) -> bool {
+        self.environment
+            .contribution_limit_exempt_keys()
+            .contains(&participant.address())
+    }
+
+    ///
+    /// Returns the other participants holding the given identity, unless exempt from the limit of one contribution.
+    ///
+    fn identity_holders<'a>(
+        &'a self,
+        participant: &'a Participant,
+        claim: &'a IdentityClaim,
+    ) -> impl Iterator<Item = &'a Participant> + 'a {
+        self.identities
+            .iter()
+            .filter(move |(other, identity)| *other != participant && identity.claim == *claim)
+            .map(|(other, _)| other)
+            .filter(move |other| !self.is_contribution_limit_exempt(other))
+    }
+
+    ///
+    /// Checks that neither the given participant nor, if the identities are unique as set in the environment, another
+    /// participant with the given identity contributed to the ceremony already.
+    ///
+    pub(crate) fn unique_contribution_checks(
+        &self,
+        participant: &Participant,
+        identity: Option<&IdentityClaim>,
+    ) -> Result<(), CoordinatorError> {
+        if self.is_contribution_limit_exempt(participant) {
+            return Ok(());
+        }
+
+        if let Some(round_height) = self.contributed_round(participant) {
+            return Err(CoordinatorError::ParticipantAlreadyContributed { round_height });
+        }
+
+        if let Some(claim) = identity.filter(|_| self.environment.unique_identity()) {
+            if self
+                .identity_holders(participant, claim)
+                .any(|other| self.contributed_round(other).is_some())
+            {
+                return Err(CoordinatorError::IdentityAlreadyUsed);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that the identity claimed by the given participant joining the queue is not used by another participant,
+    /// neither in a previous round nor in the queue or in the current round, if the identities are unique as set in the
+    /// environment.
+    ///
+    pub(crate) fn unique_identity_checks(
+        &self,
+        participant: &Participant,
+        identity: &IdentityClaim,
+    ) -> Result<(), CoordinatorError> {
+        if !self.environment.unique_identity() || self.is_contribution_limit_exempt(participant) {
+            return Ok(());
+        }
+
+        let used = self.identity_holders(participant, identity).any(|other| {
+            self.contributed_round(other).is_some()
+                || self.queue.contains_key(other)
+                || self.next.contains_key(other)
+                || self.current_contributors.contains_key(other)
+        });
+        match used {
+            true => Err(CoordinatorError::IdentityAlreadyUsed),
+            false => Ok(()),
+        }
+    }
+
     ///
     /// Adds the given participant to the queue if they are permitted to participate.
     ///
@@ -1803,22 +2718,98 @@ impl CoordinatorState {
             participant.clone(),
             (reliability_score, None, time.now_utc(), time.now_utc()),
         );
+        self.participant_history
+            .record(&participant, ParticipantEvent::JoinedQueue, time.now_utc());
 
         // Add ip (if env set and if any) to the set of currently known addresses
-        if *IP_BAN {
+        if self.ip_ban() {
             if let Some(ip) = participant_ip {
+                let ip = self.ip_bucket(&ip);
                 self.runtime_state.current_ips.insert(ip, participant.clone());
             }
         }
 
         // Add token (if blacklisting) to the set of currenly known ones
-        if *TOKEN_BLACKLIST {
+        if self.token_blacklist() {
             self.runtime_state.tokens_in_use.insert(token, participant);
         }
 
         Ok(())
     }
 
+    ///
+    /// Records the outcome of the human verification of the given participant, replacing any previous one.
+    ///
+    #[inline]
+    pub(super) fn record_human_verification(&mut self, participant: Participant, verification: HumanVerification) {
+        self.human_verifications.insert(participant, verification);
+    }
+
+    ///
+    /// Returns the outcome of the human verification of the given participant, if any.
+    ///
+    #[inline]
+    pub fn human_verification(&self, participant: &Participant) -> Option<&HumanVerification> {
+        self.human_verifications.get(participant)
+    }
+
+    ///
+    /// Returns the outcomes of the human verification of all the participants.
+    ///
+    #[inline]
+    pub(super) fn human_verifications(&self) -> &HashMap<Participant, HumanVerification> {
+        &self.human_verifications
+    }
+
+    ///
+    /// Records the identity validated for the given participant, replacing any previous one.
+    ///
+    #[inline]
+    pub(super) fn record_identity(&mut self, participant: Participant, identity: VerifiedIdentity) {
+        self.identities.insert(participant, identity);
+    }
+
+    ///
+    /// Returns the identity claimed by the given participant, if any.
+    ///
+    #[inline]
+    pub fn identity(&self, participant: &Participant) -> Option<&VerifiedIdentity> {
+        self.identities.get(participant)
+    }
+
+    ///
+    /// Returns the identities claimed by all the participants.
+    ///
+    #[inline]
+    pub(super) fn identities(&self) -> &HashMap<Participant, VerifiedIdentity> {
+        &self.identities
+    }
+
+    ///
+    /// Returns `true` if the ceremony has not been launched yet: no contributor
+    /// has been assigned to a round.
+    ///
+    #[inline]
+    pub fn is_prelaunch(&self) -> bool {
+        self.current_round_height() <= 1
+            && self.current_contributors.is_empty()
+            && self.finished_contributors.values().all(HashMap::is_empty)
+    }
+
+    ///
+    /// Restores the allowlist of tokens and the tokens and addresses reserved by the
+    /// contributors in the queue, which are not persisted with the state, from the
+    /// given pre-launch snapshot.
+    ///
+    #[inline]
+    pub(super) fn restore_prelaunch_runtime(&mut self, prelaunch: &PrelaunchSnapshot) {
+        self.runtime_state = RuntimeState {
+            tokens: prelaunch.allowlist.clone(),
+            tokens_in_use: prelaunch.reserved_tokens.clone(),
+            current_ips: prelaunch.reserved_ips.clone(),
+        };
+    }
+
     ///
     /// Removes the given participant from the queue.
     ///
@@ -2045,61 +3036,363 @@ impl CoordinatorState {
             return Err(CoordinatorError::ChunkIdInvalid);
         }
 
-        warn!(
-            "Disposing chunk {} contribution {} from {}",
-            chunk_id, contribution_id, participant
-        );
+        warn!(
+            "Disposing chunk {} contribution {} from {}",
+            chunk_id, contribution_id, participant
+        );
+
+        match participant {
+            Participant::Contributor(_) => match self.current_contributors.get_mut(participant) {
+                // Move the disposing task to the list of disposed tasks for the contributor.
+                Some(participant) => participant.dispose_task(chunk_id, contribution_id, time),
+                None => Err(CoordinatorError::ParticipantNotFound(participant.clone())),
+            },
+            Participant::Verifier(_) => match self.current_verifiers.get_mut(participant) {
+                // Move the disposing task to the list of disposed tasks for the verifier.
+                Some(participant) => participant.dispose_task(chunk_id, contribution_id, time),
+                None => Err(CoordinatorError::ParticipantNotFound(participant.clone())),
+            },
+        }
+    }
+
+    ///
+    /// Adds the given (chunk ID, contribution ID) task to the pending verification set.
+    /// The verification task is then assigned to the verifier with the least number of tasks in its queue.
+    ///
+    #[inline]
+    pub(super) fn add_pending_verification(&mut self, task: &Task) -> Result<(), CoordinatorError> {
+        // Check that the chunk ID is valid.
+        if task.chunk_id() > self.environment.number_of_chunks() {
+            return Err(CoordinatorError::ChunkIdInvalid);
+        }
+
+        // Check that the pending verification set does not already contain the chunk ID.
+        if self.pending_verification.contains_key(task) {
+            return Err(CoordinatorError::ChunkIdAlreadyAdded);
+        }
+
+        let verifier = self
+            .environment
+            .coordinator_verifiers()
+            .first()
+            .ok_or_else(|| CoordinatorError::VerifierMissing)?
+            .clone();
+
+        info!(
+            "Adding (chunk {}, contribution {}) to pending verifications",
+            task.chunk_id(),
+            task.contribution_id(),
+        );
+
+        self.pending_verification.insert(task.clone(), verifier.clone());
+
+        Ok(())
+    }
+
+    pub fn get_pending_verifications(&self) -> &HashMap<Task, Participant> {
+        &self.pending_verification
+    }
+
+    ///
+    /// Records the vote of a verifier on the given task pending verification in the
+    /// current round, and returns the outcome of the votes collected so far.
+    ///
+    pub(super) fn add_verification_vote(
+        &mut self,
+        task: &Task,
+        vote: VerificationVote,
+    ) -> Result<QuorumStatus, CoordinatorError> {
+        let round_height = self.current_round_height.ok_or(CoordinatorError::RoundHeightNotSet)?;
+        if !self.pending_verification.contains_key(task) {
+            return Err(CoordinatorError::VerificationNotPending);
+        }
+
+        let index = match self
+            .verification_quorums
+            .iter()
+            .position(|quorum| quorum.round_height == round_height && quorum.task() == *task)
+        {
+            Some(index) => index,
+            None => {
+                self.verification_quorums
+                    .push(VerificationQuorum::new(round_height, task));
+                self.verification_quorums.len() - 1
+            }
+        };
+
+        let quorum = &mut self.verification_quorums[index];
+        if !quorum.add_vote(vote) {
+            return Err(CoordinatorError::VerifierAlreadyVoted);
+        }
+
+        Ok(quorum.status(self.environment.verification_quorum()))
+    }
+
+    ///
+    /// Returns the votes of the verifiers on the given task of the given round, if any.
+    ///
+    pub fn verification_quorum(&self, round_height: u64, task: &Task) -> Option<&VerificationQuorum> {
+        self.verification_quorums
+            .iter()
+            .find(|quorum| quorum.round_height == round_height && quorum.task() == *task)
+    }
+
+    ///
+    /// Returns the votes of the verifiers on all the contributions of the given round.
+    ///
+    pub fn verification_quorums(&self, round_height: u64) -> Vec<VerificationQuorum> {
+        self.verification_quorums
+            .iter()
+            .filter(|quorum| quorum.round_height == round_height)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops the votes on the contributions of the given round and the later ones, which are discarded by a reset.
+    fn take_verification_quorums_before(&mut self, round_height: u64) -> Vec<VerificationQuorum> {
+        std::mem::take(&mut self.verification_quorums)
+            .into_iter()
+            .filter(|quorum| quorum.round_height < round_height)
+            .collect()
+    }
+
+    ///
+    /// Records the approval of an operator on the injection of the given contribution in the
+    /// current round, and returns the state of the injection.
+    ///
+    pub(super) fn approve_manual_contribution(
+        &mut self,
+        contribution: ManualContribution,
+        approval: OperatorApproval,
+    ) -> Result<InjectionStatus, CoordinatorError> {
+        let round_height = self.current_round_height.ok_or(CoordinatorError::RoundHeightNotSet)?;
+        if contribution.round_height != round_height {
+            return Err(CoordinatorError::RoundHeightMismatch);
+        }
+
+        let index = match self
+            .manual_injections
+            .iter()
+            .position(|injection| injection.contribution == contribution)
+        {
+            Some(index) => index,
+            None => {
+                self.manual_injections.push(ManualInjection::new(contribution));
+                self.manual_injections.len() - 1
+            }
+        };
+
+        let injection = &mut self.manual_injections[index];
+        if injection.status() == InjectionStatus::Injected {
+            return Err(CoordinatorError::ManualContributionAlreadyInjected);
+        }
+        // An operator approving again an approved contribution retries its injection
+        if !injection.add_approval(approval) && injection.status() != InjectionStatus::Approved {
+            return Err(CoordinatorError::OperatorAlreadyApproved);
+        }
+
+        Ok(injection.status())
+    }
+
+    ///
+    /// Returns the injection of the given contribution, if any operator approved it.
+    ///
+    pub fn manual_injection(&self, contribution: &ManualContribution) -> Option<&ManualInjection> {
+        self.manual_injections
+            .iter()
+            .find(|injection| &injection.contribution == contribution)
+    }
+
+    ///
+    /// Marks the given approved contribution as injected.
+    ///
+    pub(super) fn set_manual_contribution_injected(
+        &mut self,
+        contribution: &ManualContribution,
+        time: &dyn TimeSource,
+    ) -> Result<(), CoordinatorError> {
+        let injection = self
+            .manual_injections
+            .iter_mut()
+            .find(|injection| &injection.contribution == contribution)
+            .ok_or(CoordinatorError::ManualContributionNotApproved)?;
+
+        match injection.status() {
+            InjectionStatus::Approved => {
+                injection.injected_at = Some(time.now_utc());
+                Ok(())
+            }
+            InjectionStatus::Injected => Err(CoordinatorError::ManualContributionAlreadyInjected),
+            InjectionStatus::Pending { .. } => Err(CoordinatorError::ManualContributionNotApproved),
+        }
+    }
+
+    ///
+    /// Returns the contributions injected manually in the given round.
+    ///
+    pub fn manual_injections(&self, round_height: u64) -> Vec<ManualInjection> {
+        self.manual_injections
+            .iter()
+            .filter(|injection| injection.contribution.round_height == round_height)
+            .filter(|injection| injection.status() == InjectionStatus::Injected)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops the injections of the given round and the later ones, which are discarded by a reset.
+    fn take_manual_injections_before(&mut self, round_height: u64) -> Vec<ManualInjection> {
+        std::mem::take(&mut self.manual_injections)
+            .into_iter()
+            .filter(|injection| injection.contribution.round_height < round_height)
+            .collect()
+    }
+
+    ///
+    /// Records the attestation of a verifier on a contribution it verified.
+    ///
+    pub(super) fn add_verifier_attestation(&mut self, attestation: VerifierAttestation) {
+        self.verifier_attestations.push(attestation);
+    }
+
+    ///
+    /// Returns the attestations of the verifiers on the contributions of the given round.
+    ///
+    pub fn verifier_attestations(&self, round_height: u64) -> Vec<VerifierAttestation> {
+        self.verifier_attestations
+            .iter()
+            .filter(|attestation| attestation.round_height == round_height)
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Queues the given contributions of completed rounds to be verified again, skipping the ones already queued.
+    /// Returns the newly queued ones.
+    ///
+    pub(super) fn queue_reverifications(&mut self, tasks: Vec<ReverificationTask>) -> Vec<ReverificationTask> {
+        let mut queued = Vec::new();
+        for task in tasks {
+            if !self.reverification_queue.contains(&task) {
+                self.reverification_queue.push_back(task);
+                queued.push(task);
+            }
+        }
+
+        queued
+    }
+
+    ///
+    /// Returns the next contribution waiting to be verified again, if any.
+    ///
+    pub(super) fn next_reverification(&self) -> Option<ReverificationTask> {
+        self.reverification_queue.front().copied()
+    }
+
+    ///
+    /// Removes the re-verified contribution from the queue and records its report, replacing the one of its previous
+    /// re-verification.
+    ///
+    pub(super) fn complete_reverification(&mut self, report: ReverificationReport) {
+        let task = report.task();
+        self.reverification_queue.retain(|queued| *queued != task);
+        self.reverification_reports.retain(|previous| previous.task() != task);
+        self.reverification_reports.push(report);
+    }
 
-        match participant {
-            Participant::Contributor(_) => match self.current_contributors.get_mut(participant) {
-                // Move the disposing task to the list of disposed tasks for the contributor.
-                Some(participant) => participant.dispose_task(chunk_id, contribution_id, time),
-                None => Err(CoordinatorError::ParticipantNotFound(participant.clone())),
-            },
-            Participant::Verifier(_) => match self.current_verifiers.get_mut(participant) {
-                // Move the disposing task to the list of disposed tasks for the verifier.
-                Some(participant) => participant.dispose_task(chunk_id, contribution_id, time),
-                None => Err(CoordinatorError::ParticipantNotFound(participant.clone())),
-            },
-        }
+    ///
+    /// Returns the number of contributions waiting to be verified, for the
+    /// first time or again.
+    ///
+    pub fn verification_backlog(&self) -> usize {
+        self.pending_verification.len() + self.reverification_queue.len()
     }
 
     ///
-    /// Adds the given (chunk ID, contribution ID) task to the pending verification set.
-    /// The verification task is then assigned to the verifier with the least number of tasks in its queue.
+    /// Returns the contributions waiting to be verified again, in order.
     ///
-    #[inline]
-    pub(super) fn add_pending_verification(&mut self, task: &Task) -> Result<(), CoordinatorError> {
-        // Check that the chunk ID is valid.
-        if task.chunk_id() > self.environment.number_of_chunks() {
-            return Err(CoordinatorError::ChunkIdInvalid);
-        }
+    pub fn reverification_queue(&self) -> Vec<ReverificationTask> {
+        self.reverification_queue.iter().copied().collect()
+    }
 
-        // Check that the pending verification set does not already contain the chunk ID.
-        if self.pending_verification.contains_key(task) {
-            return Err(CoordinatorError::ChunkIdAlreadyAdded);
-        }
+    ///
+    /// Returns the reports of the latest re-verification of the contributions of completed rounds.
+    ///
+    pub fn reverification_reports(&self) -> &[ReverificationReport] {
+        &self.reverification_reports
+    }
 
-        let verifier = self
-            .environment
-            .coordinator_verifiers()
-            .first()
-            .ok_or_else(|| CoordinatorError::VerifierMissing)?
-            .clone();
+    ///
+    /// Records the proof of computation time of the contribution of a
+    /// contributor, in place of the one it sent before for the same round.
+    ///
+    pub(super) fn add_computation_proof(&mut self, record: ComputationProofRecord) {
+        self.computation_proofs.retain(|recorded| {
+            recorded.round_height != record.round_height || recorded.contributor != record.contributor
+        });
+        self.computation_proofs.push(record);
+    }
 
-        info!(
-            "Adding (chunk {}, contribution {}) to pending verifications",
-            task.chunk_id(),
-            task.contribution_id(),
-        );
+    ///
+    /// Returns the proofs of computation time of the contributions of the given round.
+    ///
+    pub fn computation_proofs(&self, round_height: u64) -> Vec<ComputationProofRecord> {
+        self.computation_proofs
+            .iter()
+            .filter(|record| record.round_height == round_height)
+            .cloned()
+            .collect()
+    }
 
-        self.pending_verification.insert(task.clone(), verifier.clone());
+    ///
+    /// Returns the statistics of the time spent computing the contributions
+    /// of the finished contributors, with their proofs of computation time.
+    ///
+    pub fn computation_stats(&self) -> ComputationStats {
+        let contributions = self
+            .finished_contributors
+            .iter()
+            .flat_map(|(round_height, contributors)| {
+                contributors
+                    .iter()
+                    .map(move |(participant, info)| (*round_height, participant, info))
+            })
+            .filter_map(|(round_height, participant, info)| {
+                let seconds = (info.finished_at? - info.started_at?).whole_seconds().max(0) as u64;
+                let contributor = participant.address();
+                let proof = self
+                    .computation_proofs
+                    .iter()
+                    .find(|record| record.round_height == round_height && record.contributor == contributor)
+                    .map(|record| &record.proof);
+
+                Some(ContributionComputation {
+                    round_height,
+                    contributor,
+                    seconds,
+                    iterations: proof.map(|proof| proof.iterations()),
+                    proof_elapsed_ms: proof.map(|proof| proof.elapsed_ms()),
+                    anomalies: Vec::new(),
+                })
+            })
+            .collect();
 
-        Ok(())
+        ComputationStats::new(contributions)
     }
 
-    pub fn get_pending_verifications(&self) -> &HashMap<Task, Participant> {
-        &self.pending_verification
+    /// Drops the proofs of computation time of the given round and the later ones, which are discarded by a reset.
+    fn take_computation_proofs_before(&mut self, round_height: u64) -> Vec<ComputationProofRecord> {
+        std::mem::take(&mut self.computation_proofs)
+            .into_iter()
+            .filter(|record| record.round_height < round_height)
+            .collect()
+    }
+
+    /// Drops the attestations of the given round and the later ones, which are discarded by a reset.
+    fn take_verifier_attestations_before(&mut self, round_height: u64) -> Vec<VerifierAttestation> {
+        std::mem::take(&mut self.verifier_attestations)
+            .into_iter()
+            .filter(|attestation| attestation.round_height < round_height)
+            .collect()
     }
 
     ///
@@ -2629,6 +3922,8 @@ impl CoordinatorState {
 
         // Drop the participant from the queue, precommit, and current round.
         let drop = self.drop_participant(participant, time)?;
+        self.participant_history
+            .record(participant, ParticipantEvent::Banned, time.now_utc());
 
         // Add the participant to the banned list.
         self.banned.insert(participant.clone());
@@ -2704,6 +3999,8 @@ impl CoordinatorState {
             bucket_id,
             time,
         );
+        participant_info.human_verification = self.human_verifications.get(&next_contributor).cloned();
+        participant_info.identity = self.identities.get(&next_contributor).cloned();
         participant_info.start(tasks, time)?;
         trace!("{:?}", participant_info);
         self.current_contributors
@@ -2757,50 +4054,40 @@ impl CoordinatorState {
             _ => return Err(CoordinatorError::RoundHeightNotSet),
         };
 
-        // Sort the participants in the queue by time joined.
-        let mut queue: Vec<_> = self
-            .queue
-            .clone()
-            .into_par_iter()
-            .map(|(p, (r, _, ls, j))| (p, r, ls, j))
-            .collect();
-        queue.par_sort_by(|a, b| (a.3).cmp(&b.3));
-
-        // Parse the queue participants into contributors and verifiers,
-        // and check that they are not banned participants.
-        let contributors: Vec<(_, _, _, _)> = queue
-            .clone()
-            .into_par_iter()
-            .filter(|(p, _, _, _)| p.is_contributor() && !self.banned.contains(&p))
-            .collect();
+        // Keep the contributors of the queue which are not banned participants.
+        let banned = &self.banned;
+        self.queue.retain(|participant, _| participant.is_contributor() && !banned.contains(participant));
 
         // Fetch the permitted number of contributors
         let maximum_contributors = self.environment.maximum_contributors_per_round();
 
-        // Initialize the updated queue.
-        let mut updated_queue = HashMap::with_capacity(contributors.len());
-
-        // Update assigned round height for each contributor.
-        for (index, round) in contributors.chunks(maximum_contributors).enumerate() {
-            for (contributor, reliability, last_seen, joined) in round.into_iter() {
-                let assigned_round = next_round + index as u64;
-                trace!(
-                    "Assigning contributor {} who joined at {} with reliability {} in queue to round {}",
-                    contributor,
-                    joined,
-                    reliability,
-                    assigned_round
-                );
-                updated_queue.insert(
-                    contributor.clone(),
-                    (*reliability, Some(assigned_round), *last_seen, *joined),
-                );
+        // Update assigned round height for each contributor, in order of joining or, if the queue
+        // of the next round is shuffled, in the order of the shuffle.
+        let order = match self
+            .queue_shuffles
+            .iter_mut()
+            .find(|shuffle| shuffle.round_height == next_round)
+        {
+            Some(shuffle) => {
+                shuffle.queue = self.queue.iter().map(|(participant, _)| participant.clone()).collect();
+                shuffle.order(self.queue.len())
             }
+            None => (0..self.queue.len()).collect(),
+        };
+        let mut entries: Vec<_> = self.queue.iter_mut().collect();
+        for (position, index) in order.into_iter().enumerate() {
+            let (contributor, (reliability, assigned_round, _, joined)) = &mut entries[index];
+            let round = next_round + (position / maximum_contributors) as u64;
+            trace!(
+                "Assigning contributor {} who joined at {} with reliability {} in queue to round {}",
+                contributor,
+                joined,
+                reliability,
+                round
+            );
+            *assigned_round = Some(round);
         }
 
-        // Set the queue to the updated queue.
-        self.queue = updated_queue;
-
         Ok(())
     }
 
@@ -2850,6 +4137,16 @@ impl CoordinatorState {
 
         trace!("Marking {} current contributors as finished", newly_finished.len());
 
+        for contributor in newly_finished.keys() {
+            self.participant_history.record(
+                contributor,
+                ParticipantEvent::Contributed {
+                    round_height: current_round_height,
+                },
+                time.now_utc(),
+            );
+        }
+
         // Update the map of finished contributors.
         match self.finished_contributors.get_mut(&current_round_height) {
             Some(contributors) => contributors.extend(newly_finished.into_iter()),
@@ -2876,13 +4173,15 @@ impl CoordinatorState {
     }
 
     pub(super) fn update_dropped_queued_participants(&mut self, time: &dyn TimeSource) -> Result<(), CoordinatorError> {
-        let queue_seen_timeout = self.environment.queue_seen_timeout();
+        let queue_seen_timeout = self.queue_seen_timeout();
 
         let now = time.now_utc();
 
         for (participant, (_, _, last_seen, _)) in self.queue.clone() {
             if now - last_seen > queue_seen_timeout {
                 let _ = self.drop_participant(&participant, time)?;
+                self.participant_history
+                    .record(&participant, ParticipantEvent::TimedOut, now);
             }
         }
 
@@ -2898,16 +4197,24 @@ impl CoordinatorState {
         time: &dyn TimeSource,
     ) -> Result<Vec<DropParticipant>, CoordinatorError> {
         // Fetch the timeout threshold for contributors.
-        let participant_lock_timeout = self.environment.participant_lock_timeout();
+        let participant_lock_timeout = self.participant_lock_timeout();
 
         // Fetch the current time.
         let now = time.now_utc();
 
+        // Fetch the timeout threshold for contributors computing offline.
+        let offline_contribution_timeout = self.environment.offline_contribution_timeout();
+
         self.current_contributors
             .clone()
             .iter()
             .chain(self.current_verifiers.clone().iter())
             .filter_map(|(participant, participant_info)| {
+                let participant_lock_timeout = match participant_info.offline_since {
+                    Some(_) => offline_contribution_timeout,
+                    None => participant_lock_timeout,
+                };
+
                 // Check timeout on lock
                 let exceeded_chunk_names: Vec<String> = participant_info
                     .locked_chunks
@@ -2941,6 +4248,8 @@ impl CoordinatorState {
                         participant_lock_timeout.whole_seconds(),
                         exceeded_chunks_string,
                     );
+                    self.participant_history
+                        .record(participant, ParticipantEvent::TimedOut, now);
                     Some(self.drop_participant(participant, time))
                 } else if !self.is_coordinator_contributor(&participant) && exceeded_round_timeout {
                     tracing::warn!(
@@ -2949,6 +4258,8 @@ impl CoordinatorState {
                         participant,
                         participant_lock_timeout.whole_seconds(),
                     );
+                    self.participant_history
+                        .record(participant, ParticipantEvent::TimedOut, now);
                     Some(self.drop_participant(participant, time))
                 } else {
                     None
@@ -2965,7 +4276,8 @@ impl CoordinatorState {
         time: &dyn TimeSource,
     ) -> Result<Vec<DropParticipant>, CoordinatorError> {
         // Fetch the timeout threshold for contributors.
-        let contributor_seen_timeout = self.environment.contributor_seen_timeout();
+        let contributor_seen_timeout = self.contributor_seen_timeout();
+        let offline_contribution_timeout = self.environment.offline_contribution_timeout();
 
         // Fetch the current time.
         let now = time.now_utc();
@@ -2974,6 +4286,12 @@ impl CoordinatorState {
             .clone()
             .iter()
             .filter_map(|(participant, participant_info)| {
+                // Contributors computing offline may not send heartbeats while transferring the files manually.
+                let contributor_seen_timeout = match participant_info.offline_since {
+                    Some(_) => offline_contribution_timeout,
+                    None => contributor_seen_timeout,
+                };
+
                 // Fetch the elapsed time.
                 let elapsed = now - participant_info.last_seen;
 
@@ -2987,6 +4305,8 @@ impl CoordinatorState {
                         elapsed.whole_seconds()
                     );
                     // Drop the participant.
+                    self.participant_history
+                        .record(participant, ParticipantEvent::TimedOut, now);
                     Some(self.drop_participant(participant, time))
                 } else {
                     None
@@ -3017,6 +4337,17 @@ impl CoordinatorState {
                 if count > self.environment.participant_ban_threshold() as usize {
                     self.banned.insert(participant_info.id.clone());
 
+                    // The ban is recorded at the time of the last drop of the participant.
+                    let last_dropped_at = self
+                        .dropped
+                        .iter()
+                        .filter(|dropped| dropped.id == participant_info.id)
+                        .filter_map(|dropped| dropped.dropped_at)
+                        .max()
+                        .unwrap_or(participant_info.last_seen);
+                    self.participant_history
+                        .record(&participant_info.id, ParticipantEvent::Banned, last_dropped_at);
+
                     debug!("{} is being banned", participant_info.id);
                 }
             }
@@ -3208,9 +4539,8 @@ impl CoordinatorState {
         // Parse the queued participants for the next round and split into contributors and verifiers.
         let mut contributors: Vec<(_, (_, _, _, _))> = self
             .queue
-            .clone()
-            .into_par_iter()
-            .map(|(p, (r, rh, ls, j))| (p, (r, rh.unwrap_or_default(), ls, j)))
+            .iter()
+            .map(|(p, (r, rh, ls, j))| (p.clone(), (*r, rh.unwrap_or_default(), *ls, *j)))
             .filter(|(p, (_, rh, _, _))| p.is_contributor() && *rh == next_round_height)
             .collect();
 
@@ -3299,6 +4629,8 @@ impl CoordinatorState {
                 // Initialize the participant info for the contributor.
                 let mut participant_info =
                     ParticipantInfo::new(participant.clone(), next_round_height, reliability, bucket_id, time);
+                participant_info.human_verification = self.human_verifications.get(&participant).cloned();
+                participant_info.identity = self.identities.get(&participant).cloned();
                 participant_info.start(tasks, time)?;
 
                 // Check that the chunk IDs are set in the participant information.
@@ -3357,6 +4689,9 @@ impl CoordinatorState {
         // Set the current status to the commit.
         self.status = CoordinatorStatus::Commit;
 
+        // The reissued challenges belong to the previous round.
+        self.restarting_contributors.clear();
+
         // Add all participants from next to current.
         let mut number_of_contributors = 0;
         let mut number_of_verifiers = 0;
@@ -3471,8 +4806,7 @@ impl CoordinatorState {
         // Parse the queue for assigned contributors and verifiers of the next round.
         let number_of_assigned_contributors = self
             .queue
-            .clone()
-            .into_par_iter()
+            .iter()
             .filter(|(p, (_, rh, _, _))| p.is_contributor() && rh.unwrap_or_default() == next_round_height)
             .count();
 
@@ -3528,25 +4862,12 @@ impl CoordinatorState {
             return Ok(());
         }
 
-        let info = self
-            .current_contributors
-            .iter_mut()
-            .find(|(p, _info)| *p == participant)
-            .map(|(_p, info)| info);
-
-        let info = match info {
+        let info = match self.current_contributors.get_mut(participant) {
             Some(info) => Some(info),
             None => self
                 .finished_contributors
-                .iter_mut()
-                .map(|(_round, finished_contributors)| {
-                    finished_contributors
-                        .iter_mut()
-                        .find(|(p, _info)| *p == participant)
-                        .map(|(_p, info)| info)
-                })
-                .next()
-                .flatten(),
+                .values_mut()
+                .find_map(|finished_contributors| finished_contributors.get_mut(participant)),
         };
 
         if let Some(info) = info {
@@ -3650,6 +4971,9 @@ mod tests {
     use crate::{
         coordinator_state::*,
         environment::{Parameters, Testing},
+        ip_prefix::IpPrefixes,
+        ip_retention::{hash_ip, IP_HASH_SALT},
+        objects::DESTRUCTION_REMINDER_INTERVAL,
         testing::prelude::*,
         CoordinatorState, MockTimeSource, SystemTimeSource,
     };
@@ -3730,6 +5054,187 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unique_contribution() {
+        let time = SystemTimeSource::new();
+        let environment = TEST_ENVIRONMENT.clone();
+
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let contributor_2 = TEST_CONTRIBUTOR_ID_2.clone();
+
+        // Initialize a new coordinator state.
+        let mut state = CoordinatorState::new(environment.clone());
+        let current_round_height = 5;
+        state.initialize(current_round_height);
+        assert_eq!(None, state.contributed_round(&contributor_1));
+        assert!(state.add_to_queue_checks(&contributor_1, None).is_ok());
+
+        // The first contributor finished in the current round.
+        state.finished_contributors.insert(
+            current_round_height,
+            vec![(
+                contributor_1.clone(),
+                ParticipantInfo::new(contributor_1.clone(), current_round_height, 10, 0, &time),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(Some(current_round_height), state.contributed_round(&contributor_1));
+        match state.add_to_queue_checks(&contributor_1, None) {
+            Err(CoordinatorError::ParticipantAlreadyContributed { round_height }) => {
+                assert_eq!(current_round_height, round_height)
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+        assert!(state.unique_contribution_checks(&contributor_1, None).is_err());
+
+        // The second contributor is unaffected.
+        assert_eq!(None, state.contributed_round(&contributor_2));
+        assert!(state.unique_contribution_checks(&contributor_2, None).is_ok());
+    }
+
+    #[test]
+    fn test_unique_identity() {
+        let time = SystemTimeSource::new();
+        let environment: Environment = Testing::from(Parameters::Test8Chunks).unique_identity(true).into();
+
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let contributor_2 = TEST_CONTRIBUTOR_ID_2.clone();
+        let identity = VerifiedIdentity {
+            claim: IdentityClaim::Did {
+                did: String::from("did:web:university.example:alice"),
+            },
+            resolver: String::from("did-web"),
+            issuer: None,
+            verified_at: OffsetDateTime::now_utc(),
+        };
+        let other_claim = IdentityClaim::Did {
+            did: String::from("did:web:university.example:bob"),
+        };
+
+        // Initialize a new coordinator state.
+        let mut state = CoordinatorState::new(environment);
+        let current_round_height = 5;
+        state.initialize(current_round_height);
+        state.record_identity(contributor_1.clone(), identity.clone());
+
+        // The identity is used once the first contributor is in the current round.
+        state.current_contributors.insert(
+            contributor_1.clone(),
+            ParticipantInfo::new(contributor_1.clone(), current_round_height, 10, 0, &time),
+        );
+        assert!(matches!(
+            state.unique_identity_checks(&contributor_2, &identity.claim),
+            Err(CoordinatorError::IdentityAlreadyUsed)
+        ));
+        assert!(state.unique_identity_checks(&contributor_2, &other_claim).is_ok());
+        assert!(
+            state
+                .unique_contribution_checks(&contributor_2, Some(&identity.claim))
+                .is_ok()
+        );
+
+        // The first contributor finished in the current round.
+        let info = state.current_contributors.remove(&contributor_1).unwrap();
+        state.finished_contributors.insert(
+            current_round_height,
+            vec![(contributor_1.clone(), info)].into_iter().collect(),
+        );
+        assert!(matches!(
+            state.unique_identity_checks(&contributor_2, &identity.claim),
+            Err(CoordinatorError::IdentityAlreadyUsed)
+        ));
+        assert!(matches!(
+            state.unique_contribution_checks(&contributor_2, Some(&identity.claim)),
+            Err(CoordinatorError::IdentityAlreadyUsed)
+        ));
+        assert!(
+            state
+                .unique_contribution_checks(&contributor_2, Some(&other_claim))
+                .is_ok()
+        );
+
+        // The identities can be reused unless unique.
+        state.environment = TEST_ENVIRONMENT.clone();
+        assert!(state.unique_identity_checks(&contributor_2, &identity.claim).is_ok());
+        assert!(
+            state
+                .unique_contribution_checks(&contributor_2, Some(&identity.claim))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_contribution_limit_exempt() {
+        let time = SystemTimeSource::new();
+
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let contributor_2 = TEST_CONTRIBUTOR_ID_2.clone();
+        let environment: Environment = Testing::from(Parameters::Test8Chunks)
+            .unique_identity(true)
+            .contribution_limit_exempt_keys(&[contributor_1.address()])
+            .into();
+        let identity = VerifiedIdentity {
+            claim: IdentityClaim::Did {
+                did: String::from("did:web:university.example:alice"),
+            },
+            resolver: String::from("did-web"),
+            issuer: None,
+            verified_at: OffsetDateTime::now_utc(),
+        };
+
+        // Initialize a new coordinator state.
+        let mut state = CoordinatorState::new(environment);
+        let current_round_height = 5;
+        state.initialize(current_round_height);
+        state.record_identity(contributor_1.clone(), identity.clone());
+        state.record_identity(contributor_2.clone(), identity.clone());
+        assert!(state.is_contribution_limit_exempt(&contributor_1));
+        assert!(!state.is_contribution_limit_exempt(&contributor_2));
+
+        // The exempt contributor finished in the current round.
+        state.finished_contributors.insert(
+            current_round_height,
+            vec![(
+                contributor_1.clone(),
+                ParticipantInfo::new(contributor_1.clone(), current_round_height, 10, 0, &time),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        // The exempt contributor can contribute again, with the same identity.
+        assert!(state.add_to_queue_checks(&contributor_1, None).is_ok());
+        assert!(
+            state
+                .unique_contribution_checks(&contributor_1, Some(&identity.claim))
+                .is_ok()
+        );
+        assert!(state.unique_identity_checks(&contributor_1, &identity.claim).is_ok());
+
+        // The identity is not used by the contribution of the exempt contributor.
+        assert!(state.unique_identity_checks(&contributor_2, &identity.claim).is_ok());
+        assert!(
+            state
+                .unique_contribution_checks(&contributor_2, Some(&identity.claim))
+                .is_ok()
+        );
+
+        // The other contributor is still limited to one contribution.
+        state
+            .finished_contributors
+            .get_mut(&current_round_height)
+            .unwrap()
+            .insert(
+                contributor_2.clone(),
+                ParticipantInfo::new(contributor_2.clone(), current_round_height, 10, 0, &time),
+            );
+        assert!(matches!(
+            state.unique_contribution_checks(&contributor_2, None),
+            Err(CoordinatorError::ParticipantAlreadyContributed { .. })
+        ));
+    }
+
     #[test]
     fn test_add_duplicate_ip_to_queue_contributor() {
         let time = SystemTimeSource::new();
@@ -3773,13 +5278,238 @@ mod tests {
         state.drop_participant(&contributor_1, &time).unwrap();
 
         // Verify the IP still exists as one participant associated with it is left in the queue.
-        assert!(state.blacklisted_ips.contains_key(&contributor_ip));
+        assert!(state.blacklisted_ips.contains_key(&contributor_ip.to_string()));
 
         // Drop the second participant.
         state.drop_participant(&contributor_2, &time).unwrap();
 
         // Verify the IP has been deleted.
-        assert!(!state.blacklisted_ips.contains_key(&contributor_ip));
+        assert!(!state.blacklisted_ips.contains_key(&contributor_ip.to_string()));
+    }
+
+    #[test]
+    fn test_blacklist_ipv6_network() {
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let ip_1: IpAddr = "2001:db8:1:2::1".parse().unwrap();
+        let ip_2: IpAddr = "2001:db8:1:2:a:b:c:d".parse().unwrap();
+
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        state.initialize(1);
+        let bucket = state.ip_bucket(&ip_1);
+        state.runtime_state.current_ips.insert(bucket, contributor_1.clone());
+        state.blacklist_participant_ip(&contributor_1).unwrap();
+
+        // Another address of the /64 network of the contributor is banned as well.
+        let key = IpRetention::Full.ban_key(&state.ip_bucket(&ip_2)).unwrap();
+        assert_eq!(Some(&contributor_1), state.blacklisted_ips.get(&key));
+
+        // Unless the IPv6 addresses are bucketed by themselves.
+        let environment: Environment = Testing::from(Parameters::Test8Chunks)
+            .ip_prefixes(IpPrefixes::new(32, 128).unwrap())
+            .into();
+        let state = CoordinatorState::new(environment);
+        assert_ne!(state.ip_bucket(&ip_1), state.ip_bucket(&ip_2));
+    }
+
+    #[test]
+    fn test_redact_ips() {
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let contributor_2 = TEST_CONTRIBUTOR_ID_2.clone();
+        let ip_1 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let ip_2 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+        // Blacklist the IP of the first contributor under the full retention policy.
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        state.redact_ips(IpRetention::Full);
+        state.runtime_state.current_ips.insert(ip_1, contributor_1.clone());
+        state.blacklist_participant_ip(&contributor_1).unwrap();
+        assert!(state.blacklisted_ips.contains_key(&ip_1.to_string()));
+
+        // Switching to hashes redacts the stored IP, which stays banned.
+        assert_eq!(1, state.redact_ips(IpRetention::Hashed));
+        assert!(!state.blacklisted_ips.contains_key(&ip_1.to_string()));
+        assert_eq!(Some(&contributor_1), state.blacklisted_ips.get(&hash_ip(&ip_1, &IP_HASH_SALT)));
+        assert_eq!(0, state.redact_ips(IpRetention::Hashed));
+
+        // Under the location policy, only the location of the next IP is kept.
+        assert_eq!(1, state.redact_ips(IpRetention::Location));
+        assert!(state.blacklisted_ips.is_empty());
+        state.runtime_state.current_ips.insert(ip_2, contributor_2.clone());
+        state.blacklist_participant_ip(&contributor_2).unwrap();
+        assert!(state.blacklisted_ips.is_empty());
+        assert_eq!(Some(&String::from("unknown")), state.ip_locations().get(&contributor_2));
+
+        // Nothing is left once discarded.
+        assert_eq!(1, state.redact_ips(IpRetention::Discard));
+        assert!(state.ip_locations().is_empty());
+    }
+
+    #[test]
+    fn test_control_keys() {
+        let environment = TEST_ENVIRONMENT.clone();
+        let coordinator_key = test_coordinator_verifier(&environment).unwrap().address();
+        let other_key = String::from("other_control_key");
+
+        // The key of the coordinator's verifier is the only control key by default.
+        let mut state = CoordinatorState::new(environment);
+        assert_eq!(BTreeSet::from_iter([coordinator_key.clone()]), state.control_keys());
+        assert!(matches!(
+            state.revoke_control_key(&coordinator_key),
+            Err(CoordinatorError::ControlKeyLast)
+        ));
+        assert!(matches!(
+            state.revoke_control_key(&other_key),
+            Err(CoordinatorError::ControlKeyMissing)
+        ));
+
+        // Rotate the key of the coordinator's verifier.
+        state.add_control_key(&other_key);
+        assert_eq!(2, state.control_keys().len());
+        state.revoke_control_key(&coordinator_key).unwrap();
+        assert_eq!(BTreeSet::from_iter([other_key.clone()]), state.control_keys());
+
+        // A revoked key can be added back.
+        state.add_control_key(&coordinator_key);
+        assert_eq!(BTreeSet::from_iter([coordinator_key, other_key]), state.control_keys());
+    }
+
+    #[test]
+    fn test_destruction_reminders() {
+        let contributor_1 = TEST_CONTRIBUTOR_ID.clone();
+        let contributor_2 = TEST_CONTRIBUTOR_ID_2.clone();
+        let time = MockTimeSource::new(OffsetDateTime::now_utc());
+
+        // Expect both contributors to attest the destruction of their toxic waste.
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        state.expect_destruction_attestation(&contributor_1, 1, time.now_utc() + Duration::hours(2), &time);
+        state.expect_destruction_attestation(&contributor_2, 2, time.now_utc() + Duration::hours(5), &time);
+
+        // Nobody is reminded before the interval.
+        let (reminders, missed) = state.update_destruction_reminders(&time);
+        assert!(reminders.is_empty() && missed.is_empty());
+
+        // The contributors are reminded once per interval.
+        time.advance(DESTRUCTION_REMINDER_INTERVAL);
+        let (reminders, _) = state.update_destruction_reminders(&time);
+        assert_eq!(2, reminders.len());
+        assert!(state.update_destruction_reminders(&time).0.is_empty());
+
+        // The attestation stops the reminders, only for the expected round.
+        state.complete_destruction_attestation(&contributor_2, 1);
+        assert_eq!(2, state.pending_destruction_attestations().len());
+        state.complete_destruction_attestation(&contributor_2, 2);
+        assert_eq!(1, state.pending_destruction_attestations().len());
+
+        // The contributor is dropped once past its deadline.
+        time.advance(Duration::hours(1));
+        let (reminders, missed) = state.update_destruction_reminders(&time);
+        assert!(reminders.is_empty());
+        assert_eq!(
+            vec![contributor_1],
+            missed.into_iter().map(|(p, _)| p).collect::<Vec<_>>()
+        );
+        assert!(state.pending_destruction_attestations().is_empty());
+    }
+
+    #[test]
+    fn test_heartbeat_finished_contributors() {
+        let time = MockTimeSource::new(OffsetDateTime::now_utc());
+        let contributors: Vec<_> = (1..=5)
+            .map(|i| Participant::new_contributor(&format!("finished_contributor_{}", i)))
+            .collect();
+
+        // The contributors finished in different rounds.
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        for (round_height, contributor) in (1..).zip(&contributors) {
+            let info = ParticipantInfo::new(contributor.clone(), round_height, 10, 0, &time);
+            state
+                .finished_contributors
+                .entry(round_height)
+                .or_default()
+                .insert(contributor.clone(), info);
+        }
+
+        // The heartbeat is recorded whichever round the contributor finished in, not only in the first round found.
+        time.advance(Duration::minutes(1));
+        for (round_height, contributor) in (1..).zip(&contributors) {
+            state.heartbeat(contributor, &time).unwrap();
+            assert_eq!(
+                time.now_utc(),
+                state.finished_contributors[&round_height][contributor].last_seen
+            );
+        }
+
+        // An unknown contributor is still refused.
+        assert!(matches!(
+            state.heartbeat(&TEST_CONTRIBUTOR_ID, &time),
+            Err(CoordinatorError::ParticipantNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_verification_quarantine() {
+        let time = MockTimeSource::new(OffsetDateTime::now_utc());
+        let task = Task::new(0, 1);
+        let error = String::from("Worker exited with signal: 9");
+
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        state.initialize(1);
+
+        // The task is quarantined once its attempts exceed the maximum number of retries.
+        for attempts in 1..=2 {
+            let retries = state.record_verification_crash(&task, error.clone(), 1, &time).unwrap();
+            assert_eq!(attempts, retries.attempts);
+        }
+        assert!(state.is_verification_quarantined(&task));
+        assert!(!state.is_verification_quarantined(&Task::new(0, 2)));
+
+        // The tasks are numbered the same way in every round.
+        state.current_round_height = Some(2);
+        assert!(!state.is_verification_quarantined(&task));
+        let retries = state.record_verification_crash(&task, error, 1, &time).unwrap();
+        assert_eq!((2, 1), (retries.round_height, retries.attempts));
+        assert_eq!(1, state.verification_retries().len());
+
+        // A successful verification drops the attempts.
+        assert!(state.clear_verification_retries(&task).is_some());
+        assert!(state.verification_retries().is_empty());
+    }
+
+    #[test]
+    fn test_queue_shuffle() {
+        let time = SystemTimeSource::new();
+        let maximum_contributors = TEST_ENVIRONMENT.maximum_contributors_per_round();
+
+        let mut state = CoordinatorState::new(TEST_ENVIRONMENT.clone());
+        state.initialize(1);
+        let contributors: Vec<_> = (0..3 * maximum_contributors)
+            .map(|id| Participant::new_contributor(&id.to_string()))
+            .collect();
+        for (id, contributor) in contributors.iter().enumerate() {
+            state
+                .add_to_queue(contributor.clone(), None, format!("test_token_{}", id), 10, &time)
+                .unwrap();
+        }
+
+        // Without a shuffle, the rounds are assigned in order of joining.
+        state.update_queue().unwrap();
+        assert_eq!(Some(2), state.queue.get(&contributors[0]).unwrap().1);
+
+        // With a shuffle, in the order of the shuffle, recomputable from the published queue.
+        let shuffle = QueueShuffle::new(2, "00ff").unwrap();
+        state.prepare_queue_shuffle(shuffle);
+        state.update_queue().unwrap();
+        let shuffle = state.queue_shuffle(2).unwrap();
+        assert_eq!(contributors, shuffle.queue);
+        for (position, contributor) in shuffle.shuffled().iter().enumerate() {
+            let round = 2 + (position / maximum_contributors) as u64;
+            assert_eq!(Some(round), state.queue.get(contributor).unwrap().1);
+        }
+
+        // Preparing the shuffle of the round again replaces it.
+        state.prepare_queue_shuffle(QueueShuffle::new(2, "00fe").unwrap());
+        assert_eq!(1, state.queue_shuffles().len());
+        assert!(state.queue_shuffle(3).is_none());
     }
 
     #[test]
@@ -3937,12 +5667,15 @@ mod tests {
                 true => {
                     assert_eq!(reliability, participant.0);
                     assert_eq!(Some(6), participant.1);
+                    assert_eq!(Some((1, id as u64)), state.queue_position(&contributor));
                 }
                 false => {
                     assert_eq!(reliability, participant.0);
                     assert_eq!(Some(7), participant.1);
+                    assert_eq!(Some((2, id as u64)), state.queue_position(&contributor));
                 }
             }
+            assert_eq!(Some(id - 1), state.queue.position(&contributor));
         }
 
         // Update the state of the queue.
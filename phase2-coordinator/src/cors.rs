@@ -0,0 +1,201 @@
+//! CORS policy of the REST API, to allow browser-based clients to interact with the [Coordinator](`crate::Coordinator`).
+//!
+//! Public read endpoints can be accessed from any origin, while the endpoints requiring a signed request are only
+//! exposed to the origins configured through the `CORS_ALLOWED_ORIGINS` env variable (comma separated list).
+
+use crate::rest_utils::{ACCESS_SECRET_HEADER, BODY_DIGEST_HEADER, PUBKEY_HEADER, SIGNATURE_HEADER};
+
+use lazy_static::lazy_static;
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::{Header, Method, Status},
+    Request,
+    Response,
+};
+use std::{collections::HashSet, io::Cursor};
+
+pub const ORIGIN_HEADER: &str = "Origin";
+pub const ALLOW_ORIGIN_HEADER: &str = "Access-Control-Allow-Origin";
+pub const ALLOW_METHODS_HEADER: &str = "Access-Control-Allow-Methods";
+pub const ALLOW_HEADERS_HEADER: &str = "Access-Control-Allow-Headers";
+pub const MAX_AGE_HEADER: &str = "Access-Control-Max-Age";
+pub const VARY_HEADER: &str = "Vary";
+
+/// Endpoints which don't require a signed request and can be read from any origin.
+pub const PUBLIC_ROUTES: [&str; 6] = [
+    "/healthcheck",
+    "/contribution_info",
+    "/ceremony_status",
+    "/ceremony/status",
+    "/circuits",
+    "/transcript",
+];
+
+/// Prefixes of the endpoints which don't require a signed request and can be read from any origin.
+pub const PUBLIC_ROUTE_PREFIXES: [&str; 1] = ["/badge/"];
+
+/// Cache duration of the preflight response, in seconds.
+const PREFLIGHT_MAX_AGE: u64 = 86400;
+
+lazy_static! {
+    pub static ref CORS_ALLOWED_ORIGINS: HashSet<String> = match std::env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(origins) => origins
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect(),
+        Err(_) => HashSet::new(),
+    };
+}
+
+/// The CORS policy applied to a route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CorsPolicy {
+    /// The route can be accessed from any origin.
+    Public,
+    /// The route can only be accessed from the configured origins.
+    Restricted,
+}
+
+/// Fairing applying the CORS policies to the responses of the server and answering preflight requests.
+#[derive(Clone, Debug)]
+pub struct Cors {
+    allowed_origins: HashSet<String>,
+    public_routes: HashSet<String>,
+}
+
+impl Default for Cors {
+    /// Generates a [`Cors`] instance with the origins configured in the env and the default [`PUBLIC_ROUTES`].
+    fn default() -> Self {
+        Self::new(CORS_ALLOWED_ORIGINS.iter().cloned())
+    }
+}
+
+impl Cors {
+    pub fn new<I, S>(allowed_origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed_origins: allowed_origins.into_iter().map(Into::into).collect(),
+            public_routes: PUBLIC_ROUTES.iter().map(|route| route.to_string()).collect(),
+        }
+    }
+
+    /// Marks the given route as readable from any origin.
+    pub fn with_public_route(mut self, route: &str) -> Self {
+        self.public_routes.insert(route.to_string());
+        self
+    }
+
+    /// Returns the policy to apply to the given path.
+    pub fn policy(&self, path: &str) -> CorsPolicy {
+        match self.public_routes.contains(path) || PUBLIC_ROUTE_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+            true => CorsPolicy::Public,
+            false => CorsPolicy::Restricted,
+        }
+    }
+
+    /// Returns the value of the allowed origin header for a request coming from `origin` on `path`, if the request is
+    /// allowed.
+    pub fn allowed_origin(&self, path: &str, origin: &str) -> Option<String> {
+        match self.policy(path) {
+            CorsPolicy::Public => Some(String::from("*")),
+            CorsPolicy::Restricted if self.allowed_origins.contains(origin) => Some(origin.to_string()),
+            CorsPolicy::Restricted => None,
+        }
+    }
+
+    fn allowed_methods(&self, path: &str) -> &'static str {
+        match self.policy(path) {
+            CorsPolicy::Public => "GET, OPTIONS",
+            CorsPolicy::Restricted => "GET, POST, OPTIONS",
+        }
+    }
+
+    fn allowed_headers(&self, path: &str) -> String {
+        match self.policy(path) {
+            CorsPolicy::Public => String::from("Content-Type"),
+            CorsPolicy::Restricted => format!(
+                "Content-Type, {}, {}, {}, {}",
+                PUBKEY_HEADER, SIGNATURE_HEADER, BODY_DIGEST_HEADER, ACCESS_SECRET_HEADER
+            ),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS policy",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let origin = match request.headers().get_one(ORIGIN_HEADER) {
+            Some(origin) => origin,
+            // Not a cross-origin request
+            None => return,
+        };
+        let path = request.uri().path().as_str();
+
+        let allowed_origin = match self.allowed_origin(path, origin) {
+            Some(o) => o,
+            None => {
+                if request.method() == Method::Options {
+                    // Preflight of a disallowed origin
+                    response.set_status(Status::Forbidden);
+                    response.set_sized_body(0, Cursor::new(""));
+                }
+
+                return;
+            }
+        };
+
+        response.set_header(Header::new(ALLOW_ORIGIN_HEADER, allowed_origin));
+        response.set_header(Header::new(VARY_HEADER, ORIGIN_HEADER));
+
+        // Answer preflight requests, which are not handled by any route
+        if request.method() == Method::Options {
+            response.set_header(Header::new(ALLOW_METHODS_HEADER, self.allowed_methods(path)));
+            response.set_header(Header::new(ALLOW_HEADERS_HEADER, self.allowed_headers(path)));
+            response.set_header(Header::new(MAX_AGE_HEADER, PREFLIGHT_MAX_AGE.to_string()));
+            response.set_status(Status::NoContent);
+            response.set_sized_body(0, Cursor::new(""));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy() {
+        let cors = Cors::new(vec!["https://ceremony.namada.net"]).with_public_route("/dashboard");
+
+        assert_eq!(cors.policy("/healthcheck"), CorsPolicy::Public);
+        assert_eq!(cors.policy("/ceremony/status"), CorsPolicy::Public);
+        assert_eq!(cors.policy("/dashboard"), CorsPolicy::Public);
+        assert_eq!(cors.policy("/badge/contributor.svg"), CorsPolicy::Public);
+        assert_eq!(cors.policy("/contributor/join_queue"), CorsPolicy::Restricted);
+    }
+
+    #[test]
+    fn test_allowed_origin() {
+        let cors = Cors::new(vec!["https://ceremony.namada.net"]);
+
+        assert_eq!(
+            cors.allowed_origin("/healthcheck", "https://evil.com"),
+            Some(String::from("*"))
+        );
+        assert_eq!(
+            cors.allowed_origin("/contributor/join_queue", "https://ceremony.namada.net"),
+            Some(String::from("https://ceremony.namada.net"))
+        );
+        assert_eq!(cors.allowed_origin("/contributor/join_queue", "https://evil.com"), None);
+    }
+}
@@ -0,0 +1,40 @@
+//! Minimal status page of the ceremony, embedded in the binary of the coordinator and served by the same server as the
+//! REST API, to run a ceremony without deploying a separate frontend.
+//!
+//! The page is available with the `dashboard` feature at [`DASHBOARD_PATH`]. It displays the queue, the current round
+//! and the recent contributions, polling from the browser only the public endpoints of the API: `/ceremony_status`,
+//! `/contribution_info` and `/circuits`.
+
+use rocket::{
+    get,
+    response::content::{RawCss, RawHtml, RawJavaScript},
+    routes,
+    Route,
+};
+
+/// Base path of the dashboard.
+pub const DASHBOARD_PATH: &str = "/dashboard";
+
+const INDEX: &str = include_str!("../dashboard/index.html");
+const SCRIPT: &str = include_str!("../dashboard/dashboard.js");
+const STYLE: &str = include_str!("../dashboard/dashboard.css");
+
+#[get("/")]
+pub fn index() -> RawHtml<&'static str> {
+    RawHtml(INDEX)
+}
+
+#[get("/dashboard.js")]
+pub fn script() -> RawJavaScript<&'static str> {
+    RawJavaScript(SCRIPT)
+}
+
+#[get("/dashboard.css")]
+pub fn style() -> RawCss<&'static str> {
+    RawCss(STYLE)
+}
+
+/// Returns the routes of the dashboard, to be mounted at [`DASHBOARD_PATH`].
+pub fn routes() -> Vec<Route> {
+    routes![index, script, style]
+}
@@ -1,9 +1,19 @@
-use crate::{authentication::KeyPair, objects::Participant, storage::Disk};
+use crate::{
+    authentication::{ssh, KeyPair},
+    changelog::Deprecation,
+    hashing::{ContributionHasher, HashAlgorithm},
+    ip_prefix::IpPrefixes,
+    ip_retention::IpRetention,
+    objects::Participant,
+    storage::Disk,
+};
 pub use phase2::{helpers::CurveKind, ContributionMode, ProvingSystem};
 use setup_utils::{CheckForCorrectness, UseCompression};
 
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 
 type BatchSize = usize;
 type ChunkSize = usize;
@@ -76,6 +86,34 @@ pub enum Deployment {
     Testing,
     Development,
     Production,
+    /// A public rehearsal of the ceremony, on the parameters of the test circuit, whose transcript is thrown away.
+    Rehearsal,
+}
+
+/// The resource limits of the sandboxed verification subprocess.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxLimits {
+    /// The maximum address space, in bytes, of the subprocess. Unlimited if not set.
+    #[serde(default)]
+    pub memory: Option<u64>,
+    /// The maximum CPU time, in seconds, of the subprocess. Unlimited if not set.
+    #[serde(default)]
+    pub cpu_seconds: Option<u64>,
+}
+
+/// Where the coordinator runs the verification of the contributions, which parses files uploaded by the contributors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComputeBackend {
+    /// In the process of the coordinator.
+    InProcess,
+    /// In a subprocess without network and filesystem access and with limited resources, see [`crate::sandbox`].
+    Sandboxed(SandboxLimits),
+}
+
+impl Default for ComputeBackend {
+    fn default() -> Self {
+        ComputeBackend::InProcess
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -257,6 +295,11 @@ pub struct Environment {
     participant_lock_timeout: time::Duration,
     /// The maximum duration a queued contributor can go without a heartbeat.
     queue_seen_timeout: time::Duration,
+    /// The maximum duration a contributor computing on an air-gapped machine
+    /// can hold a lock or go without being seen, since the challenge and the
+    /// response are transferred manually.
+    #[serde(default = "default_offline_contribution_timeout")]
+    offline_contribution_timeout: time::Duration,
     /// The number of drops tolerated by a participant before banning them from future rounds.
     participant_ban_threshold: u16,
     /// The setting to allow current contributors to join the queue for the next round.
@@ -265,6 +308,27 @@ pub struct Environment {
     allow_current_verifiers_in_queue: bool,
     /// The minimum number of seconds to wait after aggregation before starting the next round.
     queue_wait_time: u64,
+    /// The minimum duration between two periodic updates of the coordinator.
+    #[serde(default = "default_minimum_update_interval")]
+    minimum_update_interval: time::Duration,
+    /// The maximum duration between two periodic updates of the coordinator.
+    #[serde(default = "default_maximum_update_interval")]
+    maximum_update_interval: time::Duration,
+    /// The maximum number of rounds of the ceremony, after which it is finalized.
+    /// Unlimited if not set.
+    #[serde(default)]
+    max_rounds: Option<u64>,
+    /// The maximum duration of the ceremony since its start, after which it is finalized.
+    /// Unlimited if not set.
+    #[serde(default)]
+    max_ceremony_duration: Option<time::Duration>,
+    /// The window before the end of the ceremony during which its approaching end is announced.
+    #[serde(default = "default_final_window")]
+    final_window: time::Duration,
+    /// The time given to a contributor, once its contribution info is posted, to attest the destruction of its toxic
+    /// waste, during which it is reminded.
+    #[serde(default = "default_destruction_attestation_deadline")]
+    destruction_attestation_deadline: time::Duration,
 
     /// The contributors managed by the coordinator.
     coordinator_contributors: Vec<Participant>,
@@ -272,17 +336,114 @@ pub struct Environment {
     coordinator_verifiers: Vec<Participant>,
     /// The signing key used by the default coordinator's verifier
     default_verifier_signing_key: String,
+    /// The number of independent verifiers that must agree on each contribution
+    /// before it is considered verified.
+    #[serde(default = "default_verification_quorum")]
+    verification_quorum: usize,
+    /// The external verifiers allowed to vote on the verification of the contributions.
+    #[serde(default)]
+    quorum_verifiers: Vec<Participant>,
+    /// The public keys of the operators allowed to approve the manual injection of a contribution, and deriving the
+    /// seed of the genesis challenge.
+    #[serde(default)]
+    operators: Vec<String>,
+    /// The public keys allowed to sign the privileged requests of the coordinator, on top of the key of the
+    /// coordinator's verifier.
+    #[serde(default)]
+    control_keys: Vec<String>,
+    /// The maximum memory, in bytes, a verification is allowed to allocate.
+    /// Unlimited if not set.
+    #[serde(default)]
+    verification_memory_budget: Option<u64>,
+    /// Where the contributions are verified.
+    #[serde(default)]
+    compute_backend: ComputeBackend,
+    /// The number of times the verification of a contribution which crashed the verifier is retried, before the
+    /// contribution is quarantined for the operators to decide on it.
+    #[serde(default = "default_max_verification_retries")]
+    max_verification_retries: u32,
+    /// The number of contributions waiting to be verified above which no new chunk is locked, until the verifications
+    /// catch up. Unlimited if not set.
+    #[serde(default)]
+    verification_backlog_threshold: Option<usize>,
+    /// The hash function of the contribution files.
+    #[serde(default)]
+    hash_algorithm: HashAlgorithm,
+    /// What is kept of the IPs of the contributors who have finished.
+    #[serde(default)]
+    ip_retention: IpRetention,
+    /// The widths of the prefixes by which the IPs of the contributors are bucketed by the IP ban.
+    #[serde(default)]
+    ip_prefixes: IpPrefixes,
+    /// Whether the queue is shuffled each round by a seed derived from the response of the previous round, instead of
+    /// being served in order of joining.
+    #[serde(default)]
+    queue_shuffle: bool,
+    /// Whether round 1 starts only once the operators have derived the seed of the genesis challenge by a commit-reveal
+    /// of their shares, instead of the genesis challenge starting with a blank hash.
+    #[serde(default)]
+    genesis_seed_ceremony: bool,
+    /// Whether an identity claimed by a contributor joining the queue can only be used by a single contributor in the
+    /// whole ceremony, on top of the limit of one contribution per public key.
+    #[serde(default)]
+    unique_identity: bool,
+    /// The public keys exempt from the limit of one contribution per public key and per identity, e.g. the keys of the
+    /// operators or of the tests.
+    #[serde(default)]
+    contribution_limit_exempt_keys: Vec<String>,
 
     /// The software version number of the coordinator.
     software_version: u64,
+    /// The endpoints of the REST API which are going to be removed, published in the changelog of the API.
+    #[serde(default)]
+    api_deprecations: Vec<Deprecation>,
     /// The deployment environment of this coordinator.
     deployment: Deployment,
     /// The base directory for disk storage of this coordinator.
     local_base_directory: String,
+    /// The root directories, each one on its own disk, among which the contribution files are distributed.
+    /// The contribution files are stored in the base directory if empty.
+    #[serde(default)]
+    storage_shards: Vec<String>,
 
     disable_reliability_zeroing: bool,
 }
 
+/// Default timeout of the contributions computed on air-gapped machines.
+fn default_offline_contribution_timeout() -> time::Duration {
+    time::Duration::hours(2)
+}
+
+/// Default minimum interval between the updates of the coordinator.
+fn default_minimum_update_interval() -> time::Duration {
+    time::Duration::seconds(5)
+}
+
+/// Default maximum interval between the updates of the coordinator.
+fn default_maximum_update_interval() -> time::Duration {
+    time::Duration::seconds(60)
+}
+
+/// Default window before the end of the ceremony during which its approaching end is announced.
+fn default_final_window() -> time::Duration {
+    time::Duration::hours(1)
+}
+
+/// Default time given to a contributor to attest the destruction of its toxic waste.
+fn default_destruction_attestation_deadline() -> time::Duration {
+    time::Duration::days(1)
+}
+
+/// Default number of retries of a verification which crashed the verifier.
+fn default_max_verification_retries() -> u32 {
+    3
+}
+
+/// Default verification quorum, only the coordinator's verifier is required.
+fn default_verification_quorum() -> usize {
+    1
+}
+
 impl Environment {
     ///
     /// Returns the parameter settings of the coordinator.
@@ -388,6 +549,15 @@ impl Environment {
         self.queue_seen_timeout
     }
 
+    ///
+    /// Returns the maximum duration that a contributor computing
+    /// offline can hold a lock or go without being seen before being
+    /// dropped from the ceremony by the coordinator.
+    ///
+    pub const fn offline_contribution_timeout(&self) -> time::Duration {
+        self.offline_contribution_timeout
+    }
+
     ///
     /// Returns the number of times the coordinator tolerates
     /// a dropped participant before banning them from future rounds.
@@ -420,6 +590,57 @@ impl Environment {
         self.queue_wait_time
     }
 
+    ///
+    /// Returns the minimum duration between two periodic updates of the
+    /// coordinator, used while contributions are waiting for their
+    /// verification or participants are about to time out.
+    ///
+    pub const fn minimum_update_interval(&self) -> time::Duration {
+        self.minimum_update_interval
+    }
+
+    ///
+    /// Returns the maximum duration between two periodic updates of the
+    /// coordinator, reached while the ceremony is idle.
+    ///
+    pub const fn maximum_update_interval(&self) -> time::Duration {
+        self.maximum_update_interval
+    }
+
+    ///
+    /// Returns the maximum number of rounds of the ceremony, after which
+    /// the coordinator stops accepting contributors and finalizes it.
+    /// Returns [`None`] if the number of rounds is unlimited.
+    ///
+    pub const fn max_rounds(&self) -> Option<u64> {
+        self.max_rounds
+    }
+
+    ///
+    /// Returns the maximum duration of the ceremony since its start, after
+    /// which the coordinator stops accepting contributors and finalizes it.
+    /// Returns [`None`] if the duration is unlimited.
+    ///
+    pub const fn max_ceremony_duration(&self) -> Option<time::Duration> {
+        self.max_ceremony_duration
+    }
+
+    ///
+    /// Returns the window before the end of the ceremony during which the
+    /// coordinator announces its approaching end.
+    ///
+    pub const fn final_window(&self) -> time::Duration {
+        self.final_window
+    }
+
+    ///
+    /// Returns the time given to a contributor, once its contribution info is
+    /// posted, to attest the destruction of its toxic waste.
+    ///
+    pub const fn destruction_attestation_deadline(&self) -> time::Duration {
+        self.destruction_attestation_deadline
+    }
+
     ///
     /// Returns the contributors managed by the coordinator.
     ///
@@ -443,6 +664,127 @@ impl Environment {
         self.default_verifier_signing_key.clone()
     }
 
+    ///
+    /// Returns the number of independent verifiers, including the
+    /// coordinator's verifier, that must agree on the result of the
+    /// verification of a contribution before the round can advance.
+    ///
+    pub const fn verification_quorum(&self) -> usize {
+        self.verification_quorum
+    }
+
+    /// Returns the external verifiers allowed to vote on the verification of the contributions.
+    pub const fn quorum_verifiers(&self) -> &Vec<Participant> {
+        &self.quorum_verifiers
+    }
+
+    /// Returns the public keys of the operators allowed to approve the manual injection of a contribution, and deriving
+    /// the seed of the genesis challenge.
+    pub const fn operators(&self) -> &Vec<String> {
+        &self.operators
+    }
+
+    /// Returns the public keys allowed to sign the privileged requests, on top of the coordinator's verifier key.
+    pub const fn control_keys(&self) -> &Vec<String> {
+        &self.control_keys
+    }
+
+    ///
+    /// Returns the maximum memory, in bytes, that the verification of a
+    /// contribution can allocate. Verifications requiring more memory are
+    /// aborted. Returns [`None`] if the memory is only limited by the cgroup
+    /// of the coordinator, if any.
+    ///
+    pub const fn verification_memory_budget(&self) -> Option<u64> {
+        self.verification_memory_budget
+    }
+
+    ///
+    /// Returns where the coordinator runs the verification of the
+    /// contributions.
+    ///
+    pub const fn compute_backend(&self) -> ComputeBackend {
+        self.compute_backend
+    }
+
+    ///
+    /// Returns the number of contributions waiting to be verified above
+    /// which the contributors are paused before locking a chunk. Returns
+    /// [`None`] if the contributors are never paused.
+    ///
+    pub const fn verification_backlog_threshold(&self) -> Option<usize> {
+        self.verification_backlog_threshold
+    }
+
+    ///
+    /// Returns the number of times the verification of a contribution which
+    /// crashed the verifier is retried before quarantining the contribution.
+    ///
+    pub const fn max_verification_retries(&self) -> u32 {
+        self.max_verification_retries
+    }
+
+    ///
+    /// Returns the hash function of the contribution files of the ceremony.
+    ///
+    pub const fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
+    ///
+    /// Returns the retention policy of the IPs of the contributors who have finished.
+    ///
+    pub const fn ip_retention(&self) -> IpRetention {
+        self.ip_retention
+    }
+
+    ///
+    /// Returns the widths of the prefixes by which the IPs of the contributors
+    /// are bucketed, e.g. to ban a whole IPv6 /64 network.
+    ///
+    pub const fn ip_prefixes(&self) -> IpPrefixes {
+        self.ip_prefixes
+    }
+
+    ///
+    /// Returns true if the queue is shuffled each round, seeded by the
+    /// response hash of the previous round.
+    ///
+    pub const fn queue_shuffle(&self) -> bool {
+        self.queue_shuffle
+    }
+
+    ///
+    /// Returns true if round 1 waits for the operators to derive the seed
+    /// of the genesis challenge.
+    ///
+    pub const fn genesis_seed_ceremony(&self) -> bool {
+        self.genesis_seed_ceremony
+    }
+
+    ///
+    /// Returns true if an identity claimed by a contributor can only be
+    /// used by a single contributor in the ceremony.
+    ///
+    pub const fn unique_identity(&self) -> bool {
+        self.unique_identity
+    }
+
+    ///
+    /// Returns the public keys, in the format of the native ones, exempt
+    /// from the limit of one contribution per public key and per identity.
+    ///
+    pub const fn contribution_limit_exempt_keys(&self) -> &Vec<String> {
+        &self.contribution_limit_exempt_keys
+    }
+
+    ///
+    /// Returns the hasher of the contribution files of the ceremony.
+    ///
+    pub fn contribution_hasher(&self) -> &'static dyn ContributionHasher {
+        self.hash_algorithm.hasher()
+    }
+
     ///
     /// Returns the software version number of the coordinator.
     ///
@@ -450,6 +792,13 @@ impl Environment {
         self.software_version
     }
 
+    ///
+    /// Returns the endpoints of the REST API which are going to be removed.
+    ///
+    pub const fn api_deprecations(&self) -> &Vec<Deprecation> {
+        &self.api_deprecations
+    }
+
     ///
     /// Returns the deployment environment of the coordinator.
     ///
@@ -457,6 +806,13 @@ impl Environment {
         &self.deployment
     }
 
+    ///
+    /// Returns `true` if the ceremony is a rehearsal, whose artifacts are not for production.
+    ///
+    pub fn is_rehearsal(&self) -> bool {
+        self.deployment == Deployment::Rehearsal
+    }
+
     ///
     /// Returns the base directory for disk storage of this coordinator.
     ///
@@ -464,6 +820,15 @@ impl Environment {
         &self.local_base_directory
     }
 
+    ///
+    /// Returns the root directories of the storage shards, among which
+    /// the contribution, round and parameters files are distributed.
+    /// These files are stored in the base directory if empty.
+    ///
+    pub const fn storage_shards(&self) -> &Vec<String> {
+        &self.storage_shards
+    }
+
     ///
     /// Returns the appropriate number of chunks for the coordinator
     /// to run given a proof system, power and chunk size.
@@ -564,6 +929,156 @@ impl Testing {
         deployment
     }
 
+    pub fn offline_contribution_timeout(&self, offline_contribution_timeout: time::Duration) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.offline_contribution_timeout = offline_contribution_timeout;
+        deployment
+    }
+
+    pub fn max_rounds(&self, max_rounds: u64) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.max_rounds = Some(max_rounds);
+        deployment
+    }
+
+    pub fn max_ceremony_duration(&self, max_ceremony_duration: time::Duration) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.max_ceremony_duration = Some(max_ceremony_duration);
+        deployment
+    }
+
+    pub fn final_window(&self, final_window: time::Duration) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.final_window = final_window;
+        deployment
+    }
+
+    pub fn destruction_attestation_deadline(&self, destruction_attestation_deadline: time::Duration) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.destruction_attestation_deadline = destruction_attestation_deadline;
+        deployment
+    }
+
+    pub fn verification_quorum(&self, verification_quorum: usize) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.verification_quorum = verification_quorum;
+        deployment
+    }
+
+    #[inline]
+    pub fn quorum_verifiers(&self, verifiers: &[Participant]) -> Self {
+        // Check that all participants are verifiers.
+        if verifiers.into_par_iter().filter(|p| !p.is_verifier()).count() > 0 {
+            panic!("Specifying to environment a list of quorum verifiers with non-verifiers.")
+        }
+
+        let mut deployment = self.clone();
+        deployment.environment.quorum_verifiers = verifiers.to_vec();
+        deployment
+    }
+
+    #[inline]
+    pub fn operators(&self, operators: &[String]) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.operators = operators.to_vec();
+        deployment
+    }
+
+    #[inline]
+    pub fn control_keys(&self, control_keys: &[String]) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.control_keys = control_keys.to_vec();
+        deployment
+    }
+
+    pub fn verification_memory_budget(&self, verification_memory_budget: u64) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.verification_memory_budget = Some(verification_memory_budget);
+        deployment
+    }
+
+    pub fn compute_backend(&self, compute_backend: ComputeBackend) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.compute_backend = compute_backend;
+        deployment
+    }
+
+    pub fn max_verification_retries(&self, max_verification_retries: u32) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.max_verification_retries = max_verification_retries;
+        deployment
+    }
+
+    pub fn hash_algorithm(&self, hash_algorithm: HashAlgorithm) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.hash_algorithm = hash_algorithm;
+        deployment
+    }
+
+    pub fn ip_retention(&self, ip_retention: IpRetention) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.ip_retention = ip_retention;
+        deployment
+    }
+
+    pub fn ip_prefixes(&self, ip_prefixes: IpPrefixes) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.ip_prefixes = ip_prefixes;
+        deployment
+    }
+
+    pub fn queue_shuffle(&self, queue_shuffle: bool) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.queue_shuffle = queue_shuffle;
+        deployment
+    }
+
+    pub fn genesis_seed_ceremony(&self, genesis_seed_ceremony: bool) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.genesis_seed_ceremony = genesis_seed_ceremony;
+        deployment
+    }
+
+    pub fn unique_identity(&self, unique_identity: bool) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.unique_identity = unique_identity;
+        deployment
+    }
+
+    pub fn contribution_limit_exempt_keys(&self, contribution_limit_exempt_keys: &[String]) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.contribution_limit_exempt_keys = contribution_limit_exempt_keys
+            .iter()
+            .map(|key| ssh::canonical_public_key(key).into_owned())
+            .collect();
+        deployment
+    }
+
+    pub fn verification_backlog_threshold(&self, verification_backlog_threshold: usize) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.verification_backlog_threshold = Some(verification_backlog_threshold);
+        deployment
+    }
+
+    pub fn update_interval(&self, minimum: time::Duration, maximum: time::Duration) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.minimum_update_interval = minimum;
+        deployment.environment.maximum_update_interval = maximum;
+        deployment
+    }
+
+    pub fn storage_shards(&self, roots: &[String]) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.storage_shards = roots.to_vec();
+        deployment
+    }
+
+    pub fn api_deprecations(&self, deprecations: &[Deprecation]) -> Self {
+        let mut deployment = self.clone();
+        deployment.environment.api_deprecations = deprecations.to_vec();
+        deployment
+    }
+
     fn generate_namada_env(keypair: &KeyPair) -> Self {
         Self {
             environment: Environment {
@@ -587,18 +1102,42 @@ impl Testing {
                 verifier_seen_timeout: time::Duration::minutes(15),
                 participant_lock_timeout: time::Duration::minutes(20),
                 queue_seen_timeout: time::Duration::minutes(5),
+                offline_contribution_timeout: default_offline_contribution_timeout(),
                 participant_ban_threshold: 5,
                 allow_current_contributors_in_queue: true,
                 allow_current_verifiers_in_queue: true,
                 queue_wait_time: 0,
+                minimum_update_interval: default_minimum_update_interval(),
+                maximum_update_interval: default_maximum_update_interval(),
+                max_rounds: None,
+                max_ceremony_duration: None,
+                final_window: default_final_window(),
+                destruction_attestation_deadline: default_destruction_attestation_deadline(),
 
                 coordinator_contributors: vec![Participant::new_contributor("testing-coordinator-contributor")],
                 coordinator_verifiers: vec![Participant::new_verifier(keypair.pubkey())],
                 default_verifier_signing_key: keypair.sigkey().to_owned(),
+                verification_quorum: default_verification_quorum(),
+                quorum_verifiers: vec![],
+                operators: vec![],
+                control_keys: vec![],
+                verification_memory_budget: None,
+                compute_backend: ComputeBackend::InProcess,
+                max_verification_retries: default_max_verification_retries(),
+                verification_backlog_threshold: None,
+                hash_algorithm: HashAlgorithm::default(),
+                ip_retention: IpRetention::default(),
+                ip_prefixes: IpPrefixes::default(),
+                queue_shuffle: false,
+                genesis_seed_ceremony: false,
+                unique_identity: false,
+                contribution_limit_exempt_keys: vec![],
 
                 software_version: 1,
+                api_deprecations: vec![],
                 deployment: Deployment::Testing,
                 local_base_directory: "./transcript/testing".to_string(),
+                storage_shards: vec![],
 
                 disable_reliability_zeroing: false,
             },
@@ -714,18 +1253,42 @@ impl Development {
                 verifier_seen_timeout: time::Duration::minutes(15),
                 participant_lock_timeout: time::Duration::minutes(20),
                 queue_seen_timeout: time::Duration::minutes(10),
+                offline_contribution_timeout: default_offline_contribution_timeout(),
                 participant_ban_threshold: 5,
                 allow_current_contributors_in_queue: true,
                 allow_current_verifiers_in_queue: true,
                 queue_wait_time: 60,
+                minimum_update_interval: default_minimum_update_interval(),
+                maximum_update_interval: default_maximum_update_interval(),
+                max_rounds: None,
+                max_ceremony_duration: None,
+                final_window: default_final_window(),
+                destruction_attestation_deadline: default_destruction_attestation_deadline(),
 
                 coordinator_contributors: vec![Participant::new_contributor("development-coordinator-contributor")],
                 coordinator_verifiers: vec![Participant::new_verifier(keypair.pubkey())],
                 default_verifier_signing_key: keypair.sigkey().to_owned(),
+                verification_quorum: default_verification_quorum(),
+                quorum_verifiers: vec![],
+                operators: vec![],
+                control_keys: vec![],
+                verification_memory_budget: None,
+                compute_backend: ComputeBackend::InProcess,
+                max_verification_retries: default_max_verification_retries(),
+                verification_backlog_threshold: None,
+                hash_algorithm: HashAlgorithm::default(),
+                ip_retention: IpRetention::default(),
+                ip_prefixes: IpPrefixes::default(),
+                queue_shuffle: false,
+                genesis_seed_ceremony: false,
+                unique_identity: false,
+                contribution_limit_exempt_keys: vec![],
 
                 software_version: 1,
+                api_deprecations: vec![],
                 deployment: Deployment::Development,
                 local_base_directory: "./transcript/development".to_string(),
+                storage_shards: vec![],
 
                 disable_reliability_zeroing: false,
             },
@@ -800,6 +1363,85 @@ impl Production {
         self
     }
 
+    pub fn offline_contribution_timeout(mut self, timeout: time::Duration) -> Self {
+        self.environment.offline_contribution_timeout = timeout;
+        self
+    }
+
+    pub fn verification_quorum(mut self, verification_quorum: usize) -> Self {
+        self.environment.verification_quorum = verification_quorum;
+        self
+    }
+
+    pub fn verification_memory_budget(mut self, verification_memory_budget: u64) -> Self {
+        self.environment.verification_memory_budget = Some(verification_memory_budget);
+        self
+    }
+
+    pub fn compute_backend(mut self, compute_backend: ComputeBackend) -> Self {
+        self.environment.compute_backend = compute_backend;
+        self
+    }
+
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.environment.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    pub fn ip_retention(mut self, ip_retention: IpRetention) -> Self {
+        self.environment.ip_retention = ip_retention;
+        self
+    }
+
+    pub fn ip_prefixes(mut self, ip_prefixes: IpPrefixes) -> Self {
+        self.environment.ip_prefixes = ip_prefixes;
+        self
+    }
+
+    pub fn queue_shuffle(mut self, queue_shuffle: bool) -> Self {
+        self.environment.queue_shuffle = queue_shuffle;
+        self
+    }
+
+    pub fn genesis_seed_ceremony(mut self, genesis_seed_ceremony: bool) -> Self {
+        self.environment.genesis_seed_ceremony = genesis_seed_ceremony;
+        self
+    }
+
+    pub fn unique_identity(mut self, unique_identity: bool) -> Self {
+        self.environment.unique_identity = unique_identity;
+        self
+    }
+
+    pub fn contribution_limit_exempt_keys(mut self, contribution_limit_exempt_keys: &[String]) -> Self {
+        self.environment.contribution_limit_exempt_keys = contribution_limit_exempt_keys
+            .iter()
+            .map(|key| ssh::canonical_public_key(key).into_owned())
+            .collect();
+        self
+    }
+
+    pub fn verification_backlog_threshold(mut self, verification_backlog_threshold: usize) -> Self {
+        self.environment.verification_backlog_threshold = Some(verification_backlog_threshold);
+        self
+    }
+
+    pub fn update_interval(mut self, minimum: time::Duration, maximum: time::Duration) -> Self {
+        self.environment.minimum_update_interval = minimum;
+        self.environment.maximum_update_interval = maximum;
+        self
+    }
+
+    pub fn storage_shards(mut self, roots: &[String]) -> Self {
+        self.environment.storage_shards = roots.to_vec();
+        self
+    }
+
+    pub fn api_deprecations(mut self, deprecations: &[Deprecation]) -> Self {
+        self.environment.api_deprecations = deprecations.to_vec();
+        self
+    }
+
     pub fn disable_reliability_zeroing(mut self, disable_zeroing: bool) -> Self {
         self.environment.disable_reliability_zeroing = disable_zeroing;
         self
@@ -834,6 +1476,146 @@ impl Production {
             Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
             Err(_) => None,
         };
+        let offline_timeout = match std::env::var("NAMADA_MPC_OFFLINE_TIMEOUT_SECONDS") {
+            Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+            Err(_) => None,
+        };
+        let verification_quorum = match std::env::var("NAMADA_MPC_VERIFICATION_QUORUM") {
+            Ok(k) => k.parse::<usize>().unwrap(),
+            Err(_) => default_verification_quorum(),
+        };
+        let quorum_verifiers = match std::env::var("NAMADA_MPC_QUORUM_VERIFIERS") {
+            Ok(pubkeys) => pubkeys
+                .split(',')
+                .map(str::trim)
+                .filter(|pubkey| !pubkey.is_empty())
+                .map(Participant::new_verifier)
+                .collect(),
+            Err(_) => vec![],
+        };
+        let operators = match std::env::var("NAMADA_MPC_OPERATORS") {
+            Ok(pubkeys) => pubkeys
+                .split(',')
+                .map(str::trim)
+                .filter(|pubkey| !pubkey.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(_) => vec![],
+        };
+        let control_keys = match std::env::var("NAMADA_MPC_CONTROL_KEYS") {
+            Ok(pubkeys) => pubkeys
+                .split(',')
+                .map(str::trim)
+                .filter(|pubkey| !pubkey.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(_) => vec![],
+        };
+        let verification_memory_budget = match std::env::var("NAMADA_MPC_VERIFICATION_MEMORY_BUDGET") {
+            Ok(bytes) => Some(bytes.parse::<u64>().unwrap()),
+            Err(_) => None,
+        };
+        let compute_backend = match std::env::var("NAMADA_MPC_COMPUTE_BACKEND").as_deref() {
+            Ok("sandboxed") => ComputeBackend::Sandboxed(SandboxLimits {
+                memory: std::env::var("NAMADA_MPC_SANDBOX_MEMORY_LIMIT")
+                    .ok()
+                    .map(|bytes| bytes.parse::<u64>().unwrap()),
+                cpu_seconds: std::env::var("NAMADA_MPC_SANDBOX_CPU_SECONDS")
+                    .ok()
+                    .map(|seconds| seconds.parse::<u64>().unwrap()),
+            }),
+            Ok("in_process") | Err(_) => ComputeBackend::InProcess,
+            Ok(backend) => panic!("Unknown compute backend {}", backend),
+        };
+        let max_verification_retries = match std::env::var("NAMADA_MPC_MAX_VERIFICATION_RETRIES") {
+            Ok(retries) => Some(retries.parse::<u32>().unwrap()),
+            Err(_) => None,
+        };
+        let verification_backlog_threshold = match std::env::var("NAMADA_MPC_VERIFICATION_BACKLOG_THRESHOLD") {
+            Ok(threshold) => Some(threshold.parse::<usize>().unwrap()),
+            Err(_) => None,
+        };
+        let hash_algorithm = match std::env::var("NAMADA_MPC_HASH_ALGORITHM") {
+            Ok(algorithm) => algorithm.parse::<HashAlgorithm>().unwrap(),
+            Err(_) => HashAlgorithm::default(),
+        };
+        let ip_retention = match std::env::var("NAMADA_MPC_IP_RETENTION") {
+            Ok(policy) => policy.parse::<IpRetention>().unwrap(),
+            Err(_) => IpRetention::default(),
+        };
+        let default_prefixes = IpPrefixes::default();
+        let ip_prefixes = IpPrefixes::new(
+            std::env::var("NAMADA_MPC_IPV4_PREFIX")
+                .map(|width| width.parse::<u8>().unwrap())
+                .unwrap_or(default_prefixes.ipv4),
+            std::env::var("NAMADA_MPC_IPV6_PREFIX")
+                .map(|width| width.parse::<u8>().unwrap())
+                .unwrap_or(default_prefixes.ipv6),
+        )
+        .unwrap();
+        let queue_shuffle = match std::env::var("NAMADA_MPC_QUEUE_SHUFFLE") {
+            Ok(s) if s == "true" => true,
+            _ => false,
+        };
+        let genesis_seed_ceremony = match std::env::var("NAMADA_MPC_GENESIS_SEED_CEREMONY") {
+            Ok(s) if s == "true" => true,
+            _ => false,
+        };
+        let unique_identity = match std::env::var("UNIQUE_IDENTITY") {
+            Ok(s) if s == "true" => true,
+            _ => false,
+        };
+        let contribution_limit_exempt_keys = match std::env::var("CONTRIBUTION_LIMIT_EXEMPT_KEYS") {
+            Ok(pubkeys) => pubkeys
+                .split(',')
+                .map(str::trim)
+                .filter(|pubkey| !pubkey.is_empty())
+                .map(|pubkey| ssh::canonical_public_key(pubkey).into_owned())
+                .collect(),
+            Err(_) => vec![],
+        };
+        let storage_shards = match std::env::var("NAMADA_MPC_STORAGE_SHARDS") {
+            Ok(roots) => roots
+                .split(',')
+                .map(str::trim)
+                .filter(|root| !root.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            Err(_) => vec![],
+        };
+        let api_deprecations = match std::env::var("NAMADA_MPC_API_DEPRECATIONS") {
+            Ok(deprecations) => deprecations
+                .split(',')
+                .filter(|deprecation| !deprecation.trim().is_empty())
+                .map(|deprecation| deprecation.parse::<Deprecation>().unwrap())
+                .collect(),
+            Err(_) => vec![],
+        };
+        let minimum_update_interval = match std::env::var("NAMADA_MPC_MIN_UPDATE_INTERVAL_SECONDS") {
+            Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+            Err(_) => None,
+        };
+        let maximum_update_interval = match std::env::var("NAMADA_MPC_MAX_UPDATE_INTERVAL_SECONDS") {
+            Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+            Err(_) => None,
+        };
+        let max_rounds = match std::env::var("NAMADA_MPC_MAX_ROUNDS") {
+            Ok(rounds) => Some(rounds.parse::<u64>().unwrap()),
+            Err(_) => None,
+        };
+        let max_ceremony_duration = match std::env::var("NAMADA_MPC_MAX_DURATION_SECONDS") {
+            Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+            Err(_) => None,
+        };
+        let final_window = match std::env::var("NAMADA_MPC_FINAL_WINDOW_SECONDS") {
+            Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+            Err(_) => None,
+        };
+        let destruction_attestation_deadline =
+            match std::env::var("NAMADA_MPC_DESTRUCTION_ATTESTATION_DEADLINE_SECONDS") {
+                Ok(t) => Some(time::Duration::seconds(t.parse::<i64>().unwrap())),
+                Err(_) => None,
+            };
 
         Self {
             environment: Environment {
@@ -857,18 +1639,45 @@ impl Production {
                 verifier_seen_timeout: test_timeout.unwrap_or_else(|| time::Duration::days(7)),
                 participant_lock_timeout: test_timeout.unwrap_or_else(|| time::Duration::minutes(20)),
                 queue_seen_timeout: test_timeout.unwrap_or_else(|| time::Duration::seconds(150)),
+                offline_contribution_timeout: offline_timeout
+                    .or(test_timeout)
+                    .unwrap_or_else(default_offline_contribution_timeout),
                 participant_ban_threshold: 5,
                 allow_current_contributors_in_queue: false,
                 allow_current_verifiers_in_queue: true,
                 queue_wait_time: 5,
+                minimum_update_interval: minimum_update_interval.unwrap_or_else(default_minimum_update_interval),
+                maximum_update_interval: maximum_update_interval.unwrap_or_else(default_maximum_update_interval),
+                max_rounds,
+                max_ceremony_duration,
+                final_window: final_window.unwrap_or_else(default_final_window),
+                destruction_attestation_deadline: destruction_attestation_deadline
+                    .unwrap_or_else(default_destruction_attestation_deadline),
 
                 coordinator_contributors: vec![Participant::new_contributor("coordinator-contributor")],
                 coordinator_verifiers: vec![Participant::new_verifier(keypair.pubkey())],
                 default_verifier_signing_key: keypair.sigkey().to_owned(),
+                verification_quorum,
+                quorum_verifiers,
+                operators,
+                control_keys,
+                verification_memory_budget,
+                compute_backend,
+                max_verification_retries: max_verification_retries.unwrap_or_else(default_max_verification_retries),
+                verification_backlog_threshold,
+                hash_algorithm,
+                ip_retention,
+                ip_prefixes,
+                queue_shuffle,
+                genesis_seed_ceremony,
+                unique_identity,
+                contribution_limit_exempt_keys,
 
                 software_version: 1,
+                api_deprecations,
                 deployment: Deployment::Production,
                 local_base_directory: "./transcript".to_string(),
+                storage_shards,
 
                 disable_reliability_zeroing: false,
             },
@@ -905,6 +1714,239 @@ impl std::default::Default for Production {
     }
 }
 
+/// The endpoints of the REST API which are served by debug builds only.
+pub const DEBUG_ENDPOINTS: [&str; 2] = ["/update", "/verify"];
+
+/// Whether the circuits of this build are the tiny test circuits, as in debug builds and rehearsal builds, rather than
+/// the full MASP circuits.
+pub const TEST_CIRCUITS: bool = cfg!(any(debug_assertions, feature = "rehearsal"));
+
+#[derive(Debug, Error)]
+pub enum PresetError {
+    #[error("Endpoint {0} is for debugging only and can't be served with the {1} preset")]
+    DebugEndpoint(String, Preset),
+    #[error("Invalid value {1} for {0}")]
+    InvalidOverride(&'static str, String),
+    #[error("Invalid preset {0}, expected one of dev, testnet, rehearsal or production")]
+    InvalidPreset(String),
+    #[error("The {0} preset can't run on the circuits of this build, rebuild with{1} the rehearsal feature")]
+    MismatchingCircuits(Preset, &'static str),
+}
+
+/// A named configuration of the ceremony, selected with the NAMADA_MPC_PRESET env variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Dev,
+    Testnet,
+    /// A public test ceremony, token-gated like the production one, on the tiny parameters of the test circuits.
+    Rehearsal,
+    Production,
+}
+
+impl Preset {
+    /// Returns the preset selected in the env, if any.
+    pub fn from_env() -> Result<Option<Self>, PresetError> {
+        match std::env::var("NAMADA_MPC_PRESET") {
+            Ok(preset) => Ok(Some(preset.parse()?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    ///
+    /// Returns the complete environment of this preset, with the given overrides applied.
+    ///
+    pub fn environment(&self, keypair: &KeyPair, overrides: &Overrides) -> Environment {
+        let mut environment = match self {
+            Preset::Dev => Development::new(keypair).environment,
+            Preset::Testnet => {
+                let mut environment = Production::new(keypair).environment;
+                environment.contributor_seen_timeout = time::Duration::minutes(5);
+                environment.verifier_seen_timeout = time::Duration::days(1);
+                environment.participant_lock_timeout = time::Duration::minutes(30);
+                environment.queue_seen_timeout = time::Duration::minutes(5);
+                environment.allow_current_contributors_in_queue = true;
+                environment.local_base_directory = "./transcript/testnet".to_string();
+                environment
+            }
+            Preset::Rehearsal => {
+                let mut environment = Production::new(keypair).environment;
+                environment.deployment = Deployment::Rehearsal;
+                environment.contributor_seen_timeout = time::Duration::minutes(2);
+                environment.verifier_seen_timeout = time::Duration::hours(1);
+                environment.participant_lock_timeout = time::Duration::minutes(10);
+                environment.queue_seen_timeout = time::Duration::minutes(2);
+                environment.offline_contribution_timeout = time::Duration::minutes(10);
+                environment.allow_current_contributors_in_queue = true;
+                environment.local_base_directory = "./transcript/rehearsal".to_string();
+                environment
+            }
+            Preset::Production => Production::new(keypair).environment,
+        };
+        overrides.apply(&mut environment);
+
+        environment
+    }
+
+    ///
+    /// Checks that none of the given endpoints is reserved to debug builds,
+    /// if this is the production preset.
+    ///
+    pub fn validate_endpoints<'a>(&self, endpoints: impl IntoIterator<Item = &'a str>) -> Result<(), PresetError> {
+        if *self != Preset::Production {
+            return Ok(());
+        }
+
+        match endpoints.into_iter().find(|endpoint| DEBUG_ENDPOINTS.contains(endpoint)) {
+            Some(endpoint) => Err(PresetError::DebugEndpoint(endpoint.to_owned(), *self)),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Checks that the circuits of this build match this preset: the rehearsal
+    /// preset runs on the test circuits only, and the production preset never does.
+    ///
+    pub fn validate_circuits(&self, test_circuits: bool) -> Result<(), PresetError> {
+        match (self, test_circuits) {
+            (Preset::Rehearsal, false) => Err(PresetError::MismatchingCircuits(*self, "")),
+            (Preset::Production, true) => Err(PresetError::MismatchingCircuits(*self, "out")),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Preset::Dev => write!(f, "dev"),
+            Preset::Testnet => write!(f, "testnet"),
+            Preset::Rehearsal => write!(f, "rehearsal"),
+            Preset::Production => write!(f, "production"),
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = PresetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "dev" | "development" => Ok(Preset::Dev),
+            "testnet" => Ok(Preset::Testnet),
+            "rehearsal" => Ok(Preset::Rehearsal),
+            "production" => Ok(Preset::Production),
+            _ => Err(PresetError::InvalidPreset(s.to_owned())),
+        }
+    }
+}
+
+/// The explicit overrides of the environment of a [`Preset`].
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    parameters: Option<Settings>,
+    participant_timeout: Option<time::Duration>,
+    offline_contribution_timeout: Option<time::Duration>,
+    verification_quorum: Option<usize>,
+    local_base_directory: Option<String>,
+    storage_shards: Option<Vec<String>>,
+}
+
+impl Overrides {
+    ///
+    /// Returns the overrides set in the env: NAMADA_MPC_TIMEOUT_SECONDS,
+    /// NAMADA_MPC_OFFLINE_TIMEOUT_SECONDS, NAMADA_MPC_VERIFICATION_QUORUM,
+    /// NAMADA_MPC_BASE_DIRECTORY and NAMADA_MPC_STORAGE_SHARDS.
+    ///
+    pub fn from_env() -> Result<Self, PresetError> {
+        let seconds = |var: &'static str| match std::env::var(var) {
+            Ok(t) => t
+                .parse::<i64>()
+                .map(|t| Some(time::Duration::seconds(t)))
+                .map_err(|_| PresetError::InvalidOverride(var, t)),
+            Err(_) => Ok(None),
+        };
+
+        let mut overrides = Self {
+            participant_timeout: seconds("NAMADA_MPC_TIMEOUT_SECONDS")?,
+            offline_contribution_timeout: seconds("NAMADA_MPC_OFFLINE_TIMEOUT_SECONDS")?,
+            local_base_directory: std::env::var("NAMADA_MPC_BASE_DIRECTORY").ok(),
+            ..Default::default()
+        };
+        if let Ok(k) = std::env::var("NAMADA_MPC_VERIFICATION_QUORUM") {
+            let quorum = k
+                .parse::<usize>()
+                .map_err(|_| PresetError::InvalidOverride("NAMADA_MPC_VERIFICATION_QUORUM", k))?;
+            overrides.verification_quorum = Some(quorum);
+        }
+        if let Ok(roots) = std::env::var("NAMADA_MPC_STORAGE_SHARDS") {
+            let roots: Vec<String> = roots
+                .split(',')
+                .map(str::trim)
+                .filter(|root| !root.is_empty())
+                .map(str::to_owned)
+                .collect();
+            overrides.storage_shards = Some(roots);
+        }
+
+        Ok(overrides)
+    }
+
+    pub fn parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = Some(parameters.to_settings());
+        self
+    }
+
+    /// Overrides the seen timeouts of the contributors, the verifiers and the queue, and the lock timeout.
+    pub fn participant_timeout(mut self, timeout: time::Duration) -> Self {
+        self.participant_timeout = Some(timeout);
+        self
+    }
+
+    pub fn offline_contribution_timeout(mut self, timeout: time::Duration) -> Self {
+        self.offline_contribution_timeout = Some(timeout);
+        self
+    }
+
+    pub fn verification_quorum(mut self, verification_quorum: usize) -> Self {
+        self.verification_quorum = Some(verification_quorum);
+        self
+    }
+
+    pub fn local_base_directory(mut self, directory: &str) -> Self {
+        self.local_base_directory = Some(directory.to_owned());
+        self
+    }
+
+    pub fn storage_shards(mut self, roots: &[String]) -> Self {
+        self.storage_shards = Some(roots.to_vec());
+        self
+    }
+
+    fn apply(&self, environment: &mut Environment) {
+        if let Some(parameters) = &self.parameters {
+            environment.parameters = parameters.clone();
+        }
+        if let Some(timeout) = self.participant_timeout {
+            environment.contributor_seen_timeout = timeout;
+            environment.verifier_seen_timeout = timeout;
+            environment.participant_lock_timeout = timeout;
+            environment.queue_seen_timeout = timeout;
+        }
+        if let Some(timeout) = self.offline_contribution_timeout.or(self.participant_timeout) {
+            environment.offline_contribution_timeout = timeout;
+        }
+        if let Some(verification_quorum) = self.verification_quorum {
+            environment.verification_quorum = verification_quorum;
+        }
+        if let Some(directory) = &self.local_base_directory {
+            environment.local_base_directory = directory.clone();
+        }
+        if let Some(roots) = &self.storage_shards {
+            environment.storage_shards = roots.clone();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::environment::*;
@@ -966,4 +2008,66 @@ mod tests {
         assert_eq!(ChunkSize::from(1639_usize), chunk_size);
         assert_eq!(number_of_chunks as u64, Testing::from(parameters).number_of_chunks());
     }
+
+    #[test]
+    fn test_presets() {
+        let keypair = KeyPair::new();
+
+        for (name, preset) in [
+            ("dev", Preset::Dev),
+            ("testnet", Preset::Testnet),
+            ("rehearsal", Preset::Rehearsal),
+            ("production", Preset::Production),
+        ] {
+            assert_eq!(preset, name.parse().unwrap());
+            assert_eq!(name, preset.to_string());
+        }
+        assert!("staging".parse::<Preset>().is_err());
+
+        let dev = Preset::Dev.environment(&keypair, &Overrides::default());
+        assert_eq!(&Deployment::Development, dev.deployment());
+        let testnet = Preset::Testnet.environment(&keypair, &Overrides::default());
+        assert_eq!(&Deployment::Production, testnet.deployment());
+        assert_eq!("./transcript/testnet", testnet.local_base_directory());
+        let rehearsal = Preset::Rehearsal.environment(&keypair, &Overrides::default());
+        assert_eq!(&Deployment::Rehearsal, rehearsal.deployment());
+        assert!(rehearsal.is_rehearsal() && !testnet.is_rehearsal());
+        assert_eq!("./transcript/rehearsal", rehearsal.local_base_directory());
+
+        let overrides = Overrides::default()
+            .participant_timeout(time::Duration::seconds(30))
+            .verification_quorum(3)
+            .local_base_directory("./transcript/override");
+        let production = Preset::Production.environment(&keypair, &overrides);
+        assert_eq!(&Deployment::Production, production.deployment());
+        assert_eq!(time::Duration::seconds(30), production.contributor_seen_timeout());
+        assert_eq!(time::Duration::seconds(30), production.offline_contribution_timeout());
+        assert_eq!(3, production.verification_quorum());
+        assert_eq!("./transcript/override", production.local_base_directory());
+    }
+
+    #[test]
+    fn test_preset_debug_endpoints() {
+        let endpoints = ["/contributor/join_queue", "/update", "/verify"];
+
+        assert!(Preset::Dev.validate_endpoints(endpoints).is_ok());
+        assert!(Preset::Testnet.validate_endpoints(endpoints).is_ok());
+        assert!(matches!(
+            Preset::Production.validate_endpoints(endpoints),
+            Err(PresetError::DebugEndpoint(endpoint, Preset::Production)) if endpoint == "/update"
+        ));
+        assert!(Preset::Production.validate_endpoints(["/contributor/join_queue", "/update_cohorts"]).is_ok());
+    }
+
+    #[test]
+    fn test_preset_circuits() {
+        assert!(Preset::Rehearsal.validate_circuits(true).is_ok());
+        assert!(matches!(
+            Preset::Rehearsal.validate_circuits(false),
+            Err(PresetError::MismatchingCircuits(Preset::Rehearsal, _))
+        ));
+        assert!(Preset::Production.validate_circuits(false).is_ok());
+        assert!(Preset::Production.validate_circuits(true).is_err());
+        assert!(Preset::Testnet.validate_circuits(true).is_ok());
+    }
 }
@@ -0,0 +1,415 @@
+//! Events of the ceremony pushed to external message queues, so that other systems can react to the progress of the
+//! ceremony without polling the REST API.
+//!
+//! The coordinator reports its events through the callback set with
+//! [`set_event_callback`](`crate::Coordinator::set_event_callback`). Each event is numbered, signed with the key of the
+//! coordinator and published as json to every configured [`EventPublisher`] from a background thread, so that a slow or
+//! unavailable broker never stalls the ceremony. The publishers are enabled through the following env variables, each
+//! requiring the matching cargo feature:
+//!
+//! - `EVENTS_NATS_URL` (`events-nats`): address of the NATS server
+//! - `EVENTS_KAFKA_BROKERS` (`events-kafka`): comma separated list of the addresses of the Kafka brokers
+//! - `EVENTS_AMQP_URL` (`events-amqp`): `amqp://` address of the AMQP broker
+//! - `EVENTS_AMQP_EXCHANGE`: exchange of the AMQP messages (defaults to the default exchange)
+//! - `EVENTS_SUBJECT`: prefix of the NATS subjects and of the AMQP routing keys, followed by the type of the event, and
+//!   Kafka topic (defaults to `namada.ceremony`)
+
+use crate::{
+    authentication::{KeyPair, Signature},
+    objects::Participant,
+};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    mpsc::{self, Sender},
+    Arc,
+    Mutex,
+};
+use time::OffsetDateTime;
+use tracing::{error, warn};
+
+const DEFAULT_SUBJECT: &str = "namada.ceremony";
+
+lazy_static! {
+    pub static ref EVENTS_NATS_URL: Option<String> = std::env::var("EVENTS_NATS_URL").ok();
+    pub static ref EVENTS_KAFKA_BROKERS: Option<Vec<String>> = std::env::var("EVENTS_KAFKA_BROKERS")
+        .ok()
+        .map(|brokers| brokers.split(',').map(|broker| broker.trim().to_owned()).collect());
+    pub static ref EVENTS_AMQP_URL: Option<String> = std::env::var("EVENTS_AMQP_URL").ok();
+    pub static ref EVENTS_AMQP_EXCHANGE: String = std::env::var("EVENTS_AMQP_EXCHANGE").unwrap_or_default();
+    pub static ref EVENTS_SUBJECT: String =
+        std::env::var("EVENTS_SUBJECT").unwrap_or_else(|_| DEFAULT_SUBJECT.to_owned());
+}
+
+/// An event of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CeremonyEvent {
+    /// A contributor joined the queue.
+    Joined { participant: Participant },
+    /// A contributor locked its chunk and started its contribution.
+    TurnStarted {
+        participant: Participant,
+        round_height: u64,
+        chunk_id: u64,
+    },
+    /// A contributor uploaded its contribution.
+    Uploaded {
+        participant: Participant,
+        round_height: u64,
+        chunk_id: u64,
+    },
+    /// The verification of a contribution completed.
+    Verified {
+        participant: Participant,
+        round_height: u64,
+        succeeded: bool,
+    },
+    /// The ceremony advanced to a new round.
+    RoundAdvanced { round_height: u64 },
+    /// A contributor hasn't attested the destruction of the toxic waste of its contribution yet.
+    DestructionAttestationReminder {
+        participant: Participant,
+        round_height: u64,
+        #[serde(with = "time::serde::timestamp")]
+        deadline: OffsetDateTime,
+    },
+    /// A new phase of the schedule of the ceremony started.
+    PhaseStarted { phase: String },
+}
+
+impl CeremonyEvent {
+    /// Returns the type of the event, as serialized in the `type` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CeremonyEvent::Joined { .. } => "joined",
+            CeremonyEvent::TurnStarted { .. } => "turn_started",
+            CeremonyEvent::Uploaded { .. } => "uploaded",
+            CeremonyEvent::Verified { .. } => "verified",
+            CeremonyEvent::RoundAdvanced { .. } => "round_advanced",
+            CeremonyEvent::DestructionAttestationReminder { .. } => "destruction_attestation_reminder",
+            CeremonyEvent::PhaseStarted { .. } => "phase_started",
+        }
+    }
+}
+
+/// An event numbered in the order of emission. The numbering restarts with the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventMessage {
+    pub sequence: u64,
+    #[serde(with = "time::serde::timestamp")]
+    pub timestamp: OffsetDateTime,
+    pub event: CeremonyEvent,
+}
+
+impl EventMessage {
+    /// Returns the message signed by the coordinator: the json encoding of the event, with the fields of the objects
+    /// sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the event with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedEvent> {
+        let event_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedEvent {
+            message: self,
+            coordinator_public_key,
+            signature: event_signature,
+        })
+    }
+}
+
+/// An [`EventMessage`] signed by the coordinator, as published to the message queues.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEvent {
+    pub message: EventMessage,
+    /// The public key of the coordinator. Consumers should check it against the key they expect rather than trust it.
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedEvent {
+    /// Returns `true` if the event is signed by the coordinator with the given public key.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        match self.message.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// A message queue to which the events are published.
+pub trait EventPublisher: Send {
+    /// Returns the name of the message queue, for the logs.
+    fn name(&self) -> &'static str;
+
+    /// Publishes the json encoded signed event of the given type.
+    fn publish(&mut self, event_name: &str, payload: &[u8]) -> anyhow::Result<()>;
+}
+
+/// Publishes the events to the subject `<EVENTS_SUBJECT>.<event type>` of a NATS server.
+#[cfg(feature = "events-nats")]
+pub struct NatsPublisher {
+    connection: nats::Connection,
+    subject: String,
+}
+
+#[cfg(feature = "events-nats")]
+impl NatsPublisher {
+    pub fn connect(url: &str, subject: String) -> anyhow::Result<Self> {
+        Ok(Self {
+            connection: nats::connect(url)?,
+            subject,
+        })
+    }
+}
+
+#[cfg(feature = "events-nats")]
+impl EventPublisher for NatsPublisher {
+    fn name(&self) -> &'static str {
+        "NATS"
+    }
+
+    fn publish(&mut self, event_name: &str, payload: &[u8]) -> anyhow::Result<()> {
+        Ok(self
+            .connection
+            .publish(&format!("{}.{}", self.subject, event_name), payload)?)
+    }
+}
+
+/// Publishes the events to the topic `<EVENTS_SUBJECT>` of a Kafka cluster, keyed by the type of the event.
+#[cfg(feature = "events-kafka")]
+pub struct KafkaPublisher {
+    producer: kafka::producer::Producer,
+    topic: String,
+}
+
+#[cfg(feature = "events-kafka")]
+impl KafkaPublisher {
+    pub fn connect(brokers: Vec<String>, topic: String) -> anyhow::Result<Self> {
+        let producer = kafka::producer::Producer::from_hosts(brokers)
+            .with_ack_timeout(std::time::Duration::from_secs(5))
+            .with_required_acks(kafka::producer::RequiredAcks::One)
+            .create()?;
+
+        Ok(Self { producer, topic })
+    }
+}
+
+#[cfg(feature = "events-kafka")]
+impl EventPublisher for KafkaPublisher {
+    fn name(&self) -> &'static str {
+        "Kafka"
+    }
+
+    fn publish(&mut self, event_name: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let record = kafka::producer::Record::from_key_value(&self.topic, event_name, payload);
+        Ok(self.producer.send(&record)?)
+    }
+}
+
+/// Publishes the events to an exchange of an AMQP broker, with the routing key `<EVENTS_SUBJECT>.<event type>`.
+#[cfg(feature = "events-amqp")]
+pub struct AmqpPublisher {
+    // The connection must outlive its channel
+    _connection: amiquip::Connection,
+    channel: amiquip::Channel,
+    exchange: String,
+    subject: String,
+}
+
+#[cfg(feature = "events-amqp")]
+impl AmqpPublisher {
+    pub fn connect(url: &str, exchange: String, subject: String) -> anyhow::Result<Self> {
+        let mut connection = amiquip::Connection::insecure_open(url)?;
+        let channel = connection.open_channel(None)?;
+
+        Ok(Self {
+            _connection: connection,
+            channel,
+            exchange,
+            subject,
+        })
+    }
+}
+
+#[cfg(feature = "events-amqp")]
+impl EventPublisher for AmqpPublisher {
+    fn name(&self) -> &'static str {
+        "AMQP"
+    }
+
+    fn publish(&mut self, event_name: &str, payload: &[u8]) -> anyhow::Result<()> {
+        let routing_key = format!("{}.{}", self.subject, event_name);
+        Ok(self
+            .channel
+            .basic_publish(self.exchange.clone(), amiquip::Publish::new(payload, routing_key))?)
+    }
+}
+
+/// Signs the events of the coordinator and publishes them to the message queues from a background thread.
+pub struct EventPublishers {
+    sender: Mutex<Sender<(OffsetDateTime, CeremonyEvent)>>,
+}
+
+impl EventPublishers {
+    /// Spawns the thread publishing the events to the given message queues, signed with the given keypair.
+    pub fn spawn(publishers: Vec<Box<dyn EventPublisher>>, signature: Arc<dyn Signature>, keypair: KeyPair) -> Self {
+        let (sender, receiver) = mpsc::channel::<(OffsetDateTime, CeremonyEvent)>();
+
+        std::thread::spawn(move || {
+            let mut publishers = publishers;
+            for (sequence, (timestamp, event)) in receiver.into_iter().enumerate() {
+                let event_name = event.name();
+                let message = EventMessage {
+                    sequence: sequence as u64,
+                    timestamp,
+                    event,
+                };
+                let payload = match message
+                    .sign(signature.as_ref(), keypair.pubkey().to_owned(), keypair.sigkey())
+                    .and_then(|signed| Ok(serde_json::to_vec(&signed)?))
+                {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        error!("Couldn't sign the {} event {}: {}", event_name, sequence, e);
+                        continue;
+                    }
+                };
+
+                for publisher in publishers.iter_mut() {
+                    if let Err(e) = publisher.publish(event_name, &payload) {
+                        warn!(
+                            "Couldn't publish the {} event {} to {}: {}",
+                            event_name,
+                            sequence,
+                            publisher.name(),
+                            e
+                        );
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Connects to the message queues configured in the env, if any, and spawns the thread publishing the events.
+    #[allow(unused_mut)]
+    pub fn from_env(signature: Arc<dyn Signature>, keypair: KeyPair) -> anyhow::Result<Option<Self>> {
+        let mut publishers: Vec<Box<dyn EventPublisher>> = vec![];
+
+        #[cfg(feature = "events-nats")]
+        if let Some(url) = EVENTS_NATS_URL.as_ref() {
+            publishers.push(Box::new(NatsPublisher::connect(url, EVENTS_SUBJECT.clone())?));
+        }
+        #[cfg(feature = "events-kafka")]
+        if let Some(brokers) = EVENTS_KAFKA_BROKERS.as_ref() {
+            publishers.push(Box::new(KafkaPublisher::connect(brokers.clone(), EVENTS_SUBJECT.clone())?));
+        }
+        #[cfg(feature = "events-amqp")]
+        if let Some(url) = EVENTS_AMQP_URL.as_ref() {
+            publishers.push(Box::new(AmqpPublisher::connect(
+                url,
+                EVENTS_AMQP_EXCHANGE.clone(),
+                EVENTS_SUBJECT.clone(),
+            )?));
+        }
+
+        if publishers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::spawn(publishers, signature, keypair)))
+    }
+
+    /// Returns the callback to be passed to [`set_event_callback`](`crate::Coordinator::set_event_callback`).
+    pub fn callback(self) -> Arc<dyn Fn(OffsetDateTime, CeremonyEvent) -> () + Send + Sync> {
+        Arc::new(move |timestamp, event| {
+            if self.sender.lock().unwrap().send((timestamp, event)).is_err() {
+                error!("The thread publishing the events is gone");
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::Production;
+
+    /// Collects the published events.
+    struct Collector(Arc<Mutex<Vec<(String, Vec<u8>)>>>);
+
+    impl EventPublisher for Collector {
+        fn name(&self) -> &'static str {
+            "collector"
+        }
+
+        fn publish(&mut self, event_name: &str, payload: &[u8]) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push((event_name.to_owned(), payload.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn publish_signed_events() {
+        let keypair = KeyPair::new();
+        let published = Arc::new(Mutex::new(vec![]));
+        let publishers = EventPublishers::spawn(
+            vec![Box::new(Collector(published.clone()))],
+            Arc::new(Production),
+            keypair.clone(),
+        );
+
+        let now = OffsetDateTime::from_unix_timestamp(1672531200).unwrap();
+        let participant = Participant::new_contributor("contributor");
+        let callback = publishers.callback();
+        callback(
+            now,
+            CeremonyEvent::Joined {
+                participant: participant.clone(),
+            },
+        );
+        callback(now, CeremonyEvent::RoundAdvanced { round_height: 1 });
+        drop(callback);
+
+        // Wait for the thread to publish the events
+        let start = std::time::Instant::now();
+        while published.lock().unwrap().len() < 2 {
+            assert!(start.elapsed() < std::time::Duration::from_secs(10));
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let published = published.lock().unwrap();
+        let events: Vec<SignedEvent> = published
+            .iter()
+            .map(|(_, payload)| serde_json::from_slice(payload).unwrap())
+            .collect();
+        assert_eq!(published[0].0, "joined");
+        assert_eq!(published[1].0, "round_advanced");
+        assert_eq!(events[0].message.sequence, 0);
+        assert_eq!(events[0].message.event, CeremonyEvent::Joined { participant });
+        assert_eq!(events[1].message.sequence, 1);
+        assert_eq!(events[1].message.timestamp, now);
+        assert!(events.iter().all(|event| event.verify(&Production, keypair.pubkey())));
+
+        // A tampered event is rejected
+        let mut tampered = events[1].clone();
+        tampered.message.event = CeremonyEvent::RoundAdvanced { round_height: 2 };
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+    }
+}
@@ -0,0 +1,619 @@
+//! Export of the audit log, of the statistics of the rounds and of the dispositions of the participants as flat
+//! tables, for the auditors to analyze the ceremony with standard tooling, e.g. by loading them in a SQL database.
+//!
+//! The tables are built from a [`Snapshot`] of the coordinator, taken from the storage by the
+//! `export <csv|parquet> <directory>` command of the coordinator, or from the running coordinator by
+//! `GET /admin/export?<format>`, which returns the files in a zip archive. Each table is written to `<table>.csv` or
+//! `<table>.parquet`, along with `schema.json` describing the columns of every table. The CSV files start with a header
+//! line, leave the null values empty and write the timestamps in RFC 3339; the Parquet files, which require the
+//! `export-parquet` cargo feature, store the timestamps as milliseconds since the Unix epoch, in UTC.
+//!
+//! - `audit_log`: one row per entry of the audit log of the participants, in chronological order
+//!   - `id` (integer): sequential identifier of the entry
+//!   - `timestamp` (timestamp)
+//!   - `participant` (text): `<public key>.contributor` or `<public key>.verifier`
+//!   - `event` (text): `joined_queue`, `withdrew`, `contributed`, `attestation_posted`, `destruction_attested`,
+//!     `destruction_attestation_missed`, `timed_out`, `dropped`, `banned` or `unbanned`
+//!   - `round_height` (integer, nullable): round of the contribution the event refers to
+//!   - `url` (text, nullable): url of the attestation post
+//!   - `content_hash` (text, nullable): hash of the content of the attestation post when registered
+//!   - `destruction_method` (text, nullable): method of destruction of the toxic waste
+//! - `rounds`: one row per round
+//!   - `round_height` (integer)
+//!   - `started_at` (timestamp, nullable)
+//!   - `finished_at` (timestamp, nullable): time at which the last contribution of the round was verified
+//!   - `duration_seconds` (integer, nullable)
+//!   - `contributors` (integer, nullable): number of contributors authorized in the round
+//!   - `verifiers` (integer, nullable): number of verifiers authorized in the round
+//!   - `contribution_files` (integer, nullable): number of contribution files in the transcript of the round
+//!   - `quorum_verifications` (integer, nullable): number of contributions verified by a quorum of verifiers
+//!   - `injections` (integer, nullable): number of contributions injected manually
+//!   - `attestations` (integer, nullable): number of attestations of the verifiers
+//!   - `computation_proofs` (integer, nullable): number of proofs of computation time
+//!   - `rehearsal` (boolean, nullable)
+//!   - `chain_hash` (text, nullable): head of the hash chain of the transcript up to the round
+//! - `participants`: one row per participant ever seen, ordered by first appearance
+//!   - `participant` (text)
+//!   - `disposition` (text): `Queued`, `Withdrew`, `Contributed`, `TimedOut`, `Dropped`, `Banned` or `Unbanned`
+//!   - `first_seen` (timestamp)
+//!   - `last_seen` (timestamp)
+//!   - `events` (integer): number of entries of the participant in the audit log
+//!   - `contributions` (integer): number of rounds the participant contributed to
+//!   - `last_round` (integer, nullable): last round the participant contributed to
+//!
+//! The columns without the nullable mention are never null.
+
+use crate::{
+    backup::Snapshot,
+    objects::{AuditEntry, ParticipantEvent, ParticipantRecord},
+    storage::hash_chain,
+};
+
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use thiserror::Error;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// The file describing the columns of the exported tables.
+pub const SCHEMA_FILE: &str = "schema.json";
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Error while writing CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Error in IO: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("Error in json: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "export-parquet")]
+    #[error("Error while writing Parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Export format {0} is not supported, expected csv or parquet with the export-parquet feature")]
+    UnsupportedFormat(String),
+    #[error("Error while writing the archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+type Result<T> = std::result::Result<T, ExportError>;
+
+/// The format of the exported tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Returns the extension of the files of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" if cfg!(feature = "export-parquet") => Ok(ExportFormat::Parquet),
+            _ => Err(ExportError::UnsupportedFormat(s.to_owned())),
+        }
+    }
+}
+
+/// The type of the values of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Integer,
+    Timestamp,
+    Text,
+    Boolean,
+}
+
+/// The description of a column of an exported table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ColumnSchema {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    pub nullable: bool,
+}
+
+const fn column(name: &'static str, column_type: ColumnType, nullable: bool) -> ColumnSchema {
+    ColumnSchema {
+        name,
+        column_type,
+        nullable,
+    }
+}
+
+pub const AUDIT_LOG_COLUMNS: &[ColumnSchema] = &[
+    column("id", ColumnType::Integer, false),
+    column("timestamp", ColumnType::Timestamp, false),
+    column("participant", ColumnType::Text, false),
+    column("event", ColumnType::Text, false),
+    column("round_height", ColumnType::Integer, true),
+    column("url", ColumnType::Text, true),
+    column("content_hash", ColumnType::Text, true),
+    column("destruction_method", ColumnType::Text, true),
+];
+
+pub const ROUNDS_COLUMNS: &[ColumnSchema] = &[
+    column("round_height", ColumnType::Integer, false),
+    column("started_at", ColumnType::Timestamp, true),
+    column("finished_at", ColumnType::Timestamp, true),
+    column("duration_seconds", ColumnType::Integer, true),
+    column("contributors", ColumnType::Integer, true),
+    column("verifiers", ColumnType::Integer, true),
+    column("contribution_files", ColumnType::Integer, true),
+    column("quorum_verifications", ColumnType::Integer, true),
+    column("injections", ColumnType::Integer, true),
+    column("attestations", ColumnType::Integer, true),
+    column("computation_proofs", ColumnType::Integer, true),
+    column("rehearsal", ColumnType::Boolean, true),
+    column("chain_hash", ColumnType::Text, true),
+];
+
+pub const PARTICIPANTS_COLUMNS: &[ColumnSchema] = &[
+    column("participant", ColumnType::Text, false),
+    column("disposition", ColumnType::Text, false),
+    column("first_seen", ColumnType::Timestamp, false),
+    column("last_seen", ColumnType::Timestamp, false),
+    column("events", ColumnType::Integer, false),
+    column("contributions", ColumnType::Integer, false),
+    column("last_round", ColumnType::Integer, true),
+];
+
+/// A value of a cell of an exported table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Timestamp(OffsetDateTime),
+    Text(String),
+    Boolean(bool),
+}
+
+impl Value {
+    fn integer(value: Option<u64>) -> Self {
+        value.map_or(Value::Null, |value| Value::Integer(value as i64))
+    }
+
+    fn timestamp(value: Option<OffsetDateTime>) -> Self {
+        value.map_or(Value::Null, Value::Timestamp)
+    }
+
+    fn text(value: Option<&str>) -> Self {
+        value.map_or(Value::Null, |value| Value::Text(value.to_owned()))
+    }
+
+    /// Returns the value as written in a CSV file.
+    fn to_csv(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Integer(value) => value.to_string(),
+            Value::Timestamp(value) => value.format(&Rfc3339).unwrap_or_default(),
+            Value::Text(value) => value.clone(),
+            Value::Boolean(value) => value.to_string(),
+        }
+    }
+}
+
+/// An exported table, with the values of its rows in the order of its columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub name: &'static str,
+    pub columns: &'static [ColumnSchema],
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Returns the table encoded in the given format.
+    pub fn encode(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        match format {
+            ExportFormat::Csv => self.encode_csv(),
+            #[cfg(feature = "export-parquet")]
+            ExportFormat::Parquet => self.encode_parquet(),
+            #[cfg(not(feature = "export-parquet"))]
+            ExportFormat::Parquet => Err(ExportError::UnsupportedFormat(format.extension().to_owned())),
+        }
+    }
+
+    fn encode_csv(&self) -> Result<Vec<u8>> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(self.columns.iter().map(|column| column.name))?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(Value::to_csv))?;
+        }
+
+        writer.into_inner().map_err(|e| e.into_error().into())
+    }
+
+    #[cfg(feature = "export-parquet")]
+    fn encode_parquet(&self) -> Result<Vec<u8>> {
+        use parquet::{
+            column::writer::ColumnWriter,
+            data_type::ByteArray,
+            file::{properties::WriterProperties, writer::SerializedFileWriter},
+            schema::parser::parse_message_type,
+        };
+        use std::sync::Arc;
+
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let repetition = if column.nullable { "OPTIONAL" } else { "REQUIRED" };
+                let (physical_type, logical_type) = match column.column_type {
+                    ColumnType::Integer => ("INT64", ""),
+                    ColumnType::Timestamp => ("INT64", " (TIMESTAMP_MILLIS)"),
+                    ColumnType::Text => ("BYTE_ARRAY", " (UTF8)"),
+                    ColumnType::Boolean => ("BOOLEAN", ""),
+                };
+                format!("{} {} {}{};", repetition, physical_type, column.name, logical_type)
+            })
+            .collect();
+        let schema = Arc::new(parse_message_type(&format!(
+            "message {} {{ {} }}",
+            self.name,
+            fields.join(" ")
+        ))?);
+
+        let mut buffer = vec![];
+        let mut writer = SerializedFileWriter::new(&mut buffer, schema, Arc::new(WriterProperties::builder().build()))?;
+        let mut row_group = writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column_writer) = row_group.next_column()? {
+            let values = self.rows.iter().map(|row| &row[index]);
+            // The definition levels tell the null values apart in the optional columns
+            let levels: Vec<i16> = values.clone().map(|value| (*value != Value::Null) as i16).collect();
+            let levels = match self.columns[index].nullable {
+                true => Some(levels.as_slice()),
+                false => None,
+            };
+
+            match column_writer.untyped() {
+                ColumnWriter::Int64ColumnWriter(typed) => {
+                    let values: Vec<i64> = values
+                        .filter_map(|value| match value {
+                            Value::Integer(value) => Some(*value),
+                            Value::Timestamp(value) => Some((value.unix_timestamp_nanos() / 1_000_000) as i64),
+                            _ => None,
+                        })
+                        .collect();
+                    typed.write_batch(&values, levels, None)?;
+                }
+                ColumnWriter::ByteArrayColumnWriter(typed) => {
+                    let values: Vec<ByteArray> = values
+                        .filter_map(|value| match value {
+                            Value::Text(value) => Some(ByteArray::from(value.as_str())),
+                            _ => None,
+                        })
+                        .collect();
+                    typed.write_batch(&values, levels, None)?;
+                }
+                ColumnWriter::BoolColumnWriter(typed) => {
+                    let values: Vec<bool> = values
+                        .filter_map(|value| match value {
+                            Value::Boolean(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect();
+                    typed.write_batch(&values, levels, None)?;
+                }
+                _ => unreachable!("The columns are integers, timestamps, texts or booleans"),
+            }
+            column_writer.close()?;
+            index += 1;
+        }
+        row_group.close()?;
+        writer.close()?;
+
+        Ok(buffer)
+    }
+}
+
+/// The tables exported from a [`Snapshot`] of the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CeremonyExport {
+    pub tables: Vec<Table>,
+}
+
+impl CeremonyExport {
+    /// Builds the tables from the given snapshot.
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        Self {
+            tables: vec![
+                audit_log_table(&snapshot.participants),
+                rounds_table(snapshot),
+                participants_table(&snapshot.participants),
+            ],
+        }
+    }
+
+    /// Returns the names and the contents of the files of the export in the given format, the schema last.
+    pub fn files(&self, format: ExportFormat) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut files = self
+            .tables
+            .iter()
+            .map(|table| Ok((format!("{}.{}", table.name, format.extension()), table.encode(format)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let schema: BTreeMap<&str, &[ColumnSchema]> =
+            self.tables.iter().map(|table| (table.name, table.columns)).collect();
+        files.push((SCHEMA_FILE.to_owned(), serde_json::to_vec_pretty(&schema)?));
+
+        Ok(files)
+    }
+
+    /// Writes the files of the export in the given format to the given directory, created if missing, and returns
+    /// their paths.
+    pub fn write(&self, directory: &Path, format: ExportFormat) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(directory)?;
+
+        self.files(format)?
+            .into_iter()
+            .map(|(name, content)| {
+                let path = directory.join(name);
+                fs::write(&path, content)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Returns the zip archive of the files of the export in the given format.
+    pub fn archive(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        let mut archive = zip::ZipWriter::new(Cursor::new(vec![]));
+        for (name, content) in self.files(format)? {
+            archive.start_file(name, zip::write::FileOptions::default())?;
+            archive.write_all(&content)?;
+        }
+
+        Ok(archive.finish()?.into_inner())
+    }
+}
+
+/// Returns the name of the event in the `event` column of the audit log.
+fn event_name(event: &ParticipantEvent) -> &'static str {
+    match event {
+        ParticipantEvent::JoinedQueue => "joined_queue",
+        ParticipantEvent::Withdrew => "withdrew",
+        ParticipantEvent::Contributed { .. } => "contributed",
+        ParticipantEvent::AttestationPosted { .. } => "attestation_posted",
+        ParticipantEvent::DestructionAttested { .. } => "destruction_attested",
+        ParticipantEvent::DestructionAttestationMissed { .. } => "destruction_attestation_missed",
+        ParticipantEvent::TimedOut => "timed_out",
+        ParticipantEvent::Dropped => "dropped",
+        ParticipantEvent::Banned => "banned",
+        ParticipantEvent::Unbanned => "unbanned",
+    }
+}
+
+/// Returns the round the event refers to, if any.
+fn event_round(event: &ParticipantEvent) -> Option<u64> {
+    match event {
+        ParticipantEvent::Contributed { round_height }
+        | ParticipantEvent::AttestationPosted { round_height, .. }
+        | ParticipantEvent::DestructionAttested { round_height, .. }
+        | ParticipantEvent::DestructionAttestationMissed { round_height } => Some(*round_height),
+        _ => None,
+    }
+}
+
+fn audit_log_table(records: &[ParticipantRecord]) -> Table {
+    let mut entries: Vec<&AuditEntry> = records.iter().flat_map(|record| &record.audit_entries).collect();
+    entries.sort_by_key(|entry| entry.id);
+
+    let rows = entries
+        .into_iter()
+        .map(|entry| {
+            let (url, content_hash, destruction_method) = match &entry.event {
+                ParticipantEvent::AttestationPosted { url, content_hash, .. } => {
+                    (Some(url.as_str()), Some(content_hash.as_str()), None)
+                }
+                ParticipantEvent::DestructionAttested { method, .. } => (None, None, Some(method.name())),
+                _ => (None, None, None),
+            };
+
+            vec![
+                Value::Integer(entry.id as i64),
+                Value::Timestamp(entry.timestamp),
+                Value::Text(entry.participant.to_string()),
+                Value::Text(event_name(&entry.event).to_owned()),
+                Value::integer(event_round(&entry.event)),
+                Value::text(url),
+                Value::text(content_hash),
+                Value::text(destruction_method),
+            ]
+        })
+        .collect();
+
+    Table {
+        name: "audit_log",
+        columns: AUDIT_LOG_COLUMNS,
+        rows,
+    }
+}
+
+fn rounds_table(snapshot: &Snapshot) -> Table {
+    let rounds: BTreeMap<u64, _> = snapshot
+        .rounds
+        .iter()
+        .map(|round| (round.round_height(), round))
+        .collect();
+    let manifests: BTreeMap<u64, _> = snapshot
+        .manifests
+        .iter()
+        .map(|manifest| (manifest.round_height, manifest))
+        .collect();
+    let chain_hashes: BTreeMap<u64, String> = hash_chain(&snapshot.manifests).into_iter().collect();
+    let heights: BTreeSet<u64> = rounds.keys().chain(manifests.keys()).copied().collect();
+
+    let rows = heights
+        .into_iter()
+        .map(|height| {
+            let round = rounds.get(&height);
+            let manifest = manifests.get(&height);
+            let started_at = round.and_then(|round| round.started_at());
+            let finished_at = round.and_then(|round| round.finished_at());
+            let duration = match (started_at, finished_at) {
+                (Some(started_at), Some(finished_at)) => Some((finished_at - started_at).whole_seconds().max(0) as u64),
+                _ => None,
+            };
+
+            vec![
+                Value::Integer(height as i64),
+                Value::timestamp(started_at),
+                Value::timestamp(finished_at),
+                Value::integer(duration),
+                Value::integer(round.map(|round| round.number_of_contributors())),
+                Value::integer(round.map(|round| round.number_of_verifiers())),
+                Value::integer(manifest.map(|manifest| manifest.contribution_files().count() as u64)),
+                Value::integer(manifest.map(|manifest| manifest.verifications.len() as u64)),
+                Value::integer(manifest.map(|manifest| manifest.injections.len() as u64)),
+                Value::integer(manifest.map(|manifest| manifest.attestations.len() as u64)),
+                Value::integer(manifest.map(|manifest| manifest.computation_proofs.len() as u64)),
+                manifest.map_or(Value::Null, |manifest| Value::Boolean(manifest.rehearsal)),
+                Value::text(chain_hashes.get(&height).map(String::as_str)),
+            ]
+        })
+        .collect();
+
+    Table {
+        name: "rounds",
+        columns: ROUNDS_COLUMNS,
+        rows,
+    }
+}
+
+fn participants_table(records: &[ParticipantRecord]) -> Table {
+    let rows = records
+        .iter()
+        .map(|record| {
+            let contributed: BTreeSet<u64> = record
+                .audit_entries
+                .iter()
+                .filter_map(|entry| match entry.event {
+                    ParticipantEvent::Contributed { round_height } => Some(round_height),
+                    _ => None,
+                })
+                .collect();
+
+            vec![
+                Value::Text(record.participant.to_string()),
+                Value::Text(format!("{:?}", record.disposition)),
+                Value::Timestamp(record.first_seen),
+                Value::Timestamp(record.last_seen),
+                Value::Integer(record.audit_entries.len() as i64),
+                Value::Integer(contributed.len() as i64),
+                Value::integer(contributed.iter().next_back().copied()),
+            ]
+        })
+        .collect();
+
+    Table {
+        name: "participants",
+        columns: PARTICIPANTS_COLUMNS,
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::{DestructionMethod, Participant, ParticipantHistory};
+
+    #[test]
+    fn test_export_csv() {
+        let contributor = Participant::new_contributor("contributor");
+        let other = Participant::new_contributor("other");
+        let start = OffsetDateTime::from_unix_timestamp(1672531200).unwrap();
+        let end = start + time::Duration::minutes(10);
+
+        let mut history = ParticipantHistory::default();
+        history.record(&contributor, ParticipantEvent::JoinedQueue, start);
+        history.record(&other, ParticipantEvent::JoinedQueue, start);
+        history.record(&contributor, ParticipantEvent::Contributed { round_height: 1 }, end);
+        history.record(&other, ParticipantEvent::TimedOut, end);
+        history.record(
+            &contributor,
+            ParticipantEvent::DestructionAttested {
+                round_height: 1,
+                method: DestructionMethod::SecureErase,
+            },
+            end,
+        );
+
+        let snapshot = Snapshot {
+            created_at: end,
+            round_height: 1,
+            coordinator_state: serde_json::Value::Null,
+            rounds: vec![],
+            manifests: vec![],
+            participants: history.records(),
+        };
+        let export = CeremonyExport::from_snapshot(&snapshot);
+        let files = export.files(ExportFormat::Csv).unwrap();
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec![
+            "audit_log.csv",
+            "rounds.csv",
+            "participants.csv",
+            SCHEMA_FILE
+        ]);
+
+        // The audit log is in chronological order, whatever the order of the participants
+        let audit_log = String::from_utf8(files[0].1.clone()).unwrap();
+        let lines: Vec<&str> = audit_log.lines().collect();
+        assert_eq!(
+            lines[0],
+            "id,timestamp,participant,event,round_height,url,content_hash,destruction_method"
+        );
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[3],
+            "2,2023-01-01T00:10:00Z,contributor.contributor,contributed,1,,,"
+        );
+        assert_eq!(
+            lines[5],
+            "4,2023-01-01T00:10:00Z,contributor.contributor,destruction_attested,1,,,secure_erase"
+        );
+
+        // No round without rounds nor manifests
+        assert_eq!(String::from_utf8(files[1].1.clone()).unwrap().lines().count(), 1);
+
+        let participants = String::from_utf8(files[2].1.clone()).unwrap();
+        let lines: Vec<&str> = participants.lines().collect();
+        assert_eq!(
+            lines[1],
+            "contributor.contributor,Contributed,2023-01-01T00:00:00Z,2023-01-01T00:10:00Z,3,1,1"
+        );
+        assert_eq!(
+            lines[2],
+            "other.contributor,TimedOut,2023-01-01T00:00:00Z,2023-01-01T00:10:00Z,2,0,"
+        );
+
+        // Every column of every table is described in the schema
+        let schema: serde_json::Value = serde_json::from_slice(&files[3].1).unwrap();
+        assert_eq!(schema["rounds"].as_array().unwrap().len(), ROUNDS_COLUMNS.len());
+        assert_eq!(schema["audit_log"][4]["name"], "round_height");
+        assert_eq!(schema["audit_log"][4]["type"], "integer");
+        assert_eq!(schema["audit_log"][4]["nullable"], true);
+
+        assert!(matches!(
+            "xlsx".parse::<ExportFormat>(),
+            Err(ExportError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+//! Hash functions of the contribution files.
+//!
+//! Every challenge starts with the hash of the response it was derived from, and the contributions are signed over the
+//! hashes of their challenge, response and next challenge. The hash function is chosen per ceremony, in the
+//! environment of the coordinator, and recorded in the genesis config published by `/ceremony/metadata`, so that a
+//! ceremony can adopt another function without ambiguity on how its files are chained. The header of the files keeps
+//! its size of [`CONTRIBUTION_HASH_SIZE`] bytes whatever the function: shorter digests are padded with zeros.
+
+use serde::{Deserialize, Serialize};
+use setup_utils::{calculate_hash, GenericArray, U64};
+use sha2::{
+    digest::{Digest, DynDigest},
+    Sha256,
+};
+use std::{fmt, panic::RefUnwindSafe, str::FromStr};
+
+/// The size in bytes of the hashes of the contribution files, as written in the header of the challenges.
+pub const CONTRIBUTION_HASH_SIZE: usize = 64;
+
+/// A hash function of the contribution files. Hashers are used across the verification, whose panics are caught on
+/// invalid contributions.
+pub trait ContributionHasher: Send + Sync + RefUnwindSafe {
+    /// Returns the algorithm of the hasher.
+    fn algorithm(&self) -> HashAlgorithm;
+
+    /// Returns the digest of the given bytes.
+    fn digest(&self, bytes: &[u8]) -> Vec<u8>;
+
+    /// Returns a hash to feed one chunk at a time, for the files too large to be read in memory.
+    fn incremental(&self) -> IncrementalHash;
+
+    /// Returns the hash of the given bytes as written in the header of the challenges: the digest padded with zeros to
+    /// [`CONTRIBUTION_HASH_SIZE`] bytes.
+    fn hash(&self, bytes: &[u8]) -> GenericArray<u8, U64> {
+        pad_digest(&self.digest(bytes))
+    }
+}
+
+/// A hash fed one chunk at a time, equal to [`ContributionHasher::hash`] of the whole content once finalized.
+pub struct IncrementalHash(Box<dyn DynDigest + Send>);
+
+impl IncrementalHash {
+    /// Feeds the next chunk of the content.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    /// Returns the hash of the content fed so far, padded like [`ContributionHasher::hash`].
+    pub fn finalize(self) -> GenericArray<u8, U64> {
+        pad_digest(&self.0.finalize())
+    }
+}
+
+fn pad_digest(digest: &[u8]) -> GenericArray<u8, U64> {
+    let mut hash = GenericArray::default();
+    hash[..digest.len()].copy_from_slice(digest);
+    hash
+}
+
+/// BLAKE2b with 512 bits output, the default hash function.
+pub struct Blake2b512Hasher;
+
+impl ContributionHasher for Blake2b512Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Blake2b512
+    }
+
+    fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+        calculate_hash(bytes).to_vec()
+    }
+
+    fn incremental(&self) -> IncrementalHash {
+        IncrementalHash(Box::new(blake2::Blake2b512::default()))
+    }
+}
+
+/// SHA-256.
+pub struct Sha256Hasher;
+
+impl ContributionHasher for Sha256Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Sha256
+    }
+
+    fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+        Sha256::digest(bytes).to_vec()
+    }
+
+    fn incremental(&self) -> IncrementalHash {
+        IncrementalHash(Box::new(Sha256::default()))
+    }
+}
+
+/// SHA3 with 512 bits output, available with the `sha3` feature.
+#[cfg(feature = "sha3")]
+pub struct Sha3_512Hasher;
+
+#[cfg(feature = "sha3")]
+impl ContributionHasher for Sha3_512Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Sha3_512
+    }
+
+    fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+        sha3::Sha3_512::digest(bytes).to_vec()
+    }
+
+    fn incremental(&self) -> IncrementalHash {
+        IncrementalHash(Box::new(sha3::Sha3_512::default()))
+    }
+}
+
+/// The hash function of the contribution files of a ceremony.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    #[serde(rename = "blake2b-512")]
+    Blake2b512,
+    #[serde(rename = "sha-256")]
+    Sha256,
+    #[cfg(feature = "sha3")]
+    #[serde(rename = "sha3-512")]
+    Sha3_512,
+}
+
+impl HashAlgorithm {
+    /// Returns the hasher of the algorithm.
+    pub fn hasher(&self) -> &'static dyn ContributionHasher {
+        match self {
+            HashAlgorithm::Blake2b512 => &Blake2b512Hasher,
+            HashAlgorithm::Sha256 => &Sha256Hasher,
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_512 => &Sha3_512Hasher,
+        }
+    }
+
+    /// Returns `true` for the default algorithm, BLAKE2b-512.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for HashAlgorithm {
+    fn default() -> Self {
+        HashAlgorithm::Blake2b512
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Blake2b512 => write!(f, "blake2b-512"),
+            HashAlgorithm::Sha256 => write!(f, "sha-256"),
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_512 => write!(f, "sha3-512"),
+        }
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blake2b-512" => Ok(HashAlgorithm::Blake2b512),
+            "sha-256" => Ok(HashAlgorithm::Sha256),
+            #[cfg(feature = "sha3")]
+            "sha3-512" => Ok(HashAlgorithm::Sha3_512),
+            _ => Err(format!("Unsupported hash algorithm {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashers() {
+        let blake2b = HashAlgorithm::Blake2b512.hasher();
+        assert_eq!(blake2b.hash(b"challenge"), calculate_hash(b"challenge"));
+
+        // Shorter digests are padded to the size of the header
+        let sha256 = HashAlgorithm::Sha256.hasher();
+        let hash = sha256.hash(b"challenge");
+        assert_eq!(&hash[..32], Sha256::digest(b"challenge").as_slice());
+        assert!(hash[32..].iter().all(|byte| *byte == 0));
+
+        for &algorithm in &[HashAlgorithm::Blake2b512, HashAlgorithm::Sha256] {
+            assert_eq!(algorithm.hasher().algorithm(), algorithm);
+
+            // Hashing one chunk at a time gives the same hash
+            let mut incremental = algorithm.hasher().incremental();
+            incremental.update(b"chall");
+            incremental.update(b"enge");
+            assert_eq!(incremental.finalize(), algorithm.hasher().hash(b"challenge"));
+
+            assert_eq!(algorithm.to_string().parse::<HashAlgorithm>().unwrap(), algorithm);
+            assert_eq!(serde_json::to_string(&algorithm).unwrap(), format!("\"{}\"", algorithm));
+        }
+        assert!("md5".parse::<HashAlgorithm>().is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Optional check that the contributors joining the queue from a browser are humans, on top of the proof-of-work, to
+//! keep bots out of the web contributions.
+//!
+//! The browser solves the challenge of a provider like Cloudflare Turnstile or hCaptcha, and sends the token it
+//! receives along with the request to join the queue. The coordinator then validates the token with the provider. The
+//! check is enabled by setting the `HUMAN_VERIFICATION_PROVIDER` env variable to the name of the provider, and
+//! `HUMAN_VERIFICATION_SECRET` to the secret key of the site registered with it.
+
+use crate::objects::HumanVerification;
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::{net::IpAddr, sync::Arc, time::Duration};
+use time::OffsetDateTime;
+
+lazy_static! {
+    /// The name of the provider of the challenges: `turnstile`, `hcaptcha` or `recaptcha`.
+    pub static ref HUMAN_VERIFICATION_PROVIDER: Option<String> = std::env::var("HUMAN_VERIFICATION_PROVIDER").ok();
+    /// The hostname of the site serving the challenges. If set, tokens solved on other sites are rejected.
+    pub static ref HUMAN_VERIFICATION_HOSTNAME: Option<String> = std::env::var("HUMAN_VERIFICATION_HOSTNAME").ok();
+}
+
+/// Timeout of the requests to the provider.
+const SITEVERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Upper bound of the size of a token, larger ones are rejected without asking the provider.
+const MAX_TOKEN_SIZE: usize = 2048;
+
+/// A service validating, server-side, the tokens of the challenges solved by the browsers.
+#[rocket::async_trait]
+pub trait HumanVerificationProvider: Send + Sync {
+    /// The name of the provider, recorded with the outcome of the verification.
+    fn name(&self) -> &str;
+
+    /// Validates the token solved by the browser at the given address, and returns the hostname of the site on which
+    /// the challenge was solved, if reported by the provider.
+    async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> Result<Option<String>, String>;
+}
+
+/// The answer of a `siteverify` endpoint, in the format shared by Turnstile, hCaptcha and reCAPTCHA.
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// A provider exposing a `siteverify` endpoint, to which the token and the secret key of the site are posted.
+#[derive(Clone, Debug)]
+pub struct SiteVerifyProvider {
+    name: String,
+    url: String,
+    secret: String,
+}
+
+impl SiteVerifyProvider {
+    pub fn new(name: &str, url: &str, secret: String) -> Self {
+        Self {
+            name: name.to_owned(),
+            url: url.to_owned(),
+            secret,
+        }
+    }
+
+    /// Cloudflare Turnstile.
+    pub fn turnstile(secret: String) -> Self {
+        Self::new(
+            "turnstile",
+            "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            secret,
+        )
+    }
+
+    /// hCaptcha.
+    pub fn hcaptcha(secret: String) -> Self {
+        Self::new("hcaptcha", "https://api.hcaptcha.com/siteverify", secret)
+    }
+
+    /// Google reCAPTCHA.
+    pub fn recaptcha(secret: String) -> Self {
+        Self::new("recaptcha", "https://www.google.com/recaptcha/api/siteverify", secret)
+    }
+}
+
+#[rocket::async_trait]
+impl HumanVerificationProvider for SiteVerifyProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> Result<Option<String>, String> {
+        let mut form = vec![("secret", self.secret.clone()), ("response", token.to_owned())];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip.to_string()));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(SITEVERIFY_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let body = client
+            .post(&self.url)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("the provider is unreachable: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("the provider is unreachable: {}", e))?;
+        let response: SiteVerifyResponse =
+            serde_json::from_slice(&body).map_err(|e| format!("unexpected answer of the provider: {}", e))?;
+
+        if !response.success {
+            return Err(format!("rejected by the provider: {}", response.error_codes.join(", ")));
+        }
+
+        Ok(response.hostname)
+    }
+}
+
+/// Checks the tokens sent by the contributors joining the queue. Disabled if no provider is set.
+#[derive(Clone, Default)]
+pub struct HumanVerifier {
+    provider: Option<Arc<dyn HumanVerificationProvider>>,
+    hostname: Option<String>,
+}
+
+impl HumanVerifier {
+    pub fn new(provider: Option<Arc<dyn HumanVerificationProvider>>, hostname: Option<String>) -> Self {
+        Self { provider, hostname }
+    }
+
+    /// Returns the verifier configured in the env, disabled if no provider is set.
+    ///
+    /// # Panics
+    /// If the provider is unknown or its secret key is missing.
+    pub fn from_env() -> Self {
+        let provider: Arc<dyn HumanVerificationProvider> = match HUMAN_VERIFICATION_PROVIDER.as_deref() {
+            None => return Self::default(),
+            Some(name) => {
+                let secret =
+                    std::env::var("HUMAN_VERIFICATION_SECRET").expect("Missing required env HUMAN_VERIFICATION_SECRET");
+                match name {
+                    "turnstile" => Arc::new(SiteVerifyProvider::turnstile(secret)),
+                    "hcaptcha" => Arc::new(SiteVerifyProvider::hcaptcha(secret)),
+                    "recaptcha" => Arc::new(SiteVerifyProvider::recaptcha(secret)),
+                    _ => panic!("HUMAN_VERIFICATION_PROVIDER must be one of turnstile, hcaptcha or recaptcha"),
+                }
+            }
+        };
+
+        Self::new(Some(provider), HUMAN_VERIFICATION_HOSTNAME.clone())
+    }
+
+    /// Returns `true` if a human verification is required to join the queue.
+    pub fn is_enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Validates the token with the provider and returns the outcome to be recorded. Returns [`None`] if the human
+    /// verification is disabled.
+    pub async fn check(
+        &self,
+        token: Option<&str>,
+        remote_ip: Option<IpAddr>,
+    ) -> Result<Option<HumanVerification>, String> {
+        let provider = match &self.provider {
+            Some(provider) => provider,
+            None => return Ok(None),
+        };
+
+        let token = token.ok_or_else(|| String::from("missing token"))?;
+        if token.is_empty() || token.len() > MAX_TOKEN_SIZE {
+            return Err(String::from("malformed token"));
+        }
+
+        let hostname = provider.verify(token, remote_ip).await?;
+        if let Some(expected) = &self.hostname {
+            if hostname.as_ref() != Some(expected) {
+                return Err(format!(
+                    "the challenge was solved on {}, expected {}",
+                    hostname.as_deref().unwrap_or("an unknown site"),
+                    expected
+                ));
+            }
+        }
+
+        Ok(Some(HumanVerification {
+            provider: provider.name().to_owned(),
+            hostname,
+            verified_at: OffsetDateTime::now_utc(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accepts a single token, solved on the given site.
+    struct MockProvider {
+        token: &'static str,
+        hostname: &'static str,
+    }
+
+    #[rocket::async_trait]
+    impl HumanVerificationProvider for MockProvider {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn verify(&self, token: &str, _remote_ip: Option<IpAddr>) -> Result<Option<String>, String> {
+            if token == self.token {
+                Ok(Some(self.hostname.to_owned()))
+            } else {
+                Err(String::from("rejected by the provider: invalid-input-response"))
+            }
+        }
+    }
+
+    fn verifier(hostname: Option<&str>) -> HumanVerifier {
+        let provider = MockProvider {
+            token: "valid",
+            hostname: "ceremony.namada.net",
+        };
+
+        HumanVerifier::new(Some(Arc::new(provider)), hostname.map(str::to_owned))
+    }
+
+    #[test]
+    fn test_disabled() {
+        let verifier = HumanVerifier::default();
+
+        assert!(!verifier.is_enabled());
+        assert_eq!(futures::executor::block_on(verifier.check(None, None)), Ok(None));
+    }
+
+    #[test]
+    fn test_check() {
+        let verifier = verifier(None);
+
+        // Missing, malformed or rejected tokens fail the check
+        assert!(futures::executor::block_on(verifier.check(None, None)).is_err());
+        assert!(futures::executor::block_on(verifier.check(Some(""), None)).is_err());
+        assert!(futures::executor::block_on(verifier.check(Some(&"a".repeat(MAX_TOKEN_SIZE + 1)), None)).is_err());
+        assert!(futures::executor::block_on(verifier.check(Some("invalid"), None)).is_err());
+
+        let verification = futures::executor::block_on(verifier.check(Some("valid"), None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(verification.provider, "mock");
+        assert_eq!(verification.hostname.as_deref(), Some("ceremony.namada.net"));
+    }
+
+    #[test]
+    fn test_hostname() {
+        assert!(futures::executor::block_on(verifier(Some("ceremony.namada.net")).check(Some("valid"), None)).is_ok());
+        assert!(futures::executor::block_on(verifier(Some("other.site")).check(Some("valid"), None)).is_err());
+    }
+}
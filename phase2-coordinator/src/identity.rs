@@ -0,0 +1,445 @@
+//! Optional identities of the participants on top of their public keys, such as decentralized identifiers (DIDs) or
+//! verifiable credentials, so that a ceremony can require its contributors to hold credentials issued by institutions.
+//!
+//! Bare public keys remain the default. A contributor claims an identity along with its request to join the queue, and
+//! the coordinator validates the claim with a pluggable [`IdentityResolver`]. The resolver is enabled by setting the
+//! `IDENTITY_RESOLVER` env variable: `did-web` resolves `did:web` identifiers and checks that their document lists the
+//! public key of the contributor, `http` delegates the validation of the claims, e.g. of the credentials issued by an
+//! institution, to the service at `IDENTITY_RESOLVER_URL`. Contributors without an identity are refused only if
+//! `IDENTITY_REQUIRED` is set to `true`, or during the phases of the ceremony requiring an identity.
+
+use crate::{
+    objects::{IdentityClaim, VerifiedIdentity},
+    Participant,
+};
+
+use lazy_static::lazy_static;
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+use time::OffsetDateTime;
+use url::Url;
+
+lazy_static! {
+    /// The name of the resolver of the identities: `did-web` or `http`.
+    pub static ref IDENTITY_RESOLVER: Option<String> = std::env::var("IDENTITY_RESOLVER").ok();
+    /// Whether the contributors must claim an identity to join the queue.
+    pub static ref IDENTITY_REQUIRED: bool =
+        std::env::var("IDENTITY_REQUIRED").map_or(false, |required| required == "true");
+}
+
+/// Timeout of the requests to resolve an identity.
+const RESOLVER_TIMEOUT: Duration = Duration::from_secs(10);
+/// Upper bound of the size of a DID or of the reference of a credential, larger ones are rejected without resolving
+/// them.
+const MAX_REFERENCE_SIZE: usize = 2048;
+
+/// A service validating the identities claimed by the participants.
+#[rocket::async_trait]
+pub trait IdentityResolver: Send + Sync {
+    /// The name of the resolver, recorded with the validated identity.
+    fn name(&self) -> &str;
+
+    /// Validates the identity claimed by the given participant, and returns the issuer of the credential, if reported
+    /// by the resolver.
+    async fn resolve(&self, claim: &IdentityClaim, participant: &Participant) -> Result<Option<String>, String>;
+}
+
+/// A verification method of a DID document, with the public key in one of the formats of an Ed25519 key.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerificationMethod {
+    #[serde(default)]
+    public_key_hex: Option<String>,
+    #[serde(default)]
+    public_key_jwk: Option<Jwk>,
+}
+
+/// A public key in the JSON Web Key format.
+#[derive(Debug, Deserialize)]
+struct Jwk {
+    kty: String,
+    crv: String,
+    x: String,
+}
+
+/// The fields of a DID document needed to check the public key of a participant.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidDocument {
+    id: String,
+    #[serde(default)]
+    verification_method: Vec<VerificationMethod>,
+}
+
+impl DidDocument {
+    /// Returns `true` if one of the verification methods holds the given hex encoded Ed25519 public key.
+    fn lists_key(&self, public_key: &str) -> bool {
+        let key = match hex::decode(public_key) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+
+        self.verification_method.iter().any(|method| {
+            let hex_key = method.public_key_hex.as_deref().and_then(|hex_key| hex::decode(hex_key).ok());
+            let jwk_key = method
+                .public_key_jwk
+                .as_ref()
+                .filter(|jwk| jwk.kty == "OKP" && jwk.crv == "Ed25519")
+                .and_then(|jwk| base64::decode_config(&jwk.x, base64::URL_SAFE_NO_PAD).ok());
+
+            hex_key.as_ref() == Some(&key) || jwk_key.as_ref() == Some(&key)
+        })
+    }
+}
+
+/// Resolves the `did:web` identifiers, whose documents are served over https by the domain of the identifier, and
+/// checks that the document lists the public key of the participant in one of its verification methods, as
+/// `publicKeyHex` or as an Ed25519 `publicKeyJwk`. Verifiable credentials are rejected.
+#[derive(Clone, Debug, Default)]
+pub struct DidWebResolver;
+
+impl DidWebResolver {
+    /// Returns the url of the document of the given `did:web` identifier.
+    pub fn document_url(did: &str) -> Result<Url, String> {
+        let id = did
+            .strip_prefix("did:web:")
+            .ok_or_else(|| format!("{} is not a did:web identifier", did))?;
+        // The port of the domain is percent encoded, the path is separated by colons
+        let mut segments = id.split(':').map(|segment| segment.replace("%3A", ":").replace("%3a", ":"));
+        let domain = segments.next().unwrap_or_default();
+        let path: Vec<String> = segments.collect();
+        if domain.is_empty() || path.iter().any(|segment| segment.is_empty() || segment.contains('/')) {
+            return Err(format!("{} is not a valid did:web identifier", did));
+        }
+
+        let url = match path.is_empty() {
+            true => format!("https://{}/.well-known/did.json", domain),
+            false => format!("https://{}/{}/did.json", domain, path.join("/")),
+        };
+        Url::parse(&url).map_err(|e| format!("{} is not a valid did:web identifier: {}", did, e))
+    }
+}
+
+#[rocket::async_trait]
+impl IdentityResolver for DidWebResolver {
+    fn name(&self) -> &str {
+        "did-web"
+    }
+
+    async fn resolve(&self, claim: &IdentityClaim, participant: &Participant) -> Result<Option<String>, String> {
+        let did = match claim {
+            IdentityClaim::Did { did } => did,
+            IdentityClaim::VerifiableCredential { .. } => {
+                return Err(String::from("only did:web identifiers are accepted"));
+            }
+        };
+        let url = Self::document_url(did)?;
+
+        let client = reqwest::Client::builder()
+            .timeout(RESOLVER_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let body = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("the document of {} is unreachable: {}", did, e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("the document of {} is unreachable: {}", did, e))?;
+        let document: DidDocument =
+            serde_json::from_slice(&body).map_err(|e| format!("malformed document of {}: {}", did, e))?;
+
+        if document.id != *did {
+            return Err(format!("the document of {} is the one of {}", did, document.id));
+        }
+        if !document.lists_key(&participant.address()) {
+            return Err(format!("the document of {} doesn't list the public key of the participant", did));
+        }
+
+        Ok(None)
+    }
+}
+
+/// The request posted to an [`HttpIdentityResolver`].
+#[derive(Debug, Serialize)]
+struct ResolutionRequest<'a> {
+    claim: &'a IdentityClaim,
+    public_key: String,
+}
+
+/// The answer of an [`HttpIdentityResolver`].
+#[derive(Debug, Deserialize)]
+struct ResolutionResponse {
+    valid: bool,
+    #[serde(default)]
+    issuer: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Delegates the validation of the claims to an external service, e.g. the verifier of the credentials issued by an
+/// institution. The claim and the public key of the participant are posted as json to the service, which answers with
+/// whether the claim is valid for that key and the issuer of the credential.
+#[derive(Clone, Debug)]
+pub struct HttpIdentityResolver {
+    url: String,
+}
+
+impl HttpIdentityResolver {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+#[rocket::async_trait]
+impl IdentityResolver for HttpIdentityResolver {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn resolve(&self, claim: &IdentityClaim, participant: &Participant) -> Result<Option<String>, String> {
+        let request = ResolutionRequest {
+            claim,
+            public_key: participant.address(),
+        };
+        let body = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+
+        let client = reqwest::Client::builder()
+            .timeout(RESOLVER_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let body = client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("the resolver is unreachable: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("the resolver is unreachable: {}", e))?;
+        let response: ResolutionResponse =
+            serde_json::from_slice(&body).map_err(|e| format!("unexpected answer of the resolver: {}", e))?;
+
+        if !response.valid {
+            return Err(format!(
+                "rejected by the resolver: {}",
+                response.reason.as_deref().unwrap_or("no reason given")
+            ));
+        }
+
+        Ok(response.issuer)
+    }
+}
+
+/// Checks the identities claimed by the contributors joining the queue. Disabled if no resolver is set.
+#[derive(Clone, Default)]
+pub struct IdentityVerifier {
+    resolver: Option<Arc<dyn IdentityResolver>>,
+    required: bool,
+}
+
+impl IdentityVerifier {
+    pub fn new(resolver: Option<Arc<dyn IdentityResolver>>, required: bool) -> Self {
+        Self { resolver, required }
+    }
+
+    /// Returns the verifier configured in the env, disabled if no resolver is set.
+    ///
+    /// # Panics
+    /// If the resolver is unknown, if the url of the `http` resolver is missing or if an identity is required without
+    /// a resolver.
+    pub fn from_env() -> Self {
+        let resolver: Arc<dyn IdentityResolver> = match IDENTITY_RESOLVER.as_deref() {
+            None => {
+                assert!(!*IDENTITY_REQUIRED, "IDENTITY_REQUIRED requires IDENTITY_RESOLVER");
+                return Self::default();
+            }
+            Some("did-web") => Arc::new(DidWebResolver),
+            Some("http") => Arc::new(HttpIdentityResolver::new(
+                std::env::var("IDENTITY_RESOLVER_URL").expect("Missing required env IDENTITY_RESOLVER_URL"),
+            )),
+            Some(_) => panic!("IDENTITY_RESOLVER must be one of did-web or http"),
+        };
+
+        Self::new(Some(resolver), *IDENTITY_REQUIRED)
+    }
+
+    /// Returns `true` if identities are accepted to join the queue.
+    pub fn is_enabled(&self) -> bool {
+        self.resolver.is_some()
+    }
+
+    /// Returns `true` if an identity is required to join the queue.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Validates the identity claimed by the given participant and returns it to be recorded. Returns [`None`] if no
+    /// identity is claimed and none is required, by the coordinator or by the current phase of the ceremony.
+    pub async fn check(
+        &self,
+        claim: Option<&IdentityClaim>,
+        participant: &Participant,
+        phase_required: bool,
+    ) -> Result<Option<VerifiedIdentity>, String> {
+        let claim = match claim {
+            Some(claim) => claim,
+            None if self.required || phase_required => {
+                return Err(String::from("an identity is required to join the queue"));
+            }
+            None => return Ok(None),
+        };
+        let resolver = self
+            .resolver
+            .as_ref()
+            .ok_or_else(|| String::from("identities are not accepted by this coordinator"))?;
+
+        if claim.reference().is_empty() || claim.reference().len() > MAX_REFERENCE_SIZE {
+            return Err(String::from("malformed identity"));
+        }
+
+        let issuer = resolver.resolve(claim, participant).await?;
+
+        Ok(Some(VerifiedIdentity {
+            claim: claim.clone(),
+            resolver: resolver.name().to_owned(),
+            issuer,
+            verified_at: OffsetDateTime::now_utc(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accepts the credential of a single participant.
+    struct MockResolver {
+        reference: &'static str,
+        participant: Participant,
+    }
+
+    #[rocket::async_trait]
+    impl IdentityResolver for MockResolver {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn resolve(&self, claim: &IdentityClaim, participant: &Participant) -> Result<Option<String>, String> {
+            if claim.reference() == self.reference && *participant == self.participant {
+                Ok(Some(String::from("did:web:university.example")))
+            } else {
+                Err(String::from("rejected by the resolver: unknown credential"))
+            }
+        }
+    }
+
+    fn credential(reference: &str) -> IdentityClaim {
+        IdentityClaim::VerifiableCredential {
+            reference: reference.to_owned(),
+        }
+    }
+
+    fn verifier(required: bool) -> IdentityVerifier {
+        let resolver = MockResolver {
+            reference: "https://university.example/credentials/1",
+            participant: Participant::new_contributor("alice"),
+        };
+
+        IdentityVerifier::new(Some(Arc::new(resolver)), required)
+    }
+
+    #[test]
+    fn test_disabled() {
+        let verifier = IdentityVerifier::default();
+        let alice = Participant::new_contributor("alice");
+
+        assert!(!verifier.is_enabled());
+        assert_eq!(futures::executor::block_on(verifier.check(None, &alice, false)), Ok(None));
+        // Claims can't be validated without a resolver
+        let claim = credential("https://university.example/credentials/1");
+        assert!(futures::executor::block_on(verifier.check(Some(&claim), &alice, false)).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let alice = Participant::new_contributor("alice");
+        let bob = Participant::new_contributor("bob");
+        let claim = credential("https://university.example/credentials/1");
+
+        // Bare keys are accepted unless an identity is required
+        assert_eq!(futures::executor::block_on(verifier(false).check(None, &alice, false)), Ok(None));
+        assert!(futures::executor::block_on(verifier(true).check(None, &alice, false)).is_err());
+        assert!(futures::executor::block_on(verifier(false).check(None, &alice, true)).is_err());
+
+        // Malformed or rejected claims, and claims of other participants, fail the check
+        let verifier = verifier(true);
+        assert!(futures::executor::block_on(verifier.check(Some(&credential("")), &alice, false)).is_err());
+        let oversized = credential(&"a".repeat(MAX_REFERENCE_SIZE + 1));
+        assert!(futures::executor::block_on(verifier.check(Some(&oversized), &alice, false)).is_err());
+        let unknown = credential("https://university.example/credentials/2");
+        assert!(futures::executor::block_on(verifier.check(Some(&unknown), &alice, false)).is_err());
+        assert!(futures::executor::block_on(verifier.check(Some(&claim), &bob, false)).is_err());
+
+        let identity = futures::executor::block_on(verifier.check(Some(&claim), &alice, false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.claim, claim);
+        assert_eq!(identity.resolver, "mock");
+        assert_eq!(identity.issuer.as_deref(), Some("did:web:university.example"));
+    }
+
+    #[test]
+    fn test_document_url() {
+        assert_eq!(
+            DidWebResolver::document_url("did:web:example.org").unwrap().as_str(),
+            "https://example.org/.well-known/did.json"
+        );
+        assert_eq!(
+            DidWebResolver::document_url("did:web:example.org%3A8443:users:alice")
+                .unwrap()
+                .as_str(),
+            "https://example.org:8443/users/alice/did.json"
+        );
+        assert!(DidWebResolver::document_url("did:key:z6Mk").is_err());
+        assert!(DidWebResolver::document_url("did:web:").is_err());
+        assert!(DidWebResolver::document_url("did:web:example.org::alice").is_err());
+    }
+
+    #[test]
+    fn test_lists_key() {
+        let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        let document: DidDocument = serde_json::from_str(
+            r#"{
+                "id": "did:web:example.org",
+                "verificationMethod": [
+                    {
+                        "id": "did:web:example.org#key-1",
+                        "type": "JsonWebKey2020",
+                        "publicKeyJwk": {
+                            "kty": "OKP",
+                            "crv": "Ed25519",
+                            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(document.lists_key(public_key));
+        assert!(!document.lists_key(&"00".repeat(32)));
+
+        let document: DidDocument = serde_json::from_str(&format!(
+            r#"{{ "id": "did:web:example.org", "verificationMethod": [{{ "publicKeyHex": "{}" }}] }}"#,
+            public_key.to_uppercase()
+        ))
+        .unwrap();
+        assert!(document.lists_key(public_key));
+        assert!(!document.lists_key("not hex"));
+    }
+}
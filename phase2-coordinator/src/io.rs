@@ -1,4 +1,8 @@
-use std::{fmt::Display, io::Write, ops::Deref};
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    ops::Deref,
+};
 
 #[cfg(not(debug_assertions))]
 use std::process;
@@ -112,8 +116,16 @@ where
     loop {
         print!("{} ", request);
         std::io::stdout().flush()?;
-        std::io::stdin().read_line(&mut response)?;
-        response = response.trim().to_owned();
+        // Read the bytes of the line, to ask again rather than fail if the terminal doesn't send UTF-8
+        let mut line = Vec::new();
+        std::io::stdin().lock().read_until(b'\n', &mut line)?;
+        response = match String::from_utf8(line) {
+            Ok(line) => line.trim().to_owned(),
+            Err(_) => {
+                println!("{}", "Invalid characters, please check that your terminal uses UTF-8...".red().bold());
+                continue;
+            }
+        };
 
         match expected {
             Some(re) => {
@@ -158,6 +170,21 @@ pub fn keypair_from_mnemonic() -> Result<KeyPair> {
     Ok(KeyPair::try_from_seed(&seed)?)
 }
 
+/// Regenerates the [`KeyPair`] of a contributor from the mnemonic typed by the user, e.g. to sign on an air-gapped machine.
+pub fn keypair_from_user_mnemonic() -> Result<KeyPair> {
+    let words = get_user_input(
+        format!(
+            "Enter the {} words of your mnemonic, separated by spaces:",
+            MNEMONIC_LEN
+        )
+        .yellow(),
+        Some(&Regex::new(r"^[[:alpha:]]+(\s+[[:alpha:]]+)*$")?),
+    )?;
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, &words).map_err(|e| IOError::MnemonicError(e))?;
+
+    Ok(KeyPair::try_from_seed(&mnemonic.to_seed_normalized(""))?)
+}
+
 /// Generates a new [`KeyPair`] from a randomly generated mnemonic.
 /// Cases:
 /// - Contributor -> print and check the mnemonic with the user
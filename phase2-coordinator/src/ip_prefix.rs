@@ -0,0 +1,118 @@
+//! Normalization of the IP addresses of the contributors, checked by the IP ban when `NAMADA_MPC_IP_BAN` is set.
+//!
+//! An IPv6 client usually holds a whole /64 network, so banning its address alone lets it join again from any other
+//! address of its network. The IPs are therefore bucketed by [`IpPrefixes`] before being compared: the address of a
+//! client is truncated to the width of its prefix, /32 for IPv4 and /64 for IPv6 by default. The IPv4 clients of a
+//! dual-stack socket or proxy, seen as IPv4-mapped IPv6 addresses, are converted back to IPv4 first so that a client
+//! is bucketed the same way whichever stack it comes from.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// The widths of the prefixes by which the IPs of the contributors are bucketed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpPrefixes {
+    pub ipv4: u8,
+    pub ipv6: u8,
+}
+
+impl Default for IpPrefixes {
+    fn default() -> Self {
+        Self { ipv4: 32, ipv6: 64 }
+    }
+}
+
+impl IpPrefixes {
+    /// Returns the prefixes with the given widths, or an error if one of them is longer than its addresses.
+    pub fn new(ipv4: u8, ipv6: u8) -> Result<Self, String> {
+        if ipv4 > 32 {
+            return Err(format!("Invalid IPv4 prefix width {}", ipv4));
+        }
+        if ipv6 > 128 {
+            return Err(format!("Invalid IPv6 prefix width {}", ipv6));
+        }
+
+        Ok(Self { ipv4, ipv6 })
+    }
+
+    /// Returns the network of the given IP, its canonical address truncated to the width of its prefix.
+    pub fn bucket(&self, ip: &IpAddr) -> IpAddr {
+        match canonical_ip(*ip) {
+            IpAddr::V4(ip) => {
+                let mask = u32::MAX.checked_shl(32 - self.ipv4 as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+            }
+            IpAddr::V6(ip) => {
+                let mask = u128::MAX.checked_shl(128 - self.ipv6 as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+            }
+        }
+    }
+}
+
+/// Converts the IPv4-mapped IPv6 addresses, `::ffff:a.b.c.d`, to IPv4.
+pub fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, high, low] => IpAddr::V4(Ipv4Addr::from(((high as u32) << 16) | low as u32)),
+            _ => ip,
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
+
+/// Parses the address of a client forwarded by a proxy, either an IP or a socket address, e.g. `192.0.2.1:443` or
+/// `[2001:db8::1]:443`, and returns its canonical IP.
+pub fn parse_forwarded_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    let ip = match value.parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(_) => match value.parse::<SocketAddr>() {
+            Ok(address) => address.ip(),
+            // A bracketed IPv6 address without a port
+            Err(_) => value.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?,
+        },
+    };
+
+    Some(canonical_ip(ip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket() {
+        let prefixes = IpPrefixes::default();
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        // The addresses of a /64 share their bucket
+        assert_eq!(prefixes.bucket(&ip("2001:db8:1:2:a:b:c:d")), ip("2001:db8:1:2::"));
+        assert_eq!(prefixes.bucket(&ip("2001:db8:1:2::1")), ip("2001:db8:1:2::"));
+        assert_ne!(prefixes.bucket(&ip("2001:db8:1:3::1")), ip("2001:db8:1:2::"));
+        assert_eq!(prefixes.bucket(&ip("192.0.2.1")), ip("192.0.2.1"));
+
+        // The IPv4 clients of a dual-stack socket are bucketed as IPv4
+        assert_eq!(prefixes.bucket(&ip("::ffff:192.0.2.1")), ip("192.0.2.1"));
+
+        let prefixes = IpPrefixes::new(24, 48).unwrap();
+        assert_eq!(prefixes.bucket(&ip("192.0.2.200")), ip("192.0.2.0"));
+        assert_eq!(prefixes.bucket(&ip("2001:db8:1:2::1")), ip("2001:db8:1::"));
+        assert_eq!(IpPrefixes::new(0, 0).unwrap().bucket(&ip("192.0.2.1")), ip("0.0.0.0"));
+        assert!(IpPrefixes::new(33, 64).is_err());
+        assert!(IpPrefixes::new(32, 129).is_err());
+    }
+
+    #[test]
+    fn test_parse_forwarded_ip() {
+        let ip = |s: &str| s.parse::<IpAddr>().ok();
+
+        assert_eq!(parse_forwarded_ip("192.0.2.1"), ip("192.0.2.1"));
+        assert_eq!(parse_forwarded_ip(" 192.0.2.1:443 "), ip("192.0.2.1"));
+        assert_eq!(parse_forwarded_ip("2001:db8::1"), ip("2001:db8::1"));
+        assert_eq!(parse_forwarded_ip("[2001:db8::1]:443"), ip("2001:db8::1"));
+        assert_eq!(parse_forwarded_ip("[2001:db8::1]"), ip("2001:db8::1"));
+        assert_eq!(parse_forwarded_ip("[::ffff:192.0.2.1]:443"), ip("192.0.2.1"));
+        assert_eq!(parse_forwarded_ip("unknown"), None);
+    }
+}
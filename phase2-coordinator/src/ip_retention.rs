@@ -0,0 +1,228 @@
+//! Retention of the IP addresses of the contributors, which are kept to ban the IPs already used when
+//! `NAMADA_MPC_IP_BAN` is set.
+//!
+//! The IP of a contributor is kept in memory while it is in the queue or contributing. Once the contributor has
+//! finished, its IP is kept according to the [`IpRetention`] policy of the environment, and the IPs stored under a
+//! previous policy are redacted when the coordinator starts. The policy is published in the metadata of the ceremony.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, fs, io, net::IpAddr, path::Path, str::FromStr};
+
+lazy_static! {
+    /// The secret salt of the hashes of the IPs under the [`IpRetention::Hashed`] policy. Keep it stable for the whole
+    /// ceremony, the IPs hashed with another salt are no longer banned.
+    pub static ref IP_HASH_SALT: String = std::env::var("NAMADA_MPC_IP_HASH_SALT").unwrap_or_default();
+    /// The database of the ASNs and countries of the IP ranges, used under the [`IpRetention::Location`] policy.
+    pub static ref IP_LOCATOR: Option<IpLocator> = std::env::var("NAMADA_MPC_IP_LOCATION_DATABASE")
+        .ok()
+        .map(|path| IpLocator::load(path).expect("Error while loading the IP location database"));
+}
+
+/// Prefix of the salted hashes of the IPs.
+const HASH_PREFIX: &str = "sha256:";
+/// The location of the IPs missing from the database.
+const UNKNOWN_LOCATION: &str = "unknown";
+
+/// What is kept of the IP of a contributor once it has finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpRetention {
+    /// The IP, banned for the rest of the ceremony.
+    Full,
+    /// The salted hash of the IP, banned for the rest of the ceremony.
+    Hashed,
+    /// Only the ASN and the country of the IP, which is no longer banned.
+    Location,
+    /// Nothing, the IP is no longer banned.
+    Discard,
+}
+
+impl Default for IpRetention {
+    fn default() -> Self {
+        IpRetention::Full
+    }
+}
+
+impl fmt::Display for IpRetention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpRetention::Full => write!(f, "full"),
+            IpRetention::Hashed => write!(f, "hashed"),
+            IpRetention::Location => write!(f, "location"),
+            IpRetention::Discard => write!(f, "discard"),
+        }
+    }
+}
+
+impl FromStr for IpRetention {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(IpRetention::Full),
+            "hashed" => Ok(IpRetention::Hashed),
+            "location" => Ok(IpRetention::Location),
+            "discard" => Ok(IpRetention::Discard),
+            _ => Err(format!("Unknown IP retention policy {}", s)),
+        }
+    }
+}
+
+/// What is kept of a stored IP after its redaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RetainedIp {
+    /// The key under which the IP stays banned.
+    Banned(String),
+    /// The ASN and the country of the IP.
+    Located(String),
+    Discarded,
+}
+
+impl IpRetention {
+    /// Returns the key under which the given IP is banned once its contributor has finished, if any.
+    pub fn ban_key(&self, ip: &IpAddr) -> Option<String> {
+        match self {
+            IpRetention::Full => Some(ip.to_string()),
+            IpRetention::Hashed => Some(hash_ip(ip, &IP_HASH_SALT)),
+            IpRetention::Location | IpRetention::Discard => None,
+        }
+    }
+
+    /// Returns what is kept of the given IP once its contributor has finished.
+    pub fn retain(&self, ip: &IpAddr) -> RetainedIp {
+        match self {
+            IpRetention::Location => RetainedIp::Located(locate_ip(ip)),
+            IpRetention::Discard => RetainedIp::Discarded,
+            _ => self.ban_key(ip).map_or(RetainedIp::Discarded, RetainedIp::Banned),
+        }
+    }
+
+    /// Redacts a key stored under a previous policy, which is either an IP or its salted hash. The hashes can't be
+    /// reverted, so they are kept as they are under the [`IpRetention::Full`] policy.
+    pub fn redact(&self, key: &str) -> RetainedIp {
+        match key.parse::<IpAddr>() {
+            Ok(ip) => self.retain(&ip),
+            Err(_) => match self {
+                IpRetention::Full | IpRetention::Hashed => RetainedIp::Banned(key.to_owned()),
+                IpRetention::Location | IpRetention::Discard => RetainedIp::Discarded,
+            },
+        }
+    }
+}
+
+/// Returns the salted hash of the IP.
+pub fn hash_ip(ip: &IpAddr, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(ip.to_string().as_bytes());
+
+    format!("{}{}", HASH_PREFIX, hex::encode(hasher.finalize()))
+}
+
+/// Returns the ASN and the country of the IP from the [`IP_LOCATOR`], or `unknown`.
+fn locate_ip(ip: &IpAddr) -> String {
+    IP_LOCATOR
+        .as_ref()
+        .and_then(|locator| locator.locate(ip))
+        .unwrap_or(UNKNOWN_LOCATION)
+        .to_owned()
+}
+
+/// The ASN and the country of the IP ranges, loaded from a database in the tab-separated format of
+/// [iptoasn](https://iptoasn.com): the first and last IPs of each range, its ASN, its country code and the description
+/// of the AS.
+#[derive(Clone, Debug, Default)]
+pub struct IpLocator {
+    /// The ranges sorted by their first IP, with their location, `AS<number>/<country>`.
+    ranges: Vec<(IpAddr, IpAddr, String)>,
+}
+
+impl IpLocator {
+    /// Loads the database from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Parses the database, skipping the malformed lines and the ranges which aren't routed.
+    pub fn parse(database: &str) -> Self {
+        let mut ranges: Vec<(IpAddr, IpAddr, String)> = database
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let start: IpAddr = fields.next()?.parse().ok()?;
+                let end: IpAddr = fields.next()?.parse().ok()?;
+                let asn = fields.next()?;
+                let country = fields.next()?;
+
+                match asn {
+                    "0" => None,
+                    _ => Some((start, end, format!("AS{}/{}", asn, country))),
+                }
+            })
+            .collect();
+        ranges.sort_by_key(|(start, _, _)| *start);
+
+        Self { ranges }
+    }
+
+    /// Returns the location of the range containing the IP, if any.
+    pub fn locate(&self, ip: &IpAddr) -> Option<&str> {
+        let index = self.ranges.partition_point(|(start, _, _)| start <= ip);
+        let (_, end, location) = self.ranges.get(index.checked_sub(1)?)?;
+
+        match ip <= end {
+            true => Some(location.as_str()),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_retention() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        assert_eq!(IpRetention::Full.retain(&ip), RetainedIp::Banned(ip.to_string()));
+        assert_eq!(IpRetention::Discard.retain(&ip), RetainedIp::Discarded);
+        assert_eq!(IpRetention::Location.retain(&ip), RetainedIp::Located(UNKNOWN_LOCATION.to_owned()));
+
+        let hash = hash_ip(&ip, &IP_HASH_SALT);
+        assert_eq!(IpRetention::Hashed.retain(&ip), RetainedIp::Banned(hash.clone()));
+        assert_ne!(hash_ip(&ip, "another salt"), hash);
+
+        // Full IPs are redacted, hashes are only dropped
+        assert_eq!(IpRetention::Hashed.redact(&ip.to_string()), RetainedIp::Banned(hash.clone()));
+        assert_eq!(IpRetention::Full.redact(&hash), RetainedIp::Banned(hash.clone()));
+        assert_eq!(IpRetention::Location.redact(&hash), RetainedIp::Discarded);
+
+        for policy in &[
+            IpRetention::Full,
+            IpRetention::Hashed,
+            IpRetention::Location,
+            IpRetention::Discard,
+        ] {
+            assert_eq!(policy.to_string().parse::<IpRetention>().unwrap(), *policy);
+            assert_eq!(serde_json::to_string(policy).unwrap(), format!("\"{}\"", policy));
+        }
+    }
+
+    #[test]
+    fn test_ip_locator() {
+        let locator = IpLocator::parse(
+            "1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET\n\
+             1.0.1.0\t1.0.3.255\t0\tNone\tNot routed\n\
+             malformed line\n\
+             2001:200::\t2001:200:ffff:ffff:ffff:ffff:ffff:ffff\t2500\tJP\tWIDE-BB\n",
+        );
+
+        assert_eq!(locator.locate(&"1.0.0.1".parse().unwrap()), Some("AS13335/US"));
+        assert_eq!(locator.locate(&"1.0.2.1".parse().unwrap()), None);
+        assert_eq!(locator.locate(&"0.255.255.255".parse().unwrap()), None);
+        assert_eq!(locator.locate(&"2001:200::1".parse().unwrap()), Some("AS2500/JP"));
+    }
+}
@@ -24,13 +24,31 @@
 //! of how the [objects::task::Task]s for each participant in the
 //! ceremony are generated depending on the number of chunks and the
 //! number of participants in the round.
+//!
+//! The coordinator, with its queue, rounds, locks and verification
+//! orchestration, is a synchronous library which performs no network
+//! I/O and depends neither on an HTTP framework nor on an async
+//! runtime: every operation is a plain method call on [Coordinator],
+//! and the embedder decides how to drive it. The REST API in `rest`,
+//! built on Rocket, is one such frontend and is enabled by the
+//! `server` feature together with the Amazon S3 client in `s3`.
+//! Alternative frontends (gRPC, CLI-driven, embedded) can depend on
+//! this crate with `default-features = false` and reuse the same logic.
 
 #[macro_use]
 pub mod macros;
 
 pub mod authentication;
 
+pub mod backup;
+
+pub mod changelog;
+
+#[cfg(debug_assertions)]
+pub mod chaos;
+
 pub mod commands;
+pub mod conversion;
 
 pub mod coordinator;
 pub use coordinator::*;
@@ -46,17 +64,63 @@ pub mod coordinator_state;
 pub use coordinator_state::CoordinatorState;
 
 pub mod environment;
+pub mod events;
+pub mod export;
+pub mod hashing;
 pub mod io;
+pub mod ip_prefix;
+pub mod ip_retention;
+pub mod memory;
+pub mod messages;
 
 pub mod objects;
 pub use objects::{ContributionFileSignature, ContributionState, Participant, Round};
 
 pub mod storage;
 
+pub mod capability_probe;
+pub mod pow;
+pub mod practice;
+pub mod prelaunch;
+pub mod publication;
+pub mod sandbox;
+pub mod scheduler;
+pub mod self_test;
+pub mod shared_store;
+pub mod startup;
+
+#[cfg(feature = "server")]
+pub mod compression;
+#[cfg(feature = "server")]
+pub mod cors;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
+#[cfg(feature = "server")]
+pub mod human_verification;
+#[cfg(feature = "server")]
+pub mod identity;
+#[cfg(feature = "server")]
+pub mod lifecycle;
+#[cfg(feature = "server")]
+pub mod lock_monitor;
+#[cfg(feature = "websocket")]
+pub mod notifications;
+#[cfg(feature = "server")]
+pub mod rehearsal;
+#[cfg(feature = "server")]
+pub mod request_log;
+#[cfg(feature = "server")]
 pub mod rest;
+#[cfg(feature = "server")]
 pub mod rest_utils;
 
+#[cfg(feature = "server")]
 pub mod s3;
+#[cfg(feature = "server")]
+pub mod webhooks;
+
+#[cfg(all(unix, feature = "server"))]
+pub mod systemd;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
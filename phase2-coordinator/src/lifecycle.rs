@@ -0,0 +1,219 @@
+//! Availability of the endpoints of the REST API across the [phases](`LifecyclePhase`) of the lifecycle of the
+//! ceremony.
+//!
+//! Each endpoint is valid in a set of phases only, e.g. the contributions can't be uploaded before the launch of the
+//! ceremony or once it is over, and no contributor joins the queue once the ceremony reached its maximum length. The
+//! [`Lifecycle`] request guard, the first guard of the endpoints which aren't available in every phase, refuses the
+//! requests outside of these phases with a `409 Conflict` and a json [`LifecycleConflict`] body holding the current
+//! phase, instead of letting the endpoint fail on an inconsistent state.
+
+use crate::{
+    lock_monitor::LOCK_MONITOR,
+    objects::LifecyclePhase::{self, *},
+    rest_utils::{Coordinator, ResponseError},
+};
+
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome, Request},
+    State,
+};
+use serde::{Deserialize, Serialize};
+
+/// Every phase.
+const ALWAYS: &[LifecyclePhase] = &LifecyclePhase::ALL;
+/// Until the ceremony is over.
+const ACTIVE: &[LifecyclePhase] = &[Prelaunch, Running, Draining];
+/// While the contributors are accepted in the queue.
+const OPEN: &[LifecyclePhase] = &[Prelaunch, Running];
+/// While contributions are computed and verified.
+const CONTRIBUTING: &[LifecyclePhase] = &[Running, Draining];
+/// Once a contribution may have completed.
+const LAUNCHED: &[LifecyclePhase] = &[Running, Draining, Over];
+/// Before the launch of the ceremony.
+const PRELAUNCH: &[LifecyclePhase] = &[Prelaunch];
+
+/// Returns the phases in which the endpoint with the given handler name is valid, or [`None`] if the endpoint is
+/// unknown.
+pub fn endpoint_phases(endpoint: &str) -> Option<&'static [LifecyclePhase]> {
+    let phases = match endpoint {
+        "get_pow_puzzle" | "get_capability_probe" | "get_capability_probe_blob" | "join_queue" => OPEN,
+        "lock_chunk"
+        | "get_challenge_url"
+        | "get_challenge_stream"
+        | "get_contribution_url"
+        | "contribute_chunk"
+        | "upload_contribution"
+        | "post_computation_proof"
+        | "upload_contribution_range"
+        | "get_contribution_upload_progress"
+        | "resume_session"
+        | "get_challenge_bundle"
+        | "post_response_bundle"
+        | "reissue_challenge"
+        | "get_pending_verifications"
+        | "post_verification_vote"
+        | "get_manual_contribution_url"
+        | "post_manual_contribution"
+        | "post_reverify"
+        | "reject_quarantined_contribution"
+        | "verify_quarantined_contribution" => CONTRIBUTING,
+        "heartbeat"
+        | "update_coordinator"
+        | "verify_chunks"
+        | "update_cohorts"
+        | "post_verifier_delegation"
+        | "lock_practice_round"
+        | "get_practice_challenge"
+        | "post_practice_contribution" => ACTIVE,
+        "post_contribution_info" | "post_attestation" | "post_attestation_post" | "post_destruction_attestation" => {
+            LAUNCHED
+        }
+        "post_prelaunch_snapshot" | "post_prelaunch_restore" | "commit_genesis_seed" | "reveal_genesis_seed" => {
+            PRELAUNCH
+        }
+        "stop_coordinator"
+        | "get_contributor_queue_status"
+        | "get_contributor_queue_events"
+        | "get_verification_status"
+        | "get_contributions_info"
+        | "get_coordinator_state"
+        | "get_participants_history"
+        | "get_export"
+        | "get_storage_shards"
+        | "rebalance_storage"
+        | "get_reverification_status"
+        | "get_lock_metrics"
+        | "get_startup_profile"
+        | "get_control_keys"
+        | "add_control_key"
+        | "remove_control_key"
+        | "get_webhook_deliveries"
+        | "get_verification_quarantine"
+        | "get_transcript"
+        | "get_transcript_manifests"
+        | "get_healthcheck"
+        | "get_ceremony_status"
+        | "get_public_ceremony_status"
+        | "get_circuit_registry"
+        | "get_ceremony_metadata"
+        | "get_badge"
+        | "get_ceremony_parameters"
+        | "get_announcements"
+        | "get_queue_shuffles"
+        | "get_genesis_seed"
+        | "get_computation_stats"
+        | "get_api_changelog"
+        | "get_messages" => ALWAYS,
+        _ => return None,
+    };
+
+    Some(phases)
+}
+
+/// The body of the response to a request outside of the phases of its endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleConflict {
+    pub message: String,
+    /// The path of the request.
+    pub endpoint: String,
+    pub phase: LifecyclePhase,
+    /// The phases in which the endpoint is valid.
+    pub allowed_phases: Vec<LifecyclePhase>,
+}
+
+impl LifecycleConflict {
+    pub fn new(endpoint: String, phase: LifecyclePhase, allowed_phases: &[LifecyclePhase]) -> Self {
+        Self {
+            message: format!("Endpoint {} is not available while the ceremony is {}", endpoint, phase),
+            endpoint,
+            phase,
+            allowed_phases: allowed_phases.to_vec(),
+        }
+    }
+}
+
+/// Checks that the ceremony is in one of the phases of the endpoint of the request via [`FromRequest`]. The
+/// endpoints which are valid in every phase, or unknown, don't read the state of the coordinator.
+pub struct Lifecycle;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Lifecycle {
+    type Error = ResponseError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let phases = match request
+            .route()
+            .and_then(|route| route.name.as_deref())
+            .and_then(endpoint_phases)
+        {
+            Some(phases) if phases != ALWAYS => phases,
+            _ => return Outcome::Success(Self),
+        };
+
+        let coordinator = request
+            .guard::<&State<Coordinator>>()
+            .await
+            .succeeded()
+            .expect("Managed state should always be retrievable");
+        let phase = match LOCK_MONITOR.read(coordinator).await.lifecycle_phase() {
+            Ok(phase) => phase,
+            // The errors of the coordinator are reported by the endpoint
+            Err(_) => return Outcome::Success(Self),
+        };
+
+        if phases.contains(&phase) {
+            Outcome::Success(Self)
+        } else {
+            let conflict = LifecycleConflict::new(request.uri().path().to_string(), phase, phases);
+            // Cache error data for the error catcher
+            request.local_cache(|| Some(conflict.clone()));
+
+            Outcome::Failure((Status::new(461), ResponseError::InvalidLifecyclePhase(conflict)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_phases() {
+        // The endpoints available in each phase, from the prelaunch to the end of the ceremony
+        let matrix: &[(&str, [bool; 4])] = &[
+            ("join_queue", [true, true, false, false]),
+            ("get_pow_puzzle", [true, true, false, false]),
+            ("lock_chunk", [false, true, true, false]),
+            ("upload_contribution", [false, true, true, false]),
+            ("contribute_chunk", [false, true, true, false]),
+            ("post_verification_vote", [false, true, true, false]),
+            ("heartbeat", [true, true, true, false]),
+            ("post_contribution_info", [false, true, true, true]),
+            ("post_prelaunch_snapshot", [true, false, false, false]),
+            ("get_ceremony_status", [true, true, true, true]),
+            ("get_transcript", [true, true, true, true]),
+        ];
+
+        for (endpoint, expected) in matrix {
+            let phases = endpoint_phases(endpoint).unwrap();
+            for (phase, allowed) in LifecyclePhase::ALL.iter().zip(expected) {
+                assert_eq!(phases.contains(phase), *allowed, "{} while {}", endpoint, phase);
+            }
+        }
+        assert!(endpoint_phases("unknown").is_none());
+    }
+
+    #[test]
+    fn test_lifecycle_conflict() {
+        let conflict = LifecycleConflict::new(String::from("/contributor/join_queue"), Draining, OPEN);
+        let json = serde_json::to_value(&conflict).unwrap();
+
+        assert_eq!(json["phase"], "draining");
+        assert_eq!(json["allowed_phases"], serde_json::json!(["prelaunch", "running"]));
+        assert_eq!(
+            conflict.message,
+            "Endpoint /contributor/join_queue is not available while the ceremony is draining"
+        );
+    }
+}
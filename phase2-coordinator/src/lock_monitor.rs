@@ -0,0 +1,356 @@
+//! Instrumentation of the lock of the [Coordinator](`crate::Coordinator`) shared by the endpoints and the background
+//! tasks, to diagnose the stalls of the server.
+//!
+//! Every acquisition goes through [`LOCK_MONITOR`], which records, by call site, how long the acquisitions waited for
+//! the lock, exposed by `/admin/lock_metrics`. The call sites of the current holders and waiters of the lock are tracked
+//! too: the watchdog started with the server logs a snapshot of them as soon as a wait exceeds the threshold set by the
+//! `LOCK_WAIT_THRESHOLD_SECONDS` env variable, which is the state needed to find the holder of a deadlock.
+
+use crate::Coordinator;
+
+use lazy_static::lazy_static;
+use rocket::tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    ops::{Deref, DerefMut},
+    panic::Location,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{error, warn};
+
+lazy_static! {
+    pub static ref LOCK_WAIT_THRESHOLD: Duration = std::env::var("LOCK_WAIT_THRESHOLD_SECONDS")
+        .ok()
+        .map(|seconds| seconds.parse().expect("Invalid LOCK_WAIT_THRESHOLD_SECONDS"))
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_LOCK_WAIT_THRESHOLD);
+    /// The monitor of the lock of the coordinator.
+    pub static ref LOCK_MONITOR: LockMonitor = LockMonitor::new(*LOCK_WAIT_THRESHOLD);
+}
+
+/// Default wait for the lock above which a snapshot of its holders is logged.
+const DEFAULT_LOCK_WAIT_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// The access to the lock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockMode {
+    Read,
+    Write,
+}
+
+/// The wait times of the acquisitions of the lock from a call site.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockWaitStats {
+    pub acquisitions: u64,
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64,
+    /// The number of waits above the threshold.
+    pub slow_acquisitions: u64,
+}
+
+/// A holder of the lock, or an acquisition waiting for it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockUser {
+    /// The call site of the acquisition, as `file:line:column`.
+    pub site: String,
+    pub mode: LockMode,
+    /// The time since the lock was acquired, for a holder, or since the acquisition started, for a waiter.
+    pub elapsed_ms: u64,
+}
+
+/// The metrics of the lock, returned by `/admin/lock_metrics`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockMetrics {
+    pub threshold_ms: u64,
+    /// The wait times by call site.
+    pub sites: BTreeMap<String, LockWaitStats>,
+    pub holders: Vec<LockUser>,
+    pub waiters: Vec<LockUser>,
+}
+
+struct LockUse {
+    site: &'static Location<'static>,
+    mode: LockMode,
+    since: Instant,
+    /// Whether a stall of the waiter has been reported already.
+    reported: bool,
+}
+
+impl LockUse {
+    fn user(&self) -> LockUser {
+        LockUser {
+            site: self.site.to_string(),
+            mode: self.mode,
+            elapsed_ms: self.since.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MonitorState {
+    next_id: u64,
+    holders: HashMap<u64, LockUse>,
+    waiters: HashMap<u64, LockUse>,
+    sites: BTreeMap<String, LockWaitStats>,
+}
+
+/// Records the acquisitions of the lock of the coordinator, see the [module documentation](self).
+pub struct LockMonitor {
+    threshold: Duration,
+    state: Mutex<MonitorState>,
+}
+
+impl LockMonitor {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            state: Mutex::new(MonitorState::default()),
+        }
+    }
+
+    /// Returns the wait above which an acquisition is reported.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Acquires the read lock of the coordinator.
+    #[track_caller]
+    pub fn read<'a>(
+        &'static self,
+        lock: &'a RwLock<Coordinator>,
+    ) -> impl Future<Output = Monitored<RwLockReadGuard<'a, Coordinator>>> + 'a {
+        self.acquire(Location::caller(), LockMode::Read, lock.read())
+    }
+
+    /// Acquires the write lock of the coordinator.
+    #[track_caller]
+    pub fn write<'a>(
+        &'static self,
+        lock: &'a RwLock<Coordinator>,
+    ) -> impl Future<Output = Monitored<RwLockWriteGuard<'a, Coordinator>>> + 'a {
+        self.acquire(Location::caller(), LockMode::Write, lock.write())
+    }
+
+    /// Acquires the read lock of the coordinator, with a guard which can be moved to another task.
+    #[track_caller]
+    pub fn read_owned(
+        &'static self,
+        lock: Arc<RwLock<Coordinator>>,
+    ) -> impl Future<Output = Monitored<OwnedRwLockReadGuard<Coordinator>>> {
+        self.acquire(Location::caller(), LockMode::Read, lock.read_owned())
+    }
+
+    /// Acquires the write lock of the coordinator, with a guard which can be moved to another task.
+    #[track_caller]
+    pub fn write_owned(
+        &'static self,
+        lock: Arc<RwLock<Coordinator>>,
+    ) -> impl Future<Output = Monitored<OwnedRwLockWriteGuard<Coordinator>>> {
+        self.acquire(Location::caller(), LockMode::Write, lock.write_owned())
+    }
+
+    async fn acquire<G>(
+        &'static self,
+        site: &'static Location<'static>,
+        mode: LockMode,
+        acquisition: impl Future<Output = G>,
+    ) -> Monitored<G> {
+        let waiting = Waiting {
+            monitor: self,
+            id: self.wait(site, mode),
+        };
+        let guard = acquisition.await;
+        self.acquired(waiting.id);
+
+        Monitored {
+            guard,
+            monitor: self,
+            id: waiting.id,
+        }
+    }
+
+    fn wait(&self, site: &'static Location<'static>, mode: LockMode) -> u64 {
+        let mut state = self.state.lock().expect("Lock monitor lock poisoned");
+        let id = state.next_id;
+        state.next_id += 1;
+        state.waiters.insert(
+            id,
+            LockUse {
+                site,
+                mode,
+                since: Instant::now(),
+                reported: false,
+            },
+        );
+
+        id
+    }
+
+    fn acquired(&self, id: u64) {
+        let mut state = self.state.lock().expect("Lock monitor lock poisoned");
+        let waiter = match state.waiters.remove(&id) {
+            Some(waiter) => waiter,
+            None => return,
+        };
+
+        let wait = waiter.since.elapsed();
+        let stats = state.sites.entry(waiter.site.to_string()).or_default();
+        stats.acquisitions += 1;
+        stats.total_wait_ms += wait.as_millis() as u64;
+        stats.max_wait_ms = stats.max_wait_ms.max(wait.as_millis() as u64);
+        if wait >= self.threshold {
+            stats.slow_acquisitions += 1;
+            warn!(
+                "Acquisition of the {:?} lock at {} waited {} ms",
+                waiter.mode,
+                waiter.site,
+                wait.as_millis()
+            );
+        }
+
+        state.holders.insert(
+            id,
+            LockUse {
+                since: Instant::now(),
+                ..waiter
+            },
+        );
+    }
+
+    fn release(&self, id: u64) {
+        let mut state = self.state.lock().expect("Lock monitor lock poisoned");
+        state.holders.remove(&id);
+        state.waiters.remove(&id);
+    }
+
+    /// Returns the wait times by call site and the current holders and waiters of the lock.
+    pub fn metrics(&self) -> LockMetrics {
+        let state = self.state.lock().expect("Lock monitor lock poisoned");
+
+        LockMetrics {
+            threshold_ms: self.threshold.as_millis() as u64,
+            sites: state.sites.clone(),
+            holders: state.holders.values().map(LockUse::user).collect(),
+            waiters: state.waiters.values().map(LockUse::user).collect(),
+        }
+    }
+
+    /// Logs a snapshot of the holders and the waiters of the lock if an acquisition has been waiting for longer than the
+    /// threshold, once per stalled acquisition. Returns `true` if a stall was reported.
+    pub fn report_stalls(&self) -> bool {
+        let mut state = self.state.lock().expect("Lock monitor lock poisoned");
+        let threshold = self.threshold;
+        let mut stalled = false;
+        for waiter in state.waiters.values_mut() {
+            if !waiter.reported && waiter.since.elapsed() >= threshold {
+                waiter.reported = true;
+                stalled = true;
+            }
+        }
+        if !stalled {
+            return false;
+        }
+
+        let describe = |uses: &HashMap<u64, LockUse>| -> String {
+            let mut uses: Vec<LockUser> = uses.values().map(LockUse::user).collect();
+            uses.sort_by(|a, b| b.elapsed_ms.cmp(&a.elapsed_ms));
+            uses.iter()
+                .map(|user| format!("\n  {:?} at {} for {} ms", user.mode, user.site, user.elapsed_ms))
+                .collect()
+        };
+        error!(
+            "Acquisition of the coordinator lock stalled for more than {} s, holders:{}\nwaiters:{}",
+            threshold.as_secs(),
+            describe(&state.holders),
+            describe(&state.waiters)
+        );
+
+        true
+    }
+}
+
+/// Forgets a waiter whose acquisition is cancelled.
+struct Waiting {
+    monitor: &'static LockMonitor,
+    id: u64,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        let mut state = self.monitor.state.lock().expect("Lock monitor lock poisoned");
+        state.waiters.remove(&self.id);
+    }
+}
+
+/// A guard of the lock, recording its release.
+pub struct Monitored<G> {
+    guard: G,
+    monitor: &'static LockMonitor,
+    id: u64,
+}
+
+impl<G: Deref> Deref for Monitored<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for Monitored<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<G> Drop for Monitored<G> {
+    fn drop(&mut self) {
+        self.monitor.release(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rocket::async_test]
+    async fn test_lock_monitor() {
+        let monitor: &'static LockMonitor = Box::leak(Box::new(LockMonitor::new(Duration::from_millis(10))));
+        let lock = Arc::new(RwLock::new(0u64));
+
+        let read = monitor.acquire(Location::caller(), LockMode::Read, lock.read()).await;
+        let metrics = monitor.metrics();
+        assert_eq!(metrics.holders.len(), 1);
+        assert_eq!(metrics.holders[0].mode, LockMode::Read);
+        assert!(metrics.holders[0].site.contains("lock_monitor.rs"));
+        assert!(!monitor.report_stalls());
+
+        // A writer waiting behind the reader stalls
+        let waiting_lock = lock.clone();
+        let writer = rocket::tokio::spawn(async move {
+            let mut write = monitor
+                .acquire(Location::caller(), LockMode::Write, waiting_lock.write_owned())
+                .await;
+            *write += 1;
+        });
+        rocket::tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(monitor.metrics().waiters.len(), 1);
+        assert!(monitor.report_stalls());
+        // Reported once
+        assert!(!monitor.report_stalls());
+
+        drop(read);
+        writer.await.unwrap();
+        assert_eq!(*lock.read().await, 1);
+        let metrics = monitor.metrics();
+        assert!(metrics.holders.is_empty() && metrics.waiters.is_empty());
+        let stats: Vec<&LockWaitStats> = metrics.sites.values().collect();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.iter().map(|stats| stats.acquisitions).sum::<u64>(), 2);
+        assert_eq!(stats.iter().map(|stats| stats.slow_acquisitions).sum::<u64>(), 1);
+    }
+}
@@ -1,18 +1,50 @@
 use phase2_coordinator::{
-    authentication::Production as ProductionSig,
+    authentication::{KeyPair, Production as ProductionSig},
+    backup::Backups,
+    capability_probe::CapabilityProber,
+    compression::Compression,
+    conversion::OUTPUT_FORMATS,
+    cors::Cors,
+    human_verification::HumanVerifier,
+    identity::IdentityVerifier,
     io::{self, KeyPairUser},
+    lock_monitor::LOCK_MONITOR,
+    pow::{ProofOfWork, POW_DIFFICULTY},
+    practice::PracticeRounds,
+    publication::{SignedTranscriptDelta, TranscriptPublisher},
+    rehearsal::RehearsalMarker,
+    request_log::RequestLog,
     rest,
-    rest_utils::{self, ResponseError, TOKENS_PATH, TOKENS_ZIP_FILE, UPDATE_TIME},
+    rest_utils::{self, ChallengeETags, QueueEvents, ResponseError, VerificationTracker, TOKENS_PATH, TOKENS_ZIP_FILE},
     s3::{S3Ctx, REGION},
+    sandbox,
+    self_test,
+    shared_store,
+    webhooks::RoundWebhooks,
+    environment::{ComputeBackend, Environment, Overrides, Preset, TEST_CIRCUITS},
+    events::EventPublishers,
+    export::{CeremonyExport, ExportFormat},
     Coordinator,
 };
 
 #[cfg(debug_assertions)]
-use phase2_coordinator::environment::Testing;
+use phase2_coordinator::{chaos::Chaos, environment::Testing};
+
+#[cfg(feature = "dashboard")]
+use phase2_coordinator::dashboard;
+
+#[cfg(feature = "websocket")]
+use phase2_coordinator::notifications;
 
 #[cfg(not(debug_assertions))]
 use phase2_coordinator::environment::Production;
 
+#[cfg(unix)]
+use phase2_coordinator::systemd::{self, PidFile, SocketActivation, PID_FILE};
+
+#[cfg(unix)]
+use rocket::tokio::signal::unix::{signal, SignalKind};
+
 use rocket::{
     self, catchers, routes,
     tokio::{
@@ -27,21 +59,26 @@ use rocket::{
 use anyhow::Result;
 use rand::Rng;
 use rusoto_ssm::{Ssm, SsmClient};
-use std::{convert::TryInto, io::Write, sync::Arc};
+use std::{convert::TryInto, io::Write, path::Path, sync::Arc, time::Duration};
 
 use tracing::{error, info, warn};
 
-/// Periodically updates the [`Coordinator`]
+/// Periodically updates the [`Coordinator`], adapting the interval between two updates to its load
 async fn update_coordinator(coordinator: Arc<RwLock<Coordinator>>, recv: Receiver<bool>) -> Result<()> {
+    let mut update_interval = LOCK_MONITOR.read(&coordinator).await.update_interval();
+
     loop {
-        tokio::time::sleep(UPDATE_TIME).await;
+        tokio::time::sleep(update_interval).await;
 
         info!("Updating coordinator...");
         match rest_utils::perform_coordinator_update(coordinator.clone()).await {
-            Ok(_) => info!(
-                "Update of coordinator completed, {:#?} to the next update round...",
-                UPDATE_TIME
-            ),
+            Ok(_) => {
+                update_interval = LOCK_MONITOR.write(&coordinator).await.schedule_next_update();
+                info!(
+                    "Update of coordinator completed, {:#?} to the next update round...",
+                    update_interval
+                );
+            }
             Err(e) => {
                 if let ResponseError::CoordinatorError(phase2_coordinator::CoordinatorError::CeremonyIsOver) = e {
                     // Return Ok to initialize the shutdown process in select! expression
@@ -69,7 +106,9 @@ async fn verify_contributions(coordinator: Arc<RwLock<Coordinator>>, recv: Recei
     let s3_ctx = S3Ctx::new().await?;
 
     loop {
-        tokio::time::sleep(UPDATE_TIME).await;
+        // Follow the interval scheduled by the update task, which is minimal while contributions are pending
+        let verification_interval = LOCK_MONITOR.read(&coordinator).await.update_interval();
+        tokio::time::sleep(verification_interval).await;
 
         info!("Verifying contributions...");
         let start = std::time::Instant::now();
@@ -77,7 +116,7 @@ async fn verify_contributions(coordinator: Arc<RwLock<Coordinator>>, recv: Recei
         info!(
             "Verification of contributions completed in {:#?}. {:#?} to the next verification round...",
             start.elapsed(),
-            UPDATE_TIME
+            LOCK_MONITOR.read(&coordinator).await.update_interval()
         );
 
         // Return if shutdown signal has been received on the channel
@@ -88,6 +127,139 @@ async fn verify_contributions(coordinator: Arc<RwLock<Coordinator>>, recv: Recei
     }
 }
 
+/// Saves a backup of the [`Coordinator`] at the times of the backup schedule. A failed backup is logged and retried at
+/// the next scheduled time, without affecting the ceremony.
+async fn backup_coordinator(coordinator: Arc<RwLock<Coordinator>>, backups: Backups) {
+    loop {
+        let now = LOCK_MONITOR.read(&coordinator).await.now_utc();
+        let next = match backups.schedule().next_after(now) {
+            Some(next) => next,
+            None => {
+                warn!("Backup schedule {} has no next occurrence, exiting backup task", backups.schedule());
+                return;
+            }
+        };
+        tokio::time::sleep((next - now).try_into().unwrap_or_default()).await;
+
+        info!("Saving backup of coordinator...");
+        let read_lock = LOCK_MONITOR.read_owned(coordinator.clone()).await;
+        let backups = backups.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<String> {
+            let snapshot = read_lock.backup_snapshot()?;
+            drop(read_lock);
+            Ok(backups.save(&snapshot)?)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(name)) => info!("Saved backup {}", name),
+            Ok(Err(e)) => error!("Backup of coordinator failed: {}", e),
+            Err(e) => error!("Backup task panicked: {}", e),
+        }
+    }
+}
+
+/// Logs a snapshot of the holders and the waiters of the lock of the [`Coordinator`] whenever an acquisition stalls for
+/// longer than the threshold of the [`LOCK_MONITOR`].
+async fn watch_lock_waits() {
+    let mut interval = tokio::time::interval((LOCK_MONITOR.threshold() / 2).max(Duration::from_secs(1)));
+    loop {
+        interval.tick().await;
+        LOCK_MONITOR.report_stalls();
+    }
+}
+
+/// Publishes the signed deltas of the transcript of the rounds completed since the last publication, at the interval of
+/// the updates, and delivers the webhooks of the published rounds. A failed publication is logged and retried at the
+/// next interval, without affecting the ceremony.
+async fn publish_transcript(
+    coordinator: Arc<RwLock<Coordinator>>,
+    publisher: TranscriptPublisher,
+    round_webhooks: RoundWebhooks,
+) {
+    loop {
+        let update_interval = LOCK_MONITOR.read(&coordinator).await.update_interval();
+        tokio::time::sleep(update_interval).await;
+
+        let read_lock = LOCK_MONITOR.read_owned(coordinator.clone()).await;
+        let publisher = publisher.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<Vec<SignedTranscriptDelta>> {
+            // The rounds below the current one are complete and their contributions verified
+            let next_round = publisher.next_round()?;
+            let manifests = (next_round..read_lock.current_round_height()?)
+                .map(|round_height| read_lock.round_manifest(round_height))
+                .collect::<Result<Vec<_>, _>>()?;
+            let base_directory = std::path::PathBuf::from(read_lock.environment().local_base_directory());
+            let now = read_lock.now_utc();
+            drop(read_lock);
+
+            publisher.publish(&base_directory, &manifests, now)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(deltas)) if deltas.is_empty() => (),
+            Ok(Ok(deltas)) => {
+                let rounds: Vec<u64> = deltas.iter().map(|delta| delta.delta.round_height).collect();
+                info!("Published the transcript of rounds {:?}", rounds);
+                if let Err(e) = round_webhooks.dispatch(&deltas) {
+                    error!("Delivery of the webhooks of rounds {:?} failed: {}", rounds, e);
+                }
+            }
+            Ok(Err(e)) => error!("Publication of the transcript failed: {}", e),
+            Err(e) => error!("Transcript publication task panicked: {}", e),
+        }
+    }
+}
+
+/// Restores the backup with the given name, or the most recent one, before the [`Coordinator`] is loaded.
+fn restore_backup(environment: &Environment, name: Option<&str>) -> Result<()> {
+    let backups = Backups::from_env()?.ok_or_else(|| anyhow::anyhow!("BACKUP_DIR is not set"))?;
+    let snapshot = backups.load(name)?;
+
+    info!("Restoring backup of {}...", snapshot.created_at);
+    let mismatches = Coordinator::restore_backup(environment, &snapshot)?;
+    for file in &mismatches {
+        warn!("File {} must be restored from the transcript", file);
+    }
+
+    Ok(())
+}
+
+/// Writes the export of the audit log and of the statistics of the ceremony in the given format to the given directory
+fn export_ceremony(environment: Environment, format: &str, directory: &str) -> Result<()> {
+    let format: ExportFormat = format.parse()?;
+    let coordinator = Coordinator::new(environment, Arc::new(ProductionSig))?;
+    let export = CeremonyExport::from_snapshot(&coordinator.backup_snapshot()?);
+
+    for path in export.write(Path::new(directory), format)? {
+        info!("Exported {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Runs the self-test and prints its report, exiting before any traffic is served if a check fails.
+async fn run_self_test(environment: &Environment, keypair: &KeyPair) {
+    let environment = environment.clone();
+    let keypair = keypair.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        self_test::run(&environment, Arc::new(ProductionSig), keypair.sigkey(), keypair.pubkey())
+    })
+    .await
+    .expect("Self-test task panicked");
+
+    report.log();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("Error while serializing the self-test report")
+    );
+    if !report.passed {
+        error!("Self-test failed, refusing to serve traffic");
+        std::process::exit(1);
+    }
+}
+
 /// Checks and prints the env variables of interest for the ceremony
 macro_rules! print_env {
     ($($env:expr),*) => {
@@ -160,15 +332,38 @@ async fn finalize_ceremony(coordinator: Arc<RwLock<Coordinator>>) -> Result<()>
         warn!("Ignoring error while performing last update: {}", e);
     }
 
+    info!("Exporting the final parameters...");
+    if let Err(e) = LOCK_MONITOR.write(&coordinator).await.export_parameters(&OUTPUT_FORMATS) {
+        // Log any error without interrupting the shutdown procedure
+        warn!("Ignoring error while exporting the final parameters: {}", e);
+    }
+
     info!("Saving final coordinator state");
-    coordinator.write().await.shutdown()?;
+    LOCK_MONITOR.write(&coordinator).await.shutdown()?;
 
     Ok(())
 }
 
+/// Waits for the SIGTERM sent by the service manager to stop the coordinator.
+#[cfg(unix)]
+async fn wait_sigterm() {
+    let mut sigterm = signal(SignalKind::terminate()).expect("Error while registering the SIGTERM handler");
+    sigterm.recv().await;
+}
+
+/// Never completes, since there is no SIGTERM outside of unix.
+#[cfg(not(unix))]
+async fn wait_sigterm() {
+    std::future::pending().await
+}
+
 /// Rocket main function using the [`tokio`] runtime
 #[rocket::main]
 pub async fn main() {
+    if std::env::args().nth(1).as_deref() == Some(sandbox::SANDBOX_WORKER_ARG) {
+        std::process::exit(sandbox::run_worker());
+    }
+
     let tracing_enable_color = std::env::var("RUST_LOG_COLOR").is_ok();
     tracing_subscriber::fmt().with_ansi(tracing_enable_color).init();
     print_env!(
@@ -176,15 +371,84 @@ pub async fn main() {
         "AWS_S3_BUCKET",
         "AWS_REGION",
         "NAMADA_MPC_IP_BAN",
+        "NAMADA_MPC_TRUSTED_PROXY",
+        "NAMADA_MPC_PRESET",
+        "NAMADA_MPC_BASE_DIRECTORY",
         "NAMADA_MPC_TIMEOUT_SECONDS",
+        "NAMADA_MPC_OFFLINE_TIMEOUT_SECONDS",
+        "NAMADA_MPC_VERIFICATION_QUORUM",
+        "NAMADA_MPC_QUORUM_VERIFIERS",
+        "NAMADA_MPC_OPERATORS",
+        "NAMADA_MPC_CONTROL_KEYS",
+        "NAMADA_MPC_VERIFICATION_MEMORY_BUDGET",
+        "NAMADA_MPC_COMPUTE_BACKEND",
+        "NAMADA_MPC_SANDBOX_MEMORY_LIMIT",
+        "NAMADA_MPC_SANDBOX_CPU_SECONDS",
+        "NAMADA_MPC_MAX_VERIFICATION_RETRIES",
+        "NAMADA_MPC_VERIFICATION_BACKLOG_THRESHOLD",
+        "NAMADA_MPC_HASH_ALGORITHM",
+        "NAMADA_MPC_IP_RETENTION",
+        "NAMADA_MPC_IPV4_PREFIX",
+        "NAMADA_MPC_IPV6_PREFIX",
+        "NAMADA_MPC_QUEUE_SHUFFLE",
+        "NAMADA_MPC_IP_LOCATION_DATABASE",
+        "NAMADA_MPC_MIN_UPDATE_INTERVAL_SECONDS",
+        "NAMADA_MPC_MAX_UPDATE_INTERVAL_SECONDS",
+        "NAMADA_MPC_MAX_ROUNDS",
+        "NAMADA_MPC_MAX_DURATION_SECONDS",
+        "NAMADA_MPC_FINAL_WINDOW_SECONDS",
+        "NAMADA_MPC_DESTRUCTION_ATTESTATION_DEADLINE_SECONDS",
+        "NAMADA_MPC_OUTPUT_FORMATS",
+        "NAMADA_MPC_STORAGE_SHARDS",
+        "NAMADA_MPC_API_DEPRECATIONS",
         "HEALTH_PATH",
         "NAMADA_TOKENS_PATH",
         "CEREMONY_START_TIMESTAMP",
+        "CEREMONY_METADATA_PATH",
         "TOKENS_FILE_PREFIX",
         "NAMADA_COHORT_TIME",
-        "TOKEN_BLACKLIST"
+        "TOKEN_BLACKLIST",
+        "CORS_ALLOWED_ORIGINS",
+        "COMPRESSION_MIN_SIZE",
+        "POW_DIFFICULTY",
+        "CAPABILITY_PROBE_POLICY",
+        "CAPABILITY_PROBE_TIMEOUT_SECONDS",
+        "HUMAN_VERIFICATION_PROVIDER",
+        "HUMAN_VERIFICATION_HOSTNAME",
+        "IDENTITY_RESOLVER",
+        "IDENTITY_RESOLVER_URL",
+        "IDENTITY_REQUIRED",
+        "REQUEST_LOG_PATH",
+        "REQUEST_LOG_ENDPOINTS",
+        "REQUEST_LOG_MAX_BODY_SIZE",
+        "REQUEST_LOG_REDACTED_FIELDS",
+        "REQUEST_LOG_MAX_FILE_SIZE",
+        "REQUEST_LOG_MAX_FILES",
+        "BACKUP_DIR",
+        "BACKUP_SCHEDULE",
+        "BACKUP_KEEP",
+        "BACKUP_MAX_AGE_DAYS",
+        "TRANSCRIPT_PUBLISH_DIR",
+        "TRANSCRIPT_PUBLIC_URL",
+        "ROUND_WEBHOOK_MAX_ATTEMPTS",
+        "EVENTS_KAFKA_BROKERS",
+        "EVENTS_AMQP_EXCHANGE",
+        "EVENTS_SUBJECT",
+        "PID_FILE",
+        "LISTEN_FDS",
+        "NOTIFY_SOCKET",
+        "LOCK_WAIT_THRESHOLD_SECONDS",
+        "CHAOS_LATENCY_RATE",
+        "CHAOS_MAX_LATENCY_MS",
+        "CHAOS_UNAVAILABLE_RATE",
+        "CHAOS_VERIFICATION_FAILURE_RATE"
     );
 
+    #[cfg(unix)]
+    let _pid_file = PID_FILE
+        .clone()
+        .map(|path| PidFile::create(path).expect("Error while writing the pid file"));
+
     // Generate, publish and export the secret token
     generate_secret().await.expect("Error while generating secret token");
 
@@ -194,26 +458,69 @@ pub async fn main() {
         .unwrap()
         .expect("Error while generating the keypair");
 
-    #[cfg(debug_assertions)]
-    let environment: Testing = {
-        phase2_coordinator::testing::clear_test_storage(&Testing::default().into());
-        Testing::new(&keypair)
+    // A preset selected in the env replaces the default environment of the build
+    let preset = Preset::from_env().expect("Error while reading the preset");
+    let environment: Environment = match preset {
+        Some(preset) => {
+            preset
+                .validate_circuits(TEST_CIRCUITS)
+                .expect("Invalid circuits for the preset");
+            let overrides = Overrides::from_env().expect("Error while reading the overrides of the preset");
+            preset.environment(&keypair, &overrides)
+        }
+        #[cfg(debug_assertions)]
+        None => {
+            phase2_coordinator::testing::clear_test_storage(&Testing::default().into());
+            Testing::new(&keypair).into()
+        }
+        #[cfg(not(debug_assertions))]
+        None => Production::new(&keypair).into(),
     };
-
-    #[cfg(not(debug_assertions))]
-    let environment: Production = { Production::new(&keypair) };
+    let rehearsal = environment.is_rehearsal();
+    if matches!(environment.compute_backend(), ComputeBackend::Sandboxed(_)) && !sandbox::SANDBOX_SUPPORTED {
+        panic!("The sandboxed compute backend requires a build with the sandbox feature, on Linux");
+    }
 
     // Always download token files from S3 to check for updates
     download_tokens().await.expect("Error while retrieving tokens");
 
+    // Restore the state of the coordinator from a backup, when started with `restore --from-backup [name]`, check
+    // that the coordinator is able to run the ceremony, when started with `--self-test`, or export the audit log and
+    // the statistics of the ceremony from the storage, when started with `export <csv|parquet> <directory>`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => (),
+        ["restore", "--from-backup"] => restore_backup(&environment, None).expect("Error while restoring the backup"),
+        ["restore", "--from-backup", name] => {
+            restore_backup(&environment, Some(*name)).expect("Error while restoring the backup")
+        }
+        ["--self-test"] => run_self_test(&environment, &keypair).await,
+        ["export", format, directory] => {
+            return export_ceremony(environment, format, directory).expect("Error while exporting the ceremony");
+        }
+        _ => panic!(
+            "Usage: phase2-coordinator [restore --from-backup [name] | --self-test | export <format> <directory>]"
+        ),
+    }
+
     // Initialize the coordinator
-    let coordinator =
-        Coordinator::new(environment.into(), Arc::new(ProductionSig)).expect("Failed to instantiate coordinator");
+    let mut coordinator =
+        Coordinator::new(environment, Arc::new(ProductionSig)).expect("Failed to instantiate coordinator");
+    let verification_tracker = VerificationTracker::default();
+    coordinator.set_verification_callback(verification_tracker.callback());
+
+    // Publish the events of the ceremony to the streams of queue events and to the message queues configured in the
+    // env, if any. The urls of the NATS and AMQP servers are not printed with the other env variables, since they may
+    // contain credentials
+    let queue_events = QueueEvents::default();
+    let publishers = EventPublishers::from_env(Arc::new(ProductionSig), keypair.clone())
+        .expect("Error while connecting to the message queues");
+    coordinator.set_event_callback(queue_events.callback(publishers.map(EventPublishers::callback)));
     let coordinator: Arc<RwLock<Coordinator>> = Arc::new(RwLock::new(coordinator));
     let up_coordinator = coordinator.clone();
     let verify_coordinator = coordinator.clone();
 
-    let mut write_lock = coordinator.clone().write_owned().await;
+    let mut write_lock = LOCK_MONITOR.write_owned(coordinator.clone()).await;
 
     tokio::task::spawn_blocking(move || write_lock.initialize().expect("Initialization of coordinator failed!"))
         .await
@@ -222,55 +529,255 @@ pub async fn main() {
     // Build Rocket REST server
     #[cfg(debug_assertions)]
     let routes = routes![
+        rest::get_pow_puzzle,
+        rest::get_capability_probe,
+        rest::get_capability_probe_blob,
+        rest::lock_practice_round,
+        rest::get_practice_challenge,
+        rest::post_practice_contribution,
         rest::join_queue,
         rest::lock_chunk,
         rest::get_challenge_url,
+        rest::get_challenge_stream,
         rest::get_contribution_url,
         rest::contribute_chunk,
+        rest::upload_contribution,
+        rest::post_computation_proof,
+        rest::upload_contribution_range,
+        rest::get_contribution_upload_progress,
+        rest::resume_session,
+        rest::get_challenge_bundle,
+        rest::post_response_bundle,
+        rest::get_pending_verifications,
+        rest::post_verification_vote,
+        rest::get_manual_contribution_url,
+        rest::post_manual_contribution,
+        rest::commit_genesis_seed,
+        rest::reveal_genesis_seed,
         rest::update_coordinator,
         rest::heartbeat,
         rest::stop_coordinator,
         rest::verify_chunks,
+        rest::reissue_challenge,
         rest::get_contributor_queue_status,
+        rest::get_contributor_queue_events,
+        rest::get_verification_status,
         rest::post_contribution_info,
         rest::get_contributions_info,
         rest::get_coordinator_state,
+        rest::get_participants_history,
+        rest::get_export,
+        rest::get_storage_shards,
+        rest::rebalance_storage,
+        rest::post_reverify,
+        rest::get_reverification_status,
+        rest::post_verifier_delegation,
+        rest::get_lock_metrics,
+        rest::get_startup_profile,
+        rest::get_control_keys,
+        rest::add_control_key,
+        rest::remove_control_key,
+        rest::get_webhook_deliveries,
+        rest::get_verification_quarantine,
+        rest::reject_quarantined_contribution,
+        rest::verify_quarantined_contribution,
+        rest::post_prelaunch_snapshot,
+        rest::post_prelaunch_restore,
+        rest::get_transcript,
+        rest::get_transcript_manifests,
         rest::get_healthcheck,
+        rest::get_ceremony_status,
+        rest::get_public_ceremony_status,
+        rest::get_circuit_registry,
+        rest::get_ceremony_metadata,
+        rest::get_badge,
+        rest::get_ceremony_parameters,
+        rest::get_announcements,
+        rest::get_queue_shuffles,
+        rest::get_genesis_seed,
+        rest::get_computation_stats,
+        rest::get_api_changelog,
+        rest::get_messages,
         rest::update_cohorts,
-        rest::post_attestation
+        rest::post_attestation,
+        rest::post_attestation_post,
+        rest::post_destruction_attestation
     ];
 
     #[cfg(not(debug_assertions))]
     let routes = routes![
+        rest::get_pow_puzzle,
+        rest::get_capability_probe,
+        rest::get_capability_probe_blob,
+        rest::lock_practice_round,
+        rest::get_practice_challenge,
+        rest::post_practice_contribution,
         rest::join_queue,
         rest::lock_chunk,
         rest::get_challenge_url,
+        rest::get_challenge_stream,
         rest::get_contribution_url,
         rest::contribute_chunk,
+        rest::upload_contribution,
+        rest::post_computation_proof,
+        rest::upload_contribution_range,
+        rest::get_contribution_upload_progress,
+        rest::resume_session,
+        rest::get_challenge_bundle,
+        rest::post_response_bundle,
+        rest::get_pending_verifications,
+        rest::post_verification_vote,
+        rest::get_manual_contribution_url,
+        rest::post_manual_contribution,
+        rest::commit_genesis_seed,
+        rest::reveal_genesis_seed,
         rest::heartbeat,
         rest::stop_coordinator,
+        rest::reissue_challenge,
         rest::get_contributor_queue_status,
+        rest::get_contributor_queue_events,
+        rest::get_verification_status,
         rest::post_contribution_info,
         rest::get_coordinator_state,
+        rest::get_participants_history,
+        rest::get_export,
+        rest::get_storage_shards,
+        rest::rebalance_storage,
+        rest::post_reverify,
+        rest::get_reverification_status,
+        rest::post_verifier_delegation,
+        rest::get_lock_metrics,
+        rest::get_startup_profile,
+        rest::get_control_keys,
+        rest::add_control_key,
+        rest::remove_control_key,
+        rest::get_webhook_deliveries,
+        rest::get_verification_quarantine,
+        rest::reject_quarantined_contribution,
+        rest::verify_quarantined_contribution,
+        rest::post_prelaunch_snapshot,
+        rest::post_prelaunch_restore,
+        rest::get_transcript,
+        rest::get_transcript_manifests,
         rest::get_healthcheck,
+        rest::get_ceremony_status,
+        rest::get_public_ceremony_status,
+        rest::get_circuit_registry,
+        rest::get_ceremony_metadata,
+        rest::get_badge,
+        rest::get_ceremony_parameters,
+        rest::get_announcements,
+        rest::get_queue_shuffles,
+        rest::get_genesis_seed,
+        rest::get_computation_stats,
+        rest::get_api_changelog,
+        rest::get_messages,
         rest::update_cohorts,
-        rest::post_attestation
+        rest::post_attestation,
+        rest::post_attestation_post,
+        rest::post_destruction_attestation
     ];
 
-    let build_rocket = rocket::build().mount("/", routes).manage(coordinator.clone()).register(
-        "/",
-        catchers![
-            rest_utils::invalid_signature,
-            rest_utils::unauthorized,
-            rest_utils::missing_required_header,
-            rest_utils::io_error,
-            rest_utils::unprocessable_entity,
-            rest_utils::mismatching_checksum,
-            rest_utils::invalid_header
-        ],
-    );
+    // The production preset can't be served together with the endpoints reserved to debugging
+    if let Some(preset) = preset {
+        let endpoints: Vec<String> = routes.iter().map(|route| route.uri.path().to_string()).collect();
+        preset
+            .validate_endpoints(endpoints.iter().map(String::as_str))
+            .expect("Invalid endpoints for the preset");
+    }
+
+    // With socket activation, the rest server is bound to a local port and receives the connections accepted on the
+    // inherited socket
+    #[cfg(unix)]
+    let socket_activation = SocketActivation::from_env().expect("Error while retrieving the inherited socket");
+    #[cfg(unix)]
+    let figment = match socket_activation {
+        Some(_) => SocketActivation::figment(rocket::Config::figment()),
+        None => rocket::Config::figment(),
+    };
+    #[cfg(not(unix))]
+    let figment = rocket::Config::figment();
+
+    // Anyone holding a token can join a rehearsal, without a proof-of-work
+    let proof_of_work = if rehearsal {
+        ProofOfWork::new(None)
+    } else {
+        let shared_store = shared_store::from_env().expect("Error while connecting to the shared store");
+        ProofOfWork::with_store(*POW_DIFFICULTY, shared_store)
+    };
+    // Shared by the endpoint of the status of the deliveries and the publication task
+    let round_webhooks = RoundWebhooks::from_env();
+
+    let build_rocket = rocket::custom(figment)
+        .mount("/", routes)
+        .manage(coordinator.clone())
+        .manage(verification_tracker)
+        .manage(ChallengeETags::default())
+        .manage(PracticeRounds::default())
+        .manage(queue_events)
+        .manage(proof_of_work)
+        .manage(CapabilityProber::default())
+        .manage(HumanVerifier::from_env())
+        .manage(IdentityVerifier::from_env())
+        .manage(round_webhooks.clone())
+        .attach(Cors::default())
+        .register(
+            "/",
+            catchers![
+                rest_utils::invalid_signature,
+                rest_utils::unauthorized,
+                rest_utils::missing_required_header,
+                rest_utils::io_error,
+                rest_utils::unprocessable_entity,
+                rest_utils::mismatching_checksum,
+                rest_utils::invalid_header,
+                rest_utils::schema_violation,
+                rest_utils::payload_too_large,
+                rest_utils::unsupported_encoding,
+                rest_utils::invalid_lifecycle_phase
+            ],
+        );
+
+    // Serve the status page of the ceremony from the same server
+    #[cfg(feature = "dashboard")]
+    let build_rocket = build_rocket.mount(dashboard::DASHBOARD_PATH, dashboard::routes());
+    // The dashboard reads the contribution info, otherwise published only to S3 in release builds
+    #[cfg(all(feature = "dashboard", not(debug_assertions)))]
+    let build_rocket = build_rocket.mount("/", routes![rest::get_contributions_info]);
+
+    // Log the requests and their responses, if configured
+    let build_rocket = match RequestLog::from_env().expect("Error while opening the request log") {
+        Some(request_log) => build_rocket.attach(request_log),
+        None => build_rocket,
+    };
+
+    // Inject the faults of the chaos mode, if configured
+    #[cfg(debug_assertions)]
+    let build_rocket = build_rocket.attach(Chaos::default());
+
+    // Mark all the responses of a rehearsal as non-production
+    let build_rocket = if rehearsal {
+        build_rocket.attach(RehearsalMarker)
+    } else {
+        build_rocket
+    };
+
+    // Compress the responses last, once the other fairings are done with their bodies
+    let build_rocket = build_rocket.attach(Compression::default());
+
+    // Forward the connections from the inherited socket, if any, once the server is bound
+    #[cfg(unix)]
+    let build_rocket = match socket_activation {
+        Some(socket_activation) => build_rocket.attach(socket_activation),
+        None => build_rocket,
+    };
+
     let ignite_rocket = build_rocket.ignite().await.expect("Coordinator server didn't ignite");
 
+    // The state of the coordinator has been loaded
+    #[cfg(unix)]
+    systemd::notify_ready();
+
     // Sleep until ceremony start time has been reached
     #[cfg(not(debug_assertions))]
     {
@@ -305,9 +812,53 @@ pub async fn main() {
     // Spawn task to verify the contributions periodically
     let mut verify_handle = rocket::tokio::spawn(verify_contributions(verify_coordinator, rx));
 
+    // Spawn the watchdog of the lock of the coordinator. The task is left running until the shutdown
+    rocket::tokio::spawn(watch_lock_waits());
+
+    // Spawn task to back up the coordinator on schedule, if enabled. The task is left running until the shutdown
+    if let Some(backups) = Backups::from_env().expect("Error while configuring the backups") {
+        info!("Backing up coordinator on schedule {}", backups.schedule());
+        rocket::tokio::spawn(backup_coordinator(coordinator.clone(), backups));
+    }
+
+    // Spawn task to publish the transcript of the completed rounds, if enabled. The task is left running until the
+    // shutdown
+    if let Some(publisher) = TranscriptPublisher::from_env(Arc::new(ProductionSig), keypair.clone())
+        .expect("Error while configuring the transcript publication")
+    {
+        info!("Publishing the transcript of the completed rounds");
+        if round_webhooks.is_enabled() {
+            info!("Delivering the webhooks of the published rounds");
+        }
+        rocket::tokio::spawn(publish_transcript(coordinator.clone(), publisher, round_webhooks));
+    }
+
+    // Spawn task to push the notifications of the contributors over WebSocket, if enabled. The task is left running
+    // until the shutdown
+    #[cfg(feature = "websocket")]
+    if let Some(address) = notifications::WEBSOCKET_ADDRESS.as_deref() {
+        info!(
+            "Pushing the notifications of the contributors over WebSocket on {}",
+            address
+        );
+        let coordinator = coordinator.clone();
+        let queue_events = ignite_rocket
+            .state::<QueueEvents>()
+            .cloned()
+            .expect("The queue events are managed by the rest server");
+        rocket::tokio::spawn(async move {
+            if let Err(e) = notifications::serve(address, coordinator, queue_events).await {
+                error!("WebSocket channel of the notifications failed: {}", e);
+            }
+        });
+    }
+
     // Spawn Rocket server task
     let mut rocket_handle = rocket::tokio::spawn(ignite_rocket.launch());
 
+    // SIGTERM goes through the same draining path of the other shutdown causes
+    let sigterm = wait_sigterm();
+
     // Pass mutable refs to be able to manually abort the tasks when needed
     // NOTE: the passed-in futures are not cancel-safe per se. We enforce safety during the shutdown by means of a communication channel to notify the concurrent tasks to terminate
     // The rocket tasks is instead shut down from the Shutdown handler
@@ -317,6 +868,8 @@ pub async fn main() {
                 Ok(()) => {
                     // Cohorts are over, terminate the ceremony
                     info!("Cohorts are over, notifying rest server to shut down...");
+                    #[cfg(unix)]
+                    systemd::notify_stopping();
 
                     // Cancel concurrent tasks
                     info!("Cancelling concurrent tasks...");
@@ -354,6 +907,8 @@ pub async fn main() {
                 Ok(_) => {
                     // Rest server received shutdown signal, terminate the ceremony
                     info!("Rocket task completed, ending the ceremony...");
+                    #[cfg(unix)]
+                    systemd::notify_stopping();
 
                     // Cancel concurrent tasks
                     info!("Cancelling concurrent tasks...");
@@ -378,6 +933,38 @@ pub async fn main() {
                 },
                 Err(e) => error!("Rocket failed: {}", e)
             }
+        },
+        _ = sigterm => {
+            info!("Received SIGTERM, draining the coordinator...");
+            #[cfg(unix)]
+            systemd::notify_stopping();
+
+            // Cancel concurrent tasks and let the rest server complete the pending requests
+            info!("Cancelling concurrent tasks...");
+            tx.send(true).expect("Error while sending shutdown notification to concurrent tasks, channel is already closed");
+            shutdown.notify();
+
+            let (v_res, u_res, r_res) = tokio::join!(
+                verify_handle,
+                update_handle,
+                rocket_handle
+            );
+
+            if let Err(e) = v_res {
+                warn!("Ignoring error while joining verify task: {}", e);
+            }
+
+            if let Err(e) = u_res {
+                warn!("Ignoring error while joining update task: {}", e);
+            }
+
+            if let Err(e) = r_res {
+                warn!("Ignoring error while joining rocket task: {}", e);
+            }
+
+            info!("Concurrent tasks terminated");
+
+            finalize_ceremony(coordinator).await.expect("Failed ceremony state finalize");
         }
     }
 }
@@ -0,0 +1,116 @@
+//! Memory budget of the verification of the contributions, to prevent a large contribution from exhausting the memory
+//! of the coordinator host.
+//!
+//! A verification loads the challenge and response files in memory and deserializes the parameters they contain.
+//! Before reading them, the coordinator estimates the memory required from the size of the files and aborts the
+//! verification if the estimate exceeds the configured budget or, when the coordinator runs in a cgroup with a memory
+//! limit, the memory left in the cgroup.
+
+use crate::CoordinatorError;
+
+use std::path::Path;
+use tracing::{debug, error};
+
+/// Ratio between the memory allocated by a verification and the size of the files it reads: the content of the files
+/// plus the deserialized parameters, which take slightly more space than their uncompressed serialization.
+pub const VERIFICATION_MEMORY_FACTOR: u64 = 2;
+
+/// Memory controller files of cgroup v2, then v1: limit and current usage.
+const CGROUP_MEMORY_FILES: [(&str, &str); 2] = [
+    ("/sys/fs/cgroup/memory.max", "/sys/fs/cgroup/memory.current"),
+    (
+        "/sys/fs/cgroup/memory/memory.limit_in_bytes",
+        "/sys/fs/cgroup/memory/memory.usage_in_bytes",
+    ),
+];
+
+/// Returns the estimated memory, in bytes, required to verify a contribution given the size of the challenge and
+/// response files.
+pub fn verification_memory(challenge_size: u64, response_size: u64) -> u64 {
+    challenge_size
+        .saturating_add(response_size)
+        .saturating_mul(VERIFICATION_MEMORY_FACTOR)
+}
+
+/// Returns the memory, in bytes, still available in the cgroup of the process, or [`None`] if the process is not
+/// limited by a cgroup.
+pub fn cgroup_available_memory() -> Option<u64> {
+    CGROUP_MEMORY_FILES
+        .iter()
+        .find_map(|(limit, current)| read_cgroup_available_memory(Path::new(limit), Path::new(current)))
+}
+
+fn read_cgroup_available_memory(limit_path: &Path, current_path: &Path) -> Option<u64> {
+    let limit = std::fs::read_to_string(limit_path).ok()?;
+    // cgroup v2 writes "max" when unlimited
+    let limit: u64 = limit.trim().parse().ok()?;
+    let current: u64 = std::fs::read_to_string(current_path).ok()?.trim().parse().ok()?;
+
+    Some(limit.saturating_sub(current))
+}
+
+/// Checks that `required` bytes fit both in the given budget, if any, and in the memory left in the cgroup, if any.
+pub fn check_budget(budget: Option<u64>, required: u64) -> Result<(), CoordinatorError> {
+    check_available(budget, cgroup_available_memory(), required)
+}
+
+fn check_available(budget: Option<u64>, cgroup_available: Option<u64>, required: u64) -> Result<(), CoordinatorError> {
+    let available = match (budget, cgroup_available) {
+        (Some(budget), Some(cgroup)) => budget.min(cgroup),
+        (Some(available), None) | (None, Some(available)) => available,
+        (None, None) => return Ok(()),
+    };
+    debug!("Verification requires {} bytes of memory, {} available", required, available);
+
+    if required > available {
+        error!(
+            "Verification requires {} bytes of memory, exceeding the {} available",
+            required, available
+        );
+        return Err(CoordinatorError::VerificationMemoryBudgetExceeded { required, available });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_available() {
+        assert!(check_available(None, None, u64::MAX).is_ok());
+        assert!(check_available(Some(100), None, 100).is_ok());
+        assert!(matches!(
+            check_available(Some(100), None, 101),
+            Err(CoordinatorError::VerificationMemoryBudgetExceeded {
+                required: 101,
+                available: 100
+            })
+        ));
+        assert!(matches!(
+            check_available(Some(100), Some(50), 60),
+            Err(CoordinatorError::VerificationMemoryBudgetExceeded {
+                required: 60,
+                available: 50
+            })
+        ));
+        assert!(check_available(None, Some(50), 50).is_ok());
+    }
+
+    #[test]
+    fn test_read_cgroup_available_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let limit = dir.path().join("memory.max");
+        let current = dir.path().join("memory.current");
+
+        assert_eq!(read_cgroup_available_memory(&limit, &current), None);
+
+        std::fs::write(&current, "300\n").unwrap();
+        std::fs::write(&limit, "max\n").unwrap();
+        assert_eq!(read_cgroup_available_memory(&limit, &current), None);
+
+        std::fs::write(&limit, "1000\n").unwrap();
+        assert_eq!(read_cgroup_available_memory(&limit, &current), Some(700));
+    }
+}
@@ -0,0 +1,214 @@
+//! Catalog of the human-readable messages of the statuses and the errors of the REST API, in the languages of the
+//! participants.
+//!
+//! Each message is identified by a stable code, e.g. `error.invalid_token` or `status.queue`, returned with the
+//! responses so that the clients don't depend on the wording of the messages. The text of a message may hold the
+//! placeholders `{0}`, `{1}`, ... filled with the arguments of the status or the error. The catalog is embedded in the
+//! crate, in `messages/catalog.json`, and the operator can add languages or override messages with a json file of the
+//! same format, `{"<code>": {"<language>": "<message>"}}`, set in the `NAMADA_MPC_MESSAGE_CATALOG` env variable.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+use thiserror::Error;
+
+/// The language of the messages when the client doesn't accept any language of the catalog.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The catalog embedded in the crate.
+const EMBEDDED_CATALOG: &str = include_str!("../messages/catalog.json");
+
+lazy_static! {
+    pub static ref MESSAGE_CATALOG: MessageCatalog = match std::env::var("NAMADA_MPC_MESSAGE_CATALOG") {
+        Ok(path) => MessageCatalog::load(Path::new(&path)).expect("Invalid NAMADA_MPC_MESSAGE_CATALOG"),
+        Err(_) => MessageCatalog::embedded(),
+    };
+}
+
+#[derive(Debug, Error)]
+pub enum MessageCatalogError {
+    #[error("Error while reading the message catalog: {0}")]
+    IO(#[from] std::io::Error),
+    #[error("Message catalog is not valid: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The messages of the catalog, by code and by language.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageCatalog(BTreeMap<String, BTreeMap<String, String>>);
+
+/// The messages of the catalog in a single language, served to the clients localizing the codes of the responses.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedCatalog {
+    pub language: String,
+    /// The messages by code, in [`DEFAULT_LANGUAGE`] when missing in the language.
+    pub messages: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Returns the catalog embedded in the crate.
+    pub fn embedded() -> Self {
+        serde_json::from_str(EMBEDDED_CATALOG).expect("The embedded message catalog should be valid")
+    }
+
+    /// Returns the embedded catalog extended with the messages of the given file.
+    pub fn load(path: &Path) -> Result<Self, MessageCatalogError> {
+        let mut catalog = Self::embedded();
+        catalog.extend(serde_json::from_slice(&fs::read(path)?)?);
+
+        Ok(catalog)
+    }
+
+    /// Adds the messages of the given catalog, replacing the ones with the same code and language.
+    pub fn extend(&mut self, other: MessageCatalog) {
+        for (code, messages) in other.0 {
+            self.0.entry(code).or_default().extend(messages);
+        }
+    }
+
+    /// Returns the languages with at least one message in the catalog.
+    pub fn languages(&self) -> BTreeSet<&str> {
+        self.0
+            .values()
+            .flat_map(|messages| messages.keys())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns the language of the catalog preferred by the given `Accept-Language` header, if any. A language tag
+    /// also matches its primary language, e.g. `fr-CH` matches `fr`, and the wildcard matches [`DEFAULT_LANGUAGE`].
+    pub fn negotiate(&self, accept_language: &str) -> Option<&str> {
+        let languages = self.languages();
+        parse_accept_language(accept_language).into_iter().find_map(|tag| {
+            if tag == "*" {
+                return languages.get(DEFAULT_LANGUAGE).copied();
+            }
+            let primary = tag.split('-').next().unwrap_or_default();
+            languages
+                .iter()
+                .find(|language| language.eq_ignore_ascii_case(&tag))
+                .or_else(|| languages.iter().find(|language| language.eq_ignore_ascii_case(primary)))
+                .copied()
+        })
+    }
+
+    /// Returns the message with the given code in the given language, if any, with its placeholders replaced by the
+    /// given arguments.
+    pub fn render(&self, code: &str, language: &str, args: &[String]) -> Option<String> {
+        let mut message = self.0.get(code)?.get(language)?.clone();
+        for (index, arg) in args.iter().enumerate() {
+            message = message.replace(&format!("{{{}}}", index), arg);
+        }
+
+        Some(message)
+    }
+
+    /// Returns the messages of the catalog in the given language.
+    pub fn localized(&self, language: &str) -> LocalizedCatalog {
+        let messages = self
+            .0
+            .iter()
+            .filter_map(|(code, messages)| {
+                let message = messages.get(language).or_else(|| messages.get(DEFAULT_LANGUAGE))?;
+                Some((code.clone(), message.clone()))
+            })
+            .collect();
+
+        LocalizedCatalog {
+            language: language.to_string(),
+            messages,
+        }
+    }
+}
+
+/// Returns the language tags of the given `Accept-Language` header, by decreasing quality, leaving out the ones with a
+/// null quality.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut tags: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .next()
+                .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+            match tag.is_empty() || quality <= 0.0 {
+                true => None,
+                false => Some((tag.to_string(), quality)),
+            }
+        })
+        .collect();
+    // The sort is stable, the tags of the same quality keep their order
+    tags.sort_by(|(_, left), (_, right)| right.partial_cmp(left).unwrap_or(std::cmp::Ordering::Equal));
+
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_catalog() {
+        let catalog = MessageCatalog::embedded();
+        let languages = catalog.languages();
+        assert!(languages.contains(DEFAULT_LANGUAGE));
+
+        // Every message is translated in every language, with the same placeholders
+        let placeholders = |message: &str| (0..4).filter(|i| message.contains(&format!("{{{}}}", i))).count();
+        for (code, messages) in &catalog.0 {
+            assert_eq!(messages.len(), languages.len(), "{} is not translated", code);
+            let expected = placeholders(&messages[DEFAULT_LANGUAGE]);
+            for (language, message) in messages {
+                assert_eq!(placeholders(message), expected, "{} in {}", code, language);
+            }
+        }
+    }
+
+    #[test]
+    fn test_negotiate_and_render() {
+        let mut catalog = MessageCatalog::embedded();
+        assert_eq!(
+            parse_accept_language("de;q=0.5, fr-CH, fr;q=0.9, en;q=0, *;q=0.1"),
+            vec!["fr-CH", "fr", "de", "*"]
+        );
+        assert_eq!(catalog.negotiate("fr-CH, fr;q=0.9, en;q=0.8"), Some("fr"));
+        assert_eq!(catalog.negotiate("ES"), Some("es"));
+        assert_eq!(catalog.negotiate("de, *;q=0.5"), Some(DEFAULT_LANGUAGE));
+        assert_eq!(catalog.negotiate("de, ja"), None);
+
+        let args = ["3".to_string(), "10".to_string()];
+        assert_eq!(
+            catalog.render("status.queue", "fr", &args).unwrap(),
+            "Vous êtes dans la file, en position 3 sur 10"
+        );
+        assert_eq!(catalog.render("status.queue", "de", &args), None);
+        assert_eq!(catalog.render("status.unknown", "en", &args), None);
+
+        // The operator adds a language and overrides a message
+        let extension: MessageCatalog = serde_json::from_value(serde_json::json!({
+            "status.round": {"de": "Sie sind an der Reihe", "en": "Your turn"},
+        }))
+        .unwrap();
+        catalog.extend(extension);
+        assert_eq!(catalog.negotiate("de, ja"), Some("de"));
+        assert_eq!(catalog.render("status.round", "en", &[]).unwrap(), "Your turn");
+        assert_eq!(
+            catalog.render("status.round", "fr", &[]).unwrap(),
+            "C'est votre tour de contribuer"
+        );
+
+        let localized = catalog.localized("de");
+        assert_eq!(localized.messages["status.round"], "Sie sind an der Reihe");
+        assert_eq!(
+            localized.messages["status.finished"],
+            catalog.0["status.finished"]["en"]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! WebSocket channel pushing to the contributors the notifications of the progress of their contribution, instead of
+//! having them poll the REST API.
+//!
+//! The version of Rocket serving the REST API can't upgrade its connections, so the channel listens on its own address,
+//! set in `WEBSOCKET_ADDRESS`, e.g. `0.0.0.0:8081`, and is disabled if unset. A contributor subscribes once, by opening
+//! a connection with the [`PUBKEY_HEADER`] and [`SIGNATURE_HEADER`] headers of a signed GET request to the REST API,
+//! and then receives each [`Notification`] as a json text message. The notifications are derived from the events of the
+//! ceremony broadcast by [`QueueEvents`] and from the state of the coordinator, read again on each event and at least
+//! every [`NOTIFICATIONS_REFRESH`], when the connection is also pinged.
+
+use crate::{
+    authentication::ssh,
+    events::CeremonyEvent,
+    lock_monitor::LOCK_MONITOR,
+    objects::Participant,
+    rest,
+    rest_utils::{
+        legacy_signatures_accepted, ContributorStatus, Coordinator, QueueEvents, SignatureHeaders, PUBKEY_HEADER,
+        SIGNATURE_HEADER,
+    },
+};
+
+use futures::{SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use rocket::tokio::{
+    self,
+    net::{TcpListener, TcpStream},
+    sync::broadcast::error::RecvError,
+};
+use serde::{Deserialize, Serialize};
+use std::{io, time::Duration};
+use time::OffsetDateTime;
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+};
+use tracing::debug;
+
+lazy_static! {
+    /// The address of the WebSocket channel, disabled if unset.
+    pub static ref WEBSOCKET_ADDRESS: Option<String> = std::env::var("WEBSOCKET_ADDRESS").ok();
+}
+
+/// The maximum interval between two reads of the state of the contributor, and between two pings of the connection.
+pub const NOTIFICATIONS_REFRESH: Duration = Duration::from_secs(15);
+/// The time before the timeout of a contributor from which it is warned.
+pub const TIMEOUT_WARNING: time::Duration = time::Duration::minutes(2);
+
+/// A notification pushed to a contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Notification {
+    /// The contributor entered the current round: its challenge is ready to be locked and downloaded.
+    ChallengeReady,
+    /// The contributor acquired the lock on the chunk of its contribution.
+    LockGranted { round_height: u64, chunk_id: u64 },
+    /// The verification of the contribution of the contributor completed.
+    VerificationResult { round_height: u64, succeeded: bool },
+    /// The contributor is dropped at the deadline unless it sends a heartbeat or completes its contribution.
+    TimeoutWarning {
+        #[serde(with = "time::serde::timestamp")]
+        deadline: OffsetDateTime,
+    },
+}
+
+/// Accepts the WebSocket connections of the contributors on the given address and pushes them their notifications.
+pub async fn serve(address: &str, coordinator: Coordinator, queue_events: QueueEvents) -> io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let coordinator = coordinator.clone();
+        let queue_events = queue_events.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = notify_contributor(stream, coordinator, queue_events).await {
+                debug!("WebSocket connection from {} closed with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// Returns the contributor authenticated by the signature headers of the opening handshake, if valid, and whether it
+/// signed the legacy message, only accepted until the deadline of [`legacy_signatures_accepted`]. OpenSSH public keys
+/// are converted to the format of the native ones
+fn authenticate(request: &Request) -> Option<(Participant, bool)> {
+    let header = |name| request.headers().get(name).and_then(|value| value.to_str().ok());
+    let headers = SignatureHeaders::new(header(PUBKEY_HEADER)?, None, Some(header(SIGNATURE_HEADER)?.into()));
+    let legacy = match headers.try_verify_signature() {
+        Ok(true) => false,
+        _ if matches!(headers.try_verify_legacy_signature(), Ok(true)) => true,
+        _ => return None,
+    };
+
+    let contributor = Participant::new_contributor(&ssh::canonical_public_key(headers.pubkey));
+
+    Some((contributor, legacy))
+}
+
+/// Pushes the notifications of the contributor authenticated by the opening handshake of the connection, until either
+/// side closes it.
+async fn notify_contributor(
+    stream: TcpStream,
+    coordinator: Coordinator,
+    queue_events: QueueEvents,
+) -> anyhow::Result<()> {
+    // Subscribed before the handshake, so that no event is missed between the first read of the state and the loop
+    let mut events = queue_events.subscribe();
+    let mut authenticated = None;
+    let mut socket = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+        authenticated = authenticate(request);
+        match authenticated {
+            Some(_) => Ok(response),
+            None => {
+                let mut error = ErrorResponse::new(Some("Request's signature is invalid".to_owned()));
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error)
+            }
+        }
+    })
+    .await?;
+    let (contributor, legacy) =
+        authenticated.ok_or_else(|| anyhow::anyhow!("Handshake completed without a contributor"))?;
+
+    // The handshake is answered synchronously, the time of the coordinator is only read afterwards, and only for the
+    // legacy signatures
+    if legacy && !legacy_signatures_accepted(&coordinator).await {
+        socket
+            .close(Some(CloseFrame {
+                code: CloseCode::Policy,
+                reason: "Request's signature is invalid".into(),
+            }))
+            .await?;
+        return Ok(());
+    }
+
+    let mut refresh = tokio::time::interval(NOTIFICATIONS_REFRESH);
+    let mut last_status = None;
+    let mut warned_deadline = None;
+    loop {
+        let mut notifications = vec![];
+        // The round of the current contributors is read from storage, off the async runtime
+        let read_lock = LOCK_MONITOR.read_owned(coordinator.clone()).await;
+        let participant = contributor.clone();
+        let (status, deadline) = tokio::task::spawn_blocking(move || {
+            (
+                rest::contributor_status(&read_lock, &participant),
+                read_lock.participant_timeout(&participant),
+            )
+        })
+        .await?;
+        if status == ContributorStatus::Round && last_status != Some(ContributorStatus::Round) {
+            notifications.push(Notification::ChallengeReady);
+        }
+        last_status = Some(status);
+        // The deadline moves with each heartbeat of the contributor, which is warned again before the new one
+        if let Some(deadline) = deadline {
+            if deadline - OffsetDateTime::now_utc() <= TIMEOUT_WARNING && warned_deadline != Some(deadline) {
+                notifications.push(Notification::TimeoutWarning { deadline });
+                warned_deadline = Some(deadline);
+            }
+        }
+        for notification in notifications {
+            socket
+                .send(Message::Text(serde_json::to_string(&notification)?))
+                .await?;
+        }
+
+        let event = tokio::select! {
+            event = events.recv() => event,
+            message = socket.next() => match message {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => return Err(e.into()),
+                // The pings of the contributor are answered on the next write
+                Some(Ok(_)) => continue,
+            },
+            _ = refresh.tick() => {
+                socket.send(Message::Ping(vec![])).await?;
+                continue;
+            }
+        };
+        let notification = match event {
+            Ok(CeremonyEvent::TurnStarted {
+                participant,
+                round_height,
+                chunk_id,
+            }) if participant == contributor => Notification::LockGranted { round_height, chunk_id },
+            Ok(CeremonyEvent::Verified {
+                participant,
+                round_height,
+                succeeded,
+            }) if participant == contributor => Notification::VerificationResult {
+                round_height,
+                succeeded,
+            },
+            // A connection lagging behind reads the latest state anyway
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        socket
+            .send(Message::Text(serde_json::to_string(&notification)?))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production, Signature};
+
+    #[test]
+    fn test_authenticate() {
+        let keypair = KeyPair::new();
+        let message = SignatureHeaders::new(keypair.pubkey(), None, None).message();
+        let signature = Production.sign(keypair.sigkey(), &message).unwrap();
+
+        let request = |signature: &str| {
+            Request::builder()
+                .uri("/")
+                .header(PUBKEY_HEADER, keypair.pubkey())
+                .header(SIGNATURE_HEADER, signature)
+                .body(())
+                .unwrap()
+        };
+        assert_eq!(
+            authenticate(&request(&signature)),
+            Some((Participant::new_contributor(keypair.pubkey()), false))
+        );
+
+        let legacy_message = SignatureHeaders::new(keypair.pubkey(), None, None).legacy_message();
+        let legacy_signature = Production.sign(keypair.sigkey(), &legacy_message).unwrap();
+        assert_eq!(
+            authenticate(&request(&legacy_signature)),
+            Some((Participant::new_contributor(keypair.pubkey()), true))
+        );
+
+        let other_signature = Production.sign(KeyPair::new().sigkey(), &message).unwrap();
+        assert_eq!(authenticate(&request(&other_signature)), None);
+
+        // Missing signature
+        let request = Request::builder()
+            .uri("/")
+            .header(PUBKEY_HEADER, keypair.pubkey())
+            .body(())
+            .unwrap();
+        assert_eq!(authenticate(&request), None);
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// The severity of an [`Announcement`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnnouncementLevel {
+    Info,
+    Warning,
+}
+
+/// A message of the coordinator to the participants, e.g. about the approaching end of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub level: AnnouncementLevel,
+    pub message: String,
+}
+
+impl Announcement {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            level: AnnouncementLevel::Info,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            level: AnnouncementLevel::Warning,
+            message: message.into(),
+        }
+    }
+}
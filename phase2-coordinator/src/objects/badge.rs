@@ -0,0 +1,174 @@
+use crate::authentication::Signature;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The statement of the coordinator on the participation of a public key in the ceremony, embedded by the contributors
+/// on their own sites.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipationBadge {
+    /// The name of the ceremony.
+    pub ceremony: String,
+    pub public_key: String,
+    pub contributed: bool,
+    /// The round of the contribution, if any.
+    pub round_height: Option<u64>,
+    /// Hex encoded hash of the contribution, if any.
+    pub contribution_hash: Option<String>,
+    pub issued_at: OffsetDateTime,
+}
+
+impl ParticipationBadge {
+    /// Returns the message signed by the coordinator: the json encoding of the badge, with the fields of the objects
+    /// sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the badge with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedParticipationBadge> {
+        let badge_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedParticipationBadge {
+            badge: self,
+            coordinator_public_key,
+            signature: badge_signature,
+        })
+    }
+}
+
+/// A [`ParticipationBadge`] signed by the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedParticipationBadge {
+    pub badge: ParticipationBadge,
+    /// The public key of the coordinator. Clients should check it against the key they expect rather than trust it.
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedParticipationBadge {
+    /// Returns `true` if the badge is signed by the coordinator with the given public key.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        match self.badge.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the badge as an SVG image. The signed badge is embedded in the metadata of the image as json, for the
+    /// readers of the image to verify it.
+    pub fn svg(&self) -> serde_json::Result<String> {
+        let status = match self.badge.round_height {
+            Some(round_height) if self.badge.contributed => format!("contributed in round {}", round_height),
+            _ => "not a contributor".to_string(),
+        };
+        let color = match self.badge.contributed {
+            true => "#2ea44f",
+            false => "#9f9f9f",
+        };
+        // The width of the text is approximated, as the fonts of the readers are unknown
+        let label_width = 10 + 7 * self.badge.ceremony.chars().count();
+        let status_width = 10 + 7 * status.chars().count();
+        let label = escape_xml(&self.badge.ceremony);
+        let status = escape_xml(&status);
+
+        Ok(format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" "#,
+                r#"aria-label="{label}: {status}">"#,
+                r#"<metadata>{metadata}</metadata>"#,
+                r#"<title>{label}: {status}</title>"#,
+                r##"<rect width="{label_width}" height="20" fill="#555"/>"##,
+                r#"<rect x="{label_width}" width="{status_width}" height="20" fill="{color}"/>"#,
+                r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,sans-serif" font-size="11">"##,
+                r#"<text x="{label_x}" y="14">{label}</text>"#,
+                r#"<text x="{status_x}" y="14">{status}</text>"#,
+                r#"</g></svg>"#,
+            ),
+            width = label_width + status_width,
+            label = label,
+            status = status,
+            metadata = escape_xml(&serde_json::to_string(self)?),
+            label_width = label_width,
+            status_width = status_width,
+            color = color,
+            label_x = label_width / 2,
+            status_x = label_width + status_width / 2,
+        ))
+    }
+}
+
+/// Escapes the given text for the content and the attributes of an XML document.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn test_sign_and_render_badge() {
+        let keypair = KeyPair::new();
+        let badge = ParticipationBadge {
+            ceremony: "Test <ceremony>".to_string(),
+            public_key: "contributor".to_string(),
+            contributed: true,
+            round_height: Some(3),
+            contribution_hash: Some("00ff".to_string()),
+            issued_at: OffsetDateTime::from_unix_timestamp(1672531200).unwrap(),
+        };
+        let signed = badge
+            .clone()
+            .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+            .unwrap();
+        assert!(signed.verify(&Production, keypair.pubkey()));
+        assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
+
+        let mut tampered = signed.clone();
+        tampered.badge.round_height = Some(4);
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+
+        let svg = signed.svg().unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("contributed in round 3"));
+        assert!(svg.contains("Test &lt;ceremony&gt;"));
+        assert!(!svg.contains("<ceremony>"));
+        assert!(svg.contains(&escape_xml(&signed.signature)));
+
+        let absent = ParticipationBadge {
+            contributed: false,
+            round_height: None,
+            contribution_hash: None,
+            ..badge
+        };
+        let svg = absent
+            .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+            .unwrap()
+            .svg()
+            .unwrap();
+        assert!(svg.contains("not a contributor"));
+    }
+}
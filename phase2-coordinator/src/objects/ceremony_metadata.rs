@@ -0,0 +1,288 @@
+use crate::{
+    authentication::Signature,
+    hashing::HashAlgorithm,
+    ip_retention::IpRetention,
+    objects::{ceremony_phase, CeremonyPhase, CircuitId, CircuitRegistry},
+    CoordinatorError,
+};
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use time::OffsetDateTime;
+use tracing::{error, info};
+
+/// Env variable with the path of the JSON file describing the identity of the ceremony, its genesis config.
+pub const CEREMONY_METADATA_PATH: &str = "CEREMONY_METADATA_PATH";
+
+/// A contact of an organizer of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: String,
+    pub email: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A link to a resource about the ceremony, e.g. its announcement or the instructions for the contributors.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub title: String,
+    pub url: String,
+}
+
+/// The description of a circuit of the ceremony, from the [`CircuitRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitDescription {
+    #[serde(flatten)]
+    pub id: CircuitId,
+    pub description: String,
+}
+
+/// The identity of the ceremony, displayed by the clients.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub organizers: Vec<Contact>,
+    /// The circuits of the ceremony, filled from the circuit registry of the coordinator.
+    #[serde(default)]
+    pub circuits: Vec<CircuitDescription>,
+    /// The start of the ceremony, the `CEREMONY_START_TIMESTAMP` env variable if missing from the genesis config.
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub start: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::timestamp::option")]
+    pub end: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// The schedule of the phases of the ceremony, sorted by start time, each one with its own rules to join the queue.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<CeremonyPhase>,
+    /// Whether the ceremony is a rehearsal, whose parameters are thrown away, set by the coordinator.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rehearsal: bool,
+    /// The hash function of the contribution files, the one of the environment of the coordinator if missing from the
+    /// genesis config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_algorithm: Option<HashAlgorithm>,
+    /// What is kept of the IPs of the contributors who have finished, set by the coordinator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_retention: Option<IpRetention>,
+}
+
+impl CeremonyMetadata {
+    /// Loads the metadata from the file set in the [`CEREMONY_METADATA_PATH`] env variable,
+    /// or returns the default metadata of the ceremony if the variable is not set.
+    pub fn load(circuit_registry: &CircuitRegistry) -> Result<Self, CoordinatorError> {
+        let mut metadata = match std::env::var(CEREMONY_METADATA_PATH) {
+            Ok(path) => Self::load_from_file(path)?,
+            Err(_) => Self::default(),
+        };
+
+        if metadata.start.is_none() {
+            metadata.start = std::env::var("CEREMONY_START_TIMESTAMP")
+                .ok()
+                .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok());
+        }
+        metadata.circuits = circuit_registry
+            .circuits()
+            .iter()
+            .map(|circuit| CircuitDescription {
+                id: circuit.id.clone(),
+                description: circuit.description.clone(),
+            })
+            .collect();
+
+        Ok(metadata)
+    }
+
+    /// Marks the ceremony as a rehearsal, in its name as well for the clients unaware of rehearsals.
+    pub fn mark_rehearsal(&mut self) {
+        if !self.rehearsal {
+            self.rehearsal = true;
+            self.name = format!("[REHEARSAL] {}", self.name);
+        }
+    }
+
+    /// Records the hash function of the contribution files, which must match the one of the genesis config if any.
+    pub fn record_hash_algorithm(&mut self, hash_algorithm: HashAlgorithm) -> Result<(), CoordinatorError> {
+        match self.hash_algorithm {
+            Some(declared) if declared != hash_algorithm => {
+                error!(
+                    "The genesis config declares the hash algorithm {} but the coordinator uses {}",
+                    declared, hash_algorithm
+                );
+                Err(CoordinatorError::HashAlgorithmMismatch)
+            }
+            _ => {
+                self.hash_algorithm = Some(hash_algorithm);
+                Ok(())
+            }
+        }
+    }
+
+    /// Loads the metadata from a JSON file.
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, CoordinatorError> {
+        info!("Loading ceremony metadata from {:?}", path.as_ref());
+
+        let metadata: Self = serde_json::from_slice(&fs::read(path.as_ref())?)?;
+        ceremony_phase::validate_schedule(&metadata.phases).map_err(CoordinatorError::CeremonyPhasesInvalid)?;
+
+        Ok(metadata)
+    }
+
+    /// Returns the message signed by the coordinator: the json encoding of the metadata, with the fields of the objects
+    /// sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the metadata with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedCeremonyMetadata> {
+        let metadata_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedCeremonyMetadata {
+            metadata: self,
+            coordinator_public_key,
+            signature: metadata_signature,
+        })
+    }
+}
+
+impl Default for CeremonyMetadata {
+    /// The Namada ceremony.
+    fn default() -> Self {
+        Self {
+            name: "Namada Trusted Setup Ceremony".to_string(),
+            description: "Generation of the parameters of the MASP circuits of Namada".to_string(),
+            organizers: vec![Contact {
+                name: "Heliax".to_string(),
+                email: None,
+                url: Some("https://heliax.dev".to_string()),
+            }],
+            circuits: vec![],
+            start: None,
+            end: None,
+            links: vec![Link {
+                title: "Namada".to_string(),
+                url: "https://namada.net".to_string(),
+            }],
+            phases: vec![],
+            rehearsal: false,
+            hash_algorithm: None,
+            ip_retention: None,
+        }
+    }
+}
+
+/// A [`CeremonyMetadata`] signed by the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedCeremonyMetadata {
+    pub metadata: CeremonyMetadata,
+    /// The public key of the coordinator. Clients should check it against the key they expect rather than trust it.
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedCeremonyMetadata {
+    /// Returns `true` if the metadata is signed by the coordinator with the given public key.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        match self.metadata.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn test_load_and_sign_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ceremony.json");
+        let config = serde_json::json!({
+            "name": "Test ceremony",
+            "description": "A test",
+            "start": 1672531200,
+            "links": [{"title": "Docs", "url": "https://example.com"}],
+        });
+        fs::write(&path, config.to_string()).unwrap();
+
+        let metadata = CeremonyMetadata::load_from_file(&path).unwrap();
+        assert_eq!(metadata.name, "Test ceremony");
+        assert_eq!(metadata.start.unwrap().unix_timestamp(), 1672531200);
+        assert!(metadata.end.is_none());
+        assert!(metadata.organizers.is_empty());
+        assert_eq!(metadata.links.len(), 1);
+        assert!(metadata.phases.is_empty());
+
+        let keypair = KeyPair::new();
+        let signed = metadata
+            .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+            .unwrap();
+        assert!(signed.verify(&Production, keypair.pubkey()));
+
+        // Another key, or tampered metadata, is rejected
+        assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
+        let mut tampered = signed.clone();
+        tampered.metadata.name = "Another ceremony".to_string();
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+
+        // The mark of a rehearsal is signed as well
+        let mut rehearsal = signed.metadata.clone();
+        rehearsal.mark_rehearsal();
+        rehearsal.mark_rehearsal();
+        assert_eq!(rehearsal.name, "[REHEARSAL] Test ceremony");
+        let mut tampered = rehearsal
+            .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+            .unwrap();
+        assert!(tampered.verify(&Production, keypair.pubkey()));
+        tampered.metadata.rehearsal = false;
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+
+        // The phases of the schedule must be sorted by start time
+        let config = serde_json::json!({
+            "name": "Test ceremony",
+            "description": "A test",
+            "phases": [
+                {"name": "public", "start": 1673136000},
+                {"name": "cleanup", "start": 1672531200, "queue_policy": "closed"},
+            ],
+        });
+        fs::write(&path, config.to_string()).unwrap();
+        assert!(matches!(
+            CeremonyMetadata::load_from_file(&path),
+            Err(CoordinatorError::CeremonyPhasesInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_record_hash_algorithm() {
+        let mut metadata = CeremonyMetadata::default();
+        metadata.record_hash_algorithm(HashAlgorithm::Sha256).unwrap();
+        assert_eq!(metadata.hash_algorithm, Some(HashAlgorithm::Sha256));
+
+        // The coordinator can't use another function than the one of the genesis config
+        let mut metadata: CeremonyMetadata = serde_json::from_value(serde_json::json!({
+            "name": "Test ceremony",
+            "description": "A test",
+            "hash_algorithm": "blake2b-512",
+        }))
+        .unwrap();
+        assert!(metadata.record_hash_algorithm(HashAlgorithm::Sha256).is_err());
+        metadata.record_hash_algorithm(HashAlgorithm::Blake2b512).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    environment::{CurveKind, ProvingSystem},
+    hashing::HashAlgorithm,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Identifier of the BLAKE2b hash function with 512 bits output.
+pub const BLAKE2B_512: &str = "blake2b-512";
+/// Identifier of the SHA-256 hash function.
+pub const SHA_256: &str = "sha-256";
+
+/// The hash algorithms used by the ceremony, by purpose.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashAlgorithms {
+    /// The hash of the challenges and the responses, written at the head of the next challenge and signed with the
+    /// contributions, see [`HashAlgorithm`].
+    pub contribution: String,
+    /// The digest of the body of the signed requests.
+    pub request_digest: String,
+    /// The hash chain of the transcript of the ceremony.
+    pub transcript: String,
+}
+
+impl Default for HashAlgorithms {
+    fn default() -> Self {
+        Self {
+            contribution: HashAlgorithm::default().to_string(),
+            request_digest: SHA_256.to_string(),
+            transcript: BLAKE2B_512.to_string(),
+        }
+    }
+}
+
+/// The parameters of the ceremony, from which clients configure themselves instead of hardcoding them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CeremonyParameters {
+    pub curve: CurveKind,
+    pub proving_system: ProvingSystem,
+    pub power: usize,
+    pub batch_size: usize,
+    pub chunk_size: usize,
+    pub number_of_chunks: u64,
+    /// The size in bytes of the initial challenge of the ceremony.
+    pub base_file_size: u64,
+    /// The number of bytes added to the parameters by each contribution.
+    pub contribution_size_increase: u64,
+    /// The height of the current round, to which the sizes below apply.
+    pub round_height: u64,
+    /// The size in bytes of the challenge of the current round.
+    pub challenge_size: u64,
+    /// The size in bytes of the response to the challenge of the current round.
+    pub response_size: u64,
+    /// The size in bytes of the signature uploaded with the response.
+    pub response_signature_size: u64,
+    pub hash_algorithms: HashAlgorithms,
+}
+
+impl CeremonyParameters {
+    /// Returns the differences between these parameters and the ones a client is built for, given the base size of
+    /// the parameters and their increase per contribution expected by the client.
+    pub fn mismatches(&self, base_file_size: u64, contribution_size_increase: u64) -> Vec<String> {
+        let mut mismatches = vec![];
+
+        if self.base_file_size != base_file_size {
+            mismatches.push(format!(
+                "the initial challenge has {} bytes instead of {}",
+                self.base_file_size, base_file_size
+            ));
+        }
+        if self.contribution_size_increase != contribution_size_increase {
+            mismatches.push(format!(
+                "each contribution adds {} bytes instead of {}",
+                self.contribution_size_increase, contribution_size_increase
+            ));
+        }
+        if self.hash_algorithms != HashAlgorithms::default() {
+            mismatches.push(format!(
+                "the hash algorithms are {:?} instead of {:?}",
+                self.hash_algorithms,
+                HashAlgorithms::default()
+            ));
+        }
+
+        mismatches
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Who can join the queue of contributors during a [`CeremonyPhase`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuePolicy {
+    /// Anyone holding a token.
+    Open,
+    /// Only the public keys of the allowlist of the phase.
+    Allowlist,
+    /// Nobody, e.g. while preparing the random beacon which concludes the ceremony.
+    Closed,
+}
+
+impl Default for QueuePolicy {
+    fn default() -> Self {
+        QueuePolicy::Open
+    }
+}
+
+/// The timeouts of the participants during a [`CeremonyPhase`], in seconds, overriding the ones of the environment of
+/// the coordinator when set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhaseTimeouts {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contributor_seen: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub participant_lock: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_seen: Option<u64>,
+}
+
+/// A phase of the schedule of the ceremony, with its own rules to join the queue and its own timeouts. A phase lasts
+/// from its start until the start of the next phase of the schedule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CeremonyPhase {
+    pub name: String,
+    #[serde(with = "time::serde::timestamp")]
+    pub start: OffsetDateTime,
+    #[serde(default)]
+    pub queue_policy: QueuePolicy,
+    /// The public keys allowed to join the queue with [`QueuePolicy::Allowlist`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowlist: Vec<String>,
+    /// The base difficulty of the proof-of-work required to join the queue, overriding the one of the coordinator when
+    /// set. A difficulty of zero waives the proof-of-work.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pow_difficulty: Option<u8>,
+    /// Whether an identity is required to join the queue, on top of the requirement of the coordinator.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub identity_required: bool,
+    #[serde(default)]
+    pub timeouts: PhaseTimeouts,
+    /// The message announced to the participants during the phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub announcement: Option<String>,
+}
+
+impl CeremonyPhase {
+    /// Returns `true` if the participant with the given public key can join the queue during the phase.
+    pub fn admits(&self, public_key: &str) -> bool {
+        match self.queue_policy {
+            QueuePolicy::Open => true,
+            QueuePolicy::Allowlist => self.allowlist.iter().any(|allowed| allowed == public_key),
+            QueuePolicy::Closed => false,
+        }
+    }
+}
+
+/// Checks that the phases of the schedule have distinct names and are sorted by start time, and that the allowlists
+/// are set for the phases restricted to them.
+pub fn validate_schedule(phases: &[CeremonyPhase]) -> Result<(), String> {
+    for (index, phase) in phases.iter().enumerate() {
+        if phase.name.is_empty() {
+            return Err(format!("phase {} has no name", index));
+        }
+        if phases[..index].iter().any(|previous| previous.name == phase.name) {
+            return Err(format!("phase {} is declared twice", phase.name));
+        }
+        if index > 0 && phases[index - 1].start >= phase.start {
+            return Err(format!("phase {} doesn't start after the previous one", phase.name));
+        }
+        if phase.queue_policy == QueuePolicy::Allowlist && phase.allowlist.is_empty() {
+            return Err(format!("phase {} is restricted to an empty allowlist", phase.name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the index of the phase of the schedule active at the given time, the last one started, or [`None`] before
+/// the start of the first phase.
+pub fn active_phase(phases: &[CeremonyPhase], now: OffsetDateTime) -> Option<usize> {
+    phases.iter().rposition(|phase| phase.start <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn phase(name: &str, start: OffsetDateTime, queue_policy: QueuePolicy) -> CeremonyPhase {
+        CeremonyPhase {
+            name: name.to_string(),
+            start,
+            queue_policy,
+            allowlist: vec![],
+            pow_difficulty: None,
+            identity_required: false,
+            timeouts: PhaseTimeouts::default(),
+            announcement: None,
+        }
+    }
+
+    #[test]
+    fn test_schedule() {
+        let start = OffsetDateTime::from_unix_timestamp(1672531200).unwrap();
+        let mut phases = vec![
+            phase("vip", start, QueuePolicy::Allowlist),
+            phase("public", start + Duration::weeks(1), QueuePolicy::Open),
+            phase("cleanup", start + Duration::weeks(4), QueuePolicy::Closed),
+        ];
+        assert!(validate_schedule(&phases).is_err());
+        phases[0].allowlist = vec!["vip".to_string()];
+        validate_schedule(&phases).unwrap();
+
+        assert_eq!(active_phase(&phases, start - Duration::seconds(1)), None);
+        assert_eq!(active_phase(&phases, start), Some(0));
+        assert_eq!(active_phase(&phases, start + Duration::weeks(2)), Some(1));
+        assert_eq!(active_phase(&phases, start + Duration::weeks(10)), Some(2));
+
+        assert!(phases[0].admits("vip") && !phases[0].admits("other"));
+        assert!(phases[1].admits("other"));
+        assert!(!phases[2].admits("vip"));
+
+        phases.swap(1, 2);
+        assert!(validate_schedule(&phases).is_err());
+        phases.swap(1, 2);
+        phases[2].name = "vip".to_string();
+        assert!(validate_schedule(&phases).is_err());
+
+        // Timestamps and snake case policies, as written in the genesis config
+        let json = serde_json::json!({
+            "name": "public",
+            "start": 1672531200,
+            "queue_policy": "open",
+            "pow_difficulty": 16,
+            "timeouts": {"participant_lock": 3600},
+        });
+        let parsed: CeremonyPhase = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.start, start);
+        assert_eq!(parsed.timeouts.participant_lock, Some(3600));
+        assert!(!parsed.identity_required);
+    }
+}
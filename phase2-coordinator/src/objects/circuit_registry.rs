@@ -0,0 +1,199 @@
+use crate::{objects::Task, CoordinatorError};
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt, path::Path};
+use tracing::{error, info};
+
+/// Env variable with the path of the JSON file describing the circuits of the ceremony.
+pub const CIRCUIT_REGISTRY_PATH: &str = "CIRCUIT_REGISTRY_PATH";
+
+/// The name and version identifying a circuit of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CircuitId {
+    pub name: String,
+    pub version: String,
+}
+
+impl CircuitId {
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CircuitId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+/// A circuit of the ceremony, with its constraint metadata and the location of its phase 2 parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitEntry {
+    #[serde(flatten)]
+    pub id: CircuitId,
+    pub description: String,
+    /// The number of constraints of the circuit, if known.
+    pub constraints: Option<u64>,
+    /// The chunk whose contribution files contain the phase 2 parameters of the circuit.
+    pub chunk_id: u64,
+    /// The position of the circuit parameters inside the contribution files of the chunk.
+    pub position: u64,
+}
+
+/// The registry of the circuits targeted by the contributions of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitRegistry {
+    circuits: Vec<CircuitEntry>,
+}
+
+impl CircuitRegistry {
+    /// Creates a new registry, checking that circuit ids and parameter locations are unique.
+    pub fn new(mut circuits: Vec<CircuitEntry>) -> Result<Self, CoordinatorError> {
+        let mut ids = HashSet::new();
+        let mut locations = HashSet::new();
+
+        for circuit in &circuits {
+            if !ids.insert(&circuit.id) || !locations.insert((circuit.chunk_id, circuit.position)) {
+                error!("Circuit {} is registered more than once", circuit.id);
+                return Err(CoordinatorError::CircuitRegistryInvalid);
+            }
+        }
+
+        circuits.sort_by_key(|c| (c.chunk_id, c.position));
+
+        Ok(Self { circuits })
+    }
+
+    /// Loads the registry from the file set in the [`CIRCUIT_REGISTRY_PATH`] env variable,
+    /// or returns the default registry of the ceremony if the variable is not set.
+    pub fn load() -> Result<Self, CoordinatorError> {
+        match std::env::var(CIRCUIT_REGISTRY_PATH) {
+            Ok(path) => Self::load_from_file(path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the registry from a JSON file.
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, CoordinatorError> {
+        info!("Loading circuit registry from {:?}", path.as_ref());
+        let circuits: Vec<CircuitEntry> = serde_json::from_slice(&fs::read(path.as_ref())?)?;
+
+        Self::new(circuits)
+    }
+
+    /// Checks that every circuit refers to an existing chunk.
+    pub fn check_chunks(&self, number_of_chunks: u64) -> Result<(), CoordinatorError> {
+        match self.circuits.iter().find(|c| c.chunk_id >= number_of_chunks) {
+            Some(circuit) => {
+                error!("Circuit {} refers to missing chunk {}", circuit.id, circuit.chunk_id);
+                Err(CoordinatorError::CircuitRegistryInvalid)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns all the registered circuits, ordered by chunk and position.
+    pub fn circuits(&self) -> &[CircuitEntry] {
+        &self.circuits
+    }
+
+    /// Returns the circuit with the given name and version, if registered.
+    pub fn get(&self, name: &str, version: &str) -> Option<&CircuitEntry> {
+        self.circuits
+            .iter()
+            .find(|c| c.id.name == name && c.id.version == version)
+    }
+
+    /// Returns the ids of the circuits whose parameters are contained in the given chunk.
+    pub fn circuits_for_chunk(&self, chunk_id: u64) -> Vec<CircuitId> {
+        self.circuits
+            .iter()
+            .filter(|c| c.chunk_id == chunk_id)
+            .map(|c| c.id.clone())
+            .collect()
+    }
+
+    /// Returns the ids of the circuits targeted by the given task.
+    pub fn circuits_for_task(&self, task: &Task) -> Vec<CircuitId> {
+        self.circuits_for_chunk(task.chunk_id())
+    }
+}
+
+impl Default for CircuitRegistry {
+    /// The MASP circuits of the Namada ceremony, all contained in a single chunk.
+    #[cfg(not(debug_assertions))]
+    fn default() -> Self {
+        let masp = |name: &str, description: &str, position: u64| CircuitEntry {
+            id: CircuitId::new(name, "1"),
+            description: description.to_string(),
+            constraints: None,
+            chunk_id: 0,
+            position,
+        };
+
+        Self {
+            circuits: vec![
+                masp("masp-spend", "MASP Spend circuit", 0),
+                masp("masp-output", "MASP Output circuit", 1),
+                masp("masp-convert", "MASP Convert circuit", 2),
+            ],
+        }
+    }
+
+    /// The test circuit used in place of the MASP circuits in debug builds.
+    #[cfg(debug_assertions)]
+    fn default() -> Self {
+        Self {
+            circuits: vec![CircuitEntry {
+                id: CircuitId::new("test", "1"),
+                description: "Test circuit x = x^2".to_string(),
+                constraints: Some(1),
+                chunk_id: 0,
+                position: 0,
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, chunk_id: u64, position: u64) -> CircuitEntry {
+        CircuitEntry {
+            id: CircuitId::new(name, "1"),
+            description: String::new(),
+            constraints: None,
+            chunk_id,
+            position,
+        }
+    }
+
+    #[test]
+    fn test_circuits_for_chunk() {
+        let registry = CircuitRegistry::new(vec![entry("b", 0, 1), entry("a", 0, 0), entry("c", 1, 0)]).unwrap();
+
+        assert_eq!(
+            registry.circuits_for_chunk(0),
+            vec![CircuitId::new("a", "1"), CircuitId::new("b", "1")]
+        );
+        assert_eq!(
+            registry.circuits_for_task(&Task::new(1, 3)),
+            vec![CircuitId::new("c", "1")]
+        );
+        assert!(registry.get("c", "1").is_some());
+        assert!(registry.get("c", "2").is_none());
+        assert!(registry.check_chunks(2).is_ok());
+        assert!(registry.check_chunks(1).is_err());
+    }
+
+    #[test]
+    fn test_duplicated_circuits() {
+        assert!(CircuitRegistry::new(vec![entry("a", 0, 0), entry("a", 0, 1)]).is_err());
+        assert!(CircuitRegistry::new(vec![entry("a", 0, 0), entry("b", 0, 0)]).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use time::OffsetDateTime;
+
+/// The domain separator of the hash chain of the proofs of computation time.
+const COMPUTATION_PROOF_DOMAIN: &[u8] = b"namada-computation-proof";
+
+/// The number of iterations of the hash between two checkpoints emitted by the contributor client, about a second of
+/// computation on a single core.
+pub const CHECKPOINT_ITERATIONS: u64 = 1 << 22;
+/// The smallest number of iterations between two checkpoints accepted by the coordinator.
+pub const MIN_CHECKPOINT_ITERATIONS: u64 = 1 << 16;
+/// The largest number of checkpoints of a proof, which keeps its json encoding under the default limit of the requests.
+pub const MAX_CHECKPOINTS: usize = 8192;
+/// The number of segments between checkpoints, on top of the first one, checked by the coordinator on receipt of a
+/// proof. Auditors can check every segment with [`ComputationProof::verify`].
+pub const SAMPLED_SEGMENTS: usize = 8;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ComputationProofError {
+    #[error("The proof starts from the challenge {0}, not from the locked challenge")]
+    ChallengeMismatch(String),
+    #[error("The proof has no checkpoint")]
+    CheckpointsMissing,
+    #[error("The elapsed time of checkpoint {0} is lower than the one of the previous checkpoint")]
+    ElapsedTimeDecreasing(usize),
+    #[error("Invalid hex encoding of checkpoint {0}")]
+    InvalidCheckpoint(usize),
+    #[error("The proof has {0} iterations between checkpoints, expected between {1} and {2}")]
+    IterationsOutOfRange(u64, u64, u64),
+    #[error("Checkpoint {0} doesn't follow from the previous checkpoint")]
+    SegmentMismatch(usize),
+    #[error("The proof has {0} checkpoints, more than the {1} allowed")]
+    TooManyCheckpoints(usize, usize),
+}
+
+/// Returns the first hash of the chain of a proof of computation time: the hash of the challenge of the contribution,
+/// so that the chain can't be computed before the challenge is known.
+pub fn chain_start(challenge_hash: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMPUTATION_PROOF_DOMAIN);
+    hasher.update(challenge_hash);
+    hasher.finalize().into()
+}
+
+/// Hashes the given hash the given number of times in sequence, an iteration taking as input the output of the
+/// previous one.
+pub fn iterate_hash(mut hash: [u8; 32], iterations: u64) -> [u8; 32] {
+    for _ in 0..iterations {
+        hash = Sha256::digest(hash).into();
+    }
+    hash
+}
+
+/// A checkpoint of the hash chain emitted by the contributor client during the computation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComputationCheckpoint {
+    /// Hex encoded hash of the chain at the checkpoint.
+    pub hash: String,
+    /// Milliseconds elapsed since the start of the chain when the checkpoint was reached, as measured by the client.
+    pub elapsed_ms: u64,
+}
+
+///
+/// A proof of computation time: an iterated hash, started from the hash of the challenge when the contributor client
+/// starts the computation of the contribution and run in sequence until it ends, with checkpoints at a regular number
+/// of iterations.
+///
+/// Since each iteration depends on the previous one, the chain takes time proportional to its length to compute and
+/// can't be started before the challenge is downloaded, while the segments between checkpoints can be checked in
+/// parallel. The length of the chain is a lower bound on the time spent between the download of the challenge and
+/// the upload of the contribution, which tells genuinely computed contributions from precomputed or outsourced ones
+/// sent back right away.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComputationProof {
+    /// Hex encoded hash of the challenge of the contribution.
+    pub challenge_hash: String,
+    /// The number of iterations of the hash between two checkpoints.
+    pub iterations_per_checkpoint: u64,
+    pub checkpoints: Vec<ComputationCheckpoint>,
+}
+
+impl ComputationProof {
+    /// Returns the number of iterations of the chain.
+    pub fn iterations(&self) -> u64 {
+        self.iterations_per_checkpoint * self.checkpoints.len() as u64
+    }
+
+    /// Returns the milliseconds the chain took to compute, as measured by the client.
+    pub fn elapsed_ms(&self) -> u64 {
+        self.checkpoints.last().map_or(0, |checkpoint| checkpoint.elapsed_ms)
+    }
+
+    /// Checks the form of the proof and returns the decoded hashes of the chain, from its start.
+    fn chain(&self, challenge_hash: &str) -> Result<Vec<[u8; 32]>, ComputationProofError> {
+        if self.challenge_hash != challenge_hash {
+            return Err(ComputationProofError::ChallengeMismatch(self.challenge_hash.clone()));
+        }
+        if self.checkpoints.is_empty() {
+            return Err(ComputationProofError::CheckpointsMissing);
+        }
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            return Err(ComputationProofError::TooManyCheckpoints(
+                self.checkpoints.len(),
+                MAX_CHECKPOINTS,
+            ));
+        }
+        if !(MIN_CHECKPOINT_ITERATIONS..=CHECKPOINT_ITERATIONS).contains(&self.iterations_per_checkpoint) {
+            return Err(ComputationProofError::IterationsOutOfRange(
+                self.iterations_per_checkpoint,
+                MIN_CHECKPOINT_ITERATIONS,
+                CHECKPOINT_ITERATIONS,
+            ));
+        }
+
+        let challenge_hash = hex::decode(challenge_hash)
+            .map_err(|_| ComputationProofError::ChallengeMismatch(self.challenge_hash.clone()))?;
+        let mut chain = vec![chain_start(&challenge_hash)];
+        let mut previous_elapsed_ms = 0;
+        for (index, checkpoint) in self.checkpoints.iter().enumerate() {
+            if checkpoint.elapsed_ms < previous_elapsed_ms {
+                return Err(ComputationProofError::ElapsedTimeDecreasing(index));
+            }
+            previous_elapsed_ms = checkpoint.elapsed_ms;
+
+            let mut hash = [0u8; 32];
+            hex::decode_to_slice(&checkpoint.hash, &mut hash)
+                .map_err(|_| ComputationProofError::InvalidCheckpoint(index))?;
+            chain.push(hash);
+        }
+
+        Ok(chain)
+    }
+
+    /// Checks the given segments of the chain, the segment `i` ending at the checkpoint `i`, in parallel.
+    fn verify_segments(&self, chain: &[[u8; 32]], segments: &[usize]) -> Result<(), ComputationProofError> {
+        let mismatch = segments
+            .par_iter()
+            .find_first(|&&index| iterate_hash(chain[index], self.iterations_per_checkpoint) != chain[index + 1]);
+
+        match mismatch {
+            Some(&index) => Err(ComputationProofError::SegmentMismatch(index)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks every segment of the chain of the proof, started from the given hex encoded hash of the challenge.
+    /// Returns the number of iterations of the chain.
+    pub fn verify(&self, challenge_hash: &str) -> Result<u64, ComputationProofError> {
+        let chain = self.chain(challenge_hash)?;
+        let segments: Vec<usize> = (0..self.checkpoints.len()).collect();
+        self.verify_segments(&chain, &segments)?;
+
+        Ok(self.iterations())
+    }
+
+    /// Checks the first segment of the chain of the proof, which binds it to the given hex encoded hash of the
+    /// challenge, and [`SAMPLED_SEGMENTS`] other segments drawn at random, so that a chain with a share of forged
+    /// segments is caught with a probability growing with that share. Returns the number of iterations of the chain.
+    pub fn verify_sample<R: Rng>(&self, challenge_hash: &str, rng: &mut R) -> Result<u64, ComputationProofError> {
+        let chain = self.chain(challenge_hash)?;
+        let segments = match self.checkpoints.len() - 1 {
+            0 => vec![0],
+            others => std::iter::once(0)
+                .chain((0..SAMPLED_SEGMENTS).map(|_| rng.gen_range(1..=others)))
+                .collect(),
+        };
+        self.verify_segments(&chain, &segments)?;
+
+        Ok(self.iterations())
+    }
+}
+
+/// The proof of computation time of a contribution, received by the coordinator with the upload of the contribution
+/// and published in the manifest of the round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputationProofRecord {
+    pub round_height: u64,
+    /// The public key of the contributor.
+    pub contributor: String,
+    pub proof: ComputationProof,
+    pub received_at: OffsetDateTime,
+}
+
+/// What makes the contribution of a round stand out in the [`ComputationStats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComputationAnomaly {
+    /// The contributor didn't submit a proof of computation time.
+    MissingProof,
+    /// The contributor spent in the round less than a fraction of the median time of the contributions.
+    FastRound,
+    /// The chain of the proof is shorter than a fraction of the median length of the proofs.
+    ShortProof,
+}
+
+/// The time spent computing a contribution, as measured by the coordinator and as proved by the contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContributionComputation {
+    pub round_height: u64,
+    /// The public key of the contributor.
+    pub contributor: String,
+    /// The seconds between the start of the contribution and its end, measured by the coordinator.
+    pub seconds: u64,
+    /// The number of iterations of the proof of computation time, if any.
+    pub iterations: Option<u64>,
+    /// The milliseconds the proof took to compute, as measured by the client.
+    pub proof_elapsed_ms: Option<u64>,
+    pub anomalies: Vec<ComputationAnomaly>,
+}
+
+/// The statistics of the time spent computing the contributions of the ceremony, flagging the anomalously fast ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputationStats {
+    /// The median of the seconds spent in a round by the contributors.
+    pub median_seconds: Option<u64>,
+    /// The median of the number of iterations of the proofs of computation time.
+    pub median_iterations: Option<u64>,
+    pub contributions: Vec<ContributionComputation>,
+}
+
+impl ComputationStats {
+    /// The factor below the medians under which a contribution is anomalously fast.
+    pub const ANOMALY_FACTOR: u64 = 4;
+
+    /// Computes the statistics of the given contributions, whose anomalies are flagged against the medians.
+    pub fn new(mut contributions: Vec<ContributionComputation>) -> Self {
+        let median_seconds = median(contributions.iter().map(|contribution| contribution.seconds).collect());
+        let median_iterations = median(
+            contributions
+                .iter()
+                .filter_map(|contribution| contribution.iterations)
+                .collect(),
+        );
+
+        for contribution in contributions.iter_mut() {
+            contribution.anomalies.clear();
+            if contribution.iterations.is_none() {
+                contribution.anomalies.push(ComputationAnomaly::MissingProof);
+            }
+            if matches!(median_seconds, Some(median) if contribution.seconds * Self::ANOMALY_FACTOR < median) {
+                contribution.anomalies.push(ComputationAnomaly::FastRound);
+            }
+            if let (Some(iterations), Some(median)) = (contribution.iterations, median_iterations) {
+                if iterations * Self::ANOMALY_FACTOR < median {
+                    contribution.anomalies.push(ComputationAnomaly::ShortProof);
+                }
+            }
+        }
+        contributions.sort_by_key(|contribution| contribution.round_height);
+
+        Self {
+            median_seconds,
+            median_iterations,
+            contributions,
+        }
+    }
+}
+
+/// Returns the median of the given values, the lower one of the two middle values if their number is even.
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    values.sort_unstable();
+    values.get(values.len().checked_sub(1)? / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(challenge_hash: &[u8], checkpoints: usize) -> ComputationProof {
+        let mut hash = chain_start(challenge_hash);
+        ComputationProof {
+            challenge_hash: hex::encode(challenge_hash),
+            iterations_per_checkpoint: MIN_CHECKPOINT_ITERATIONS,
+            checkpoints: (0..checkpoints as u64)
+                .map(|index| {
+                    hash = iterate_hash(hash, MIN_CHECKPOINT_ITERATIONS);
+                    ComputationCheckpoint {
+                        hash: hex::encode(hash),
+                        elapsed_ms: index * 10,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn computation(round_height: u64, seconds: u64, iterations: Option<u64>) -> ContributionComputation {
+        ContributionComputation {
+            round_height,
+            contributor: round_height.to_string(),
+            seconds,
+            iterations,
+            proof_elapsed_ms: None,
+            anomalies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_computation_proof() {
+        let challenge_hash = [1u8; 64];
+        let mut proof = proof(&challenge_hash, 12);
+        assert_eq!(
+            proof.verify(&hex::encode(challenge_hash)),
+            Ok(12 * MIN_CHECKPOINT_ITERATIONS)
+        );
+        assert_eq!(
+            proof.verify_sample(&hex::encode(challenge_hash), &mut rand::rngs::OsRng),
+            Ok(12 * MIN_CHECKPOINT_ITERATIONS)
+        );
+        assert_eq!(proof.elapsed_ms(), 110);
+
+        // The chain can't be reused for another challenge
+        assert_eq!(
+            proof.verify(&hex::encode([2u8; 64])),
+            Err(ComputationProofError::ChallengeMismatch(hex::encode(challenge_hash)))
+        );
+        let mut other = proof.clone();
+        other.challenge_hash = hex::encode([2u8; 64]);
+        assert_eq!(
+            other.verify(&hex::encode([2u8; 64])),
+            Err(ComputationProofError::SegmentMismatch(0))
+        );
+
+        proof.checkpoints[7].hash = hex::encode([0u8; 32]);
+        assert_eq!(
+            proof.verify(&hex::encode(challenge_hash)),
+            Err(ComputationProofError::SegmentMismatch(7))
+        );
+        proof.checkpoints[7].elapsed_ms = 0;
+        assert_eq!(
+            proof.verify(&hex::encode(challenge_hash)),
+            Err(ComputationProofError::ElapsedTimeDecreasing(7))
+        );
+        proof.iterations_per_checkpoint = 1;
+        assert_eq!(
+            proof.verify(&hex::encode(challenge_hash)),
+            Err(ComputationProofError::IterationsOutOfRange(
+                1,
+                MIN_CHECKPOINT_ITERATIONS,
+                CHECKPOINT_ITERATIONS
+            ))
+        );
+    }
+
+    #[test]
+    fn test_computation_stats() {
+        let stats = ComputationStats::new(vec![
+            computation(3, 600, Some(4000)),
+            computation(1, 500, Some(5000)),
+            computation(2, 100, Some(600)),
+            computation(4, 700, None),
+        ]);
+
+        assert_eq!(stats.median_seconds, Some(500));
+        assert_eq!(stats.median_iterations, Some(4000));
+        let anomalies: Vec<(u64, Vec<ComputationAnomaly>)> = stats
+            .contributions
+            .iter()
+            .map(|contribution| (contribution.round_height, contribution.anomalies.clone()))
+            .collect();
+        assert_eq!(anomalies, vec![
+            (1, vec![]),
+            (2, vec![ComputationAnomaly::FastRound, ComputationAnomaly::ShortProof]),
+            (3, vec![]),
+            (4, vec![ComputationAnomaly::MissingProof]),
+        ]);
+        assert_eq!(ComputationStats::new(Vec::new()), ComputationStats::default());
+    }
+}
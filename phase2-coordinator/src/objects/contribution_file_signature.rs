@@ -1,9 +1,36 @@
-#[cfg(any(test, feature = "operator"))]
-use crate::coordinator::CoordinatorError;
+use crate::{
+    authentication::{post_quantum::PostQuantumScheme, ssh::SshSignature},
+    coordinator::CoordinatorError,
+};
 
 use serde::{Deserialize, Serialize};
 use serde_diff::SerdeDiff;
 
+/// Length, in hex characters, of the hashes of the contribution files.
+const HASH_HEX_LENGTH: usize = 128;
+/// Upper bound of the length of a signature, enough for an armored SSH signature.
+const MAX_SIGNATURE_LENGTH: usize = 1024;
+
+/// Checks that a hash is the lowercase hex encoding of 64 bytes, as produced by [`hex::encode`].
+fn check_hash(name: &str, hash: &str) -> Result<(), CoordinatorError> {
+    if hash.len() != HASH_HEX_LENGTH {
+        return Err(CoordinatorError::ContributionFileSignatureMalformed(format!(
+            "{} is {} characters long, expected {}",
+            name,
+            hash.len(),
+            HASH_HEX_LENGTH
+        )));
+    }
+    if !hash.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')) {
+        return Err(CoordinatorError::ContributionFileSignatureMalformed(format!(
+            "{} is not lowercase hex",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
 ///
 /// The contribution state for a given chunk ID that is signed by the participant.
 ///
@@ -13,7 +40,7 @@ use serde_diff::SerdeDiff;
 /// 3. The hash of the next challenge file if the participant was a verifier.
 ///
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, SerdeDiff)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ContributionState {
     /// The hash of the challenge file.
     challenge_hash: String,
@@ -68,11 +95,16 @@ impl ContributionState {
 /// The signature and state of the contribution.
 ///
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, SerdeDiff)]
+#[serde(deny_unknown_fields)]
 pub struct ContributionFileSignature {
     /// The signature of the contribution state.
     signature: String,
     /// The state of the contribution that is signed.
     state: ContributionState,
+    /// The name of the signature scheme, as returned by [`Signature::name`](`crate::authentication::Signature::name`).
+    /// Signatures without a scheme are checked with the scheme of the coordinator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scheme: Option<String>,
 }
 
 impl ContributionFileSignature {
@@ -86,7 +118,85 @@ impl ContributionFileSignature {
             return Err(CoordinatorError::ContributionSignatureSizeMismatch);
         }
         tracing::debug!("Completed creating contribution signature");
-        Ok(Self { signature, state })
+        Ok(Self {
+            signature,
+            state,
+            scheme: None,
+        })
+    }
+
+    /// Names the signature scheme with which the signature was produced.
+    pub fn with_scheme(mut self, scheme: String) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    /// Names the post-quantum scheme of the signature, if any, for the transcript to record it.
+    pub fn with_post_quantum_scheme(self) -> Self {
+        match PostQuantumScheme::split(&self.signature) {
+            Some((scheme, _)) => self.with_scheme(scheme.name().to_string()),
+            None => self,
+        }
+    }
+
+    /// Deserializes a signature uploaded by a participant and checks its structure with [`Self::validate`].
+    pub fn from_slice(bytes: &[u8], scheme: &str) -> Result<Self, CoordinatorError> {
+        let signature: Self = serde_json::from_slice(bytes)
+            .map_err(|e| CoordinatorError::ContributionFileSignatureMalformed(e.to_string()))?;
+        signature.validate(scheme)?;
+
+        Ok(signature.with_post_quantum_scheme())
+    }
+
+    ///
+    /// Checks the structure of the signature before it is persisted, so that a malformed one is rejected on upload
+    /// rather than when read back: the signature scheme, if named, must be the given one or the post-quantum scheme of
+    /// the signature, the signature must be the hex encoding of 64 bytes, an SSH signature or a post-quantum signature
+    /// of the expected length, and the hashes the lowercase hex encoding of 64 bytes.
+    ///
+    pub fn validate(&self, scheme: &str) -> Result<(), CoordinatorError> {
+        let post_quantum = PostQuantumScheme::split(&self.signature);
+        if let Some(name) = &self.scheme {
+            if name != scheme && post_quantum.map_or(true, |(post_quantum, _)| name != post_quantum.name()) {
+                return Err(CoordinatorError::ContributionFileSignatureMalformed(format!(
+                    "unknown signature scheme {}",
+                    name
+                )));
+            }
+        }
+
+        if let Some((post_quantum, encoded)) = post_quantum {
+            if !hex::decode(encoded).map_or(false, |bytes| bytes.len() == post_quantum.signature_length()) {
+                return Err(CoordinatorError::ContributionFileSignatureMalformed(format!(
+                    "signature is not a {} signature",
+                    post_quantum
+                )));
+            }
+        } else {
+            if self.signature.len() > MAX_SIGNATURE_LENGTH {
+                return Err(CoordinatorError::ContributionFileSignatureMalformed(String::from(
+                    "signature is too long",
+                )));
+            }
+            let well_formed = if SshSignature::is_ssh_signature(&self.signature) {
+                SshSignature::parse(&self.signature).is_ok()
+            } else {
+                hex::decode(&self.signature).map_or(false, |bytes| bytes.len() == 64)
+            };
+            if !well_formed {
+                return Err(CoordinatorError::ContributionFileSignatureMalformed(String::from(
+                    "signature is neither 64 hex encoded bytes nor an SSH signature",
+                )));
+            }
+        }
+
+        check_hash("challenge hash", &self.state.challenge_hash)?;
+        check_hash("response hash", &self.state.response_hash)?;
+        if let Some(next_challenge_hash) = &self.state.next_challenge_hash {
+            check_hash("next challenge hash", next_challenge_hash)?;
+        }
+
+        Ok(())
     }
 
     /// Returns a reference to the signature.
@@ -125,8 +235,22 @@ impl ContributionFileSignature {
 mod tests {
     use super::*;
 
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
     use setup_utils::calculate_hash;
 
+    /// Returns a well formed signature of dummy contribution files.
+    fn valid_signature() -> ContributionFileSignature {
+        let contribution_state = ContributionState::new(
+            calculate_hash(&[1; 128]).to_vec(),
+            calculate_hash(&[2; 128]).to_vec(),
+            Some(calculate_hash(&[3; 128]).to_vec()),
+        )
+        .unwrap();
+
+        ContributionFileSignature::new(hex::encode([4u8; 64]), contribution_state).unwrap()
+    }
+
     #[test]
     pub fn test_contribution_signature() {
         // Construct the dummy challenge, response, and next_challenge files.
@@ -249,4 +373,119 @@ mod tests {
 
         assert!(contribution_state.is_err())
     }
+
+    #[test]
+    pub fn test_contribution_signature_validate() {
+        let signature = valid_signature();
+        assert!(signature.validate("Production").is_ok());
+        assert!(signature
+            .clone()
+            .with_scheme("Production".to_string())
+            .validate("Production")
+            .is_ok());
+
+        // Unknown schemes are rejected
+        assert!(matches!(
+            signature
+                .clone()
+                .with_scheme("Unknown".to_string())
+                .validate("Production"),
+            Err(CoordinatorError::ContributionFileSignatureMalformed(_))
+        ));
+
+        // So are badly encoded signatures and hashes
+        let mut malformed = signature.clone();
+        malformed.signature = "zz".repeat(64);
+        assert!(malformed.validate("Production").is_err());
+
+        let mut malformed = signature.clone();
+        malformed.state.challenge_hash = malformed.state.challenge_hash.to_uppercase();
+        assert!(malformed.validate("Production").is_err());
+
+        let mut malformed = signature.clone();
+        malformed.state.response_hash.truncate(64);
+        assert!(malformed.validate("Production").is_err());
+
+        let mut malformed = signature;
+        malformed.state.next_challenge_hash = Some(String::new());
+        assert!(malformed.validate("Production").is_err());
+    }
+
+    #[test]
+    pub fn test_contribution_signature_from_slice() {
+        let signature = valid_signature();
+        let bytes = serde_json::to_vec(&signature).unwrap();
+        assert_eq!(
+            ContributionFileSignature::from_slice(&bytes, "Production").unwrap(),
+            signature
+        );
+
+        // Unknown fields are rejected
+        let mut value: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        value["extra"] = serde_json::Value::Bool(true);
+        assert!(ContributionFileSignature::from_slice(&serde_json::to_vec(&value).unwrap(), "Production").is_err());
+
+        assert!(ContributionFileSignature::from_slice(b"", "Production").is_err());
+        assert!(ContributionFileSignature::from_slice(b"{}", "Production").is_err());
+    }
+
+    #[test]
+    pub fn test_contribution_signature_post_quantum() {
+        let mut signature = valid_signature();
+        signature.signature = format!("dilithium3:{}", hex::encode(vec![4u8; 3293]));
+        let bytes = serde_json::to_vec(&signature).unwrap();
+
+        // The scheme of the signature is recorded
+        let uploaded = ContributionFileSignature::from_slice(&bytes, "Production").unwrap();
+        assert_eq!(uploaded.scheme.as_deref(), Some("dilithium3"));
+        assert!(uploaded.validate("Production").is_ok());
+
+        // The length of the signature must be the one of the scheme
+        let mut malformed = signature.clone();
+        malformed.signature = format!("sphincs-sha2-256s:{}", hex::encode(vec![4u8; 3293]));
+        assert!(malformed.validate("Production").is_err());
+
+        // So must be the named scheme
+        assert!(signature
+            .with_scheme("sphincs-sha2-256s".to_string())
+            .validate("Production")
+            .is_err());
+    }
+
+    /// Feeds random mutations of a valid signature, and random bytes, to the deserializer: it must never panic, and
+    /// whatever it accepts must be well formed.
+    #[test]
+    pub fn test_contribution_signature_fuzz() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let valid = serde_json::to_vec(&valid_signature()).unwrap();
+
+        for _ in 0..10_000 {
+            let mut bytes = valid.clone();
+            for _ in 0..rng.gen_range(1..=4) {
+                if bytes.is_empty() {
+                    break;
+                }
+                let index = rng.gen_range(0..bytes.len());
+                match rng.gen_range(0..4) {
+                    0 => bytes[index] = rng.gen(),
+                    1 => bytes.insert(index, rng.gen()),
+                    2 => {
+                        bytes.remove(index);
+                    }
+                    _ => bytes.truncate(index + 1),
+                }
+            }
+
+            if let Ok(signature) = ContributionFileSignature::from_slice(&bytes, "Production") {
+                assert!(signature.validate("Production").is_ok());
+                assert_eq!(signature.get_challenge_hash().len(), HASH_HEX_LENGTH);
+            }
+        }
+
+        for _ in 0..1_000 {
+            let length = rng.gen_range(0..512);
+            let bytes: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+            assert!(ContributionFileSignature::from_slice(&bytes, "Production").is_err());
+        }
+    }
 }
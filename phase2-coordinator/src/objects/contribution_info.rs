@@ -1,24 +1,89 @@
-use crate::authentication::{KeyPair, Production, Signature};
+use crate::{
+    authentication::{KeyPair, Production, Signature},
+    objects::SignedDestructionAttestation,
+};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum length of the name of a contributor, in user-perceived characters.
+pub const MAX_FULL_NAME_LENGTH: usize = 128;
+/// Maximum length of the email of a contributor, in user-perceived characters, the limit of RFC 5321 on addresses.
+pub const MAX_EMAIL_LENGTH: usize = 254;
+/// Maximum length of a language tag, the one BCP 47 requires implementations to support.
+const MAX_LANGUAGE_TAG_LENGTH: usize = 35;
+/// Maximum average size in bytes of the user-perceived characters of a text, so that stacking combining marks on a
+/// few characters can't make it arbitrarily large. Emoji sequences, the largest in common use, take about 25 bytes.
+const MAX_GRAPHEME_SIZE: usize = 32;
 
 #[derive(Debug, Error)]
 pub enum ContributionInfoError {
+    #[error("The {0} contains the forbidden character {1:?}")]
+    ForbiddenCharacter(&'static str, char),
+    #[error("Invalid language tag {0}, expected a BCP 47 tag such as en, ja or ar-EG")]
+    InvalidLanguageTag(String),
     #[error("Keypair doesn't match the pubkey")]
     InvalidSigKey,
+    #[error("The {0} is required")]
+    MissingText(&'static str),
     #[error("Error while serializing ContributionInfo: {0}")]
     SerdeError(#[from] serde_json::Error),
     #[error("Error while signing ContributionInfo: {0}")]
     SignatureError(String),
+    #[error("The {0} is longer than {1} characters")]
+    TextTooLong(&'static str, usize),
     #[error("Expected ContributionInfo to be serialized as a Map")]
     UnexpectedSerializationFormat,
 }
 
+/// Returns `true` for the characters of explicit bidirectional formatting, the embeddings, overrides and isolates,
+/// which reorder the display of the text following them.
+fn is_bidi_formatting(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+///
+/// Checks a text of the info displayed publicly. Its length is counted in grapheme clusters, the characters perceived
+/// by the users, so that names in any script get the same room whatever the number of bytes or code points of their
+/// characters. Control characters and explicit bidirectional formatting, which can spoof the display of the
+/// surrounding text, are forbidden; the implicit marks of direction, e.g. U+200F in right-to-left text, are allowed.
+///
+pub fn validate_text(field: &'static str, text: &str, max_length: usize) -> Result<(), ContributionInfoError> {
+    if let Some(c) = text.chars().find(|&c| c.is_control() || is_bidi_formatting(c)) {
+        return Err(ContributionInfoError::ForbiddenCharacter(field, c));
+    }
+
+    if text.len() > max_length * MAX_GRAPHEME_SIZE || text.graphemes(true).count() > max_length {
+        return Err(ContributionInfoError::TextTooLong(field, max_length));
+    }
+
+    Ok(())
+}
+
+///
+/// Checks that a language tag is well-formed in the syntax of BCP 47, e.g. `ja`, `ar-EG` or `zh-Hant-TW`. The subtags
+/// aren't checked against the registry.
+///
+pub fn validate_language_tag(tag: &str) -> Result<(), ContributionInfoError> {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let well_formed = tag.len() <= MAX_LANGUAGE_TAG_LENGTH
+        && (2..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    match well_formed {
+        true => Ok(()),
+        false => Err(ContributionInfoError::InvalidLanguageTag(tag.to_owned())),
+    }
+}
+
 /// Timestamps of the contribution
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContributionTimeStamps {
     // User starts the CLI
     pub start_contribution: DateTime<Utc>,
@@ -53,6 +118,20 @@ impl Default for ContributionTimeStamps {
     }
 }
 
+/// Details of a contribution computed on an air-gapped machine
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OfflineContributionInfo {
+    // Hash of the challenge in the challenge bundle
+    pub challenge_hash: String,
+    // Deadline to upload the response bundle given by the coordinator
+    pub deadline: DateTime<Utc>,
+    // User has downloaded the challenge bundle to transfer it to the air-gapped machine
+    pub challenge_bundle_downloaded: DateTime<Utc>,
+    // User has transferred the response bundle back from the air-gapped machine
+    pub response_bundle_received: DateTime<Utc>,
+}
+
 /// A summarized version of [`ContributionTimeStamps`]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TrimmedContributionTimeStamps {
@@ -71,11 +150,15 @@ impl From<ContributionTimeStamps> for TrimmedContributionTimeStamps {
 
 /// Summary info about the contribution
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContributionInfo {
     // Name of the contributor
     pub full_name: Option<String>,
     // Email of the contributor
     pub email: Option<String>,
+    /// BCP 47 tag of the language of the name of the contributor, e.g. `ja` or `ar`, to display it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
     // ed25519 public key, hex encoded
     pub public_key: String,
     // User can choose to contribute on another machine
@@ -96,6 +179,16 @@ pub struct ContributionInfo {
     pub contribution_file_signature: String,
     /// Url providing an attestation of the contribution
     pub attestation: Option<String>,
+    /// Hex encoded sha256 hash of the content of the attestation post, archived when the post was registered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_hash: Option<String>,
+    // Present if the contribution was computed on an air-gapped machine with the offline flow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offline_contribution: Option<OfflineContributionInfo>,
+    /// Declaration of the contributor that the toxic waste of the contribution was destroyed, set by the coordinator
+    /// once posted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destruction_attestation: Option<SignedDestructionAttestation>,
     // Some timestamps to get performance metrics of the ceremony
     pub timestamps: ContributionTimeStamps,
     // Signature of this struct, computed on the json string encoding of all the other fields of this struct
@@ -103,6 +196,22 @@ pub struct ContributionInfo {
 }
 
 impl ContributionInfo {
+    /// Checks the texts of the info displayed publicly: the name and the email of the contributor, and the tag of
+    /// their language.
+    pub fn validate(&self) -> Result<(), ContributionInfoError> {
+        if let Some(full_name) = &self.full_name {
+            validate_text("full name", full_name, MAX_FULL_NAME_LENGTH)?;
+        }
+        if let Some(email) = &self.email {
+            validate_text("email", email, MAX_EMAIL_LENGTH)?;
+        }
+        if let Some(language) = &self.language {
+            validate_language_tag(language)?;
+        }
+
+        Ok(())
+    }
+
     /// Calculates the hash of the json string encoding all the fields of the struct
     /// expect for the signature itself.
     fn hash_for_signature(&self) -> Result<String, ContributionInfoError> {
@@ -157,6 +266,8 @@ impl ContributionInfo {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TrimmedContributionInfo {
     full_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
     public_key: String,
     is_another_machine: bool,
     is_own_seed_of_randomness: bool,
@@ -165,6 +276,12 @@ pub struct TrimmedContributionInfo {
     contribution_hash: String,
     contribution_hash_signature: String,
     attestation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attestation_hash: Option<String>,
+    #[serde(default)]
+    is_offline: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    destruction_attestation: Option<SignedDestructionAttestation>,
     timestamps: TrimmedContributionTimeStamps,
 }
 
@@ -172,6 +289,7 @@ impl From<ContributionInfo> for TrimmedContributionInfo {
     fn from(parent: ContributionInfo) -> Self {
         Self {
             full_name: parent.full_name,
+            language: parent.language,
             public_key: parent.public_key,
             is_another_machine: parent.is_another_machine,
             is_own_seed_of_randomness: parent.is_own_seed_of_randomness,
@@ -180,12 +298,23 @@ impl From<ContributionInfo> for TrimmedContributionInfo {
             contribution_hash: parent.contribution_file_hash,
             contribution_hash_signature: parent.contribution_file_signature,
             attestation: parent.attestation,
+            attestation_hash: parent.attestation_hash,
+            is_offline: parent.offline_contribution.is_some(),
+            destruction_attestation: parent.destruction_attestation,
             timestamps: parent.timestamps.into(),
         }
     }
 }
 
 impl TrimmedContributionInfo {
+    pub fn full_name(&self) -> Option<&str> {
+        self.full_name.as_deref()
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
     pub fn public_key(&self) -> &str {
         self.public_key.as_ref()
     }
@@ -194,6 +323,10 @@ impl TrimmedContributionInfo {
         self.ceremony_round
     }
 
+    pub fn contribution_hash(&self) -> &str {
+        self.contribution_hash.as_ref()
+    }
+
     #[cfg(debug_assertions)]
     pub fn is_another_machine(&self) -> bool {
         self.is_another_machine
@@ -203,13 +336,21 @@ impl TrimmedContributionInfo {
     pub fn is_own_seed_of_randomness(&self) -> bool {
         self.is_own_seed_of_randomness
     }
+
+    pub fn is_offline(&self) -> bool {
+        self.is_offline
+    }
+
+    pub fn destruction_attestation(&self) -> Option<&SignedDestructionAttestation> {
+        self.destruction_attestation.as_ref()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::authentication::KeyPair;
 
-    use super::ContributionInfo;
+    use super::*;
 
     #[test]
     fn sign_and_verify() {
@@ -233,4 +374,41 @@ mod tests {
         test_info.try_sign(&keypair).unwrap();
         assert!(test_info.verify_signature().unwrap());
     }
+
+    #[test]
+    fn validate_texts() {
+        let mut info = ContributionInfo::default();
+        for (name, language) in [("山田 太郎", "ja"), ("محمد بن موسى", "ar-EG"), ("שָׁלוֹם\u{200F}", "he")] {
+            info.full_name = Some(name.to_string());
+            info.language = Some(language.to_string());
+            assert!(info.validate().is_ok());
+        }
+
+        // The length is counted in user-perceived characters rather than in bytes or code points
+        let family = "👨\u{200D}👩\u{200D}👧";
+        assert!(validate_text("full name", &family.repeat(MAX_FULL_NAME_LENGTH), MAX_FULL_NAME_LENGTH).is_ok());
+        assert!(validate_text("full name", &"名".repeat(MAX_FULL_NAME_LENGTH + 1), MAX_FULL_NAME_LENGTH).is_err());
+        let zalgo = format!("a{}", "\u{0301}".repeat(MAX_GRAPHEME_SIZE * 2));
+        assert!(matches!(
+            validate_text("full name", &zalgo, 1),
+            Err(ContributionInfoError::TextTooLong("full name", 1))
+        ));
+
+        // Controls and bidirectional overrides are forbidden
+        info.full_name = Some("\u{202E}evil".to_string());
+        assert!(matches!(
+            info.validate(),
+            Err(ContributionInfoError::ForbiddenCharacter("full name", '\u{202E}'))
+        ));
+        info.full_name = Some("Test\nName".to_string());
+        assert!(info.validate().is_err());
+
+        info.full_name = None;
+        for tag in ["", "e", "en_US", "日本", "en-", "toolongtag"] {
+            info.language = Some(tag.to_string());
+            assert!(matches!(info.validate(), Err(ContributionInfoError::InvalidLanguageTag(_))));
+        }
+        info.language = Some("zh-Hant-TW".to_string());
+        assert!(info.validate().is_ok());
+    }
 }
@@ -0,0 +1,22 @@
+use crate::objects::{ChallengeBundle, LockedLocators};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The state of the contribution of a contributor holding a lock, to resume it after a crash of the client, possibly
+/// from another machine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContributionSession {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    /// The locators of the files locked by the contributor.
+    pub locked_locators: LockedLocators,
+    /// The time after which the contributor is dropped if the contribution has not been uploaded.
+    pub deadline: OffsetDateTime,
+    /// The challenge bundle, if the contributor switched to the offline flow.
+    pub challenge_bundle: Option<ChallengeBundle>,
+    /// Whether the contribution file has already been uploaded.
+    pub contribution_uploaded: bool,
+    /// Whether the signature of the contribution file has already been uploaded.
+    pub signature_uploaded: bool,
+}
@@ -0,0 +1,282 @@
+use crate::objects::Participant;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+/// The state of a queued participant: its reliability score, its assigned future round, the time it was last seen,
+/// and its time of joining.
+pub type QueueInfo = (u8, Option<u64>, OffsetDateTime, OffsetDateTime);
+
+/// An entry of the ordering journal of the queue, in which the state of the queue is persisted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// Increasing with every participant joining the queue, orders the participants who joined at the same time.
+    pub sequence: u64,
+    pub participant: Participant,
+    pub info: QueueInfo,
+}
+
+/// The queue of the participants waiting to be assigned to a round, ordered by time of joining.
+///
+/// The queue is indexed by participant, so that the state and the position of a participant, and the number of
+/// queued contributors, are looked up in constant time, without walking the queue. Joining and leaving the queue
+/// shift the participants who joined later.
+///
+/// The queue is persisted as its ordering journal: the list of its entries in order, each with its sequence number.
+/// The map of the participants to their state, in which the queue used to be persisted, is still accepted when
+/// loading it.
+#[derive(Clone, Debug, Default)]
+pub struct ContributorQueue {
+    /// The entries of the queue, in order.
+    entries: Vec<QueueEntry>,
+    /// The index of each participant in the entries.
+    positions: HashMap<Participant, usize>,
+    /// The number of contributors in the queue.
+    number_of_contributors: usize,
+    /// The sequence number of the next participant joining the queue.
+    next_sequence: u64,
+}
+
+impl ContributorQueue {
+    /// Returns the number of participants in the queue.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of contributors in the queue.
+    pub fn number_of_contributors(&self) -> usize {
+        self.number_of_contributors
+    }
+
+    pub fn contains_key(&self, participant: &Participant) -> bool {
+        self.positions.contains_key(participant)
+    }
+
+    pub fn get(&self, participant: &Participant) -> Option<&QueueInfo> {
+        self.positions.get(participant).map(|&index| &self.entries[index].info)
+    }
+
+    /// Returns the state of a queued participant, to update the time it was last seen or its assigned round. The
+    /// time of joining orders the queue and must not be changed.
+    pub fn get_mut(&mut self, participant: &Participant) -> Option<&mut QueueInfo> {
+        let index = *self.positions.get(participant)?;
+        Some(&mut self.entries[index].info)
+    }
+
+    /// Returns the zero-based position of a participant in the queue.
+    pub fn position(&self, participant: &Participant) -> Option<usize> {
+        self.positions.get(participant).copied()
+    }
+
+    /// Returns the participants in the queue with their state, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Participant, &QueueInfo)> {
+        self.entries.iter().map(|entry| (&entry.participant, &entry.info))
+    }
+
+    /// Returns the participants in the queue with their mutable state, in order. The time of joining orders the
+    /// queue and must not be changed.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Participant, &mut QueueInfo)> {
+        self.entries.iter_mut().map(|entry| (&entry.participant, &mut entry.info))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &QueueInfo> {
+        self.entries.iter().map(|entry| &entry.info)
+    }
+
+    ///
+    /// Adds a participant to the queue, after the participants who joined before or at the same time, and returns
+    /// its previous state if it was already queued. A queued participant keeps its position unless its time of
+    /// joining changes.
+    ///
+    pub fn insert(&mut self, participant: Participant, info: QueueInfo) -> Option<QueueInfo> {
+        if let Some(&index) = self.positions.get(&participant) {
+            if self.entries[index].info.3 == info.3 {
+                return Some(std::mem::replace(&mut self.entries[index].info, info));
+            }
+        }
+
+        let previous = self.remove(&participant);
+
+        let index = self.entries.partition_point(|entry| entry.info.3 <= info.3);
+        if participant.is_contributor() {
+            self.number_of_contributors += 1;
+        }
+        self.entries.insert(index, QueueEntry {
+            sequence: self.next_sequence,
+            participant,
+            info,
+        });
+        self.next_sequence += 1;
+        self.reindex(index);
+
+        previous
+    }
+
+    /// Removes a participant from the queue and returns its state.
+    pub fn remove(&mut self, participant: &Participant) -> Option<QueueInfo> {
+        let index = self.positions.remove(participant)?;
+        let entry = self.entries.remove(index);
+        if entry.participant.is_contributor() {
+            self.number_of_contributors -= 1;
+        }
+        self.reindex(index);
+
+        Some(entry.info)
+    }
+
+    /// Retains only the participants for which the predicate returns `true`.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Participant, &QueueInfo) -> bool) {
+        self.entries.retain(|entry| predicate(&entry.participant, &entry.info));
+        self.rebuild_index();
+    }
+
+    /// Rebuilds the index and the number of contributors from the entries.
+    fn rebuild_index(&mut self) {
+        self.positions.clear();
+        self.number_of_contributors = self
+            .entries
+            .iter()
+            .filter(|entry| entry.participant.is_contributor())
+            .count();
+        self.reindex(0);
+    }
+
+    /// Updates the index of the entries from the given one onwards.
+    fn reindex(&mut self, from: usize) {
+        for (index, entry) in self.entries.iter().enumerate().skip(from) {
+            self.positions.insert(entry.participant.clone(), index);
+        }
+    }
+
+    /// Rebuilds the queue from the entries of its ordering journal.
+    fn from_entries(mut entries: Vec<QueueEntry>) -> Self {
+        entries.sort_by(|a, b| (a.info.3, a.sequence).cmp(&(b.info.3, b.sequence)));
+
+        let mut queue = Self {
+            next_sequence: entries.iter().map(|entry| entry.sequence + 1).max().unwrap_or_default(),
+            entries,
+            ..Default::default()
+        };
+        queue.rebuild_index();
+        queue
+    }
+}
+
+impl IntoIterator for ContributorQueue {
+    type IntoIter = std::iter::Map<std::vec::IntoIter<QueueEntry>, fn(QueueEntry) -> (Participant, QueueInfo)>;
+    type Item = (Participant, QueueInfo);
+
+    fn into_iter(self) -> Self::IntoIter {
+        let split: fn(QueueEntry) -> (Participant, QueueInfo) = |entry| (entry.participant, entry.info);
+        self.entries.into_iter().map(split)
+    }
+}
+
+impl Serialize for ContributorQueue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+/// The persisted forms of the queue.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PersistedQueue {
+    Journal(Vec<QueueEntry>),
+    Map(HashMap<Participant, QueueInfo>),
+}
+
+impl<'de> Deserialize<'de> for ContributorQueue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = match PersistedQueue::deserialize(deserializer)? {
+            PersistedQueue::Journal(entries) => entries,
+            PersistedQueue::Map(map) => {
+                let mut map: Vec<_> = map.into_iter().collect();
+                map.sort_by(|a, b| (a.1).3.cmp(&(b.1).3));
+                map.into_iter()
+                    .enumerate()
+                    .map(|(sequence, (participant, info))| QueueEntry {
+                        sequence: sequence as u64,
+                        participant,
+                        info,
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(Self::from_entries(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn info(joined: OffsetDateTime) -> QueueInfo {
+        (10, None, joined, joined)
+    }
+
+    #[test]
+    fn test_contributor_queue_positions() {
+        let now = OffsetDateTime::now_utc();
+        let first = Participant::new_contributor("first");
+        let second = Participant::new_contributor("second");
+        let third = Participant::new_contributor("third");
+
+        let mut queue = ContributorQueue::default();
+        queue.insert(second.clone(), info(now));
+        queue.insert(third.clone(), info(now));
+        // Joined before the others, e.g. put back into the queue on a rollback
+        queue.insert(first.clone(), info(now - Duration::minutes(1)));
+        assert_eq!(3, queue.number_of_contributors());
+        assert_eq!(Some(0), queue.position(&first));
+        assert_eq!(Some(1), queue.position(&second));
+        assert_eq!(Some(2), queue.position(&third));
+
+        // Updating the state of a participant keeps its position
+        let mut assigned = info(now);
+        assigned.1 = Some(2);
+        assert_eq!(Some(info(now)), queue.insert(second.clone(), assigned));
+        assert_eq!(Some(1), queue.position(&second));
+        assert_eq!(Some(&assigned), queue.get(&second));
+
+        assert!(queue.remove(&first).is_some());
+        assert_eq!(None, queue.position(&first));
+        assert_eq!(Some(0), queue.position(&second));
+        assert_eq!(Some(1), queue.position(&third));
+        assert_eq!(2, queue.number_of_contributors());
+    }
+
+    #[test]
+    fn test_contributor_queue_journal() {
+        let now = OffsetDateTime::now_utc();
+        let mut queue = ContributorQueue::default();
+        for name in &["a", "b", "c"] {
+            queue.insert(Participant::new_contributor(name), info(now));
+        }
+        queue.remove(&Participant::new_contributor("a"));
+
+        // The order of the participants who joined at the same time is restored from the journal
+        let restored: ContributorQueue = serde_json::from_str(&serde_json::to_string(&queue).unwrap()).unwrap();
+        assert_eq!(queue.entries, restored.entries);
+        assert_eq!(Some(1), restored.position(&Participant::new_contributor("c")));
+
+        // A participant joining the restored queue goes last
+        let mut restored = restored;
+        restored.insert(Participant::new_contributor("d"), info(now));
+        assert_eq!(Some(2), restored.position(&Participant::new_contributor("d")));
+        assert!(restored.entries.windows(2).all(|w| w[0].sequence < w[1].sequence));
+
+        // The queue persisted as a map is still loaded
+        let map: HashMap<_, _> = queue.iter().map(|(p, i)| (p.clone(), *i)).collect();
+        let legacy: ContributorQueue = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_eq!(2, legacy.len());
+        assert_eq!(2, legacy.number_of_contributors());
+    }
+}
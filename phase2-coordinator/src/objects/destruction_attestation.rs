@@ -0,0 +1,172 @@
+use crate::objects::{validate_text, ContributionInfoError};
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use time::OffsetDateTime;
+
+/// Maximum length of the description of the destruction of the toxic waste, in user-perceived characters.
+pub const MAX_DESTRUCTION_DETAILS_LENGTH: usize = 512;
+/// The interval between two reminders to a contributor who hasn't attested the destruction of its toxic waste yet.
+pub const DESTRUCTION_REMINDER_INTERVAL: time::Duration = time::Duration::hours(1);
+
+/// How the toxic waste of a contribution, the randomness used to compute it, was destroyed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DestructionMethod {
+    /// The randomness was only held in memory by the process computing the contribution, which exited.
+    ProcessExited,
+    /// The files holding the randomness were securely erased.
+    SecureErase,
+    /// The machine computing the contribution was wiped, e.g. a live system was shut down.
+    MachineWiped,
+    /// The storage of the machine computing the contribution was physically destroyed.
+    HardwareDestroyed,
+    /// Another method, described in the details of the attestation.
+    Other,
+}
+
+impl DestructionMethod {
+    pub const ALL: [DestructionMethod; 5] = [
+        DestructionMethod::ProcessExited,
+        DestructionMethod::SecureErase,
+        DestructionMethod::MachineWiped,
+        DestructionMethod::HardwareDestroyed,
+        DestructionMethod::Other,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DestructionMethod::ProcessExited => "process_exited",
+            DestructionMethod::SecureErase => "secure_erase",
+            DestructionMethod::MachineWiped => "machine_wiped",
+            DestructionMethod::HardwareDestroyed => "hardware_destroyed",
+            DestructionMethod::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for DestructionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for DestructionMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DestructionMethod::ALL
+            .iter()
+            .find(|method| method.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown destruction method {}", s))
+    }
+}
+
+/// The declaration of a contributor that the toxic waste of its contribution was destroyed, bound to the hash of the
+/// contribution file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DestructionAttestation {
+    pub round_height: u64,
+    /// Hex encoded hash of the contribution file, the `contribution_file_hash` of the contribution info of the round.
+    pub contribution_hash: String,
+    pub method: DestructionMethod,
+    /// Description of the destruction, published as it is. Required by [`DestructionMethod::Other`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl DestructionAttestation {
+    /// Returns the message signed by the contributor: the json encoding of the attestation, with the fields of the
+    /// objects sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Checks the description of the destruction, displayed publicly.
+    pub fn validate(&self) -> Result<(), ContributionInfoError> {
+        match &self.details {
+            Some(details) => validate_text("details", details, MAX_DESTRUCTION_DETAILS_LENGTH),
+            None if self.method == DestructionMethod::Other => Err(ContributionInfoError::MissingText("details")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A [`DestructionAttestation`] signed by its contributor, published in the contribution info of its round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedDestructionAttestation {
+    pub attestation: DestructionAttestation,
+    /// Signature of the contributor on [`DestructionAttestation::message`].
+    pub signature: String,
+    pub timestamp: OffsetDateTime,
+}
+
+/// A contributor expected to attest the destruction of the toxic waste of its contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDestructionAttestation {
+    pub round_height: u64,
+    /// The time after which the contributor is no longer reminded.
+    pub deadline: OffsetDateTime,
+    /// The time of the latest reminder, or of the contribution before the first one.
+    pub reminded_at: OffsetDateTime,
+}
+
+impl PendingDestructionAttestation {
+    /// Returns `true` if the contributor should be reminded at the given time.
+    pub fn is_reminder_due(&self, now: OffsetDateTime) -> bool {
+        now < self.deadline && now - self.reminded_at >= DESTRUCTION_REMINDER_INTERVAL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destruction_attestation() {
+        let mut attestation = DestructionAttestation {
+            round_height: 1,
+            contribution_hash: String::from("00ff"),
+            method: DestructionMethod::ProcessExited,
+            details: None,
+        };
+        assert!(attestation.validate().is_ok());
+        assert_eq!(
+            attestation.message().unwrap(),
+            r#"{"contribution_hash":"00ff","method":"process_exited","round_height":1}"#
+        );
+
+        // Other methods must be described
+        attestation.method = DestructionMethod::Other;
+        assert!(matches!(
+            attestation.validate(),
+            Err(ContributionInfoError::MissingText("details"))
+        ));
+        attestation.details = Some(String::from("Melted the disk"));
+        assert!(attestation.validate().is_ok());
+        attestation.details = Some(String::from("\u{202E}evil"));
+        assert!(attestation.validate().is_err());
+
+        for method in &DestructionMethod::ALL {
+            assert_eq!(method.to_string().parse::<DestructionMethod>().unwrap(), *method);
+            assert_eq!(serde_json::to_string(method).unwrap(), format!("\"{}\"", method));
+        }
+    }
+
+    #[test]
+    fn test_reminder_due() {
+        let contributed_at = OffsetDateTime::from_unix_timestamp(1672531200).unwrap();
+        let pending = PendingDestructionAttestation {
+            round_height: 1,
+            deadline: contributed_at + time::Duration::hours(3),
+            reminded_at: contributed_at,
+        };
+
+        assert!(!pending.is_reminder_due(contributed_at + time::Duration::minutes(59)));
+        assert!(pending.is_reminder_due(contributed_at + DESTRUCTION_REMINDER_INTERVAL));
+        assert!(!pending.is_reminder_due(pending.deadline));
+    }
+}
@@ -0,0 +1,191 @@
+use blake2::{Blake2b512, Digest};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The domain separator of the seed of the genesis challenge.
+const GENESIS_SEED_DOMAIN: &[u8] = b"namada-genesis-seed";
+
+/// The size in bytes of the seed of the genesis challenge, which takes the place of the hash of the previous
+/// contribution at the start of the challenge.
+pub const GENESIS_SEED_SIZE: usize = 64;
+
+/// Returns the hex encoded commitment to the given share of the seed: its Blake2b-512 hash.
+pub fn seed_commitment(share: &[u8]) -> String {
+    hex::encode(Blake2b512::digest(share))
+}
+
+/// The commitment of an operator to its share of the seed of the genesis challenge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedCommitment {
+    /// The public key of the operator.
+    pub operator: String,
+    /// Hex encoded commitment to the share, as returned by [`seed_commitment`].
+    pub commitment: String,
+    /// Signature of the operator on [`SeedCommitment::message`].
+    pub signature: String,
+    pub timestamp: OffsetDateTime,
+}
+
+impl SeedCommitment {
+    /// Returns the message signed by an operator to commit to its share.
+    pub fn message(commitment: &str) -> String {
+        format!("genesis-seed-commitment:{}", commitment)
+    }
+}
+
+/// The reveal of the share of the seed of the genesis challenge an operator committed to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedReveal {
+    /// The public key of the operator.
+    pub operator: String,
+    /// Hex encoded share of the seed.
+    pub share: String,
+    /// Signature of the operator on [`SeedReveal::message`].
+    pub signature: String,
+    pub timestamp: OffsetDateTime,
+}
+
+impl SeedReveal {
+    /// Returns the message signed by an operator to reveal its share.
+    pub fn message(share: &str) -> String {
+        format!("genesis-seed-reveal:{}", share)
+    }
+}
+
+/// The state of the collection of the seed of the genesis challenge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenesisSeedStatus {
+    /// Not every operator has committed to its share yet.
+    Committing { commitments: usize, required: usize },
+    /// Every operator has committed, not every operator has revealed its share yet.
+    Revealing { reveals: usize, required: usize },
+    /// The seed is derived and written in the genesis challenge, round 1 can start.
+    Derived { seed: String },
+}
+
+/// The commit-reveal of the shares of the seed of the genesis challenge by the operators, published in the manifest
+/// of round 0 and covered by the hash chain of the transcript.
+///
+/// The operators first commit to their shares, and reveal them only once every operator has committed, so that none of
+/// them can choose its share knowing the others. The seed is
+/// `Blake2b-512("namada-genesis-seed" || for each share, in order of operator: len(operator) as u64 little endian ||
+/// operator || len(share) as u64 little endian || share)`: it is unpredictable as long as a single operator is honest.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisSeed {
+    pub commitments: Vec<SeedCommitment>,
+    pub reveals: Vec<SeedReveal>,
+    /// Hex encoded seed, once derived from the shares of every operator.
+    pub seed: Option<String>,
+}
+
+impl GenesisSeed {
+    /// Returns the commitment of the given operator, if any.
+    pub fn commitment(&self, operator: &str) -> Option<&SeedCommitment> {
+        self.commitments
+            .iter()
+            .find(|commitment| commitment.operator == operator)
+    }
+
+    /// Returns `true` if the given operator has revealed its share.
+    pub fn has_revealed(&self, operator: &str) -> bool {
+        self.reveals.iter().any(|reveal| reveal.operator == operator)
+    }
+
+    /// Returns the state of the collection, given the number of operators.
+    pub fn status(&self, required: usize) -> GenesisSeedStatus {
+        match &self.seed {
+            Some(seed) => GenesisSeedStatus::Derived { seed: seed.clone() },
+            None if self.commitments.len() < required => GenesisSeedStatus::Committing {
+                commitments: self.commitments.len(),
+                required,
+            },
+            None => GenesisSeedStatus::Revealing {
+                reveals: self.reveals.len(),
+                required,
+            },
+        }
+    }
+
+    /// Returns the seed derived from the revealed shares, whatever the order in which they were revealed.
+    pub fn derive(&self) -> Result<Vec<u8>, hex::FromHexError> {
+        let mut reveals: Vec<&SeedReveal> = self.reveals.iter().collect();
+        reveals.sort_by(|left, right| left.operator.cmp(&right.operator));
+
+        let mut hasher = Blake2b512::new();
+        hasher.update(GENESIS_SEED_DOMAIN);
+        for reveal in reveals {
+            let share = hex::decode(&reveal.share)?;
+            hasher.update((reveal.operator.len() as u64).to_le_bytes());
+            hasher.update(reveal.operator.as_bytes());
+            hasher.update((share.len() as u64).to_le_bytes());
+            hasher.update(&share);
+        }
+
+        Ok(hasher.finalize().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reveal(operator: &str, share: &[u8]) -> SeedReveal {
+        SeedReveal {
+            operator: operator.to_string(),
+            share: hex::encode(share),
+            signature: String::new(),
+            timestamp: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_genesis_seed() {
+        let mut genesis_seed = GenesisSeed::default();
+        assert_eq!(
+            GenesisSeedStatus::Committing {
+                commitments: 0,
+                required: 2
+            },
+            genesis_seed.status(2)
+        );
+
+        for operator in &["first", "second"] {
+            genesis_seed.commitments.push(SeedCommitment {
+                operator: operator.to_string(),
+                commitment: seed_commitment(operator.as_bytes()),
+                signature: String::new(),
+                timestamp: OffsetDateTime::now_utc(),
+            });
+        }
+        assert_eq!(
+            genesis_seed.commitment("first").unwrap().commitment,
+            seed_commitment(b"first")
+        );
+        assert!(genesis_seed.commitment("third").is_none());
+        assert_eq!(
+            GenesisSeedStatus::Revealing {
+                reveals: 0,
+                required: 2
+            },
+            genesis_seed.status(2)
+        );
+
+        // The seed doesn't depend on the order of the reveals, but on every share
+        genesis_seed.reveals = vec![reveal("second", b"second"), reveal("first", b"first")];
+        assert!(genesis_seed.has_revealed("first"));
+        let seed = genesis_seed.derive().unwrap();
+        assert_eq!(seed.len(), GENESIS_SEED_SIZE);
+        genesis_seed.reveals.reverse();
+        assert_eq!(genesis_seed.derive().unwrap(), seed);
+        genesis_seed.reveals[0] = reveal("first", b"other");
+        assert_ne!(genesis_seed.derive().unwrap(), seed);
+
+        genesis_seed.seed = Some(hex::encode(&seed));
+        assert_eq!(
+            GenesisSeedStatus::Derived {
+                seed: hex::encode(&seed)
+            },
+            genesis_seed.status(2)
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The outcome of the check, by a human verification provider, that a contributor joined the queue from a browser
+/// operated by a human.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HumanVerification {
+    /// The name of the provider, e.g. `turnstile`.
+    pub provider: String,
+    /// The hostname of the site on which the challenge was solved, as reported by the provider.
+    pub hostname: Option<String>,
+    /// The time at which the token of the challenge was validated with the provider.
+    pub verified_at: OffsetDateTime,
+}
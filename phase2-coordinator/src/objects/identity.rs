@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use time::OffsetDateTime;
+
+/// An identity claimed by a participant on top of its public key, e.g. to prove its affiliation to an institution.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IdentityClaim {
+    /// A decentralized identifier, e.g. `did:web:example.org:alice`, whose document lists the public key of the
+    /// participant.
+    Did { did: String },
+    /// A reference to a verifiable credential issued to the participant, e.g. the url of the credential.
+    VerifiableCredential { reference: String },
+}
+
+impl IdentityClaim {
+    /// Returns the DID or the reference of the credential.
+    pub fn reference(&self) -> &str {
+        match self {
+            IdentityClaim::Did { did } => did,
+            IdentityClaim::VerifiableCredential { reference } => reference,
+        }
+    }
+}
+
+impl fmt::Display for IdentityClaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityClaim::Did { did } => write!(f, "DID {}", did),
+            IdentityClaim::VerifiableCredential { reference } => write!(f, "verifiable credential {}", reference),
+        }
+    }
+}
+
+/// An identity claimed by a participant and validated by an identity resolver.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedIdentity {
+    pub claim: IdentityClaim,
+    /// The name of the resolver, e.g. `did-web`.
+    pub resolver: String,
+    /// The issuer of the credential, as reported by the resolver.
+    pub issuer: Option<String>,
+    /// The time at which the claim was validated by the resolver.
+    pub verified_at: OffsetDateTime,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The phase of the lifecycle of the ceremony, which determines the endpoints available to the participants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecyclePhase {
+    /// No contributor has been assigned to a round yet.
+    Prelaunch,
+    /// The contributors are accepted in the queue and contribute in turn.
+    Running,
+    /// The ceremony reached its maximum length: no more contributors are accepted, the current contribution completes.
+    Draining,
+    /// All the contributions are complete, the coordinator is shutting down.
+    Over,
+}
+
+impl LifecyclePhase {
+    /// All the phases, in the order of the lifecycle.
+    pub const ALL: [LifecyclePhase; 4] = [
+        LifecyclePhase::Prelaunch,
+        LifecyclePhase::Running,
+        LifecyclePhase::Draining,
+        LifecyclePhase::Over,
+    ];
+}
+
+impl fmt::Display for LifecyclePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecyclePhase::Prelaunch => write!(f, "prelaunch"),
+            LifecyclePhase::Running => write!(f, "running"),
+            LifecyclePhase::Draining => write!(f, "draining"),
+            LifecyclePhase::Over => write!(f, "over"),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::objects::Participant;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The number of distinct operators that must approve a manual contribution before it is injected.
+pub const REQUIRED_OPERATOR_APPROVALS: usize = 2;
+
+/// A contribution computed out-of-band, e.g. on an audited machine, to be injected by the operators on behalf of a
+/// participant.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManualContribution {
+    /// The contributor on behalf of which the contribution is injected. It must hold the lock of the current round.
+    pub participant: Participant,
+    pub round_height: u64,
+    /// Hex encoded hash of the contribution file.
+    pub contribution_hash: String,
+    /// Why the contribution is injected manually, published in the transcript.
+    pub reason: String,
+}
+
+impl ManualContribution {
+    /// Returns the message signed by an operator to approve the injection: the json encoding of the contribution,
+    /// with the fields of the objects sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+}
+
+/// The approval of an operator on the injection of a [`ManualContribution`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorApproval {
+    /// The public key of the operator.
+    pub operator: String,
+    /// Signature of the operator on [`ManualContribution::message`].
+    pub signature: String,
+    pub timestamp: OffsetDateTime,
+}
+
+/// The state of the injection of a [`ManualContribution`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InjectionStatus {
+    /// Not enough operators have approved the injection yet.
+    Pending { approvals: usize, required: usize },
+    /// Enough operators approved the injection, the contribution can be injected.
+    Approved,
+    /// The contribution has been injected.
+    Injected,
+}
+
+/// The approvals of the operators on a [`ManualContribution`], published in the transcript of its round to mark
+/// the contribution as injected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualInjection {
+    pub contribution: ManualContribution,
+    pub approvals: Vec<OperatorApproval>,
+    pub injected_at: Option<OffsetDateTime>,
+}
+
+impl ManualInjection {
+    pub fn new(contribution: ManualContribution) -> Self {
+        Self {
+            contribution,
+            approvals: Vec::new(),
+            injected_at: None,
+        }
+    }
+
+    /// Returns `true` if the given operator already approved the injection.
+    pub fn has_approved(&self, operator: &str) -> bool {
+        self.approvals.iter().any(|approval| approval.operator == operator)
+    }
+
+    /// Adds the approval of an operator. Returns `false`, leaving the injection untouched,
+    /// if the operator already approved it.
+    pub fn add_approval(&mut self, approval: OperatorApproval) -> bool {
+        if self.has_approved(&approval.operator) {
+            return false;
+        }
+
+        self.approvals.push(approval);
+        true
+    }
+
+    /// Returns the state of the injection.
+    pub fn status(&self) -> InjectionStatus {
+        match self.injected_at {
+            Some(_) => InjectionStatus::Injected,
+            None if self.approvals.len() >= REQUIRED_OPERATOR_APPROVALS => InjectionStatus::Approved,
+            None => InjectionStatus::Pending {
+                approvals: self.approvals.len(),
+                required: REQUIRED_OPERATOR_APPROVALS,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approval(operator: &str) -> OperatorApproval {
+        OperatorApproval {
+            operator: operator.to_string(),
+            signature: String::new(),
+            timestamp: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_manual_injection_status() {
+        let mut injection = ManualInjection::new(ManualContribution {
+            participant: Participant::new_contributor("contributor"),
+            round_height: 1,
+            contribution_hash: "aa".to_string(),
+            reason: "Computed on the audited machine".to_string(),
+        });
+        assert_eq!(
+            InjectionStatus::Pending {
+                approvals: 0,
+                required: REQUIRED_OPERATOR_APPROVALS
+            },
+            injection.status()
+        );
+
+        // The same operator can't approve twice
+        assert!(injection.add_approval(approval("first")));
+        assert!(!injection.add_approval(approval("first")));
+        assert_eq!(
+            InjectionStatus::Pending {
+                approvals: 1,
+                required: REQUIRED_OPERATOR_APPROVALS
+            },
+            injection.status()
+        );
+
+        assert!(injection.add_approval(approval("second")));
+        assert_eq!(InjectionStatus::Approved, injection.status());
+
+        injection.injected_at = Some(OffsetDateTime::now_utc());
+        assert_eq!(InjectionStatus::Injected, injection.status());
+    }
+}
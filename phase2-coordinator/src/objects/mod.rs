@@ -1,6 +1,27 @@
+pub mod announcement;
+pub use announcement::*;
+
+pub mod badge;
+pub use badge::*;
+
+pub mod ceremony_metadata;
+pub use ceremony_metadata::*;
+
+pub mod ceremony_parameters;
+pub use ceremony_parameters::*;
+
+pub mod ceremony_phase;
+pub use ceremony_phase::*;
+
 pub mod chunk;
 pub use chunk::*;
 
+pub mod circuit_registry;
+pub use circuit_registry::*;
+
+pub mod computation_proof;
+pub use computation_proof::*;
+
 pub mod contribution;
 pub use contribution::*;
 
@@ -10,11 +31,62 @@ pub use contribution_file_signature::*;
 pub mod contribution_info;
 pub use contribution_info::*;
 
+pub mod contribution_session;
+pub use contribution_session::*;
+
+pub mod contributor_queue;
+pub use contributor_queue::*;
+
+pub mod destruction_attestation;
+pub use destruction_attestation::*;
+
+pub mod genesis_seed;
+pub use genesis_seed::*;
+
+pub mod human_verification;
+pub use human_verification::*;
+
+pub mod identity;
+pub use identity::*;
+
+pub mod lifecycle_phase;
+pub use lifecycle_phase::*;
+
+pub mod manual_contribution;
+pub use manual_contribution::*;
+
+pub mod offline_contribution;
+pub use offline_contribution::*;
+
 pub mod participant;
 pub use participant::*;
 
+pub mod participant_history;
+pub use participant_history::*;
+
+pub mod queue_shuffle;
+pub use queue_shuffle::*;
+
+pub mod reverification;
+pub use reverification::*;
+
 pub mod round;
 pub use round::*;
 
 pub mod task;
 pub use task::Task;
+
+pub mod verification_quarantine;
+pub use verification_quarantine::*;
+
+pub mod verification_status;
+pub use verification_status::*;
+
+pub mod verification_quorum;
+pub use verification_quorum::*;
+
+pub mod verifier_attestation;
+pub use verifier_attestation::*;
+
+pub mod verifier_delegation;
+pub use verifier_delegation::*;
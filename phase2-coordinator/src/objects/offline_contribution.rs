@@ -0,0 +1,64 @@
+use crate::{
+    objects::ContributionFileSignature,
+    storage::{ContributionLocator, ContributionSignatureLocator},
+};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The description of a challenge to be computed on an air-gapped machine. It is downloaded by the online client
+/// together with the challenge file and both are manually transferred to the offline machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeBundle {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    /// The ID of the contribution to produce.
+    pub contribution_id: u64,
+    /// Hex encoded hash of the challenge file.
+    pub challenge_hash: String,
+    /// The time after which the contributor is dropped if the response bundle has not been uploaded.
+    pub deadline: OffsetDateTime,
+}
+
+impl ChallengeBundle {
+    /// Returns the locator of the contribution file computed from this challenge.
+    pub fn contribution_locator(&self) -> ContributionLocator {
+        ContributionLocator::new(self.round_height, self.chunk_id, self.contribution_id, false)
+    }
+
+    /// Returns the locator of the signature of the contribution file computed from this challenge.
+    pub fn contribution_signature_locator(&self) -> ContributionSignatureLocator {
+        ContributionSignatureLocator::new(self.round_height, self.chunk_id, self.contribution_id, false)
+    }
+}
+
+/// The result of the computation on an air-gapped machine, signed there with the key of the contributor and manually
+/// transferred back to the online client together with the contribution file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseBundle {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+    /// Signature of the hashes of the challenge and the contribution file.
+    pub contribution_file_signature: ContributionFileSignature,
+}
+
+impl ResponseBundle {
+    /// Creates the response bundle answering the given challenge bundle.
+    pub fn new(challenge_bundle: &ChallengeBundle, contribution_file_signature: ContributionFileSignature) -> Self {
+        Self {
+            round_height: challenge_bundle.round_height,
+            chunk_id: challenge_bundle.chunk_id,
+            contribution_id: challenge_bundle.contribution_id,
+            contribution_file_signature,
+        }
+    }
+
+    /// Returns `true` if this response has been computed from the given challenge bundle.
+    pub fn answers(&self, challenge_bundle: &ChallengeBundle) -> bool {
+        self.round_height == challenge_bundle.round_height
+            && self.chunk_id == challenge_bundle.chunk_id
+            && self.contribution_id == challenge_bundle.contribution_id
+            && self.contribution_file_signature.get_challenge_hash() == challenge_bundle.challenge_hash
+    }
+}
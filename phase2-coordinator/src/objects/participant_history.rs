@@ -0,0 +1,178 @@
+use crate::objects::{DestructionMethod, Participant};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+/// An event in the lifecycle of a participant of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParticipantEvent {
+    /// The participant joined the queue of contributors.
+    JoinedQueue,
+    /// The participant left the queue before being assigned to a round.
+    Withdrew,
+    /// The participant completed its contribution in the given round.
+    Contributed { round_height: u64 },
+    /// The participant registered the public post attesting its contribution in the given round, archived with the
+    /// hash of its content at the time of registration.
+    AttestationPosted {
+        round_height: u64,
+        url: String,
+        content_hash: String,
+    },
+    /// The participant attested the destruction of the toxic waste of its contribution in the given round.
+    DestructionAttested {
+        round_height: u64,
+        method: DestructionMethod,
+    },
+    /// The participant didn't attest the destruction of the toxic waste of its contribution in the given round before
+    /// the deadline.
+    DestructionAttestationMissed { round_height: u64 },
+    /// The participant was dropped for exceeding one of the timeouts of the ceremony.
+    TimedOut,
+    /// The participant was dropped by the coordinator.
+    Dropped,
+    /// The participant was banned from the ceremony.
+    Banned,
+    /// The participant was removed from the banned list.
+    Unbanned,
+}
+
+/// The final disposition of a participant, given by the last relevant event of its history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Disposition {
+    Queued,
+    Withdrew,
+    Contributed,
+    TimedOut,
+    Dropped,
+    Banned,
+    Unbanned,
+}
+
+impl From<&ParticipantEvent> for Disposition {
+    fn from(event: &ParticipantEvent) -> Self {
+        match event {
+            ParticipantEvent::JoinedQueue => Disposition::Queued,
+            ParticipantEvent::Withdrew => Disposition::Withdrew,
+            ParticipantEvent::Contributed { .. } => Disposition::Contributed,
+            ParticipantEvent::AttestationPosted { .. } => Disposition::Contributed,
+            ParticipantEvent::DestructionAttested { .. } => Disposition::Contributed,
+            ParticipantEvent::DestructionAttestationMissed { .. } => Disposition::Contributed,
+            ParticipantEvent::TimedOut => Disposition::TimedOut,
+            ParticipantEvent::Dropped => Disposition::Dropped,
+            ParticipantEvent::Banned => Disposition::Banned,
+            ParticipantEvent::Unbanned => Disposition::Unbanned,
+        }
+    }
+}
+
+/// An entry of the audit log of the participants.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Sequential identifier of the entry in the audit log.
+    pub id: u64,
+    pub timestamp: OffsetDateTime,
+    pub participant: Participant,
+    pub event: ParticipantEvent,
+}
+
+/// The summary of the history of a participant, with the entries of the audit log concerning it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipantRecord {
+    pub participant: Participant,
+    pub disposition: Disposition,
+    pub first_seen: OffsetDateTime,
+    pub last_seen: OffsetDateTime,
+    pub audit_entries: Vec<AuditEntry>,
+}
+
+/// Append-only audit log of the events of all the participants ever seen by the coordinator.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ParticipantHistory {
+    entries: Vec<AuditEntry>,
+}
+
+impl ParticipantHistory {
+    /// Appends an event of the given participant to the audit log and returns the id of the new entry.
+    pub fn record(&mut self, participant: &Participant, event: ParticipantEvent, timestamp: OffsetDateTime) -> u64 {
+        let id = self.entries.len() as u64;
+        self.entries.push(AuditEntry {
+            id,
+            timestamp,
+            participant: participant.clone(),
+            event,
+        });
+
+        id
+    }
+
+    /// Returns all the entries of the audit log, in chronological order.
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Returns the records of all the participants ever seen, ordered by first appearance.
+    pub fn records(&self) -> Vec<ParticipantRecord> {
+        let mut records: Vec<ParticipantRecord> = vec![];
+        let mut positions: HashMap<&Participant, usize> = HashMap::new();
+
+        for entry in &self.entries {
+            match positions.get(&entry.participant) {
+                Some(position) => {
+                    let record = &mut records[*position];
+                    record.disposition = Disposition::from(&entry.event);
+                    record.last_seen = entry.timestamp;
+                    record.audit_entries.push(entry.clone());
+                }
+                None => {
+                    positions.insert(&entry.participant, records.len());
+                    records.push(ParticipantRecord {
+                        participant: entry.participant.clone(),
+                        disposition: Disposition::from(&entry.event),
+                        first_seen: entry.timestamp,
+                        last_seen: entry.timestamp,
+                        audit_entries: vec![entry.clone()],
+                    });
+                }
+            }
+        }
+
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participant_records() {
+        let contributor = Participant::new_contributor("contributor");
+        let other = Participant::new_contributor("other");
+        let start = OffsetDateTime::now_utc();
+        let end = start + time::Duration::minutes(10);
+
+        let mut history = ParticipantHistory::default();
+        history.record(&contributor, ParticipantEvent::JoinedQueue, start);
+        history.record(&other, ParticipantEvent::JoinedQueue, start);
+        history.record(&other, ParticipantEvent::TimedOut, end);
+        let id = history.record(&contributor, ParticipantEvent::Contributed { round_height: 1 }, end);
+        assert_eq!(3, id);
+
+        let records = history.records();
+        assert_eq!(2, records.len());
+
+        assert_eq!(records[0].participant, contributor);
+        assert_eq!(records[0].disposition, Disposition::Contributed);
+        assert_eq!(records[0].first_seen, start);
+        assert_eq!(records[0].last_seen, end);
+        assert_eq!(
+            records[0].audit_entries.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![0, 3]
+        );
+
+        assert_eq!(records[1].participant, other);
+        assert_eq!(records[1].disposition, Disposition::TimedOut);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::objects::Participant;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::convert::TryInto;
+
+/// The name of the algorithm shuffling the queue, published with each shuffle.
+pub const QUEUE_SHUFFLE_ALGORITHM: &str = "sha256-fisher-yates-v1";
+/// The domain separator of the seeds of the shuffles.
+const QUEUE_SHUFFLE_DOMAIN: &[u8] = b"namada-queue-shuffle";
+
+/// The shuffle of the eligible contributors of the queue assigned to a round, when `NAMADA_MPC_QUEUE_SHUFFLE` is set.
+///
+/// The seed is `SHA-256("namada-queue-shuffle" || round_height as u64 little endian || response_hash)`, where the
+/// response hash is the hash of the response of the final contribution of the previous round, so that nobody knows it
+/// before the previous round completes. The queue is shuffled by a Fisher-Yates shuffle: for `i` from `len - 1` down to
+/// `1`, the entries `i` and `j` are swapped, with `j` the first 8 bytes of `SHA-256(seed || i as u64 little endian)`,
+/// read as a little endian integer, modulo `i + 1`. The contributors are then assigned to the rounds in the order of the
+/// shuffled queue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueShuffle {
+    /// The round assigned to the first contributors of the shuffled queue.
+    pub round_height: u64,
+    /// Hex encoded hash of the response of the final contribution of the previous round.
+    pub response_hash: String,
+    /// Hex encoded seed of the shuffle.
+    pub seed: String,
+    pub algorithm: String,
+    /// The eligible contributors of the queue in order of joining, before the shuffle, as of the latest update of the
+    /// queue before the start of the round.
+    pub queue: Vec<Participant>,
+}
+
+impl QueueShuffle {
+    /// Returns the shuffle of the queue for the given round, seeded by the given hex encoded response hash of the
+    /// previous round.
+    pub fn new(round_height: u64, response_hash: &str) -> Result<Self, hex::FromHexError> {
+        let mut hasher = Sha256::new();
+        hasher.update(QUEUE_SHUFFLE_DOMAIN);
+        hasher.update(round_height.to_le_bytes());
+        hasher.update(hex::decode(response_hash)?);
+
+        Ok(Self {
+            round_height,
+            response_hash: response_hash.to_lowercase(),
+            seed: hex::encode(hasher.finalize()),
+            algorithm: QUEUE_SHUFFLE_ALGORITHM.to_string(),
+            queue: vec![],
+        })
+    }
+
+    /// Returns the indices in the unshuffled queue of the entries of the shuffled queue of the given length.
+    pub fn order(&self, len: usize) -> Vec<usize> {
+        // The seed is encoded by [`QueueShuffle::new`]
+        let seed = hex::decode(&self.seed).unwrap_or_default();
+
+        let mut order: Vec<usize> = (0..len).collect();
+        for i in (1..len).rev() {
+            let mut hasher = Sha256::new();
+            hasher.update(&seed);
+            hasher.update((i as u64).to_le_bytes());
+            let draw = u64::from_le_bytes(hasher.finalize()[..8].try_into().expect("A digest is 32 bytes long"));
+            order.swap(i, (draw % (i as u64 + 1)) as usize);
+        }
+
+        order
+    }
+
+    /// Returns the queue in the shuffled order.
+    pub fn shuffled(&self) -> Vec<Participant> {
+        self.order(self.queue.len())
+            .into_iter()
+            .map(|index| self.queue[index].clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_shuffle() {
+        let shuffle = QueueShuffle::new(2, "00FF").unwrap();
+        assert_eq!(shuffle.response_hash, "00ff");
+        assert_eq!(shuffle.algorithm, QUEUE_SHUFFLE_ALGORITHM);
+        assert_eq!(shuffle, QueueShuffle::new(2, "00ff").unwrap());
+        assert_ne!(shuffle.seed, QueueShuffle::new(3, "00ff").unwrap().seed);
+        assert_ne!(shuffle.seed, QueueShuffle::new(2, "00fe").unwrap().seed);
+        assert!(QueueShuffle::new(2, "xyz").is_err());
+
+        // The order is a deterministic permutation
+        let mut order = shuffle.order(16);
+        assert_eq!(order, shuffle.order(16));
+        assert_ne!(order, (0..16).collect::<Vec<_>>());
+        order.sort_unstable();
+        assert_eq!(order, (0..16).collect::<Vec<_>>());
+        assert!(shuffle.order(0).is_empty());
+        assert_eq!(shuffle.order(1), vec![0]);
+
+        let mut shuffle = shuffle;
+        shuffle.queue = (0..4).map(|i| Participant::new_contributor(&i.to_string())).collect();
+        let shuffled = shuffle.shuffled();
+        for (position, index) in shuffle.order(4).into_iter().enumerate() {
+            assert_eq!(shuffled[position], shuffle.queue[index]);
+        }
+    }
+}
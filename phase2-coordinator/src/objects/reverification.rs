@@ -0,0 +1,165 @@
+use crate::{
+    authentication::Signature,
+    objects::{Task, SOFTWARE_VERSION},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A contribution of a completed round queued by an operator to be verified again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReverificationTask {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+}
+
+impl ReverificationTask {
+    pub fn new(round_height: u64, task: &Task) -> Self {
+        Self {
+            round_height,
+            chunk_id: task.chunk_id(),
+            contribution_id: task.contribution_id(),
+        }
+    }
+
+    /// Returns the task of the contribution.
+    pub fn task(&self) -> Task {
+        Task::new(self.chunk_id, self.contribution_id)
+    }
+}
+
+/// The outcome of the re-verification of a contribution of a completed round. Unlike a [`VerifierAttestation`], a
+/// report neither changes the progression of the ceremony nor the manifests of the transcript, it only states whether
+/// the contribution still verifies with the current build of the coordinator.
+///
+/// [`VerifierAttestation`]: crate::objects::VerifierAttestation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReverificationReport {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+    /// The public key of the verifier.
+    pub verifier: String,
+    /// The build of the software which ran the re-verification.
+    pub software_version: String,
+    /// Hex encoded hash of the response file, empty if it couldn't be read.
+    pub response_hash: String,
+    /// The error of a failed re-verification.
+    pub error: Option<String>,
+    /// Unix timestamp of the re-verification.
+    pub timestamp: i64,
+    /// Signature of the verifier on [`ReverificationReport::message`].
+    pub signature: String,
+}
+
+impl ReverificationReport {
+    /// Signs the report of the re-verification of the given task with the key of the verifier.
+    pub fn sign(
+        signature: &dyn Signature,
+        task: &ReverificationTask,
+        verifier: String,
+        verifier_signing_key: &str,
+        outcome: Result<String, String>,
+        timestamp: i64,
+    ) -> anyhow::Result<Self> {
+        let (response_hash, error) = match outcome {
+            Ok(response_hash) => (response_hash, None),
+            Err(error) => (String::new(), Some(error)),
+        };
+        let mut report = Self {
+            round_height: task.round_height,
+            chunk_id: task.chunk_id,
+            contribution_id: task.contribution_id,
+            verifier,
+            software_version: SOFTWARE_VERSION.to_string(),
+            response_hash,
+            error,
+            timestamp,
+            signature: String::new(),
+        };
+        report.signature = signature.sign(verifier_signing_key, &report.message())?;
+
+        Ok(report)
+    }
+
+    /// Returns `true` if the contribution verified again.
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns the message signed by the verifier.
+    pub fn message(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}",
+            self.round_height,
+            self.chunk_id,
+            self.contribution_id,
+            self.verifier,
+            self.software_version,
+            self.response_hash,
+            self.error.as_deref().unwrap_or_default(),
+            self.timestamp
+        )
+    }
+
+    /// Returns `true` if the report is signed by its verifier.
+    pub fn verify(&self, signature: &dyn Signature) -> bool {
+        signature.verify(&self.verifier, &self.message(), &self.signature)
+    }
+
+    /// Returns the re-verified task.
+    pub fn task(&self) -> ReverificationTask {
+        ReverificationTask {
+            round_height: self.round_height,
+            chunk_id: self.chunk_id,
+            contribution_id: self.contribution_id,
+        }
+    }
+}
+
+/// The contributions of completed rounds waiting to be verified again, in order, and the reports of the latest
+/// re-verification of the contributions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReverificationStatus {
+    pub queue: Vec<ReverificationTask>,
+    pub reports: Vec<ReverificationReport>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn test_sign_reverification_report() {
+        let keypair = KeyPair::new();
+        let task = ReverificationTask::new(1, &Task::new(0, 1));
+        let report = ReverificationReport::sign(
+            &Production,
+            &task,
+            keypair.pubkey().to_owned(),
+            keypair.sigkey(),
+            Ok("aa".to_string()),
+            0,
+        )
+        .unwrap();
+        assert!(report.succeeded());
+        assert_eq!(report.task(), task);
+        assert!(report.verify(&Production));
+
+        // A failed re-verification can't be passed off as a successful one
+        let failed = ReverificationReport::sign(
+            &Production,
+            &task,
+            keypair.pubkey().to_owned(),
+            keypair.sigkey(),
+            Err("VerificationFailed".to_string()),
+            0,
+        )
+        .unwrap();
+        assert!(!failed.succeeded());
+        let mut tampered = failed.clone();
+        tampered.error = None;
+        assert!(!tampered.verify(&Production));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     environment::Environment,
-    objects::{participant::*, Chunk},
+    objects::{participant::*, Chunk, CircuitId},
     storage::{
         ContributionLocator, ContributionSignatureLocator, Disk, Locator, LocatorPath, Object, StorageAction,
         StorageLocator, UpdateAction,
@@ -35,6 +35,24 @@ pub struct LockedLocators {
     current_contribution: ContributionLocator,
     next_contribution: ContributionLocator,
     next_contribution_file_signature: ContributionSignatureLocator,
+    /// The circuits targeted by the contribution to the locked chunk.
+    #[serde(default)]
+    circuits: Vec<CircuitId>,
+    /// The sizes of the files to download and upload, missing from the locators of older coordinators.
+    #[serde(default)]
+    sizes: Option<ArtifactSizes>,
+}
+
+/// The sizes in bytes of the files exchanged for a contribution, so that the clients can preallocate them and show the
+/// progress of the transfers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactSizes {
+    /// The size of the challenge to download.
+    pub challenge: u64,
+    /// The expected size of the contribution to upload.
+    pub contribution: u64,
+    /// The expected size of the signature of the contribution to upload.
+    pub contribution_file_signature: u64,
 }
 
 impl LockedLocators {
@@ -57,6 +75,28 @@ impl LockedLocators {
     pub fn next_contribution_file_signature(&self) -> ContributionSignatureLocator {
         self.next_contribution_file_signature
     }
+
+    /// Get a reference to the circuits targeted by the contribution.
+    pub fn circuits(&self) -> &[CircuitId] {
+        &self.circuits
+    }
+
+    /// Set the circuits targeted by the contribution.
+    pub(crate) fn with_circuits(mut self, circuits: Vec<CircuitId>) -> Self {
+        self.circuits = circuits;
+        self
+    }
+
+    /// Get the sizes of the files to download and upload, if reported by the coordinator.
+    pub fn sizes(&self) -> Option<ArtifactSizes> {
+        self.sizes
+    }
+
+    /// Set the sizes of the files to download and upload.
+    pub(crate) fn with_sizes(mut self, sizes: ArtifactSizes) -> Self {
+        self.sizes = Some(sizes);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, SerdeDiff)]
@@ -174,6 +214,18 @@ impl Round {
         self.height
     }
 
+    /// Returns the time at which the round started, if known.
+    #[inline]
+    pub fn started_at(&self) -> Option<OffsetDateTime> {
+        self.started_at
+    }
+
+    /// Returns the time at which the last contribution of the round was verified, if the round is complete.
+    #[inline]
+    pub fn finished_at(&self) -> Option<OffsetDateTime> {
+        self.finished_at
+    }
+
     /// Returns the number of contributors authorized for this round.
     #[inline]
     pub fn number_of_contributors(&self) -> u64 {
@@ -538,33 +590,8 @@ impl Round {
                     return Err(CoordinatorError::UnauthorizedChunkContributor);
                 }
 
-                // Fetch the current round height.
-                let current_round_height = self.round_height();
-                // Fetch the current contribution ID.
-                let current_contribution_id = self.chunk(chunk_id)?.current_contribution_id();
-                // Fetch if this is the first round.
-                let is_initial_round = current_round_height == 1;
-                // Fetch if this is the initial contribution.
-                let is_initial_contribution = current_contribution_id == 0;
-                // Fetch the final contribution ID from the previous round.
-                let previous_final_id = self.expected_number_of_contributions() - 1;
-                // Fetch the previous contribution locator.
-                let previous_contribution = match (is_initial_round, is_initial_contribution) {
-                    // This is the initial contribution in the initial round, return the verified response from the previous round.
-                    (true, true) => ContributionLocator::new(0, chunk_id, 0, true),
-                    // This is the initial contribution in the chunk, return the final response from the previous round.
-                    (false, true) => {
-                        ContributionLocator::new(current_round_height - 1, chunk_id, previous_final_id, false)
-                    }
-                    // This is a typical contribution in the chunk, return the previous response from this round.
-                    (true, false) | (false, false) => {
-                        ContributionLocator::new(current_round_height, chunk_id, current_contribution_id - 1, false)
-                    }
-                };
-
-                // Fetch the current contribution locator.
-                let current_contribution =
-                    ContributionLocator::new(current_round_height, chunk_id, current_contribution_id, true);
+                // Fetch the previous and current contribution locators.
+                let (previous_contribution, current_contribution) = self.contribution_locators(chunk_id)?;
 
                 // This call enforces a strict check that the
                 // next contribution locator does NOT exist and
@@ -581,6 +608,8 @@ impl Round {
                     current_contribution,
                     next_contribution,
                     next_contribution_file_signature,
+                    circuits: vec![],
+                    sizes: None,
                 }
             }
             Participant::Verifier(_) => {
@@ -635,6 +664,86 @@ impl Round {
         Ok(locked_locators)
     }
 
+    ///
+    /// Returns the locators of the files locked by the given contributor
+    /// for the given chunk ID, to resume a contribution interrupted after
+    /// the lock was acquired.
+    ///
+    /// If the chunk is NOT locked by the given contributor,
+    /// this function will return a `CoordinatorError`.
+    ///
+    pub(crate) fn locked_locators(
+        &self,
+        chunk_id: u64,
+        participant: &Participant,
+    ) -> Result<LockedLocators, CoordinatorError> {
+        if !participant.is_contributor() {
+            return Err(CoordinatorError::ExpectedContributor);
+        }
+
+        // Check that the chunk is still locked by the given contributor.
+        let chunk = self.chunk(chunk_id)?;
+        if !chunk.is_locked_by(participant) {
+            return Err(CoordinatorError::ParticipantDidntLockChunkId);
+        }
+
+        // Fetch the previous and current contribution locators.
+        let (previous_contribution, current_contribution) = self.contribution_locators(chunk_id)?;
+        // Fetch the next contribution ID, the files of which were initialized when the lock was acquired.
+        let next_contribution_id = chunk.next_contribution_id(self.expected_number_of_contributions())?;
+
+        Ok(LockedLocators {
+            previous_contribution,
+            current_contribution,
+            next_contribution: ContributionLocator::new(self.round_height(), chunk_id, next_contribution_id, false),
+            next_contribution_file_signature: ContributionSignatureLocator::new(
+                self.round_height(),
+                chunk_id,
+                next_contribution_id,
+                false,
+            ),
+            circuits: vec![],
+            sizes: None,
+        })
+    }
+
+    ///
+    /// Returns the locators of the previous and current contributions
+    /// for a given chunk ID.
+    ///
+    fn contribution_locators(
+        &self,
+        chunk_id: u64,
+    ) -> Result<(ContributionLocator, ContributionLocator), CoordinatorError> {
+        // Fetch the current round height.
+        let current_round_height = self.round_height();
+        // Fetch the current contribution ID.
+        let current_contribution_id = self.chunk(chunk_id)?.current_contribution_id();
+        // Fetch if this is the first round.
+        let is_initial_round = current_round_height == 1;
+        // Fetch if this is the initial contribution.
+        let is_initial_contribution = current_contribution_id == 0;
+        // Fetch the final contribution ID from the previous round.
+        let previous_final_id = self.expected_number_of_contributions() - 1;
+        // Fetch the previous contribution locator.
+        let previous_contribution = match (is_initial_round, is_initial_contribution) {
+            // This is the initial contribution in the initial round, return the verified response from the previous round.
+            (true, true) => ContributionLocator::new(0, chunk_id, 0, true),
+            // This is the initial contribution in the chunk, return the final response from the previous round.
+            (false, true) => ContributionLocator::new(current_round_height - 1, chunk_id, previous_final_id, false),
+            // This is a typical contribution in the chunk, return the previous response from this round.
+            (true, false) | (false, false) => {
+                ContributionLocator::new(current_round_height, chunk_id, current_contribution_id - 1, false)
+            }
+        };
+
+        // Fetch the current contribution locator.
+        let current_contribution =
+            ContributionLocator::new(current_round_height, chunk_id, current_contribution_id, true);
+
+        Ok((previous_contribution, current_contribution))
+    }
+
     /// Initialize the files for the next challenge
     pub fn initialize_verifier_response_files(
         &self,
@@ -730,12 +839,14 @@ impl Round {
             current_contribution,
             next_contribution,
             next_contribution_file_signature,
+            circuits: vec![],
+            sizes: None,
         })
     }
 
     ///
     /// Updates the contribution corresponding to a given chunk ID and
-    /// contribution ID as verified.
+    /// contribution ID as verified, at the given time.
     ///
     /// This function assumes the current contribution already has
     /// a verifier assigned to it.
@@ -748,6 +859,7 @@ impl Round {
         participant: Participant,
         verified_locator: LocatorPath,
         verified_signature_locator: LocatorPath,
+        verified_at: OffsetDateTime,
     ) -> Result<(), CoordinatorError> {
         // Set the current contribution as verified for the given chunk ID.
         self.chunk_mut(chunk_id)?.verify_contribution(
@@ -758,8 +870,8 @@ impl Round {
         )?;
 
         // If all chunks are complete and the finished at timestamp has not been set yet,
-        // then set it with the current UTC timestamp.
-        self.try_finish(OffsetDateTime::now_utc());
+        // then set it with the verification timestamp.
+        self.try_finish(verified_at);
 
         Ok(())
     }
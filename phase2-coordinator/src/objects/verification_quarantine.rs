@@ -0,0 +1,94 @@
+use crate::objects::Task;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The attempts of the verification of a contribution of the current round which crashed the verifier, e.g. a sandboxed
+/// worker killed on exceeding its limits. Once the attempts exceed the maximum number of retries of the environment,
+/// the contribution is quarantined: it's no longer verified automatically and waits for a decision of the operators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationRetries {
+    pub round_height: u64,
+    pub task: Task,
+    /// The number of attempts which crashed the verifier.
+    pub attempts: u32,
+    /// The error of the latest attempt.
+    pub last_error: String,
+    pub last_attempt_at: OffsetDateTime,
+    /// The time at which the contribution was quarantined, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quarantined_at: Option<OffsetDateTime>,
+}
+
+impl VerificationRetries {
+    pub fn new(round_height: u64, task: Task, now: OffsetDateTime) -> Self {
+        Self {
+            round_height,
+            task,
+            attempts: 0,
+            last_error: String::new(),
+            last_attempt_at: now,
+            quarantined_at: None,
+        }
+    }
+
+    /// Records an attempt which crashed the verifier, and quarantines the contribution once the attempts exceed the
+    /// given maximum number of retries. Returns `true` if the contribution was quarantined by this attempt.
+    pub fn record_crash(&mut self, error: String, max_retries: u32, now: OffsetDateTime) -> bool {
+        self.attempts += 1;
+        self.last_error = error;
+        self.last_attempt_at = now;
+
+        if self.quarantined_at.is_none() && self.attempts > max_retries {
+            self.quarantined_at = Some(now);
+            return true;
+        }
+
+        false
+    }
+
+    #[inline]
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined_at.is_some()
+    }
+}
+
+/// The decision of the operators on a quarantined contribution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineDecision {
+    /// Reject the contribution: the round is rolled back and the contributor banned, as for an invalid contribution.
+    Reject,
+    /// Verify the contribution once more regardless of its attempts, e.g. after raising the limits of the sandbox.
+    Verify,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_crash() {
+        let now = OffsetDateTime::from_unix_timestamp(1672531200).unwrap();
+        let mut retries = VerificationRetries::new(1, Task::new(0, 1), now);
+
+        // The first attempt and the retries
+        for _ in 0..2 {
+            assert!(!retries.record_crash(String::from("Worker exited with signal: 9"), 2, now));
+            assert!(!retries.is_quarantined());
+        }
+        assert!(retries.record_crash(String::from("Worker exited with signal: 9"), 2, now));
+        assert_eq!(retries.quarantined_at, Some(now));
+        assert_eq!(retries.attempts, 3);
+
+        // A manual verification crashing again keeps the time of the quarantine
+        let later = now + time::Duration::hours(1);
+        assert!(!retries.record_crash(String::from("Worker exited with signal: 24"), 2, later));
+        assert_eq!(retries.quarantined_at, Some(now));
+        assert_eq!(retries.last_attempt_at, later);
+        assert_eq!(
+            serde_json::from_value::<QuarantineDecision>(serde_json::json!("reject")).unwrap(),
+            QuarantineDecision::Reject
+        );
+    }
+}
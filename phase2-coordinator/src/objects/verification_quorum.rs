@@ -0,0 +1,145 @@
+use crate::objects::{Participant, Task};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// The attestation of a verifier on the result of the verification of a contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationVote {
+    pub verifier: Participant,
+    /// Hex encoded hash of the verified contribution, i.e. of the next challenge.
+    pub next_challenge_hash: String,
+    /// Signature of the verifier on [VerificationVote::message].
+    pub signature: String,
+    pub timestamp: OffsetDateTime,
+}
+
+impl VerificationVote {
+    /// Returns the message signed by a verifier to vote on the verification of the given contribution.
+    pub fn message(round_height: u64, task: &Task, next_challenge_hash: &str) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            round_height,
+            task.chunk_id(),
+            task.contribution_id(),
+            next_challenge_hash
+        )
+    }
+}
+
+/// The outcome of the votes collected on the verification of a contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuorumStatus {
+    /// Not enough verifiers have voted yet.
+    Pending { votes: usize, quorum: usize },
+    /// The quorum of verifiers agreed on the given next challenge hash.
+    Agreed { next_challenge_hash: String },
+    /// The verifiers produced different next challenges for the same contribution.
+    Disagreement { next_challenge_hashes: Vec<String> },
+}
+
+/// The votes of the verifiers on the verification of a single contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationQuorum {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+    pub votes: Vec<VerificationVote>,
+}
+
+impl VerificationQuorum {
+    pub fn new(round_height: u64, task: &Task) -> Self {
+        Self {
+            round_height,
+            chunk_id: task.chunk_id(),
+            contribution_id: task.contribution_id(),
+            votes: Vec::new(),
+        }
+    }
+
+    /// Returns the task of the contribution this quorum is about.
+    pub fn task(&self) -> Task {
+        Task::new(self.chunk_id, self.contribution_id)
+    }
+
+    /// Returns `true` if the given verifier already voted.
+    pub fn has_voted(&self, verifier: &Participant) -> bool {
+        self.votes.iter().any(|vote| &vote.verifier == verifier)
+    }
+
+    /// Adds the vote of a verifier. Returns `false`, leaving the quorum untouched,
+    /// if the verifier already voted.
+    pub fn add_vote(&mut self, vote: VerificationVote) -> bool {
+        if self.has_voted(&vote.verifier) {
+            return false;
+        }
+
+        self.votes.push(vote);
+        true
+    }
+
+    /// Returns the outcome of the votes given the number of verifiers that must agree.
+    pub fn status(&self, quorum: usize) -> QuorumStatus {
+        let mut next_challenge_hashes: Vec<String> = Vec::new();
+        for vote in &self.votes {
+            if !next_challenge_hashes.contains(&vote.next_challenge_hash) {
+                next_challenge_hashes.push(vote.next_challenge_hash.clone());
+            }
+        }
+
+        match next_challenge_hashes.len() {
+            0 | 1 if self.votes.len() < quorum => QuorumStatus::Pending {
+                votes: self.votes.len(),
+                quorum,
+            },
+            1 => QuorumStatus::Agreed {
+                next_challenge_hash: next_challenge_hashes.remove(0),
+            },
+            _ => QuorumStatus::Disagreement { next_challenge_hashes },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(verifier: &Participant, next_challenge_hash: &str) -> VerificationVote {
+        VerificationVote {
+            verifier: verifier.clone(),
+            next_challenge_hash: next_challenge_hash.to_string(),
+            signature: String::new(),
+            timestamp: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_verification_quorum_status() {
+        let first = Participant::new_verifier("first");
+        let second = Participant::new_verifier("second");
+        let third = Participant::new_verifier("third");
+
+        let mut quorum = VerificationQuorum::new(1, &Task::new(0, 1));
+        assert_eq!(QuorumStatus::Pending { votes: 0, quorum: 2 }, quorum.status(2));
+
+        assert!(quorum.add_vote(vote(&first, "aa")));
+        assert!(!quorum.add_vote(vote(&first, "aa")));
+        assert_eq!(QuorumStatus::Pending { votes: 1, quorum: 2 }, quorum.status(2));
+
+        assert!(quorum.add_vote(vote(&second, "aa")));
+        assert_eq!(
+            QuorumStatus::Agreed {
+                next_challenge_hash: "aa".to_string()
+            },
+            quorum.status(2)
+        );
+
+        assert!(quorum.add_vote(vote(&third, "bb")));
+        assert_eq!(
+            QuorumStatus::Disagreement {
+                next_challenge_hashes: vec!["aa".to_string(), "bb".to_string()]
+            },
+            quorum.status(2)
+        );
+    }
+}
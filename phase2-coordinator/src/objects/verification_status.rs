@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The status of the verification of the latest contribution submitted by a contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerificationStatus {
+    /// The contribution has been received and is waiting for the verifier.
+    Pending,
+    /// The verifier is processing the contribution, in the given phase of the verification.
+    Running(VerificationPhase),
+    /// The contribution has been verified successfully.
+    Succeeded,
+    /// The contribution failed verification, with the reason of the failure.
+    Failed(String),
+}
+
+impl VerificationStatus {
+    /// Returns `true` if the verification has reached a final state.
+    pub fn is_completed(&self) -> bool {
+        matches!(self, VerificationStatus::Succeeded | VerificationStatus::Failed(_))
+    }
+}
+
+/// The phases of a running verification, in order. The verification doesn't report its progress within a phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationPhase {
+    /// The verifier checks the contribution against its challenge.
+    Verifying,
+    /// The contribution is verified, and waits for the votes of the other verifiers of the quorum.
+    Voting,
+    /// The contribution is verified, and is being recorded in the round.
+    Finalizing,
+}
+
+impl fmt::Display for VerificationPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationPhase::Verifying => write!(f, "verifying the contribution"),
+            VerificationPhase::Voting => write!(f, "waiting for the votes of the other verifiers"),
+            VerificationPhase::Finalizing => write!(f, "recording the verified contribution"),
+        }
+    }
+}
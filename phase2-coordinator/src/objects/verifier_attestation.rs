@@ -0,0 +1,100 @@
+use crate::{authentication::Signature, objects::Task};
+
+use serde::{Deserialize, Serialize};
+
+/// The build of the coordinator software, recorded in the attestations of the verifications it runs.
+pub const SOFTWARE_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The attestation of a verifier on a contribution it verified, published in the manifest of the round and covered by
+/// the hash chain of the transcript, so that the transcript proves who verified the contribution and with which build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierAttestation {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+    /// The public key of the verifier.
+    pub verifier: String,
+    /// The build of the software which ran the verification.
+    pub software_version: String,
+    /// Hex encoded hash of the verification report: the file signature of the verified contribution, which binds the
+    /// hashes of the challenge, the response and the next challenge.
+    pub report_hash: String,
+    /// Signature of the verifier on [`VerifierAttestation::message`].
+    pub signature: String,
+}
+
+impl VerifierAttestation {
+    /// Signs the attestation of the verification of the given task with the key of the verifier.
+    pub fn sign(
+        signature: &dyn Signature,
+        round_height: u64,
+        task: &Task,
+        verifier: String,
+        verifier_signing_key: &str,
+        report_hash: String,
+    ) -> anyhow::Result<Self> {
+        let mut attestation = Self {
+            round_height,
+            chunk_id: task.chunk_id(),
+            contribution_id: task.contribution_id(),
+            verifier,
+            software_version: SOFTWARE_VERSION.to_string(),
+            report_hash,
+            signature: String::new(),
+        };
+        attestation.signature = signature.sign(verifier_signing_key, &attestation.message())?;
+
+        Ok(attestation)
+    }
+
+    /// Returns the message signed by the verifier.
+    pub fn message(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            self.round_height,
+            self.chunk_id,
+            self.contribution_id,
+            self.verifier,
+            self.software_version,
+            self.report_hash
+        )
+    }
+
+    /// Returns `true` if the attestation is signed by its verifier.
+    pub fn verify(&self, signature: &dyn Signature) -> bool {
+        signature.verify(&self.verifier, &self.message(), &self.signature)
+    }
+
+    /// Returns the task of the verified contribution.
+    pub fn task(&self) -> Task {
+        Task::new(self.chunk_id, self.contribution_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn test_sign_verifier_attestation() {
+        let keypair = KeyPair::new();
+        let attestation = VerifierAttestation::sign(
+            &Production,
+            1,
+            &Task::new(0, 1),
+            keypair.pubkey().to_owned(),
+            keypair.sigkey(),
+            "aa".to_string(),
+        )
+        .unwrap();
+        assert_eq!(attestation.software_version, SOFTWARE_VERSION);
+        assert_eq!(attestation.task(), Task::new(0, 1));
+        assert!(attestation.verify(&Production));
+
+        // A tampered attestation is rejected
+        let mut tampered = attestation.clone();
+        tampered.software_version = "phase2-coordinator/0.0.0".to_string();
+        assert!(!tampered.verify(&Production));
+    }
+}
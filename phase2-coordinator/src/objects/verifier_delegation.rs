@@ -0,0 +1,118 @@
+use crate::authentication::Signature;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A delegation issued by the coordinator, authorizing an external verifier which is not one of the quorum verifiers
+/// of the environment to call the verification endpoints for a bounded range of rounds, until it expires. Trusted
+/// helpers can thus be enlisted during load spikes without a change of the configuration of the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifierDelegation {
+    /// The public key of the delegated verifier.
+    pub verifier: String,
+    /// The first round the verifier is allowed to verify.
+    pub from_round: u64,
+    /// The last round the verifier is allowed to verify, inclusive.
+    pub to_round: u64,
+    #[serde(with = "time::serde::timestamp")]
+    pub issued_at: OffsetDateTime,
+    #[serde(with = "time::serde::timestamp")]
+    pub expires_at: OffsetDateTime,
+}
+
+impl VerifierDelegation {
+    /// Returns the message signed by the coordinator: the json encoding of the delegation, with the fields sorted by
+    /// name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the delegation with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedVerifierDelegation> {
+        let delegation_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedVerifierDelegation {
+            delegation: self,
+            coordinator_public_key,
+            signature: delegation_signature,
+        })
+    }
+
+    /// Returns `true` if the delegation allows its verifier to verify the given round at the given time.
+    pub fn covers(&self, round_height: u64, now: OffsetDateTime) -> bool {
+        now < self.expires_at && (self.from_round..=self.to_round).contains(&round_height)
+    }
+}
+
+/// A [`VerifierDelegation`] signed by the coordinator, presented by the delegated verifier with its requests.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedVerifierDelegation {
+    pub delegation: VerifierDelegation,
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedVerifierDelegation {
+    /// Returns `true` if the delegation is signed by the coordinator with the given public key.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        match self.delegation.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Encodes the delegation as the value of a header: its json encoding in base64.
+    pub fn to_header(&self) -> serde_json::Result<String> {
+        Ok(base64::encode(serde_json::to_vec(self)?))
+    }
+
+    /// Decodes the delegation from the value of a header.
+    pub fn from_header(header: &str) -> Option<Self> {
+        serde_json::from_slice(&base64::decode(header).ok()?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authentication::{KeyPair, Production};
+
+    #[test]
+    fn test_sign_verifier_delegation() {
+        let coordinator = KeyPair::new();
+        let now = OffsetDateTime::now_utc();
+        let delegation = VerifierDelegation {
+            verifier: KeyPair::new().pubkey().to_owned(),
+            from_round: 2,
+            to_round: 4,
+            issued_at: now,
+            expires_at: now + time::Duration::hours(1),
+        };
+        assert!(delegation.covers(2, now) && delegation.covers(4, now));
+        assert!(!delegation.covers(1, now) && !delegation.covers(5, now));
+        assert!(!delegation.covers(3, now + time::Duration::hours(1)));
+
+        let signed = delegation
+            .sign(&Production, coordinator.pubkey().to_owned(), coordinator.sigkey())
+            .unwrap();
+        assert!(signed.verify(&Production, coordinator.pubkey()));
+        let decoded = SignedVerifierDelegation::from_header(&signed.to_header().unwrap()).unwrap();
+        assert_eq!(decoded, signed);
+        assert!(SignedVerifierDelegation::from_header("not a delegation").is_none());
+
+        // Another key, or a widened delegation, is rejected
+        assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
+        let mut tampered = signed.clone();
+        tampered.delegation.to_round = 10;
+        assert!(!tampered.verify(&Production, coordinator.pubkey()));
+    }
+}
@@ -0,0 +1,267 @@
+//! Optional proof-of-work required to join the queue of contributors, to make automated flooding of the queue expensive
+//! without requiring an identity from the contributors.
+//!
+//! The proof-of-work is enabled by setting the `POW_DIFFICULTY` env variable to the minimum number of leading zero bits
+//! required in the hash of a solution. The difficulty is then raised automatically with the rate of recent queue joins.
+//! The current phase of the ceremony may override the base difficulty, a difficulty of zero waiving the proof-of-work.
+//! The puzzles and the rate of joins are kept in the [`SharedStore`] of the frontends.
+
+use crate::{
+    objects::Participant,
+    shared_store::{MemoryStore, SharedStore},
+};
+
+use lazy_static::lazy_static;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{sync::Arc, time::Duration};
+use tracing::warn;
+
+lazy_static! {
+    pub static ref POW_DIFFICULTY: Option<u8> = std::env::var("POW_DIFFICULTY")
+        .ok()
+        .map(|difficulty| difficulty.parse().expect("POW_DIFFICULTY must be a number of bits"));
+}
+
+/// Time after which an unsolved puzzle expires.
+const PUZZLE_TTL: Duration = Duration::from_secs(600);
+/// Time window over which the rate of queue joins is computed.
+const JOIN_RATE_WINDOW: Duration = Duration::from_secs(600);
+/// Number of joins in the window above which the difficulty starts to increase.
+const TARGET_JOINS_PER_WINDOW: usize = 20;
+/// Upper bound of the difficulty, in bits.
+const MAX_DIFFICULTY: u8 = 32;
+/// Key of the counter of the recent queue joins in the shared store.
+const JOINS_KEY: &str = "pow:joins";
+
+/// A proof-of-work puzzle bound to the public key of a contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowPuzzle {
+    /// Hex encoded random seed of the puzzle.
+    pub seed: String,
+    /// Number of leading zero bits required in the hash of the solution.
+    pub difficulty: u8,
+}
+
+impl PowPuzzle {
+    /// Checks that the nonce solves the puzzle for the given participant.
+    pub fn verify(&self, participant: &Participant, nonce: u64) -> bool {
+        leading_zero_bits(&self.digest(participant, nonce)) >= self.difficulty as u32
+    }
+
+    /// Searches the first nonce solving the puzzle for the given participant.
+    pub fn solve(&self, participant: &Participant) -> PowSolution {
+        let nonce = (0u64..)
+            .find(|nonce| self.verify(participant, *nonce))
+            .expect("A solution should exist");
+
+        PowSolution {
+            seed: self.seed.clone(),
+            nonce,
+        }
+    }
+
+    fn digest(&self, participant: &Participant, nonce: u64) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.seed.as_bytes());
+        hasher.update(participant.address().as_bytes());
+        hasher.update(nonce.to_le_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// The solution of a [`PowPuzzle`], included in the signed request to join the queue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowSolution {
+    pub seed: String,
+    pub nonce: u64,
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in bytes {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+
+    bits
+}
+
+/// Returns the key of the puzzle issued to the participant in the shared store.
+fn puzzle_key(participant: &Participant) -> String {
+    format!("pow:puzzle:{}", participant.address())
+}
+
+/// Issues the proof-of-work puzzles and checks their solutions. Disabled if no base difficulty is set.
+pub struct ProofOfWork {
+    base_difficulty: Option<u8>,
+    store: Arc<dyn SharedStore>,
+}
+
+impl Default for ProofOfWork {
+    /// Generates a [`ProofOfWork`] instance with the base difficulty configured in the env.
+    fn default() -> Self {
+        Self::new(*POW_DIFFICULTY)
+    }
+}
+
+impl ProofOfWork {
+    pub fn new(base_difficulty: Option<u8>) -> Self {
+        Self::with_store(base_difficulty, Arc::new(MemoryStore::default()))
+    }
+
+    /// Returns a [`ProofOfWork`] keeping its puzzles and the rate of joins in the given store, shared by the frontends.
+    pub fn with_store(base_difficulty: Option<u8>, store: Arc<dyn SharedStore>) -> Self {
+        Self { base_difficulty, store }
+    }
+
+    /// Returns the base difficulty, the one of the current phase of the ceremony if set.
+    fn base_difficulty(&self, phase_difficulty: Option<u8>) -> Option<u8> {
+        match phase_difficulty {
+            Some(0) => None,
+            Some(difficulty) => Some(difficulty),
+            None => self.base_difficulty,
+        }
+    }
+
+    /// Returns `true` if a proof-of-work is required to join the queue, given the difficulty of the current phase of
+    /// the ceremony.
+    pub fn is_enabled(&self, phase_difficulty: Option<u8>) -> bool {
+        self.base_difficulty(phase_difficulty).is_some()
+    }
+
+    /// Returns the current difficulty, increased by one bit every time the number of recent joins doubles over the target.
+    pub fn difficulty(&self, phase_difficulty: Option<u8>) -> Option<u8> {
+        let base_difficulty = self.base_difficulty(phase_difficulty)?;
+
+        // The base difficulty still applies while the store is unavailable
+        let recent_joins = self.store.count(JOINS_KEY).unwrap_or_else(|e| {
+            warn!("Error while reading the rate of queue joins: {}", e);
+            0
+        }) as usize;
+        let mut difficulty = base_difficulty;
+        let mut threshold = TARGET_JOINS_PER_WINDOW;
+        while recent_joins >= threshold && difficulty < MAX_DIFFICULTY {
+            difficulty += 1;
+            threshold *= 2;
+        }
+
+        Some(difficulty.min(MAX_DIFFICULTY))
+    }
+
+    /// Issues a new puzzle for the given participant, replacing any previous one. Returns [`None`] if the proof-of-work
+    /// is disabled.
+    pub fn issue(&self, participant: &Participant, phase_difficulty: Option<u8>) -> anyhow::Result<Option<PowPuzzle>> {
+        let difficulty = match self.difficulty(phase_difficulty) {
+            Some(difficulty) => difficulty,
+            None => return Ok(None),
+        };
+        let mut seed = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut seed);
+
+        let puzzle = PowPuzzle {
+            seed: hex::encode(seed),
+            difficulty,
+        };
+
+        self.store
+            .put(&puzzle_key(participant), &serde_json::to_vec(&puzzle)?, PUZZLE_TTL)?;
+
+        Ok(Some(puzzle))
+    }
+
+    /// Checks the solution to the puzzle issued to the participant. The puzzle can only be used once: on success the join
+    /// is recorded for the tuning of the difficulty. Always succeeds if the proof-of-work is disabled.
+    pub fn check(
+        &self,
+        participant: &Participant,
+        solution: Option<&PowSolution>,
+        phase_difficulty: Option<u8>,
+    ) -> Result<(), String> {
+        if !self.is_enabled(phase_difficulty) {
+            return Ok(());
+        }
+
+        let solution = solution.ok_or_else(|| String::from("missing solution"))?;
+
+        // Expired puzzles are dropped by the store
+        let puzzle: PowPuzzle = match self.store.take(&puzzle_key(participant)) {
+            Ok(Some(puzzle)) => serde_json::from_slice(&puzzle).map_err(|e| e.to_string())?,
+            Ok(None) => return Err(String::from("no unexpired puzzle was issued to the participant")),
+            Err(e) => return Err(format!("the puzzles are unavailable: {}", e)),
+        };
+
+        if puzzle.seed != solution.seed || !puzzle.verify(participant, solution.nonce) {
+            return Err(String::from("invalid solution"));
+        }
+
+        if let Err(e) = self.store.increment(JOINS_KEY, JOIN_RATE_WINDOW) {
+            warn!("Error while recording a queue join: {}", e);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle() {
+        let pow = ProofOfWork::new(Some(8));
+        let participant = Participant::new_contributor("contributor");
+        let other = Participant::new_contributor("other");
+
+        // Missing or wrong solutions are rejected
+        assert!(pow.check(&participant, None, None).is_err());
+        let puzzle = pow.issue(&participant, None).unwrap().unwrap();
+        let solution = puzzle.solve(&participant);
+        assert!(pow.check(&other, Some(&solution), None).is_err());
+        assert!(pow
+            .check(
+                &participant,
+                Some(&PowSolution {
+                    seed: String::from("wrong seed"),
+                    nonce: solution.nonce
+                }),
+                None
+            )
+            .is_err());
+
+        // Puzzles are single use
+        let puzzle = pow.issue(&participant, None).unwrap().unwrap();
+        let solution = puzzle.solve(&participant);
+        assert!(pow.check(&participant, Some(&solution), None).is_ok());
+        assert!(pow.check(&participant, Some(&solution), None).is_err());
+    }
+
+    #[test]
+    fn test_difficulty_tuning() {
+        assert_eq!(ProofOfWork::new(None).difficulty(None), None);
+        assert!(ProofOfWork::new(None)
+            .check(&Participant::new_contributor("c"), None, None)
+            .is_ok());
+
+        let pow = ProofOfWork::new(Some(1));
+        assert_eq!(pow.difficulty(None), Some(1));
+
+        for i in 0..TARGET_JOINS_PER_WINDOW {
+            let participant = Participant::new_contributor(&i.to_string());
+            let solution = pow.issue(&participant, None).unwrap().unwrap().solve(&participant);
+            pow.check(&participant, Some(&solution), None).unwrap();
+        }
+        assert_eq!(pow.difficulty(None), Some(2));
+
+        // The phases of the ceremony override the base difficulty, or waive the proof-of-work
+        assert_eq!(ProofOfWork::new(None).difficulty(Some(4)), Some(4));
+        assert!(!pow.is_enabled(Some(0)));
+        assert_eq!(pow.issue(&Participant::new_contributor("c"), Some(0)).unwrap(), None);
+        assert!(pow.check(&Participant::new_contributor("c"), None, Some(0)).is_ok());
+    }
+}
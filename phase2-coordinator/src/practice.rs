@@ -0,0 +1,239 @@
+//! Practice rounds, for the contributors to try the whole protocol with their setup before their slot in the ceremony.
+//!
+//! A contributor in the queue or in the round locks a practice round, downloads the practice challenge, the parameters
+//! of a tiny test circuit preceded by the header of the challenges, computes its contribution on top of it and uploads
+//! it, to receive the outcome of its verification. The practice rounds live apart from the state of the coordinator:
+//! they don't take the lock of the ceremony, don't move the queue and don't appear in the transcript. A practice round
+//! is forgotten once its contribution is received, or after `PRACTICE_ROUND_TIMEOUT_SECONDS` (30 minutes by default).
+
+use crate::{commands::TestCircuit, hashing::ContributionHasher, objects::Participant};
+
+use bls12_381::Scalar;
+use lazy_static::lazy_static;
+use masp_phase2::{verify_contribution, MPCParameters};
+use serde::{Deserialize, Serialize};
+use setup_utils::blank_hash;
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+lazy_static! {
+    pub static ref PRACTICE_ROUND_TIMEOUT: Duration = std::env::var("PRACTICE_ROUND_TIMEOUT_SECONDS")
+        .ok()
+        .map(|seconds| seconds.parse().expect("Invalid PRACTICE_ROUND_TIMEOUT_SECONDS"))
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_PRACTICE_ROUND_TIMEOUT);
+    /// The practice challenge, the same for every practice round.
+    static ref PRACTICE_CHALLENGE: Vec<u8> = practice_challenge();
+}
+
+/// Default time allowed between the lock of a practice round and the upload of its contribution.
+const DEFAULT_PRACTICE_ROUND_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Maximum size in bytes of a practice contribution, well above the size of the parameters of the test circuit.
+pub const MAX_PRACTICE_CONTRIBUTION_SIZE: u64 = 1 << 20;
+
+/// Returns the practice challenge: a blank header, since no response precedes it, followed by the initial parameters
+/// of the test circuit.
+fn practice_challenge() -> Vec<u8> {
+    let params = MPCParameters::new(TestCircuit { x: Some(Scalar::one()) })
+        .expect("Couldn't initialize the parameters of the practice challenge");
+    let mut challenge = blank_hash().to_vec();
+    params
+        .write(&mut challenge)
+        .expect("Couldn't write the parameters of the practice challenge");
+
+    challenge
+}
+
+/// Returns the practice challenge.
+pub fn challenge() -> &'static [u8] {
+    &PRACTICE_CHALLENGE
+}
+
+/// A practice round locked by a contributor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PracticeLock {
+    /// Size in bytes of the practice challenge.
+    pub challenge_size: u64,
+    /// Hex encoded hash of the practice challenge, which starts the contribution as it starts the contributions to the
+    /// ceremony.
+    pub challenge_hash: String,
+    /// Time in seconds allowed to upload the contribution.
+    pub timeout_seconds: u64,
+}
+
+/// The outcome of the verification of a practice contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PracticeVerification {
+    pub verified: bool,
+    /// Hex encoded hash of the contribution.
+    pub contribution_hash: String,
+    /// Why the contribution was rejected, if it was.
+    pub reason: Option<String>,
+}
+
+/// Verifies the given practice contribution on top of the practice challenge, the same way as the contributions to the
+/// ceremony: the contribution starts with the hash of the challenge, and its parameters hold a new valid contribution
+/// to the ones of the challenge.
+pub fn verify(hasher: &dyn ContributionHasher, contribution: &[u8]) -> PracticeVerification {
+    let contribution_hash = hex::encode(hasher.hash(contribution));
+    let rejected = |reason: &str| PracticeVerification {
+        verified: false,
+        contribution_hash: contribution_hash.clone(),
+        reason: Some(reason.to_owned()),
+    };
+
+    let challenge = challenge();
+    if contribution.get(0..64) != Some(hasher.hash(challenge).as_slice()) {
+        return rejected("the contribution doesn't start with the hash of the practice challenge");
+    }
+    let before = match MPCParameters::read(&challenge[64..], false) {
+        Ok(before) => before,
+        Err(_) => return rejected("the practice challenge can't be read"),
+    };
+    let after = match MPCParameters::read(&contribution[64..], true) {
+        Ok(after) => after,
+        Err(_) => return rejected("the parameters of the contribution are invalid"),
+    };
+    // The verification panics on some invalid contributions
+    match panic::catch_unwind(AssertUnwindSafe(|| verify_contribution(&before, &after))) {
+        Ok(Ok(_)) => PracticeVerification {
+            verified: true,
+            contribution_hash,
+            reason: None,
+        },
+        _ => rejected("the contribution isn't a valid contribution to the practice challenge"),
+    }
+}
+
+/// Keeps track of the practice rounds locked by the contributors.
+#[derive(Clone)]
+pub struct PracticeRounds {
+    timeout: Duration,
+    locks: Arc<Mutex<HashMap<Participant, Instant>>>,
+}
+
+impl Default for PracticeRounds {
+    /// Generates a [`PracticeRounds`] instance with the timeout configured in the env.
+    fn default() -> Self {
+        Self::new(*PRACTICE_ROUND_TIMEOUT)
+    }
+}
+
+impl PracticeRounds {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Locks a new practice round for the given contributor, replacing any previous one.
+    pub fn lock(&self, participant: &Participant, hasher: &dyn ContributionHasher) -> PracticeLock {
+        let mut locks = self.locks.lock().expect("Practice rounds lock poisoned");
+        locks.retain(|_, locked| locked.elapsed() < self.timeout);
+        locks.insert(participant.clone(), Instant::now());
+
+        PracticeLock {
+            challenge_size: challenge().len() as u64,
+            challenge_hash: hex::encode(hasher.hash(challenge())),
+            timeout_seconds: self.timeout.as_secs(),
+        }
+    }
+
+    /// Returns `true` if the contributor holds a practice round which hasn't expired.
+    pub fn is_locked(&self, participant: &Participant) -> bool {
+        self.locks
+            .lock()
+            .expect("Practice rounds lock poisoned")
+            .get(participant)
+            .map_or(false, |locked| locked.elapsed() < self.timeout)
+    }
+
+    /// Releases the practice round of the contributor, to verify its contribution. A practice round takes a single
+    /// contribution.
+    pub fn release(&self, participant: &Participant) -> Result<(), String> {
+        let locked = self
+            .locks
+            .lock()
+            .expect("Practice rounds lock poisoned")
+            .remove(participant)
+            .ok_or_else(|| String::from("no practice round is locked by the contributor"))?;
+
+        match locked.elapsed() < self.timeout {
+            true => Ok(()),
+            false => Err(format!(
+                "the practice round wasn't completed within {} seconds",
+                self.timeout.as_secs()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::HashAlgorithm;
+    use rand::{rngs::OsRng, RngCore, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    fn contribute(hasher: &dyn ContributionHasher) -> Vec<u8> {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let mut params = MPCParameters::read(&challenge()[64..], false).unwrap();
+        params.contribute(&mut ChaChaRng::from_seed(seed), &0u32);
+
+        let mut contribution = hasher.hash(challenge()).to_vec();
+        params.write(&mut contribution).unwrap();
+        contribution
+    }
+
+    #[test]
+    fn test_practice_verification() {
+        let hasher = HashAlgorithm::Blake2b512.hasher();
+
+        let contribution = contribute(hasher);
+        let verification = verify(hasher, &contribution);
+        assert!(verification.verified, "{:?}", verification);
+        assert_eq!(verification.contribution_hash, hex::encode(hasher.hash(&contribution)));
+
+        // The challenge itself holds no new contribution
+        let mut unchanged = hasher.hash(challenge()).to_vec();
+        unchanged.extend_from_slice(&challenge()[64..]);
+        assert!(!verify(hasher, &unchanged).verified);
+
+        // Another header
+        let mut wrong_header = contribution.clone();
+        wrong_header[0] ^= 1;
+        assert!(!verify(hasher, &wrong_header).verified);
+
+        assert!(!verify(hasher, &contribution[..100]).verified);
+    }
+
+    #[test]
+    fn test_practice_rounds() {
+        let hasher = HashAlgorithm::Blake2b512.hasher();
+        let participant = Participant::new_contributor("contributor");
+        let practice = PracticeRounds::new(DEFAULT_PRACTICE_ROUND_TIMEOUT);
+        assert!(!practice.is_locked(&participant));
+        assert!(practice.release(&participant).is_err());
+
+        let lock = practice.lock(&participant, hasher);
+        assert_eq!(lock.challenge_size, challenge().len() as u64);
+        assert!(practice.is_locked(&participant));
+        assert_eq!(practice.release(&participant), Ok(()));
+        assert!(practice.release(&participant).is_err());
+
+        // Expired practice rounds
+        let practice = PracticeRounds::new(Duration::from_millis(1));
+        practice.lock(&participant, hasher);
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!practice.is_locked(&participant));
+        assert!(practice.release(&participant).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! Snapshot of the state of the coordinator before the launch of the ceremony, to run dress rehearsals of the launch.
+//!
+//! The organizers take a [`PrelaunchSnapshot`] once the ceremony is configured and the contributors have registered,
+//! rehearse the launch with the real allowlist, and then restore the snapshot: the rounds and the artifacts of the
+//! rehearsal are wiped, while the configuration, the allowlist of tokens, the tokens and addresses reserved by the
+//! contributors in the queue and their registrations are back to their pre-launch state.
+
+use crate::{backup::Snapshot, objects::Participant};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+};
+
+/// The file name of the pre-launch snapshot, relative to the storage base directory.
+pub const PRELAUNCH_FILE: &str = "prelaunch.json";
+
+/// The pre-launch state of the coordinator.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrelaunchSnapshot {
+    /// The state of the coordinator, with its configuration and the queue of the registered contributors, and the
+    /// state of the rounds, in the format of the backups.
+    pub snapshot: Snapshot,
+    /// The tokens allowed to join the ceremony, by cohort.
+    pub allowlist: Vec<HashSet<String>>,
+    /// The tokens reserved by the contributors in the queue.
+    pub reserved_tokens: HashMap<String, Participant>,
+    /// The IP addresses reserved by the contributors in the queue.
+    pub reserved_ips: HashMap<IpAddr, Participant>,
+}
+
+/// The summary of a pre-launch snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrelaunchSummary {
+    /// The time of the snapshot, as a unix timestamp.
+    pub created_at: i64,
+    pub round_height: u64,
+    /// The number of contributors in the queue.
+    pub registrations: usize,
+    /// The number of tokens of the allowlist.
+    pub allowlist: usize,
+}
+
+/// The outcome of the restore of a pre-launch snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrelaunchRestore {
+    /// The round height of the snapshot, to which the ceremony is back.
+    pub round_height: u64,
+    /// The rounds of the rehearsal removed from storage.
+    pub removed_rounds: Vec<u64>,
+    /// The rounds whose contributions have been removed, with their initial challenges kept.
+    pub cleared_rounds: Vec<u64>,
+    /// The files of the snapshot which are missing from the storage or have a different size.
+    pub mismatches: Vec<String>,
+}
@@ -0,0 +1,337 @@
+//! Progressive publication of the transcript while the ceremony is running, so that the transcript of the completed
+//! rounds stays auditable even if the coordinator disappears before the end of the ceremony.
+//!
+//! Once the ceremony has advanced past a round, the round is complete and its contributions verified. The coordinator
+//! then publishes a delta of the transcript for that round: the files of its manifest, copied at their path in the
+//! storage, and a [`SignedTranscriptDelta`] binding the manifest to the updated head of the hash chain of the
+//! transcript. The deltas are published in the order of the rounds, from round 0, so that anyone can follow the hash
+//! chain from the published files alone. Publication is enabled by setting the `TRANSCRIPT_PUBLISH_DIR` env variable
+//! to a directory served publicly, e.g. a web root or a mounted bucket, laid out as:
+//!
+//! - `round_<height>/...`: the files of the rounds
+//! - `deltas/round_<height>.json`: the signed delta of each round
+//! - `head.json`: the signed delta of the last published round
+
+use crate::{
+    authentication::{KeyPair, Signature},
+    storage::RoundManifest,
+};
+
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use time::OffsetDateTime;
+
+/// The file of the signed delta of the last published round, relative to the publication directory.
+pub const HEAD_FILE: &str = "head.json";
+/// The directory of the signed deltas of all the published rounds, relative to the publication directory.
+pub const DELTAS_DIRECTORY: &str = "deltas";
+
+lazy_static! {
+    pub static ref TRANSCRIPT_PUBLISH_DIR: Option<PathBuf> =
+        std::env::var("TRANSCRIPT_PUBLISH_DIR").ok().map(PathBuf::from);
+}
+
+/// The delta of the transcript published for a completed round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptDelta {
+    pub round_height: u64,
+    /// The manifest of the round, listing the files published with the delta.
+    pub manifest: RoundManifest,
+    /// The head of the hash chain of the transcript up to the previous round, in hex, empty for round 0.
+    pub previous_head: String,
+    /// The head of the hash chain of the transcript up to this round, in hex.
+    pub head: String,
+    #[serde(with = "time::serde::timestamp")]
+    pub published_at: OffsetDateTime,
+}
+
+impl TranscriptDelta {
+    /// Returns the message signed by the coordinator: the json encoding of the delta, with the fields of the objects
+    /// sorted by name.
+    pub fn message(&self) -> serde_json::Result<String> {
+        // Objects of [`serde_json::Value`] are backed by a sorted map
+        Ok(serde_json::to_value(self)?.to_string())
+    }
+
+    /// Signs the delta with the given key of the coordinator.
+    pub fn sign(
+        self,
+        signature: &dyn Signature,
+        coordinator_public_key: String,
+        coordinator_signing_key: &str,
+    ) -> anyhow::Result<SignedTranscriptDelta> {
+        let delta_signature = signature.sign(coordinator_signing_key, &self.message()?)?;
+
+        Ok(SignedTranscriptDelta {
+            delta: self,
+            coordinator_public_key,
+            signature: delta_signature,
+        })
+    }
+}
+
+/// A [`TranscriptDelta`] signed by the coordinator, as published.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTranscriptDelta {
+    pub delta: TranscriptDelta,
+    /// The public key of the coordinator. Auditors should check it against the key they expect rather than trust it.
+    pub coordinator_public_key: String,
+    pub signature: String,
+}
+
+impl SignedTranscriptDelta {
+    /// Returns `true` if the delta is signed by the coordinator with the given public key, and its head is the one of
+    /// the hash chain of its manifest.
+    pub fn verify(&self, signature: &dyn Signature, coordinator_public_key: &str) -> bool {
+        let previous_head = match hex::decode(&self.delta.previous_head) {
+            Ok(previous_head) => previous_head,
+            Err(_) => return false,
+        };
+        if hex::encode(self.delta.manifest.chain_hash(&previous_head)) != self.delta.head {
+            return false;
+        }
+
+        match self.delta.message() {
+            Ok(message) => {
+                self.coordinator_public_key == coordinator_public_key
+                    && signature.verify(coordinator_public_key, &message, &self.signature)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Writes the given content to a temporary file first, so that a reader never sees a partial file.
+fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(tmp_path, path)
+}
+
+/// Publishes the deltas of the transcript to a directory, signed with the key of the coordinator.
+#[derive(Clone)]
+pub struct TranscriptPublisher {
+    root: PathBuf,
+    signature: Arc<dyn Signature>,
+    keypair: KeyPair,
+}
+
+impl TranscriptPublisher {
+    pub fn new(root: PathBuf, signature: Arc<dyn Signature>, keypair: KeyPair) -> anyhow::Result<Self> {
+        fs::create_dir_all(root.join(DELTAS_DIRECTORY))?;
+
+        Ok(Self {
+            root,
+            signature,
+            keypair,
+        })
+    }
+
+    /// Returns the publisher configured in the env, or [`None`] if the publication is disabled.
+    pub fn from_env(signature: Arc<dyn Signature>, keypair: KeyPair) -> anyhow::Result<Option<Self>> {
+        match TRANSCRIPT_PUBLISH_DIR.clone() {
+            Some(root) => Ok(Some(Self::new(root, signature, keypair)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the signed delta of the last published round, or [`None`] if no round has been published yet.
+    pub fn head(&self) -> anyhow::Result<Option<SignedTranscriptDelta>> {
+        let path = self.root.join(HEAD_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+    }
+
+    /// Returns the height of the next round to publish.
+    pub fn next_round(&self) -> anyhow::Result<u64> {
+        Ok(self.head()?.map_or(0, |head| head.delta.round_height + 1))
+    }
+
+    /// Publishes the deltas of the given rounds, ordered by round height, with the files of their manifests copied from
+    /// the storage base directory. The rounds must follow the last published one. Returns the published deltas.
+    pub fn publish(
+        &self,
+        base_directory: &Path,
+        manifests: &[RoundManifest],
+        now: OffsetDateTime,
+    ) -> anyhow::Result<Vec<SignedTranscriptDelta>> {
+        let head = self.head()?;
+        let mut next_round = head.as_ref().map_or(0, |head| head.delta.round_height + 1);
+        let mut previous_head = head.map(|head| head.delta.head).unwrap_or_default();
+
+        let mut published = vec![];
+        for manifest in manifests {
+            if manifest.round_height != next_round {
+                return Err(anyhow!(
+                    "Round {} can't be published before round {}",
+                    manifest.round_height,
+                    next_round
+                ));
+            }
+
+            let delta = self.publish_round(base_directory, manifest, &previous_head, now)?;
+            next_round += 1;
+            previous_head = delta.delta.head.clone();
+            published.push(delta);
+        }
+
+        Ok(published)
+    }
+
+    /// Copies the files of the round, then publishes its signed delta and moves the head to it, so that the head
+    /// always points to a complete round.
+    fn publish_round(
+        &self,
+        base_directory: &Path,
+        manifest: &RoundManifest,
+        previous_head: &str,
+        now: OffsetDateTime,
+    ) -> anyhow::Result<SignedTranscriptDelta> {
+        for file in &manifest.files {
+            let destination = self.root.join(&file.name);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let tmp_path = destination.with_extension("tmp");
+            let size = fs::copy(base_directory.join(&file.name), &tmp_path)?;
+            if size != file.size {
+                fs::remove_file(&tmp_path)?;
+                return Err(anyhow!(
+                    "File {} has {} bytes instead of the {} of the manifest",
+                    file.name,
+                    size,
+                    file.size
+                ));
+            }
+            fs::rename(tmp_path, destination)?;
+        }
+
+        let delta = TranscriptDelta {
+            round_height: manifest.round_height,
+            manifest: manifest.clone(),
+            previous_head: previous_head.to_owned(),
+            head: hex::encode(manifest.chain_hash(&hex::decode(previous_head)?)),
+            published_at: now,
+        }
+        .sign(
+            self.signature.as_ref(),
+            self.keypair.pubkey().to_owned(),
+            self.keypair.sigkey(),
+        )?;
+
+        let content = serde_json::to_vec_pretty(&delta)?;
+        write_atomically(
+            &self
+                .root
+                .join(DELTAS_DIRECTORY)
+                .join(format!("round_{}.json", manifest.round_height)),
+            &content,
+        )?;
+        write_atomically(&self.root.join(HEAD_FILE), &content)?;
+
+        Ok(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{authentication::Production, storage::TranscriptFile};
+
+    fn manifest(round_height: u64, content: &[u8], base_directory: &Path) -> RoundManifest {
+        let name = format!("round_{}/chunk_0/contribution_0.verified", round_height);
+        fs::create_dir_all(base_directory.join(format!("round_{}/chunk_0", round_height))).unwrap();
+        fs::write(base_directory.join(&name), content).unwrap();
+
+        RoundManifest {
+            round_height,
+            files: vec![TranscriptFile {
+                name,
+                size: content.len() as u64,
+                hash: None,
+            }],
+            verifications: vec![],
+            injections: vec![],
+            attestations: vec![],
+            parameters: vec![],
+            rehearsal: false,
+            genesis_seed: None,
+            computation_proofs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_publish() {
+        let storage = tempfile::tempdir().unwrap();
+        let public = tempfile::tempdir().unwrap();
+        let keypair = KeyPair::new();
+        let publisher =
+            TranscriptPublisher::new(public.path().to_owned(), Arc::new(Production), keypair.clone()).unwrap();
+        let manifests = vec![
+            manifest(0, b"challenge 0", storage.path()),
+            manifest(1, b"challenge 1", storage.path()),
+        ];
+
+        assert_eq!(publisher.next_round().unwrap(), 0);
+        let deltas = publisher
+            .publish(storage.path(), &manifests[..1], OffsetDateTime::now_utc())
+            .unwrap();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].delta.previous_head, "");
+        assert_eq!(publisher.next_round().unwrap(), 1);
+
+        // Rounds are published in order only
+        assert!(publisher
+            .publish(storage.path(), &manifests[..1], OffsetDateTime::now_utc())
+            .is_err());
+
+        let deltas = publisher
+            .publish(storage.path(), &manifests[1..], OffsetDateTime::now_utc())
+            .unwrap();
+        assert_eq!(publisher.head().unwrap().as_ref(), deltas.last());
+
+        // The heads follow the hash chain of the transcript, and the files are published at their path in the storage
+        let chain = crate::storage::hash_chain(&manifests);
+        assert_eq!(deltas[0].delta.head, chain[1].1);
+        assert_eq!(deltas[0].delta.previous_head, chain[0].1);
+        assert!(deltas[0].verify(&Production, keypair.pubkey()));
+        assert_eq!(
+            fs::read(public.path().join("round_1/chunk_0/contribution_0.verified")).unwrap(),
+            b"challenge 1"
+        );
+        let published: SignedTranscriptDelta =
+            serde_json::from_slice(&fs::read(public.path().join("deltas/round_0.json")).unwrap()).unwrap();
+        assert!(published.verify(&Production, keypair.pubkey()));
+
+        // A tampered delta or another key are rejected
+        let mut tampered = published.clone();
+        tampered.delta.manifest.files[0].size += 1;
+        assert!(!tampered.verify(&Production, keypair.pubkey()));
+        assert!(!published.verify(&Production, KeyPair::new().pubkey()));
+    }
+
+    #[test]
+    fn test_size_mismatch() {
+        let storage = tempfile::tempdir().unwrap();
+        let public = tempfile::tempdir().unwrap();
+        let publisher =
+            TranscriptPublisher::new(public.path().to_owned(), Arc::new(Production), KeyPair::new()).unwrap();
+        let mut manifest = manifest(0, b"challenge 0", storage.path());
+        manifest.files[0].size += 1;
+
+        assert!(publisher
+            .publish(storage.path(), &[manifest], OffsetDateTime::now_utc())
+            .is_err());
+        assert!(publisher.head().unwrap().is_none());
+    }
+}
@@ -0,0 +1,36 @@
+//! Marking of the responses of a coordinator running a rehearsal of the ceremony.
+//!
+//! A rehearsal runs on the tiny parameters of the test circuits, with shortened timeouts and relaxed identity
+//! requirements, to let the contributors try the ceremony end to end before it starts. Every response of the REST API
+//! carries the [`CEREMONY_MODE_HEADER`], so that no client, dashboard or mirror takes its artifacts for production ones.
+
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    http::Header,
+    Request,
+    Response,
+};
+
+/// Header marking the responses of a rehearsal.
+pub const CEREMONY_MODE_HEADER: &str = "Ceremony-Mode";
+
+/// Value of the [`CEREMONY_MODE_HEADER`] in a rehearsal.
+pub const REHEARSAL_MODE: &str = "rehearsal";
+
+/// Fairing adding the [`CEREMONY_MODE_HEADER`] to all the responses of the server.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RehearsalMarker;
+
+#[rocket::async_trait]
+impl Fairing for RehearsalMarker {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rehearsal marker",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, _request: &'r Request<'_>, response: &mut Response<'r>) {
+        response.set_header(Header::new(CEREMONY_MODE_HEADER, REHEARSAL_MODE));
+    }
+}
@@ -0,0 +1,556 @@
+//! Structured log of the requests to the REST API and of their responses, to debug the integration of the clients.
+//!
+//! The log is enabled by setting the `REQUEST_LOG_PATH` env variable to the path of its file, separate from the
+//! application logs. Each exchange is appended to the file as a line of json, and the file is rotated once it exceeds
+//! its maximum size. The log is configured through the following env variables:
+//!
+//! - `REQUEST_LOG_ENDPOINTS`: comma separated list of the paths to log, all of them by default
+//! - `REQUEST_LOG_MAX_BODY_SIZE`: number of bytes of each body included in the log (defaults to 4096). Request bodies
+//!   are further capped to the 512 bytes which can be read without consuming them
+//! - `REQUEST_LOG_REDACTED_FIELDS`: comma separated list of additional fields to redact
+//! - `REQUEST_LOG_MAX_FILE_SIZE`: size, in bytes, above which the file is rotated (defaults to 10 MiB)
+//! - `REQUEST_LOG_MAX_FILES`: number of rotated files to keep (defaults to 5)
+//!
+//! The headers, query parameters and json fields named after a redaction rule, or ending with `_` followed by the rule
+//! (e.g. `contribution_hash_signature` for `signature`), are redacted. Signatures, keys, secrets, tokens and seeds are
+//! always redacted.
+
+use crate::rest_utils::{ACCESS_SECRET_HEADER, CONTENT_LENGTH_HEADER, PUBKEY_HEADER, SIGNATURE_HEADER};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    Data,
+    Request,
+    Response,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{self, Cursor, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+    time::Instant,
+};
+use tracing::error;
+
+/// Replacement of the redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Fields which are always redacted.
+pub const DEFAULT_REDACTED_FIELDS: [&str; 6] = ["signature", "key", "pubkey", "secret", "token", "seed"];
+
+/// Headers which are always redacted, in addition to the ones matching the redaction rules.
+const REDACTED_HEADERS: [&str; 5] = [
+    PUBKEY_HEADER,
+    SIGNATURE_HEADER,
+    ACCESS_SECRET_HEADER,
+    "Authorization",
+    "Cookie",
+];
+
+const DEFAULT_MAX_BODY_SIZE: usize = 4096;
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// Number of bytes of a request body which can be peeked without consuming it.
+const PEEK_SIZE: usize = 512;
+
+/// Responses larger than this, or streamed without a known size, are logged without their body.
+const MAX_BUFFERED_RESPONSE_SIZE: usize = 1024 * 1024;
+
+fn number_from_env<T: FromStr>(var: &str, default: T) -> T {
+    match std::env::var(var) {
+        Ok(number) => number.parse().unwrap_or_else(|_| panic!("{} must be a number", var)),
+        Err(_) => default,
+    }
+}
+
+fn list_from_env(var: &str) -> Option<Vec<String>> {
+    std::env::var(var).ok().map(|list| {
+        list.split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    })
+}
+
+lazy_static! {
+    pub static ref REQUEST_LOG_PATH: Option<PathBuf> = std::env::var("REQUEST_LOG_PATH").ok().map(PathBuf::from);
+    pub static ref REQUEST_LOG_ENDPOINTS: Option<Vec<String>> = list_from_env("REQUEST_LOG_ENDPOINTS");
+    pub static ref REQUEST_LOG_MAX_BODY_SIZE: usize = number_from_env("REQUEST_LOG_MAX_BODY_SIZE", DEFAULT_MAX_BODY_SIZE);
+    pub static ref REQUEST_LOG_REDACTED_FIELDS: Vec<String> =
+        list_from_env("REQUEST_LOG_REDACTED_FIELDS").unwrap_or_default();
+    pub static ref REQUEST_LOG_MAX_FILE_SIZE: u64 = number_from_env("REQUEST_LOG_MAX_FILE_SIZE", DEFAULT_MAX_FILE_SIZE);
+    pub static ref REQUEST_LOG_MAX_FILES: usize = number_from_env("REQUEST_LOG_MAX_FILES", DEFAULT_MAX_FILES);
+    /// A json field with a scalar value, possibly truncated.
+    static ref JSON_FIELD: Regex =
+        Regex::new(r#""(?P<field>[A-Za-z0-9_-]+)"\s*:\s*(?:"(?:[^"\\]|\\.)*"?|[^,}\]\s]*)"#).unwrap();
+}
+
+/// The rules deciding which fields of the requests and responses are redacted.
+#[derive(Clone, Debug)]
+pub struct Redaction {
+    rules: HashSet<String>,
+}
+
+impl Default for Redaction {
+    /// Generates a [`Redaction`] instance with the default rules and the ones configured in the env.
+    fn default() -> Self {
+        Self::new(REQUEST_LOG_REDACTED_FIELDS.iter())
+    }
+}
+
+impl Redaction {
+    /// Returns the redaction rules extending the [`DEFAULT_REDACTED_FIELDS`] with the given ones.
+    pub fn new<I, S>(rules: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rules = DEFAULT_REDACTED_FIELDS
+            .iter()
+            .map(|rule| rule.to_string())
+            .chain(rules.into_iter().map(|rule| normalize_field(rule.as_ref())))
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Returns `true` if the value of the given field, header or query parameter must be redacted.
+    pub fn is_redacted(&self, field: &str) -> bool {
+        let field = normalize_field(field);
+        self.rules
+            .iter()
+            .any(|rule| field == *rule || field.ends_with(&format!("_{}", rule)))
+    }
+
+    /// Redacts the fields of a json value, at any depth.
+    pub fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (field, value) in fields.iter_mut() {
+                    if self.is_redacted(field) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_json(value)),
+            _ => (),
+        }
+    }
+
+    /// Returns the redacted text of a body, capped to `max_size` bytes. Bodies which are not valid json, e.g. because
+    /// they have been truncated, are redacted field by field.
+    pub fn redact_body(&self, body: &[u8], max_size: usize) -> String {
+        let text = match std::str::from_utf8(body) {
+            Ok(text) => text,
+            // A truncated body can end in the middle of a character
+            Err(e) if e.error_len().is_none() => std::str::from_utf8(&body[..e.valid_up_to()]).unwrap_or_default(),
+            Err(_) => return format!("<{} bytes of binary data>", body.len()),
+        };
+
+        let redacted = match serde_json::from_str::<Value>(text) {
+            Ok(mut value) => {
+                self.redact_json(&mut value);
+                value.to_string()
+            }
+            Err(_) => self.redact_text(text),
+        };
+
+        truncate(redacted, max_size)
+    }
+
+    /// Redacts the parameters of a query string.
+    pub fn redact_query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|parameter| match parameter.split_once('=') {
+                Some((name, _)) if self.is_redacted(name) => format!("{}={}", name, REDACTED),
+                _ => parameter.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    fn redact_text(&self, text: &str) -> String {
+        JSON_FIELD
+            .replace_all(text, |captures: &Captures| match self.is_redacted(&captures["field"]) {
+                true => format!("\"{}\":\"{}\"", &captures["field"], REDACTED),
+                false => captures[0].to_string(),
+            })
+            .into_owned()
+    }
+
+    fn is_redacted_header(&self, name: &str) -> bool {
+        REDACTED_HEADERS.iter().any(|header| header.eq_ignore_ascii_case(name)) || self.is_redacted(name)
+    }
+}
+
+fn normalize_field(field: &str) -> String {
+    field.to_lowercase().replace('-', "_")
+}
+
+/// Caps the text to `max_size` bytes, marking it as truncated.
+fn truncate(mut text: String, max_size: usize) -> String {
+    if text.len() <= max_size {
+        return text;
+    }
+
+    let mut end = max_size;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str("...");
+
+    text
+}
+
+/// A log file rotated once it exceeds its maximum size: the file is renamed with the `.1` suffix, the previous `.1`
+/// file to `.2` and so on, dropping the oldest one.
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: impl Into<PathBuf>, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    /// Appends a line to the file, rotating it first if the line doesn't fit.
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let line = format!("{}\n", line);
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..self.max_files).rev() {
+            let path = self.rotated_path(index);
+            if path.exists() {
+                fs::rename(&path, self.rotated_path(index + 1))?;
+            }
+        }
+
+        match self.max_files {
+            0 => fs::remove_file(&self.path)?,
+            _ => fs::rename(&self.path, self.rotated_path(1))?,
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+/// The request of an exchange to log, kept until its response.
+struct PendingRequest {
+    start: Instant,
+    method: String,
+    path: String,
+    query: Option<String>,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    body_size: Option<u64>,
+}
+
+/// An exchange, as written in the log.
+#[derive(Serialize)]
+struct LogEntry<'a> {
+    timestamp: String,
+    method: &'a str,
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<&'a str>,
+    headers: &'a BTreeMap<String, String>,
+    request_body: &'a Option<String>,
+    request_body_size: Option<u64>,
+    status: u16,
+    duration_ms: u128,
+    response_body: Option<String>,
+    response_body_size: Option<usize>,
+}
+
+/// Fairing writing the requests to the server and their responses to the request log.
+#[derive(Debug)]
+pub struct RequestLog {
+    file: Mutex<RotatingFile>,
+    redaction: Redaction,
+    endpoints: Option<HashSet<String>>,
+    max_body_size: usize,
+}
+
+impl RequestLog {
+    pub fn new(file: RotatingFile, redaction: Redaction) -> Self {
+        Self {
+            file: Mutex::new(file),
+            redaction,
+            endpoints: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Returns the request log configured in the env, or [`None`] if `REQUEST_LOG_PATH` is not set.
+    pub fn from_env() -> io::Result<Option<Self>> {
+        let path = match REQUEST_LOG_PATH.as_ref() {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let file = RotatingFile::open(path, *REQUEST_LOG_MAX_FILE_SIZE, *REQUEST_LOG_MAX_FILES)?;
+        let mut request_log = Self::new(file, Redaction::default()).with_max_body_size(*REQUEST_LOG_MAX_BODY_SIZE);
+        if let Some(endpoints) = REQUEST_LOG_ENDPOINTS.as_ref() {
+            request_log = request_log.with_endpoints(endpoints.iter());
+        }
+
+        Ok(Some(request_log))
+    }
+
+    /// Restricts the log to the given paths.
+    pub fn with_endpoints<I, S>(mut self, endpoints: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.endpoints = Some(
+            endpoints
+                .into_iter()
+                .map(|endpoint| endpoint.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Caps the bodies included in the log to the given number of bytes.
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Returns `true` if the exchanges on the given path are logged.
+    pub fn is_logged(&self, path: &str) -> bool {
+        match &self.endpoints {
+            Some(endpoints) => endpoints.contains(path),
+            None => true,
+        }
+    }
+
+    fn write(&self, entry: &LogEntry<'_>) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Error while serializing the request log entry: {}", e);
+                return;
+            }
+        };
+
+        let mut file = self.file.lock().expect("Request log lock poisoned");
+        if let Err(e) = file.write_line(&line) {
+            error!("Error while writing the request log: {}", e);
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestLog {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request log",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, data: &mut Data<'_>) {
+        let path = request.uri().path().as_str().to_string();
+        if !self.is_logged(&path) {
+            return;
+        }
+
+        let headers = request
+            .headers()
+            .iter()
+            .map(|header| {
+                let name = header.name().as_str().to_string();
+                let value = match self.redaction.is_redacted_header(&name) {
+                    true => REDACTED.to_string(),
+                    false => header.value().to_string(),
+                };
+                (name, value)
+            })
+            .collect();
+        let body_size = request
+            .headers()
+            .get_one(CONTENT_LENGTH_HEADER)
+            .and_then(|size| size.parse().ok());
+        let peeked = data.peek(PEEK_SIZE.min(self.max_body_size)).await;
+        let body = match peeked.is_empty() {
+            true => None,
+            false => Some(self.redaction.redact_body(peeked, self.max_body_size)),
+        };
+
+        let pending = PendingRequest {
+            start: Instant::now(),
+            method: request.method().as_str().to_string(),
+            path,
+            query: request.uri().query().map(|query| self.redaction.redact_query(query.as_str())),
+            headers,
+            body,
+            body_size,
+        };
+        request.local_cache(|| Some(pending));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let pending = match request.local_cache(|| None::<PendingRequest>) {
+            Some(pending) => pending,
+            None => return,
+        };
+
+        let response_body_size = response.body().preset_size();
+        let response_body = match response_body_size {
+            Some(size) if size > 0 && size <= MAX_BUFFERED_RESPONSE_SIZE => match response.body_mut().to_bytes().await {
+                Ok(body) => {
+                    let logged = self.redaction.redact_body(&body, self.max_body_size);
+                    response.set_sized_body(body.len(), Cursor::new(body));
+                    Some(logged)
+                }
+                Err(e) => {
+                    error!("Error while reading the response body for the request log: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        self.write(&LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method: &pending.method,
+            path: &pending.path,
+            query: pending.query.as_deref(),
+            headers: &pending.headers,
+            request_body: &pending.body,
+            request_body_size: pending.body_size,
+            status: response.status().code,
+            duration_ms: pending.start.elapsed().as_millis(),
+            response_body,
+            response_body_size,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_redacted() {
+        let redaction = Redaction::new(vec!["Mnemonic"]);
+
+        assert!(redaction.is_redacted("signature"));
+        assert!(redaction.is_redacted("contribution_hash_signature"));
+        assert!(redaction.is_redacted("public_key"));
+        assert!(redaction.is_redacted("ATS-Pubkey"));
+        assert!(redaction.is_redacted("mnemonic"));
+        assert!(!redaction.is_redacted("contribution_hash"));
+        assert!(!redaction.is_redacted("keystroke"));
+        assert!(redaction.is_redacted_header("Access-Secret"));
+        assert!(!redaction.is_redacted_header("Content-Type"));
+    }
+
+    #[test]
+    fn test_redact_body() {
+        let redaction = Redaction::new(Vec::<String>::new());
+        let body = json!({
+            "round_height": 1,
+            "public_key": "abcd",
+            "votes": [{ "signature": "efgh", "chunk_id": 0 }]
+        });
+
+        let redacted: Value =
+            serde_json::from_str(&redaction.redact_body(body.to_string().as_bytes(), usize::MAX)).unwrap();
+        assert_eq!(
+            redacted,
+            json!({
+                "round_height": 1,
+                "public_key": REDACTED,
+                "votes": [{ "signature": REDACTED, "chunk_id": 0 }]
+            })
+        );
+
+        // Truncated json
+        let truncated = r#"{"round_height":1,"contribution_hash_signature":"abcd"#;
+        assert_eq!(
+            redaction.redact_body(truncated.as_bytes(), usize::MAX),
+            format!(r#"{{"round_height":1,"contribution_hash_signature":"{}""#, REDACTED)
+        );
+
+        assert_eq!(redaction.redact_body(&[0xff, 0x00], usize::MAX), "<2 bytes of binary data>");
+        assert_eq!(redaction.redact_body(b"abcdef", 3), "abc...");
+    }
+
+    #[test]
+    fn test_redact_query() {
+        let redaction = Redaction::new(Vec::<String>::new());
+
+        assert_eq!(
+            redaction.redact_query("round=1&token=abcd"),
+            format!("round=1&token={}", REDACTED)
+        );
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.log");
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+
+        for line in &["first", "second", "third", "fourth"] {
+            file.write_line(line).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        assert_eq!(fs::read_to_string(dir.path().join("requests.log.1")).unwrap(), "third\n");
+        assert_eq!(fs::read_to_string(dir.path().join("requests.log.2")).unwrap(), "second\n");
+        assert!(!dir.path().join("requests.log.3").exists());
+    }
+}
@@ -1,77 +1,336 @@
 //! REST API endpoints exposed by the [Coordinator](`crate::Coordinator`).
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     io::{Cursor, Read, Write},
+    path::PathBuf,
 };
 use tracing::warn;
 
 use crate::{
-    objects::{ContributionInfo, LockedLocators},
+    capability_probe::{CapabilityProbe, CapabilityProber},
+    changelog::SignedChangelog,
+    events::CeremonyEvent,
+    export::{CeremonyExport, ExportFormat},
+    human_verification::HumanVerifier,
+    identity::IdentityVerifier,
+    lifecycle::Lifecycle,
+    lock_monitor::{LockMetrics, LOCK_MONITOR},
+    messages::{LocalizedCatalog, MESSAGE_CATALOG},
+    objects::{
+        Announcement, CeremonyParameters, ChallengeBundle, CircuitRegistry, ComputationStats, ContributionInfo,
+        ContributionSession, GenesisSeedStatus, InjectionStatus, LockedLocators, ParticipantRecord, QuarantineDecision,
+        QueueShuffle, QuorumStatus, ResponseBundle, ReverificationStatus, ReverificationTask, SignedCeremonyMetadata,
+        SignedVerifierDelegation, Task, VerificationQuorum, VerificationRetries, VerificationStatus,
+    },
+    pow::{PowPuzzle, ProofOfWork},
+    practice::{self, PracticeLock, PracticeRounds, PracticeVerification, MAX_PRACTICE_CONTRIBUTION_SIZE},
+    prelaunch::{PrelaunchRestore, PrelaunchSummary},
     rest_utils::{
-        self, ContributorStatus, Coordinator, CurrentContributor, LazyJson, NewParticipant, PostChunkRequest,
-        ResponseError, Result, Secret, ServerAuth, HEALTH_PATH, TOKENS_PATH, TOKENS_ZIP_FILE,
+        self, AcceptLanguage, AttestationPostRequest, CeremonyStatus, ChallengeETags, ComputationProofRequest,
+        ConditionalFile, ContributorStatus, Coordinator, CurrentContributor, DestructionAttestationRequest,
+        DownloadConditions, GenesisSeedSummary, JoinQueueRequest, JoinQueueResponse, LazyJson,
+        ManualContributionApproval, NewParticipant, Operator, PostChunkRequest, PublicCeremonyStatus, QueueEvent,
+        QueueEvents, QuorumVerifier, RequestContent, ResponseError, Result, Secret, SeedCommitmentRequest,
+        SeedRevealRequest, ServerAuth, SizedStream, UploadProgress, VerificationTracker, VerificationVoteRequest,
+        WithMessageCode, ANONYMIZE_PUBLIC_STATUS, HEALTH_PATH, QUEUE_EVENTS_REFRESH, TOKENS_PATH, TOKENS_ZIP_FILE,
     },
     s3::S3Ctx,
-    storage::{Locator, Object},
-    CoordinatorState, Participant,
+    startup::StartupProfile,
+    storage::{ContributionLocator, Disk, Locator, Object, RoundManifest, ShardUsage},
+    webhooks::{RoundWebhooks, WebhookDelivery},
+    ContributionFileSignature, CoordinatorError, CoordinatorState, Participant,
 };
 use rocket::{
+    data::Data,
     get, post,
+    response::stream::{ByteStream, Event, EventStream},
     serde::json::Json,
-    tokio::{fs, task},
+    tokio::{self, fs, sync::broadcast::error::RecvError, task},
     Shutdown, State,
 };
 
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
 use url::Url;
 
-/// Add the incoming contributor to the queue of contributors.
-#[post("/contributor/join_queue", format = "json", data = "<token>")]
+/// Get the proof-of-work puzzle to solve before joining the queue of contributors. Returns [`None`] if no proof-of-work
+/// is required in the current phase of the ceremony.
+#[get("/contributor/pow_puzzle", format = "json")]
+pub async fn get_pow_puzzle(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    pow: &State<ProofOfWork>,
+    participant: Participant,
+) -> Result<Json<Option<PowPuzzle>>> {
+    let phase_difficulty = LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .ceremony_phase()
+        .and_then(|phase| phase.pow_difficulty);
+    let puzzle = pow
+        .issue(&participant, phase_difficulty)
+        .map_err(|e| ResponseError::CoordinatorError(e.into()))?;
+
+    Ok(Json(puzzle))
+}
+
+/// Get the capability probe to complete before joining the queue of contributors, with a blob sized like the challenge
+/// of the current round. Returns [`None`] if no probe is required.
+#[get("/contributor/capability_probe", format = "json")]
+pub async fn get_capability_probe(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    prober: &State<CapabilityProber>,
+    participant: Participant,
+) -> Result<Json<Option<CapabilityProbe>>> {
+    if !prober.is_enabled() {
+        return Ok(Json(None));
+    }
+
+    let round_height = LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .current_round_height()
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+    let size = Object::anoma_contribution_file_size(round_height, 0);
+
+    Ok(Json(prober.issue(&participant, size)))
+}
+
+/// Stream the blob of the capability probe issued to the contributor. The hash of the blob is recorded once it has been
+/// fully streamed, so that the probe only succeeds if the whole blob was downloaded.
+#[get("/contributor/capability_probe/blob")]
+pub async fn get_capability_probe_blob(
+    _lifecycle: Lifecycle,
+    prober: &State<CapabilityProber>,
+    participant: Participant,
+) -> Result<SizedStream<ByteStream![Vec<u8>]>> {
+    let probe = prober
+        .issued(&participant)
+        .ok_or_else(|| ResponseError::InvalidCapabilityProbe(String::from("no probe was issued to the participant")))?;
+    let prober = prober.inner().clone();
+
+    let length = probe.size;
+    let body = ByteStream! {
+        let mut hasher = Sha256::new();
+        for chunk in probe.blob() {
+            hasher.update(&chunk);
+            yield chunk;
+        }
+        prober.record_served(&participant, &probe.seed, hex::encode(hasher.finalize()));
+    };
+
+    Ok(SizedStream { length, body })
+}
+
+/// Lock a practice round, for a contributor in the queue or in the round to try the whole protocol with its setup
+/// before its slot, apart from the state of the ceremony, see [`practice`](`crate::practice`). Any previous practice
+/// round of the contributor is replaced.
+#[post("/contributor/practice/lock")]
+pub async fn lock_practice_round(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    practice_rounds: &State<PracticeRounds>,
+    participant: Participant,
+) -> Result<Json<PracticeLock>> {
+    // The round of the current contributor is read from storage, off the async runtime
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let contributor = participant.clone();
+    let (authorized, hasher) = task::spawn_blocking(move || {
+        let authorized = read_lock.is_queue_contributor(&contributor) || read_lock.is_current_contributor(&contributor);
+        (authorized, read_lock.environment().contribution_hasher())
+    })
+    .await?;
+    if !authorized {
+        return Err(ResponseError::UnauthorizedParticipant(
+            participant,
+            "/contributor/practice/lock".to_string(),
+            "Not a contributor of the ceremony".to_string(),
+        ));
+    }
+
+    Ok(Json(practice_rounds.lock(&participant, hasher)))
+}
+
+/// Download the challenge of the practice round locked by the contributor, as a raw `application/octet-stream` body.
+#[get("/contributor/practice/challenge")]
+pub async fn get_practice_challenge(
+    _lifecycle: Lifecycle,
+    practice_rounds: &State<PracticeRounds>,
+    participant: Participant,
+) -> Result<Vec<u8>> {
+    if !practice_rounds.is_locked(&participant) {
+        return Err(ResponseError::UnauthorizedParticipant(
+            participant,
+            "/contributor/practice/challenge".to_string(),
+            "No practice round is locked by the contributor".to_string(),
+        ));
+    }
+
+    Ok(practice::challenge().to_vec())
+}
+
+/// Upload the contribution to the practice round locked by the contributor as the raw body of the request, and get the
+/// outcome of its verification. The practice round is over once its contribution is received, whatever the outcome.
+#[post("/contributor/practice/contribution", data = "<contribution>")]
+pub async fn post_practice_contribution(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    practice_rounds: &State<PracticeRounds>,
+    participant: Participant,
+    content: RequestContent<'_>,
+    contribution: Data<'_>,
+) -> Result<Json<PracticeVerification>> {
+    let unauthorized = |participant: Participant, cause: String| {
+        ResponseError::UnauthorizedParticipant(participant, "/contributor/practice/contribution".to_string(), cause)
+    };
+    if !practice_rounds.is_locked(&participant) {
+        return Err(unauthorized(
+            participant,
+            "No practice round is locked by the contributor".to_string(),
+        ));
+    }
+    let contribution = rest_utils::read_body(contribution, &content, MAX_PRACTICE_CONTRIBUTION_SIZE).await?;
+    practice_rounds
+        .release(&participant)
+        .map_err(|e| unauthorized(participant, e))?;
+
+    let hasher = LOCK_MONITOR.read(coordinator).await.environment().contribution_hasher();
+    let verification = task::spawn_blocking(move || practice::verify(hasher, &contribution)).await?;
+
+    Ok(Json(verification))
+}
+
+/// Add the incoming contributor to the queue of contributors. Returns the cohort of its token and its position in the
+/// queue. A contributor retrying its request with the same token, e.g. after a timeout, is left at its position.
+#[post("/contributor/join_queue", format = "json", data = "<request>")]
 pub async fn join_queue(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
+    pow: &State<ProofOfWork>,
+    prober: &State<CapabilityProber>,
+    human_verifier: &State<HumanVerifier>,
+    identity_verifier: &State<IdentityVerifier>,
     new_participant: NewParticipant,
-    token: LazyJson<String>,
-) -> Result<Json<u64>> {
+    request: LazyJson<JoinQueueRequest>,
+) -> Result<Json<JoinQueueResponse>> {
+    let participant = new_participant.participant;
+
+    // A retry passed the checks of the first request already, whose human verification token can't be reused
+    let (retry, phase) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let retry = read_lock.state().is_queue_retry(&participant, &request.token);
+
+        (retry, read_lock.ceremony_phase().cloned())
+    };
+    let (warning, human_verification, identity) = if retry {
+        (None, None, None)
+    } else {
+        // The queue policy of the current phase is checked when adding the participant to the queue
+        let phase_difficulty = phase.as_ref().and_then(|phase| phase.pow_difficulty);
+        pow.check(&participant, request.pow_solution.as_ref(), phase_difficulty)
+            .map_err(ResponseError::InvalidProofOfWork)?;
+        let warning = prober
+            .check(&participant, request.capability_proof.as_ref())
+            .map_err(ResponseError::InvalidCapabilityProbe)?;
+        if let Some(warning) = &warning {
+            warn!(
+                "Contributor {} joins the queue with a warning: {}",
+                participant, warning
+            );
+        }
+        let human_verification = human_verifier
+            .check(request.human_verification_token.as_deref(), new_participant.ip_address)
+            .await
+            .map_err(ResponseError::InvalidHumanVerification)?;
+        let identity = identity_verifier
+            .check(
+                request.identity.as_ref(),
+                &participant,
+                phase.as_ref().map_or(false, |phase| phase.identity_required),
+            )
+            .await
+            .map_err(ResponseError::InvalidIdentity)?;
+
+        (warning, human_verification, identity)
+    };
+
     // NOTE: check on the token happens only here meaning that a contributor can join the ceremony at the very last moment of a cohort and
     // contribute effectively in the following cohort. Forcing the contribution to happen in the correct cohort would take more complicated checks
     // and could lower the amount of contributions received
-    let cohort = rest_utils::token_check((*coordinator).clone(), token.as_str()).await?;
-    let mut write_lock = (*coordinator).clone().write_owned().await;
+    let cohort = rest_utils::token_check(coordinator, &participant, request.token.as_str()).await?;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
 
-    task::spawn_blocking(move || {
-        write_lock.add_to_queue(
-            new_participant.participant,
-            new_participant.ip_address,
-            token.clone(),
-            10,
-        )
+    let (queue_position, queue_size) = task::spawn_blocking(move || {
+        if let Some(identity) = &identity {
+            write_lock
+                .state()
+                .unique_identity_checks(&participant, &identity.claim)?;
+        }
+        // Retries racing with the first request are left at the position of the first one
+        write_lock.add_to_queue(participant.clone(), new_participant.ip_address, request.0.token, 10)?;
+
+        if let Some(verification) = human_verification {
+            write_lock.record_human_verification(participant.clone(), verification)?;
+        }
+        if let Some(identity) = identity {
+            write_lock.record_identity(participant.clone(), identity)?;
+        }
+
+        write_lock
+            .queue_position(&participant)
+            .ok_or(CoordinatorError::ParticipantMissing)
     })
     .await?
     .map_err(|e| ResponseError::CoordinatorError(e))?;
 
-    Ok(Json(cohort))
+    Ok(Json(JoinQueueResponse {
+        cohort,
+        queue_position,
+        queue_size,
+        warning,
+    }))
 }
 
 /// Lock a [Chunk](`crate::objects::Chunk`) in the ceremony. This should be the first function called when attempting to contribute to a chunk. Once the chunk is locked, it is ready to be downloaded.
 #[get("/contributor/lock_chunk", format = "json")]
 pub async fn lock_chunk(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
     participant: CurrentContributor,
 ) -> Result<Json<LockedLocators>> {
-    let mut write_lock = (*coordinator).clone().write_owned().await;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
     match task::spawn_blocking(move || write_lock.try_lock(&participant)).await? {
         Ok((_, locked_locators)) => Ok(Json(locked_locators)),
         Err(e) => Err(ResponseError::CoordinatorError(e)),
     }
 }
 
-/// Get the challenge key on Amazon S3 from the [Coordinator](`crate::Coordinator`).
+/// Get the challenge key on Amazon S3 from the [Coordinator](`crate::Coordinator`). The contributor must hold the lock
+/// on the chunk of the challenge in the requested round.
 #[post("/contributor/challenge", format = "json", data = "<round_height>")]
 pub async fn get_challenge_url(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
-    _participant: CurrentContributor,
+    participant: CurrentContributor,
     round_height: LazyJson<u64>,
 ) -> Result<Json<String>> {
+    // Check the ownership of the challenge before serving it, from S3 or from the storage of the coordinator. The
+    // challenge is opened under the lock but read once it is released, not to block the coordinator for the duration
+    // of the read
+    let (challenge_locator, file) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let challenge_locator = read_lock
+            .locked_challenge(&participant, *round_height)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        let (_, file) = read_lock
+            .open_challenge(&challenge_locator)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+        (challenge_locator, file)
+    };
+
     let s3_ctx = S3Ctx::new().await?;
     let key = format!("round_{}/chunk_0/contribution_0.verified", *round_height);
 
@@ -82,8 +341,7 @@ pub async fn get_challenge_url(
 
     // Since we don't chunk the parameters, we have one chunk and one allowed contributor per round. Thus the challenge will always be located at round_{i}/chunk_0/contribution_0.verified
     // For example, the 1st challenge (after the initialization) is located at round_1/chunk_0/contribution_0.verified
-    let read_lock = (*coordinator).clone().read_owned().await;
-    let challenge = match task::spawn_blocking(move || read_lock.get_challenge(*round_height, 0, 0, true)).await? {
+    let challenge = match task::spawn_blocking(move || Disk::read_contribution_file(file, &challenge_locator)).await? {
         Ok(challenge) => challenge,
         Err(e) => return Err(ResponseError::CoordinatorError(e)),
     };
@@ -94,20 +352,55 @@ pub async fn get_challenge_url(
     Ok(Json(url))
 }
 
+/// Stream the challenge of the given round from the storage of the [Coordinator](`crate::Coordinator`), as a raw
+/// `application/octet-stream` body with its `Content-Length`, instead of uploading it to S3 with
+/// [`get_challenge_url`]. The challenge is read from disk as the client consumes it, rather than loaded in memory. The
+/// contributor must hold the lock on the chunk of the challenge.
+///
+/// The challenge is sent with the hash of its content as `ETag`, and a single byte range can be requested with the
+/// `Range` header, so that a contributor retrying the download after a network failure skips it with `If-None-Match`,
+/// or resumes it with `If-Range`.
+#[get("/contributor/challenge/stream?<round_height>")]
+pub async fn get_challenge_stream(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    challenge_etags: &State<ChallengeETags>,
+    participant: CurrentContributor,
+    conditions: DownloadConditions,
+    round_height: u64,
+) -> Result<ConditionalFile<ByteStream![Vec<u8>]>> {
+    // The challenge is opened under the lock, so that it is streamed as it was even if it is reissued meanwhile
+    let (path, file, hasher) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let (path, file) = read_lock
+            .open_locked_challenge(&participant, round_height)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        (path, file, read_lock.environment().contribution_hasher())
+    };
+
+    let etag = challenge_etags.etag(&path, &file, hasher).await?;
+    rest_utils::stream_file_conditionally(path, file, etag, &conditions).await
+}
+
 /// Request the urls where to upload a [Chunk](`crate::objects::Chunk`) contribution and the ContributionFileSignature.
 #[post("/upload/chunk", format = "json", data = "<round_height>")]
 pub async fn get_contribution_url(
+    _lifecycle: Lifecycle,
     _participant: CurrentContributor,
     round_height: LazyJson<u64>,
 ) -> Result<Json<(String, String)>> {
-    let contrib_key = format!("round_{}/chunk_0/contribution_1.unverified", *round_height);
-    let contrib_sig_key = format!("round_{}/chunk_0/contribution_1.unverified.signature", *round_height);
+    contribution_urls(*round_height).await.map(Json)
+}
+
+/// Returns the urls where to upload the contribution of the given round and its signature.
+async fn contribution_urls(round_height: u64) -> Result<(String, String)> {
+    let contrib_key = format!("round_{}/chunk_0/contribution_1.unverified", round_height);
+    let contrib_sig_key = format!("round_{}/chunk_0/contribution_1.unverified.signature", round_height);
 
     // Prepare urls for the upload
     let s3_ctx = S3Ctx::new().await?;
-    let urls = s3_ctx.get_contribution_urls(contrib_key, contrib_sig_key);
 
-    Ok(Json(urls))
+    Ok(s3_ctx.get_contribution_urls(contrib_key, contrib_sig_key))
 }
 
 /// Notify the [Coordinator](`crate::Coordinator`) of a finished and uploaded [Contribution](`crate::objects::Contribution`). This will unlock the given [Chunk](`crate::objects::Chunk`).
@@ -117,39 +410,425 @@ pub async fn get_contribution_url(
     data = "<contribute_chunk_request>"
 )]
 pub async fn contribute_chunk(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
     participant: CurrentContributor,
     contribute_chunk_request: LazyJson<PostChunkRequest>,
 ) -> Result<()> {
-    // Download contribution and its signature from S3 to local disk from the provided Urls
+    let request = contribute_chunk_request.0;
+
+    // Download contribution and its signature from S3 to local disk from the provided Urls, unless streamed to the
+    // coordinator
+    let uploaded = match request.contribution_file_signature {
+        Some(_) => None,
+        None => {
+            let s3_ctx = S3Ctx::new().await?;
+            Some(s3_ctx.get_contribution(request.round_height).await?)
+        }
+    };
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || {
+        match (request.contribution_file_signature, uploaded) {
+            (Some(signature), _) => {
+                let (_, spool_path) = write_lock.contribution_spool(&participant)?;
+                write_lock.write_spooled_contribution(
+                    request.contribution_locator,
+                    request.contribution_signature_locator,
+                    signature,
+                )?;
+                rest_utils::close_upload_session(&spool_path);
+            }
+            (None, uploaded) => {
+                let (contribution, contribution_sig) = uploaded.unwrap_or_default();
+                let contribution_sig =
+                    ContributionFileSignature::from_slice(&contribution_sig, &write_lock.signature().name())?;
+                write_lock.write_contribution(
+                    request.contribution_locator,
+                    contribution,
+                    request.contribution_signature_locator,
+                    contribution_sig,
+                )?
+            }
+        }
+        write_lock.try_contribute(&participant, 0) // Only 1 chunk per round, chunk_id is always 0
+    })
+    .await?
+    .map_or_else(|e| Err(ResponseError::CoordinatorError(e)), |_| Ok(()))
+}
+
+/// Send the proof of computation time of the contribution of the current contributor, the checkpoints of the hash
+/// chain run by its client during the computation, together with the upload of the contribution. The first segment of
+/// the chain and a sample of the others are checked against the locked challenge before the proof is recorded in the
+/// manifest of the round. Returns the number of iterations of the chain.
+#[post("/contributor/computation_proof", format = "json", data = "<request>")]
+pub async fn post_computation_proof(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+    request: LazyJson<ComputationProofRequest>,
+) -> Result<Json<u64>> {
+    let request = request.0;
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let contributor = (*participant).clone();
+    let round_height = request.round_height;
+    let challenge_hash = task::spawn_blocking(move || read_lock.locked_challenge_hash(&contributor, round_height))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    // The segments are checked without holding the lock of the coordinator
+    let proof = request.proof;
+    let (proof, iterations) = task::spawn_blocking(move || {
+        let iterations = proof.verify_sample(&challenge_hash, &mut OsRng);
+        (proof, iterations)
+    })
+    .await?;
+    let iterations = iterations
+        .map_err(|e| ResponseError::CoordinatorError(CoordinatorError::ComputationProofInvalid(e.to_string())))?;
+
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    task::spawn_blocking(move || write_lock.add_computation_proof(&participant, round_height, proof))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(iterations))
+}
+
+/// Stream the contribution of the current contributor to the [Coordinator](`crate::Coordinator`) as the raw body of the
+/// request, instead of uploading it to the urls of [`get_contribution_url`]. The body is spooled to disk and hashed on
+/// the fly rather than buffered in memory. Returns the hex encoded hash of the contribution, which is moved in place
+/// once posted to [`contribute_chunk`] together with its signature.
+#[post("/contributor/upload/contribution", data = "<contribution>")]
+pub async fn upload_contribution(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+    content: RequestContent<'_>,
+    contribution: Data<'_>,
+) -> Result<Json<String>> {
+    let (contribution_locator, spool_path, hasher) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let (contribution_locator, spool_path) = read_lock
+            .contribution_spool(&participant)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        (contribution_locator, spool_path, read_lock.environment().contribution_hasher())
+    };
+
+    let expected_size = Object::anoma_contribution_file_size(
+        contribution_locator.round_height(),
+        contribution_locator.contribution_id(),
+    );
+    if content.content_length() as u64 != expected_size {
+        return Err(ResponseError::CoordinatorError(CoordinatorError::ContributionFileSizeMismatch));
+    }
+
+    rest_utils::spool_body(contribution, &content, &spool_path, hasher).await.map(Json)
+}
+
+/// Append a range of the contribution of the current contributor, starting at the given offset, to the contribution
+/// streamed to the [Coordinator](`crate::Coordinator`). The range at offset zero starts a new upload session, whose id
+/// the next ranges must carry along with the number of bytes received so far as their offset, so that clients on
+/// unstable connections upload the contribution in ranges sized to their bandwidth and resume it after a failure.
+/// Returns the progress of the upload, with the hex encoded hash of the contribution once received in full.
+#[post("/contributor/upload/contribution/range?<offset>&<session>", data = "<range>")]
+pub async fn upload_contribution_range(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+    content: RequestContent<'_>,
+    offset: u64,
+    session: Option<&str>,
+    range: Data<'_>,
+) -> Result<Json<UploadProgress>> {
+    let (contribution_locator, spool_path, hasher) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let (contribution_locator, spool_path) = read_lock
+            .contribution_spool(&participant)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        (contribution_locator, spool_path, read_lock.environment().contribution_hasher())
+    };
+    let size = Object::anoma_contribution_file_size(
+        contribution_locator.round_height(),
+        contribution_locator.contribution_id(),
+    );
+
+    rest_utils::append_spool(range, &content, &spool_path, offset, session, size, hasher)
+        .await
+        .map(Json)
+}
+
+/// Get the progress of the contribution of the current contributor uploaded in ranges to
+/// [`upload_contribution_range`], to resume it after a failure.
+#[get("/contributor/upload/contribution/range", format = "json")]
+pub async fn get_contribution_upload_progress(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+) -> Result<Json<UploadProgress>> {
+    let (contribution_locator, spool_path, hasher) = {
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let (contribution_locator, spool_path) = read_lock
+            .contribution_spool(&participant)
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        (contribution_locator, spool_path, read_lock.environment().contribution_hasher())
+    };
+    let size = Object::anoma_contribution_file_size(
+        contribution_locator.round_height(),
+        contribution_locator.contribution_id(),
+    );
+
+    rest_utils::spool_progress(&spool_path, size, hasher).await.map(Json)
+}
+
+/// Resume the contribution of a contributor still holding its lock after a crash of the client, possibly from another
+/// machine with the same keypair. Returns the locked files, the deadline of the lock and which files have already been
+/// uploaded, so that the client can skip straight to the missing steps.
+#[post("/contributor/resume_session")]
+pub async fn resume_session(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+) -> Result<Json<ContributionSession>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let mut session = task::spawn_blocking(move || write_lock.resume_session(&participant))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    let s3_ctx = S3Ctx::new().await?;
+    let (contribution_uploaded, signature_uploaded) = s3_ctx.contribution_uploaded(session.round_height).await;
+    session.contribution_uploaded = contribution_uploaded;
+    session.signature_uploaded = signature_uploaded;
+
+    Ok(Json(session))
+}
+
+/// Switch to the offline flow, to compute the contribution on an air-gapped machine, and get the [`ChallengeBundle`] to
+/// transfer there together with the challenge. The contributor must have locked the chunk and, from now on, is subject
+/// to the relaxed offline timeout.
+#[get("/contributor/offline/challenge_bundle", format = "json")]
+pub async fn get_challenge_bundle(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+) -> Result<Json<ChallengeBundle>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.start_offline_contribution(&participant))
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Notify the [Coordinator](`crate::Coordinator`) of a contribution computed on an air-gapped machine, with the
+/// [`ResponseBundle`] signed there. The contribution file must have been uploaded to the urls of
+/// [`get_contribution_url`]: its signature is taken from the bundle. This will unlock the given
+/// [Chunk](`crate::objects::Chunk`).
+#[post("/contributor/offline/response_bundle", format = "json", data = "<response_bundle>")]
+pub async fn post_response_bundle(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: CurrentContributor,
+    response_bundle: LazyJson<ResponseBundle>,
+) -> Result<()> {
+    // Check the bundle against the challenge before downloading the contribution
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    if !read_lock.is_offline_contributor(&participant) {
+        return Err(ResponseError::InvalidResponseBundle(format!(
+            "contributor {} didn't download a challenge bundle",
+            participant.address()
+        )));
+    }
+
+    let offline_participant = (*participant).clone();
+    let challenge_bundle = task::spawn_blocking(move || read_lock.challenge_bundle(&offline_participant))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    if !response_bundle.answers(&challenge_bundle) {
+        return Err(ResponseError::InvalidResponseBundle(format!(
+            "response to round {} contribution {} doesn't match the challenge bundle of round {} contribution {}",
+            response_bundle.round_height,
+            response_bundle.contribution_id,
+            challenge_bundle.round_height,
+            challenge_bundle.contribution_id
+        )));
+    }
+
     let s3_ctx = S3Ctx::new().await?;
-    let (contribution, contribution_sig) = s3_ctx.get_contribution(contribute_chunk_request.round_height).await?;
-    let mut write_lock = (*coordinator).clone().write_owned().await;
+    let (contribution, _) = s3_ctx.get_contribution(challenge_bundle.round_height).await?;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
 
     task::spawn_blocking(move || {
-        write_lock.write_contribution(contribute_chunk_request.contribution_locator, contribution)?;
-        write_lock.write_contribution_file_signature(
-            contribute_chunk_request.contribution_signature_locator,
-            serde_json::from_slice(&contribution_sig)?,
+        write_lock.write_contribution(
+            challenge_bundle.contribution_locator(),
+            contribution,
+            challenge_bundle.contribution_signature_locator(),
+            response_bundle.0.contribution_file_signature,
         )?;
-        write_lock.try_contribute(&participant, 0) // Only 1 chunk per round, chunk_id is always 0
+        write_lock.try_contribute(&participant, challenge_bundle.chunk_id)
     })
     .await?
     .map_or_else(|e| Err(ResponseError::CoordinatorError(e)), |_| Ok(()))
 }
 
+/// Get the contributions of the current round pending verification, with the votes collected so far. This endpoint is
+/// accessible only by the external verifiers of the verification quorum.
+#[get("/verifier/pending_verifications", format = "json")]
+pub async fn get_pending_verifications(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _verifier: QuorumVerifier,
+) -> Result<Json<Vec<VerificationQuorum>>> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || read_lock.pending_verification_quorums())
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Vote on the verification of a contribution of the current round, with the signed hash of the next challenge
+/// produced by the verifier. The contribution is verified once the quorum of verifiers agrees on it. This endpoint is
+/// accessible only by the external verifiers of the verification quorum.
+#[post("/verifier/vote", format = "json", data = "<request>")]
+pub async fn post_verification_vote(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    verifier: QuorumVerifier,
+    request: LazyJson<VerificationVoteRequest>,
+) -> Result<Json<QuorumStatus>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let vote = request.0;
+
+    task::spawn_blocking(move || {
+        if vote.round_height != write_lock.current_round_height()? {
+            return Err(CoordinatorError::RoundHeightMismatch);
+        }
+
+        write_lock.add_verification_vote(
+            &verifier,
+            &Task::new(vote.chunk_id, vote.contribution_id),
+            vote.next_challenge_hash,
+            vote.signature,
+        )
+    })
+    .await?
+    .map(Json)
+    .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Request the urls where to upload a contribution computed out-of-band and its ContributionFileSignature, to be
+/// injected with [`post_manual_contribution`]. This endpoint is accessible only by the operators.
+#[post("/operator/upload/chunk", format = "json", data = "<round_height>")]
+pub async fn get_manual_contribution_url(
+    _lifecycle: Lifecycle,
+    _operator: Operator,
+    round_height: LazyJson<u64>,
+) -> Result<Json<(String, String)>> {
+    contribution_urls(*round_height).await.map(Json)
+}
+
+/// Approve the manual injection of a contribution computed out-of-band on behalf of the contributor holding the lock of
+/// the current round. Once enough distinct operators approved it, the contribution uploaded to the urls of
+/// [`get_manual_contribution_url`] is injected and marked as such in the manifest of the round. This endpoint is
+/// accessible only by the operators.
+#[post("/operator/manual_contribution", format = "json", data = "<request>")]
+pub async fn post_manual_contribution(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    operator: Operator,
+    request: LazyJson<ManualContributionApproval>,
+) -> Result<Json<InjectionStatus>> {
+    let approval = request.0;
+    let contribution = approval.contribution.clone();
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    let status = task::spawn_blocking(move || {
+        write_lock.approve_manual_contribution(&operator, approval.contribution, approval.signature)
+    })
+    .await?
+    .map_err(|e| ResponseError::CoordinatorError(e))?;
+    if status != InjectionStatus::Approved {
+        return Ok(Json(status));
+    }
+
+    // Download the approved contribution and its signature from S3
+    let s3_ctx = S3Ctx::new().await?;
+    let (contribution_file, contribution_sig) = s3_ctx.get_contribution(contribution.round_height).await?;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || {
+        write_lock.inject_manual_contribution(
+            &contribution,
+            contribution_file,
+            serde_json::from_slice(&contribution_sig)?,
+        )
+    })
+    .await?
+    .map_or_else(
+        |e| Err(ResponseError::CoordinatorError(e)),
+        |_| Ok(Json(InjectionStatus::Injected)),
+    )
+}
+
+/// Commit to the share of the seed of the genesis challenge of an operator, before round 1 starts. The shares are
+/// revealed with [`reveal_genesis_seed`] once every operator committed to its share. This endpoint is accessible only
+/// by the operators.
+#[post("/operator/genesis_seed/commit", format = "json", data = "<request>")]
+pub async fn commit_genesis_seed(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    operator: Operator,
+    request: LazyJson<SeedCommitmentRequest>,
+) -> Result<Json<GenesisSeedStatus>> {
+    let request = request.0;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.commit_genesis_seed(&operator, request.commitment, request.signature))
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Reveal the share of the seed of the genesis challenge an operator committed to with [`commit_genesis_seed`]. Once
+/// every operator revealed its share, the seed is derived and written in the genesis challenge, and round 1 can start.
+/// This endpoint is accessible only by the operators.
+#[post("/operator/genesis_seed/reveal", format = "json", data = "<request>")]
+pub async fn reveal_genesis_seed(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    operator: Operator,
+    request: LazyJson<SeedRevealRequest>,
+) -> Result<Json<GenesisSeedStatus>> {
+    let request = request.0;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.reveal_genesis_seed(&operator, request.share, request.signature))
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
 /// Update the [Coordinator](`crate::Coordinator`) state. This endpoint is accessible only by the coordinator itself.
 #[cfg(debug_assertions)]
 #[get("/update")]
-pub async fn update_coordinator(coordinator: &State<Coordinator>, _auth: ServerAuth) -> Result<()> {
+pub async fn update_coordinator(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: ServerAuth,
+) -> Result<()> {
     rest_utils::perform_coordinator_update((*coordinator).clone()).await
 }
 
 /// Let the [Coordinator](`crate::Coordinator`) know that the participant is still alive and participating (or waiting to participate) in the ceremony.
 #[post("/contributor/heartbeat")]
-pub async fn heartbeat(coordinator: &State<Coordinator>, participant: Participant) -> Result<()> {
-    coordinator
-        .write()
+pub async fn heartbeat(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: Participant,
+) -> Result<()> {
+    LOCK_MONITOR
+        .write(coordinator)
         .await
         .heartbeat(&participant)
         .map_err(|e| ResponseError::CoordinatorError(e))
@@ -165,13 +844,52 @@ pub async fn stop_coordinator(_auth: ServerAuth, shutdown: Shutdown) {
 /// Verify all the pending contributions. This endpoint is accessible only by the coordinator itself.
 #[cfg(debug_assertions)]
 #[get("/verify")]
-pub async fn verify_chunks(coordinator: &State<Coordinator>, _auth: ServerAuth) -> Result<()> {
+pub async fn verify_chunks(_lifecycle: Lifecycle, coordinator: &State<Coordinator>, _auth: ServerAuth) -> Result<()> {
     rest_utils::perform_verify_chunks((*coordinator).clone(), &S3Ctx::new().await?).await
 }
 
+/// Reissue the challenge of the current round, e.g. after a restore of the storage, and upload it again to Amazon S3.
+/// Returns the contributors which lost their lock and must restart the contribution. This endpoint is accessible only by
+/// the coordinator itself.
+#[get("/reissue_challenge")]
+pub async fn reissue_challenge(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: ServerAuth,
+) -> Result<Json<Vec<Participant>>> {
+    let s3_ctx = S3Ctx::new().await?;
+
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let (challenge_locator, file, restarting_contributors) =
+        task::spawn_blocking(move || -> std::result::Result<_, CoordinatorError> {
+            let restarting_contributors = write_lock.reissue_challenge()?;
+            let round_height = write_lock.current_round_height()?;
+            // Only 1 chunk per round, chunk_id is always 0
+            let challenge_locator = ContributionLocator::new(round_height, 0, 0, true);
+            let (_, file) = write_lock.open_challenge(&challenge_locator)?;
+
+            Ok((challenge_locator, file, restarting_contributors))
+        })
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    // The reissued challenge is opened under the lock and read once it is released
+    let challenge = task::spawn_blocking(move || Disk::read_contribution_file(file, &challenge_locator))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+    let round_height = challenge_locator.round_height();
+
+    // Overwrite the challenge on S3, which may not match the reissued one
+    let key = format!("round_{}/chunk_0/contribution_0.verified", round_height);
+    s3_ctx.upload_challenge(key, challenge).await?;
+
+    Ok(Json(restarting_contributors))
+}
+
 /// Load new tokens to update the future cohorts. The `tokens` parameter is the serialized zip folder
 #[post("/update_cohorts", format = "json", data = "<tokens>")]
 pub async fn update_cohorts(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
     _auth: ServerAuth,
     tokens: LazyJson<Vec<u8>>,
@@ -201,8 +919,8 @@ pub async fn update_cohorts(
     .unwrap()?;
 
     // Check that the new tokens for the current cohort match the old ones (to prevent inconsistencies during contributions in the current cohort)
-    let read_lock = coordinator.read().await;
-    let cohort = read_lock.state().get_current_cohort_index();
+    let read_lock = LOCK_MONITOR.read(coordinator).await;
+    let cohort = read_lock.current_cohort_index();
     let old_tokens = match read_lock.state().tokens(cohort) {
         Some(t) => t,
         None => return Err(ResponseError::CeremonyIsOver),
@@ -246,57 +964,125 @@ pub async fn update_cohorts(
     .unwrap()?;
 
     // Update cohorts in coordinator's state
-    coordinator.write().await.update_tokens(new_tokens);
+    LOCK_MONITOR.write(coordinator).await.update_tokens(new_tokens);
 
     Ok(())
 }
 
-/// Get the queue status of the contributor.
+/// Get the queue status of the contributor, with the code of its message in the
+/// [message catalog](`crate::messages::MessageCatalog`).
 #[get("/contributor/queue_status", format = "json")]
 pub async fn get_contributor_queue_status(
     coordinator: &State<Coordinator>,
     participant: Participant,
-) -> Json<ContributorStatus> {
-    let contributor = participant.clone();
+) -> Result<WithMessageCode<Json<ContributorStatus>>> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let status = task::spawn_blocking(move || contributor_status(&read_lock, &participant)).await?;
 
-    let read_lock = (*coordinator).clone().read_owned().await;
-    // Check that the contributor is authorized to lock a chunk in the current round.
-    if task::spawn_blocking(move || read_lock.is_current_contributor(&contributor))
-        .await
-        .unwrap()
-    {
-        return Json(ContributorStatus::Round);
-    }
-
-    let read_lock = coordinator.read().await;
+    let (code, _) = status.message();
+    Ok(WithMessageCode(Json(status), code))
+}
 
-    if read_lock.is_queue_contributor(&participant) {
-        let queue_size = read_lock.number_of_queue_contributors() as u64;
+/// Stream the changes of the queue status of the contributor as Server-Sent Events, instead of polling
+/// `/contributor/queue_status`: the [`QueueEvent`]s `status` when the [`ContributorStatus`] changes, `your_turn` when
+/// the contributor enters the current round and `round_completed` when the ceremony advances to a new round. The status
+/// is read again on each event of the ceremony, and at least every [`QUEUE_EVENTS_REFRESH`].
+#[get("/contributor/queue_events")]
+pub async fn get_contributor_queue_events(
+    coordinator: &State<Coordinator>,
+    queue_events: &State<QueueEvents>,
+    participant: Participant,
+    mut shutdown: Shutdown,
+) -> EventStream![] {
+    let coordinator = coordinator.inner().clone();
+    let mut receiver = queue_events.subscribe();
 
-        let queue_position = match read_lock.state().queue_contributor_info(&participant) {
-            Some((_, Some(round), _, _)) => round - read_lock.state().current_round_height(),
-            Some((_, None, _, _)) => queue_size,
-            None => return Json(ContributorStatus::Other),
-        };
+    EventStream! {
+        let mut last_status = None;
+        loop {
+            let read_lock = LOCK_MONITOR.read_owned(coordinator.clone()).await;
+            let contributor = participant.clone();
+            let status = match task::spawn_blocking(move || contributor_status(&read_lock, &contributor)).await {
+                Ok(status) => status,
+                Err(_) => break,
+            };
+            if last_status.as_ref() != Some(&status) {
+                let your_turn = status == ContributorStatus::Round;
+                let event = QueueEvent::Status { status: status.clone() };
+                yield Event::json(&event).event(event.name());
+                if your_turn {
+                    yield Event::json(&QueueEvent::YourTurn).event(QueueEvent::YourTurn.name());
+                }
+                last_status = Some(status);
+            }
 
-        return Json(ContributorStatus::Queue(queue_position, queue_size));
+            let event = tokio::select! {
+                event = receiver.recv() => event,
+                _ = tokio::time::sleep(QUEUE_EVENTS_REFRESH) => continue,
+                _ = &mut shutdown => break,
+            };
+            match event {
+                Ok(CeremonyEvent::RoundAdvanced { round_height }) => {
+                    let event = QueueEvent::RoundCompleted { round_height };
+                    yield Event::json(&event).event(event.name());
+                }
+                // A stream lagging behind reads the latest status anyway
+                Ok(_) | Err(RecvError::Lagged(_)) => (),
+                Err(RecvError::Closed) => break,
+            }
+        }
     }
+}
 
-    if read_lock.is_finished_contributor(&participant) {
-        return Json(ContributorStatus::Finished);
+/// Returns the status of the given participant related to the current round. The round of the current contributors is
+/// read from storage, to be called in a blocking task.
+pub(crate) fn contributor_status(coordinator: &crate::Coordinator, participant: &Participant) -> ContributorStatus {
+    // Answered from memory, only the round of the current contributors is read from storage
+    if coordinator.must_restart(participant) {
+        // The lock of the contributor was released by a challenge reissue
+        ContributorStatus::Restart
+    } else if coordinator.is_current_contributor(participant) {
+        // The contributor is authorized to lock a chunk in the current round
+        if coordinator.is_paused_by_verification_backlog(participant) {
+            ContributorStatus::VerificationBacklog(coordinator.verification_backlog() as u64)
+        } else {
+            ContributorStatus::Round
+        }
+    } else if let Some((queue_position, queue_size)) = coordinator.queue_position(participant) {
+        ContributorStatus::Queue(queue_position, queue_size)
+    } else if coordinator.is_finished_contributor(participant) {
+        ContributorStatus::Finished
+    } else if coordinator.is_banned_participant(participant) {
+        ContributorStatus::Banned
+    } else {
+        // Not in the queue, not finished, nor in the current round
+        ContributorStatus::Other
     }
+}
 
-    if read_lock.is_banned_participant(&participant) {
-        return Json(ContributorStatus::Banned);
-    }
+/// Get the messages of the statuses and the errors in the language accepted by the client, by code.
+#[get("/messages", format = "json")]
+pub async fn get_messages(language: AcceptLanguage) -> Json<LocalizedCatalog> {
+    Json(MESSAGE_CATALOG.localized(language.0))
+}
 
-    // Not in the queue, not finished, nor in the current round
-    Json(ContributorStatus::Other)
+/// Get the status of the verification of the latest contribution submitted by the contributor.
+#[get("/contributor/verification_status", format = "json")]
+pub async fn get_verification_status(
+    tracker: &State<VerificationTracker>,
+    participant: Participant,
+) -> Result<Json<VerificationStatus>> {
+    match tracker.get(&participant) {
+        Some(status) => Ok(Json(status)),
+        None => Err(ResponseError::UnknownContributor(participant.address())),
+    }
 }
 
-/// Write [`ContributionInfo`] to disk
+/// Write [`ContributionInfo`] to disk. The info must be posted once the contribution has been received by the
+/// [Coordinator](`crate::Coordinator`), and its `contribution_file_hash` must match the hash of the received response file
 #[post("/contributor/contribution_info", format = "json", data = "<request>")]
 pub async fn post_contribution_info(
+    _lifecycle: Lifecycle,
     coordinator: &State<Coordinator>,
     participant: CurrentContributor,
     request: LazyJson<ContributionInfo>,
@@ -310,7 +1096,12 @@ pub async fn post_contribution_info(
         )));
     }
 
-    let current_round_height = match coordinator.read().await.current_round_height() {
+    // The name and email are published as they are
+    request
+        .validate()
+        .map_err(|e| ResponseError::InvalidContributionInfo(e.to_string()))?;
+
+    let current_round_height = match LOCK_MONITOR.read(coordinator).await.current_round_height() {
         Ok(r) => r,
         Err(e) => return Err(ResponseError::CoordinatorError(e)),
     };
@@ -323,39 +1114,84 @@ pub async fn post_contribution_info(
         )));
     }
 
-    // Write contribution info and summary to file
-    let mut write_lock = (*coordinator).clone().write_owned().await;
+    if request.destruction_attestation.is_some() {
+        return Err(ResponseError::InvalidContributionInfo(
+            "The destruction attestation must be posted to /contributor/destruction_attestation".to_string(),
+        ));
+    }
+
+    if request.offline_contribution.is_some()
+        != LOCK_MONITOR
+            .read(coordinator)
+            .await
+            .is_offline_contributor(&participant)
+    {
+        return Err(ResponseError::InvalidContributionInfo(format!(
+            "Offline contribution in info doesn't match the flow followed by the participant {}",
+            participant.address()
+        )));
+    }
+
+    // Check that the info refers to a contribution received by the coordinator
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let contributor = (*participant).clone();
+    let contribution_file_hashes = task::spawn_blocking(move || read_lock.contribution_file_hashes(&contributor))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    if contribution_file_hashes.is_empty() {
+        return Err(ResponseError::InvalidContributionInfo(format!(
+            "No contribution received from the participant {}",
+            participant.address()
+        )));
+    }
+
+    if !contribution_file_hashes
+        .iter()
+        .any(|hash| hash.eq_ignore_ascii_case(&request.contribution_file_hash))
+    {
+        return Err(ResponseError::InvalidContributionInfo(format!(
+            "Contribution file hash in info {} doesn't match the contribution received by the coordinator",
+            request.contribution_file_hash
+        )));
+    }
+
+    // Write contribution info and summary to file, then expect the attestation of the destruction of the toxic waste
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let contributor = (*participant).clone();
 
     task::spawn_blocking(move || {
         write_lock.write_contribution_info(request.clone())?;
 
-        write_lock.update_contribution_summary(request.0.into())
+        let round_height = request.ceremony_round;
+        write_lock.update_contribution_summary(request.0.into())?;
+        write_lock.expect_destruction_attestation(&contributor, round_height)
     })
     .await?
     .map_err(|e| ResponseError::CoordinatorError(e))
 }
 
-/// Uploads the attestation for a contribution
-#[post("/contributor/attestation", format = "json", data = "<request>")]
-pub async fn post_attestation(
-    coordinator: &State<Coordinator>,
+/// Checks that the participant is the finished contributor of the given round, the only one allowed to attest it. A
+/// round without contribution has nothing to attest
+async fn check_attestation_round(
+    coordinator: &Coordinator,
     participant: Participant,
-    request: LazyJson<(u64, String)>,
+    round: u64,
+    endpoint: &'static str,
 ) -> Result<()> {
-    let (round, attestation) = request.0;
-
-    // Check url format
-    if let Err(e) = Url::parse(attestation.as_str()) {
-        return Err(ResponseError::IoError(e.to_string()));
-    }
-
-    let read_lock = (*coordinator).clone().read_owned().await;
+    let read_lock = LOCK_MONITOR.read_owned(coordinator.clone()).await;
     task::spawn_blocking(move || {
+        let locator = Locator::ContributionInfoFile { round_height: round };
+        if !read_lock.storage().exists(&locator) {
+            return Err(ResponseError::CoordinatorError(
+                crate::CoordinatorError::ContributionMissing,
+            ));
+        }
         if !read_lock.is_finished_contributor_at_round(&participant, round) {
             // Only finished contributors are allowed to query this endpoint
             return Err(ResponseError::UnauthorizedParticipant(
                 participant,
-                "/contributor/attestation".to_string(),
+                endpoint.to_string(),
                 "Not a current nor finished contributor".to_string(),
             ));
         }
@@ -363,7 +1199,7 @@ pub async fn post_attestation(
         // Check the provided round height matches the signing participant
         match read_lock
             .storage()
-            .get(&Locator::ContributionInfoFile { round_height: round })
+            .get(&locator)
             .map_err(|e| ResponseError::CoordinatorError(e))?
         {
             Object::ContributionInfoFile(f) => {
@@ -378,32 +1214,129 @@ pub async fn post_attestation(
             _ => Err(ResponseError::CoordinatorError(crate::CoordinatorError::StorageFailed)),
         }
     })
-    .await??;
+    .await?
+}
+
+/// Uploads the attestation for a contribution
+#[post("/contributor/attestation", format = "json", data = "<request>")]
+pub async fn post_attestation(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: Participant,
+    request: LazyJson<(u64, String)>,
+) -> Result<()> {
+    let (round, attestation) = request.0;
+
+    // Check url format
+    if let Err(e) = Url::parse(attestation.as_str()) {
+        return Err(ResponseError::InvalidAttestationPost(e.to_string()));
+    }
+
+    check_attestation_round(coordinator, participant, round, "/contributor/attestation").await?;
 
     // Update the contribution info and the summary with the attestation
-    let mut write_lock = (*coordinator).clone().write_owned().await;
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
 
-    task::spawn_blocking(move || write_lock.update_contribution_info_attestation(round, attestation))
+    task::spawn_blocking(move || write_lock.update_contribution_info_attestation(round, attestation, None))
         .await?
         .map_err(|e| ResponseError::CoordinatorError(e))
 }
 
+/// Registers the public post attesting a contribution, e.g. a tweet. The coordinator fetches the post and checks the
+/// hash of its content against the one provided by the contributor, then archives the hash in the contribution info and
+/// in the audit log, so that later edits or deletions of the post are detectable
+#[post("/contributor/attestation_post", format = "json", data = "<request>")]
+pub async fn post_attestation_post(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: Participant,
+    request: LazyJson<AttestationPostRequest>,
+) -> Result<()> {
+    let request = request.0;
+
+    // Check url format, only public web pages can be archived
+    match Url::parse(request.url.as_str()) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => (),
+        Ok(url) => return Err(ResponseError::InvalidAttestationPost(format!("unsupported scheme {}", url.scheme()))),
+        Err(e) => return Err(ResponseError::InvalidAttestationPost(e.to_string())),
+    }
+
+    check_attestation_round(
+        coordinator,
+        participant.clone(),
+        request.round_height,
+        "/contributor/attestation_post",
+    )
+    .await?;
+
+    let content_hash = rest_utils::fetch_attestation_post(&request.url).await?;
+    if content_hash != request.content_hash.to_lowercase() {
+        return Err(ResponseError::MismatchingChecksum(request.content_hash, content_hash));
+    }
+
+    // Archive the post in the contribution info, the summary and the audit log
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || {
+        write_lock.record_attestation_post(&participant, request.round_height, request.url, content_hash)
+    })
+    .await?
+    .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Attests the destruction of the toxic waste of a contribution, the randomness used to compute it. The attestation is
+/// signed by the contributor and bound to the hash of its contribution file, then published in the contribution info
+/// and in the summary. Until then, the contributor is reminded through the events of the ceremony
+#[post("/contributor/destruction_attestation", format = "json", data = "<request>")]
+pub async fn post_destruction_attestation(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    participant: Participant,
+    request: LazyJson<DestructionAttestationRequest>,
+) -> Result<()> {
+    let request = request.0;
+
+    // The details are published as they are
+    request
+        .attestation
+        .validate()
+        .map_err(|e| ResponseError::InvalidDestructionAttestation(e.to_string()))?;
+
+    check_attestation_round(
+        coordinator,
+        participant.clone(),
+        request.attestation.round_height,
+        "/contributor/destruction_attestation",
+    )
+    .await?;
+
+    // Publish the attestation in the contribution info and the summary
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || {
+        write_lock.record_destruction_attestation(&participant, request.attestation, request.signature)
+    })
+    .await?
+    .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
 /// Retrieve the contributions' info. This endpoint is accessible by anyone and does not require a signed request.
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "dashboard"))]
 #[get("/contribution_info")]
-pub async fn get_contributions_info(coordinator: &State<Coordinator>) -> Result<Vec<u8>> {
-    let read_lock = (*coordinator).clone().read_owned().await;
+pub async fn get_contributions_info(coordinator: &State<Coordinator>) -> Result<(rocket::http::ContentType, Vec<u8>)> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
     let summary = task::spawn_blocking(move || read_lock.storage().get_contributions_summary())
         .await?
         .map_err(|e| ResponseError::CoordinatorError(e))?;
 
-    Ok(summary)
+    // The names of the contributors are in any script, the charset keeps the clients from guessing it
+    Ok((rocket::http::ContentType::JSON.with_params(("charset", "utf-8")), summary))
 }
 
 /// Retrieve the coordinator.json status file
 #[get("/coordinator_status")]
 pub async fn get_coordinator_state(coordinator: &State<Coordinator>, _auth: Secret) -> Result<Vec<u8>> {
-    let read_lock = (*coordinator).clone().read_owned().await;
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
     let state = task::spawn_blocking(move || read_lock.storage().get_coordinator_state())
         .await?
         .map_err(|e| ResponseError::CoordinatorError(e))?;
@@ -411,6 +1344,329 @@ pub async fn get_coordinator_state(coordinator: &State<Coordinator>, _auth: Secr
     Ok(state)
 }
 
+/// Retrieve all the participants ever seen by the coordinator, with their final disposition, timestamps and the entries of
+/// the audit log concerning them
+#[get("/participants_history", format = "json")]
+pub async fn get_participants_history(
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+) -> Result<Json<Vec<ParticipantRecord>>> {
+    Ok(Json(LOCK_MONITOR.read(coordinator).await.participant_records()))
+}
+
+/// Retrieve the audit log, the statistics of the rounds and the dispositions of the participants as CSV, the default,
+/// or Parquet tables, with their schema, in a zip archive. See [`export`](crate::export) for the schema of the tables
+#[get("/admin/export?<format>")]
+pub async fn get_export(
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    format: Option<&str>,
+) -> Result<(rocket::http::ContentType, Vec<u8>)> {
+    let format: ExportFormat = format
+        .unwrap_or("csv")
+        .parse()
+        .map_err(|_| ResponseError::UnsupportedExportFormat(format.unwrap_or_default().to_owned()))?;
+
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let snapshot = task::spawn_blocking(move || read_lock.backup_snapshot())
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+    let archive = task::spawn_blocking(move || CeremonyExport::from_snapshot(&snapshot).archive(format))
+        .await?
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+    Ok((rocket::http::ContentType::ZIP, archive))
+}
+
+/// Retrieve the number of objects and the space used on each storage shard
+#[get("/storage/shards", format = "json")]
+pub async fn get_storage_shards(coordinator: &State<Coordinator>, _auth: Secret) -> Result<Json<Vec<ShardUsage>>> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let usage = task::spawn_blocking(move || read_lock.storage_shards())
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(usage))
+}
+
+/// Move the objects in storage to their assigned shard, after a change of the configured shards. Returns the number of
+/// moved objects
+#[post("/storage/rebalance")]
+pub async fn rebalance_storage(coordinator: &State<Coordinator>, _auth: Secret) -> Result<Json<usize>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let moved = task::spawn_blocking(move || write_lock.rebalance_storage())
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(moved))
+}
+
+/// Queue the verified contributions of the completed rounds, from the given one onwards, to be verified again by the
+/// verification workers, without altering the progression of the ceremony. Returns the newly queued contributions
+#[post("/admin/reverify?<from_round>")]
+pub async fn post_reverify(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    from_round: u64,
+) -> Result<Json<Vec<ReverificationTask>>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let queued = task::spawn_blocking(move || -> Result<_> {
+        let current_round_height = write_lock
+            .current_round_height()
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        if from_round >= current_round_height {
+            return Err(ResponseError::InvalidRoundRange(from_round, current_round_height.saturating_sub(1)));
+        }
+
+        write_lock
+            .queue_reverification(from_round)
+            .map_err(|e| ResponseError::CoordinatorError(e))
+    })
+    .await??;
+
+    Ok(Json(queued))
+}
+
+/// Retrieve the contributions waiting to be verified again and the reports of their latest re-verification
+#[get("/admin/reverify", format = "json")]
+pub async fn get_reverification_status(coordinator: &State<Coordinator>, _auth: Secret) -> Json<ReverificationStatus> {
+    Json(LOCK_MONITOR.read(coordinator).await.reverification_status())
+}
+
+/// Retrieve the wait times of the acquisitions of the lock of the coordinator by call site, and its current holders and
+/// waiters
+#[get("/admin/lock_metrics", format = "json")]
+pub async fn get_lock_metrics(_auth: Secret) -> Json<LockMetrics> {
+    Json(LOCK_MONITOR.metrics())
+}
+
+/// Retrieve the time spent in each phase of the startup of the coordinator
+#[get("/admin/startup_profile", format = "json")]
+pub async fn get_startup_profile(coordinator: &State<Coordinator>, _auth: Secret) -> Json<StartupProfile> {
+    Json(LOCK_MONITOR.read(coordinator).await.startup_profile().clone())
+}
+
+/// Retrieve the status of the deliveries of the webhooks of the published rounds
+#[get("/admin/webhooks", format = "json")]
+pub async fn get_webhook_deliveries(webhooks: &State<RoundWebhooks>, _auth: Secret) -> Json<Vec<WebhookDelivery>> {
+    Json(webhooks.deliveries())
+}
+
+/// Issue a delegation, signed by the coordinator, allowing the external verifier with the given public key to call the
+/// verification endpoints for the rounds `from_round..=to_round` during `duration_seconds`. The verifier sends it
+/// base64 encoded in the `ATS-Delegation` header of its requests
+#[post("/admin/delegate_verifier?<verifier>&<from_round>&<to_round>&<duration_seconds>")]
+pub async fn post_verifier_delegation(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    verifier: String,
+    from_round: u64,
+    to_round: u64,
+    duration_seconds: u32,
+) -> Result<Json<SignedVerifierDelegation>> {
+    let verifier = Participant::new_verifier(&verifier);
+    let duration = time::Duration::seconds(duration_seconds.into());
+
+    LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .delegate_verifier(&verifier, from_round, to_round, duration)
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Get the public keys allowed to sign the privileged requests of the coordinator
+#[get("/admin/control_keys")]
+pub async fn get_control_keys(coordinator: &State<Coordinator>, _auth: Secret) -> Json<BTreeSet<String>> {
+    Json(LOCK_MONITOR.read(coordinator).await.control_keys())
+}
+
+/// Add a public key allowed to sign the privileged requests of the coordinator, and return the control keys
+#[post("/admin/control_keys/add?<pubkey>")]
+pub async fn add_control_key(
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    pubkey: String,
+) -> Result<Json<BTreeSet<String>>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.add_control_key(&pubkey))
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Revoke a public key allowed to sign the privileged requests of the coordinator, and return the control keys. Fails
+/// with a conflict for the last control key
+#[post("/admin/control_keys/remove?<pubkey>")]
+pub async fn remove_control_key(
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    pubkey: String,
+) -> Result<Json<BTreeSet<String>>> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.remove_control_key(&pubkey))
+        .await?
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Retrieve the attempts of the verification of the contributions of the current round which crashed the verifier. The
+/// contributions whose attempts exceeded the maximum number of retries are quarantined until the operators reject them
+/// or verify them once more
+#[get("/admin/verification_quarantine", format = "json")]
+pub async fn get_verification_quarantine(
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+) -> Json<Vec<VerificationRetries>> {
+    Json(LOCK_MONITOR.read(coordinator).await.verification_retries())
+}
+
+/// Reject a quarantined contribution as an invalid one: the round is reset and the contributor banned
+#[post("/admin/verification_quarantine/reject?<chunk_id>&<contribution_id>")]
+pub async fn reject_quarantined_contribution(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    chunk_id: u64,
+    contribution_id: u64,
+) -> Result<()> {
+    resolve_quarantined_contribution(
+        coordinator,
+        Task::new(chunk_id, contribution_id),
+        QuarantineDecision::Reject,
+    )
+    .await
+}
+
+/// Verify a quarantined contribution once more, e.g. after raising the limits of the sandbox. The contribution stays
+/// quarantined if it crashes the verifier again
+#[post("/admin/verification_quarantine/verify?<chunk_id>&<contribution_id>")]
+pub async fn verify_quarantined_contribution(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+    chunk_id: u64,
+    contribution_id: u64,
+) -> Result<()> {
+    resolve_quarantined_contribution(
+        coordinator,
+        Task::new(chunk_id, contribution_id),
+        QuarantineDecision::Verify,
+    )
+    .await
+}
+
+async fn resolve_quarantined_contribution(
+    coordinator: &State<Coordinator>,
+    task: Task,
+    decision: QuarantineDecision,
+) -> Result<()> {
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+
+    task::spawn_blocking(move || write_lock.resolve_quarantined_verification(&task, decision))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Save a snapshot of the state of the coordinator before the launch of the ceremony, with its configuration, the
+/// allowlist and the reservations and registrations of the contributors in the queue, to be restored after a dress
+/// rehearsal of the launch. Fails with a conflict once a contributor has been assigned to a round
+#[post("/admin/prelaunch/snapshot")]
+pub async fn post_prelaunch_snapshot(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+) -> Result<Json<PrelaunchSummary>> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let summary = task::spawn_blocking(move || read_lock.snapshot_prelaunch())
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(summary))
+}
+
+/// Restore the pre-launch snapshot after a dress rehearsal of the launch, wiping the rounds and the artifacts of the
+/// rehearsal, in storage and on Amazon S3, while keeping the registrations of the contributors
+#[post("/admin/prelaunch/restore")]
+pub async fn post_prelaunch_restore(
+    _lifecycle: Lifecycle,
+    coordinator: &State<Coordinator>,
+    _auth: Secret,
+) -> Result<Json<PrelaunchRestore>> {
+    let s3_ctx = S3Ctx::new().await?;
+
+    let mut write_lock = LOCK_MONITOR.write_owned((*coordinator).clone()).await;
+    let restore = task::spawn_blocking(move || write_lock.restore_prelaunch())
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    // Keep the rehearsal contributions and challenges from being served again once the rounds are run for real
+    for round_height in &restore.removed_rounds {
+        s3_ctx.delete_round(*round_height, false).await?;
+    }
+    for round_height in &restore.cleared_rounds {
+        s3_ctx.delete_round(*round_height, true).await?;
+    }
+
+    Ok(Json(restore))
+}
+
+/// Returns the storage base directory and the manifests of the rounds in the range `from_round..=to_round`.
+async fn transcript_manifests(
+    coordinator: &State<Coordinator>,
+    from_round: u64,
+    to_round: u64,
+) -> Result<(PathBuf, Vec<RoundManifest>)> {
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    task::spawn_blocking(move || -> Result<_> {
+        let current_round_height = read_lock
+            .current_round_height()
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+        if from_round > to_round || to_round > current_round_height {
+            return Err(ResponseError::InvalidRoundRange(from_round, to_round));
+        }
+
+        let manifests = (from_round..=to_round)
+            .map(|round_height| read_lock.round_manifest(round_height))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+        Ok((PathBuf::from(read_lock.environment().local_base_directory()), manifests))
+    })
+    .await?
+}
+
+/// Stream a tar archive of the transcript of the rounds in the range `from_round..=to_round`, including the manifest of
+/// each round. The length of the archive is sent in the `Content-Length` header, also answered to `HEAD` requests. This
+/// endpoint is accessible by anyone and does not require a signed request.
+#[get("/transcript?<from_round>&<to_round>")]
+pub async fn get_transcript(
+    coordinator: &State<Coordinator>,
+    from_round: u64,
+    to_round: u64,
+) -> Result<SizedStream<ByteStream![Vec<u8>]>> {
+    let (base_directory, manifests) = transcript_manifests(coordinator, from_round, to_round).await?;
+
+    Ok(rest_utils::stream_transcript(base_directory, manifests))
+}
+
+/// Get the manifests of the rounds in the range `from_round..=to_round`, without the files of the transcript, to compare
+/// the transcripts of two coordinators. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/transcript/manifests?<from_round>&<to_round>", format = "json")]
+pub async fn get_transcript_manifests(
+    coordinator: &State<Coordinator>,
+    from_round: u64,
+    to_round: u64,
+) -> Result<Json<Vec<RoundManifest>>> {
+    let (_, manifests) = transcript_manifests(coordinator, from_round, to_round).await?;
+
+    Ok(Json(manifests))
+}
+
 /// Retrieve healthcheck info. This endpoint is accessible by anyone and does not require a signed request.
 #[get("/healthcheck", format = "json")]
 pub async fn get_healthcheck() -> Result<String> {
@@ -420,3 +1676,150 @@ pub async fn get_healthcheck() -> Result<String> {
 
     Ok(content)
 }
+
+/// Retrieve a summary of the state of the ceremony. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/ceremony_status", format = "json")]
+pub async fn get_ceremony_status(coordinator: &State<Coordinator>) -> Result<Json<CeremonyStatus>> {
+    let read_lock = LOCK_MONITOR.read(coordinator).await;
+
+    Ok(Json(CeremonyStatus {
+        round_height: read_lock
+            .current_round_height()
+            .map_err(|e| ResponseError::CoordinatorError(e))?,
+        queue_size: read_lock.number_of_queue_contributors(),
+        contribution_in_progress: read_lock.number_of_current_contributors() > 0,
+        update_interval: read_lock.update_interval().as_secs(),
+        rehearsal: read_lock.environment().is_rehearsal(),
+        phase: read_lock.ceremony_phase().map(|phase| phase.name.clone()),
+    }))
+}
+
+/// Retrieve the public status of the ceremony, with the current contributor and the progress of the ceremony, for the
+/// dashboards. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/ceremony/status", format = "json")]
+pub async fn get_public_ceremony_status(coordinator: &State<Coordinator>) -> Result<Json<PublicCeremonyStatus>> {
+    let read_lock = LOCK_MONITOR.read(coordinator).await;
+    let current_contributor = match read_lock.current_contributor_ids().next() {
+        Some(participant) if *ANONYMIZE_PUBLIC_STATUS => Some(rest_utils::anonymized_id(participant)),
+        Some(participant) => Some(participant.address()),
+        None => None,
+    };
+
+    Ok(Json(PublicCeremonyStatus {
+        round_height: read_lock
+            .current_round_height()
+            .map_err(|e| ResponseError::CoordinatorError(e))?,
+        current_contributor,
+        queue_size: read_lock.number_of_queue_contributors(),
+        completed_contributions: read_lock.number_of_finished_contributors(),
+        uptime: read_lock.uptime().whole_seconds().max(0) as u64,
+    }))
+}
+
+/// Retrieve the changelog of the REST API, with the versions of the protocol and the deprecated endpoints, signed by the
+/// coordinator. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/api/changelog", format = "json")]
+pub async fn get_api_changelog(coordinator: &State<Coordinator>) -> Result<Json<SignedChangelog>> {
+    let changelog = LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .signed_changelog()
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(changelog))
+}
+
+/// Retrieve the statement of the participation of a contributor, signed by the coordinator, as `<pubkey>.json` or as an
+/// `<pubkey>.svg` image embedding the signed statement, to display on third-party sites. This endpoint is accessible by
+/// anyone and does not require a signed request.
+#[get("/badge/<file>")]
+pub async fn get_badge(coordinator: &State<Coordinator>, file: &str) -> Result<(rocket::http::ContentType, String)> {
+    let (public_key, svg) = match (file.strip_suffix(".json"), file.strip_suffix(".svg")) {
+        (Some(public_key), _) => (public_key, false),
+        (_, Some(public_key)) => (public_key, true),
+        _ => return Err(ResponseError::UnknownBadgeFormat(file.to_string())),
+    };
+
+    let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+    let public_key = public_key.to_string();
+    let badge = task::spawn_blocking(move || read_lock.participation_badge(&public_key))
+        .await?
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    if svg {
+        let image = badge.svg().map_err(|e| ResponseError::SerdeError(e.to_string()))?;
+        Ok((rocket::http::ContentType::SVG, image))
+    } else {
+        let json = serde_json::to_string(&badge).map_err(|e| ResponseError::SerdeError(e.to_string()))?;
+        Ok((rocket::http::ContentType::JSON, json))
+    }
+}
+
+/// Retrieve the registry of the circuits targeted by the ceremony. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/circuits", format = "json")]
+pub async fn get_circuit_registry(coordinator: &State<Coordinator>) -> Json<CircuitRegistry> {
+    Json(LOCK_MONITOR.read(coordinator).await.circuit_registry().clone())
+}
+
+/// Retrieve the identity of the ceremony to display to the contributors, signed by the coordinator. This endpoint is
+/// accessible by anyone and does not require a signed request.
+#[get("/ceremony/metadata", format = "json")]
+pub async fn get_ceremony_metadata(coordinator: &State<Coordinator>) -> Result<Json<SignedCeremonyMetadata>> {
+    let metadata = LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .signed_ceremony_metadata()
+        .map_err(|e| ResponseError::CoordinatorError(e))?;
+
+    Ok(Json(metadata))
+}
+
+/// Retrieve the parameters of the ceremony, with the expected sizes of the challenge and the response of the current
+/// round and the hash algorithms, for clients to detect a misconfiguration before contributing. This endpoint is
+/// accessible by anyone and does not require a signed request.
+#[get("/ceremony/parameters", format = "json")]
+pub async fn get_ceremony_parameters(coordinator: &State<Coordinator>) -> Result<Json<CeremonyParameters>> {
+    LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .ceremony_parameters()
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Retrieve the announcements of the coordinator to the participants, e.g. the approaching end of the ceremony. This
+/// endpoint is accessible by anyone and does not require a signed request.
+#[get("/ceremony/announcements", format = "json")]
+pub async fn get_announcements(coordinator: &State<Coordinator>) -> Result<Json<Vec<Announcement>>> {
+    LOCK_MONITOR
+        .read(coordinator)
+        .await
+        .announcements()
+        .map(Json)
+        .map_err(|e| ResponseError::CoordinatorError(e))
+}
+
+/// Retrieve the shuffles of the queue of the rounds, with their seeds and the order of the queue they were applied to,
+/// so that anyone can recompute the contributors assigned to each round. This endpoint is accessible by anyone and does
+/// not require a signed request.
+#[get("/queue/shuffles", format = "json")]
+pub async fn get_queue_shuffles(coordinator: &State<Coordinator>) -> Json<Vec<QueueShuffle>> {
+    Json(LOCK_MONITOR.read(coordinator).await.queue_shuffles())
+}
+
+/// Retrieve the statistics of the time spent computing the contributions, as measured by the coordinator and as proved
+/// by the proofs of computation time of the contributors, flagging the anomalously fast contributions. This endpoint
+/// is accessible by anyone and does not require a signed request.
+#[get("/ceremony/computation_stats", format = "json")]
+pub async fn get_computation_stats(coordinator: &State<Coordinator>) -> Json<ComputationStats> {
+    Json(LOCK_MONITOR.read(coordinator).await.computation_stats())
+}
+
+/// Retrieve the commitments and the reveals of the shares of the seed of the genesis challenge by the operators, and
+/// the state of its derivation. This endpoint is accessible by anyone and does not require a signed request.
+#[get("/ceremony/genesis_seed", format = "json")]
+pub async fn get_genesis_seed(coordinator: &State<Coordinator>) -> Json<GenesisSeedSummary> {
+    let (genesis_seed, status) = LOCK_MONITOR.read(coordinator).await.genesis_seed();
+
+    Json(GenesisSeedSummary { status, genesis_seed })
+}
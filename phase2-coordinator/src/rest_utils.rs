@@ -1,44 +1,88 @@
 // Utility types for the rest API
 
 use crate::{
-    authentication::{Production, Signature},
-    coordinator_state::TOKEN_BLACKLIST,
-    objects::Task,
+    authentication::{ssh, Production, Signature},
+    capability_probe::CapabilityProof,
+    compression::{ContentEncoding, CONTENT_ENCODING_HEADER},
+    events::CeremonyEvent,
+    hashing::ContributionHasher,
+    ip_prefix::{canonical_ip, parse_forwarded_ip},
+    lifecycle::LifecycleConflict,
+    lock_monitor::LOCK_MONITOR,
+    messages::{DEFAULT_LANGUAGE, MESSAGE_CATALOG},
+    objects::{
+        ComputationProof, ContributionFileSignature, DestructionAttestation, GenesisSeed, GenesisSeedStatus,
+        IdentityClaim, ManualContribution, SignedVerifierDelegation, Task, VerificationStatus,
+    },
+    pow::PowSolution,
     s3::{S3Ctx, S3Error},
-    storage::{ContributionLocator, ContributionSignatureLocator},
+    storage::{
+        tar_header, tar_padding, ContributionLocator, ContributionSignatureLocator, RoundManifest, TranscriptFile,
+        TAR_BLOCK_SIZE,
+    },
     CoordinatorError, Participant,
 };
 
 pub use crate::{coordinator_state::TOKENS_PATH, s3::TOKENS_ZIP_FILE};
-use blake2::Digest;
 use rocket::{
     catch,
-    data::FromData,
+    data::{ByteUnit, Data, FromData, Limits},
     error,
-    http::{ContentType, Status},
+    http::{ContentType, Status, StatusClass},
     request::{FromRequest, Outcome, Request},
-    response::{Responder, Response},
+    response::{stream::ByteStream, Responder, Response},
     serde::{Deserialize, DeserializeOwned, Serialize},
-    tokio::{sync::RwLock, task},
+    tokio::{
+        fs,
+        io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+        sync::{broadcast, Mutex, RwLock},
+        task,
+    },
     State,
 };
 
 use anyhow::anyhow;
 
-use sha2::Sha256;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
 
 use lazy_static::lazy_static;
-use std::{borrow::Cow, convert::TryFrom, io::Cursor, net::IpAddr, ops::Deref, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::TryFrom,
+    io::{Cursor, SeekFrom},
+    net::IpAddr,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{self, Arc},
+    time::{Duration, SystemTime},
+};
 use thiserror::Error;
-use tracing::warn;
+use time::OffsetDateTime;
+use tracing::{error, info};
 
+/// Interval between two requests of the clients polling the coordinator.
 #[cfg(debug_assertions)]
 pub const UPDATE_TIME: Duration = Duration::from_secs(5);
 #[cfg(not(debug_assertions))]
 pub const UPDATE_TIME: Duration = Duration::from_secs(60);
 
+/// Interval at which the streams of `/contributor/queue_events` read the status of their contributor again without
+/// any event of the ceremony, to catch the changes no event reports, e.g. the dropout of a contributor ahead in the
+/// queue.
+pub const QUEUE_EVENTS_REFRESH: Duration = Duration::from_secs(120);
+/// Number of events of the ceremony buffered for each stream of `/contributor/queue_events`, above which a slow stream
+/// skips to the latest events.
+const QUEUE_EVENTS_CAPACITY: usize = 64;
+
 pub const UNKNOWN: &str = "Unknown";
+
+/// Largest attestation post fetched by the coordinator.
+pub const MAX_ATTESTATION_POST_SIZE: u64 = 4 * 1024 * 1024;
+/// Timeout of the fetch of an attestation post.
+const ATTESTATION_POST_TIMEOUT: Duration = Duration::from_secs(30);
 pub const TOKEN_REGEX: &str = r"^[A-HJ-NP-Za-km-z1-9]{115}$";
 
 // Headers
@@ -47,14 +91,51 @@ pub const PUBKEY_HEADER: &str = "ATS-Pubkey";
 pub const SIGNATURE_HEADER: &str = "ATS-Signature";
 pub const CONTENT_LENGTH_HEADER: &str = "Content-Length";
 pub const ACCESS_SECRET_HEADER: &str = "Access-Secret";
+/// Header of the delegation of the coordinator presented by an external verifier outside of the verification quorum.
+pub const DELEGATION_HEADER: &str = "ATS-Delegation";
+/// Header of the address of the client, set by the reverse proxy in front of the coordinator.
+pub const REAL_IP_HEADER: &str = "X-Real-IP";
+/// Header of the code of the status or the error of the response in the
+/// [message catalog](`crate::messages::MessageCatalog`).
+pub const MESSAGE_CODE_HEADER: &str = "ATS-Message-Code";
+pub const ACCEPT_LANGUAGE_HEADER: &str = "Accept-Language";
+pub const CONTENT_LANGUAGE_HEADER: &str = "Content-Language";
+pub const ETAG_HEADER: &str = "ETag";
+pub const IF_NONE_MATCH_HEADER: &str = "If-None-Match";
+pub const IF_RANGE_HEADER: &str = "If-Range";
+pub const RANGE_HEADER: &str = "Range";
+pub const CONTENT_RANGE_HEADER: &str = "Content-Range";
+pub const ACCEPT_RANGES_HEADER: &str = "Accept-Ranges";
+/// Header of the id of the request, set by the reverse proxy or generated by the coordinator.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+/// Domain separation of the hash signed by the requests, see [`SignatureHeaders::message`].
+pub const REQUEST_SIGNATURE_DOMAIN: &[u8] = b"namada-trusted-setup/request/v1";
 
 lazy_static! {
     pub(crate) static ref HEALTH_PATH: String = match std::env::var("HEALTH_PATH") {
         Ok(path) => path,
         Err(_) => "./health.json".to_string(),
     };
+    /// Whether the public status of the ceremony hides the public key of the current contributor.
+    pub(crate) static ref ANONYMIZE_PUBLIC_STATUS: bool = match std::env::var("NAMADA_MPC_ANONYMIZE_PUBLIC_STATUS") {
+        Ok(s) if s == "true" => true,
+        _ => false,
+    };
     pub(crate) static ref ACCESS_SECRET: String =
         std::env::var("ACCESS_SECRET").expect("Missing required env ACCESS_SECRET");
+    /// The time after which the requests signed with the legacy message of [`SignatureHeaders::legacy_message`] are
+    /// refused, accepted for the whole ceremony if unset.
+    pub(crate) static ref LEGACY_SIGNATURES_DEADLINE: Option<OffsetDateTime> =
+        std::env::var("NAMADA_MPC_LEGACY_SIGNATURES_DEADLINE").ok().map(|deadline| {
+            deadline
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+                .expect("Invalid NAMADA_MPC_LEGACY_SIGNATURES_DEADLINE, expected a unix timestamp")
+        });
+    /// The locks serializing the writes to each file spooling a contribution, see [`spool_lock`].
+    static ref SPOOL_LOCKS: sync::Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> = Default::default();
 }
 
 pub(crate) type Coordinator = Arc<RwLock<crate::Coordinator>>;
@@ -66,18 +147,40 @@ pub enum ResponseError {
     CeremonyIsOver,
     #[error("Coordinator failed: {0}")]
     CoordinatorError(CoordinatorError),
+    #[error("Attestation post is not valid: {0}")]
+    InvalidAttestationPost(String),
     #[error("Contribution info is not valid: {0}")]
     InvalidContributionInfo(String),
+    #[error("Destruction attestation is not valid: {0}")]
+    InvalidDestructionAttestation(String),
     #[error("The required access secret is either missing or invalid")]
     InvalidSecret,
     #[error("Header {0} is badly formatted")]
     InvalidHeader(&'static str),
+    #[error("Proof of work to join the queue is not valid: {0}")]
+    InvalidProofOfWork(String),
+    #[error("Capability probe to join the queue failed: {0}")]
+    InvalidCapabilityProbe(String),
+    #[error("Human verification to join the queue failed: {0}")]
+    InvalidHumanVerification(String),
+    #[error("Identity of the participant is not valid: {0}")]
+    InvalidIdentity(String),
+    #[error("{}", .0.message)]
+    InvalidLifecyclePhase(LifecycleConflict),
     #[error("Updated tokens for current cohort don't match the old ones")]
     InvalidNewTokens,
     #[error("Request's signature is invalid")]
     InvalidSignature,
     #[error("Authentification token for cohort {0} is invalid")]
     InvalidToken(usize),
+    #[error("Upload offset {0} doesn't match the {1} bytes received")]
+    InvalidUploadOffset(u64, u64),
+    #[error("Upload session {0} is not the current one")]
+    InvalidUploadSession(String),
+    #[error("Round range {0}..={1} is not valid")]
+    InvalidRoundRange(u64, u64),
+    #[error("Response bundle is not valid: {0}")]
+    InvalidResponseBundle(String),
     #[error("Io Error: {0}")]
     IoError(String),
     #[error("Checksum of body doesn't match the expected one: expc {0}, act: {1}")]
@@ -88,10 +191,14 @@ pub enum ResponseError {
     MissingSigningKey,
     #[error("Couldn't parse string to int: {0}")]
     ParseError(#[from] std::num::ParseIntError),
+    #[error("Request body exceeds the limit of {0} bytes")]
+    PayloadTooLarge(u64),
     #[error("Thread panicked: {0}")]
     RuntimeError(#[from] task::JoinError),
     #[error("Error with S3: {0}")]
     S3Error(#[from] S3Error),
+    #[error("Request body violates the expected schema: {0}")]
+    SchemaViolation(String),
     #[error("Error with Serde: {0}")]
     SerdeError(String),
     #[error("Error while terminating the ceremony: {0}")]
@@ -102,40 +209,304 @@ pub enum ResponseError {
     BlacklistedToken,
     #[error("The participant {0} is not allowed to access the endpoint {1} because of: {2}")]
     UnauthorizedParticipant(Participant, String, String),
+    #[error("Badge {0} is neither a .json nor a .svg file")]
+    UnknownBadgeFormat(String),
     #[error("Could not find contributor with public key {0}")]
     UnknownContributor(String),
     #[error("Could not find the provided Task {0} in coordinator state")]
     UnknownTask(Task),
+    #[error("Content encoding {0} is not supported")]
+    UnsupportedEncoding(String),
+    #[error("Export format {0} is not supported")]
+    UnsupportedExportFormat(String),
     #[error("Digest of request's body is not base64 encoded: {0}")]
     WrongDigestEncoding(#[from] base64::DecodeError),
 }
 
-impl<'r> Responder<'r, 'static> for ResponseError {
-    fn respond_to(self, _request: &'r Request<'_>) -> rocket::response::Result<'static> {
-        let response = format!("{}", self);
-        let mut builder = Response::build();
+impl ResponseError {
+    /// Returns the code of the error in the [message catalog](`crate::messages::MessageCatalog`) and the arguments of
+    /// its message.
+    pub fn message(&self) -> (&'static str, Vec<String>) {
+        match self {
+            ResponseError::CeremonyIsOver => ("error.ceremony_is_over", vec![]),
+            ResponseError::CoordinatorError(CoordinatorError::IdentityAlreadyUsed) => {
+                ("error.identity_already_used", vec![])
+            }
+            ResponseError::CoordinatorError(CoordinatorError::ParticipantAlreadyAdded) => {
+                ("error.participant_already_added", vec![])
+            }
+            ResponseError::CoordinatorError(CoordinatorError::ParticipantAlreadyContributed { round_height }) => {
+                ("error.participant_already_contributed", vec![round_height.to_string()])
+            }
+            ResponseError::CoordinatorError(CoordinatorError::ParticipantNotAdmittedInPhase(phase)) => {
+                ("error.participant_not_admitted_in_phase", vec![phase.clone()])
+            }
+            // The Display of the coordinator errors logs them, the Debug is the same without logging twice
+            ResponseError::CoordinatorError(e) => ("error.coordinator", vec![format!("{:?}", e)]),
+            ResponseError::InvalidAttestationPost(e) => ("error.invalid_attestation_post", vec![e.clone()]),
+            ResponseError::InvalidContributionInfo(e) => ("error.invalid_contribution_info", vec![e.clone()]),
+            ResponseError::InvalidDestructionAttestation(e) => {
+                ("error.invalid_destruction_attestation", vec![e.clone()])
+            }
+            ResponseError::InvalidSecret => ("error.invalid_secret", vec![]),
+            ResponseError::InvalidHeader(h) => ("error.invalid_header", vec![h.to_string()]),
+            ResponseError::InvalidProofOfWork(e) => ("error.invalid_proof_of_work", vec![e.clone()]),
+            ResponseError::InvalidCapabilityProbe(e) => ("error.invalid_capability_probe", vec![e.clone()]),
+            ResponseError::InvalidHumanVerification(e) => ("error.invalid_human_verification", vec![e.clone()]),
+            ResponseError::InvalidIdentity(e) => ("error.invalid_identity", vec![e.clone()]),
+            ResponseError::InvalidLifecyclePhase(conflict) => ("error.invalid_lifecycle_phase", vec![
+                conflict.endpoint.clone(),
+                conflict.phase.to_string(),
+            ]),
+            ResponseError::InvalidNewTokens => ("error.invalid_new_tokens", vec![]),
+            ResponseError::InvalidSignature => ("error.invalid_signature", vec![]),
+            ResponseError::InvalidToken(cohort) => ("error.invalid_token", vec![cohort.to_string()]),
+            ResponseError::InvalidUploadOffset(offset, received) => ("error.invalid_upload_offset", vec![
+                offset.to_string(),
+                received.to_string(),
+            ]),
+            ResponseError::InvalidUploadSession(session) => ("error.invalid_upload_session", vec![session.clone()]),
+            ResponseError::InvalidRoundRange(start, end) => {
+                ("error.invalid_round_range", vec![start.to_string(), end.to_string()])
+            }
+            ResponseError::InvalidResponseBundle(e) => ("error.invalid_response_bundle", vec![e.clone()]),
+            ResponseError::IoError(e) => ("error.io", vec![e.clone()]),
+            ResponseError::MismatchingChecksum(expected, actual) => {
+                ("error.mismatching_checksum", vec![expected.clone(), actual.clone()])
+            }
+            ResponseError::MissingRequiredHeader(h) => ("error.missing_required_header", vec![h.to_string()]),
+            ResponseError::MissingSigningKey => ("error.missing_signing_key", vec![]),
+            ResponseError::ParseError(e) => ("error.parse", vec![e.to_string()]),
+            ResponseError::PayloadTooLarge(limit) => ("error.payload_too_large", vec![limit.to_string()]),
+            ResponseError::RuntimeError(e) => ("error.runtime", vec![e.to_string()]),
+            ResponseError::S3Error(e) => ("error.s3", vec![e.to_string()]),
+            ResponseError::SchemaViolation(e) => ("error.schema_violation", vec![e.clone()]),
+            ResponseError::SerdeError(e) => ("error.serde", vec![e.clone()]),
+            ResponseError::ShutdownError(e) => ("error.shutdown", vec![e.clone()]),
+            ResponseError::TokenAlreadyInUse => ("error.token_already_in_use", vec![]),
+            ResponseError::BlacklistedToken => ("error.blacklisted_token", vec![]),
+            ResponseError::UnauthorizedParticipant(participant, endpoint, cause) => {
+                ("error.unauthorized_participant", vec![
+                    participant.to_string(),
+                    endpoint.clone(),
+                    cause.clone(),
+                ])
+            }
+            ResponseError::UnknownBadgeFormat(badge) => ("error.unknown_badge_format", vec![badge.clone()]),
+            ResponseError::UnknownContributor(pubkey) => ("error.unknown_contributor", vec![pubkey.clone()]),
+            ResponseError::UnknownTask(task) => ("error.unknown_task", vec![task.to_string()]),
+            ResponseError::UnsupportedEncoding(encoding) => ("error.unsupported_encoding", vec![encoding.clone()]),
+            ResponseError::UnsupportedExportFormat(format) => ("error.unsupported_export_format", vec![format.clone()]),
+            ResponseError::WrongDigestEncoding(e) => ("error.wrong_digest_encoding", vec![e.to_string()]),
+        }
+    }
 
-        let response_code = match self {
+    /// Returns the HTTP status of the error.
+    pub fn status(&self) -> Status {
+        match self {
             ResponseError::BlacklistedToken => Status::Unauthorized,
             ResponseError::CeremonyIsOver => Status::Unauthorized,
+            ResponseError::CoordinatorError(e) => coordinator_error_status(e),
+            ResponseError::InvalidAttestationPost(_) => Status::BadRequest,
+            ResponseError::InvalidContributionInfo(_) => Status::BadRequest,
+            ResponseError::InvalidDestructionAttestation(_) => Status::BadRequest,
             ResponseError::InvalidHeader(_) => Status::BadRequest,
+            ResponseError::InvalidProofOfWork(_) => Status::Unauthorized,
+            ResponseError::InvalidCapabilityProbe(_) => Status::Unauthorized,
+            ResponseError::InvalidHumanVerification(_) => Status::Unauthorized,
+            ResponseError::InvalidIdentity(_) => Status::Unauthorized,
+            ResponseError::InvalidLifecyclePhase(_) => Status::Conflict,
+            ResponseError::InvalidNewTokens => Status::BadRequest,
+            ResponseError::InvalidRoundRange(_, _) => Status::BadRequest,
+            ResponseError::InvalidResponseBundle(_) => Status::BadRequest,
             ResponseError::InvalidSecret => Status::Unauthorized,
             ResponseError::InvalidSignature => Status::BadRequest,
             ResponseError::InvalidToken(_) => Status::Unauthorized,
+            ResponseError::InvalidUploadOffset(_, _) => Status::Conflict,
+            ResponseError::InvalidUploadSession(_) => Status::Conflict,
+            ResponseError::IoError(_) => Status::InternalServerError,
             ResponseError::MismatchingChecksum(_, _) => Status::BadRequest,
-            ResponseError::MissingRequiredHeader(h) if h == CONTENT_LENGTH_HEADER => Status::LengthRequired,
+            ResponseError::MissingRequiredHeader(h) if *h == CONTENT_LENGTH_HEADER => Status::LengthRequired,
             ResponseError::MissingRequiredHeader(_) => Status::BadRequest,
             ResponseError::MissingSigningKey => Status::BadRequest,
+            ResponseError::ParseError(_) => Status::BadRequest,
+            ResponseError::PayloadTooLarge(_) => Status::PayloadTooLarge,
+            ResponseError::RuntimeError(_) => Status::InternalServerError,
+            ResponseError::S3Error(_) => Status::InternalServerError,
+            ResponseError::SchemaViolation(_) => Status::UnprocessableEntity,
             ResponseError::SerdeError(_) => Status::UnprocessableEntity,
+            ResponseError::ShutdownError(_) => Status::InternalServerError,
             ResponseError::TokenAlreadyInUse => Status::Unauthorized,
             ResponseError::UnauthorizedParticipant(_, _, _) => Status::Unauthorized,
+            ResponseError::UnknownBadgeFormat(_) => Status::NotFound,
+            ResponseError::UnknownContributor(_) => Status::NotFound,
+            ResponseError::UnknownTask(_) => Status::NotFound,
+            ResponseError::UnsupportedEncoding(_) => Status::UnsupportedMediaType,
+            ResponseError::UnsupportedExportFormat(_) => Status::BadRequest,
             ResponseError::WrongDigestEncoding(_) => Status::BadRequest,
-            _ => Status::InternalServerError,
+        }
+    }
+}
+
+/// Returns the HTTP status of an error of the coordinator: the errors caused by the request of the client get a 4xx
+/// status, only the failures of the coordinator itself are internal server errors.
+fn coordinator_error_status(error: &CoordinatorError) -> Status {
+    match error {
+        // The request is malformed or its content is invalid
+        CoordinatorError::ChallengeHashSizeInvalid
+        | CoordinatorError::ChunkIdInvalid
+        | CoordinatorError::ChunkIdMismatch
+        | CoordinatorError::ComputationProofInvalid(_)
+        | CoordinatorError::ContributionFileSizeMismatch
+        | CoordinatorError::ContributionHashMismatch
+        | CoordinatorError::ContributionIdMismatch
+        | CoordinatorError::ContributionLocatorIncorrect
+        | CoordinatorError::ContributionSignatureFileSizeMismatch
+        | CoordinatorError::ContributionSignatureSizeMismatch
+        | CoordinatorError::ContributorSignatureInvalid
+        | CoordinatorError::DestructionAttestationSignatureInvalid
+        | CoordinatorError::GenesisSeedInvalid
+        | CoordinatorError::HashAlgorithmMismatch
+        | CoordinatorError::JustificationInvalid
+        | CoordinatorError::NextChallengeHashSizeInvalid
+        | CoordinatorError::OperatorSignatureInvalid
+        | CoordinatorError::ResponseHashSizeInvalid
+        | CoordinatorError::VerifierDelegationInvalid
+        | CoordinatorError::VerifierSignatureInvalid => Status::BadRequest,
+        // The participant isn't allowed to do it
+        CoordinatorError::ChunkNotLockedOrByWrongParticipant
+        | CoordinatorError::ExpectedContributor
+        | CoordinatorError::ExpectedVerifier
+        | CoordinatorError::IdentityAlreadyUsed
+        | CoordinatorError::OperatorUnauthorized
+        | CoordinatorError::ParticipantAlreadyContributed { .. }
+        | CoordinatorError::ParticipantBanned
+        | CoordinatorError::ParticipantDidntLockChunkId
+        | CoordinatorError::ParticipantInCurrentRoundCannotJoinQueue
+        | CoordinatorError::ParticipantIpAlreadyAdded
+        | CoordinatorError::ParticipantNotAdmittedInPhase(_)
+        | CoordinatorError::ParticipantRoundHeightInvalid
+        | CoordinatorError::ParticipantUnauthorized
+        | CoordinatorError::ParticipantUnauthorizedForChunkId { .. }
+        | CoordinatorError::ParticipantWasDropped
+        | CoordinatorError::UnauthorizedChunkContributor
+        | CoordinatorError::UnauthorizedChunkVerifier
+        | CoordinatorError::VerifierDelegationExpired
+        | CoordinatorError::VerifierDelegationOutOfRounds => Status::Forbidden,
+        // The requested participant, round or contribution doesn't exist
+        CoordinatorError::ContributionMissing
+        | CoordinatorError::ControlKeyMissing
+        | CoordinatorError::GenesisSeedCeremonyDisabled
+        | CoordinatorError::ParticipantMissing
+        | CoordinatorError::ParticipantNotFound(_)
+        | CoordinatorError::PrelaunchSnapshotMissing
+        | CoordinatorError::RoundDoesNotExist
+        | CoordinatorError::VerificationNotPending
+        | CoordinatorError::VerificationNotQuarantined => Status::NotFound,
+        // The request conflicts with the current state of the ceremony
+        CoordinatorError::CeremonyAlreadyLaunched
+        | CoordinatorError::ChunkAlreadyComplete
+        | CoordinatorError::ChunkAlreadyVerified
+        | CoordinatorError::ChunkLockAlreadyAcquired
+        | CoordinatorError::ContributionAlreadyVerified
+        | CoordinatorError::ContributorAlreadyContributed
+        | CoordinatorError::ControlKeyLast
+        | CoordinatorError::GenesisSeedAlreadyCommitted
+        | CoordinatorError::GenesisSeedAlreadyRevealed
+        | CoordinatorError::GenesisSeedCommitmentsMissing
+        | CoordinatorError::ManualContributionAlreadyInjected
+        | CoordinatorError::ManualContributionNotApproved
+        | CoordinatorError::OperatorAlreadyApproved
+        | CoordinatorError::ParticipantAlreadyAdded
+        | CoordinatorError::ParticipantAlreadyBanned
+        | CoordinatorError::ParticipantAlreadyDropped
+        | CoordinatorError::ParticipantAlreadyFinished
+        | CoordinatorError::ParticipantAlreadyHasLockedChunk
+        | CoordinatorError::ParticipantAlreadyHasLockedChunks
+        | CoordinatorError::ParticipantAlreadyPrecommitted
+        | CoordinatorError::ParticipantAlreadyStarted
+        | CoordinatorError::QueueWaitTimeIncomplete
+        | CoordinatorError::RoundHeightMismatch
+        | CoordinatorError::VerifierAlreadyVoted => Status::Conflict,
+        CoordinatorError::ContributionFileSignatureMalformed(_) => Status::UnprocessableEntity,
+        CoordinatorError::VerificationBacklog { .. } => Status::ServiceUnavailable,
+        _ => Status::InternalServerError,
+    }
+}
+
+/// The body of the error responses.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ErrorBody {
+    /// The code of the error in the [message catalog](`crate::messages::MessageCatalog`), also returned in the
+    /// [`MESSAGE_CODE_HEADER`].
+    pub code: String,
+    /// The message of the error, localized in the language of the `Accept-Language` header if the catalog has it.
+    pub message: String,
+    /// The id of the request, also returned in the [`REQUEST_ID_HEADER`], to find the failure in the logs.
+    pub request_id: String,
+    /// The conflict with the lifecycle of the ceremony, if the endpoint isn't available in the current phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<LifecycleConflict>,
+}
+
+/// The id of a request, in its own type to be cached apart from the other strings of the request.
+struct RequestId(String);
+
+/// Returns the id of the request: the one set by the reverse proxy in the [`REQUEST_ID_HEADER`] if valid, or a new
+/// random id.
+pub fn request_id<'r>(request: &'r Request<'_>) -> &'r str {
+    let RequestId(id) = request.local_cache(|| match request.headers().get_one(REQUEST_ID_HEADER) {
+        Some(id) if !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') => {
+            RequestId(id.to_owned())
+        }
+        _ => {
+            let mut id = [0u8; 16];
+            rand::thread_rng().fill_bytes(&mut id);
+            RequestId(hex::encode(id))
+        }
+    });
+
+    id
+}
+
+impl<'r> Responder<'r, 'static> for ResponseError {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let request_id = request_id(request);
+        let status = self.status();
+        let mut builder = Response::build();
+
+        // The text of the error is localized in the language accepted by the client, the code is always returned
+        let (code, args) = self.message();
+        let localized = negotiate_language(request)
+            .and_then(|language| Some((language, MESSAGE_CATALOG.render(code, language, &args)?)));
+        let message = match localized {
+            Some((language, message)) => {
+                builder.raw_header(CONTENT_LANGUAGE_HEADER, language);
+                message
+            }
+            None => format!("{}", self),
+        };
+        if status.class() == StatusClass::ServerError {
+            error!("Request {} to {} failed: {}", request_id, request.uri(), message);
+        }
+
+        let body = ErrorBody {
+            code: code.to_owned(),
+            message,
+            request_id: request_id.to_owned(),
+            // The conflicts with the lifecycle of the ceremony are detailed, for the clients to read the current phase
+            lifecycle: match self {
+                ResponseError::InvalidLifecyclePhase(conflict) => Some(conflict),
+                _ => None,
+            },
         };
+        let response = serde_json::to_string(&body).map_err(|_| Status::InternalServerError)?;
 
         builder
-            .status(response_code)
-            .header(ContentType::Text)
+            .status(status)
+            .header(ContentType::JSON)
+            .raw_header(MESSAGE_CODE_HEADER, code)
+            .raw_header(REQUEST_ID_HEADER, request_id.to_owned())
             .sized_body(response.len(), Cursor::new(response))
             .ok()
     }
@@ -182,6 +553,32 @@ pub fn invalid_header(req: &Request) -> ResponseError {
     ResponseError::InvalidHeader(header)
 }
 
+#[catch(458)]
+pub fn schema_violation(req: &Request) -> ResponseError {
+    let message = req.local_cache(|| UNKNOWN.to_string());
+    ResponseError::SchemaViolation(message.to_string())
+}
+
+#[catch(459)]
+pub fn payload_too_large(req: &Request) -> ResponseError {
+    let limit = req.local_cache(|| ByteUnit::from(0));
+    ResponseError::PayloadTooLarge(limit.as_u64())
+}
+
+#[catch(460)]
+pub fn unsupported_encoding(req: &Request) -> ResponseError {
+    let encoding = req.local_cache(|| UNKNOWN.to_string());
+    ResponseError::UnsupportedEncoding(encoding.to_owned())
+}
+
+#[catch(461)]
+pub fn invalid_lifecycle_phase(req: &Request) -> ResponseError {
+    match req.local_cache(|| None::<LifecycleConflict>) {
+        Some(conflict) => ResponseError::InvalidLifecyclePhase(conflict.clone()),
+        None => ResponseError::IoError(UNKNOWN.to_string()),
+    }
+}
+
 #[catch(512)]
 pub fn io_error(req: &Request) -> ResponseError {
     let message = req.local_cache(|| UNKNOWN.to_string());
@@ -210,8 +607,13 @@ impl<'a> RequestContent<'a> {
         (self.len, format!("sha-256={}", self.digest))
     }
 
+    /// Returns the length of the body
+    pub fn content_length(&self) -> usize {
+        self.len
+    }
+
     /// Constructs from request's headers
-    fn try_from_header(len: &str, digest: &'a str) -> Result<Self> {
+    pub fn try_from_header(len: &str, digest: &'a str) -> Result<Self> {
         let digest = digest
             .split_once('=')
             .ok_or(ResponseError::InvalidHeader(BODY_DIGEST_HEADER))?
@@ -230,6 +632,56 @@ impl<'a> RequestContent<'a> {
     }
 }
 
+/// Guard for the length and the digest of the body of a request, required to stream a raw body
+/// which is not deserialized by a [`LazyJson`].
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestContent<'r> {
+    type Error = ResponseError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let headers = request.headers();
+        let digest = match headers.get_one(BODY_DIGEST_HEADER) {
+            Some(h) => h,
+            None => {
+                // Cache error data for the error catcher
+                request.local_cache(|| BODY_DIGEST_HEADER);
+
+                return Outcome::Failure((
+                    Status::new(454),
+                    ResponseError::MissingRequiredHeader(BODY_DIGEST_HEADER),
+                ));
+            }
+        };
+
+        let content_length = match headers.get_one(CONTENT_LENGTH_HEADER) {
+            Some(h) => h,
+            None => {
+                // Cache error data for the error catcher
+                request.local_cache(|| CONTENT_LENGTH_HEADER);
+
+                return Outcome::Failure((
+                    Status::new(454),
+                    ResponseError::MissingRequiredHeader(CONTENT_LENGTH_HEADER),
+                ));
+            }
+        };
+
+        match RequestContent::try_from_header(content_length, digest) {
+            Ok(content) => Outcome::Success(content),
+            Err(e) => {
+                // Cache error data for the error catcher
+                let header = match e {
+                    ResponseError::InvalidHeader(h) => h,
+                    _ => UNKNOWN,
+                };
+                request.local_cache(|| header);
+
+                Outcome::Failure((Status::new(457), e))
+            }
+        }
+    }
+}
+
 /// The headers involved in the signature of the request.
 #[derive(Default)]
 pub struct SignatureHeaders<'r> {
@@ -239,8 +691,28 @@ pub struct SignatureHeaders<'r> {
 }
 
 impl<'r> SignatureHeaders<'r> {
-    /// Produces the message on which to compute the signature
-    pub fn to_string(&self) -> Cow<'_, str> {
+    /// Produces the message on which to compute the signature: the hex encoded SHA-256 hash of the public key and, for
+    /// a request with a body, of its length and digest, each prefixed with its length so that the fields can't be
+    /// shifted into one another
+    pub fn message(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(REQUEST_SIGNATURE_DOMAIN);
+        let mut update = |field: &[u8]| {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        };
+        update(self.pubkey.as_bytes());
+        if let Some(content) = &self.content {
+            update(content.len.to_string().as_bytes());
+            update(content.digest.as_bytes());
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Produces the message signed by the clients prior to [`Self::message`]: the concatenation of the public key and,
+    /// for a request with a body, of its length and digest
+    pub fn legacy_message(&self) -> Cow<'_, str> {
         match &self.content {
             Some(content) => format!("{}{}{}", self.pubkey, content.len, content.digest).into(),
             None => self.pubkey.into(),
@@ -255,11 +727,18 @@ impl<'r> SignatureHeaders<'r> {
         }
     }
 
-    fn try_verify_signature(&self) -> Result<bool> {
-        match &self.signature {
-            Some(sig) => Ok(Production.verify(self.pubkey, &self.to_string(), &sig)),
-            None => Err(ResponseError::MissingSigningKey),
-        }
+    pub(crate) fn try_verify_signature(&self) -> Result<bool> {
+        let sig = self.signature.as_ref().ok_or(ResponseError::MissingSigningKey)?;
+
+        Ok(Production.verify(self.pubkey, &self.message(), sig))
+    }
+
+    /// Verifies the signature of the legacy message of [`Self::legacy_message`], only accepted until
+    /// [`LEGACY_SIGNATURES_DEADLINE`].
+    pub(crate) fn try_verify_legacy_signature(&self) -> Result<bool> {
+        let sig = self.signature.as_ref().ok_or(ResponseError::MissingSigningKey)?;
+
+        Ok(Production.verify(self.pubkey, &self.legacy_message(), sig))
     }
 }
 
@@ -293,18 +772,44 @@ impl<'r> TryFrom<&'r Request<'_>> for SignatureHeaders<'r> {
     }
 }
 
+/// Returns whether the requests signed with the legacy message of [`SignatureHeaders::legacy_message`] are accepted,
+/// so that the clients predating the hashed message keep working until the deadline set by the operator, compared to
+/// the time of the coordinator.
+pub(crate) async fn legacy_signatures_accepted(coordinator: &Coordinator) -> bool {
+    match *LEGACY_SIGNATURES_DEADLINE {
+        Some(deadline) => LOCK_MONITOR.read(coordinator).await.now_utc() < deadline,
+        None => true,
+    }
+}
+
+#[rocket::async_trait]
 trait VerifySignature<'r> {
     // Workaround to implement a single method on a foreign type instead of newtype pattern
-    fn verify_signature(&'r self) -> Result<&str>;
+    async fn verify_signature(&'r self) -> Result<Cow<'r, str>>;
 }
 
+#[rocket::async_trait]
 impl<'r> VerifySignature<'r> for Request<'_> {
-    /// Check signature of request and return the pubkey of the participant
-    fn verify_signature(&'r self) -> Result<&str> {
+    /// Check signature of request and return the pubkey of the participant. OpenSSH public keys are converted to the
+    /// format of the native ones
+    async fn verify_signature(&'r self) -> Result<Cow<'r, str>> {
         let headers = SignatureHeaders::try_from(self)?;
+        if headers.try_verify_signature()? {
+            return Ok(ssh::canonical_public_key(headers.pubkey));
+        }
+
+        // The time of the coordinator is only read for the valid legacy signatures, not on every invalid signature
+        if !headers.try_verify_legacy_signature()? {
+            return Err(ResponseError::InvalidSignature);
+        }
+        let coordinator = self
+            .guard::<&State<Coordinator>>()
+            .await
+            .succeeded()
+            .expect("Managed state should always be retrievable");
 
-        match headers.try_verify_signature()? {
-            true => Ok(headers.pubkey),
+        match legacy_signatures_accepted(coordinator).await {
+            true => Ok(ssh::canonical_public_key(headers.pubkey)),
             false => Err(ResponseError::InvalidSignature),
         }
     }
@@ -315,8 +820,8 @@ impl<'r> FromRequest<'r> for Participant {
     type Error = ResponseError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        match request.verify_signature() {
-            Ok(pubkey) => Outcome::Success(Participant::new_contributor(pubkey)),
+        match request.verify_signature().await {
+            Ok(pubkey) => Outcome::Success(Participant::new_contributor(&pubkey)),
             Err(e) => Outcome::Failure((Status::new(452), e)),
         }
     }
@@ -333,7 +838,7 @@ impl<'r> FromRequest<'r> for NewParticipant {
     type Error = ResponseError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let pubkey = match request.verify_signature() {
+        let pubkey = match request.verify_signature().await {
             Ok(h) => h,
             Err(e) => return Outcome::Failure((Status::new(452), e)),
         };
@@ -344,15 +849,18 @@ impl<'r> FromRequest<'r> for NewParticipant {
             .await
             .succeeded()
             .expect("Managed state should always be retrievable");
-        let participant = Participant::new_contributor(pubkey);
-        let ip_address = request.client_ip();
+        let participant = Participant::new_contributor(&pubkey);
+        let ip_address = client_ip(request);
+
+        // A contributor already in the queue may be retrying its request, checked against its token by the endpoint
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let checks = match read_lock.is_queue_contributor(&participant) {
+            true => Ok(()),
+            false => read_lock.state().add_to_queue_checks(&participant, ip_address.as_ref()),
+        };
+        drop(read_lock);
 
-        if let Err(e) = coordinator
-            .read()
-            .await
-            .state()
-            .add_to_queue_checks(&participant, ip_address.as_ref())
-        {
+        if let Err(e) = checks {
             // Cache error data for the error catcher
             request.local_cache(|| participant.clone());
             request.local_cache(|| (request.uri().to_string(), e.to_string()));
@@ -370,6 +878,16 @@ impl<'r> FromRequest<'r> for NewParticipant {
     }
 }
 
+/// Returns the canonical IP of the client of the request: the address forwarded by the reverse proxy in the
+/// [`REAL_IP_HEADER`], with or without a port, or else the address of the peer.
+fn client_ip(request: &Request<'_>) -> Option<IpAddr> {
+    request
+        .headers()
+        .get_one(REAL_IP_HEADER)
+        .and_then(parse_forwarded_ip)
+        .or_else(|| request.remote().map(|address| canonical_ip(address.ip())))
+}
+
 /// Implements the signature verification on the incoming current contributor request via [`FromRequest`].
 pub struct CurrentContributor(Participant);
 
@@ -386,7 +904,7 @@ impl<'r> FromRequest<'r> for CurrentContributor {
     type Error = ResponseError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let pubkey = match request.verify_signature() {
+        let pubkey = match request.verify_signature().await {
             Ok(h) => h,
             Err(e) => return Outcome::Failure((Status::new(452), e)),
         };
@@ -397,32 +915,37 @@ impl<'r> FromRequest<'r> for CurrentContributor {
             .await
             .succeeded()
             .expect("Managed state should always be retrievable");
-        let participant = Participant::new_contributor(pubkey);
-
-        let read_lock = coordinator.read().await;
-        if !read_lock.is_current_contributor(&participant) {
-            // Cache error data for the error catcher
-            let error_msg = {
-                if read_lock.is_banned_participant(&participant) {
-                    String::from("Participant has been banned from the ceremony")
-                } else if read_lock.is_dropped_participant(&participant) {
-                    String::from("Participant has been dropped from the ceremony")
-                } else {
-                    String::from("Participant is not the current contributor")
-                }
-            };
-            drop(read_lock);
-
-            request.local_cache(|| participant.clone());
-            request.local_cache(|| (request.uri().to_string(), error_msg.clone()));
+        let participant = Participant::new_contributor(&pubkey);
+
+        // The round of the current contributor is read from storage, off the async runtime
+        let read_lock = LOCK_MONITOR.read_owned((*coordinator).clone()).await;
+        let contributor = participant.clone();
+        let error_msg = task::spawn_blocking(move || {
+            if read_lock.is_current_contributor(&contributor) {
+                None
+            } else if read_lock.is_banned_participant(&contributor) {
+                Some(String::from("Participant has been banned from the ceremony"))
+            } else if read_lock.is_dropped_participant(&contributor) {
+                Some(String::from("Participant has been dropped from the ceremony"))
+            } else {
+                Some(String::from("Participant is not the current contributor"))
+            }
+        })
+        .await;
+        let error_msg = match error_msg {
+            Ok(None) => return Outcome::Success(Self(participant)),
+            Ok(Some(error_msg)) => error_msg,
+            Err(e) => return Outcome::Failure((Status::InternalServerError, ResponseError::RuntimeError(e))),
+        };
 
-            return Outcome::Failure((
-                Status::new(453),
-                ResponseError::UnauthorizedParticipant(participant, request.uri().to_string(), error_msg),
-            ));
-        }
+        // Cache error data for the error catcher
+        request.local_cache(|| participant.clone());
+        request.local_cache(|| (request.uri().to_string(), error_msg.clone()));
 
-        Outcome::Success(Self(participant))
+        Outcome::Failure((
+            Status::new(453),
+            ResponseError::UnauthorizedParticipant(participant, request.uri().to_string(), error_msg),
+        ))
     }
 }
 
@@ -442,7 +965,8 @@ impl<'r> FromRequest<'r> for Secret {
     }
 }
 
-/// Implements the signature verification on the incoming server request via [`FromRequest`].
+/// Implements the signature verification on the incoming server request via [`FromRequest`]. The request must be signed
+/// with one of the control keys of the coordinator.
 pub struct ServerAuth;
 
 #[rocket::async_trait]
@@ -450,22 +974,22 @@ impl<'r> FromRequest<'r> for ServerAuth {
     type Error = ResponseError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        let pubkey = match request.verify_signature() {
+        let pubkey = match request.verify_signature().await {
             Ok(h) => h,
             Err(e) => return Outcome::Failure((Status::new(452), e)),
         };
 
-        // Check that the signature comes from the coordinator by matching the default verifier key
+        // Check that the signature comes from one of the control keys of the coordinator
         let coordinator = request
             .guard::<&State<Coordinator>>()
             .await
             .succeeded()
             .expect("Managed state should always be retrievable");
-        let verifier = Participant::new_verifier(pubkey);
+        let verifier = Participant::new_verifier(&pubkey);
 
-        if verifier != coordinator.read().await.environment().coordinator_verifiers()[0] {
+        if !LOCK_MONITOR.read(coordinator).await.is_control_key(&pubkey) {
             // Cache error data for the error catcher
-            let error_msg = String::from("Not the coordinator's verifier");
+            let error_msg = String::from("Not a control key of the coordinator");
             request.local_cache(|| verifier.clone());
             request.local_cache(|| (request.uri().to_string(), error_msg.clone()));
 
@@ -474,11 +998,155 @@ impl<'r> FromRequest<'r> for ServerAuth {
                 ResponseError::UnauthorizedParticipant(verifier, request.uri().to_string(), error_msg),
             ));
         }
+        info!("{} {} authorized by the control key {}", request.method(), request.uri(), pubkey);
 
         Outcome::Success(Self)
     }
 }
 
+/// Implements the signature verification on the incoming request of an external verifier of the verification quorum,
+/// or of a verifier presenting a delegation of the coordinator in the [`DELEGATION_HEADER`], via [`FromRequest`].
+pub struct QuorumVerifier(Participant);
+
+impl Deref for QuorumVerifier {
+    type Target = Participant;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for QuorumVerifier {
+    type Error = ResponseError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let pubkey = match request.verify_signature().await {
+            Ok(h) => h,
+            Err(e) => return Outcome::Failure((Status::new(452), e)),
+        };
+
+        // Check that the signature comes from one of the verifiers of the quorum
+        let coordinator = request
+            .guard::<&State<Coordinator>>()
+            .await
+            .succeeded()
+            .expect("Managed state should always be retrievable");
+        let verifier = Participant::new_verifier(&pubkey);
+
+        let read_lock = LOCK_MONITOR.read(coordinator).await;
+        let authorized = match read_lock.environment().quorum_verifiers().contains(&verifier) {
+            true => Ok(()),
+            // Other verifiers must present a delegation of the coordinator covering the current round
+            false => match request
+                .headers()
+                .get_one(DELEGATION_HEADER)
+                .and_then(SignedVerifierDelegation::from_header)
+            {
+                Some(delegation) => read_lock
+                    .check_verifier_delegation(&verifier, &delegation)
+                    .map_err(|e| format!("Invalid delegation: {}", e)),
+                None => Err(String::from("Not a verifier of the verification quorum")),
+            },
+        };
+        drop(read_lock);
+
+        if let Err(error_msg) = authorized {
+            // Cache error data for the error catcher
+            request.local_cache(|| verifier.clone());
+            request.local_cache(|| (request.uri().to_string(), error_msg.clone()));
+
+            return Outcome::Failure((
+                Status::new(453),
+                ResponseError::UnauthorizedParticipant(verifier, request.uri().to_string(), error_msg),
+            ));
+        }
+
+        Outcome::Success(Self(verifier))
+    }
+}
+
+/// Implements the signature verification on the incoming request of an operator allowed to approve the manual
+/// injection of a contribution or to derive the seed of the genesis challenge via [`FromRequest`]. Derefs to the public
+/// key of the operator.
+pub struct Operator(String);
+
+impl Deref for Operator {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Operator {
+    type Error = ResponseError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let pubkey = match request.verify_signature().await {
+            Ok(h) => h,
+            Err(e) => return Outcome::Failure((Status::new(452), e)),
+        };
+
+        // Check that the signature comes from one of the operators
+        let coordinator = request
+            .guard::<&State<Coordinator>>()
+            .await
+            .succeeded()
+            .expect("Managed state should always be retrievable");
+
+        if !LOCK_MONITOR
+            .read(coordinator)
+            .await
+            .environment()
+            .operators()
+            .iter()
+            .any(|operator| operator == pubkey.as_ref())
+        {
+            // Cache error data for the error catcher
+            let error_msg = String::from("Not an operator of the ceremony");
+            let participant = Participant::new_verifier(&pubkey);
+            request.local_cache(|| participant.clone());
+            request.local_cache(|| (request.uri().to_string(), error_msg.clone()));
+
+            return Outcome::Failure((
+                Status::new(453),
+                ResponseError::UnauthorizedParticipant(participant, request.uri().to_string(), error_msg),
+            ));
+        }
+
+        Outcome::Success(Self(pubkey.into_owned()))
+    }
+}
+
+/// Serializes the body of a request to json with the fields of the objects sorted by name, so that the signed
+/// digest of the body doesn't depend on the field order of the serializer.
+pub fn to_canonical_json<T: Serialize>(body: &T) -> serde_json::Result<Vec<u8>> {
+    // Objects of [`serde_json::Value`] are backed by a sorted map
+    serde_json::to_vec(&serde_json::to_value(body)?)
+}
+
+/// Checks whether the deserialization error is caused by a field which is not part of the expected payload.
+fn is_schema_violation(error: &serde_json::Error) -> bool {
+    error.is_data() && error.to_string().starts_with("unknown field")
+}
+
+/// Deserializes the decompressed body of a request, as done by [`LazyJson`] once the digest of the body is checked.
+/// Fails with [`ResponseError::SchemaViolation`] on a field which is not part of the expected payload.
+pub fn parse_json_body<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    // Reject the bodies which aren't UTF-8 with the position of the invalid bytes, rather than with the error of the
+    // json parser on the string containing them
+    if let Err(e) = std::str::from_utf8(body) {
+        return Err(ResponseError::SerdeError(format!("Body is not valid UTF-8: {}", e)));
+    }
+
+    serde_json::from_slice(body).map_err(|e| match is_schema_violation(&e) {
+        true => ResponseError::SchemaViolation(e.to_string()),
+        false => ResponseError::SerdeError(e.to_string()),
+    })
+}
+
 /// Type to handle lazy deserialization of json encoded inputs.
 pub struct LazyJson<T>(pub T);
 
@@ -543,6 +1211,28 @@ impl<'r, T: DeserializeOwned> FromData<'r> for LazyJson<T> {
             }
         };
 
+        let encoding = match headers.get_one(CONTENT_ENCODING_HEADER) {
+            Some(h) => match h.parse::<ContentEncoding>() {
+                Ok(encoding) => encoding,
+                Err(e) => {
+                    // Cache error data for the error catcher
+                    req.local_cache(|| h.to_string());
+
+                    return rocket::data::Outcome::Failure((Status::new(460), e));
+                }
+            },
+            None => ContentEncoding::Identity,
+        };
+
+        // The limit applies to the decompressed body, which can't be smaller than the compressed one
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        if expected_content.len as u64 > limit.as_u64() {
+            // Cache error data for the error catcher
+            req.local_cache(|| limit);
+
+            return rocket::data::Outcome::Failure((Status::new(459), ResponseError::PayloadTooLarge(limit.as_u64())));
+        }
+
         let body = match data.open(expected_content.len.into()).into_bytes().await {
             Ok(bytes) => bytes.into_inner(),
             Err(e) => {
@@ -566,59 +1256,419 @@ impl<'r, T: DeserializeOwned> FromData<'r> for LazyJson<T> {
             ));
         }
 
+        // Decompress the body, whose digest is computed on the encoded content
+        let body = match encoding {
+            ContentEncoding::Identity => body,
+            _ => {
+                let decompressed = task::spawn_blocking(move || encoding.decompress(&body, limit.as_u64()))
+                    .await
+                    .unwrap_or_else(|e| Err(ResponseError::IoError(e.to_string())));
+
+                match decompressed {
+                    Ok(body) => body,
+                    Err(e @ ResponseError::PayloadTooLarge(_)) => {
+                        // Cache error data for the error catcher
+                        req.local_cache(|| limit);
+
+                        return rocket::data::Outcome::Failure((Status::new(459), e));
+                    }
+                    Err(e) => {
+                        let message = match e {
+                            ResponseError::IoError(message) => message,
+                            e => e.to_string(),
+                        };
+                        // Cache error data for the error catcher
+                        req.local_cache(|| message.clone());
+
+                        return rocket::data::Outcome::Failure((Status::new(512), ResponseError::IoError(message)));
+                    }
+                }
+            }
+        };
+
         // Deserialize data and pass it to the request handler
-        match serde_json::from_slice::<T>(&body) {
+        match parse_json_body::<T>(&body) {
             Ok(obj) => rocket::data::Outcome::Success(LazyJson(obj)),
             Err(e) => {
+                let (status, message) = match &e {
+                    ResponseError::SchemaViolation(message) => (458, message.clone()),
+                    ResponseError::SerdeError(message) => (455, message.clone()),
+                    e => (455, e.to_string()),
+                };
                 // Cache error data for the error catcher
-                req.local_cache(|| (e.to_string()));
-                rocket::data::Outcome::Failure((Status::new(455), ResponseError::SerdeError(e.to_string())))
+                req.local_cache(|| message);
+                rocket::data::Outcome::Failure((Status::new(status), e))
             }
         }
     }
 }
 
+/// Public summary of the state of the ceremony.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CeremonyStatus {
+    /// The height of the current round.
+    pub round_height: u64,
+    /// The number of contributors waiting in the queue.
+    pub queue_size: usize,
+    /// Whether a contributor is computing the contribution of the current round.
+    pub contribution_in_progress: bool,
+    /// The current interval, in seconds, between two updates of the coordinator.
+    pub update_interval: u64,
+    /// Whether the ceremony is a rehearsal, whose parameters are not for production.
+    #[serde(default)]
+    pub rehearsal: bool,
+    /// The name of the current phase of the schedule of the ceremony, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+}
+
+/// The public status of the ceremony, for the dashboards polling the coordinator without signing their requests.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PublicCeremonyStatus {
+    /// The height of the current round.
+    pub round_height: u64,
+    /// The public key of the contributor of the current round, or its [anonymized id](`anonymized_id`) with
+    /// `NAMADA_MPC_ANONYMIZE_PUBLIC_STATUS`, if any.
+    pub current_contributor: Option<String>,
+    /// The number of contributors waiting in the queue.
+    pub queue_size: usize,
+    /// The number of contributions completed since the start of the ceremony.
+    pub completed_contributions: usize,
+    /// The time, in seconds, since the coordinator booted up.
+    pub uptime: u64,
+}
+
+/// Returns the id of the participant published in place of its public key, the first 8 bytes of the hex encoded SHA-256
+/// hash of the public key.
+pub fn anonymized_id(participant: &Participant) -> String {
+    let hash = Sha256::digest(participant.address().as_bytes());
+    hex::encode(&hash[..8])
+}
+
 /// The status of the contributor related to the current round.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum ContributorStatus {
     Queue(u64, u64),
     Round,
+    /// The contributor is in the current round but no new chunk is locked until the backlog of contributions waiting to
+    /// be verified, of the given size, catches up.
+    VerificationBacklog(u64),
+    /// The challenge has been reissued and the contribution must be restarted.
+    Restart,
     Finished,
     Banned,
     Other,
 }
 
-/// Request to post a [Chunk](`crate::objects::Chunk`).
-#[derive(Clone, Deserialize, Serialize)]
-pub struct PostChunkRequest {
-    pub round_height: u64,
-    pub contribution_locator: ContributionLocator,
-    pub contribution_signature_locator: ContributionSignatureLocator,
-}
-
-impl PostChunkRequest {
-    pub fn new(
-        round_height: u64,
-        contribution_locator: ContributionLocator,
-        contribution_signature_locator: ContributionSignatureLocator,
-    ) -> Self {
-        Self {
-            round_height,
-            contribution_locator,
-            contribution_signature_locator,
+impl ContributorStatus {
+    /// Returns the code of the status in the [message catalog](`crate::messages::MessageCatalog`) and the arguments of
+    /// its message.
+    pub fn message(&self) -> (&'static str, Vec<String>) {
+        match self {
+            ContributorStatus::Queue(position, size) => ("status.queue", vec![position.to_string(), size.to_string()]),
+            ContributorStatus::Round => ("status.round", vec![]),
+            ContributorStatus::VerificationBacklog(backlog) => {
+                ("status.verification_backlog", vec![backlog.to_string()])
+            }
+            ContributorStatus::Restart => ("status.restart", vec![]),
+            ContributorStatus::Finished => ("status.finished", vec![]),
+            ContributorStatus::Banned => ("status.banned", vec![]),
+            ContributorStatus::Other => ("status.other", vec![]),
         }
     }
 }
 
-/// Checks the validity of the token for the ceremony.
-/// Returns the current cohort index
-pub(crate) async fn token_check(coordinator: Coordinator, token: &str) -> Result<u64> {
-    // Check that token is not in use nor blacklisted (only if env is set)
-    let read_lock = coordinator.read().await;
+/// Keeps track of the [`VerificationStatus`] of the latest contribution of each contributor. The tracker lives outside of the
+/// [Coordinator](`crate::Coordinator`) lock so that it can be queried while a verification is running.
+#[derive(Clone, Default)]
+pub struct VerificationTracker(Arc<sync::RwLock<HashMap<Participant, VerificationStatus>>>);
+
+impl VerificationTracker {
+    /// Updates the status of the latest contribution of the given contributor.
+    pub fn update(&self, contributor: &Participant, status: VerificationStatus) {
+        self.0
+            .write()
+            .expect("Unable to lock to write verification status")
+            .insert(contributor.clone(), status);
+    }
+
+    /// Returns the status of the latest contribution of the given contributor, if any.
+    pub fn get(&self, contributor: &Participant) -> Option<VerificationStatus> {
+        self.0
+            .read()
+            .expect("Unable to obtain lock to read verification status")
+            .get(contributor)
+            .cloned()
+    }
+
+    /// Returns the callback to be passed to [`set_verification_callback`](`crate::Coordinator::set_verification_callback`).
+    pub fn callback(&self) -> Arc<dyn Fn(&Participant, VerificationStatus) -> () + Send + Sync> {
+        let tracker = self.clone();
+        Arc::new(move |contributor, status| tracker.update(contributor, status))
+    }
+}
+
+/// An event of the stream of `/contributor/queue_events`, sent as a Server-Sent Event named after its type.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueueEvent {
+    /// The status of the contributor changed, e.g. its position in the queue. Always sent first.
+    Status { status: ContributorStatus },
+    /// The contributor entered the current round and can lock its chunk.
+    YourTurn,
+    /// The round completed and the ceremony advanced to the given round.
+    RoundCompleted { round_height: u64 },
+}
+
+impl QueueEvent {
+    /// Returns the name of the event, as serialized in the `type` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            QueueEvent::Status { .. } => "status",
+            QueueEvent::YourTurn => "your_turn",
+            QueueEvent::RoundCompleted { .. } => "round_completed",
+        }
+    }
+}
+
+/// Broadcasts the events of the ceremony to the streams of `/contributor/queue_events`, which read the status of their
+/// contributor on each of them instead of having the contributor poll `/contributor/queue_status`. The broadcast lives
+/// outside of the [Coordinator](`crate::Coordinator`) lock, like the [`VerificationTracker`].
+#[derive(Clone)]
+pub struct QueueEvents(broadcast::Sender<CeremonyEvent>);
+
+impl Default for QueueEvents {
+    fn default() -> Self {
+        Self(broadcast::channel(QUEUE_EVENTS_CAPACITY).0)
+    }
+}
+
+impl QueueEvents {
+    /// Returns a receiver of the events of the ceremony emitted from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<CeremonyEvent> {
+        self.0.subscribe()
+    }
+
+    /// Returns the callback to be passed to [`set_event_callback`](`crate::Coordinator::set_event_callback`), which
+    /// also forwards the events to the given callback, e.g. of the
+    /// [`EventPublishers`](`crate::events::EventPublishers`).
+    pub fn callback(
+        &self,
+        next: Option<Arc<dyn Fn(OffsetDateTime, CeremonyEvent) -> () + Send + Sync>>,
+    ) -> Arc<dyn Fn(OffsetDateTime, CeremonyEvent) -> () + Send + Sync> {
+        let sender = self.0.clone();
+        Arc::new(move |timestamp, event| {
+            // Fails only if no stream is open
+            let _ = sender.send(event.clone());
+            if let Some(next) = &next {
+                next(timestamp, event);
+            }
+        })
+    }
+}
+
+/// Request to join the queue of contributors.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JoinQueueRequest {
+    pub token: String,
+    /// Solution to the puzzle issued by the [`ProofOfWork`](`crate::pow::ProofOfWork`), required only if enabled.
+    #[serde(default)]
+    pub pow_solution: Option<PowSolution>,
+    /// Token of the challenge solved in the browser, checked by the
+    /// [`HumanVerifier`](`crate::human_verification::HumanVerifier`), required only if enabled.
+    #[serde(default)]
+    pub human_verification_token: Option<String>,
+    /// Identity claimed on top of the public key, validated by the
+    /// [`IdentityVerifier`](`crate::identity::IdentityVerifier`), required only if enabled and required.
+    #[serde(default)]
+    pub identity: Option<IdentityClaim>,
+    /// Proof of the probe issued by the [`CapabilityProber`](`crate::capability_probe::CapabilityProber`), required
+    /// only if enabled.
+    #[serde(default)]
+    pub capability_proof: Option<CapabilityProof>,
+}
+
+impl JoinQueueRequest {
+    pub fn new(token: String, pow_solution: Option<PowSolution>) -> Self {
+        Self {
+            token,
+            pow_solution,
+            human_verification_token: None,
+            identity: None,
+            capability_proof: None,
+        }
+    }
+
+    pub fn with_human_verification_token(mut self, human_verification_token: String) -> Self {
+        self.human_verification_token = Some(human_verification_token);
+        self
+    }
+
+    pub fn with_identity(mut self, identity: IdentityClaim) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    pub fn with_capability_proof(mut self, capability_proof: CapabilityProof) -> Self {
+        self.capability_proof = Some(capability_proof);
+        self
+    }
+}
+
+/// Response to a request to join the queue of contributors, the same for the retries of the request.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct JoinQueueResponse {
+    /// The cohort of the token of the contributor.
+    pub cohort: u64,
+    /// The position of the contributor in the queue, as in [`ContributorStatus::Queue`].
+    pub queue_position: u64,
+    /// The number of contributors in the queue.
+    pub queue_size: u64,
+    /// The warning of a failed capability probe, under the policy letting the contributor join anyway.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Request to register the public post attesting a contribution, e.g. a tweet.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationPostRequest {
+    pub round_height: u64,
+    pub url: String,
+    /// Hex encoded sha256 hash of the content of the post, as served at its url.
+    pub content_hash: String,
+}
+
+impl AttestationPostRequest {
+    pub fn new(round_height: u64, url: String, content_hash: String) -> Self {
+        Self {
+            round_height,
+            url,
+            content_hash,
+        }
+    }
+}
+
+/// Request to post a [Chunk](`crate::objects::Chunk`).
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostChunkRequest {
+    pub round_height: u64,
+    pub contribution_locator: ContributionLocator,
+    pub contribution_signature_locator: ContributionSignatureLocator,
+    /// Signature of the contribution streamed to the coordinator, if it wasn't uploaded to S3.
+    #[serde(default)]
+    pub contribution_file_signature: Option<ContributionFileSignature>,
+}
+
+impl PostChunkRequest {
+    pub fn new(
+        round_height: u64,
+        contribution_locator: ContributionLocator,
+        contribution_signature_locator: ContributionSignatureLocator,
+    ) -> Self {
+        Self {
+            round_height,
+            contribution_locator,
+            contribution_signature_locator,
+            contribution_file_signature: None,
+        }
+    }
+
+    /// Returns the request to post a contribution streamed to the coordinator by
+    /// [`upload_contribution`](`crate::rest::upload_contribution`), together with its signature.
+    pub fn spooled(
+        round_height: u64,
+        contribution_locator: ContributionLocator,
+        contribution_signature_locator: ContributionSignatureLocator,
+        contribution_file_signature: ContributionFileSignature,
+    ) -> Self {
+        Self {
+            round_height,
+            contribution_locator,
+            contribution_signature_locator,
+            contribution_file_signature: Some(contribution_file_signature),
+        }
+    }
+}
+
+/// Proof of computation time of the contribution of the current contributor, sent with its upload.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComputationProofRequest {
+    pub round_height: u64,
+    pub proof: ComputationProof,
+}
+
+/// Vote of an external verifier on the verification of a contribution of the current round.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerificationVoteRequest {
+    pub round_height: u64,
+    pub chunk_id: u64,
+    pub contribution_id: u64,
+    /// Hex encoded hash of the next challenge produced by the verification.
+    pub next_challenge_hash: String,
+    /// Signature of [`VerificationVote::message`](`crate::objects::VerificationVote::message`).
+    pub signature: String,
+}
+
+/// Attestation of a contributor that the toxic waste of its contribution was destroyed.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DestructionAttestationRequest {
+    pub attestation: DestructionAttestation,
+    /// Signature of [`DestructionAttestation::message`].
+    pub signature: String,
+}
+
+/// Approval of an operator on the manual injection of a contribution.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManualContributionApproval {
+    pub contribution: ManualContribution,
+    /// Signature of [`ManualContribution::message`].
+    pub signature: String,
+}
 
+/// Commitment of an operator to its share of the seed of the genesis challenge.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedCommitmentRequest {
+    /// Lowercase hex encoded Blake2b-512 hash of the share.
+    pub commitment: String,
+    /// Signature of [`SeedCommitment::message`](crate::objects::SeedCommitment::message).
+    pub signature: String,
+}
+
+/// Reveal of the share of an operator of the seed of the genesis challenge.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeedRevealRequest {
+    /// Hex encoded share.
+    pub share: String,
+    /// Signature of [`SeedReveal::message`](crate::objects::SeedReveal::message).
+    pub signature: String,
+}
+
+/// The commit-reveal of the seed of the genesis challenge by the operators, with its state.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GenesisSeedSummary {
+    pub status: GenesisSeedStatus,
+    pub genesis_seed: GenesisSeed,
+}
+
+/// Checks the validity of the token for the ceremony.
+/// Returns the current cohort index
+pub(crate) async fn token_check(coordinator: &Coordinator, participant: &Participant, token: &str) -> Result<u64> {
     // Check that token is not in use nor blacklisted (only if env is set)
-    if *TOKEN_BLACKLIST {
-        if read_lock.state().is_token_in_use(token) {
+    let read_lock = LOCK_MONITOR.read(coordinator).await;
+
+    // Check that token is not in use nor blacklisted (only if env is set). The token of a contributor retrying its
+    // request to join the queue is in use by the contributor itself
+    if read_lock.state().token_blacklist() {
+        if read_lock.state().is_token_in_use(token) && !read_lock.state().is_queue_retry(participant, token) {
             return Err(ResponseError::TokenAlreadyInUse);
         }
 
@@ -628,7 +1678,7 @@ pub(crate) async fn token_check(coordinator: Coordinator, token: &str) -> Result
     }
 
     // Check that the token is correct for the current cohort number
-    let cohort = read_lock.state().get_current_cohort_index();
+    let cohort = read_lock.current_cohort_index();
     let tokens = match read_lock.state().tokens(cohort) {
         Some(t) => t,
         None => return Err(ResponseError::CeremonyIsOver),
@@ -641,6 +1691,39 @@ pub(crate) async fn token_check(coordinator: Coordinator, token: &str) -> Result
     Ok((cohort + 1) as u64)
 }
 
+/// Fetches the attestation post at the given url and returns the hex encoded sha256 hash of its content.
+pub async fn fetch_attestation_post(url: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(ATTESTATION_POST_TIMEOUT)
+        .build()
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| ResponseError::InvalidAttestationPost(e.to_string()))?;
+
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| ResponseError::InvalidAttestationPost(e.to_string()))?
+    {
+        size += chunk.len() as u64;
+        if size > MAX_ATTESTATION_POST_SIZE {
+            return Err(ResponseError::InvalidAttestationPost(format!(
+                "content exceeds the limit of {} bytes",
+                MAX_ATTESTATION_POST_SIZE
+            )));
+        }
+        hasher.update(&chunk);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
 /// Performs the verification of the pending contributions
 ///
 /// # Cancel safety
@@ -650,46 +1733,19 @@ pub(crate) async fn token_check(coordinator: Coordinator, token: &str) -> Result
 /// Because of the use of [`tokio::sync::rwlock::RwLock::write_owned`], which is not cancel safe, and a spawned blocking
 /// task, which cannot be cancelled, this function is not cancel safe.
 pub async fn perform_verify_chunks(coordinator: Coordinator, s3_ctx: &S3Ctx) -> Result<()> {
-    // Get all the pending verifications, loop on each one of them and perform verification
-    // Technically, since we don't chunk contributions and we only have one contribution per round, we will always get
-    // one pending verification at max.
-    let mut write_lock = coordinator.write_owned().await;
-
-    // NOTE: we are going to rely on the single default verifier built in the coordinator itself,
-    //  no external verifiers
-    let contributions_info = task::spawn_blocking(move || -> Result<Vec<u8>> {
-        for (task, _) in write_lock.get_pending_verifications().to_owned() {
-            if let Err(e) = write_lock.default_verify(&task) {
-                warn!("Error while verifying a contribution: {}. Restarting the round...", e);
-                // FIXME: the verify_masp function may panic but the program doesn't shut down because we are executing it on a separate thread. It would be better though to make that function return a Result instead of panicking. Revert of round should be moved inside default_verify
-
-                // Get the participant who produced the contribution
-                let finished_contributor = write_lock
-                    .state()
-                    .current_round_finished_contributors()
-                    .unwrap()
-                    .first()
-                    .unwrap()
-                    .clone();
-
-                // Reset the round to prevent a coordinator stall (the corrupted contribution is not automatically dropped)
-                write_lock
-                    .reset_round()
-                    .map_err(|e| ResponseError::CoordinatorError(e))?;
-
-                // Ban the participant who produced the invalid contribution. Must be banned after the reset beacuse one can't ban a finished contributor
-                write_lock
-                    .ban_participant(&finished_contributor)
-                    .map_err(|e| ResponseError::CoordinatorError(e))?;
-            }
-        }
+    let mut write_lock = LOCK_MONITOR.write_owned(coordinator).await;
 
-        write_lock
-            .storage()
-            .get_contributions_summary()
-            .map_err(|e| ResponseError::CoordinatorError(e))
+    // NOTE: we are going to rely on the single default verifier built in the coordinator itself. In quorum mode, the
+    //  external verifiers vote through the dedicated endpoint and the contribution stays pending until they agree. The
+    //  contributions of completed rounds queued for re-verification are then verified again, one per run
+    let contributions_info = task::spawn_blocking(move || -> std::result::Result<_, CoordinatorError> {
+        let contributions_info = write_lock.verify_pending_contributions()?;
+        write_lock.reverify_next()?;
+
+        Ok(contributions_info)
     })
-    .await??;
+    .await?
+    .map_err(|e| ResponseError::CoordinatorError(e))?;
 
     // Upload json file to S3
     s3_ctx
@@ -707,9 +1763,711 @@ pub async fn perform_verify_chunks(coordinator: Coordinator, s3_ctx: &S3Ctx) ->
 /// Because of the use of [`tokio::sync::rwlock::RwLock::write_owned`], which is not cancel safe, and a spawned blocking
 /// task, which cannot be cancelled, this function is not cancel safe.
 pub async fn perform_coordinator_update(coordinator: Coordinator) -> Result<()> {
-    let mut write_lock = coordinator.write_owned().await;
+    let mut write_lock = LOCK_MONITOR.write_owned(coordinator).await;
 
     task::spawn_blocking(move || write_lock.update())
         .await?
         .map_err(|e| ResponseError::CoordinatorError(e))
 }
+
+/// Size of the chunks read from the files of the transcript while streaming it.
+const TRANSCRIPT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Size of the chunks read from a file streamed by [`stream_file`].
+const FILE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Size of the chunks in which the raw body of a request is spooled to disk.
+const SPOOL_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Opens a file of the transcript and returns it with its tar header. Fails if the size of the file is no longer the
+/// one in the manifest, from which the length of the archive has been computed.
+async fn open_transcript_file(
+    base_directory: &Path,
+    file: &TranscriptFile,
+) -> std::io::Result<([u8; TAR_BLOCK_SIZE], fs::File, u64)> {
+    let reader = fs::File::open(base_directory.join(&file.name)).await?;
+    let size = reader.metadata().await?.len();
+    if size != file.size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("the file has been updated since the manifest, {} bytes instead of {}", size, file.size),
+        ));
+    }
+
+    Ok((tar_header(&file.name, size)?, reader, size))
+}
+
+/// Streams the raw body of a request to the given file, one chunk at a time to bound the memory usage, and checks it
+/// against its expected length and digest. Returns the hex encoded hash of the body, computed on the fly with the given
+/// hasher of the ceremony. The file is removed if the body is not the expected one. Any upload in ranges to the same
+/// file is abandoned.
+pub async fn spool_body(
+    data: Data<'_>,
+    content: &RequestContent<'_>,
+    path: &Path,
+    hasher: &dyn ContributionHasher,
+) -> Result<String> {
+    let lock = spool_lock(path);
+    let _guard = lock.lock().await;
+    close_upload_session(path);
+
+    let spooled = write_spool(data, content, path, hasher).await;
+    if spooled.is_err() {
+        if let Err(e) = fs::remove_file(path).await {
+            error!("Couldn't remove the spooled body {}: {}", path.display(), e);
+        }
+    }
+
+    spooled
+}
+
+/// Reads the raw body of a request in memory and checks it against its expected length and digest. Only for the bodies
+/// up to the given limit, the larger ones are spooled to disk with [`spool_body`].
+pub async fn read_body(data: Data<'_>, content: &RequestContent<'_>, limit: u64) -> Result<Vec<u8>> {
+    if content.len as u64 > limit {
+        return Err(ResponseError::PayloadTooLarge(limit));
+    }
+    let body = data
+        .open(content.len.into())
+        .into_bytes()
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    if body.len() != content.len {
+        return Err(ResponseError::IoError(format!(
+            "Body truncated after {} of {} bytes",
+            body.len(),
+            content.len
+        )));
+    }
+
+    let digest = base64::encode(Sha256::digest(&body.value));
+    if digest != content.digest {
+        return Err(ResponseError::MismatchingChecksum(content.digest.to_string(), digest));
+    }
+
+    Ok(body.into_inner())
+}
+
+async fn write_spool(
+    data: Data<'_>,
+    content: &RequestContent<'_>,
+    path: &Path,
+    hasher: &dyn ContributionHasher,
+) -> Result<String> {
+    let mut file = fs::File::create(path)
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    let mut hash = hasher.incremental();
+    copy_body(data, content, &mut file, |bytes| hash.update(bytes)).await?;
+
+    Ok(hex::encode(hash.finalize()))
+}
+
+/// Streams the raw body of a request at the end of the given file and checks it against its expected length and
+/// digest, passing each chunk to `inspect` on the way.
+async fn copy_body(
+    data: Data<'_>,
+    content: &RequestContent<'_>,
+    file: &mut fs::File,
+    mut inspect: impl FnMut(&[u8]),
+) -> Result<()> {
+    let mut stream = data.open(content.len.into());
+    let mut sha256 = Sha256::new();
+    let mut buffer = vec![0u8; SPOOL_CHUNK_SIZE];
+    let mut written = 0;
+
+    loop {
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+
+        sha256.update(&buffer[..read]);
+        inspect(&buffer[..read]);
+        file.write_all(&buffer[..read])
+            .await
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+        written += read;
+    }
+    file.sync_all()
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+    if written != content.len {
+        return Err(ResponseError::IoError(format!(
+            "Body truncated after {} of {} bytes",
+            written, content.len
+        )));
+    }
+
+    let digest = base64::encode(sha256.finalize());
+    if digest != content.digest {
+        return Err(ResponseError::MismatchingChecksum(content.digest.to_string(), digest));
+    }
+
+    Ok(())
+}
+
+/// The progress of a contribution uploaded in ranges, see [`append_spool`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadProgress {
+    /// The number of bytes received, from the start of the contribution.
+    pub received: u64,
+    /// The expected size of the contribution.
+    pub size: u64,
+    /// The hex encoded hash of the contribution, once received in full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// The id of the upload session, started by the range at offset zero, which the next ranges must carry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+/// Returns the progress of the upload of the contribution of the given size spooled to the given file, hashed with the
+/// given hasher of the ceremony once received in full.
+pub async fn spool_progress(path: &Path, size: u64, hasher: &dyn ContributionHasher) -> Result<UploadProgress> {
+    let received = spooled_length(path).await;
+    let hash = match received == size {
+        true => Some(hash_file(path, hasher).await?),
+        false => None,
+    };
+    let session = fs::read_to_string(session_path(path)).await.ok();
+
+    Ok(UploadProgress {
+        received,
+        size,
+        hash,
+        session,
+    })
+}
+
+/// Appends the raw body of a request, a range of a contribution of the given size, to the file spooling it. The range
+/// at offset zero starts a new upload session, dropping the bytes received so far. The next ranges must carry the id of
+/// the session, so that two clients of the same contributor, e.g. after a resumed session, can't interleave their
+/// ranges, and their offset must be the number of bytes received so far. The range is checked against its expected
+/// length and digest, and dropped from the file if it is not the expected one, so that the client can upload it again.
+/// The appends to the same file are serialized, the session and the offset of a range being checked against the bytes
+/// received once the previous range is written.
+pub async fn append_spool(
+    data: Data<'_>,
+    content: &RequestContent<'_>,
+    path: &Path,
+    offset: u64,
+    session: Option<&str>,
+    size: u64,
+    hasher: &dyn ContributionHasher,
+) -> Result<UploadProgress> {
+    let lock = spool_lock(path);
+    let _guard = lock.lock().await;
+
+    let received = spooled_length(path).await;
+    if offset != 0 {
+        let current_session = fs::read_to_string(session_path(path)).await.ok();
+        if session.is_none() || session != current_session.as_deref() {
+            let session = session.unwrap_or_default().to_owned();
+            return Err(ResponseError::InvalidUploadSession(session));
+        }
+        if offset != received {
+            return Err(ResponseError::InvalidUploadOffset(offset, received));
+        }
+    }
+    if offset + content.len as u64 > size {
+        return Err(ResponseError::PayloadTooLarge(size - offset.min(size)));
+    }
+    if offset == 0 {
+        let mut session = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut session);
+        fs::write(session_path(path), hex::encode(session))
+            .await
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(path)
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    // Restarting the upload drops the received bytes
+    file.set_len(offset)
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    file.seek(SeekFrom::Start(offset))
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+    if let Err(e) = copy_body(data, content, &mut file, |_| ()).await {
+        if let Err(e) = file.set_len(offset).await {
+            error!("Couldn't drop the invalid range from {}: {}", path.display(), e);
+        }
+        return Err(e);
+    }
+
+    spool_progress(path, size, hasher).await
+}
+
+/// Returns the lock serializing the writes to the given spooled file, held for the whole upload of a body or of a
+/// range, so that concurrent uploads of the same contributor can't interleave their bytes.
+fn spool_lock(path: &Path) -> Arc<Mutex<()>> {
+    let mut locks = SPOOL_LOCKS.lock().expect("Unable to obtain lock to read the spool locks");
+    // Forget the locks of the files no longer uploaded
+    locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+    locks.entry(path.to_owned()).or_default().clone()
+}
+
+/// Returns the file holding the id of the upload session of the given spooled file, excluded from the manifests like
+/// the spooled file.
+fn session_path(path: &Path) -> PathBuf {
+    path.with_extension("session.tmp")
+}
+
+/// Closes the upload session of the given spooled file, once the file has been moved in place.
+pub fn close_upload_session(path: &Path) {
+    if let Err(e) = std::fs::remove_file(session_path(path)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            error!("Couldn't close the upload session of {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Returns the number of bytes spooled to the given file, zero if it doesn't exist.
+async fn spooled_length(path: &Path) -> u64 {
+    match fs::metadata(path).await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Returns the hex encoded hash of a file, computed with the given hasher one chunk at a time.
+async fn hash_file(path: &Path, hasher: &dyn ContributionHasher) -> Result<String> {
+    let file = fs::File::open(path)
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+    hash_opened_file(file, hasher).await
+}
+
+/// Returns the hex encoded hash of the whole content of an opened file, as [`hash_file`].
+async fn hash_opened_file(mut file: fs::File, hasher: &dyn ContributionHasher) -> Result<String> {
+    file.seek(SeekFrom::Start(0))
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    let mut hash = hasher.incremental();
+    let mut buffer = vec![0u8; SPOOL_CHUNK_SIZE];
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        hash.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hash.finalize()))
+}
+
+/// A streamed response of a known length, sent with its `Content-Length` so that the clients can preallocate the
+/// download and show its progress. Rocket answers the `HEAD` requests with the headers only.
+pub struct SizedStream<R> {
+    pub length: u64,
+    pub body: R,
+}
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for SizedStream<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response::build_from(self.body.respond_to(request)?)
+            .raw_header(CONTENT_LENGTH_HEADER, self.length.to_string())
+            .ok()
+    }
+}
+
+/// Wraps a response with the [`MESSAGE_CODE_HEADER`] of its message in the
+/// [message catalog](`crate::messages::MessageCatalog`).
+pub struct WithMessageCode<R>(pub R, pub &'static str);
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for WithMessageCode<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response::build_from(self.0.respond_to(request)?)
+            .raw_header(MESSAGE_CODE_HEADER, self.1)
+            .ok()
+    }
+}
+
+/// Returns the language of the [`MESSAGE_CATALOG`] preferred by the `Accept-Language` header of the request, if any.
+fn negotiate_language(request: &Request<'_>) -> Option<&'static str> {
+    request
+        .headers()
+        .get_one(ACCEPT_LANGUAGE_HEADER)
+        .and_then(|accept_language| MESSAGE_CATALOG.negotiate(accept_language))
+}
+
+/// The language of the [`MESSAGE_CATALOG`] negotiated with the `Accept-Language` header of the request,
+/// [`DEFAULT_LANGUAGE`] if the client doesn't accept any language of the catalog.
+pub struct AcceptLanguage(pub &'static str);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptLanguage {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Self(negotiate_language(request).unwrap_or(DEFAULT_LANGUAGE)))
+    }
+}
+
+/// Streams the given file as a raw byte stream, with its length. The file is read one chunk at a time, and only as
+/// fast as the client consumes the stream, to bound the memory usage for files of several gigabytes. Since errors can't
+/// be reported to the client once the response has started, the stream is truncated, which the client detects from
+/// the length, and the error is logged.
+pub async fn stream_file(path: PathBuf) -> Result<SizedStream<ByteStream![Vec<u8>]>> {
+    let reader = fs::File::open(&path)
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+    let length = reader
+        .metadata()
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?
+        .len();
+
+    stream_file_range(path, reader, 0, length).await
+}
+
+/// Streams the given number of bytes of the given opened file from the given offset, as [`stream_file`] streams the
+/// whole file. The path of the file is only logged.
+pub async fn stream_file_range(
+    path: PathBuf,
+    mut reader: fs::File,
+    offset: u64,
+    length: u64,
+) -> Result<SizedStream<ByteStream![Vec<u8>]>> {
+    reader
+        .seek(SeekFrom::Start(offset))
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+    let body = ByteStream! {
+        let mut remaining = length;
+        while remaining > 0 {
+            let mut buffer = vec![0u8; FILE_CHUNK_SIZE.min(remaining as usize)];
+            match reader.read(&mut buffer).await {
+                Ok(read) if read > 0 => {
+                    buffer.truncate(read);
+                    remaining -= read as u64;
+                    yield buffer;
+                }
+                Ok(_) => {
+                    error!("File {} was truncated while streaming it", path.display());
+                    return;
+                }
+                Err(e) => {
+                    error!("Couldn't read {} while streaming it: {}", path.display(), e);
+                    return;
+                }
+            }
+        }
+    };
+
+    Ok(SizedStream { length, body })
+}
+
+/// The conditional and range headers of a download, see [`stream_file_conditionally`].
+#[derive(Debug, Default)]
+pub struct DownloadConditions {
+    if_none_match: Option<String>,
+    if_range: Option<String>,
+    range: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for DownloadConditions {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = |name| request.headers().get_one(name).map(str::to_owned);
+
+        Outcome::Success(Self {
+            if_none_match: header(IF_NONE_MATCH_HEADER),
+            if_range: header(IF_RANGE_HEADER),
+            range: header(RANGE_HEADER),
+        })
+    }
+}
+
+impl DownloadConditions {
+    /// Returns `true` if the `If-None-Match` header lists the given entity tag, compared weakly, or is `*`.
+    fn matches_none(&self, etag: &str) -> bool {
+        let weak = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+
+        match &self.if_none_match {
+            Some(tags) => tags.split(',').any(|tag| tag.trim() == "*" || weak(tag) == weak(etag)),
+            None => false,
+        }
+    }
+
+    /// Returns the range of the file of the given length and entity tag requested by the `Range` header, if any. The
+    /// range is ignored, and the whole file sent, if the `If-Range` header doesn't hold the entity tag, compared
+    /// strongly, or if the range isn't a single byte range.
+    fn range(&self, etag: &str, length: u64) -> Option<ByteRange> {
+        if self
+            .if_range
+            .as_deref()
+            .map_or(false, |if_range| if_range.trim() != etag)
+        {
+            return None;
+        }
+
+        ByteRange::parse(self.range.as_deref()?, length)
+    }
+}
+
+/// A byte range of a file requested with the `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// The bytes from the first to the last, inclusive.
+    Satisfiable { first: u64, last: u64 },
+    /// The range starts after the end of the file.
+    NotSatisfiable,
+}
+
+impl ByteRange {
+    /// Parses the single byte range of a `Range` header, `bytes=<first>-<last>`, `bytes=<first>-` or
+    /// `bytes=-<suffix length>`, in a file of the given length. Returns [`None`] for the other units and for multiple
+    /// ranges, which are not supported.
+    pub fn parse(header: &str, length: u64) -> Option<Self> {
+        let (first, last) = header.trim().strip_prefix("bytes=")?.split_once('-')?;
+        let (first, last) = match (first.trim(), last.trim()) {
+            ("", suffix) => {
+                let suffix: u64 = suffix.parse().ok()?;
+                if suffix == 0 || length == 0 {
+                    return Some(ByteRange::NotSatisfiable);
+                }
+                return Some(ByteRange::Satisfiable {
+                    first: length.saturating_sub(suffix),
+                    last: length - 1,
+                });
+            }
+            (first, "") => (first.parse().ok()?, None),
+            (first, last) => {
+                let (first, last): (u64, u64) = (first.parse().ok()?, last.parse().ok()?);
+                if first > last {
+                    return None;
+                }
+                (first, Some(last))
+            }
+        };
+        if first >= length {
+            return Some(ByteRange::NotSatisfiable);
+        }
+
+        Some(ByteRange::Satisfiable {
+            first,
+            last: last.map_or(length - 1, |last: u64| last.min(length - 1)),
+        })
+    }
+}
+
+/// A file streamed with its entity tag, honoring the conditional and range headers of the request.
+pub enum ConditionalFile<R> {
+    /// The client already holds the file of the entity tag.
+    NotModified { etag: String },
+    /// The whole file.
+    Full { etag: String, stream: SizedStream<R> },
+    /// The requested range of the file, of the given total length.
+    Partial {
+        etag: String,
+        stream: SizedStream<R>,
+        first: u64,
+        last: u64,
+        total: u64,
+    },
+    /// The requested range starts after the end of the file, of the given length.
+    NotSatisfiable { length: u64 },
+}
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for ConditionalFile<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        match self {
+            ConditionalFile::NotModified { etag } => Response::build()
+                .status(Status::NotModified)
+                .raw_header(ETAG_HEADER, etag)
+                .ok(),
+            ConditionalFile::Full { etag, stream } => Response::build_from(stream.respond_to(request)?)
+                .raw_header(ETAG_HEADER, etag)
+                .raw_header(ACCEPT_RANGES_HEADER, "bytes")
+                .ok(),
+            ConditionalFile::Partial {
+                etag,
+                stream,
+                first,
+                last,
+                total,
+            } => Response::build_from(stream.respond_to(request)?)
+                .status(Status::PartialContent)
+                .raw_header(ETAG_HEADER, etag)
+                .raw_header(ACCEPT_RANGES_HEADER, "bytes")
+                .raw_header(CONTENT_RANGE_HEADER, format!("bytes {}-{}/{}", first, last, total))
+                .ok(),
+            ConditionalFile::NotSatisfiable { length } => Response::build()
+                .status(Status::RangeNotSatisfiable)
+                .raw_header(CONTENT_RANGE_HEADER, format!("bytes */{}", length))
+                .ok(),
+        }
+    }
+}
+
+/// Streams the given opened file of the given entity tag as [`stream_file`] does, unless the `If-None-Match` header of
+/// the request holds the entity tag, and only the range requested by the `Range` header, if any, so that a client
+/// retrying a download can skip it or resume it. The `If-Range` header makes sure that the range is of the same file.
+pub async fn stream_file_conditionally(
+    path: PathBuf,
+    file: std::fs::File,
+    etag: String,
+    conditions: &DownloadConditions,
+) -> Result<ConditionalFile<ByteStream![Vec<u8>]>> {
+    if conditions.matches_none(&etag) {
+        return Ok(ConditionalFile::NotModified { etag });
+    }
+    let reader = fs::File::from_std(file);
+    let total = reader
+        .metadata()
+        .await
+        .map_err(|e| ResponseError::IoError(e.to_string()))?
+        .len();
+
+    match conditions.range(&etag, total) {
+        Some(ByteRange::Satisfiable { first, last }) => Ok(ConditionalFile::Partial {
+            etag,
+            stream: stream_file_range(path, reader, first, last - first + 1).await?,
+            first,
+            last,
+            total,
+        }),
+        Some(ByteRange::NotSatisfiable) => Ok(ConditionalFile::NotSatisfiable { length: total }),
+        None => Ok(ConditionalFile::Full {
+            etag,
+            stream: stream_file_range(path, reader, 0, total).await?,
+        }),
+    }
+}
+
+/// Caches the entity tags of the challenges streamed to the contributors, the quoted hashes of their content, so that
+/// a challenge of several gigabytes is hashed once rather than on each download. An entry is computed again if the
+/// length or the modification time of the file changed, e.g. after the reset of a round.
+#[derive(Clone, Default)]
+pub struct ChallengeETags(Arc<sync::RwLock<HashMap<PathBuf, (u64, SystemTime, String)>>>);
+
+impl ChallengeETags {
+    /// Returns the entity tag of the challenge opened from the given path, hashed with the given hasher of the
+    /// ceremony. The opened file is hashed rather than the path, which may have been replaced since.
+    pub async fn etag(&self, path: &Path, file: &std::fs::File, hasher: &dyn ContributionHasher) -> Result<String> {
+        let file = fs::File::from_std(file.try_clone().map_err(|e| ResponseError::IoError(e.to_string()))?);
+        let metadata = file
+            .metadata()
+            .await
+            .map_err(|e| ResponseError::IoError(e.to_string()))?;
+        let modified = metadata.modified().map_err(|e| ResponseError::IoError(e.to_string()))?;
+
+        let cached = self
+            .0
+            .read()
+            .expect("Unable to obtain lock to read the challenge entity tags")
+            .get(path)
+            .filter(|(length, cached_modified, _)| *length == metadata.len() && *cached_modified == modified)
+            .map(|(_, _, etag)| etag.clone());
+        if let Some(etag) = cached {
+            return Ok(etag);
+        }
+
+        let etag = format!("\"{}\"", hash_opened_file(file, hasher).await?);
+        self.0
+            .write()
+            .expect("Unable to lock to write the challenge entity tags")
+            .insert(path.to_owned(), (metadata.len(), modified, etag.clone()));
+
+        Ok(etag)
+    }
+}
+
+/// Returns the length of the tar archive of the transcript of the given rounds, as streamed by [`stream_transcript`].
+pub fn transcript_length(manifests: &[RoundManifest]) -> u64 {
+    let entry_length = |size: u64| TAR_BLOCK_SIZE as u64 + size + tar_padding(size) as u64;
+
+    let entries: u64 = manifests
+        .iter()
+        .map(|manifest| {
+            let manifest_bytes = serde_json::to_vec_pretty(manifest).expect("Manifest serialization failed");
+            let files: u64 = manifest.files.iter().map(|file| entry_length(file.size)).sum();
+
+            entry_length(manifest_bytes.len() as u64) + files
+        })
+        .sum();
+
+    // End of archive marker
+    entries + 2 * TAR_BLOCK_SIZE as u64
+}
+
+/// Streams the tar archive of the transcript of the given rounds, with its length. The files of each round are preceded
+/// by the manifest of the round and are read one chunk at a time, to bound the memory usage. Since errors can't be
+/// reported to the client once the response has started, the archive is truncated, which the client detects from the
+/// length, and the error is logged.
+pub fn stream_transcript(base_directory: PathBuf, manifests: Vec<RoundManifest>) -> SizedStream<ByteStream![Vec<u8>]> {
+    let length = transcript_length(&manifests);
+    let body = ByteStream! {
+        for manifest in manifests {
+            let manifest_bytes = serde_json::to_vec_pretty(&manifest).expect("Manifest serialization failed");
+            match tar_header(&manifest.archive_name(), manifest_bytes.len() as u64) {
+                Ok(header) => yield header.to_vec(),
+                Err(e) => {
+                    error!("Couldn't add the manifest of round {} to the transcript: {}", manifest.round_height, e);
+                    return;
+                }
+            }
+            let padding = tar_padding(manifest_bytes.len() as u64);
+            yield manifest_bytes;
+            yield vec![0u8; padding];
+
+            for file in &manifest.files {
+                let (header, mut reader, size) = match open_transcript_file(&base_directory, file).await {
+                    Ok(opened) => opened,
+                    Err(e) => {
+                        error!("Couldn't add {} to the transcript: {}", file.name, e);
+                        return;
+                    }
+                };
+                yield header.to_vec();
+
+                let mut remaining = size;
+                while remaining > 0 {
+                    let mut buffer = vec![0u8; TRANSCRIPT_CHUNK_SIZE.min(remaining as usize)];
+                    match reader.read(&mut buffer).await {
+                        Ok(read) if read > 0 => {
+                            buffer.truncate(read);
+                            remaining -= read as u64;
+                            yield buffer;
+                        }
+                        Ok(_) => {
+                            error!("File {} was truncated while streaming the transcript", file.name);
+                            return;
+                        }
+                        Err(e) => {
+                            error!("Couldn't read {} while streaming the transcript: {}", file.name, e);
+                            return;
+                        }
+                    }
+                }
+                yield vec![0u8; tar_padding(size)];
+            }
+        }
+
+        // End of archive marker
+        yield vec![0u8; 2 * TAR_BLOCK_SIZE];
+    };
+
+    SizedStream { length, body }
+}
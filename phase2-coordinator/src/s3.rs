@@ -19,6 +19,8 @@ use tracing::warn;
 pub const TOKENS_ZIP_FILE: &str = "tokens.zip";
 const BACKOFF_SLEEP_TIME_MILLISECS: u32 = 100;
 const MAX_REQUEST_RETRY: u32 = 8; // This gives max 50 seconds before giving up and returning an error
+/// Content type of contributors.json: without a charset, browsers may not decode the names in it as UTF-8
+const JSON_UTF8_CONTENT_TYPE: &str = "application/json; charset=utf-8";
 
 lazy_static! {
     static ref BUCKET: String = std::env::var("AWS_S3_BUCKET").unwrap_or("bucket".to_string());
@@ -123,6 +125,7 @@ impl S3Ctx {
             bucket: self.bucket.clone(),
             key: "contributors.json".to_string(),
             body: Some(StreamingBody::from(contributions_info.clone())),
+            content_type: Some(JSON_UTF8_CONTENT_TYPE.to_string()),
             ..Default::default()
         };
 
@@ -141,6 +144,7 @@ impl S3Ctx {
                                 bucket: self.bucket.clone(),
                                 key: "contributors.json".to_string(),
                                 body: Some(StreamingBody::from(contributions_info.clone())),
+                                content_type: Some(JSON_UTF8_CONTENT_TYPE.to_string()),
                                 ..Default::default()
                             };
 
@@ -304,6 +308,51 @@ impl S3Ctx {
         rocket::tokio::try_join!(self.get_object(get_contrib), self.get_object(get_sig))
     }
 
+    /// Check whether a contribution and its signature have already been uploaded to S3.
+    pub(crate) async fn contribution_uploaded(&self, round_height: u64) -> (bool, bool) {
+        let head_contrib = HeadObjectRequest {
+            bucket: self.bucket.clone(),
+            key: format!("round_{}/chunk_0/contribution_1.unverified", round_height),
+            ..Default::default()
+        };
+        let head_sig = HeadObjectRequest {
+            bucket: self.bucket.clone(),
+            key: format!("round_{}/chunk_0/contribution_1.unverified.signature", round_height),
+            ..Default::default()
+        };
+
+        let (contrib, sig) =
+            rocket::tokio::join!(self.client.head_object(head_contrib), self.client.head_object(head_sig));
+
+        (contrib.is_ok(), sig.is_ok())
+    }
+
+    /// Delete the contribution of a round, and its challenge unless kept, from S3, e.g. after a dress rehearsal of the
+    /// launch. Deleting a missing file is not an error.
+    pub(crate) async fn delete_round(&self, round_height: u64, keep_challenge: bool) -> Result<()> {
+        let mut keys = vec![
+            format!("round_{}/chunk_0/contribution_1.unverified", round_height),
+            format!("round_{}/chunk_0/contribution_1.unverified.signature", round_height),
+        ];
+        if !keep_challenge {
+            keys.push(format!("round_{}/chunk_0/contribution_0.verified", round_height));
+        }
+
+        for key in keys {
+            let delete_object_request = DeleteObjectRequest {
+                bucket: self.bucket.clone(),
+                key,
+                ..Default::default()
+            };
+            self.client
+                .delete_object(delete_object_request)
+                .await
+                .map_err(|e| S3Error::DeleteError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
     /// Retrieve the compressed token folder.
     pub async fn get_tokens(&self) -> Result<Vec<u8>> {
         let key = match std::env::var("AWS_S3_PROD") {
@@ -0,0 +1,412 @@
+//! Verification of the contributions in a sandboxed subprocess, so that a crafted contribution exploiting a bug of the
+//! parsers of the parameters can't reach the state, the keys or the network of the coordinator.
+//!
+//! With the [`ComputeBackend::Sandboxed`](crate::environment::ComputeBackend) backend, the coordinator spawns its own
+//! executable with the [`SANDBOX_WORKER_ARG`] argument, and the worker:
+//!
+//! 1. reads a [`WorkerRequest`] with the resource limits and the hash function from its stdin,
+//! 2. limits its address space, CPU time, open files and written files with rlimits,
+//! 3. installs a seccomp filter allowing only the system calls required to compute, on all its threads: the other
+//!    system calls, among which opening files and sockets, fail with `EPERM`,
+//! 4. reads the challenge and the response from its stdin, verifies the contribution and writes a [`WorkerOutcome`]
+//!    to its stdout.
+//!
+//! The messages are exchanged on the pipes as frames: a little-endian `u64` length followed by the content. A worker
+//! crashing or killed by the kernel, e.g. on exceeding its CPU time, fails the verification with
+//! [`CoordinatorError::VerifierCrashed`], retried by the coordinator up to its maximum number of retries. The seccomp
+//! filter is only available on Linux, on x86_64 and aarch64, with the `sandbox` feature. Binaries embedding the
+//! coordinator must call [`run_worker`] when started with [`SANDBOX_WORKER_ARG`].
+
+use crate::{environment::SandboxLimits, hashing::HashAlgorithm, CoordinatorError};
+
+use serde::{Deserialize, Serialize};
+use setup_utils::{GenericArray, U64};
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+};
+use tracing::error;
+
+/// The argument starting the coordinator executable as a sandboxed verification worker.
+pub const SANDBOX_WORKER_ARG: &str = "--sandbox-worker";
+
+/// Whether the sandbox is supported by this build.
+pub const SANDBOX_SUPPORTED: bool = cfg!(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+));
+
+/// The maximum size of the frames of the messages, as opposed to the frames of the files.
+const MAX_MESSAGE_SIZE: u64 = 4096;
+
+/// The request sent to the worker before the files to verify.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerRequest {
+    pub limits: SandboxLimits,
+    /// The hash function of the contribution files.
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+}
+
+/// The outcome of the verification reported by the worker.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WorkerOutcome {
+    /// The contribution is valid, with the hash of the response in hex.
+    Verified {
+        response_hash: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// Writes a frame with the given content.
+pub fn write_frame(writer: &mut impl Write, content: &[u8]) -> io::Result<()> {
+    writer.write_all(&(content.len() as u64).to_le_bytes())?;
+    writer.write_all(content)
+}
+
+/// Reads a frame of at most `max_size` bytes.
+pub fn read_frame(reader: &mut impl Read, max_size: u64) -> io::Result<Vec<u8>> {
+    let mut length = [0u8; 8];
+    reader.read_exact(&mut length)?;
+    let length = u64::from_le_bytes(length);
+    if length > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Frame of {} bytes exceeds the maximum of {} bytes", length, max_size),
+        ));
+    }
+
+    let mut content = Vec::new();
+    reader.take(length).read_to_end(&mut content)?;
+    if content.len() as u64 != length {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(content)
+}
+
+/// Verifies the contribution in a sandboxed worker. Returns the hash of the response.
+pub fn verify(
+    limits: SandboxLimits,
+    hash_algorithm: HashAlgorithm,
+    challenge: &[u8],
+    response: &[u8],
+) -> Result<GenericArray<u8, U64>, CoordinatorError> {
+    let request = WorkerRequest { limits, hash_algorithm };
+    let outcome = spawn_worker(&request, challenge, response).map_err(|e| {
+        error!("Sandboxed verification worker failed: {}", e);
+        CoordinatorError::VerifierCrashed
+    })?;
+
+    match outcome {
+        WorkerOutcome::Verified { response_hash } => match hex::decode(response_hash) {
+            Ok(response_hash) if response_hash.len() == 64 => Ok(GenericArray::clone_from_slice(&response_hash)),
+            _ => {
+                error!("Sandboxed verification worker returned an invalid response hash");
+                Err(CoordinatorError::VerificationFailed)
+            }
+        },
+        WorkerOutcome::Failed { error } => {
+            error!("Sandboxed verification failed with {}", error);
+            Err(CoordinatorError::VerificationFailed)
+        }
+    }
+}
+
+fn spawn_worker(request: &WorkerRequest, challenge: &[u8], response: &[u8]) -> anyhow::Result<WorkerOutcome> {
+    let mut worker = Command::new(std::env::current_exe()?)
+        .arg(SANDBOX_WORKER_ARG)
+        .env_clear()
+        .current_dir("/")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // The worker reads all its input before writing its outcome, so the pipes can't fill up on both sides
+    let written = worker.stdin.take().map(|mut stdin| -> io::Result<()> {
+        write_frame(&mut stdin, &serde_json::to_vec(request)?)?;
+        write_frame(&mut stdin, challenge)?;
+        write_frame(&mut stdin, response)
+    });
+    let output = worker.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("Worker exited with {}", output.status));
+    }
+
+    // A worker failing early stops reading its input but still reports why
+    let outcome = read_frame(&mut output.stdout.as_slice(), MAX_MESSAGE_SIZE).map_err(|e| match written {
+        Some(Err(write_error)) => write_error,
+        _ => e,
+    })?;
+    Ok(serde_json::from_slice(&outcome)?)
+}
+
+/// Runs the worker on the stdin and the stdout of the process. Returns the exit code of the process.
+#[cfg(any(test, feature = "operator"))]
+pub fn run_worker() -> i32 {
+    let mut stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    let outcome = match verify_input(&mut stdin) {
+        Ok(response_hash) => WorkerOutcome::Verified {
+            response_hash: hex::encode(response_hash),
+        },
+        Err(error) => WorkerOutcome::Failed { error },
+    };
+    let written = serde_json::to_vec(&outcome)
+        .map_err(io::Error::from)
+        .and_then(|outcome| write_frame(&mut stdout, &outcome))
+        .and_then(|_| stdout.flush());
+
+    match written {
+        Ok(()) => 0,
+        Err(_) => 1,
+    }
+}
+
+#[cfg(any(test, feature = "operator"))]
+fn verify_input(input: &mut impl Read) -> Result<GenericArray<u8, U64>, String> {
+    let request = read_frame(input, MAX_MESSAGE_SIZE).map_err(|e| e.to_string())?;
+    let request: WorkerRequest = serde_json::from_slice(&request).map_err(|e| e.to_string())?;
+
+    // The files are only read once sandboxed
+    imp::enter(request.limits).map_err(|e| format!("Failed to enter the sandbox: {}", e))?;
+    let challenge = read_frame(input, u64::MAX).map_err(|e| e.to_string())?;
+    let response = read_frame(input, u64::MAX).map_err(|e| e.to_string())?;
+
+    // The verification of the transformation panics on an invalid contribution
+    let hasher = request.hash_algorithm.hasher();
+    match std::panic::catch_unwind(|| {
+        crate::commands::Verification::transform_pok_and_correctness(hasher, &challenge, &response)
+    }) {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err("invalid transformation".to_string()),
+    }
+}
+
+#[cfg(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod imp {
+    use crate::environment::SandboxLimits;
+
+    use std::io;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JEQ_K: u16 = 0x15;
+    const BPF_RET_K: u16 = 0x06;
+    const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+    const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const SECCOMP_SET_MODE_FILTER: libc::c_long = 1;
+    const SECCOMP_FILTER_FLAG_TSYNC: libc::c_long = 1;
+    /// `clone3` has the same number on all the architectures.
+    const SYS_CLONE3: libc::c_long = 435;
+
+    /// The system calls required by the verification: memory management, threads and the pipes to the coordinator.
+    const ALLOWED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_read,
+        libc::SYS_write,
+        libc::SYS_close,
+        libc::SYS_mmap,
+        libc::SYS_munmap,
+        libc::SYS_mremap,
+        libc::SYS_mprotect,
+        libc::SYS_madvise,
+        libc::SYS_brk,
+        libc::SYS_futex,
+        libc::SYS_clone,
+        libc::SYS_set_robust_list,
+        libc::SYS_sched_yield,
+        libc::SYS_sched_getaffinity,
+        libc::SYS_getrandom,
+        libc::SYS_clock_gettime,
+        libc::SYS_nanosleep,
+        libc::SYS_rt_sigaction,
+        libc::SYS_rt_sigprocmask,
+        libc::SYS_rt_sigreturn,
+        libc::SYS_sigaltstack,
+        libc::SYS_getpid,
+        libc::SYS_gettid,
+        libc::SYS_tgkill,
+        libc::SYS_exit,
+        libc::SYS_exit_group,
+    ];
+
+    #[repr(C)]
+    struct SockFilter {
+        code: u16,
+        jt: u8,
+        jf: u8,
+        k: u32,
+    }
+
+    #[repr(C)]
+    struct SockFprog {
+        len: u16,
+        filter: *const SockFilter,
+    }
+
+    const fn statement(code: u16, k: u32) -> SockFilter {
+        SockFilter { code, jt: 0, jf: 0, k }
+    }
+
+    const fn jump(k: u32, jt: u8, jf: u8) -> SockFilter {
+        SockFilter {
+            code: BPF_JEQ_K,
+            jt,
+            jf,
+            k,
+        }
+    }
+
+    /// Returns the seccomp filter: processes of another architecture are killed, since the system call numbers
+    /// would not match, the allowed system calls proceed, `clone3` fails with `ENOSYS` so that the threads are
+    /// created with `clone`, and the other system calls fail with `EPERM`.
+    fn filter() -> Vec<SockFilter> {
+        let mut filter = vec![
+            statement(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+            jump(AUDIT_ARCH, 1, 0),
+            statement(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            statement(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+        ];
+        for syscall in ALLOWED_SYSCALLS {
+            filter.push(jump(*syscall as u32, 0, 1));
+            filter.push(statement(BPF_RET_K, SECCOMP_RET_ALLOW));
+        }
+        filter.push(jump(SYS_CLONE3 as u32, 0, 1));
+        filter.push(statement(BPF_RET_K, SECCOMP_RET_ERRNO | libc::ENOSYS as u32));
+        filter.push(statement(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EPERM as u32));
+        filter
+    }
+
+    fn set_rlimit(resource: libc::__rlimit_resource_t, limit: u64) -> io::Result<()> {
+        let rlimit = libc::rlimit {
+            rlim_cur: limit,
+            rlim_max: limit,
+        };
+        match unsafe { libc::setrlimit(resource, &rlimit) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    pub(super) fn enter(limits: SandboxLimits) -> io::Result<()> {
+        if let Some(memory) = limits.memory {
+            set_rlimit(libc::RLIMIT_AS, memory)?;
+        }
+        if let Some(cpu_seconds) = limits.cpu_seconds {
+            set_rlimit(libc::RLIMIT_CPU, cpu_seconds)?;
+        }
+        set_rlimit(libc::RLIMIT_NOFILE, 0)?;
+        set_rlimit(libc::RLIMIT_FSIZE, 0)?;
+        set_rlimit(libc::RLIMIT_CORE, 0)?;
+
+        let filter = filter();
+        let program = SockFprog {
+            len: filter.len() as u16,
+            filter: filter.as_ptr(),
+        };
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::syscall(
+                libc::SYS_seccomp,
+                SECCOMP_SET_MODE_FILTER,
+                SECCOMP_FILTER_FLAG_TSYNC,
+                &program as *const SockFprog,
+            ) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_filter() {
+            let filter = filter();
+            // The filter ends with the default action and fits in a BPF program
+            assert_eq!(filter.last().unwrap().k, SECCOMP_RET_ERRNO | libc::EPERM as u32);
+            assert!(filter.len() <= 4096);
+            assert!(!ALLOWED_SYSCALLS.contains(&libc::SYS_socket));
+            assert!(!ALLOWED_SYSCALLS.contains(&libc::SYS_openat));
+        }
+    }
+}
+
+#[cfg(not(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod imp {
+    use crate::environment::SandboxLimits;
+
+    use std::io;
+
+    pub(super) fn enter(_limits: SandboxLimits) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the sandbox is not supported by this build",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let mut pipe = vec![];
+        write_frame(&mut pipe, b"challenge").unwrap();
+        write_frame(&mut pipe, b"").unwrap();
+        let mut reader = pipe.as_slice();
+
+        assert_eq!(read_frame(&mut reader, 16).unwrap(), b"challenge");
+        assert_eq!(read_frame(&mut reader, 16).unwrap(), b"");
+        assert!(read_frame(&mut reader, 16).is_err());
+
+        // Frames larger than the maximum or truncated are rejected
+        assert!(read_frame(&mut pipe.as_slice(), 8).is_err());
+        assert!(read_frame(&mut &pipe[..12], 16).is_err());
+    }
+
+    #[test]
+    fn test_worker_outcome() {
+        let mut pipe = vec![];
+        write_frame(&mut pipe, &serde_json::to_vec(&WorkerRequest::default()).unwrap()).unwrap();
+
+        // The worker reports a failure instead of verifying outside of the sandbox
+        if !SANDBOX_SUPPORTED {
+            assert!(verify_input(&mut pipe.as_slice()).is_err());
+        }
+        let outcome = WorkerOutcome::Failed {
+            error: "invalid transformation".to_string(),
+        };
+        let encoded = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(encoded, r#"{"status":"failed","error":"invalid transformation"}"#);
+        assert_eq!(serde_json::from_str::<WorkerOutcome>(&encoded).unwrap(), outcome);
+    }
+}
@@ -0,0 +1,117 @@
+//! Adaptive interval between the periodic updates of the coordinator.
+//!
+//! The coordinator is updated, and the pending contributions verified, by loops which sleep between two runs. A fixed
+//! interval is either too slow when a contribution waits for its verification or a participant is about to time out,
+//! or wasteful when the ceremony is idle. The [`UpdateScheduler`] instead shortens the interval to its minimum as soon
+//! as there is work to do, wakes up in time for the next timeout and doubles the interval, up to its maximum, while the
+//! coordinator stays idle.
+
+use std::time::Duration;
+
+/// The load of the coordinator considered to schedule its next update.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpdateLoad {
+    /// The number of contributions waiting for their verification.
+    pub pending_verifications: usize,
+    /// The time left before the next participant times out, if any.
+    pub next_timeout: Option<Duration>,
+}
+
+/// Scheduler of the updates of the coordinator, bounding the interval between `minimum` and `maximum`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdateScheduler {
+    minimum: Duration,
+    maximum: Duration,
+    interval: Duration,
+}
+
+impl UpdateScheduler {
+    /// Creates a scheduler starting from the minimum interval. A maximum lower than the minimum is raised to it.
+    pub fn new(minimum: Duration, maximum: Duration) -> Self {
+        Self {
+            minimum,
+            maximum: maximum.max(minimum),
+            interval: minimum,
+        }
+    }
+
+    /// Returns the interval computed by the last call to [`UpdateScheduler::schedule`].
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Computes and returns the interval to wait before the next update given the current load of the coordinator.
+    pub fn schedule(&mut self, load: &UpdateLoad) -> Duration {
+        let interval = if load.pending_verifications > 0 {
+            self.minimum
+        } else {
+            let backoff = self.interval.saturating_mul(2);
+            match load.next_timeout {
+                Some(next_timeout) => backoff.min(next_timeout),
+                None => backoff,
+            }
+        };
+
+        self.interval = interval.clamp(self.minimum, self.maximum);
+        self.interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMUM: Duration = Duration::from_secs(5);
+    const MAXIMUM: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_idle_backoff() {
+        let mut scheduler = UpdateScheduler::new(MINIMUM, MAXIMUM);
+        assert_eq!(scheduler.interval(), MINIMUM);
+
+        let idle = UpdateLoad::default();
+        assert_eq!(scheduler.schedule(&idle), Duration::from_secs(10));
+        assert_eq!(scheduler.schedule(&idle), Duration::from_secs(20));
+        assert_eq!(scheduler.schedule(&idle), Duration::from_secs(40));
+        assert_eq!(scheduler.schedule(&idle), MAXIMUM);
+        assert_eq!(scheduler.schedule(&idle), MAXIMUM);
+        assert_eq!(scheduler.interval(), MAXIMUM);
+    }
+
+    #[test]
+    fn test_pending_verifications() {
+        let mut scheduler = UpdateScheduler::new(MINIMUM, MAXIMUM);
+        scheduler.schedule(&UpdateLoad::default());
+        scheduler.schedule(&UpdateLoad::default());
+
+        let busy = UpdateLoad {
+            pending_verifications: 1,
+            next_timeout: None,
+        };
+        assert_eq!(scheduler.schedule(&busy), MINIMUM);
+    }
+
+    #[test]
+    fn test_imminent_timeout() {
+        let mut scheduler = UpdateScheduler::new(MINIMUM, MAXIMUM);
+        for _ in 0..4 {
+            scheduler.schedule(&UpdateLoad::default());
+        }
+
+        let timeout = |secs| UpdateLoad {
+            pending_verifications: 0,
+            next_timeout: Some(Duration::from_secs(secs)),
+        };
+        assert_eq!(scheduler.schedule(&timeout(30)), Duration::from_secs(30));
+        // Expired timeouts are checked as soon as allowed
+        assert_eq!(scheduler.schedule(&timeout(0)), MINIMUM);
+        // Distant timeouts don't prevent the backoff
+        assert_eq!(scheduler.schedule(&timeout(3600)), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_inverted_bounds() {
+        let mut scheduler = UpdateScheduler::new(MAXIMUM, MINIMUM);
+        assert_eq!(scheduler.schedule(&UpdateLoad::default()), MAXIMUM);
+    }
+}
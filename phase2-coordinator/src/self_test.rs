@@ -0,0 +1,245 @@
+//! Miniature end-to-end check of the coordinator, run at startup with `--self-test` before serving any traffic.
+//!
+//! The self-test signs and verifies a message with the key of the coordinator, writes, reads back and deletes a file
+//! in the base directory and in every storage shard, contributes to and verifies the parameters of a tiny test
+//! circuit, and checks that the clock is sane. The coordinator refuses to start if any check fails.
+
+use crate::{authentication::Signature, commands::TestCircuit, environment::Environment};
+
+use bls12_381::Scalar;
+use fs_err as fs;
+use masp_phase2::{verify_contribution, MPCParameters};
+use rand::{rngs::OsRng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+use serde::Serialize;
+use std::{path::Path, sync::Arc, time::Instant};
+use time::{macros::datetime, OffsetDateTime};
+use tracing::{error, info};
+
+/// The name of the file written to the storage roots by the self-test.
+const PROBE_FILE: &str = "self_test.probe";
+/// Any earlier time means that the clock of the host was never set.
+const MIN_CLOCK: OffsetDateTime = datetime!(2022-01-01 00:00 UTC);
+
+/// The outcome of a single check of the self-test.
+#[derive(Debug, Serialize)]
+pub struct CheckOutcome {
+    pub name: &'static str,
+    pub passed: bool,
+    /// What has been checked, or the reason of the failure.
+    pub detail: String,
+    pub duration_ms: u128,
+}
+
+/// The report of the self-test, printed as JSON.
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checks: Vec<CheckOutcome>,
+}
+
+impl SelfTestReport {
+    /// Logs the outcome of every check.
+    pub fn log(&self) {
+        for check in &self.checks {
+            match check.passed {
+                true => info!("Self-test {} passed: {}", check.name, check.detail),
+                false => error!("Self-test {} failed: {}", check.name, check.detail),
+            }
+        }
+    }
+}
+
+/// Runs all the checks of the self-test, even if one of them fails.
+pub fn run(
+    environment: &Environment,
+    signature: Arc<dyn Signature>,
+    signing_key: &str,
+    public_key: &str,
+) -> SelfTestReport {
+    let checks = vec![
+        check("signature", || {
+            check_signature(signature.as_ref(), signing_key, public_key)
+        }),
+        check("storage", || check_storage(environment)),
+        check("verification", check_verification),
+        check("clock", check_clock),
+    ];
+
+    SelfTestReport {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+    }
+}
+
+/// Times the given check.
+fn check<F: FnOnce() -> Result<String, String>>(name: &'static str, f: F) -> CheckOutcome {
+    let start = Instant::now();
+    let result = f();
+    let duration_ms = start.elapsed().as_millis();
+
+    match result {
+        Ok(detail) => CheckOutcome {
+            name,
+            passed: true,
+            detail,
+            duration_ms,
+        },
+        Err(detail) => CheckOutcome {
+            name,
+            passed: false,
+            detail,
+            duration_ms,
+        },
+    }
+}
+
+/// Signs a message with the key of the coordinator, and checks that the signature is accepted for that message only.
+fn check_signature(signature: &dyn Signature, signing_key: &str, public_key: &str) -> Result<String, String> {
+    let message = "phase2-coordinator self-test";
+    let signed = signature
+        .sign(signing_key, message)
+        .map_err(|e| format!("signing failed: {}", e))?;
+
+    if !signature.verify(public_key, message, &signed) {
+        return Err(String::from(
+            "the signature is rejected by the public key of the coordinator",
+        ));
+    }
+    if signature.is_secure() && signature.verify(public_key, "phase2-coordinator tampered", &signed) {
+        return Err(String::from("the signature is accepted for another message"));
+    }
+
+    Ok(format!("{} signature of the coordinator key", signature.name()))
+}
+
+/// Writes, reads back and deletes a file in the given directory.
+fn round_trip(root: &Path) -> Result<(), String> {
+    let path = root.join(PROBE_FILE);
+    let mut content = vec![0u8; 4096];
+    OsRng.fill_bytes(&mut content);
+
+    fs::create_dir_all(root).map_err(|e| e.to_string())?;
+    fs::write(&path, &content).map_err(|e| e.to_string())?;
+    let read = fs::read(&path).map_err(|e| e.to_string());
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+
+    match read? == content {
+        true => Ok(()),
+        false => Err(format!("{} was read back with another content", path.display())),
+    }
+}
+
+/// Checks that the base directory and the roots of the storage shards are readable and writable.
+fn check_storage(environment: &Environment) -> Result<String, String> {
+    let roots: Vec<&str> = std::iter::once(environment.local_base_directory())
+        .chain(environment.storage_shards().iter().map(String::as_str))
+        .collect();
+
+    for root in &roots {
+        round_trip(Path::new(root)).map_err(|e| format!("{}: {}", root, e))?;
+    }
+
+    Ok(format!("read/write/delete round-trip on {}", roots.join(", ")))
+}
+
+/// Contributes to the parameters of a tiny test circuit and verifies the contribution, the same way as the
+/// contributions to the ceremony. Also checks that parameters without a new contribution are rejected.
+fn check_verification() -> Result<String, String> {
+    let params = MPCParameters::new(TestCircuit { x: Some(Scalar::one()) })
+        .map_err(|e| format!("initialization failed: {:?}", e))?;
+    let mut challenge = vec![];
+    params.write(&mut challenge).map_err(|e| e.to_string())?;
+
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    let mut rng = ChaChaRng::from_seed(seed);
+    let mut contributed = MPCParameters::read(&challenge[..], false).map_err(|e| e.to_string())?;
+    contributed.contribute(&mut rng, &0u32);
+    let mut response = vec![];
+    contributed.write(&mut response).map_err(|e| e.to_string())?;
+
+    let before = MPCParameters::read(&challenge[..], false).map_err(|e| e.to_string())?;
+    let after = MPCParameters::read(&response[..], true).map_err(|e| e.to_string())?;
+    verify_contribution(&before, &after).map_err(|_| String::from("a valid contribution is rejected"))?;
+    if verify_contribution(&before, &before).is_ok() {
+        return Err(String::from("parameters without a new contribution are accepted"));
+    }
+
+    Ok(String::from("contribution to the test circuit verified"))
+}
+
+/// Checks that the clock of the host has been set, and that the start of the ceremony, if set, can be read.
+fn check_clock() -> Result<String, String> {
+    let now = OffsetDateTime::now_utc();
+    if now < MIN_CLOCK {
+        return Err(format!("the clock is set to {}, before {}", now, MIN_CLOCK));
+    }
+
+    if let Ok(timestamp) = std::env::var("CEREMONY_START_TIMESTAMP") {
+        let start = timestamp
+            .parse::<i64>()
+            .ok()
+            .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+            .ok_or_else(|| format!("CEREMONY_START_TIMESTAMP {} is not a unix timestamp", timestamp))?;
+
+        return Ok(format!("the time is {}, the ceremony starts at {}", now, start));
+    }
+
+    Ok(format!("the time is {}", now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        authentication::{KeyPair, Production},
+        environment::{Parameters, Testing},
+        testing::prelude::*,
+    };
+
+    #[test]
+    #[serial]
+    fn test_self_test() {
+        let environment: Environment = Testing::from(Parameters::Test3Chunks)
+            .storage_shards(&[String::from("./transcript/testing_shard")])
+            .into();
+        let keypair = KeyPair::new();
+
+        let report = run(&environment, Arc::new(Production), keypair.sigkey(), keypair.pubkey());
+        assert!(report.passed, "{:#?}", report);
+        assert_eq!(report.checks.len(), 4);
+        assert!(!Path::new(environment.local_base_directory()).join(PROBE_FILE).exists());
+        assert!(!Path::new("./transcript/testing_shard").join(PROBE_FILE).exists());
+
+        fs::remove_dir_all("./transcript/testing_shard").unwrap();
+    }
+
+    #[test]
+    fn test_signature() {
+        let keypair = KeyPair::new();
+        let other = KeyPair::new();
+
+        assert!(check_signature(&Production, keypair.sigkey(), keypair.pubkey()).is_ok());
+        assert!(check_signature(&Production, keypair.sigkey(), other.pubkey()).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_storage_not_writable() {
+        // The root of the shard is an existing file
+        fs::create_dir_all("./transcript").unwrap();
+        fs::write("./transcript/testing_shard_file", b"").unwrap();
+        let environment: Environment = Testing::from(Parameters::Test3Chunks)
+            .storage_shards(&[String::from("./transcript/testing_shard_file")])
+            .into();
+
+        let report = run(&environment, Arc::new(Production), "", "");
+        assert!(!report.passed);
+        let storage = report.checks.iter().find(|check| check.name == "storage").unwrap();
+        assert!(!storage.passed);
+        assert!(storage.detail.starts_with("./transcript/testing_shard_file"));
+
+        fs::remove_file("./transcript/testing_shard_file").unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+//! Store of the short-lived state of the frontends of the coordinator: the single-use nonces, e.g. the seeds of the
+//! proof-of-work puzzles, the rate limiting counters and the sessions.
+//!
+//! The state is kept in memory by default, which is only consistent with a single frontend. With the
+//! `shared-store-redis` feature and `NAMADA_MPC_SHARED_STORE_URL` set to the url of a Redis server, the state is shared
+//! by all the frontends connected to it, so that a nonce consumed by one of them is refused by the others and the rate
+//! limits apply to the requests of all the frontends.
+
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+lazy_static! {
+    pub static ref SHARED_STORE_URL: Option<String> = std::env::var("NAMADA_MPC_SHARED_STORE_URL").ok();
+}
+
+/// A key-value store whose entries expire, shared by the frontends of the coordinator.
+pub trait SharedStore: Send + Sync {
+    /// Stores the value under the given key, replacing any previous one, until the given time to live elapses.
+    fn put(&self, key: &str, value: &[u8], ttl: Duration) -> anyhow::Result<()>;
+
+    /// Returns the value stored under the given key, if any.
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Removes the value stored under the given key and returns it, atomically, so that a nonce can only be consumed
+    /// once.
+    fn take(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Increments the counter of the given key and returns its new value. The window of the counter starts with its
+    /// first increment, after which the counter restarts from zero.
+    fn increment(&self, key: &str, window: Duration) -> anyhow::Result<u64>;
+
+    /// Returns the value of the counter of the given key, zero once its window has elapsed.
+    fn count(&self, key: &str) -> anyhow::Result<u64>;
+}
+
+/// Connects to the shared store configured in the env, or returns a [`MemoryStore`] if none is set.
+pub fn from_env() -> anyhow::Result<Arc<dyn SharedStore>> {
+    #[cfg(feature = "shared-store-redis")]
+    if let Some(url) = SHARED_STORE_URL.as_ref() {
+        return Ok(Arc::new(RedisStore::connect(url)?));
+    }
+    #[cfg(not(feature = "shared-store-redis"))]
+    if SHARED_STORE_URL.is_some() {
+        anyhow::bail!("NAMADA_MPC_SHARED_STORE_URL requires the shared-store-redis feature");
+    }
+
+    Ok(Arc::new(MemoryStore::default()))
+}
+
+/// The store of a single frontend, in memory.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// The values and their expiration.
+    entries: Mutex<HashMap<String, (Vec<u8>, Instant)>>,
+}
+
+impl MemoryStore {
+    fn entries(&self) -> std::sync::MutexGuard<HashMap<String, (Vec<u8>, Instant)>> {
+        let mut entries = self.entries.lock().expect("Shared store lock poisoned");
+        let now = Instant::now();
+        entries.retain(|_, (_, expiration)| *expiration > now);
+
+        entries
+    }
+}
+
+impl SharedStore for MemoryStore {
+    fn put(&self, key: &str, value: &[u8], ttl: Duration) -> anyhow::Result<()> {
+        self.entries()
+            .insert(key.to_owned(), (value.to_vec(), Instant::now() + ttl));
+
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.entries().get(key).map(|(value, _)| value.clone()))
+    }
+
+    fn take(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.entries().remove(key).map(|(value, _)| value))
+    }
+
+    fn increment(&self, key: &str, window: Duration) -> anyhow::Result<u64> {
+        let mut entries = self.entries();
+        let (value, _) = entries
+            .entry(key.to_owned())
+            .or_insert_with(|| (0u64.to_le_bytes().to_vec(), Instant::now() + window));
+        let count = counter(value)? + 1;
+        *value = count.to_le_bytes().to_vec();
+
+        Ok(count)
+    }
+
+    fn count(&self, key: &str) -> anyhow::Result<u64> {
+        match self.entries().get(key) {
+            Some((value, _)) => counter(value),
+            None => Ok(0),
+        }
+    }
+}
+
+fn counter(value: &[u8]) -> anyhow::Result<u64> {
+    let bytes: [u8; 8] = value
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("The value of the counter is not a u64"))?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Increments the counter of `KEYS[1]` and starts its window of `ARGV[1]` milliseconds on its first increment, in a
+/// single step for the counter not to be left without expiration.
+#[cfg(feature = "shared-store-redis")]
+const INCREMENT_SCRIPT: &str = r"
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[1])
+end
+return count
+";
+
+/// The store shared by the frontends connected to the same Redis server.
+#[cfg(feature = "shared-store-redis")]
+pub struct RedisStore {
+    connection: Mutex<redis::Connection>,
+    increment: redis::Script,
+}
+
+#[cfg(feature = "shared-store-redis")]
+impl RedisStore {
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            connection: Mutex::new(redis::Client::open(url)?.get_connection()?),
+            increment: redis::Script::new(INCREMENT_SCRIPT),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<redis::Connection> {
+        self.connection.lock().expect("Shared store lock poisoned")
+    }
+}
+
+#[cfg(feature = "shared-store-redis")]
+impl SharedStore for RedisStore {
+    fn put(&self, key: &str, value: &[u8], ttl: Duration) -> anyhow::Result<()> {
+        Ok(redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query(&mut *self.connection())?)
+    }
+
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(redis::cmd("GET").arg(key).query(&mut *self.connection())?)
+    }
+
+    fn take(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let (value, _): (Option<Vec<u8>>, u64) = redis::pipe()
+            .atomic()
+            .cmd("GET")
+            .arg(key)
+            .cmd("DEL")
+            .arg(key)
+            .query(&mut *self.connection())?;
+
+        Ok(value)
+    }
+
+    fn increment(&self, key: &str, window: Duration) -> anyhow::Result<u64> {
+        Ok(self
+            .increment
+            .key(key)
+            .arg(window.as_millis() as u64)
+            .invoke(&mut *self.connection())?)
+    }
+
+    fn count(&self, key: &str) -> anyhow::Result<u64> {
+        let count: Option<u64> = redis::cmd("GET").arg(key).query(&mut *self.connection())?;
+
+        Ok(count.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::default();
+
+        // Nonces are consumed once
+        store.put("nonce", b"seed", Duration::from_secs(60)).unwrap();
+        assert_eq!(store.get("nonce").unwrap().unwrap(), b"seed");
+        assert_eq!(store.take("nonce").unwrap().unwrap(), b"seed");
+        assert!(store.take("nonce").unwrap().is_none());
+
+        // Counters restart once their window has elapsed
+        assert_eq!(store.count("joins").unwrap(), 0);
+        assert_eq!(store.increment("joins", Duration::from_millis(100)).unwrap(), 1);
+        assert_eq!(store.increment("joins", Duration::from_millis(100)).unwrap(), 2);
+        assert_eq!(store.count("joins").unwrap(), 2);
+
+        store.put("session", b"token", Duration::from_millis(100)).unwrap();
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(store.get("session").unwrap().is_none());
+        assert_eq!(store.count("joins").unwrap(), 0);
+        assert_eq!(store.increment("joins", Duration::from_millis(100)).unwrap(), 1);
+    }
+}
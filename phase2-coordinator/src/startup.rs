@@ -0,0 +1,110 @@
+//! Profiling of the startup of the [Coordinator](`crate::Coordinator`).
+//!
+//! The state load and the scan of the storage grow with the number of rounds of the ceremony, and a large ceremony may
+//! take minutes to restart. The time spent in each phase of the initialization is recorded in a [`StartupProfile`],
+//! logged once the coordinator has booted up and exposed by `/admin/startup_profile`, with a warning if the startup
+//! exceeded [`STARTUP_TARGET`].
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// The target duration of a warm start, up to 100 rounds.
+pub const STARTUP_TARGET: Duration = Duration::from_secs(30);
+
+/// A phase of the startup.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    pub duration_ms: u64,
+}
+
+/// The time spent in each phase of the startup of the coordinator, in order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupProfile {
+    pub phases: Vec<StartupPhase>,
+    pub total_ms: u64,
+    pub target_ms: u64,
+    /// The height of the current round once the coordinator has booted up.
+    pub round_height: Option<u64>,
+}
+
+impl Default for StartupProfile {
+    fn default() -> Self {
+        Self {
+            phases: vec![],
+            total_ms: 0,
+            target_ms: STARTUP_TARGET.as_millis() as u64,
+            round_height: None,
+        }
+    }
+}
+
+impl StartupProfile {
+    /// Runs the given phase and records its duration.
+    pub fn time<T>(&mut self, name: &str, phase: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = phase();
+        self.record(name, start.elapsed());
+
+        result
+    }
+
+    /// Records a phase of the given duration.
+    pub fn record(&mut self, name: &str, duration: Duration) {
+        let duration_ms = duration.as_millis() as u64;
+        self.total_ms += duration_ms;
+        self.phases.push(StartupPhase {
+            name: name.to_string(),
+            duration_ms,
+        });
+    }
+
+    pub fn is_within_target(&self) -> bool {
+        self.total_ms <= self.target_ms
+    }
+
+    /// Returns a table of the phases, with their duration and their share of the startup.
+    pub fn summary(&self) -> String {
+        let mut summary = match self.round_height {
+            Some(round_height) => format!("Startup profile at round {}:\n", round_height),
+            None => "Startup profile:\n".to_string(),
+        };
+        for phase in &self.phases {
+            let share = match self.total_ms {
+                0 => 0.0,
+                total => phase.duration_ms as f64 * 100.0 / total as f64,
+            };
+            summary.push_str(&format!("  {:<24}{:>10} ms{:>7.1}%\n", phase.name, phase.duration_ms, share));
+        }
+        summary.push_str(&format!("  {:<24}{:>10} ms (target {} ms)", "total", self.total_ms, self.target_ms));
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_profile() {
+        let mut profile = StartupProfile::default();
+        assert_eq!(profile.time("state", || 42), 42);
+        profile.record("storage_consistency", Duration::from_millis(1500));
+        profile.record("round_load", Duration::from_millis(500));
+        profile.round_height = Some(100);
+
+        assert_eq!(profile.phases.len(), 3);
+        assert_eq!(profile.phases[1].duration_ms, 1500);
+        assert!(profile.total_ms >= 2000 && profile.total_ms < 3000);
+        assert!(profile.is_within_target());
+
+        let summary = profile.summary();
+        assert!(summary.starts_with("Startup profile at round 100:"));
+        assert!(summary.contains("storage_consistency"));
+        assert!(summary.contains("target 30000 ms"));
+
+        profile.record("circuit_registry", STARTUP_TARGET);
+        assert!(!profile.is_within_target());
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
+    conversion::ParameterFormat,
     environment::Environment,
     objects::{ContributionFileSignature, ContributionInfo, Round, TrimmedContributionInfo},
+    prelaunch::{PrelaunchSnapshot, PRELAUNCH_FILE},
     storage::{
-        ContributionLocator, ContributionSignatureLocator, Locator, Object, ObjectReader, ObjectWriter, StorageLocator,
-        StorageObject,
+        check_path, relative_key, relative_path, to_locator_path, ContributionLocator, ContributionSignatureLocator,
+        Inconsistency, Locator, MetadataStore, Object, ObjectReader, ObjectStatus, ObjectWriter, RoundManifest,
+        StorageLocator, StorageObject, TranscriptFile, METADATA_FILE, METADATA_JOURNAL_FILE,
     },
     CoordinatorError, CoordinatorState,
 };
@@ -12,22 +15,115 @@ use anyhow::Result;
 use fs_err::{self as fs, File, OpenOptions};
 use itertools::Itertools;
 use memmap::MmapOptions;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use setup_utils::calculate_hash;
 
 use std::{
+    collections::HashSet,
     convert::TryFrom,
-    io::{Read, Write},
+    io::{self, Read, Write},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
     str::FromStr,
 };
-use tracing::{debug, error, trace};
+use tracing::{debug, error, trace, warn};
 
-use super::{LocatorPath, StorageAction};
+use super::{hash_file, LocatorPath, StorageAction};
+
+/// The file name of the journal of the pending transaction, relative to the storage base directory.
+pub const TRANSACTION_FILE: &str = "transaction.json";
 
 #[derive(Debug)]
 pub struct Disk {
     environment: Environment,
     resolver: DiskResolver,
+    metadata: MetadataStore,
+    /// The absolute paths of the roots of the storage shards.
+    shards: Vec<PathBuf>,
+}
+
+/// The usage of a storage shard.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardUsage {
+    /// The root directory of the shard.
+    pub root: String,
+    /// The number of objects stored on the shard.
+    pub objects: u64,
+    /// The total size, in bytes, of the objects stored on the shard.
+    pub size: u64,
+}
+
+/// The write of an object in a transaction.
+#[derive(Debug, Serialize, Deserialize)]
+struct StagedWrite {
+    /// The file holding the new content of the object until the transaction completes.
+    staged: PathBuf,
+    /// The file replaced by the staged one, which differs from the path of the object if stored on a shard.
+    target: PathBuf,
+    /// The path of the object.
+    path: LocatorPath,
+    /// Whether the object is created by the transaction, in which case it is removed if the transaction is discarded.
+    #[serde(default)]
+    created: bool,
+}
+
+/// The journal of a transaction, persisted while the transaction is pending.
+#[derive(Debug, Serialize, Deserialize)]
+struct Transaction {
+    /// Whether all the objects have been staged. A committed transaction is completed when the storage is loaded
+    /// after a crash, otherwise it is discarded.
+    committed: bool,
+    writes: Vec<StagedWrite>,
+}
+
+/// Returns `true` if the object at the given locator is stored on a shard, when sharding is enabled. Only the
+/// large objects, read and written concurrently by the verifications and the uploads, are distributed.
+fn is_sharded(locator: &Locator) -> bool {
+    matches!(
+        locator,
+        Locator::RoundFile { .. } | Locator::ContributionFile(_) | Locator::ParametersFile { .. }
+    )
+}
+
+/// Links an object stored on a shard from its path in the base directory.
+#[cfg(unix)]
+fn link_blob(blob_path: &Path, path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(blob_path, path)
+}
+
+#[cfg(not(unix))]
+fn link_blob(_blob_path: &Path, _path: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "storage shards require symbolic links"))
+}
+
+/// Returns the path of the object stored on a shard and linked from the given path, or [`None`] if the object is
+/// stored in the base directory.
+fn linked_blob(path: &Path) -> Result<Option<PathBuf>, CoordinatorError> {
+    match fs::symlink_metadata(path)?.file_type().is_symlink() {
+        true => Ok(Some(fs::read_link(path)?)),
+        false => Ok(None),
+    }
+}
+
+/// Checks that the size of the contribution file at the given locator is the one expected in its round.
+fn check_contribution_file_size(locator: &ContributionLocator, found_size: u64) -> Result<(), CoordinatorError> {
+    let expected_size = Object::anoma_contribution_file_size(locator.round_height(), locator.contribution_id());
+    debug!(
+        "Round {} chunk {} filesize is {}",
+        locator.round_height(),
+        locator.chunk_id(),
+        found_size
+    );
+    if found_size != expected_size {
+        error!(
+            "Contribution file size should be {} but found {}",
+            expected_size, found_size
+        );
+        return Err(CoordinatorError::ContributionFileSizeMismatch);
+    }
+
+    Ok(())
 }
 
 impl Disk {
@@ -45,12 +141,30 @@ impl Disk {
             fs::create_dir_all(contributors_dir).expect("unable to create the contributors directory");
         }
 
+        // Load the metadata of the stored objects.
+        let metadata = MetadataStore::load(Path::new(environment.local_base_directory()).join(METADATA_FILE))?;
+
+        // Create the roots of the shards and resolve them to absolute paths, the targets of the links to the objects.
+        let shards = environment
+            .storage_shards()
+            .iter()
+            .map(|root| {
+                fs::create_dir_all(root)?;
+                Ok(fs::canonicalize(root)?)
+            })
+            .collect::<Result<Vec<_>, CoordinatorError>>()?;
+
         // Create a new `Storage` instance, and set the `Environment`.
         let mut storage = Self {
             environment: environment.clone(),
             resolver: DiskResolver::new(environment.local_base_directory()),
+            metadata,
+            shards,
         };
 
+        // Complete or discard the transaction interrupted by a crash, if any.
+        storage.recover_transaction()?;
+
         // Create the coordinator state locator if it does not exist yet.
         if !storage.exists(&Locator::CoordinatorState) {
             storage.insert(
@@ -91,16 +205,40 @@ impl Disk {
                 .chunk_directory_init(contribution_locator.round_height(), contribution_locator.chunk_id());
         }
 
-        // Open the file.
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create_new(true)
-            .open(locator_path)?;
+        // If the locator is a parameters file, initialize its directory.
+        if let Locator::ParametersFile { round_height, .. } = locator {
+            self.resolver.parameters_directory_init(round_height);
+        }
+
+        // Open the file. Objects assigned to a shard are stored there and linked from the base directory.
+        let file = match self.shard_path(&locator, &locator_path)? {
+            Some(blob_path) => {
+                if let Some(parent) = blob_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                // A leftover of a previous run can be overwritten, since the object is not in storage.
+                let file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(&blob_path)?;
+                link_blob(&blob_path, locator_path.as_path())?;
+                file
+            }
+            None => OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(locator_path.clone())?,
+        };
 
         // Set the initial file size.
         file.set_len(size)?;
 
+        // Track the new object in the metadata.
+        self.metadata.reserve(locator_path, size)?;
+
         trace!("Initialized {}", self.to_path(&locator)?);
         Ok(())
     }
@@ -248,6 +386,7 @@ impl Disk {
                 let summary: Vec<TrimmedContributionInfo> = serde_json::from_slice(&file_bytes)?;
                 Ok(Object::ContributionsInfoSummary(summary))
             }
+            Locator::ParametersFile { .. } => Ok(Object::ParametersFile(file_bytes)),
         };
 
         trace!("Fetched {}", self.to_path(locator)?);
@@ -285,11 +424,26 @@ impl Disk {
             return Err(CoordinatorError::StorageLocatorMissing);
         }
 
-        let mut file = OpenOptions::new().write(true).open(path)?;
-        file.set_len(object.size())?;
-        file.write_all(&object.to_bytes())?;
+        // Record the pending write before touching the file, so that an interrupted write can be detected.
+        let bytes = object.to_bytes();
+        self.metadata.begin_write(path.clone(), &bytes)?;
+
+        // Write the new content next to the object, on its shard if any, and move it in place, rather than rewriting
+        // the object in place, so that a reader which opened the object keeps reading its previous content.
+        let target = linked_blob(path.as_path())?.unwrap_or_else(|| path.as_path().to_path_buf());
+        let staged = PathBuf::from(format!("{}.update.tmp", target.display()));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&staged)?;
+        file.write_all(&bytes)?;
         // Sync all in-memory data to disk.
         file.flush()?;
+        file.file().sync_all()?;
+        fs::rename(&staged, &target)?;
+
+        self.metadata.finish_write(&path)?;
 
         trace!("Updated {}", self.to_path(&locator)?);
         Ok(())
@@ -339,10 +493,16 @@ impl Disk {
             return Err(CoordinatorError::StorageLocatorMissing);
         }
 
+        // Remove the object from its shard, if any, before the link to it.
+        if let Some(blob_path) = linked_blob(path.as_path())? {
+            fs::remove_file(blob_path)?;
+        }
+
         // TODO: if any of the locators are directories, make this
         // detect whether the path is a directory of a file and call
         // the appropriate function.
         fs::remove_file(path.clone())?;
+        self.metadata.remove(&path)?;
 
         trace!("Removed {}", path);
         Ok(())
@@ -366,6 +526,571 @@ impl Disk {
         Ok(file.metadata()?.len())
     }
 
+    /// Records the current content of the object at the given locator in the metadata. Must be called
+    /// once the object has been written through a [StorageObject::writer].
+    pub fn commit(&mut self, locator: &Locator) -> Result<(), CoordinatorError> {
+        let path = self.to_path(locator)?;
+        trace!("Committing {}", path);
+
+        // Check that the given locator exists in storage.
+        if !self.exists(locator) {
+            error!("Locator missing in call to commit() in storage.");
+            return Err(CoordinatorError::StorageLocatorMissing);
+        }
+
+        let (size, hash) = hash_file(path.as_path())?;
+        self.metadata.commit_hashed(path, size, hash)
+    }
+
+    /// Writes all the given objects to storage, or none of them if interrupted. The objects which
+    /// don't exist yet are created.
+    ///
+    /// The new contents are staged next to the files they replace and moved in place only once
+    /// all of them have been written and the transaction is committed in its journal. After a crash,
+    /// the transaction is completed or discarded the next time the storage is loaded.
+    pub fn write_all_or_nothing(&mut self, objects: &[(Locator, Object)]) -> Result<(), CoordinatorError> {
+        self.write_transaction(None, objects)
+    }
+
+    /// Returns the file holding the content of the object at the given locator, on its shard if any, to stream it
+    /// rather than load it in memory with a reader.
+    pub fn object_path(&self, locator: &Locator) -> Result<PathBuf, CoordinatorError> {
+        if !self.exists(locator) {
+            error!("Locator {:?} missing in call to object_path() in storage.", locator);
+            return Err(CoordinatorError::StorageLocatorMissing);
+        }
+
+        let path = self.to_path(locator)?;
+        Ok(linked_blob(path.as_path())?.unwrap_or_else(|| path.as_path().to_path_buf()))
+    }
+
+    /// Opens the file holding the content of the object at the given locator, as [`Disk::object_path`], to read it
+    /// once the lock on the coordinator is released. Objects are replaced by moving a new file in place, see
+    /// [`Disk::update`], so the opened file keeps the content the object had when opened.
+    pub fn open_object(&self, locator: &Locator) -> Result<(PathBuf, std::fs::File), CoordinatorError> {
+        let path = self.object_path(locator)?;
+        let file = std::fs::File::open(&path)?;
+
+        Ok((path, file))
+    }
+
+    /// Reads the contribution file opened by [`Disk::open_object`] and checks its size. The storage is not borrowed,
+    /// so that large files can be read once the lock on the coordinator is released.
+    pub fn read_contribution_file(
+        mut file: std::fs::File,
+        locator: &ContributionLocator,
+    ) -> Result<Vec<u8>, CoordinatorError> {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        check_contribution_file_size(locator, data.len() as u64)?;
+
+        Ok(data)
+    }
+
+    /// Returns the file to which the new content of the object at the given locator can be spooled, e.g. while it is
+    /// streamed from a request, before being moved in place by [`Disk::write_spooled`]. The file is next to the object,
+    /// on its shard if any, and excluded from the manifests.
+    pub fn spool_path(&self, locator: &Locator) -> Result<PathBuf, CoordinatorError> {
+        if !self.exists(locator) {
+            error!("Locator {:?} missing in call to spool_path() in storage.", locator);
+            return Err(CoordinatorError::StorageLocatorMissing);
+        }
+
+        let path = self.to_path(locator)?;
+        let target = linked_blob(path.as_path())?.unwrap_or_else(|| path.as_path().to_path_buf());
+        Ok(PathBuf::from(format!("{}.spool.tmp", target.display())))
+    }
+
+    /// Moves the content spooled at [`Disk::spool_path`] in place of the object at the given locator and writes the
+    /// given objects, in a single transaction as [`Disk::write_all_or_nothing`]. The spooled file is discarded if the
+    /// transaction is interrupted before being committed.
+    pub fn write_spooled(&mut self, spooled: &Locator, objects: &[(Locator, Object)]) -> Result<(), CoordinatorError> {
+        self.write_transaction(Some(spooled), objects)
+    }
+
+    fn write_transaction(
+        &mut self,
+        spooled: Option<&Locator>,
+        objects: &[(Locator, Object)],
+    ) -> Result<(), CoordinatorError> {
+        trace!("Writing {} objects in a transaction", objects.len());
+
+        // The spooled content is already staged.
+        let mut writes = Vec::with_capacity(objects.len() + 1);
+        if let Some(locator) = spooled {
+            let staged = self.spool_path(locator)?;
+            if !staged.exists() {
+                error!("Spooled content of {:?} missing in call to write_spooled() in storage.", locator);
+                return Err(CoordinatorError::StorageLocatorMissing);
+            }
+
+            let path = self.to_path(locator)?;
+            let target = linked_blob(path.as_path())?.unwrap_or_else(|| path.as_path().to_path_buf());
+            writes.push(StagedWrite {
+                staged,
+                target,
+                path,
+                created: false,
+            });
+        }
+
+        // Resolve the files to replace, on their assigned shard for the missing objects.
+        let mut missing = vec![];
+        for (locator, object) in objects {
+            let path = self.to_path(locator)?;
+            let created = !self.exists(locator);
+            let target = match created {
+                true => {
+                    missing.push((locator.clone(), object.size()));
+                    self.shard_path(locator, &path)?
+                }
+                false => linked_blob(path.as_path())?,
+            }
+            .unwrap_or_else(|| path.as_path().to_path_buf());
+            let staged = PathBuf::from(format!("{}.staged.tmp", target.display()));
+            writes.push(StagedWrite {
+                staged,
+                target,
+                path,
+                created,
+            });
+        }
+
+        // Journal the transaction before allocating the missing objects and staging, so that both can be discarded
+        // after a crash.
+        let mut transaction = Transaction {
+            committed: false,
+            writes,
+        };
+        self.persist_transaction(&transaction)?;
+
+        for (locator, size) in missing {
+            self.initialize(locator, size)?;
+        }
+
+        let staged_writes = transaction.writes.iter().skip(usize::from(spooled.is_some()));
+        for (write, (_, object)) in staged_writes.zip(objects) {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&write.staged)?;
+            file.write_all(&object.to_bytes())?;
+            file.file().sync_all()?;
+        }
+
+        // From now on, the transaction is completed even if interrupted.
+        transaction.committed = true;
+        self.persist_transaction(&transaction)?;
+        self.complete_transaction(&transaction)?;
+
+        trace!("Wrote {} objects in a transaction", objects.len());
+        Ok(())
+    }
+
+    /// Completes a committed transaction, moving the staged files in place, or discards the staged
+    /// files and the created objects of an uncommitted one, and removes its journal. Can be repeated
+    /// if interrupted.
+    fn complete_transaction(&mut self, transaction: &Transaction) -> Result<(), CoordinatorError> {
+        for write in &transaction.writes {
+            match transaction.committed {
+                true => {
+                    if write.staged.exists() {
+                        fs::rename(&write.staged, &write.target)?;
+                    }
+                    let (size, hash) = hash_file(write.path.as_path())?;
+                    self.metadata.commit_hashed(write.path.clone(), size, hash)?;
+                }
+                false => {
+                    if write.staged.exists() {
+                        fs::remove_file(&write.staged)?;
+                    }
+
+                    // The created object may have been partially allocated, with or without its link.
+                    if write.created {
+                        if write.target.as_path() != write.path.as_path() && write.target.exists() {
+                            fs::remove_file(&write.target)?;
+                        }
+                        if fs::symlink_metadata(write.path.as_path()).is_ok() {
+                            fs::remove_file(write.path.as_path())?;
+                        }
+                        self.metadata.remove(&write.path)?;
+                    }
+                }
+            }
+        }
+
+        fs::remove_file(self.transaction_path())?;
+        Ok(())
+    }
+
+    /// Completes or discards the transaction left in the journal, if any.
+    fn recover_transaction(&mut self) -> Result<(), CoordinatorError> {
+        let journal = self.transaction_path();
+        if !journal.exists() {
+            return Ok(());
+        }
+
+        let transaction: Transaction = serde_json::from_slice(&fs::read(&journal)?)?;
+        match transaction.committed {
+            true => warn!("Completing interrupted transaction of {} objects", transaction.writes.len()),
+            false => warn!("Discarding interrupted transaction of {} objects", transaction.writes.len()),
+        }
+
+        self.complete_transaction(&transaction)
+    }
+
+    /// Atomically writes the journal of the given transaction to disk.
+    fn persist_transaction(&self, transaction: &Transaction) -> Result<(), CoordinatorError> {
+        let journal = self.transaction_path();
+        let tmp_path = journal.with_extension("json.tmp");
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        file.write_all(&serde_json::to_vec(transaction)?)?;
+        file.file().sync_all()?;
+
+        fs::rename(&tmp_path, &journal)?;
+        Ok(())
+    }
+
+    /// Compares the metadata with the objects in storage and returns the inconsistencies found.
+    ///
+    /// The objects are hashed in parallel, as hashing the contributions of every round is the bulk of the startup of a
+    /// large ceremony.
+    pub fn check_consistency(&self) -> Result<Vec<Inconsistency>, CoordinatorError> {
+        let blobs = self.blob_paths()?;
+        let stored: HashSet<&LocatorPath> = blobs.iter().collect();
+
+        let entries: Vec<_> = self.metadata.iter().collect();
+        let mut inconsistencies = entries
+            .par_iter()
+            .map(|(path, metadata)| -> Result<Option<Inconsistency>, CoordinatorError> {
+                if !stored.contains(path) {
+                    return Ok(Some(Inconsistency::MissingBlob((*path).clone())));
+                }
+
+                let found = fs::metadata(path.as_path())?.len();
+                let inconsistency = match &metadata.status {
+                    ObjectStatus::Writing { .. } => Some(Inconsistency::IncompleteWrite((*path).clone())),
+                    _ if found != metadata.size => Some(Inconsistency::SizeMismatch {
+                        path: (*path).clone(),
+                        expected: metadata.size,
+                        found,
+                    }),
+                    ObjectStatus::Committed => {
+                        if metadata.hash.as_ref() != Some(&hash_file(path.as_path())?.1) {
+                            Some(Inconsistency::HashMismatch((*path).clone()))
+                        } else {
+                            None
+                        }
+                    }
+                    ObjectStatus::Reserved => None,
+                };
+
+                Ok(inconsistency)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        for path in blobs.into_iter().filter(|path| self.metadata.get(path).is_none()) {
+            inconsistencies.push(Inconsistency::UntrackedBlob(path));
+        }
+
+        Ok(inconsistencies)
+    }
+
+    /// Reconciles the metadata with the objects in storage, returning the inconsistencies
+    /// which could not be resolved automatically and require manual intervention.
+    ///
+    /// Untracked objects are adopted, entries of missing objects are dropped and interrupted
+    /// writes are committed if the content on disk matches the expected one.
+    pub fn reconcile(&mut self) -> Result<Vec<Inconsistency>, CoordinatorError> {
+        let mut unresolved = vec![];
+
+        for inconsistency in self.check_consistency()? {
+            match &inconsistency {
+                Inconsistency::MissingBlob(path) => {
+                    warn!("Dropping metadata of missing object {}", path);
+                    self.metadata.remove(path)?;
+                }
+                Inconsistency::UntrackedBlob(path) => {
+                    warn!("Adopting untracked object {}", path);
+                    let (size, hash) = hash_file(path.as_path())?;
+                    self.metadata.commit_hashed(path.clone(), size, hash)?;
+                }
+                Inconsistency::IncompleteWrite(path) => {
+                    let expected = match self.metadata.get(path).map(|m| &m.status) {
+                        Some(ObjectStatus::Writing { hash }) => hash.clone(),
+                        _ => continue,
+                    };
+
+                    if hash_file(path.as_path())?.1 == expected {
+                        warn!("Completing interrupted write of {}", path);
+                        self.metadata.finish_write(path)?;
+                    } else {
+                        error!("Object {} was partially written", path);
+                        unresolved.push(inconsistency);
+                    }
+                }
+                Inconsistency::SizeMismatch { path, .. } | Inconsistency::HashMismatch(path) => {
+                    error!("Object {} doesn't match its metadata", path);
+                    unresolved.push(inconsistency);
+                }
+            }
+        }
+
+        Ok(unresolved)
+    }
+
+    /// Returns the number of objects and the space used on each shard.
+    pub fn shard_usage(&self) -> Result<Vec<ShardUsage>, CoordinatorError> {
+        let mut usage: Vec<ShardUsage> = self
+            .shards
+            .iter()
+            .map(|root| ShardUsage {
+                root: root.to_string_lossy().into_owned(),
+                objects: 0,
+                size: 0,
+            })
+            .collect();
+
+        for path in self.blob_paths()? {
+            if let Some(blob_path) = linked_blob(path.as_path())? {
+                if let Some(index) = self.shards.iter().position(|root| blob_path.starts_with(root)) {
+                    usage[index].objects += 1;
+                    usage[index].size += fs::metadata(&blob_path)?.len();
+                }
+            }
+        }
+
+        Ok(usage)
+    }
+
+    /// Moves the objects which are not on their assigned shard, e.g. after a shard has been added or removed, or which
+    /// are on a shard while sharding is disabled. Returns the number of moved objects.
+    ///
+    /// Each object is copied before the link to it is replaced, so that it stays readable if the rebalance is
+    /// interrupted: the only leftover is then a copy which is overwritten when the object is moved again.
+    pub fn rebalance(&mut self) -> Result<usize, CoordinatorError> {
+        let mut moved = 0;
+
+        for path in self.blob_paths()? {
+            // Skip the files which are not objects of the storage.
+            let locator = match self.to_locator(&path) {
+                Ok(locator) => locator,
+                Err(_) => continue,
+            };
+
+            let current_blob_path = linked_blob(path.as_path())?;
+            let blob_path = self.shard_path(&locator, &path)?;
+            if current_blob_path == blob_path {
+                continue;
+            }
+
+            debug!("Moving {} from {:?} to {:?}", path, current_blob_path, blob_path);
+            let source = current_blob_path.clone().unwrap_or_else(|| path.clone().into());
+            let tmp_path = PathBuf::from(format!("{}.tmp", path));
+            if fs::symlink_metadata(&tmp_path).is_ok() {
+                fs::remove_file(&tmp_path)?;
+            }
+
+            match &blob_path {
+                Some(blob_path) => {
+                    if let Some(parent) = blob_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::copy(&source, blob_path)?;
+                    link_blob(blob_path, &tmp_path)?;
+                }
+                None => {
+                    fs::copy(&source, &tmp_path)?;
+                }
+            }
+            // Atomically replace the previous link, or file, with the new one.
+            fs::rename(&tmp_path, path.as_path())?;
+
+            if let Some(current_blob_path) = current_blob_path {
+                fs::remove_file(current_blob_path)?;
+            }
+            moved += 1;
+        }
+
+        Ok(moved)
+    }
+
+    /// Returns the path of the object at the given locator on its assigned shard, or [`None`] if the object is stored
+    /// in the base directory. Objects are assigned to a shard by the hash of their path.
+    fn shard_path(&self, locator: &Locator, path: &LocatorPath) -> Result<Option<PathBuf>, CoordinatorError> {
+        if self.shards.is_empty() || !is_sharded(locator) {
+            return Ok(None);
+        }
+
+        let relative_path = path
+            .as_path()
+            .strip_prefix(self.environment.local_base_directory())
+            .map_err(|_| CoordinatorError::StorageLocatorFormatIncorrect)?;
+        let hash = calculate_hash(relative_path.to_string_lossy().as_bytes());
+        let index = hash.iter().take(8).fold(0u64, |index, byte| index << 8 | *byte as u64) % self.shards.len() as u64;
+
+        Ok(Some(self.shards[index as usize].join(relative_path)))
+    }
+
+    /// Returns the manifest of the files of the given round directory. The contribution info file of the round is left
+    /// out, since it holds the private details of the contributors and the manifest is published with the transcript.
+    pub fn round_manifest(&self, round_height: u64) -> Result<RoundManifest, CoordinatorError> {
+        let round_directory = PathBuf::from(self.resolver.round_directory(round_height));
+        if !round_directory.is_dir() {
+            error!("Round directory of round {} is missing", round_height);
+            return Err(CoordinatorError::RoundDoesNotExist);
+        }
+
+        let mut paths = vec![];
+        let mut dirs = vec![round_directory];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if !path.to_string_lossy().ends_with(".tmp") {
+                    paths.push(to_locator_path(&path)?);
+                }
+            }
+        }
+
+        paths.sort();
+
+        let files = paths
+            .into_iter()
+            .map(|path| self.transcript_file_at(path))
+            .collect::<Result<Vec<_>, CoordinatorError>>()?;
+
+        Ok(RoundManifest {
+            round_height,
+            files,
+            verifications: Vec::new(),
+            injections: Vec::new(),
+            attestations: Vec::new(),
+            parameters: Vec::new(),
+            rehearsal: false,
+            genesis_seed: None,
+            computation_proofs: Vec::new(),
+        })
+    }
+
+    /// Returns the description of the object at the given locator in the manifest of a round.
+    pub fn transcript_file(&self, locator: &Locator) -> Result<TranscriptFile, CoordinatorError> {
+        self.transcript_file_at(self.to_path(locator)?)
+    }
+
+    fn transcript_file_at(&self, path: LocatorPath) -> Result<TranscriptFile, CoordinatorError> {
+        // The names in the transcript are separated by `/` whatever the platform of the coordinator
+        let name = relative_path(&path.to_string(), self.environment.local_base_directory())
+            .ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+
+        Ok(TranscriptFile {
+            name,
+            size: fs::metadata(path.as_path())?.len(),
+            hash: self.metadata.get(&path).and_then(|metadata| metadata.hash.clone()),
+        })
+    }
+
+    /// Returns the paths of all the objects stored on disk.
+    fn blob_paths(&self) -> Result<Vec<LocatorPath>, CoordinatorError> {
+        let mut paths = vec![];
+        let mut dirs = vec![PathBuf::from(self.environment.local_base_directory())];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if !path.starts_with(self.metadata_path())
+                    && !path.starts_with(self.metadata_journal_path())
+                    && !path.starts_with(self.transaction_path())
+                    && !path.starts_with(self.prelaunch_path())
+                    && !path.to_string_lossy().ends_with(".tmp")
+                {
+                    paths.push(to_locator_path(&path)?);
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Returns the path of the metadata store.
+    fn metadata_path(&self) -> PathBuf {
+        Path::new(self.environment.local_base_directory()).join(METADATA_FILE)
+    }
+
+    /// Returns the path of the journal of the metadata store.
+    fn metadata_journal_path(&self) -> PathBuf {
+        Path::new(self.environment.local_base_directory()).join(METADATA_JOURNAL_FILE)
+    }
+
+    /// Returns the path of the journal of the pending transaction.
+    fn transaction_path(&self) -> PathBuf {
+        Path::new(self.environment.local_base_directory()).join(TRANSACTION_FILE)
+    }
+
+    /// Returns the path of the pre-launch snapshot.
+    fn prelaunch_path(&self) -> PathBuf {
+        Path::new(self.environment.local_base_directory()).join(PRELAUNCH_FILE)
+    }
+
+    /// Saves the pre-launch snapshot, replacing the previous one.
+    pub fn save_prelaunch_snapshot(&self, snapshot: &PrelaunchSnapshot) -> Result<(), CoordinatorError> {
+        let path = self.prelaunch_path();
+        let staged = path.with_extension("json.tmp");
+
+        fs::write(&staged, serde_json::to_vec(snapshot)?)?;
+        fs::rename(&staged, &path)?;
+
+        Ok(())
+    }
+
+    /// Returns the pre-launch snapshot, if one has been taken.
+    pub fn prelaunch_snapshot(&self) -> Result<Option<PrelaunchSnapshot>, CoordinatorError> {
+        let path = self.prelaunch_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_slice(&fs::read(&path)?)?))
+    }
+
+    /// Removes the state and the files of a round written after the pre-launch snapshot. If `keep_initial_challenges`
+    /// is set, the initial challenges of the round are kept, otherwise the whole round is removed. Returns `false` if
+    /// the round is not in storage.
+    pub fn wipe_round(&mut self, round_height: u64, keep_initial_challenges: bool) -> Result<bool, CoordinatorError> {
+        let round_dir = PathBuf::from(self.resolver.round_directory(round_height));
+        if !round_dir.exists() {
+            return Ok(false);
+        }
+        warn!("Wiping round {}", round_height);
+
+        self.clear_dir_files(round_dir.clone(), !keep_initial_challenges);
+        self.clear_info_files(round_height);
+
+        let round_state = Locator::RoundState { round_height };
+        if self.exists(&round_state) {
+            self.remove(&round_state)?;
+        }
+        if !keep_initial_challenges {
+            fs::remove_dir_all(&round_dir)?;
+        }
+
+        Ok(true)
+    }
+
     /// Process a [StorageAction] which mutates the storage.
     pub fn process(&mut self, action: StorageAction) -> Result<()> {
         match action {
@@ -569,25 +1294,7 @@ impl StorageObject for Disk {
                 }
             }
             Locator::ContributionFile(contribution_locator) => {
-                // Check that the contribution size is correct.
-                let expected_size = Object::anoma_contribution_file_size(
-                    contribution_locator.round_height(),
-                    contribution_locator.contribution_id(),
-                );
-                let found_size = data.len() as u64;
-                debug!(
-                    "Round {} chunk {} filesize is {}",
-                    contribution_locator.round_height(),
-                    contribution_locator.chunk_id(),
-                    found_size
-                );
-                if found_size != expected_size {
-                    error!(
-                        "Contribution file size should be {} but found {}",
-                        expected_size, found_size
-                    );
-                    return Err(CoordinatorError::ContributionFileSizeMismatch.into());
-                }
+                check_contribution_file_size(contribution_locator, data.len() as u64)?;
             }
             _ => {}
         }
@@ -714,40 +1421,31 @@ impl StorageLocator for DiskResolver {
                 self.base, round_height
             ),
             Locator::ContributionsInfoSummary => format!("{}/contributors.json", self.base),
+            Locator::ParametersFile {
+                round_height,
+                chunk_id,
+                position,
+                format,
+            } => format!(
+                "{}/chunk_{}_circuit_{}.{}",
+                self.parameters_directory(*round_height),
+                chunk_id,
+                position,
+                format.extension()
+            ),
         };
-        // Sanitize the path.
-        LocatorPath::try_from(Path::new(&path))
+        // Check that the path is valid on all the supported platforms.
+        check_path(&self.base, &path)
     }
 
     #[inline]
     fn to_locator(&self, path: &LocatorPath) -> Result<Locator, CoordinatorError> {
-        // Sanitize the given path and base to the local OS.
-        let mut path = path.to_string();
-        let path = {
-            // TODO (howardwu): Change this to support absolute paths and OS specific path
-            //   conventions that may be non-standard. For now, restrict this to relative paths.
-            if !path.starts_with("./") {
-                path = format!("./{}", path);
-            }
-
-            let path = Path::new(path.as_str());
-            let base = Path::new(&self.base);
-
-            // Check that the path matches the expected base.
-            if !path.starts_with(base) {
-                error!("{:?} does not start with {:?}", path, base);
-                return Err(CoordinatorError::StorageLocatorFormatIncorrect);
-            }
-
-            path
-        };
-
-        // Strip the base prefix.
-        let key = path
-            .strip_prefix(&format!("{}/", self.base))
-            .map_err(|_| CoordinatorError::StorageLocatorFormatIncorrect)?;
-
-        let key = key.to_str().ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+        // Strip the base prefix, accepting the separators and the case folding of all the supported platforms.
+        let key = relative_key(&path.to_string(), &self.base).ok_or_else(|| {
+            error!("{:?} does not start with {:?}", path, self.base);
+            CoordinatorError::StorageLocatorFormatIncorrect
+        })?;
+        let key = key.as_str();
 
         // Check if it matches the coordinator state file.
         if key == "coordinator.json" {
@@ -784,6 +1482,29 @@ impl StorageLocator for DiskResolver {
                         return Ok(Locator::RoundFile { round_height });
                     }
 
+                    // Check if it matches a parameters file.
+                    if let Some(file) = remainder.strip_prefix("parameters/") {
+                        let (name, extension) = file
+                            .splitn(2, '.')
+                            .collect_tuple()
+                            .ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+                        let (chunk_id, position) = name
+                            .strip_prefix("chunk_")
+                            .and_then(|name| name.splitn(2, "_circuit_").collect_tuple())
+                            .ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+                        let format = ParameterFormat::ALL
+                            .iter()
+                            .find(|format| format.extension() == extension)
+                            .ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+
+                        return Ok(Locator::ParametersFile {
+                            round_height,
+                            chunk_id: u64::from_str(chunk_id)?,
+                            position: u64::from_str(position)?,
+                            format: *format,
+                        });
+                    }
+
                     // Parse the path into its components.
                     if let Some((chunk, path)) = remainder.splitn(2, "/").collect_tuple() {
                         // Check if it resembles the chunk directory.
@@ -896,13 +1617,236 @@ impl DiskResolver {
             fs::create_dir_all(&path).expect("unable to create the chunk directory");
         }
     }
+
+    /// Returns the directory of the final parameters exported in the given round.
+    #[inline]
+    fn parameters_directory(&self, round_height: u64) -> String {
+        // Format the parameters directory as `{round_directory}/parameters`.
+        format!("{}/parameters", self.round_directory(round_height))
+    }
+
+    /// Initializes the directory of the final parameters exported in the given round.
+    #[inline]
+    fn parameters_directory_init(&self, round_height: u64) {
+        let path = self.parameters_directory(round_height);
+        if !Path::new(&path).exists() {
+            fs::create_dir_all(&path).expect("unable to create the parameters directory");
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::prelude::{initialize_test_environment, serial, test_storage, TEST_ENVIRONMENT};
     // use crate::testing::prelude::*;
 
+    #[test]
+    #[serial]
+    fn test_consistency_check() {
+        initialize_test_environment(&TEST_ENVIRONMENT);
+        let mut storage = test_storage(&TEST_ENVIRONMENT);
+        let base = Path::new(TEST_ENVIRONMENT.local_base_directory());
+
+        // A freshly loaded storage is consistent.
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // Simulate a crash leaving a dangling metadata entry and an untracked object.
+        storage.insert(Locator::RoundHeight, Object::RoundHeight(0)).unwrap();
+        let round_height_path = storage.to_path(&Locator::RoundHeight).unwrap();
+        fs::remove_file(round_height_path.as_path()).unwrap();
+        let untracked_path = LocatorPath::try_from(base.join("contributors").join("untracked.json").as_path()).unwrap();
+        fs::write(untracked_path.as_path(), b"{}").unwrap();
+
+        let inconsistencies = storage.check_consistency().unwrap();
+        assert_eq!(inconsistencies.len(), 2);
+        assert!(inconsistencies.contains(&Inconsistency::MissingBlob(round_height_path)));
+        assert!(inconsistencies.contains(&Inconsistency::UntrackedBlob(untracked_path.clone())));
+
+        // Both inconsistencies can be resolved automatically.
+        assert!(storage.reconcile().unwrap().is_empty());
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // A corrupted object requires manual intervention.
+        fs::write(untracked_path.as_path(), b"[]").unwrap();
+        assert_eq!(
+            storage.reconcile().unwrap(),
+            vec![Inconsistency::HashMismatch(untracked_path)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_write_all_or_nothing() {
+        initialize_test_environment(&TEST_ENVIRONMENT);
+        let mut storage = test_storage(&TEST_ENVIRONMENT);
+
+        // Missing objects are created and existing ones replaced.
+        storage.insert(Locator::RoundHeight, Object::RoundHeight(0)).unwrap();
+        let contribution_info = Locator::ContributionInfoFile { round_height: 1 };
+        storage
+            .write_all_or_nothing(&[
+                (Locator::RoundHeight, Object::RoundHeight(1)),
+                (contribution_info.clone(), Object::ContributionInfoFile(Default::default())),
+            ])
+            .unwrap();
+        assert!(matches!(storage.get(&Locator::RoundHeight).unwrap(), Object::RoundHeight(1)));
+        assert!(storage.exists(&contribution_info));
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // Simulate a crash while staging: the transaction is discarded on load, with the objects it created.
+        let path = storage.to_path(&Locator::RoundHeight).unwrap();
+        let staged = PathBuf::from(format!("{}.staged.tmp", path));
+        let created = Locator::ContributionInfoFile { round_height: 2 };
+        let created_path = storage.to_path(&created).unwrap();
+        let mut transaction = Transaction {
+            committed: false,
+            writes: vec![
+                StagedWrite {
+                    staged: staged.clone(),
+                    target: path.as_path().to_path_buf(),
+                    path: path.clone(),
+                    created: false,
+                },
+                StagedWrite {
+                    staged: PathBuf::from(format!("{}.staged.tmp", created_path)),
+                    target: created_path.as_path().to_path_buf(),
+                    path: created_path,
+                    created: true,
+                },
+            ],
+        };
+        fs::write(&staged, b"2").unwrap();
+        storage.persist_transaction(&transaction).unwrap();
+        storage.initialize(created.clone(), 0).unwrap();
+
+        let mut storage = test_storage(&TEST_ENVIRONMENT);
+        assert!(!staged.exists());
+        assert!(!storage.exists(&created));
+        assert!(matches!(storage.get(&Locator::RoundHeight).unwrap(), Object::RoundHeight(1)));
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // Simulate a crash once committed: the transaction is completed on load.
+        transaction.committed = true;
+        transaction.writes.truncate(1);
+        fs::write(&staged, b"2").unwrap();
+        storage.persist_transaction(&transaction).unwrap();
+
+        let storage = test_storage(&TEST_ENVIRONMENT);
+        assert!(!staged.exists());
+        assert!(matches!(storage.get(&Locator::RoundHeight).unwrap(), Object::RoundHeight(2)));
+        assert!(storage.check_consistency().unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_write_spooled() {
+        initialize_test_environment(&TEST_ENVIRONMENT);
+        let mut storage = test_storage(&TEST_ENVIRONMENT);
+
+        // Only existing objects can be spooled.
+        assert!(storage.spool_path(&Locator::RoundHeight).is_err());
+        storage.insert(Locator::RoundHeight, Object::RoundHeight(0)).unwrap();
+        let spooled = storage.spool_path(&Locator::RoundHeight).unwrap();
+        assert!(spooled.to_string_lossy().ends_with(".tmp"));
+
+        // The spooled content is required.
+        let contribution_info = Locator::ContributionInfoFile { round_height: 1 };
+        let objects = [(contribution_info.clone(), Object::ContributionInfoFile(Default::default()))];
+        assert!(storage.write_spooled(&Locator::RoundHeight, &objects).is_err());
+
+        fs::write(&spooled, b"1").unwrap();
+        storage.write_spooled(&Locator::RoundHeight, &objects).unwrap();
+        assert!(!spooled.exists());
+        assert!(matches!(storage.get(&Locator::RoundHeight).unwrap(), Object::RoundHeight(1)));
+        assert!(storage.exists(&contribution_info));
+        assert!(storage.check_consistency().unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_update_keeps_open_readers() {
+        initialize_test_environment(&TEST_ENVIRONMENT);
+        let mut storage = test_storage(&TEST_ENVIRONMENT);
+
+        // An object opened before an update is read as it was, and replaced for the next readers.
+        storage.insert(Locator::RoundHeight, Object::RoundHeight(0)).unwrap();
+        let (path, mut opened) = storage.open_object(&Locator::RoundHeight).unwrap();
+        storage.update(&Locator::RoundHeight, Object::RoundHeight(1)).unwrap();
+
+        let mut content = vec![];
+        opened.read_to_end(&mut content).unwrap();
+        assert_eq!(content, Object::RoundHeight(0).to_bytes());
+        assert!(matches!(
+            storage.get(&Locator::RoundHeight).unwrap(),
+            Object::RoundHeight(1)
+        ));
+        assert!(!PathBuf::from(format!("{}.update.tmp", path.display())).exists());
+        assert!(storage.check_consistency().unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_storage_shards() {
+        use crate::environment::{Parameters, Testing};
+
+        let shards = vec![
+            "./transcript/testing_shards/0".to_string(),
+            "./transcript/testing_shards/1".to_string(),
+        ];
+        let environment: Environment = Testing::from(Parameters::Test8Chunks)
+            .storage_shards(&shards)
+            .into();
+        initialize_test_environment(&environment);
+        let mut storage = test_storage(&environment);
+
+        // The contribution files are distributed on the shards, and readable from the base directory.
+        let locators: Vec<Locator> = (0..8)
+            .map(|chunk_id| Locator::ContributionFile(ContributionLocator::new(0, chunk_id, 0, true)))
+            .collect();
+        for (chunk_id, locator) in locators.iter().enumerate() {
+            storage
+                .insert(locator.clone(), Object::ContributionFile(vec![chunk_id as u8; 16]))
+                .unwrap();
+        }
+        for (chunk_id, locator) in locators.iter().enumerate() {
+            let path = storage.to_path(locator).unwrap();
+            assert!(linked_blob(path.as_path()).unwrap().is_some());
+            assert_eq!(storage.reader(locator).unwrap().as_ref(), &[chunk_id as u8; 16][..]);
+        }
+
+        let usage = storage.shard_usage().unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage.iter().map(|shard| shard.objects).sum::<u64>(), 8);
+        assert_eq!(usage.iter().map(|shard| shard.size).sum::<u64>(), 8 * 16);
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // After removing a shard, the rebalance moves its objects to the remaining one.
+        let moved = usage[1].objects as usize;
+        let environment: Environment = Testing::from(Parameters::Test8Chunks)
+            .storage_shards(&shards[..1])
+            .into();
+        let mut storage = test_storage(&environment);
+        assert_eq!(storage.rebalance().unwrap(), moved);
+        assert_eq!(storage.rebalance().unwrap(), 0);
+
+        let usage = storage.shard_usage().unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].objects, 8);
+        for (chunk_id, locator) in locators.iter().enumerate() {
+            assert_eq!(storage.reader(locator).unwrap().as_ref(), &[chunk_id as u8; 16][..]);
+        }
+        assert!(storage.check_consistency().unwrap().is_empty());
+
+        // Removing an object also removes it from its shard.
+        let blob_path = linked_blob(storage.to_path(&locators[0]).unwrap().as_path())
+            .unwrap()
+            .unwrap();
+        storage.remove(&locators[0]).unwrap();
+        assert!(!blob_path.exists());
+    }
+
     #[test]
     fn test_to_path_coordinator_state() {
         let locator = DiskResolver::new("./transcript/test");
@@ -925,6 +1869,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_locator_windows_paths() {
+        let locator = DiskResolver::new(".\\transcript\\test");
+
+        assert_eq!(
+            Locator::ContributionFile(ContributionLocator::new(1, 0, 1, true)),
+            locator
+                .to_locator(&".\\transcript\\test\\round_1\\chunk_0\\contribution_1.verified".into())
+                .unwrap(),
+        );
+        assert_eq!(
+            Locator::RoundState { round_height: 1 },
+            locator
+                .to_locator(&"./transcript/test/ROUND_1/State.json".into())
+                .unwrap(),
+        );
+        assert_eq!(
+            LocatorPath::from(".\\transcript\\test/round_1/state.json"),
+            locator.to_path(&Locator::RoundState { round_height: 1 }).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_path_not_portable() {
+        let locator = DiskResolver::new(&format!("./{}", "a".repeat(230)));
+
+        assert!(locator.to_path(&Locator::RoundState { round_height: 1 }).is_ok());
+        assert!(locator
+            .to_path(&Locator::ContributionFile(ContributionLocator::new(1, 0, 1, true)))
+            .is_err());
+    }
+
     #[test]
     fn test_to_path_round_height() {
         let locator = DiskResolver::new("./transcript/test");
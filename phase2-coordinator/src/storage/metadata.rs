@@ -0,0 +1,372 @@
+use crate::{storage::LocatorPath, CoordinatorError};
+
+use blake2::{Blake2b512, Digest};
+use fs_err::{self as fs, File, OpenOptions};
+use serde::{Deserialize, Serialize};
+use setup_utils::calculate_hash;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use tracing::{error, trace, warn};
+
+/// The file name of the metadata store, relative to the storage base directory.
+pub const METADATA_FILE: &str = "metadata.json";
+/// The file name of the journal of the mutations of the metadata store since its last snapshot, relative to the storage
+/// base directory.
+pub const METADATA_JOURNAL_FILE: &str = "metadata.journal";
+/// The number of mutations appended to the journal before the metadata store is compacted into a new snapshot.
+const COMPACTION_THRESHOLD: usize = 1024;
+/// The size of the chunks in which the files are read to be hashed.
+const HASH_CHUNK_SIZE: usize = 1 << 20;
+
+/// The status of an object in the blob storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectStatus {
+    /// The object has been allocated but its content has not been written yet.
+    Reserved,
+    /// The content of the object is being written, with the hash of the expected content.
+    Writing { hash: String },
+    /// The content of the object has been written completely.
+    Committed,
+}
+
+/// The metadata of an object in the blob storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectMetadata {
+    /// The size in bytes of the object.
+    pub size: u64,
+    /// The hex encoded hash of the committed content of the object.
+    pub hash: Option<String>,
+    pub status: ObjectStatus,
+}
+
+/// A mismatch between the metadata store and the blob storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Inconsistency {
+    /// The metadata references an object which is missing from the blob storage.
+    MissingBlob(LocatorPath),
+    /// An object of the blob storage is not tracked in the metadata.
+    UntrackedBlob(LocatorPath),
+    /// The write of an object was interrupted before completion.
+    IncompleteWrite(LocatorPath),
+    /// The size of an object doesn't match the one in the metadata.
+    SizeMismatch {
+        path: LocatorPath,
+        expected: u64,
+        found: u64,
+    },
+    /// The content of an object doesn't match the hash in the metadata.
+    HashMismatch(LocatorPath),
+}
+
+/// Returns the hex encoded hash of the given bytes.
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    hex::encode(calculate_hash(bytes))
+}
+
+/// Returns the size and the hex encoded hash of the file at the given path, the same as [`hash_bytes`] of its content.
+/// The file is read in chunks rather than loaded in memory.
+pub fn hash_file(path: &Path) -> Result<(u64, String), CoordinatorError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Blake2b512::new();
+    let mut buffer = vec![0; HASH_CHUNK_SIZE];
+    let mut size = 0;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// A mutation of the metadata store, appended to its journal.
+#[derive(Debug, Serialize, Deserialize)]
+enum JournalEntry {
+    Insert(LocatorPath, ObjectMetadata),
+    Remove(LocatorPath),
+}
+
+/// Transactional store of the metadata (hashes, sizes and status) of the objects in storage.
+///
+/// Every mutation is appended to a journal and synced, and replayed on top of the last snapshot of the store when it is
+/// loaded. A mutation torn by a crash is the last line of the journal, and is dropped. Every [`COMPACTION_THRESHOLD`]
+/// mutations, the store is compacted: a new snapshot is written to a temporary file and renamed over the previous one,
+/// and the journal is cleared.
+#[derive(Debug)]
+pub struct MetadataStore {
+    path: PathBuf,
+    entries: BTreeMap<LocatorPath, ObjectMetadata>,
+    /// The number of mutations in the journal.
+    journal_len: usize,
+}
+
+impl MetadataStore {
+    /// Loads the metadata store at the given path, or creates an empty one if it does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CoordinatorError> {
+        let path = path.as_ref().to_path_buf();
+        trace!("Loading metadata store from {:?}", path);
+
+        let entries = match path.exists() {
+            true => serde_json::from_slice(&fs::read(&path)?)?,
+            false => BTreeMap::new(),
+        };
+        let mut store = Self {
+            path,
+            entries,
+            journal_len: 0,
+        };
+
+        let journal = store.journal_path();
+        if journal.exists() {
+            let content = fs::read_to_string(&journal)?;
+            let lines: Vec<&str> = content.lines().collect();
+            for (i, line) in lines.iter().enumerate() {
+                match serde_json::from_str(line) {
+                    Ok(entry) => {
+                        store.apply(entry);
+                        store.journal_len += 1;
+                    }
+                    // Compacted right away, not to append the next mutations to the torn one
+                    Err(e) if i + 1 == lines.len() => {
+                        warn!("Dropping the torn last mutation of the metadata: {}", e);
+                        store.persist()?;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Returns the metadata of the object at the given path, if tracked.
+    pub fn get(&self, path: &LocatorPath) -> Option<&ObjectMetadata> {
+        self.entries.get(path)
+    }
+
+    /// Returns an iterator over the tracked objects.
+    pub fn iter(&self) -> impl Iterator<Item = (&LocatorPath, &ObjectMetadata)> {
+        self.entries.iter()
+    }
+
+    /// Tracks a newly allocated object of the given size.
+    pub fn reserve(&mut self, path: LocatorPath, size: u64) -> Result<(), CoordinatorError> {
+        self.insert(
+            path,
+            ObjectMetadata {
+                size,
+                hash: None,
+                status: ObjectStatus::Reserved,
+            },
+        )
+    }
+
+    /// Records the intent to write the given content to the object at the given path.
+    pub fn begin_write(&mut self, path: LocatorPath, content: &[u8]) -> Result<(), CoordinatorError> {
+        let hash = hash_bytes(content);
+        let previous_hash = self.entries.get(&path).and_then(|m| m.hash.clone());
+
+        self.insert(
+            path,
+            ObjectMetadata {
+                size: content.len() as u64,
+                hash: previous_hash,
+                status: ObjectStatus::Writing { hash },
+            },
+        )
+    }
+
+    /// Marks the object at the given path as completely written with the given content.
+    pub fn commit(&mut self, path: LocatorPath, content: &[u8]) -> Result<(), CoordinatorError> {
+        self.commit_hashed(path, content.len() as u64, hash_bytes(content))
+    }
+
+    /// Marks the object at the given path as completely written with a content of the given size and hash, e.g. as
+    /// returned by [`hash_file`].
+    pub fn commit_hashed(&mut self, path: LocatorPath, size: u64, hash: String) -> Result<(), CoordinatorError> {
+        self.insert(
+            path,
+            ObjectMetadata {
+                size,
+                hash: Some(hash),
+                status: ObjectStatus::Committed,
+            },
+        )
+    }
+
+    /// Marks the pending write of the object at the given path as completed.
+    pub fn finish_write(&mut self, path: &LocatorPath) -> Result<(), CoordinatorError> {
+        let metadata = self.entries.get(path).ok_or(CoordinatorError::StorageLocatorMissing)?;
+
+        if let ObjectStatus::Writing { hash } = &metadata.status {
+            let metadata = ObjectMetadata {
+                size: metadata.size,
+                hash: Some(hash.clone()),
+                status: ObjectStatus::Committed,
+            };
+            self.insert(path.clone(), metadata)?;
+        }
+
+        Ok(())
+    }
+
+    /// Stops tracking the object at the given path.
+    pub fn remove(&mut self, path: &LocatorPath) -> Result<(), CoordinatorError> {
+        if self.entries.contains_key(path) {
+            self.record(JournalEntry::Remove(path.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the journal of the store.
+    fn journal_path(&self) -> PathBuf {
+        self.path.with_file_name(METADATA_JOURNAL_FILE)
+    }
+
+    fn insert(&mut self, path: LocatorPath, metadata: ObjectMetadata) -> Result<(), CoordinatorError> {
+        self.record(JournalEntry::Insert(path, metadata))
+    }
+
+    /// Applies the given mutation to the entries of the store.
+    fn apply(&mut self, entry: JournalEntry) {
+        match entry {
+            JournalEntry::Insert(path, metadata) => {
+                self.entries.insert(path, metadata);
+            }
+            JournalEntry::Remove(path) => {
+                self.entries.remove(&path);
+            }
+        }
+    }
+
+    /// Appends the given mutation to the journal and applies it, or compacts the store once the journal is full.
+    fn record(&mut self, entry: JournalEntry) -> Result<(), CoordinatorError> {
+        let line = serde_json::to_string(&entry)?;
+        self.apply(entry);
+        if self.journal_len >= COMPACTION_THRESHOLD {
+            return self.persist();
+        }
+
+        let mut journal = OpenOptions::new().create(true).append(true).open(self.journal_path())?;
+        journal.write_all(format!("{}\n", line).as_bytes())?;
+        journal.file().sync_data()?;
+        self.journal_len += 1;
+
+        Ok(())
+    }
+
+    /// Atomically writes a snapshot of the store to disk, and clears the journal it includes.
+    fn persist(&mut self) -> Result<(), CoordinatorError> {
+        let tmp_path = self.path.with_extension("json.tmp");
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(&self.entries)?)?;
+        file.file().sync_all()?;
+
+        fs::rename(&tmp_path, &self.path).map_err(|e| {
+            error!("Failed to persist the metadata store: {}", e);
+            CoordinatorError::StorageUpdateFailed
+        })?;
+
+        // Replaying the journal over the new snapshot is harmless if interrupted before it is cleared
+        let journal = self.journal_path();
+        if journal.exists() {
+            fs::remove_file(&journal)?;
+        }
+        self.journal_len = 0;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_store_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join(METADATA_FILE);
+        let locator_path = LocatorPath::from("./transcript/test/round_0/state.json");
+
+        let mut store = MetadataStore::load(&store_path).unwrap();
+        store.reserve(locator_path.clone(), 4).unwrap();
+        assert_eq!(store.get(&locator_path).unwrap().status, ObjectStatus::Reserved);
+
+        store.begin_write(locator_path.clone(), b"test").unwrap();
+        assert_eq!(
+            store.get(&locator_path).unwrap().status,
+            ObjectStatus::Writing {
+                hash: hash_bytes(b"test")
+            }
+        );
+        store.finish_write(&locator_path).unwrap();
+
+        let store = MetadataStore::load(&store_path).unwrap();
+        let metadata = store.get(&locator_path).unwrap();
+        assert_eq!(metadata.status, ObjectStatus::Committed);
+        assert_eq!(metadata.size, 4);
+        assert_eq!(metadata.hash, Some(hash_bytes(b"test")));
+
+        let mut store = store;
+        store.remove(&locator_path).unwrap();
+        assert!(MetadataStore::load(&store_path).unwrap().get(&locator_path).is_none());
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contribution");
+        let content: Vec<u8> = (0..3 * HASH_CHUNK_SIZE + 7).map(|i| i as u8).collect();
+        fs::write(&path, &content).unwrap();
+
+        assert_eq!(hash_file(&path).unwrap(), (content.len() as u64, hash_bytes(&content)));
+    }
+
+    #[test]
+    fn test_metadata_store_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_path = dir.path().join(METADATA_FILE);
+        let journal_path = dir.path().join(METADATA_JOURNAL_FILE);
+        let locator_path = LocatorPath::from("./transcript/test/round_0/state.json");
+
+        // The mutations are journaled, not written to the snapshot
+        let mut store = MetadataStore::load(&store_path).unwrap();
+        store.commit(locator_path.clone(), b"test").unwrap();
+        assert!(!store_path.exists());
+        assert_eq!(
+            MetadataStore::load(&store_path).unwrap().get(&locator_path).unwrap().hash,
+            Some(hash_bytes(b"test"))
+        );
+
+        // A mutation torn by a crash is dropped
+        let mut journal = OpenOptions::new().append(true).open(&journal_path).unwrap();
+        journal.write_all(b"{\"Remove\":").unwrap();
+        let mut store = MetadataStore::load(&store_path).unwrap();
+        assert!(store.get(&locator_path).is_some());
+        assert!(!journal_path.exists());
+        store.remove(&locator_path).unwrap();
+        assert!(MetadataStore::load(&store_path).unwrap().get(&locator_path).is_none());
+
+        // The journal is compacted once full
+        for _ in store.journal_len..COMPACTION_THRESHOLD {
+            store.reserve(locator_path.clone(), 4).unwrap();
+        }
+        assert!(journal_path.exists());
+        store.reserve(locator_path.clone(), 4).unwrap();
+        assert!(!journal_path.exists());
+        let store = MetadataStore::load(&store_path).unwrap();
+        assert_eq!(store.get(&locator_path).unwrap().status, ObjectStatus::Reserved);
+    }
+}
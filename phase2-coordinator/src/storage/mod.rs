@@ -1,5 +1,14 @@
 pub mod disk;
 pub use disk::*;
 
+pub mod metadata;
+pub use metadata::*;
+
+pub mod portable;
+pub use portable::*;
+
 pub mod storage;
 pub use storage::*;
+
+pub mod transcript;
+pub use transcript::*;
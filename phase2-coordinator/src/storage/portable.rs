@@ -0,0 +1,172 @@
+//! Portable encoding of the locators to paths, valid on the filesystems of Linux, macOS and Windows, so that the
+//! storage of a coordinator can be copied to, and verified offline on, any of the major desktop platforms.
+//!
+//! The names of the files and directories are made only of lowercase ASCII letters, digits, `_`, `-` and `.`, so that
+//! two distinct names never collide on the case-insensitive filesystems of macOS and Windows, and never use the names
+//! reserved by Windows. The paths are joined with `/`, accepted as a separator on all platforms, and fit in the
+//! legacy path length limit of Windows. Both `/` and `\` are accepted as separators when parsing a path.
+
+use crate::{storage::LocatorPath, CoordinatorError};
+
+use std::path::{Path, MAIN_SEPARATOR};
+use tracing::error;
+
+/// Maximum length of a path, the legacy `MAX_PATH` limit of Windows, which also holds the terminating null character.
+pub const MAX_PATH_LENGTH: usize = 259;
+
+/// Maximum length of the name of a file or directory on the filesystems of all the platforms.
+pub const MAX_NAME_LENGTH: usize = 255;
+
+/// The device names reserved by Windows, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9", "lpt1", "lpt2",
+    "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Returns `true` if the name of a file or directory is valid and unambiguous on all the platforms.
+pub fn is_portable_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default();
+
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && name != "."
+        && name != ".."
+        && !name.ends_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.'))
+        && !RESERVED_NAMES.contains(&stem)
+}
+
+/// Returns the path with `/` as the only separator, and without its leading `./`.
+pub fn normalize_separators(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut path = path.as_str();
+    while let Some(stripped) = path.strip_prefix("./") {
+        path = stripped;
+    }
+
+    path.to_owned()
+}
+
+/// Converts a path read from the filesystem to a locator path, separated by `/` like the paths of the locators.
+pub fn to_locator_path(path: &Path) -> Result<LocatorPath, CoordinatorError> {
+    let path = path.to_str().ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+
+    Ok(LocatorPath::from(path.replace(MAIN_SEPARATOR, "/")))
+}
+
+/// Returns the given path relative to the base directory, with `/` as the only separator, or [`None`] if the path is
+/// not in the base directory.
+pub fn relative_path(path: &str, base: &str) -> Option<String> {
+    let path = normalize_separators(path);
+    let base = normalize_separators(base);
+    let base = base.trim_end_matches('/');
+    if base.is_empty() || base == "." {
+        return Some(path);
+    }
+
+    path.strip_prefix(base)?.strip_prefix('/').map(str::to_owned)
+}
+
+/// Returns the key of the given path relative to the base directory, with `/` as the only separator, and folded to
+/// lowercase like the case-insensitive filesystems do. Returns [`None`] if the path is not in the base directory.
+pub fn relative_key(path: &str, base: &str) -> Option<String> {
+    relative_path(path, base).map(|key| key.to_ascii_lowercase())
+}
+
+/// Checks that the path of an object, in the given base directory, is valid on all the platforms. The base directory
+/// is set by the operator and only counts in the length of the path.
+pub fn check_path(base: &str, path: &str) -> Result<LocatorPath, CoordinatorError> {
+    let key = relative_path(path, base).ok_or(CoordinatorError::StorageLocatorFormatIncorrect)?;
+
+    if let Some(name) = key.split('/').find(|name| !is_portable_name(name)) {
+        error!("{} is not a portable name", name);
+        return Err(CoordinatorError::StoragePathNotPortable(path.to_owned()));
+    }
+    if path.len() > MAX_PATH_LENGTH {
+        error!("{} exceeds the length limit of {} characters", path, MAX_PATH_LENGTH);
+        return Err(CoordinatorError::StoragePathNotPortable(path.to_owned()));
+    }
+
+    Ok(LocatorPath::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_name() {
+        assert!(is_portable_name("contribution_0.verified.signature"));
+        assert!(is_portable_name("namada_contributor_info_round_1.json"));
+
+        // Uppercase letters would collide with their lowercase counterparts on case-insensitive filesystems
+        assert!(!is_portable_name("Contribution_0.verified"));
+        // Characters invalid on Windows, or separators
+        for name in ["a:b", "a*b", "a?b", "a<b", "a|b", "a\"b", "a\\b", "a/b", "a b"] {
+            assert!(!is_portable_name(name), "{}", name);
+        }
+        // Reserved names, with or without extension, and names stripped of their trailing dot by Windows
+        assert!(!is_portable_name("con"));
+        assert!(!is_portable_name("nul.json"));
+        assert!(!is_portable_name("com1.verified"));
+        assert!(is_portable_name("console.json"));
+        assert!(!is_portable_name("round_1."));
+        assert!(!is_portable_name(".."));
+        assert!(!is_portable_name(""));
+        assert!(!is_portable_name(&"a".repeat(MAX_NAME_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_windows_paths() {
+        assert_eq!(normalize_separators(".\\transcript\\test"), "transcript/test");
+        assert_eq!(
+            relative_key(
+                ".\\transcript\\test\\round_1\\chunk_0\\contribution_0.verified",
+                "./transcript/test"
+            ),
+            Some(String::from("round_1/chunk_0/contribution_0.verified"))
+        );
+        // Mixed separators, as produced by joining a path read from the env with a key
+        assert_eq!(
+            relative_key("./transcript/test\\round_1\\state.json", ".\\transcript\\test\\"),
+            Some(String::from("round_1/state.json"))
+        );
+        // The case is folded like on case-insensitive filesystems
+        assert_eq!(
+            relative_key(
+                "C:\\Ceremony\\Transcript\\ROUND_1\\State.json",
+                "C:\\Ceremony\\Transcript"
+            ),
+            Some(String::from("round_1/state.json"))
+        );
+        assert_eq!(
+            relative_key("./transcript/other/round_1/state.json", "./transcript/test"),
+            None
+        );
+        assert_eq!(
+            relative_key("./transcript/testing/round_1/state.json", "./transcript/test"),
+            None
+        );
+        assert_eq!(
+            relative_key(".\\coordinator.json", "."),
+            Some(String::from("coordinator.json"))
+        );
+    }
+
+    #[test]
+    fn test_check_path() {
+        assert_eq!(
+            check_path("C:\\Ceremony", "C:\\Ceremony/round_1/chunk_0/contribution_0.verified").unwrap(),
+            LocatorPath::from("C:\\Ceremony/round_1/chunk_0/contribution_0.verified")
+        );
+        assert!(check_path("./transcript/test", "./transcript/test/round_1/CON").is_err());
+        assert!(check_path("./transcript/test", "./transcript/other/round_1/state.json").is_err());
+
+        // The base directory only counts in the length of the path
+        let base = format!("./{}", "Base".repeat(55));
+        assert!(check_path(&base, &format!("{}/round_1/state.json", base)).is_ok());
+        assert!(check_path(&base, &format!("{}/round_1/chunk_0/contribution_0.verified", base)).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    conversion::ParameterFormat,
     environment::Environment,
     objects::{ContributionFileSignature, ContributionInfo, Round, TrimmedContributionInfo},
     CoordinatorError, CoordinatorState,
@@ -16,6 +17,7 @@ use std::{
 use super::Disk;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContributionLocator {
     round_height: u64,
     chunk_id: u64,
@@ -24,14 +26,14 @@ pub struct ContributionLocator {
 }
 
 // Parameters generated from `masp-mpc` crate have size 84_720_180, to this add the needed 64 bytes for the hash of the contribution that is placed at the head of the contribution file.
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(debug_assertions, feature = "rehearsal")))]
 pub const ANOMA_BASE_FILE_SIZE: u64 = 84_720_244; // 145_449_460 prod: 84_720_244, testing: 2_332
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "rehearsal"))]
 pub const ANOMA_BASE_FILE_SIZE: u64 = 2_332; // prod: 84_720_244, testing: 2_332
                                              // With `masp-mpc` the contribution file grows by 1632 bytes on each new contribution
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(debug_assertions, feature = "rehearsal")))]
 pub const ANOMA_PER_ROUND_FILE_SIZE_INCREASE: u64 = 1_632; // prod: 1_632, testing: 544
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "rehearsal"))]
 pub const ANOMA_PER_ROUND_FILE_SIZE_INCREASE: u64 = 544; // prod: 1_632, testing: 544
 
 impl ContributionLocator {
@@ -62,6 +64,7 @@ impl ContributionLocator {
 }
 
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContributionSignatureLocator {
     round_height: u64,
     chunk_id: u64,
@@ -107,6 +110,13 @@ pub enum Locator {
     ContributionFileSignature(ContributionSignatureLocator),
     ContributionInfoFile { round_height: u64 },
     ContributionsInfoSummary,
+    /// The final parameters of a circuit, identified by its location in the contribution files, exported in a format.
+    ParametersFile {
+        round_height: u64,
+        chunk_id: u64,
+        position: u64,
+        format: ParameterFormat,
+    },
 }
 
 impl From<ContributionLocator> for Locator {
@@ -132,6 +142,7 @@ pub enum Object {
     ContributionFileSignature(ContributionFileSignature),
     ContributionInfoFile(ContributionInfo),
     ContributionsInfoSummary(Vec<TrimmedContributionInfo>),
+    ParametersFile(Vec<u8>),
 }
 
 impl Object {
@@ -153,6 +164,7 @@ impl Object {
             Object::ContributionsInfoSummary(summary) => {
                 serde_json::to_vec_pretty(summary).expect("Contribution info summary to bytes failed")
             }
+            Object::ParametersFile(parameters) => parameters.to_vec(),
         }
     }
 
@@ -167,6 +179,7 @@ impl Object {
             Object::ContributionFileSignature(_) => self.to_bytes().len() as u64,
             Object::ContributionInfoFile(_) => self.to_bytes().len() as u64,
             Object::ContributionsInfoSummary(_) => self.to_bytes().len() as u64,
+            Object::ParametersFile(parameters) => parameters.len() as u64,
         }
     }
 
@@ -0,0 +1,582 @@
+//! Manifests and tar encoding of the transcript of the ceremony, downloadable by round range, comparison of the
+//! transcripts of two coordinators, and detection of the contributions reused across two ceremonies.
+
+use crate::{
+    conversion::ParameterFormat,
+    objects::{
+        CircuitId, ComputationProofRecord, GenesisSeed, ManualInjection, SeedCommitment, SeedReveal,
+        TrimmedContributionInfo, VerificationQuorum, VerifierAttestation,
+    },
+};
+
+use blake2::{Blake2b512, Digest};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io,
+};
+
+/// Size of the blocks of a tar archive.
+pub const TAR_BLOCK_SIZE: usize = 512;
+
+/// Largest file size which can be encoded in the 11 octal digits of a ustar header.
+const TAR_MAX_FILE_SIZE: u64 = 0o77777777777;
+
+/// A file of the transcript of a round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptFile {
+    /// Path of the file, relative to the storage base directory.
+    pub name: String,
+    /// The size in bytes of the file.
+    pub size: u64,
+    /// The hex encoded hash of the file, if committed in the metadata store.
+    pub hash: Option<String>,
+}
+
+/// The list of the files of a round included in the transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundManifest {
+    pub round_height: u64,
+    pub files: Vec<TranscriptFile>,
+    /// The signed votes of the verifiers on the contributions of the round, when running with a verification quorum.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verifications: Vec<VerificationQuorum>,
+    /// The contributions of the round injected manually, with the approvals of the operators.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injections: Vec<ManualInjection>,
+    /// The signed attestations of the verifiers on the contributions of the round: who verified them and with which
+    /// software build.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attestations: Vec<VerifierAttestation>,
+    /// The final parameters of the ceremony exported in the round, when the ceremony is over.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<ParametersFile>,
+    /// Whether the round belongs to a rehearsal of the ceremony, whose parameters are not for production.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rehearsal: bool,
+    /// The commit-reveal of the seed of the genesis challenge by the operators, in the manifest of round 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_seed: Option<GenesisSeed>,
+    /// The proofs of computation time sent by the contributors of the round with their contributions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub computation_proofs: Vec<ComputationProofRecord>,
+}
+
+/// The final parameters of a circuit exported in a format, with the checksum of the file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParametersFile {
+    pub circuit: CircuitId,
+    pub format: ParameterFormat,
+    pub file: TranscriptFile,
+}
+
+impl RoundManifest {
+    /// Returns the path of the manifest in the transcript archive.
+    pub fn archive_name(&self) -> String {
+        format!("round_{}/manifest.json", self.round_height)
+    }
+
+    /// Returns the challenge, response and round files of the round, which are the same on every coordinator running
+    /// the ceremony. The states of the rounds and the signatures depend on the coordinator and are left out.
+    pub fn contribution_files(&self) -> impl Iterator<Item = &TranscriptFile> {
+        self.files
+            .iter()
+            .filter(|file| file.name.ends_with(".verified") || file.name.ends_with(".unverified"))
+    }
+
+    /// Returns the head of the hash chain of the transcript up to this round, given the head of the previous round.
+    pub fn chain_hash(&self, previous: &[u8]) -> Vec<u8> {
+        let mut hasher = Blake2b512::new();
+        hasher.update(previous);
+        hasher.update(self.round_height.to_le_bytes());
+        for file in self.contribution_files() {
+            hasher.update(file.to_string().as_bytes());
+        }
+        for parameters in &self.parameters {
+            hasher.update(parameters.file.to_string().as_bytes());
+        }
+        for attestation in &self.attestations {
+            hasher.update(attestation.message().as_bytes());
+            hasher.update(attestation.signature.as_bytes());
+        }
+        if self.rehearsal {
+            hasher.update(b"rehearsal");
+        }
+        if let Some(genesis_seed) = &self.genesis_seed {
+            for commitment in &genesis_seed.commitments {
+                hasher.update(SeedCommitment::message(&commitment.commitment).as_bytes());
+                hasher.update(commitment.signature.as_bytes());
+            }
+            for reveal in &genesis_seed.reveals {
+                hasher.update(SeedReveal::message(&reveal.share).as_bytes());
+                hasher.update(reveal.signature.as_bytes());
+            }
+        }
+        for record in &self.computation_proofs {
+            hasher.update(record.contributor.as_bytes());
+            hasher.update(record.proof.challenge_hash.as_bytes());
+            hasher.update(record.proof.iterations_per_checkpoint.to_le_bytes());
+            for checkpoint in &record.proof.checkpoints {
+                hasher.update(checkpoint.hash.as_bytes());
+            }
+        }
+
+        hasher.finalize().to_vec()
+    }
+}
+
+impl fmt::Display for TranscriptFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} bytes, hash {})",
+            self.name,
+            self.size,
+            self.hash.as_deref().unwrap_or("uncommitted")
+        )
+    }
+}
+
+/// The first artifact on which the transcripts of two coordinators diverge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub round_height: u64,
+    /// The name of the diverging artifact.
+    pub artifact: String,
+    /// The artifact in the first transcript, if present.
+    pub left: Option<String>,
+    /// The artifact in the second transcript, if present.
+    pub right: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "round {} diverges on {}: {} != {}",
+            self.round_height,
+            self.artifact,
+            self.left.as_deref().unwrap_or("missing"),
+            self.right.as_deref().unwrap_or("missing")
+        )
+    }
+}
+
+/// Returns the heads of the hash chain of the given manifests, ordered by round height, in hex.
+pub fn hash_chain(manifests: &[RoundManifest]) -> Vec<(u64, String)> {
+    let mut head = vec![];
+    manifests
+        .iter()
+        .map(|manifest| {
+            head = manifest.chain_hash(&head);
+            (manifest.round_height, hex::encode(&head))
+        })
+        .collect()
+}
+
+/// Compares the transcripts of two coordinators, given the manifests of the same range of rounds ordered by round
+/// height, and returns the first diverging artifact, if any.
+pub fn compare_manifests(left: &[RoundManifest], right: &[RoundManifest]) -> Option<Divergence> {
+    let (left_chain, right_chain) = (hash_chain(left), hash_chain(right));
+
+    // The first diverging round is the first one with a different head of the hash chain
+    let round = left_chain
+        .iter()
+        .zip(right_chain.iter())
+        .position(|(left_head, right_head)| left_head != right_head);
+    let round = match round {
+        Some(round) => round,
+        None if left.len() == right.len() => return None,
+        None => {
+            let round = left.len().min(right.len());
+            let round_height = left.get(round).or_else(|| right.get(round)).unwrap().round_height;
+            return Some(Divergence {
+                round_height,
+                artifact: format!("round_{}", round_height),
+                left: left.get(round).map(|_| "present".to_string()),
+                right: right.get(round).map(|_| "present".to_string()),
+            });
+        }
+    };
+    let (left, right) = (&left[round], &right[round]);
+    if left.round_height != right.round_height {
+        return Some(Divergence {
+            round_height: left.round_height.min(right.round_height),
+            artifact: "round height".to_string(),
+            left: Some(left.round_height.to_string()),
+            right: Some(right.round_height.to_string()),
+        });
+    }
+
+    let artifacts = |manifest: &RoundManifest| {
+        let mut artifacts = manifest
+            .contribution_files()
+            .map(|file| (file.name.clone(), file.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        for parameters in &manifest.parameters {
+            artifacts.insert(parameters.file.name.clone(), parameters.file.to_string());
+        }
+        for attestation in &manifest.attestations {
+            let name = format!(
+                "attestation of chunk {} contribution {} by {}",
+                attestation.chunk_id, attestation.contribution_id, attestation.verifier
+            );
+            artifacts.insert(name, attestation.message());
+        }
+        artifacts
+    };
+    let round_height = left.round_height;
+    let (mut left_artifacts, mut right_artifacts) = (artifacts(left), artifacts(right));
+    let names: BTreeSet<String> = left_artifacts.keys().chain(right_artifacts.keys()).cloned().collect();
+
+    names.into_iter().find_map(|name| {
+        let (left, right) = (left_artifacts.remove(&name), right_artifacts.remove(&name));
+        match left == right {
+            true => None,
+            false => Some(Divergence {
+                round_height,
+                artifact: name,
+                left,
+                right,
+            }),
+        }
+    })
+}
+
+/// An artifact of a ceremony found again in another ceremony, which hints at a contribution copied from one to the
+/// other instead of computed from its own randomness.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Reuse {
+    /// A contribution of the first ceremony has the same hash as a contribution of the second one.
+    Contribution {
+        hash: String,
+        /// The contribution in the first ceremony.
+        left: String,
+        /// The contribution in the second ceremony.
+        right: String,
+    },
+    /// A contributor of the first ceremony also contributed to the second one.
+    PublicKey {
+        public_key: String,
+        left_round: u64,
+        right_round: u64,
+    },
+}
+
+impl fmt::Display for Reuse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reuse::Contribution { hash, left, right } => {
+                write!(f, "{} and {} have the same hash {}", left, right, hash)
+            }
+            Reuse::PublicKey {
+                public_key,
+                left_round,
+                right_round,
+            } => write!(
+                f,
+                "{} contributed to both ceremonies, in rounds {} and {}",
+                public_key, left_round, right_round
+            ),
+        }
+    }
+}
+
+/// Returns the responses of the contributors in the first transcript whose hash is committed in the second one as
+/// well, in the order of the first transcript. The challenges are left out, as two ceremonies may start from the same
+/// initial parameters, or one from the final parameters of the other.
+pub fn find_reused_contributions(left: &[RoundManifest], right: &[RoundManifest]) -> Vec<Reuse> {
+    let responses = |manifests: &[RoundManifest]| {
+        manifests
+            .iter()
+            .flat_map(|manifest| manifest.files.iter())
+            .filter(|file| file.name.ends_with(".unverified") && !file.name.ends_with("/contribution_0.unverified"))
+            .filter_map(|file| Some((file.hash.clone()?, file.name.clone())))
+            .collect::<Vec<_>>()
+    };
+    let right_responses: BTreeMap<String, String> = responses(right).into_iter().rev().collect();
+
+    responses(left)
+        .into_iter()
+        .filter_map(|(hash, left)| {
+            let right = right_responses.get(&hash)?.clone();
+            Some(Reuse::Contribution { hash, left, right })
+        })
+        .collect()
+}
+
+/// Returns the public keys of the contributors of the first ceremony who also contributed to the second one, and the
+/// contributions of the first ceremony whose hash, as signed by their contributor, is found in the second one.
+pub fn find_reused_contributors(left: &[TrimmedContributionInfo], right: &[TrimmedContributionInfo]) -> Vec<Reuse> {
+    let right_keys: BTreeMap<&str, u64> = right
+        .iter()
+        .rev()
+        .map(|info| (info.public_key(), info.ceremony_round()))
+        .collect();
+    let right_hashes: BTreeMap<&str, &TrimmedContributionInfo> = right
+        .iter()
+        .rev()
+        .filter(|info| !info.contribution_hash().is_empty())
+        .map(|info| (info.contribution_hash(), info))
+        .collect();
+    let describe = |info: &TrimmedContributionInfo| {
+        format!(
+            "contribution of {} in round {}",
+            info.public_key(),
+            info.ceremony_round()
+        )
+    };
+
+    let mut reuses = vec![];
+    for info in left {
+        if let Some(right_round) = right_keys.get(info.public_key()) {
+            reuses.push(Reuse::PublicKey {
+                public_key: info.public_key().to_string(),
+                left_round: info.ceremony_round(),
+                right_round: *right_round,
+            });
+        }
+        if let Some(right_info) = right_hashes.get(info.contribution_hash()) {
+            reuses.push(Reuse::Contribution {
+                hash: info.contribution_hash().to_string(),
+                left: describe(info),
+                right: describe(right_info),
+            });
+        }
+    }
+
+    reuses
+}
+
+/// Returns the ustar header of a regular file of the archive.
+pub fn tar_header(name: &str, size: u64) -> io::Result<[u8; TAR_BLOCK_SIZE]> {
+    if size > TAR_MAX_FILE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("file {} is too big for the archive", name),
+        ));
+    }
+
+    // Names longer than 100 bytes are split in the prefix field at a path separator
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name)
+    } else {
+        name.char_indices()
+            .filter(|(i, c)| *c == '/' && *i <= 155 && name.len() - i - 1 <= 100)
+            .map(|(i, _)| (&name[..i], &name[i + 1..]))
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("file name {} is too long for the archive", name),
+                )
+            })?
+    };
+
+    let mut header = [0u8; TAR_BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with the checksum field filled with spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|byte| *byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    Ok(header)
+}
+
+/// Returns the number of zero bytes following a file of the given size to fill its last block.
+pub fn tar_padding(size: u64) -> usize {
+    (TAR_BLOCK_SIZE - (size % TAR_BLOCK_SIZE as u64) as usize) % TAR_BLOCK_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::ContributionInfo;
+
+    #[test]
+    fn test_tar_header() {
+        let header = tar_header("round_1/chunk_0/contribution_1.verified", 1025).unwrap();
+        assert_eq!(&header[..39], b"round_1/chunk_0/contribution_1.verified");
+        assert_eq!(&header[124..136], b"00000002001\0");
+        assert_eq!(&header[257..263], b"ustar\0");
+
+        let mut unsigned = header;
+        unsigned[148..156].copy_from_slice(b"        ");
+        let checksum: u32 = unsigned.iter().map(|byte| *byte as u32).sum();
+        assert_eq!(&header[148..156], format!("{:06o}\0 ", checksum).as_bytes());
+
+        // Long names are split in the prefix
+        let long_name = format!("{}/{}", "a".repeat(120), "b".repeat(50));
+        let header = tar_header(&long_name, 0).unwrap();
+        assert_eq!(&header[..50], "b".repeat(50).as_bytes());
+        assert_eq!(&header[345..465], "a".repeat(120).as_bytes());
+        assert!(tar_header(&"a".repeat(101), 0).is_err());
+
+        assert_eq!(tar_padding(1025), 511);
+        assert_eq!(tar_padding(1024), 0);
+    }
+
+    fn manifest(round_height: u64, hashes: &[&str]) -> RoundManifest {
+        let mut files: Vec<TranscriptFile> = hashes
+            .iter()
+            .enumerate()
+            .map(|(id, hash)| TranscriptFile {
+                name: format!("round_{}/chunk_0/contribution_{}.verified", round_height, id),
+                size: 1024,
+                hash: Some(hash.to_string()),
+            })
+            .collect();
+        files.push(TranscriptFile {
+            name: format!("round_{}/state.json", round_height),
+            size: round_height,
+            hash: None,
+        });
+
+        RoundManifest {
+            round_height,
+            files,
+            verifications: Vec::new(),
+            injections: Vec::new(),
+            attestations: Vec::new(),
+            parameters: Vec::new(),
+            rehearsal: false,
+            genesis_seed: None,
+            computation_proofs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_manifests() {
+        let left = vec![manifest(0, &["a"]), manifest(1, &["b", "c"]), manifest(2, &["d"])];
+        assert_eq!(compare_manifests(&left, &left), None);
+
+        // The states of the rounds are specific to each coordinator
+        let mut right = left.clone();
+        right[1].files.last_mut().unwrap().size = 10;
+        assert_eq!(hash_chain(&left), hash_chain(&right));
+        assert_eq!(compare_manifests(&left, &right), None);
+
+        // The first diverging file is reported, even if the following rounds diverge as well
+        right[1].files[1].hash = Some("e".to_string());
+        right[2].files[0].hash = Some("f".to_string());
+        let divergence = compare_manifests(&left, &right).unwrap();
+        assert_eq!(divergence.round_height, 1);
+        assert_eq!(divergence.artifact, "round_1/chunk_0/contribution_1.verified");
+        assert!(divergence.right.unwrap().contains("hash e"));
+
+        // The heads of the hash chain diverge from the first diverging round
+        let (left_chain, right_chain) = (hash_chain(&left), hash_chain(&right));
+        assert_eq!(left_chain[0], right_chain[0]);
+        assert_ne!(left_chain[1], right_chain[1]);
+        assert_ne!(left_chain[2], right_chain[2]);
+
+        // A missing file or round is reported as such
+        right = left.clone();
+        right[2].files.remove(0);
+        let divergence = compare_manifests(&left, &right).unwrap();
+        assert_eq!(divergence.artifact, "round_2/chunk_0/contribution_0.verified");
+        assert_eq!(divergence.right, None);
+
+        let divergence = compare_manifests(&left, &left[..2]).unwrap();
+        assert_eq!(divergence.round_height, 2);
+        assert_eq!(divergence.right, None);
+
+        // The attestations of the verifiers are covered by the hash chain
+        right = left.clone();
+        right[1].attestations.push(VerifierAttestation {
+            round_height: 1,
+            chunk_id: 0,
+            contribution_id: 1,
+            verifier: "verifier".to_string(),
+            software_version: "phase2-coordinator/1.1.0".to_string(),
+            report_hash: "aa".to_string(),
+            signature: String::new(),
+        });
+        assert_ne!(hash_chain(&left)[1], hash_chain(&right)[1]);
+        let divergence = compare_manifests(&left, &right).unwrap();
+        assert_eq!(divergence.artifact, "attestation of chunk 0 contribution 1 by verifier");
+        assert_eq!(divergence.left, None);
+
+        // A rehearsal transcript never passes for a production one
+        right = left.clone();
+        right[0].rehearsal = true;
+        assert_ne!(hash_chain(&left)[0], hash_chain(&right)[0]);
+    }
+
+    #[test]
+    fn test_find_reuse() {
+        let response = |round_height: u64, contribution_id: u64, hash: &str| TranscriptFile {
+            name: format!(
+                "round_{}/chunk_0/contribution_{}.unverified",
+                round_height, contribution_id
+            ),
+            size: 1024,
+            hash: Some(hash.to_string()),
+        };
+        let mut left = vec![manifest(0, &["genesis"]), manifest(1, &["a"])];
+        let mut right = vec![manifest(0, &["genesis"]), manifest(1, &["b"]), manifest(2, &["c"])];
+        left[0].files.push(response(0, 1, "a"));
+        left[1].files.push(response(1, 1, "d"));
+        right[0].files.push(response(0, 1, "e"));
+        right[1].files.push(response(1, 1, "f"));
+        right[2].files.push(response(2, 1, "g"));
+        // The shared initial challenge is not a reuse
+        assert!(find_reused_contributions(&left, &right).is_empty());
+
+        right[2].files.last_mut().unwrap().hash = Some("d".to_string());
+        right[1].files.push(TranscriptFile {
+            hash: None,
+            ..response(1, 2, "")
+        });
+        assert_eq!(find_reused_contributions(&left, &right), vec![Reuse::Contribution {
+            hash: "d".to_string(),
+            left: "round_1/chunk_0/contribution_1.unverified".to_string(),
+            right: "round_2/chunk_0/contribution_1.unverified".to_string(),
+        }]);
+
+        let info = |public_key: &str, ceremony_round: u64, hash: &str| {
+            TrimmedContributionInfo::from(ContributionInfo {
+                public_key: public_key.to_string(),
+                ceremony_round,
+                contribution_file_hash: hash.to_string(),
+                ..Default::default()
+            })
+        };
+        let left = vec![info("alice", 1, "a"), info("bob", 2, "b"), info("carol", 3, "")];
+        let right = vec![
+            info("dave", 1, "c"),
+            info("bob", 4, "d"),
+            info("eve", 2, "a"),
+            info("frank", 3, ""),
+        ];
+        let reuses = find_reused_contributors(&left, &right);
+        assert_eq!(reuses, vec![
+            Reuse::Contribution {
+                hash: "a".to_string(),
+                left: "contribution of alice in round 1".to_string(),
+                right: "contribution of eve in round 2".to_string(),
+            },
+            Reuse::PublicKey {
+                public_key: "bob".to_string(),
+                left_round: 2,
+                right_round: 4,
+            },
+        ]);
+        assert_eq!(
+            reuses[1].to_string(),
+            "bob contributed to both ceremonies, in rounds 2 and 4"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Integration of the coordinator with systemd and other service managers.
+//!
+//! - Socket activation: if the process is started with a listening socket passed by systemd (`LISTEN_PID` and
+//!   `LISTEN_FDS` env variables), the connections accepted on it are forwarded to the REST server, which is bound to a
+//!   port of the loopback interface. Rocket can only serve the listeners it binds itself, so the forwarding hides the
+//!   address of the clients: with the IP ban enabled, the coordinator refuses to start unless `NAMADA_MPC_TRUSTED_PROXY`
+//!   declares that the socket is exposed behind a reverse proxy setting the `X-Real-IP` header.
+//! - Readiness notification: `READY=1` and `STOPPING=1` are sent to the `NOTIFY_SOCKET` of a `Type=notify` service.
+//! - PID file: the id of the process is written to the path in the `PID_FILE` env variable, and removed on exit.
+
+use crate::coordinator_state::IP_BAN;
+use lazy_static::lazy_static;
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    figment::Figment,
+    tokio::{
+        self, io,
+        net::{TcpListener, TcpStream},
+    },
+    Orbit,
+    Rocket,
+};
+use std::{
+    fs,
+    net::{Ipv4Addr, SocketAddr},
+    os::unix::{io::FromRawFd, net::UnixDatagram},
+    path::PathBuf,
+    sync::Mutex,
+};
+use tracing::{debug, error, info, warn};
+
+/// First file descriptor passed by systemd, see `sd_listen_fds(3)`.
+const LISTEN_FDS_START: i32 = 3;
+
+lazy_static! {
+    pub static ref PID_FILE: Option<PathBuf> = std::env::var("PID_FILE").ok().map(PathBuf::from);
+    /// Whether the coordinator is exposed behind a reverse proxy setting the `X-Real-IP` header.
+    pub static ref TRUSTED_PROXY: bool = match std::env::var("NAMADA_MPC_TRUSTED_PROXY") {
+        Ok(s) if s == "true" => true,
+        _ => false,
+    };
+}
+
+/// Returns the listening socket passed by systemd, if the process was socket activated. The env variables of the
+/// activation are cleared to prevent them from being inherited by child processes.
+pub fn inherited_listener() -> io::Result<Option<std::net::TcpListener>> {
+    let listen_pid = std::env::var("LISTEN_PID").ok().and_then(|pid| pid.parse::<u32>().ok());
+    let listen_fds = std::env::var("LISTEN_FDS").ok().and_then(|fds| fds.parse::<i32>().ok());
+
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(var);
+    }
+
+    match (listen_pid, listen_fds) {
+        (Some(pid), Some(fds)) if pid == std::process::id() && fds > 0 => {
+            if fds > 1 {
+                warn!(
+                    "Received {} sockets from the service manager, only the first one is used",
+                    fds
+                );
+            }
+
+            // Safety: systemd guarantees that the file descriptors starting from LISTEN_FDS_START are open and owned
+            // by this process
+            let listener = unsafe { std::net::TcpListener::from_raw_fd(LISTEN_FDS_START) };
+            listener.set_nonblocking(true)?;
+
+            Ok(Some(listener))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Checks that the address of the clients can still be told apart when their connections are forwarded from an
+/// inherited socket, which is the case only behind a trusted reverse proxy setting the `X-Real-IP` header.
+fn check_forwarding(ip_ban: bool, trusted_proxy: bool) -> io::Result<()> {
+    if ip_ban && !trusted_proxy {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the IP ban requires NAMADA_MPC_TRUSTED_PROXY with socket activation, since the forwarded connections \
+             all come from the loopback interface",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fairing forwarding the connections accepted on the socket passed by systemd to the REST server, once it is bound.
+pub struct SocketActivation {
+    listener: Mutex<Option<std::net::TcpListener>>,
+}
+
+impl SocketActivation {
+    /// Returns the fairing of the socket passed by systemd, if the process was socket activated. Fails if the address
+    /// of the clients is required to enforce the IP ban but would be hidden by the forwarding.
+    pub fn from_env() -> io::Result<Option<Self>> {
+        let listener = match inherited_listener()? {
+            Some(listener) => listener,
+            None => return Ok(None),
+        };
+        check_forwarding(*IP_BAN, *TRUSTED_PROXY)?;
+
+        Ok(Some(Self {
+            listener: Mutex::new(Some(listener)),
+        }))
+    }
+
+    /// Binds the REST server of the given configuration to a port of the loopback interface chosen when it launches,
+    /// so that no other process can take the port in the meantime.
+    pub fn figment(figment: Figment) -> Figment {
+        figment.merge(("address", Ipv4Addr::LOCALHOST)).merge(("port", 0))
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for SocketActivation {
+    fn info(&self) -> Info {
+        Info {
+            name: "Socket activation",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let listener = match self.listener.lock().ok().and_then(|mut listener| listener.take()) {
+            Some(listener) => listener,
+            None => return,
+        };
+
+        // The configuration holds the port bound by the server at launch
+        let address = SocketAddr::new(rocket.config().address, rocket.config().port);
+        info!("Forwarding connections from the inherited socket to {}", address);
+        tokio::spawn(async move {
+            if let Err(e) = forward_connections(listener, address).await {
+                error!("Forwarding from the inherited socket failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Forwards the connections accepted on the inherited listener to the REST server listening on `target`.
+async fn forward_connections(listener: std::net::TcpListener, target: SocketAddr) -> io::Result<()> {
+    let listener = TcpListener::from_std(listener)?;
+
+    loop {
+        let (mut inbound, peer) = listener.accept().await?;
+
+        tokio::spawn(async move {
+            match TcpStream::connect(target).await {
+                Ok(mut outbound) => {
+                    if let Err(e) = io::copy_bidirectional(&mut inbound, &mut outbound).await {
+                        debug!("Connection from {} closed with error: {}", peer, e);
+                    }
+                }
+                Err(e) => warn!("Couldn't forward connection from {} to the rest server: {}", peer, e),
+            }
+        });
+    }
+}
+
+/// Sends a state notification to the service manager. Does nothing if the process is not run as a `Type=notify`
+/// service.
+fn notify(state: &str) {
+    let socket_path = match std::env::var("NOTIFY_SOCKET") {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+
+    if socket_path.starts_with('@') {
+        warn!("Abstract notification socket {} is not supported", socket_path);
+        return;
+    }
+
+    if let Err(e) = UnixDatagram::unbound().and_then(|socket| socket.send_to(state.as_bytes(), &socket_path)) {
+        warn!("Couldn't notify {} to the service manager: {}", state, e);
+    }
+}
+
+/// Notifies the service manager that the state of the coordinator has been loaded and the server is starting.
+pub fn notify_ready() {
+    notify("READY=1");
+}
+
+/// Notifies the service manager that the coordinator is draining and saving its state before exiting.
+pub fn notify_stopping() {
+    notify("STOPPING=1");
+}
+
+/// File containing the id of the running process, removed when dropped.
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the id of the current process to the given path.
+    pub fn create(path: PathBuf) -> io::Result<Self> {
+        fs::write(&path, format!("{}\n", std::process::id()))?;
+
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Couldn't remove pid file {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.pid");
+
+        let pid_file = PidFile::create(path.clone()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), format!("{}\n", std::process::id()));
+
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_listener_of_other_process() {
+        std::env::set_var("LISTEN_PID", (std::process::id() + 1).to_string());
+        std::env::set_var("LISTEN_FDS", "1");
+
+        assert!(inherited_listener().unwrap().is_none());
+        assert!(std::env::var("LISTEN_FDS").is_err());
+    }
+
+    #[test]
+    fn test_forwarding_with_ip_ban() {
+        assert!(check_forwarding(false, false).is_ok());
+        assert!(check_forwarding(false, true).is_ok());
+        assert!(check_forwarding(true, true).is_ok());
+        assert_eq!(
+            check_forwarding(true, false).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
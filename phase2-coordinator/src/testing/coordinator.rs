@@ -103,19 +103,21 @@ pub(crate) fn test_logger() {
     });
 }
 
-/// Clears the transcript directory for testing purposes only.
+/// Clears the transcript directory and the storage shards for testing purposes only.
 pub fn clear_test_storage(environment: &Environment) {
-    let path = environment.local_base_directory();
-    if Path::new(path).exists() {
-        warn!("Coordinator is clearing {:?}", &path);
-        match fs::remove_dir_all(&path) {
-            Ok(_) => (),
-            Err(error) => error!(
-                "The testing framework tried to clear the test transcript and failed. {}",
-                error
-            ),
+    let shards = environment.storage_shards().iter().map(String::as_str);
+    for path in std::iter::once(environment.local_base_directory()).chain(shards) {
+        if Path::new(path).exists() {
+            warn!("Coordinator is clearing {:?}", &path);
+            match fs::remove_dir_all(&path) {
+                Ok(_) => (),
+                Err(error) => error!(
+                    "The testing framework tried to clear the test transcript and failed. {}",
+                    error
+                ),
+            }
+            warn!("Coordinator cleared {:?}", &path);
         }
-        warn!("Coordinator cleared {:?}", &path);
     }
 }
 
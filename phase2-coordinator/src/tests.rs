@@ -1,13 +1,23 @@
 use crate::{
-    authentication::Dummy,
+    authentication::{Dummy, Signature},
     commands::{Seed, SigningKey, SEED_LENGTH},
+    conversion::ParameterFormat,
     environment::{Environment, Parameters, Settings, Testing},
-    objects::Task,
-    storage::{Disk, StorageLocator},
+    events::CeremonyEvent,
+    objects::{
+        chain_start, iterate_hash, seed_commitment, ComputationCheckpoint, ComputationProof, GenesisSeedStatus,
+        HumanVerification, IdentityClaim, InjectionStatus, LifecyclePhase, ManualContribution, QuorumStatus,
+        ReverificationTask, Task, VerificationVote, VerifiedIdentity, GENESIS_SEED_SIZE, MIN_CHECKPOINT_ITERATIONS,
+        SOFTWARE_VERSION,
+    },
+    storage::{
+        hash_chain, ContributionLocator, ContributionSignatureLocator, Disk, Locator, StorageLocator, StorageObject,
+    },
     testing::prelude::*,
-    Coordinator, CoordinatorError, MockTimeSource, Participant, Round,
+    ContributionFileSignature, Coordinator, CoordinatorError, MockTimeSource, Participant, Round,
 };
 use phase2::{helpers::CurveKind, ContributionMode, ProvingSystem};
+use setup_utils::calculate_hash;
 use time::OffsetDateTime;
 
 use fs_err as fs;
@@ -17,7 +27,7 @@ use std::{
     collections::{HashSet, LinkedList},
     iter::FromIterator,
     net::{IpAddr, Ipv4Addr},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 fn create_contributor(id: &str) -> (Participant, SigningKey, Seed) {
@@ -2264,6 +2274,185 @@ fn participant_lock_timeout_drop_test() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test that a contributor holding a lock can resume its session,
+/// which keeps it alive, until [Environment::participant_lock_timeout].
+#[test]
+#[serial]
+fn resume_session_test() -> anyhow::Result<()> {
+    let start = OffsetDateTime::now_utc();
+    let time = Arc::new(MockTimeSource::new(start));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+
+    let testing_deployment: Testing = Testing::from(parameters)
+        .contributor_seen_timeout(time::Duration::minutes(5))
+        .participant_lock_timeout(time::Duration::minutes(20));
+
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, _, _) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    // A contributor without a lock has no session to resume.
+    assert!(coordinator.resume_session(&contributor1).is_err());
+
+    let (chunk_id, locked_locators) = coordinator.try_lock(&contributor1)?;
+
+    // The client crashes and the contributor resumes the session before the seen timeout.
+    time.advance(time::Duration::minutes(4));
+    let session = coordinator.resume_session(&contributor1)?;
+    assert_eq!(session.round_height, 1);
+    assert_eq!(session.chunk_id, chunk_id);
+    assert_eq!(session.locked_locators.next_contribution(), locked_locators.next_contribution());
+    assert_eq!(
+        session.locked_locators.next_contribution_file_signature(),
+        locked_locators.next_contribution_file_signature()
+    );
+    assert_eq!(session.deadline, start + time::Duration::minutes(20));
+    assert!(session.challenge_bundle.is_none());
+
+    // The resumption counts as a heartbeat.
+    time.advance(time::Duration::minutes(4));
+    coordinator.update()?;
+    assert!(coordinator.dropped_participants().is_empty());
+
+    // The lock still expires at the deadline.
+    time.advance(time::Duration::minutes(13));
+    coordinator.update()?;
+    assert_eq!(&contributor1, coordinator.dropped_participants().get(0).unwrap().id());
+    assert!(coordinator.resume_session(&contributor1).is_err());
+
+    Ok(())
+}
+
+/// Test that the coordinator reports the events of the ceremony
+/// to the event callback.
+#[test]
+#[serial]
+fn ceremony_events_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Environment::from(Testing::from(parameters)));
+
+    // Instantiate a coordinator collecting its events.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let collected = events.clone();
+    coordinator.set_event_callback(Arc::new(move |timestamp, event| {
+        collected.lock().unwrap().push((timestamp, event))
+    }));
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, _, _) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), String::from("test_token"), 10)?;
+
+    // Update the ceremony to round 1 and start the contribution.
+    time.advance(time::Duration::minutes(1));
+    coordinator.update()?;
+    let (chunk_id, _) = coordinator.try_lock(&contributor1)?;
+
+    let events = events.lock().unwrap();
+    assert_eq!(
+        events.iter().map(|(_, event)| event.clone()).collect::<Vec<_>>(),
+        vec![
+            CeremonyEvent::Joined {
+                participant: contributor1.clone(),
+            },
+            CeremonyEvent::RoundAdvanced { round_height: 1 },
+            CeremonyEvent::TurnStarted {
+                participant: contributor1,
+                round_height: 1,
+                chunk_id,
+            },
+        ]
+    );
+    assert!(events[0].0 < events[1].0);
+
+    Ok(())
+}
+
+/// Test that restoring a backup snapshot brings the state of
+/// the coordinator back to the time of the snapshot.
+#[test]
+#[serial]
+fn backup_restore_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Environment::from(Testing::from(parameters)));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment.clone(), Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, _, _) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), String::from("test_token"), 10)?;
+
+    // Snapshot the ceremony with the contributor in the queue.
+    let snapshot = coordinator.backup_snapshot()?;
+    assert_eq!(snapshot.round_height, 0);
+    assert_eq!(snapshot.rounds.len(), 1);
+    assert_eq!(snapshot.manifests.len(), 1);
+    assert_eq!(snapshot.created_at, time.time());
+
+    // Update the ceremony to round 1, moving the contributor into the round.
+    coordinator.update()?;
+    assert_eq!(coordinator.current_round_height()?, 1);
+    assert!(!coordinator.is_queue_contributor(&contributor1));
+    drop(coordinator);
+
+    // The files of round 0 are still in storage.
+    let mismatches = Coordinator::restore_backup(&environment, &snapshot)?;
+    assert!(mismatches.is_empty());
+
+    let coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time)?;
+    assert_eq!(coordinator.current_round_height()?, 0);
+    assert!(coordinator.is_queue_contributor(&contributor1));
+    assert_eq!(coordinator.get_round(0)?, snapshot.rounds[0]);
+
+    Ok(())
+}
+
 /// Test that a participant who stays in the queue for more
 /// than [Environment::queue_seen_timeout] is dropped from the
 /// queue by the coordinator.
@@ -2470,6 +2659,1249 @@ fn rollback_locked_chunk() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test that with a verification quorum a contribution is verified only
+/// once the external verifiers agree with the coordinator's verifier.
+#[test]
+#[serial]
+fn verification_quorum_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+
+    let (external_verifier, external_signing_key) = create_verifier("external");
+    let testing_deployment: Testing = Testing::from(parameters)
+        .verification_quorum(2)
+        .quorum_verifiers(&[external_verifier.clone()]);
+
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+    let round_height = coordinator.current_round_height()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    let task = fetch_task_for_verifier(&coordinator).unwrap();
+
+    // The vote of the coordinator's verifier alone is not enough.
+    coordinator.default_verify(&task)?;
+    assert!(coordinator.get_pending_verifications().contains_key(&task));
+    let votes = coordinator
+        .state()
+        .verification_quorum(round_height, &task)
+        .unwrap()
+        .votes
+        .clone();
+    assert_eq!(1, votes.len());
+
+    // Verifying again doesn't add a vote.
+    coordinator.default_verify(&task)?;
+    assert_eq!(
+        1,
+        coordinator
+            .state()
+            .verification_quorum(round_height, &task)
+            .unwrap()
+            .votes
+            .len()
+    );
+
+    let next_challenge_hash = votes[0].next_challenge_hash.clone();
+    let message = VerificationVote::message(round_height, &task, &next_challenge_hash);
+    assert!(matches!(
+        coordinator.add_verification_vote(
+            &external_verifier,
+            &task,
+            next_challenge_hash.clone(),
+            "invalid".to_string()
+        ),
+        Err(CoordinatorError::VerifierSignatureInvalid)
+    ));
+
+    let signature = Dummy.sign(&external_signing_key, &message)?;
+    let status =
+        coordinator.add_verification_vote(&external_verifier, &task, next_challenge_hash.clone(), signature)?;
+    assert_eq!(QuorumStatus::Agreed { next_challenge_hash }, status);
+    assert!(!coordinator.get_pending_verifications().contains_key(&task));
+    assert!(coordinator
+        .current_round()?
+        .chunk(task.chunk_id())?
+        .get_contribution(task.contribution_id())?
+        .is_verified());
+
+    Ok(())
+}
+
+/// Test that a contribution computed out-of-band is injected only once
+/// two distinct operators approved it, and is marked in the transcript.
+#[test]
+#[serial]
+fn manual_contribution_injection_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let operators = vec!["test-operator-1".to_string(), "test-operator-2".to_string()];
+    let testing_deployment: Testing = Testing::from(parameters).operators(&operators);
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+    let round_height = coordinator.current_round_height()?;
+
+    // Compute the contribution out-of-band, without submitting it.
+    let (chunk_id, locked_locators) = coordinator.try_lock(&contributor1)?;
+    let response_locator = locked_locators.next_contribution();
+    coordinator.run_computation(
+        round_height,
+        chunk_id,
+        response_locator.contribution_id(),
+        &contributor1,
+        &contributor_signing_key1,
+        &seed1,
+    )?;
+    let storage = coordinator.storage();
+    let contribution_file = storage
+        .reader(&Locator::ContributionFile(response_locator.clone()))?
+        .to_vec();
+    let contribution_file_signature: ContributionFileSignature = serde_json::from_slice(
+        &storage
+            .reader(&Locator::ContributionFileSignature(
+                locked_locators.next_contribution_file_signature(),
+            ))?
+            .to_vec(),
+    )?;
+
+    let contribution = ManualContribution {
+        participant: contributor1.clone(),
+        round_height,
+        contribution_hash: hex::encode(calculate_hash(&contribution_file)),
+        reason: "Computed on the audited machine".to_string(),
+    };
+    let signature = Dummy.sign("operator_key", &contribution.message()?)?;
+
+    // Only the operators can approve the injection, once each.
+    assert!(matches!(
+        coordinator.approve_manual_contribution("test-operator-3", contribution.clone(), signature.clone()),
+        Err(CoordinatorError::OperatorUnauthorized)
+    ));
+    let status = coordinator.approve_manual_contribution(&operators[0], contribution.clone(), signature.clone())?;
+    assert_eq!(InjectionStatus::Pending { approvals: 1, required: 2 }, status);
+    assert!(matches!(
+        coordinator.approve_manual_contribution(&operators[0], contribution.clone(), signature.clone()),
+        Err(CoordinatorError::OperatorAlreadyApproved)
+    ));
+
+    // The contribution can't be injected before the second approval.
+    assert!(matches!(
+        coordinator.inject_manual_contribution(
+            &contribution,
+            contribution_file.clone(),
+            contribution_file_signature.clone()
+        ),
+        Err(CoordinatorError::ManualContributionNotApproved)
+    ));
+
+    let status = coordinator.approve_manual_contribution(&operators[1], contribution.clone(), signature)?;
+    assert_eq!(InjectionStatus::Approved, status);
+
+    // A file other than the approved one is rejected.
+    assert!(matches!(
+        coordinator.inject_manual_contribution(
+            &contribution,
+            vec![0; contribution_file.len()],
+            contribution_file_signature.clone()
+        ),
+        Err(CoordinatorError::ContributionHashMismatch)
+    ));
+
+    let locator = coordinator.inject_manual_contribution(
+        &contribution,
+        contribution_file.clone(),
+        contribution_file_signature.clone(),
+    )?;
+    assert_eq!(response_locator, locator);
+    assert!(coordinator.is_finished_contributor(&contributor1));
+    assert!(matches!(
+        coordinator.inject_manual_contribution(&contribution, contribution_file, contribution_file_signature),
+        Err(CoordinatorError::ManualContributionAlreadyInjected)
+    ));
+
+    // The injection is published in the manifest of the round.
+    let injections = coordinator.round_manifest(round_height)?.injections;
+    assert_eq!(1, injections.len());
+    assert_eq!(contribution, injections[0].contribution);
+    assert_eq!(2, injections[0].approvals.len());
+    assert!(injections[0].injected_at.is_some());
+
+    Ok(())
+}
+
+/// Test that round 1 starts only once the operators committed to and then
+/// revealed their shares of the seed of the genesis challenge, which starts
+/// with the seed, and that the commit-reveal is in the transcript of round 0.
+#[test]
+#[serial]
+fn genesis_seed_ceremony_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let operators = vec!["test-operator-1".to_string(), "test-operator-2".to_string()];
+    let testing_deployment: Testing = Testing::from(parameters)
+        .operators(&operators)
+        .genesis_seed_ceremony(true);
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, _, _) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    coordinator.add_to_queue(
+        contributor1.clone(),
+        Some(contributor_1_ip),
+        String::from("test_token"),
+        10,
+    )?;
+
+    // Round 1 waits for the seed of the genesis challenge.
+    coordinator.update()?;
+    assert_eq!(0, coordinator.current_round_height()?);
+
+    let shares = [
+        b"share of the first operator".to_vec(),
+        b"share of the second operator".to_vec(),
+    ];
+    let commitments: Vec<String> = shares.iter().map(|share| seed_commitment(share)).collect();
+    let signature = Dummy.sign("operator_key", "")?;
+
+    // The shares are revealed once every operator committed to its share.
+    assert!(matches!(
+        coordinator.commit_genesis_seed("test-operator-3", commitments[0].clone(), signature.clone()),
+        Err(CoordinatorError::OperatorUnauthorized)
+    ));
+    assert!(matches!(
+        coordinator.commit_genesis_seed(&operators[0], "xyz".to_string(), signature.clone()),
+        Err(CoordinatorError::GenesisSeedInvalid)
+    ));
+    let status = coordinator.commit_genesis_seed(&operators[0], commitments[0].clone(), signature.clone())?;
+    assert_eq!(
+        GenesisSeedStatus::Committing {
+            commitments: 1,
+            required: 2
+        },
+        status
+    );
+    assert!(matches!(
+        coordinator.commit_genesis_seed(&operators[0], commitments[0].clone(), signature.clone()),
+        Err(CoordinatorError::GenesisSeedAlreadyCommitted)
+    ));
+    assert!(matches!(
+        coordinator.reveal_genesis_seed(&operators[0], hex::encode(&shares[0]), signature.clone()),
+        Err(CoordinatorError::GenesisSeedCommitmentsMissing)
+    ));
+    let status = coordinator.commit_genesis_seed(&operators[1], commitments[1].clone(), signature.clone())?;
+    assert_eq!(
+        GenesisSeedStatus::Revealing {
+            reveals: 0,
+            required: 2
+        },
+        status
+    );
+
+    // A share must match the commitment of its operator.
+    assert!(matches!(
+        coordinator.reveal_genesis_seed(&operators[0], hex::encode(&shares[1]), signature.clone()),
+        Err(CoordinatorError::GenesisSeedInvalid)
+    ));
+    let status = coordinator.reveal_genesis_seed(&operators[0], hex::encode(&shares[0]), signature.clone())?;
+    assert_eq!(
+        GenesisSeedStatus::Revealing {
+            reveals: 1,
+            required: 2
+        },
+        status
+    );
+    assert!(matches!(
+        coordinator.reveal_genesis_seed(&operators[0], hex::encode(&shares[0]), signature.clone()),
+        Err(CoordinatorError::GenesisSeedAlreadyRevealed)
+    ));
+    coordinator.update()?;
+    assert_eq!(0, coordinator.current_round_height()?);
+
+    let status = coordinator.reveal_genesis_seed(&operators[1], hex::encode(&shares[1]), signature.clone())?;
+    let (genesis_seed, _) = coordinator.genesis_seed();
+    let seed = genesis_seed.derive()?;
+    assert_eq!(
+        GenesisSeedStatus::Derived {
+            seed: hex::encode(&seed)
+        },
+        status
+    );
+
+    // The genesis challenge starts with the seed, and the commit-reveal is in the transcript of round 0.
+    let challenge = coordinator
+        .storage()
+        .reader(&Locator::ContributionFile(ContributionLocator::new(1, 0, 0, true)))?
+        .to_vec();
+    assert_eq!(seed, challenge[..GENESIS_SEED_SIZE].to_vec());
+    let manifest = coordinator.round_manifest(0)?;
+    assert_eq!(Some(genesis_seed), manifest.genesis_seed);
+
+    // Round 1 starts with the seeded challenge.
+    coordinator.update()?;
+    assert_eq!(1, coordinator.current_round_height()?);
+    assert!(matches!(
+        coordinator.commit_genesis_seed(&operators[0], commitments[0].clone(), signature),
+        Err(CoordinatorError::CeremonyAlreadyLaunched)
+    ));
+
+    Ok(())
+}
+
+/// Test that the proof of computation time of a contributor starts from the
+/// challenge it locked, is published in the manifest of the round, and
+/// appears in the statistics of the computation of the contributions.
+#[test]
+#[serial]
+fn computation_proof_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+    let round_height = coordinator.current_round_height()?;
+
+    // Only the contributors holding a lock have a challenge to start the chain from.
+    assert!(matches!(
+        coordinator.locked_challenge_hash(&contributor1, round_height),
+        Err(CoordinatorError::ParticipantUnauthorized)
+    ));
+    let (chunk_id, locked_locators) = coordinator.try_lock(&contributor1)?;
+    let challenge_hash = coordinator.locked_challenge_hash(&contributor1, round_height)?;
+    let challenge = coordinator
+        .storage()
+        .reader(&Locator::ContributionFile(locked_locators.current_contribution()))?
+        .to_vec();
+    assert_eq!(challenge_hash, hex::encode(calculate_hash(&challenge)));
+
+    let mut hash = chain_start(&hex::decode(&challenge_hash)?);
+    let proof = ComputationProof {
+        challenge_hash: challenge_hash.clone(),
+        iterations_per_checkpoint: MIN_CHECKPOINT_ITERATIONS,
+        checkpoints: (1..=4)
+            .map(|index| {
+                hash = iterate_hash(hash, MIN_CHECKPOINT_ITERATIONS);
+                ComputationCheckpoint {
+                    hash: hex::encode(hash),
+                    elapsed_ms: index * 1000,
+                }
+            })
+            .collect(),
+    };
+    assert_eq!(
+        proof.verify_sample(&challenge_hash, &mut rand::thread_rng()),
+        Ok(4 * MIN_CHECKPOINT_ITERATIONS)
+    );
+
+    // The proof is recorded for the current round only.
+    assert!(matches!(
+        coordinator.add_computation_proof(&contributor1, round_height + 1, proof.clone()),
+        Err(CoordinatorError::RoundHeightMismatch)
+    ));
+    coordinator.add_computation_proof(&contributor1, round_height, proof.clone())?;
+
+    // Contribute ten minutes later.
+    time.advance(time::Duration::minutes(10));
+    coordinator.run_computation(
+        round_height,
+        chunk_id,
+        locked_locators.next_contribution().contribution_id(),
+        &contributor1,
+        &contributor_signing_key1,
+        &seed1,
+    )?;
+    coordinator.try_contribute(&contributor1, chunk_id)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+
+    // The proof is published in the manifest of the round and covered by the hash chain.
+    let manifest = coordinator.round_manifest(round_height)?;
+    assert_eq!(1, manifest.computation_proofs.len());
+    assert_eq!(proof, manifest.computation_proofs[0].proof);
+    assert_eq!(contributor1.address(), manifest.computation_proofs[0].contributor);
+    let mut unproved = manifest.clone();
+    unproved.computation_proofs.clear();
+    assert_ne!(hash_chain(&[manifest]), hash_chain(&[unproved]));
+
+    let stats = coordinator.computation_stats();
+    assert_eq!(1, stats.contributions.len());
+    let contribution = &stats.contributions[0];
+    assert_eq!(round_height, contribution.round_height);
+    assert_eq!(600, contribution.seconds);
+    assert_eq!(Some(4 * MIN_CHECKPOINT_ITERATIONS), contribution.iterations);
+    assert_eq!(Some(4000), contribution.proof_elapsed_ms);
+    assert!(contribution.anomalies.is_empty());
+
+    Ok(())
+}
+
+/// Test that the ceremony stops accepting contributors once it reached its
+/// maximum number of rounds, and ends after the last contribution.
+#[test]
+#[serial]
+fn max_rounds_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters).max_rounds(1);
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+    assert_eq!(LifecyclePhase::Prelaunch, coordinator.lifecycle_phase()?);
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let (contributor2, _, _) = create_contributor("2");
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(
+        contributor1.clone(),
+        Some(IpAddr::V4("0.0.0.1".parse().unwrap())),
+        token.clone(),
+        10,
+    )?;
+    assert!(coordinator.announcements()?.is_empty());
+
+    // Update the ceremony to round 1, the last one.
+    coordinator.update()?;
+    assert!(coordinator.is_draining()?);
+    assert_eq!(LifecyclePhase::Draining, coordinator.lifecycle_phase()?);
+    assert_eq!(1, coordinator.announcements()?.len());
+    assert!(matches!(
+        coordinator.add_to_queue(contributor2, Some(IpAddr::V4("0.0.0.2".parse().unwrap())), token, 10),
+        Err(CoordinatorError::CeremonyIsOver)
+    ));
+
+    // The current contribution completes before the end of the ceremony.
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.update()?;
+    coordinator.verify_pending_contributions()?;
+    assert!(matches!(coordinator.update(), Err(CoordinatorError::CeremonyIsOver)));
+    assert_eq!(1, coordinator.current_round_height()?);
+    assert_eq!(LifecyclePhase::Over, coordinator.lifecycle_phase()?);
+
+    Ok(())
+}
+
+/// Test that the approaching end of the ceremony is announced during its
+/// final window, and that the ceremony ends after its maximum duration.
+#[test]
+#[serial]
+fn max_ceremony_duration_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters)
+        .max_ceremony_duration(time::Duration::hours(1))
+        .final_window(time::Duration::minutes(30));
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+    assert!(coordinator.ceremony_deadline().is_some());
+    assert!(coordinator.announcements()?.is_empty());
+
+    // The end of the ceremony is announced during the final window.
+    time.advance(time::Duration::minutes(40));
+    assert!(!coordinator.is_draining()?);
+    assert_eq!(1, coordinator.announcements()?.len());
+
+    // No contributor is accepted after the deadline, and the ceremony ends.
+    time.advance(time::Duration::minutes(30));
+    assert!(coordinator.is_draining()?);
+    let (contributor1, _, _) = create_contributor("1");
+    assert!(matches!(
+        coordinator.add_to_queue(
+            contributor1,
+            Some(IpAddr::V4("0.0.0.1".parse().unwrap())),
+            String::from("test_token"),
+            10
+        ),
+        Err(CoordinatorError::CeremonyIsOver)
+    ));
+    assert!(matches!(coordinator.update(), Err(CoordinatorError::CeremonyIsOver)));
+
+    Ok(())
+}
+
+/// Test that the phases of the genesis config apply their queue policies
+/// and their timeouts, and are announced, as the ceremony goes through
+/// them.
+#[test]
+#[serial]
+fn ceremony_phases_test() -> anyhow::Result<()> {
+    let now = OffsetDateTime::now_utc();
+    let time = Arc::new(MockTimeSource::new(now));
+
+    let (contributor1, _, _) = create_contributor("1");
+    let (contributor2, _, _) = create_contributor("2");
+    let (contributor3, _, _) = create_contributor("3");
+
+    // A week reserved to the first contributor, a public phase and a cleanup phase.
+    let genesis_dir = tempfile::tempdir()?;
+    let genesis_path = genesis_dir.path().join("ceremony.json");
+    let genesis = serde_json::json!({
+        "name": "Test ceremony",
+        "description": "A test",
+        "phases": [
+            {
+                "name": "vip",
+                "start": (now - time::Duration::minutes(1)).unix_timestamp(),
+                "queue_policy": "allowlist",
+                "allowlist": [contributor1.address()],
+            },
+            {
+                "name": "public",
+                "start": (now + time::Duration::hours(1)).unix_timestamp(),
+                "announcement": "The ceremony is open to everyone",
+                "timeouts": {"queue_seen": 60},
+            },
+            {
+                "name": "cleanup",
+                "start": (now + time::Duration::hours(2)).unix_timestamp(),
+                "queue_policy": "closed",
+            },
+        ],
+    });
+    fs::write(&genesis_path, genesis.to_string())?;
+    std::env::set_var("CEREMONY_METADATA_PATH", &genesis_path);
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters).final_window(time::Duration::minutes(30));
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone());
+    std::env::remove_var("CEREMONY_METADATA_PATH");
+    let mut coordinator = coordinator?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+    coordinator.update()?;
+    assert_eq!(Some("vip"), coordinator.state().ceremony_phase());
+    assert!(coordinator.announcements()?.is_empty());
+
+    // Only the contributors of the allowlist join the queue during the first phase.
+    assert!(matches!(
+        coordinator.add_to_queue(
+            contributor2.clone(),
+            Some(IpAddr::V4("0.0.0.2".parse().unwrap())),
+            String::from("test_token_2"),
+            10
+        ),
+        Err(CoordinatorError::ParticipantNotAdmittedInPhase(phase)) if phase == "vip"
+    ));
+    coordinator.add_to_queue(
+        contributor1.clone(),
+        Some(IpAddr::V4("0.0.0.1".parse().unwrap())),
+        String::from("test_token_1"),
+        10,
+    )?;
+    coordinator.remove_from_queue(&contributor1)?;
+
+    // The next phase is announced during the final window before it.
+    time.advance(time::Duration::minutes(40));
+    assert_eq!(1, coordinator.announcements()?.len());
+
+    // The public phase admits everyone, and drops the contributors from the queue after its own timeout.
+    time.advance(time::Duration::minutes(30));
+    coordinator.update()?;
+    assert_eq!(Some("public"), coordinator.state().ceremony_phase());
+    assert_eq!("The ceremony is open to everyone", coordinator.announcements()?[0].message);
+    coordinator.add_to_queue(
+        contributor2.clone(),
+        Some(IpAddr::V4("0.0.0.2".parse().unwrap())),
+        String::from("test_token_2"),
+        10,
+    )?;
+    time.advance(time::Duration::minutes(2));
+    coordinator.update()?;
+    assert!(!coordinator.is_queue_contributor(&contributor2));
+    assert!(coordinator.current_contributors().is_empty());
+
+    // Nobody joins the queue once it's closed.
+    time.advance(time::Duration::hours(1));
+    coordinator.update()?;
+    assert_eq!(Some("cleanup"), coordinator.state().ceremony_phase());
+    assert!(matches!(
+        coordinator.add_to_queue(
+            contributor3,
+            Some(IpAddr::V4("0.0.0.3".parse().unwrap())),
+            String::from("test_token_3"),
+            10
+        ),
+        Err(CoordinatorError::ParticipantNotAdmittedInPhase(phase)) if phase == "cleanup"
+    ));
+
+    Ok(())
+}
+
+/// Test that a verification exceeding the memory budget is aborted and
+/// resets the round without banning the contributor.
+#[test]
+#[serial]
+fn verification_memory_budget_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters).verification_memory_budget(1);
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    let task = fetch_task_for_verifier(&coordinator).unwrap();
+
+    let error = coordinator.default_verify(&task).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<CoordinatorError>(),
+        Some(CoordinatorError::VerificationMemoryBudgetExceeded { available: 1, .. })
+    ));
+
+    coordinator.verify_pending_contributions()?;
+    assert!(!coordinator.is_banned_participant(&contributor1));
+
+    Ok(())
+}
+
+/// Test that the interval between the updates of the coordinator backs off
+/// while idle and shortens on imminent timeouts and pending verifications.
+#[test]
+#[serial]
+fn adaptive_update_interval_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters)
+        .contributor_seen_timeout(time::Duration::minutes(5))
+        .update_interval(time::Duration::seconds(5), time::Duration::seconds(60));
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+    assert_eq!(coordinator.update_interval(), std::time::Duration::from_secs(5));
+
+    // Back off while idle
+    assert_eq!(coordinator.schedule_next_update(), std::time::Duration::from_secs(10));
+    assert_eq!(coordinator.schedule_next_update(), std::time::Duration::from_secs(20));
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    // Wake up in time for the contributor's seen timeout
+    time.update(|now| now + time::Duration::minutes(5) - time::Duration::seconds(15));
+    assert_eq!(coordinator.schedule_next_update(), std::time::Duration::from_secs(15));
+
+    // Update as often as allowed while the contribution is pending verification
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    assert_eq!(coordinator.schedule_next_update(), std::time::Duration::from_secs(5));
+    assert_eq!(coordinator.update_interval(), std::time::Duration::from_secs(5));
+
+    Ok(())
+}
+
+/// Test that the time-based behavior of the coordinator depends only on its
+/// source of time, and not on the system time.
+#[test]
+#[serial]
+fn mock_time_source_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let testing_deployment: Testing = Testing::from(parameters).contributor_seen_timeout(time::Duration::minutes(5));
+    let environment = initialize_test_environment(&Environment::from(testing_deployment));
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+    assert_eq!(coordinator.now_utc(), time.time());
+    assert_eq!(coordinator.current_cohort_index(), 0);
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    // The contributor is still in time just before the timeout.
+    time.advance(time::Duration::minutes(5) - time::Duration::seconds(1));
+    coordinator.update()?;
+    assert!(coordinator.is_current_contributor(&contributor1));
+
+    // The round finishes at the time of the last verification.
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    time.advance(time::Duration::minutes(1));
+    coordinator.verify_pending_contributions()?;
+    assert_eq!(coordinator.get_round(1)?.finished_at(), Some(time.time()));
+
+    // The next cohort starts after its duration, whatever the system time.
+    time.advance(time::Duration::days(1));
+    assert_eq!(coordinator.current_cohort_index(), 1);
+
+    Ok(())
+}
+
+/// Test that the final parameters are exported in all the formats and
+/// listed in the manifest of the round.
+#[test]
+#[serial]
+fn export_parameters_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+
+    let exported = coordinator.export_parameters(&ParameterFormat::ALL)?;
+    assert_eq!(
+        exported.len(),
+        coordinator.circuit_registry().circuits().len() * ParameterFormat::ALL.len()
+    );
+    assert!(exported
+        .iter()
+        .all(|parameters| parameters.file.size > 0 && parameters.file.hash.is_some()));
+
+    // Exporting again overwrites the previous files.
+    assert_eq!(coordinator.export_parameters(&[ParameterFormat::Raw])?, exported);
+
+    Ok(())
+}
+
+/// Test that the manifest of a round records who verified its contributions
+/// and with which software build, covered by the hash chain of the transcript.
+#[test]
+#[serial]
+fn verifier_attestation_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+    let verifier = environment.coordinator_verifiers()[0].clone();
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+
+    let manifest = coordinator.round_manifest(1)?;
+    assert_eq!(1, manifest.attestations.len());
+    let attestation = &manifest.attestations[0];
+    assert_eq!(attestation.task(), Task::new(0, 1));
+    assert_eq!(attestation.verifier, verifier.address());
+    assert_eq!(attestation.software_version, SOFTWARE_VERSION);
+    assert!(attestation.verify(&Dummy));
+
+    // The report is the file signature of the next challenge, the first one of the next round
+    let report = Locator::ContributionFileSignature(ContributionSignatureLocator::new(2, 0, 0, true));
+    let report_hash = hex::encode(calculate_hash(coordinator.storage().reader(&report)?.as_ref()));
+    assert_eq!(attestation.report_hash, report_hash);
+    assert!(coordinator.round_manifest(2)?.attestations.is_empty());
+
+    // The attestations are covered by the hash chain
+    let mut unattested = manifest.clone();
+    unattested.attestations.clear();
+    assert_ne!(hash_chain(&[manifest]), hash_chain(&[unattested]));
+
+    Ok(())
+}
+
+/// Re-verifies the contributions of a completed round without altering the progression of the ceremony, and reports a
+/// contribution tampered with after its verification.
+#[test]
+#[serial]
+fn reverification_test() -> anyhow::Result<()> {
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+    let verifier = environment.coordinator_verifiers()[0].clone();
+
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), String::from("test_token"), 10)?;
+    coordinator.update()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+    assert_eq!(2, coordinator.current_round_height()?);
+    let manifest = coordinator.round_manifest(1)?;
+
+    // Only the completed rounds are re-verified
+    assert!(matches!(coordinator.queue_reverification(2), Err(CoordinatorError::RoundNotComplete)));
+
+    let task = ReverificationTask::new(1, &Task::new(0, 1));
+    assert_eq!(vec![task], coordinator.queue_reverification(0)?);
+    assert!(coordinator.queue_reverification(1)?.is_empty());
+    assert_eq!(vec![task], coordinator.reverification_status().queue);
+
+    let report = coordinator.reverify_next()?.unwrap();
+    assert!(report.succeeded());
+    assert_eq!(report.task(), task);
+    assert_eq!(report.verifier, verifier.address());
+    assert!(report.verify(&Dummy));
+    let response = Locator::ContributionFile(ContributionLocator::new(1, 0, 1, false));
+    let response_hash = hex::encode(calculate_hash(coordinator.storage().reader(&response)?.as_ref()));
+    assert_eq!(report.response_hash, response_hash);
+    assert!(coordinator.reverify_next()?.is_none());
+
+    // The progression of the ceremony and the transcript are left untouched
+    assert_eq!(2, coordinator.current_round_height()?);
+    assert_eq!(manifest, coordinator.round_manifest(1)?);
+
+    // A contribution tampered with after its verification fails, and its report replaces the previous one
+    coordinator.storage().writer(&response)?.as_mut()[100] ^= 1;
+    coordinator.queue_reverification(1)?;
+    let report = coordinator.reverify_next()?.unwrap();
+    assert!(!report.succeeded());
+    assert_eq!(vec![report], coordinator.reverification_status().reports);
+    assert!(coordinator.reverification_status().queue.is_empty());
+
+    Ok(())
+}
+
+/// The outcome of the human verification of a contributor is kept in its participant info once assigned to a round.
+#[test]
+#[serial]
+fn human_verification_test() -> anyhow::Result<()> {
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new(environment, Arc::new(Dummy))?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor, _, _) = create_contributor("1");
+    let contributor_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+    let verification = HumanVerification {
+        provider: String::from("turnstile"),
+        hostname: Some(String::from("ceremony.namada.net")),
+        verified_at: OffsetDateTime::now_utc(),
+    };
+
+    coordinator.add_to_queue(contributor.clone(), Some(contributor_ip), token, 10)?;
+    coordinator.record_human_verification(contributor.clone(), verification.clone())?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    let (_, participant_info) = coordinator
+        .current_contributors()
+        .into_iter()
+        .find(|(participant, _)| *participant == contributor)
+        .unwrap();
+    assert_eq!(Some(&verification), participant_info.human_verification());
+
+    Ok(())
+}
+
+/// Test that the pre-launch snapshot is restored after a rehearsal of the launch, wiping the rounds of the rehearsal
+/// while keeping the registrations, and that the ceremony can then be launched again.
+#[test]
+#[serial]
+fn prelaunch_restore_test() -> anyhow::Result<()> {
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    let mut coordinator = Coordinator::new(environment, Arc::new(Dummy))?;
+    coordinator.initialize()?;
+
+    // No snapshot has been taken yet
+    assert!(matches!(
+        coordinator.restore_prelaunch(),
+        Err(CoordinatorError::PrelaunchSnapshotMissing)
+    ));
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), String::from("test_token"), 10)?;
+
+    let summary = coordinator.snapshot_prelaunch()?;
+    assert_eq!(summary.round_height, 0);
+    assert_eq!(summary.registrations, 1);
+
+    // Rehearse the launch
+    coordinator.update()?;
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+    assert_eq!(2, coordinator.current_round_height()?);
+    assert!(matches!(
+        coordinator.snapshot_prelaunch(),
+        Err(CoordinatorError::CeremonyAlreadyLaunched)
+    ));
+
+    let (contributor2, _, _) = create_contributor("2");
+    let verification = HumanVerification {
+        provider: String::from("turnstile"),
+        hostname: None,
+        verified_at: OffsetDateTime::now_utc(),
+    };
+    coordinator.record_human_verification(contributor2.clone(), verification.clone())?;
+
+    let restore = coordinator.restore_prelaunch()?;
+    assert_eq!(restore.round_height, 0);
+    assert!(restore.removed_rounds.contains(&2));
+    assert_eq!(restore.cleared_rounds, vec![1, 0]);
+    assert!(restore.mismatches.is_empty());
+
+    // The rounds of the rehearsal are gone, the initial challenge of round 1 and the registrations are kept
+    assert_eq!(0, coordinator.current_round_height()?);
+    assert!(coordinator.is_queue_contributor(&contributor1));
+    assert_eq!(Some(&verification), coordinator.state().human_verification(&contributor2));
+    let storage = coordinator.storage();
+    assert!(!storage.exists(&Locator::RoundState { round_height: 1 }));
+    assert!(!storage.exists(&Locator::ContributionFile(ContributionLocator::new(1, 0, 1, false))));
+    assert!(!storage.exists(&Locator::ContributionFile(ContributionLocator::new(2, 0, 0, true))));
+    assert!(storage.exists(&Locator::ContributionFile(ContributionLocator::new(1, 0, 0, true))));
+
+    // The ceremony is launched again from the pre-launch state
+    coordinator.update()?;
+    assert_eq!(1, coordinator.current_round_height()?);
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    coordinator.verify_pending_contributions()?;
+    coordinator.update()?;
+    assert_eq!(2, coordinator.current_round_height()?);
+
+    Ok(())
+}
+
+/// The identity claimed by a contributor is kept in its participant info once assigned to a round, while a contributor
+/// with a bare public key has none.
+#[test]
+#[serial]
+fn identity_test() -> anyhow::Result<()> {
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    // Instantiate a coordinator.
+    let mut coordinator = Coordinator::new(environment, Arc::new(Dummy))?;
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, _, _) = create_contributor("1");
+    let (contributor2, _, _) = create_contributor("2");
+    let identity = VerifiedIdentity {
+        claim: IdentityClaim::Did {
+            did: String::from("did:web:university.example:alice"),
+        },
+        resolver: String::from("did-web"),
+        issuer: None,
+        verified_at: OffsetDateTime::now_utc(),
+    };
+
+    coordinator.add_to_queue(
+        contributor1.clone(),
+        Some(IpAddr::V4("0.0.0.1".parse().unwrap())),
+        String::from("test_token"),
+        10,
+    )?;
+    coordinator.record_identity(contributor1.clone(), identity.clone())?;
+    coordinator.add_to_queue(
+        contributor2.clone(),
+        Some(IpAddr::V4("0.0.0.2".parse().unwrap())),
+        String::from("test_token_2"),
+        9,
+    )?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    let (_, participant_info) = coordinator
+        .current_contributors()
+        .into_iter()
+        .find(|(participant, _)| *participant == contributor1)
+        .unwrap();
+    assert_eq!(Some(&identity), participant_info.identity());
+    assert_eq!(Some(&identity), coordinator.state().identity(&contributor1));
+    assert!(coordinator.state().identity(&contributor2).is_none());
+
+    Ok(())
+}
+
+/// Test that a verification failure injected by the chaos mode resets
+/// the round without banning the contributor.
+#[test]
+#[serial]
+#[cfg(debug_assertions)]
+fn chaos_verification_failure_test() -> anyhow::Result<()> {
+    use crate::chaos::ChaosConfig;
+
+    let time = Arc::new(MockTimeSource::new(OffsetDateTime::now_utc()));
+
+    let parameters = Parameters::Custom(Settings::new(
+        ContributionMode::Chunked,
+        ProvingSystem::Groth16,
+        CurveKind::Bls12_377,
+        6,  /* power */
+        16, /* batch_size */
+        16, /* chunk_size */
+    ));
+    let environment = initialize_test_environment(&Testing::from(parameters).into());
+
+    // Instantiate a coordinator failing all the verifications.
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Dummy), time.clone())?;
+    coordinator.set_chaos_config(ChaosConfig {
+        verification_failure_rate: 1.0,
+        ..ChaosConfig::disabled()
+    });
+
+    // Initialize the ceremony to round 0.
+    coordinator.initialize()?;
+
+    let (contributor1, contributor_signing_key1, seed1) = create_contributor("1");
+    let contributor_1_ip = IpAddr::V4("0.0.0.1".parse().unwrap());
+    let token = String::from("test_token");
+
+    coordinator.add_to_queue(contributor1.clone(), Some(contributor_1_ip), token, 10)?;
+
+    // Update the ceremony to round 1.
+    coordinator.update()?;
+
+    coordinator.contribute(&contributor1, &contributor_signing_key1, &seed1)?;
+    let task = fetch_task_for_verifier(&coordinator).unwrap();
+
+    coordinator.verify_pending_contributions()?;
+    assert!(!coordinator.get_pending_verifications().contains_key(&task));
+    assert!(!coordinator.is_banned_participant(&contributor1));
+
+    Ok(())
+}
+
 #[test]
 #[serial]
 fn round_on_groth16_bls12_377() {
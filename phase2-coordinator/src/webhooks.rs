@@ -0,0 +1,424 @@
+//! Webhooks notifying the publication pipelines downstream of the coordinator, e.g. the updates of the website, the
+//! synchronization of the mirrors or the pinning of the files on IPFS, that a round has been published.
+//!
+//! Once the signed delta of the transcript of a round has been published by the
+//! [`TranscriptPublisher`](`crate::publication::TranscriptPublisher`), its manifest is sealed and a [`RoundWebhook`] is
+//! posted as json to every url of the comma separated `ROUND_WEBHOOK_URLS` env variable. The webhook carries the signed
+//! delta and the urls of the published files, under the `TRANSCRIPT_PUBLIC_URL` the publication directory is served
+//! at. A failed delivery is retried with an exponential backoff, up to `ROUND_WEBHOOK_MAX_ATTEMPTS` attempts (8 by
+//! default), and the status of the deliveries is exposed by `/admin/webhooks`. The deliveries in progress are lost if
+//! the coordinator restarts.
+
+use crate::publication::{SignedTranscriptDelta, DELTAS_DIRECTORY, TRANSCRIPT_PUBLISH_DIR};
+
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use time::OffsetDateTime;
+use tracing::{info, warn};
+use url::Url;
+
+lazy_static! {
+    pub static ref ROUND_WEBHOOK_URLS: Vec<String> = std::env::var("ROUND_WEBHOOK_URLS")
+        .map(|urls| urls.split(',').map(|url| url.trim().to_owned()).collect())
+        .unwrap_or_default();
+    /// The public url of the publication directory of the transcript.
+    pub static ref TRANSCRIPT_PUBLIC_URL: Option<String> = std::env::var("TRANSCRIPT_PUBLIC_URL").ok();
+    pub static ref ROUND_WEBHOOK_MAX_ATTEMPTS: u32 = std::env::var("ROUND_WEBHOOK_MAX_ATTEMPTS")
+        .ok()
+        .map(|attempts| attempts.parse().expect("Invalid ROUND_WEBHOOK_MAX_ATTEMPTS"))
+        .unwrap_or(DEFAULT_MAX_ATTEMPTS);
+}
+
+/// Default number of attempts to deliver a webhook.
+const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+/// Wait before the first retry of a failed delivery, doubled at each attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+/// Upper bound of the wait between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+/// Timeout of the requests delivering a webhook.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The notification of the publication of a round, posted to the webhooks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundWebhook {
+    pub round_height: u64,
+    /// The signed delta of the transcript of the round, with its manifest. Receivers should check its signature
+    /// against the key of the coordinator they expect.
+    pub delta: SignedTranscriptDelta,
+    /// The url of the signed delta.
+    pub delta_url: String,
+    /// The urls of the files of the manifest, in its order.
+    pub artifact_urls: Vec<String>,
+}
+
+/// The state of the delivery of a webhook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryState {
+    /// The webhook is being delivered, or waits for its next attempt.
+    Pending,
+    Delivered,
+    /// All the attempts failed.
+    Failed,
+}
+
+/// The status of the delivery of the webhook of a round to an endpoint, returned by `/admin/webhooks`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub round_height: u64,
+    pub endpoint: String,
+    pub state: DeliveryState,
+    pub attempts: u32,
+    /// The error of the last failed attempt.
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::timestamp::option")]
+    pub last_attempt: Option<OffsetDateTime>,
+}
+
+/// Delivers the webhooks of the published rounds and records the status of the deliveries. Disabled if no endpoint is
+/// set.
+#[derive(Clone)]
+pub struct RoundWebhooks {
+    endpoints: Vec<Url>,
+    public_url: Option<Url>,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    deliveries: Arc<Mutex<BTreeMap<(u64, String), WebhookDelivery>>>,
+}
+
+impl Default for RoundWebhooks {
+    /// Generates a disabled [`RoundWebhooks`] instance.
+    fn default() -> Self {
+        Self {
+            endpoints: vec![],
+            public_url: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: INITIAL_BACKOFF,
+            deliveries: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+}
+
+impl RoundWebhooks {
+    /// Returns webhooks posted to the given endpoints, with the urls of the files under the given public url of the
+    /// publication directory.
+    pub fn new(endpoints: Vec<Url>, public_url: Url, max_attempts: u32, initial_backoff: Duration) -> Self {
+        Self {
+            endpoints,
+            public_url: Some(public_url),
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the webhooks configured in the env, disabled if no endpoint is set.
+    ///
+    /// # Panics
+    /// If an url is malformed, or if the publication of the transcript or its public url are missing.
+    pub fn from_env() -> Self {
+        if ROUND_WEBHOOK_URLS.is_empty() {
+            return Self::default();
+        }
+        assert!(
+            TRANSCRIPT_PUBLISH_DIR.is_some(),
+            "ROUND_WEBHOOK_URLS requires TRANSCRIPT_PUBLISH_DIR"
+        );
+
+        let endpoints = ROUND_WEBHOOK_URLS
+            .iter()
+            .map(|url| Url::parse(url).expect("Invalid url in ROUND_WEBHOOK_URLS"))
+            .collect();
+        let mut public_url = TRANSCRIPT_PUBLIC_URL
+            .clone()
+            .expect("ROUND_WEBHOOK_URLS requires TRANSCRIPT_PUBLIC_URL");
+        // The files are relative to the directory
+        if !public_url.ends_with('/') {
+            public_url.push('/');
+        }
+
+        Self::new(
+            endpoints,
+            Url::parse(&public_url).expect("Invalid TRANSCRIPT_PUBLIC_URL"),
+            *ROUND_WEBHOOK_MAX_ATTEMPTS,
+            INITIAL_BACKOFF,
+        )
+    }
+
+    /// Returns `true` if webhooks are delivered.
+    pub fn is_enabled(&self) -> bool {
+        !self.endpoints.is_empty()
+    }
+
+    /// Returns the webhook of the round of the given delta.
+    pub fn webhook(&self, delta: &SignedTranscriptDelta) -> anyhow::Result<RoundWebhook> {
+        let public_url = self
+            .public_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("The public url of the transcript is not set"))?;
+        let round_height = delta.delta.round_height;
+
+        Ok(RoundWebhook {
+            round_height,
+            delta: delta.clone(),
+            delta_url: public_url
+                .join(&format!("{}/round_{}.json", DELTAS_DIRECTORY, round_height))?
+                .to_string(),
+            artifact_urls: delta
+                .delta
+                .manifest
+                .files
+                .iter()
+                .map(|file| public_url.join(&file.name).map(String::from))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Starts the delivery of the webhooks of the given published rounds to every endpoint, in background tasks.
+    pub fn dispatch(&self, deltas: &[SignedTranscriptDelta]) -> anyhow::Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        for delta in deltas {
+            let webhook = Arc::new(self.webhook(delta)?);
+            for endpoint in &self.endpoints {
+                self.record(webhook.round_height, endpoint, |_| ());
+                rocket::tokio::spawn(self.clone().deliver(endpoint.clone(), webhook.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Posts the webhook to the endpoint until it succeeds or the attempts are exhausted.
+    async fn deliver(self, endpoint: Url, webhook: Arc<RoundWebhook>) {
+        let client = match reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                self.record(webhook.round_height, &endpoint, |delivery| {
+                    delivery.state = DeliveryState::Failed;
+                    delivery.last_error = Some(e.to_string());
+                });
+                return;
+            }
+        };
+        let body = match serde_json::to_vec(webhook.as_ref()) {
+            Ok(body) => body,
+            Err(e) => {
+                self.record(webhook.round_height, &endpoint, |delivery| {
+                    delivery.state = DeliveryState::Failed;
+                    delivery.last_error = Some(e.to_string());
+                });
+                return;
+            }
+        };
+
+        let mut backoff = self.initial_backoff;
+        for attempt in 1..=self.max_attempts {
+            let result = client
+                .post(endpoint.clone())
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            let delivered = result.is_ok();
+            self.record(webhook.round_height, &endpoint, |delivery| {
+                delivery.attempts = attempt;
+                delivery.last_attempt = Some(OffsetDateTime::now_utc());
+                match &result {
+                    Ok(_) => delivery.state = DeliveryState::Delivered,
+                    Err(e) => {
+                        delivery.last_error = Some(e.to_string());
+                        if attempt == self.max_attempts {
+                            delivery.state = DeliveryState::Failed;
+                        }
+                    }
+                }
+            });
+
+            match result {
+                Ok(_) => {
+                    info!("Delivered the webhook of round {} to {}", webhook.round_height, endpoint);
+                    return;
+                }
+                Err(e) => warn!(
+                    "Attempt {} to deliver the webhook of round {} to {} failed: {}",
+                    attempt, webhook.round_height, endpoint, e
+                ),
+            }
+            if attempt < self.max_attempts {
+                rocket::tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+
+    /// Updates the status of the delivery of the webhook of the round to the endpoint, created as pending if missing.
+    fn record(&self, round_height: u64, endpoint: &Url, update: impl FnOnce(&mut WebhookDelivery)) {
+        let mut deliveries = self.deliveries.lock().expect("Webhook deliveries lock poisoned");
+        let delivery = deliveries
+            .entry((round_height, endpoint.to_string()))
+            .or_insert_with(|| WebhookDelivery {
+                round_height,
+                endpoint: endpoint.to_string(),
+                state: DeliveryState::Pending,
+                attempts: 0,
+                last_error: None,
+                last_attempt: None,
+            });
+
+        update(delivery);
+    }
+
+    /// Returns the status of the deliveries, ordered by round and endpoint.
+    pub fn deliveries(&self) -> Vec<WebhookDelivery> {
+        let deliveries = self.deliveries.lock().expect("Webhook deliveries lock poisoned");
+
+        deliveries.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        authentication::{KeyPair, Production},
+        publication::TranscriptDelta,
+        storage::{RoundManifest, TranscriptFile},
+    };
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    fn delta(round_height: u64) -> SignedTranscriptDelta {
+        let keypair = KeyPair::new();
+        let manifest = RoundManifest {
+            round_height,
+            files: vec![TranscriptFile {
+                name: format!("round_{}/chunk_0/contribution_0.verified", round_height),
+                size: 11,
+                hash: None,
+            }],
+            verifications: vec![],
+            injections: vec![],
+            attestations: vec![],
+            parameters: vec![],
+            rehearsal: false,
+            genesis_seed: None,
+            computation_proofs: Vec::new(),
+        };
+
+        TranscriptDelta {
+            round_height,
+            head: hex::encode(manifest.chain_hash(&[])),
+            manifest,
+            previous_head: String::new(),
+            published_at: OffsetDateTime::now_utc(),
+        }
+        .sign(&Production, keypair.pubkey().to_owned(), keypair.sigkey())
+        .unwrap()
+    }
+
+    /// Serves the given statuses to the successive requests, and returns the url of the server.
+    fn serve(statuses: Vec<u16>) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                serde_json::from_slice::<RoundWebhook>(&body).unwrap();
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn test_webhook() {
+        let webhooks = RoundWebhooks::new(
+            vec![],
+            Url::parse("https://transcript.example.com/namada/").unwrap(),
+            3,
+            INITIAL_BACKOFF,
+        );
+        let delta = delta(2);
+        let webhook = webhooks.webhook(&delta).unwrap();
+
+        assert_eq!(webhook.round_height, 2);
+        assert_eq!(webhook.delta, delta);
+        assert_eq!(
+            webhook.delta_url,
+            "https://transcript.example.com/namada/deltas/round_2.json"
+        );
+        assert_eq!(
+            webhook.artifact_urls,
+            vec!["https://transcript.example.com/namada/round_2/chunk_0/contribution_0.verified"]
+        );
+        assert!(RoundWebhooks::default().webhook(&delta).is_err());
+    }
+
+    #[rocket::async_test]
+    async fn test_delivery() {
+        // The first endpoint fails once, the second one always
+        let endpoints = vec![serve(vec![500, 200]), serve(vec![500, 500])];
+        let webhooks = RoundWebhooks::new(
+            endpoints.clone(),
+            Url::parse("https://transcript.example.com/").unwrap(),
+            2,
+            Duration::from_millis(1),
+        );
+        webhooks.dispatch(&[delta(0)]).unwrap();
+
+        let mut deliveries = webhooks.deliveries();
+        for _ in 0..500 {
+            if deliveries.iter().all(|delivery| delivery.state != DeliveryState::Pending) {
+                break;
+            }
+            rocket::tokio::time::sleep(Duration::from_millis(10)).await;
+            deliveries = webhooks.deliveries();
+        }
+
+        let state = |endpoint: &Url| {
+            deliveries
+                .iter()
+                .find(|delivery| delivery.endpoint == endpoint.as_str())
+                .map(|delivery| (delivery.round_height, delivery.state, delivery.attempts))
+        };
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(state(&endpoints[0]), Some((0, DeliveryState::Delivered, 2)));
+        assert_eq!(state(&endpoints[1]), Some((0, DeliveryState::Failed, 2)));
+        assert!(deliveries.iter().all(|delivery| delivery.last_error.is_some()));
+    }
+}
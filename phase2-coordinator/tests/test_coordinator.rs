@@ -5,27 +5,60 @@
 //  NOTE: these tests require the phase1radix files to be placed in the phase2-coordinator folder
 
 use std::{
-    io::Write,
+    io::{Read, Write},
     net::{IpAddr, SocketAddr},
     sync::Arc,
 };
 
 use blake2::Digest;
+use ed25519_compact::{PublicKey, SecretKey};
 use phase2_coordinator::{
-    authentication::{KeyPair, Production, Signature},
+    authentication::{
+        ssh::{SshPublicKey, SshSignature},
+        KeyPair, Production, Signature,
+    },
+    capability_probe::{CapabilityProbe, CapabilityProber},
+    changelog::{SignedChangelog, PROTOCOL_VERSION},
     commands::{Computation, RandomSource},
     coordinator_state::CoordinatorState,
+    compression::{Compression, ContentEncoding, ACCEPT_ENCODING_HEADER, CONTENT_ENCODING_HEADER},
+    cors::{Cors, ALLOW_HEADERS_HEADER, ALLOW_ORIGIN_HEADER, ORIGIN_HEADER},
     environment::Testing,
-    objects::{ContributionInfo, LockedLocators, TrimmedContributionInfo},
+    human_verification::HumanVerifier,
+    identity::IdentityVerifier,
+    lifecycle,
+    lock_monitor::LockMetrics,
+    messages::{LocalizedCatalog, DEFAULT_LANGUAGE},
+    objects::{
+        Announcement, CeremonyParameters, ChallengeBundle, CircuitRegistry, ContributionInfo, ContributionSession,
+        DestructionAttestation, DestructionMethod, Disposition, LifecyclePhase, LockedLocators, ManualContribution,
+        OfflineContributionInfo, ParticipantEvent, ParticipantRecord, ResponseBundle, ReverificationStatus,
+        ReverificationTask, SignedCeremonyMetadata, SignedParticipationBadge, SignedVerifierDelegation,
+        TrimmedContributionInfo, VerificationRetries, VerificationStatus,
+    },
+    pow::{PowPuzzle, ProofOfWork},
+    practice::{PracticeLock, PracticeRounds, PracticeVerification},
     rest,
     rest_utils::{
-        self, ContributorStatus, PostChunkRequest, ACCESS_SECRET_HEADER, BODY_DIGEST_HEADER, CONTENT_LENGTH_HEADER,
-        PUBKEY_HEADER, SIGNATURE_HEADER, TOKENS_ZIP_FILE,
+        self, AttestationPostRequest, CeremonyStatus, ChallengeETags, ContributorStatus, DestructionAttestationRequest,
+        ErrorBody, JoinQueueRequest, JoinQueueResponse, ManualContributionApproval, PostChunkRequest,
+        PublicCeremonyStatus, QueueEvent, QueueEvents, RequestContent, ResponseError, SignatureHeaders, UploadProgress,
+        VerificationTracker, VerificationVoteRequest, ACCEPT_LANGUAGE_HEADER, ACCESS_SECRET_HEADER, BODY_DIGEST_HEADER,
+        CONTENT_LANGUAGE_HEADER, CONTENT_LENGTH_HEADER, CONTENT_RANGE_HEADER, DELEGATION_HEADER, ETAG_HEADER,
+        IF_NONE_MATCH_HEADER, IF_RANGE_HEADER, MESSAGE_CODE_HEADER, PUBKEY_HEADER, RANGE_HEADER, REQUEST_ID_HEADER,
+        SIGNATURE_HEADER, TOKENS_ZIP_FILE,
+    },
+    startup::StartupProfile,
+    storage::{
+        compare_manifests, ContributionLocator, ContributionSignatureLocator, Object, RoundManifest, ShardUsage,
+        ANOMA_BASE_FILE_SIZE, ANOMA_PER_ROUND_FILE_SIZE_INCREASE,
     },
-    storage::{ContributionLocator, ContributionSignatureLocator, Object},
     testing::coordinator,
-    ContributionFileSignature, ContributionState, Coordinator, Participant,
+    webhooks::{RoundWebhooks, WebhookDelivery},
+    ContributionFileSignature, ContributionState, Coordinator, CoordinatorError, MockTimeSource, Participant,
 };
+#[cfg(feature = "dashboard")]
+use phase2_coordinator::dashboard;
 use reqwest::header::{HeaderValue, CONTENT_TYPE};
 use rocket::{
     catchers,
@@ -40,6 +73,7 @@ use sha2::Sha256;
 use zip::write::FileOptions;
 
 const ROUND_HEIGHT: u64 = 1;
+const ALLOWED_ORIGIN: &str = "https://ceremony.namada.net";
 
 struct TestParticipant {
     _inner: Participant,
@@ -55,6 +89,8 @@ struct TestCtx {
     coordinator: TestParticipant,
     // Keep TempDir in scope for some tests
     _tokens_tmp_dir: tempfile::TempDir,
+    // The source of time of the coordinator, to control time-based behaviors
+    time: Arc<MockTimeSource>,
 }
 
 /// Build the rocket server for testing with the proper configuration.
@@ -84,7 +120,12 @@ fn build_context() -> TestCtx {
     std::env::set_var("NAMADA_TOKENS_PATH", tmp_dir.path());
 
     // Instantiate the coordinator
-    let mut coordinator = Coordinator::new(environment, Arc::new(Production)).unwrap();
+    let time = Arc::new(MockTimeSource::new(time::OffsetDateTime::now_utc()));
+    let mut coordinator = Coordinator::new_with_time(environment, Arc::new(Production), time.clone()).unwrap();
+    let verification_tracker = VerificationTracker::default();
+    coordinator.set_verification_callback(verification_tracker.callback());
+    let queue_events = QueueEvents::default();
+    coordinator.set_event_callback(queue_events.callback(None));
 
     let keypair1 = KeyPair::new();
     let keypair2 = KeyPair::new();
@@ -129,25 +170,93 @@ fn build_context() -> TestCtx {
         .mount(
             "/",
             routes![
+                rest::get_pow_puzzle,
+                rest::get_capability_probe,
+                rest::get_capability_probe_blob,
+                rest::lock_practice_round,
+                rest::get_practice_challenge,
+                rest::post_practice_contribution,
                 rest::join_queue,
                 rest::lock_chunk,
                 rest::contribute_chunk,
+                rest::upload_contribution,
+                rest::post_computation_proof,
+                rest::upload_contribution_range,
+                rest::get_contribution_upload_progress,
+                rest::resume_session,
+                rest::get_challenge_bundle,
+                rest::post_response_bundle,
+                rest::get_pending_verifications,
+                rest::post_verification_vote,
+                rest::get_manual_contribution_url,
+                rest::post_manual_contribution,
+                rest::commit_genesis_seed,
+                rest::reveal_genesis_seed,
                 rest::update_coordinator,
                 rest::heartbeat,
                 rest::stop_coordinator,
                 rest::verify_chunks,
+                rest::reissue_challenge,
                 rest::get_contributor_queue_status,
+                rest::get_contributor_queue_events,
+                rest::get_verification_status,
                 rest::post_contribution_info,
                 rest::get_contributions_info,
                 rest::get_healthcheck,
+                rest::get_ceremony_status,
+                rest::get_public_ceremony_status,
+                rest::get_circuit_registry,
+                rest::get_ceremony_metadata,
+                rest::get_badge,
+                rest::get_ceremony_parameters,
+                rest::get_announcements,
+                rest::get_queue_shuffles,
+                rest::get_genesis_seed,
+                rest::get_computation_stats,
+                rest::get_api_changelog,
+                rest::get_messages,
                 rest::get_contribution_url,
                 rest::get_challenge_url,
+                rest::get_challenge_stream,
                 rest::get_coordinator_state,
+                rest::get_participants_history,
+                rest::get_export,
+                rest::get_storage_shards,
+                rest::rebalance_storage,
+                rest::post_reverify,
+                rest::get_reverification_status,
+                rest::post_verifier_delegation,
+                rest::get_lock_metrics,
+                rest::get_startup_profile,
+                rest::get_control_keys,
+                rest::add_control_key,
+                rest::remove_control_key,
+                rest::get_webhook_deliveries,
+                rest::get_verification_quarantine,
+                rest::reject_quarantined_contribution,
+                rest::verify_quarantined_contribution,
+                rest::post_prelaunch_snapshot,
+                rest::post_prelaunch_restore,
+                rest::get_transcript,
+                rest::get_transcript_manifests,
                 rest::update_cohorts,
-                rest::post_attestation
+                rest::post_attestation,
+                rest::post_attestation_post,
+                rest::post_destruction_attestation
             ],
         )
         .manage(coordinator)
+        .manage(verification_tracker)
+        .manage(ChallengeETags::default())
+        .manage(PracticeRounds::default())
+        .manage(queue_events)
+        .manage(ProofOfWork::new(None))
+        .manage(CapabilityProber::new(None, std::time::Duration::from_secs(300)))
+        .manage(HumanVerifier::default())
+        .manage(IdentityVerifier::default())
+        .manage(RoundWebhooks::default())
+        .attach(Cors::new(vec![ALLOWED_ORIGIN]))
+        .attach(Compression::new(0))
         .register(
             "/",
             catchers![
@@ -157,7 +266,11 @@ fn build_context() -> TestCtx {
                 rest_utils::io_error,
                 rest_utils::unprocessable_entity,
                 rest_utils::mismatching_checksum,
-                rest_utils::invalid_header
+                rest_utils::invalid_header,
+                rest_utils::schema_violation,
+                rest_utils::payload_too_large,
+                rest_utils::unsupported_encoding,
+                rest_utils::invalid_lifecycle_phase
             ],
         );
 
@@ -187,39 +300,73 @@ fn build_context() -> TestCtx {
         unknown_participant,
         coordinator: coord_verifier,
         _tokens_tmp_dir: tmp_dir,
+        time,
     }
 }
 
 /// Add headers and optional body to the request
-fn set_request<'a, T>(mut req: LocalRequest<'a>, keypair: &'a KeyPair, body: Option<&T>) -> LocalRequest<'a>
+fn set_request<'a, T>(req: LocalRequest<'a>, keypair: &'a KeyPair, body: Option<&T>) -> LocalRequest<'a>
 where
     T: Serialize,
 {
-    let mut msg = keypair.pubkey().to_owned();
-    req.add_header(Header::new(PUBKEY_HEADER, keypair.pubkey().to_owned()));
+    let json_body = body.map(|body| rest_utils::to_canonical_json(body).unwrap());
+    set_raw_request(req, keypair, json_body)
+}
+
+/// Add headers and optional body, already serialized and possibly compressed, to the request
+fn set_raw_request<'a>(mut req: LocalRequest<'a>, keypair: &'a KeyPair, body: Option<Vec<u8>>) -> LocalRequest<'a> {
+    for header in signed_headers(keypair, body.as_deref()) {
+        req.add_header(header);
+    }
+
+    if let Some(json_body) = body {
+        req = req.body(&json_body);
+    }
+
+    req
+}
+
+/// Returns the headers of a request signed with the given keypair, with its optional body
+fn signed_headers(keypair: &KeyPair, body: Option<&[u8]>) -> Vec<Header<'static>> {
+    sign_headers(keypair, body, |headers| headers.message())
+}
+
+/// Returns the headers of a request signed with the given keypair over the message of the clients predating the
+/// hashed one, with its optional body
+fn legacy_signed_headers(keypair: &KeyPair, body: Option<&[u8]>) -> Vec<Header<'static>> {
+    sign_headers(keypair, body, |headers| headers.legacy_message().into_owned())
+}
 
-    if let Some(body) = body {
+fn sign_headers(
+    keypair: &KeyPair,
+    body: Option<&[u8]>,
+    message: impl Fn(&SignatureHeaders) -> String,
+) -> Vec<Header<'static>> {
+    let mut headers = vec![Header::new(PUBKEY_HEADER, keypair.pubkey().to_owned())];
+    let mut content = None;
+
+    if let Some(json_body) = body {
         // Body digest
-        let json_body = serde_json::to_string(body).unwrap();
         let mut hasher = Sha256::new();
-        hasher.update(&json_body);
-        let digest = base64::encode(hasher.finalize());
-        msg = format!("{}{}{}", msg, json_body.len(), &digest);
-        req.add_header(Header::new(BODY_DIGEST_HEADER, format!("sha-256={}", digest)));
+        hasher.update(json_body);
+        let digest = hasher.finalize();
+        let header = format!("sha-256={}", base64::encode(digest));
+        headers.push(Header::new(BODY_DIGEST_HEADER, header));
+        content = Some(RequestContent::new(json_body.len(), digest));
 
         // Body length
-        req.add_header(Header::new(CONTENT_LENGTH_HEADER, json_body.len().to_string()));
+        headers.push(Header::new(CONTENT_LENGTH_HEADER, json_body.len().to_string()));
 
-        // Attach json serialized body
-        req.add_header(ContentType::JSON);
-        req = req.body(&json_body);
+        // Json serialized body
+        headers.push(ContentType::JSON.into());
     }
 
     // Sign request
+    let msg = message(&SignatureHeaders::new(keypair.pubkey(), content, None));
     let signature = Production.sign(keypair.sigkey(), &msg).unwrap();
-    req.add_header(Header::new(SIGNATURE_HEADER, signature));
+    headers.push(Header::new(SIGNATURE_HEADER, signature));
 
-    req
+    headers
 }
 
 #[test]
@@ -247,6 +394,209 @@ fn get_status() {
     assert!(response.body().is_some());
 }
 
+#[test]
+fn get_participants_history() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/participants_history");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Retrieve the history with valid token
+    req = client.get("/participants_history");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let records: Vec<ParticipantRecord> = response.into_json().unwrap();
+    let contributor = Participant::new_contributor(ctx.contributors[0].keypair.pubkey());
+    let record = records.iter().find(|record| record.participant == contributor).unwrap();
+    assert_eq!(record.disposition, Disposition::Queued);
+    assert_eq!(record.audit_entries[0].event, ParticipantEvent::JoinedQueue);
+}
+
+#[test]
+fn get_export() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Unknown format
+    let mut req = client.get("/admin/export?format=xlsx");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Export the tables as CSV
+    req = client.get("/admin/export");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::ZIP));
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(response.into_bytes().unwrap())).unwrap();
+    let mut names: Vec<&str> = archive.file_names().collect();
+    names.sort_unstable();
+    assert_eq!(names, vec![
+        "audit_log.csv",
+        "participants.csv",
+        "rounds.csv",
+        "schema.json"
+    ]);
+
+    let mut participants = String::new();
+    archive
+        .by_name("participants.csv")
+        .unwrap()
+        .read_to_string(&mut participants)
+        .unwrap();
+    let contributor = Participant::new_contributor(ctx.contributors[0].keypair.pubkey());
+    assert!(
+        participants
+            .lines()
+            .any(|line| line.starts_with(&format!("{},Queued,", contributor)))
+    );
+}
+
+#[test]
+fn storage_shards() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/storage/shards");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // No shard is configured in the test environment
+    req = client.get("/storage/shards");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let shards: Vec<ShardUsage> = response.into_json().unwrap();
+    assert!(shards.is_empty());
+
+    // Nothing to move without shards
+    req = client.post("/storage/rebalance");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_json::<usize>().unwrap(), 0);
+}
+
+#[test]
+fn reverify() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.post("/admin/reverify?from_round=0");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // The current round is not completed yet
+    req = client.post(format!("/admin/reverify?from_round={}", ROUND_HEIGHT));
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // The initial round has no contribution to verify again
+    req = client.post("/admin/reverify?from_round=0");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_json::<Vec<ReverificationTask>>().unwrap().is_empty());
+
+    req = client.get("/admin/reverify");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let status: ReverificationStatus = response.into_json().unwrap();
+    assert!(status.queue.is_empty());
+    assert!(status.reports.is_empty());
+}
+
+#[test]
+fn get_transcript() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, inverted range or range beyond the current round
+    let response = client.get("/transcript?from_round=1&to_round=0").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client
+        .get(format!("/transcript?from_round=1&to_round={}", ROUND_HEIGHT + 1))
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Download the transcript of the current round
+    let response = client
+        .get(format!("/transcript?from_round={0}&to_round={0}", ROUND_HEIGHT))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let content_length: usize = response.headers().get_one("Content-Length").unwrap().parse().unwrap();
+
+    let archive = response.into_bytes().unwrap();
+    assert_eq!(archive.len(), content_length);
+    assert_eq!(archive.len() % 512, 0);
+    let manifest_name = format!("round_{}/manifest.json", ROUND_HEIGHT);
+    assert_eq!(&archive[..manifest_name.len()], manifest_name.as_bytes());
+    assert!(archive[archive.len() - 1024..].iter().all(|byte| *byte == 0));
+
+    // The length of the archive can be requested without downloading it
+    let response = client
+        .head(format!("/transcript?from_round={0}&to_round={0}", ROUND_HEIGHT))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.headers().get_one("Content-Length"),
+        Some(content_length.to_string().as_str())
+    );
+    assert!(response.into_bytes().unwrap_or_default().is_empty());
+}
+
+#[test]
+fn get_transcript_manifests() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, range beyond the current round
+    let response = client
+        .get(format!("/transcript/manifests?from_round=0&to_round={}", ROUND_HEIGHT + 1))
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = client
+        .get(format!("/transcript/manifests?from_round=0&to_round={}", ROUND_HEIGHT))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let manifests: Vec<RoundManifest> = response.into_json().unwrap();
+    assert_eq!(manifests.len() as u64, ROUND_HEIGHT + 1);
+    assert_eq!(manifests.last().unwrap().round_height, ROUND_HEIGHT);
+
+    // A coordinator missing a contribution diverges on that file
+    assert!(compare_manifests(&manifests, &manifests).is_none());
+    let mut diverging = manifests.clone();
+    let file = diverging[0].contribution_files().next().unwrap().name.clone();
+    diverging[0].files.retain(|f| f.name != file);
+    let divergence = compare_manifests(&manifests, &diverging).unwrap();
+    assert_eq!(divergence.round_height, 0);
+    assert_eq!(divergence.artifact, file);
+}
+
 fn get_serialized_tokens_zip(tokens: Vec<&str>) -> Vec<u8> {
     let w = std::io::Cursor::new(Vec::new());
     let mut zip_writer = zip::ZipWriter::new(w);
@@ -290,7 +640,7 @@ fn update_cohorts() {
     req = client.post("/update_cohorts");
     req = set_request::<Vec<u8>>(req, &ctx.coordinator.keypair, Some(&new_invalid_tokens));
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::InternalServerError);
+    assert_eq!(response.status(), Status::BadRequest);
     assert!(response.body().is_some());
     assert!(std::fs::metadata(TOKENS_ZIP_FILE).is_err());
 
@@ -308,6 +658,19 @@ fn update_cohorts() {
     assert!(std::fs::metadata(TOKENS_ZIP_FILE).is_ok());
 }
 
+#[test]
+fn wrong_reissue_challenge() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, request from non-coordinator participant
+    let mut req = client.get("/reissue_challenge");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+}
+
 #[test]
 fn stop_coordinator() {
     let ctx = build_context();
@@ -354,171 +717,911 @@ fn get_healthcheck() {
 }
 
 #[test]
-fn get_contributor_queue_status() {
+fn get_ceremony_status() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Non-existing contributor key
-    let mut req = client.get("/contributor/queue_status");
-    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
-    let response = req.dispatch();
+    let response = client.get("/ceremony_status").dispatch();
     assert_eq!(response.status(), Status::Ok);
-    match response.into_json::<ContributorStatus>().unwrap() {
-        ContributorStatus::Other => (),
-        _ => panic!("Wrong ContributorStatus"),
-    }
 
-    // Ok
-    req = client.get("/contributor/queue_status");
-    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    match response.into_json::<ContributorStatus>().unwrap() {
-        ContributorStatus::Round => (),
-        _ => panic!("Wrong ContributorStatus"),
-    }
+    let status: CeremonyStatus = response.into_json().unwrap();
+    assert_eq!(
+        status,
+        CeremonyStatus {
+            round_height: ROUND_HEIGHT,
+            queue_size: 0,
+            contribution_in_progress: true,
+            update_interval: 5,
+            rehearsal: false,
+            phase: None,
+        }
+    );
 }
 
 #[test]
-fn heartbeat() {
+fn get_public_ceremony_status() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Non-existing contributor key
-    let mut req = client.post("/contributor/heartbeat");
-    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::InternalServerError);
-    assert!(response.body().is_some());
+    let response = client.get("/ceremony/status").dispatch();
+    assert_eq!(response.status(), Status::Ok);
 
-    // Ok
-    req = client.post("/contributor/heartbeat");
-    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
-    let response = req.dispatch();
+    let status: PublicCeremonyStatus = response.into_json().unwrap();
+    assert_eq!(status.round_height, ROUND_HEIGHT);
+    assert_eq!(
+        status.current_contributor.as_deref(),
+        Some(ctx.contributors[0].keypair.pubkey())
+    );
+    assert_eq!(status.queue_size, 0);
+    assert_eq!(status.completed_contributions, 0);
+}
+
+#[cfg(feature = "dashboard")]
+#[test]
+fn dashboard() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket.mount(dashboard::DASHBOARD_PATH, dashboard::routes()))
+        .expect("Invalid rocket instance");
+
+    let response = client.get(dashboard::DASHBOARD_PATH).dispatch();
     assert_eq!(response.status(), Status::Ok);
-    assert!(response.body().is_none());
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+    assert!(response.into_string().unwrap().contains("/dashboard/dashboard.js"));
+
+    let response = client.get(format!("{}/dashboard.js", dashboard::DASHBOARD_PATH)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JavaScript));
+    assert!(response.into_string().unwrap().contains("/ceremony_status"));
 }
 
 #[test]
-fn update_coordinator() {
+fn get_api_changelog() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Wrong, request comes from normal contributor
-    let mut req = client.get("/update");
-    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
-
-    // Ok, request comes from coordinator itself
-    req = client.get("/update");
-    req = set_request::<()>(req, &ctx.coordinator.keypair, None);
-    let response = req.dispatch();
+    let response = client.get("/api/changelog").dispatch();
     assert_eq!(response.status(), Status::Ok);
-    assert!(response.body().is_none());
+
+    let signed: SignedChangelog = response.into_json().unwrap();
+    assert_eq!(signed.changelog.protocol_version, PROTOCOL_VERSION);
+    assert!(signed.changelog.deprecations.is_empty());
+    assert!(signed.verify(&Production, &signed.coordinator_public_key));
+    assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
 }
 
 #[test]
-fn wrong_post_attestation() {
+fn get_localized_messages() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Wrong, missing contribution
-    let mut req = client.post("/contributor/attestation");
-    req = set_request::<(u64, String)>(
-        req,
-        &ctx.contributors[0].keypair,
-        Some(&(1, String::from("https://namada.net"))),
-    );
+    let response = client
+        .get("/messages")
+        .header(Header::new(ACCEPT_LANGUAGE_HEADER, "fr-CH, fr;q=0.9, en;q=0.8"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let catalog: LocalizedCatalog = response.into_json().unwrap();
+    assert_eq!(catalog.language, "fr");
+    assert_eq!(catalog.messages["status.round"], "C'est votre tour de contribuer");
+
+    // Without any language of the catalog, the messages are in english
+    let response = client
+        .get("/messages")
+        .header(Header::new(ACCEPT_LANGUAGE_HEADER, "ja"))
+        .dispatch();
+    let catalog: LocalizedCatalog = response.into_json().unwrap();
+    assert_eq!(catalog.language, DEFAULT_LANGUAGE);
+
+    // The status carries its code
+    let mut req = client.get("/contributor/queue_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::InternalServerError);
-    assert!(response.body().is_some());
+    assert_eq!(response.headers().get_one(MESSAGE_CODE_HEADER), Some("status.round"));
+
+    // The error is localized
+    let response = client
+        .get("/contributor/verification_status")
+        .header(Header::new(ACCEPT_LANGUAGE_HEADER, "fr"))
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(
+        response.headers().get_one(MESSAGE_CODE_HEADER),
+        Some("error.missing_required_header")
+    );
+    assert_eq!(response.headers().get_one(CONTENT_LANGUAGE_HEADER), Some("fr"));
+    let error: ErrorBody = response.into_json().unwrap();
+    assert_eq!(error.message, "L'en-tête requis Digest est absent de la requête");
+
+    // Or left as it is without any language of the catalog
+    let response = client.get("/contributor/verification_status").dispatch();
+    assert_eq!(
+        response.headers().get_one(MESSAGE_CODE_HEADER),
+        Some("error.missing_required_header")
+    );
+    assert!(response.headers().get_one(CONTENT_LANGUAGE_HEADER).is_none());
+    let error: ErrorBody = response.into_json().unwrap();
+    assert_eq!(
+        error.message,
+        "The required Digest header was missing from the incoming request"
+    );
 }
 
 #[test]
-fn join_queue() {
+fn structured_errors() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    let socket_address = SocketAddr::new(ctx.unknown_participant.address, 8080);
-
-    // Wrong request, invalid token
-    let mut req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
-        req,
-        &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV3"
-        )),
-    );
+    // The errors carry their code and the id of the request
+    let response = client.get("/contributor/verification_status").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let request_id = response.headers().get_one(REQUEST_ID_HEADER).unwrap().to_owned();
+    let error: ErrorBody = response.into_json().unwrap();
+    assert_eq!(error.code, "error.missing_required_header");
+    assert_eq!(error.request_id, request_id);
+    assert!(error.lifecycle.is_none());
+
+    // The id set by the reverse proxy is kept, unless it's malformed
+    let response = client
+        .get("/contributor/verification_status")
+        .header(Header::new(REQUEST_ID_HEADER, "proxy-1234"))
+        .dispatch();
+    assert_eq!(response.into_json::<ErrorBody>().unwrap().request_id, "proxy-1234");
+    let response = client
+        .get("/contributor/verification_status")
+        .header(Header::new(REQUEST_ID_HEADER, "proxy 1234"))
+        .dispatch();
+    assert_ne!(response.into_json::<ErrorBody>().unwrap().request_id, "proxy 1234");
+
+    // Each error has its status
+    let mut req = client.get("/contributor/verification_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
-
-    // Ok request
-    req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
-        req,
-        &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"
-        )),
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(
+        response.into_json::<ErrorBody>().unwrap().code,
+        "error.unknown_contributor"
     );
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    assert!(response.body().is_some());
-
-    // Wrong request, IP already in queue
-    req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
-        req,
-        &ctx.contributors[1].keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"
-        )),
+    assert_eq!(ResponseError::InvalidNewTokens.status(), Status::BadRequest);
+    assert_eq!(
+        ResponseError::InvalidContributionInfo(String::new()).status(),
+        Status::BadRequest
     );
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
-
-    // Wrong request, token already in queue
-    let socket_address = SocketAddr::new(IpAddr::V4("0.0.0.4".parse().unwrap()), 8080);
-    req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
-        req,
-        &ctx.contributors[1].keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"
-        )),
+    assert_eq!(
+        ResponseError::IoError(String::new()).status(),
+        Status::InternalServerError
     );
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
 
-    // Wrong request, already existing contributor
-    req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
-        req,
-        &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"
-        )),
+    // The errors of the coordinator caused by the client aren't internal errors
+    assert_eq!(
+        ResponseError::CoordinatorError(CoordinatorError::ParticipantAlreadyAdded).status(),
+        Status::Conflict
+    );
+    assert_eq!(
+        ResponseError::CoordinatorError(CoordinatorError::ParticipantUnauthorized).status(),
+        Status::Forbidden
+    );
+    assert_eq!(
+        ResponseError::CoordinatorError(CoordinatorError::StorageFailed).status(),
+        Status::InternalServerError
     );
-    let response = req.dispatch();
-    assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
 }
 
-/// Test wrong usage of lock_chunk.
 #[test]
-fn wrong_lock_chunk() {
+fn get_circuit_registry() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Wrong request, unknown participant
-    let mut req = client.get("/contributor/lock_chunk");
-    req = set_request::<u8>(req, &ctx.unknown_participant.keypair, None);
+    let req = client.get("/circuits");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let registry: CircuitRegistry = response.into_json().unwrap();
+    assert_eq!(registry, CircuitRegistry::default());
+
+    // The locked chunk references the registry entries
+    let locked_locators = ctx.contributors[0].locked_locators.as_ref().unwrap();
+    assert!(!locked_locators.circuits().is_empty());
+    assert_eq!(locked_locators.circuits(), registry.circuits_for_chunk(0).as_slice());
+}
+
+#[test]
+fn get_ceremony_metadata() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    let response = client.get("/ceremony/metadata").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let signed: SignedCeremonyMetadata = response.into_json().unwrap();
+    assert!(!signed.metadata.name.is_empty());
+    assert_eq!(signed.metadata.circuits.len(), CircuitRegistry::default().circuits().len());
+    assert!(signed.verify(&Production, &signed.coordinator_public_key));
+    assert!(!signed.verify(&Production, KeyPair::new().pubkey()));
+}
+
+#[test]
+fn get_badge() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let pubkey = ctx.contributors[0].keypair.pubkey();
+
+    // No contribution yet
+    let response = client.get(format!("/badge/{}.json", pubkey)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let signed: SignedParticipationBadge = response.into_json().unwrap();
+    assert_eq!(signed.badge.public_key, pubkey);
+    assert!(!signed.badge.contributed);
+    assert!(signed.badge.round_height.is_none() && signed.badge.contribution_hash.is_none());
+    assert!(signed.verify(&Production, &signed.coordinator_public_key));
+
+    let response = client.get(format!("/badge/{}.svg", pubkey)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::SVG));
+    let svg = response.into_string().unwrap();
+    assert!(svg.starts_with("<svg") && svg.contains("not a contributor"));
+
+    // Unknown format
+    let response = client.get(format!("/badge/{}.png", pubkey)).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn get_announcements() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // No limit on the length of the ceremony, nothing to announce
+    let response = client.get("/ceremony/announcements").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let announcements: Vec<Announcement> = response.into_json().unwrap();
+    assert!(announcements.is_empty());
+}
+
+#[test]
+fn get_ceremony_parameters() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    let response = client.get("/ceremony/parameters").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let parameters: CeremonyParameters = response.into_json().unwrap();
+    assert_eq!(parameters.round_height, ROUND_HEIGHT);
+    assert_eq!(parameters.challenge_size, Object::anoma_contribution_file_size(ROUND_HEIGHT, 0));
+    assert_eq!(parameters.response_size, Object::anoma_contribution_file_size(ROUND_HEIGHT, 1));
+
+    // The mismatches are reported to the clients built for another ceremony
+    assert!(parameters
+        .mismatches(ANOMA_BASE_FILE_SIZE, ANOMA_PER_ROUND_FILE_SIZE_INCREASE)
+        .is_empty());
+    let mismatches = parameters.mismatches(ANOMA_BASE_FILE_SIZE + 1, ANOMA_PER_ROUND_FILE_SIZE_INCREASE);
+    assert_eq!(mismatches.len(), 1);
+}
+
+#[test]
+fn get_pow_puzzle() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // No proof-of-work required
+    let mut req = client.get("/contributor/pow_puzzle");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let puzzle: Option<PowPuzzle> = response.into_json().unwrap();
+    assert!(puzzle.is_none());
+}
+
+#[test]
+fn get_capability_probe() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // No capability probe required
+    let mut req = client.get("/contributor/capability_probe");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let probe: Option<CapabilityProbe> = response.into_json().unwrap();
+    assert!(probe.is_none());
+
+    // No blob without a probe
+    let mut req = client.get("/contributor/capability_probe/blob");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn practice_round() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Not a contributor of the ceremony
+    let mut req = client.post("/contributor/practice/lock");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // No practice round locked
+    let mut req = client.get("/contributor/practice/challenge");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Ok
+    let mut req = client.post("/contributor/practice/lock");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let lock: PracticeLock = response.into_json().unwrap();
+
+    let mut req = client.get("/contributor/practice/challenge");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let challenge = response.into_bytes().unwrap();
+    assert_eq!(challenge.len() as u64, lock.challenge_size);
+
+    // The challenge itself holds no new contribution
+    let mut req = client.post("/contributor/practice/contribution");
+    req = set_raw_request(req, &ctx.contributors[0].keypair, Some(challenge));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let verification: PracticeVerification = response.into_json().unwrap();
+    assert!(!verification.verified);
+    assert!(verification.reason.is_some());
+
+    // The practice round takes a single contribution
+    let mut req = client.post("/contributor/practice/contribution");
+    req = set_raw_request(req, &ctx.contributors[0].keypair, Some(vec![0; 64]));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn get_contributor_queue_status() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Non-existing contributor key
+    let mut req = client.get("/contributor/queue_status");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    match response.into_json::<ContributorStatus>().unwrap() {
+        ContributorStatus::Other => (),
+        _ => panic!("Wrong ContributorStatus"),
+    }
+
+    // Ok
+    req = client.get("/contributor/queue_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    match response.into_json::<ContributorStatus>().unwrap() {
+        ContributorStatus::Round => (),
+        _ => panic!("Wrong ContributorStatus"),
+    }
+}
+
+#[test]
+fn get_contributor_queue_events() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // The stream starts with the status of the contributor, followed by its turn
+    let mut req = client.get("/contributor/queue_events");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let mut response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::EventStream));
+
+    let mut body = String::new();
+    let mut buffer = [0u8; 1024];
+    while body.matches("\n\n").count() < 2 {
+        let read = response.read(&mut buffer).unwrap();
+        assert!(read > 0);
+        body.push_str(std::str::from_utf8(&buffer[..read]).unwrap());
+    }
+    let events: Vec<QueueEvent> = body
+        .split("\n\n")
+        .take(2)
+        .map(|block| {
+            let data = block.lines().find_map(|line| line.strip_prefix("data:")).unwrap();
+            serde_json::from_str(data.trim()).unwrap()
+        })
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            QueueEvent::Status {
+                status: ContributorStatus::Round
+            },
+            QueueEvent::YourTurn
+        ]
+    );
+}
+
+#[test]
+fn wrong_get_verification_status() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Contributor without a submitted contribution
+    let mut req = client.get("/contributor/verification_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.body().is_some());
+
+    // Unsigned request
+    let req = client.get("/contributor/verification_status");
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn heartbeat() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Non-existing contributor key
+    let mut req = client.post("/contributor/heartbeat");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.body().is_some());
+
+    // Ok
+    req = client.post("/contributor/heartbeat");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.body().is_none());
+}
+
+#[test]
+fn heartbeat_with_ssh_key() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Sign with the OpenSSH encoding of the key of the current contributor
+    let keypair = &ctx.contributors[0].keypair;
+    let secret_key = SecretKey::from_slice(&hex::decode(keypair.sigkey()).unwrap()).unwrap();
+    let public_key = PublicKey::from_slice(&hex::decode(keypair.pubkey()).unwrap()).unwrap();
+    let ssh_public_key = format!("{} contributor@example", SshPublicKey::from(public_key));
+
+    // Wrong, signature of another key
+    let other_secret_key =
+        SecretKey::from_slice(&hex::decode(ctx.unknown_participant.keypair.sigkey()).unwrap()).unwrap();
+    let mut req = client.post("/contributor/heartbeat");
+    req.add_header(Header::new(PUBKEY_HEADER, ssh_public_key.clone()));
+    req.add_header(Header::new(
+        SIGNATURE_HEADER,
+        SshSignature::sign(&other_secret_key, ssh_public_key.as_bytes()).to_string(),
+    ));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Ok, the participant is identified by its raw key
+    let message = SignatureHeaders::new(&ssh_public_key, None, None).message();
+    req = client.post("/contributor/heartbeat");
+    req.add_header(Header::new(PUBKEY_HEADER, ssh_public_key.clone()));
+    req.add_header(Header::new(
+        SIGNATURE_HEADER,
+        SshSignature::sign(&secret_key, message.as_bytes()).to_string(),
+    ));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn legacy_request_signature() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // The fields of the legacy message can be shifted into one another, not the ones of the hashed message
+    let headers = SignatureHeaders::new("00ff", Some(RequestContent::new(12, b"digest")), None);
+    let shifted = SignatureHeaders::new("00ff1", Some(RequestContent::new(2, b"digest")), None);
+    assert_eq!(headers.legacy_message(), shifted.legacy_message());
+    assert_ne!(headers.message(), shifted.message());
+
+    // Ok, the clients predating the hashed message are accepted without a deadline
+    let mut req = client.post("/contributor/heartbeat");
+    for header in legacy_signed_headers(&ctx.contributors[0].keypair, None) {
+        req.add_header(header);
+    }
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    // Wrong, signature of the message of another key
+    req = client.post("/contributor/heartbeat");
+    let mut headers = legacy_signed_headers(&ctx.unknown_participant.keypair, None);
+    headers[0] = Header::new(PUBKEY_HEADER, ctx.contributors[0].keypair.pubkey().to_owned());
+    for header in headers {
+        req.add_header(header);
+    }
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn cors() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Preflight of a signed endpoint from an allowed origin
+    let req = client
+        .options("/contributor/heartbeat")
+        .header(Header::new(ORIGIN_HEADER, ALLOWED_ORIGIN));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    assert_eq!(response.headers().get_one(ALLOW_ORIGIN_HEADER), Some(ALLOWED_ORIGIN));
+    assert!(response
+        .headers()
+        .get_one(ALLOW_HEADERS_HEADER)
+        .unwrap()
+        .contains(SIGNATURE_HEADER));
+
+    // Preflight of a signed endpoint from an unknown origin
+    let req = client
+        .options("/contributor/heartbeat")
+        .header(Header::new(ORIGIN_HEADER, "https://unknown.origin"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    assert!(response.headers().get_one(ALLOW_ORIGIN_HEADER).is_none());
+
+    // Signed request from an allowed origin
+    let mut req = client
+        .post("/contributor/heartbeat")
+        .header(Header::new(ORIGIN_HEADER, ALLOWED_ORIGIN));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one(ALLOW_ORIGIN_HEADER), Some(ALLOWED_ORIGIN));
+
+    // Signed request from an unknown origin
+    let mut req = client
+        .post("/contributor/heartbeat")
+        .header(Header::new(ORIGIN_HEADER, "https://unknown.origin"));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert!(response.headers().get_one(ALLOW_ORIGIN_HEADER).is_none());
+
+    // Public endpoint from any origin
+    let req = client
+        .get("/contribution_info")
+        .header(Header::new(ORIGIN_HEADER, "https://unknown.origin"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one(ALLOW_ORIGIN_HEADER), Some("*"));
+}
+
+#[test]
+fn compression() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Response compressed with the encoding accepted by the client
+    let plain = client.get("/circuits").dispatch().into_bytes().unwrap();
+    for encoding in [ContentEncoding::Gzip, ContentEncoding::Zstd].iter() {
+        let req = client
+            .get("/circuits")
+            .header(Header::new(ACCEPT_ENCODING_HEADER, encoding.as_str()));
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one(CONTENT_ENCODING_HEADER),
+            Some(encoding.as_str())
+        );
+        let compressed = response.into_bytes().unwrap();
+        assert_eq!(encoding.decompress(&compressed, u64::MAX).unwrap(), plain);
+    }
+
+    // Compressed request body, decompressed before deserialization
+    let json_body = rest_utils::to_canonical_json(&String::from("Unexpected string")).unwrap();
+    let mut req = client
+        .post("/contributor/contribution_info")
+        .header(Header::new(CONTENT_ENCODING_HEADER, "gzip"));
+    req = set_raw_request(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(ContentEncoding::Gzip.compress(&json_body).unwrap()),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    assert!(response.into_string().unwrap().contains("invalid type"));
+
+    // Unsupported encoding
+    let mut req = client
+        .post("/contributor/contribution_info")
+        .header(Header::new(CONTENT_ENCODING_HEADER, "br"));
+    req = set_raw_request(req, &ctx.contributors[0].keypair, Some(json_body));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+}
+
+#[test]
+fn update_coordinator() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, request comes from normal contributor
+    let mut req = client.get("/update");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Ok, request comes from coordinator itself
+    req = client.get("/update");
+    req = set_request::<()>(req, &ctx.coordinator.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.body().is_none());
+}
+
+#[test]
+fn control_keys() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let coordinator_key = ctx.coordinator.keypair.pubkey().to_owned();
+    let other_key = ctx.unknown_participant.keypair.pubkey().to_owned();
+    let update = |keypair: &KeyPair| {
+        set_request::<()>(client.get("/update"), keypair, None)
+            .dispatch()
+            .status()
+    };
+    let control_keys = |action: &str, pubkey: &str, token: &str| {
+        let mut req = client.post(format!("/admin/control_keys/{}?pubkey={}", action, pubkey));
+        req.add_header(Header::new(ACCESS_SECRET_HEADER, token.to_owned()));
+        req.dispatch()
+    };
+
+    // Provide invalid token
+    let response = control_keys("add", &other_key, "wrong token");
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert_eq!(update(&ctx.unknown_participant.keypair), Status::Unauthorized);
+
+    // Add a second control key
+    let response = control_keys("add", &other_key, access_token);
+    assert_eq!(response.status(), Status::Ok);
+    let keys: Vec<String> = response.into_json().unwrap();
+    assert!(keys.contains(&coordinator_key) && keys.contains(&other_key));
+    assert_eq!(update(&ctx.unknown_participant.keypair), Status::Ok);
+    assert_eq!(update(&ctx.coordinator.keypair), Status::Ok);
+
+    // Rotate the key of the coordinator
+    let response = control_keys("remove", &coordinator_key, access_token);
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(update(&ctx.coordinator.keypair), Status::Unauthorized);
+    let mut req = client.get("/admin/control_keys");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let keys: Vec<String> = req.dispatch().into_json().unwrap();
+    assert_eq!(keys, vec![other_key.clone()]);
+
+    // Wrong, the last control key can't be revoked
+    let response = control_keys("remove", &other_key, access_token);
+    assert_eq!(response.status(), Status::Conflict);
+    let response = control_keys("remove", &coordinator_key, access_token);
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn wrong_post_attestation() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, missing contribution
+    let mut req = client.post("/contributor/attestation");
+    req = set_request::<(u64, String)>(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(&(1, String::from("https://namada.net"))),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn wrong_post_attestation_post() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, not a web page
+    let mut req = client.post("/contributor/attestation_post");
+    req = set_request::<AttestationPostRequest>(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(&AttestationPostRequest::new(
+            1,
+            String::from("file:///etc/passwd"),
+            String::from("00"),
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.body().is_some());
+
+    // Wrong, missing contribution
+    req = client.post("/contributor/attestation_post");
+    req = set_request::<AttestationPostRequest>(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(&AttestationPostRequest::new(
+            1,
+            String::from("https://namada.net"),
+            String::from("00"),
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn join_queue() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    let socket_address = SocketAddr::new(ctx.unknown_participant.address, 8080);
+
+    // Wrong request, invalid token
+    let mut req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.unknown_participant.keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV3"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Ok request
+    req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.unknown_participant.keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let joined: JoinQueueResponse = response.into_json().unwrap();
+    assert_eq!(joined.cohort, 1);
+
+    // Retry of the request with the same token, left at the same position
+    req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.unknown_participant.keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_json::<JoinQueueResponse>().unwrap(), joined);
+
+    // Wrong request, IP already in queue
+    req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.contributors[1].keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Wrong request, token already in queue
+    let socket_address = SocketAddr::new(IpAddr::V4("0.0.0.4".parse().unwrap()), 8080);
+    req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.contributors[1].keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Wrong request, already existing contributor
+    req = client.post("/contributor/join_queue").remote(socket_address);
+    req = set_request::<JoinQueueRequest>(
+        req,
+        &ctx.unknown_participant.keypair,
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"),
+            None,
+        )),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+}
+
+/// Test duplicate requests to join the queue, e.g. retried by the client on timeout, racing for the lock of the
+/// coordinator.
+#[rocket::async_test]
+async fn join_queue_racing_retries() {
+    let ctx = build_context();
+    let client = rocket::local::asynchronous::Client::tracked(ctx.rocket)
+        .await
+        .expect("Invalid rocket instance");
+
+    let socket_address = SocketAddr::new(ctx.unknown_participant.address, 8080);
+    let body = rest_utils::to_canonical_json(&JoinQueueRequest::new(
+        String::from("9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"),
+        None,
+    ))
+    .unwrap();
+
+    let requests = (0..4).map(|_| {
+        let mut req = client
+            .post("/contributor/join_queue")
+            .remote(socket_address)
+            .body(&body);
+        for header in signed_headers(&ctx.unknown_participant.keypair, Some(&body)) {
+            req.add_header(header);
+        }
+        req.dispatch()
+    });
+
+    // All the requests succeed with the same position
+    let mut responses = vec![];
+    for response in rocket::futures::future::join_all(requests).await {
+        assert_eq!(response.status(), Status::Ok);
+        responses.push(response.into_json::<JoinQueueResponse>().await.unwrap());
+    }
+    assert!(responses.iter().all(|joined| *joined == responses[0]));
+    assert_eq!(responses[0].queue_position, 1);
+    assert_eq!(responses[0].queue_size, 1);
+
+    // The contributor joined the queue once
+    let coordinator = client.rocket().state::<Arc<RwLock<Coordinator>>>().unwrap().read().await;
+    let contributor = Participant::new_contributor(ctx.unknown_participant.keypair.pubkey());
+    let record = coordinator
+        .participant_records()
+        .into_iter()
+        .find(|record| record.participant == contributor)
+        .unwrap();
+    assert_eq!(
+        record
+            .audit_entries
+            .iter()
+            .filter(|entry| entry.event == ParticipantEvent::JoinedQueue)
+            .count(),
+        1
+    );
+}
+
+/// Test wrong usage of lock_chunk.
+#[test]
+fn wrong_lock_chunk() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong request, unknown participant
+    let mut req = client.get("/contributor/lock_chunk");
+    req = set_request::<u8>(req, &ctx.unknown_participant.keypair, None);
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
     assert!(response.body().is_some());
@@ -540,6 +1643,85 @@ fn wrong_get_challenge() {
     assert!(response.body().is_some());
 }
 
+#[test]
+fn get_challenge_stream() {
+    use setup_utils::calculate_hash;
+
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let url = format!("/contributor/challenge/stream?round_height={}", ROUND_HEIGHT);
+
+    // Wrong, non-current contributor
+    let mut req = client.get(url.clone());
+    req = set_request::<()>(req, &ctx.contributors[1].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Wrong, not the current round
+    let mut req = client.get(format!("/contributor/challenge/stream?round_height={}", ROUND_HEIGHT + 1));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_ne!(response.status(), Status::Ok);
+
+    let mut req = client.get(url.clone());
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::Binary));
+    let length: u64 = response.headers().get_one("Content-Length").unwrap().parse().unwrap();
+    let etag = response.headers().get_one(ETAG_HEADER).unwrap().to_owned();
+    let challenge = response.into_bytes().unwrap();
+    assert!(length > 0);
+    assert_eq!(challenge.len() as u64, length);
+    assert_eq!(etag, format!("\"{}\"", hex::encode(calculate_hash(&challenge))));
+
+    // Already downloaded
+    let mut req = client
+        .get(url.clone())
+        .header(Header::new(IF_NONE_MATCH_HEADER, etag.clone()));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::NotModified);
+    assert_eq!(response.headers().get_one(ETAG_HEADER), Some(etag.as_str()));
+    assert!(response.into_bytes().unwrap_or_default().is_empty());
+
+    // Resumed from the 10th byte
+    let mut req = client
+        .get(url.clone())
+        .header(Header::new(RANGE_HEADER, "bytes=10-"))
+        .header(Header::new(IF_RANGE_HEADER, etag.clone()));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::PartialContent);
+    assert_eq!(
+        response.headers().get_one(CONTENT_RANGE_HEADER),
+        Some(format!("bytes 10-{}/{}", length - 1, length).as_str())
+    );
+    assert_eq!(response.into_bytes().unwrap(), challenge[10..]);
+
+    // The range of another file is ignored
+    let mut req = client
+        .get(url.clone())
+        .header(Header::new(RANGE_HEADER, "bytes=10-"))
+        .header(Header::new(IF_RANGE_HEADER, "\"other\""));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_bytes().unwrap(), challenge);
+
+    // Wrong, after the end of the challenge
+    let mut req = client
+        .get(url)
+        .header(Header::new(RANGE_HEADER, format!("bytes={}-", length)));
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::RangeNotSatisfiable);
+    assert_eq!(
+        response.headers().get_one(CONTENT_RANGE_HEADER),
+        Some(format!("bytes */{}", length).as_str())
+    );
+}
+
 /// Test wrong usage of post_contribution_chunk.
 #[test]
 fn wrong_post_contribution_chunk() {
@@ -582,44 +1764,303 @@ fn wrong_contribute_chunk() {
     assert_eq!(response.status(), Status::NotFound);
     assert!(response.body().is_some());
 
-    // Wrong request json body format
-    req = client.post("/contributor/contribute_chunk");
-    req = set_request(
-        req,
-        &ctx.contributors[0].keypair,
-        Some(&String::from("Unexpected string")),
-    );
+    // Wrong request json body format
+    req = client.post("/contributor/contribute_chunk");
+    req = set_request(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(&String::from("Unexpected string")),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    assert!(response.body().is_some());
+
+    let c = ContributionLocator::new(ROUND_HEIGHT, 0, 1, false);
+    let s = ContributionSignatureLocator::new(ROUND_HEIGHT, 0, 1, false);
+    let r = PostChunkRequest::new(ROUND_HEIGHT, c, s);
+
+    // Unknown field in the signed body
+    let mut unknown_field = serde_json::to_value(&r).unwrap();
+    unknown_field["extra"] = serde_json::Value::from(true);
+    req = client.post("/contributor/contribute_chunk");
+    req = set_request(req, &ctx.contributors[0].keypair, Some(&unknown_field));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    assert!(response.into_string().unwrap().contains("unknown field `extra`"));
+
+    // Non-existing contributor key
+    req = client.post("/contributor/contribute_chunk");
+    req = set_request::<PostChunkRequest>(req, &ctx.unknown_participant.keypair, Some(&r));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Non-current-contributor
+    req = client.post("/contributor/contribute_chunk");
+    req = set_request(req, &ctx.contributors[1].keypair, Some(&r));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn wrong_verify() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, request from non-coordinator participant
+    let mut req = client.get("/verify");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn wrong_verification_vote() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Wrong, request from the coordinator's verifier which is not an external verifier of the quorum
+    let mut req = client.get("/verifier/pending_verifications");
+    req = set_request::<()>(req, &ctx.coordinator.keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Wrong, vote from a participant which is not a verifier of the quorum
+    let vote = VerificationVoteRequest {
+        round_height: ROUND_HEIGHT,
+        chunk_id: 0,
+        contribution_id: 1,
+        next_challenge_hash: String::from("00"),
+        signature: String::from("00"),
+    };
+    let mut req = client.post("/verifier/vote");
+    req = set_request::<VerificationVoteRequest>(req, &ctx.unknown_participant.keypair, Some(&vote));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+}
+
+#[test]
+fn get_lock_metrics() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/admin/lock_metrics");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // The acquisitions of the endpoints are recorded by call site
+    let req = client.get("/contributor/queue_status");
+    set_request::<()>(req, &ctx.contributors[0].keypair, None).dispatch();
+
+    let mut req = client.get("/admin/lock_metrics");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let metrics: LockMetrics = response.into_json().unwrap();
+    assert!(metrics.sites.keys().any(|site| site.contains("rest.rs")));
+}
+
+#[test]
+fn get_startup_profile() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let client = Client::tracked(build_context().rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/admin/startup_profile");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // The phases of the startup are recorded in order
+    let mut req = client.get("/admin/startup_profile");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let profile: StartupProfile = response.into_json().unwrap();
+    let phases: Vec<&str> = profile.phases.iter().map(|phase| phase.name.as_str()).collect();
+    assert_eq!(phases.first(), Some(&"storage_load"));
+    assert!(phases.contains(&"storage_consistency"));
+    assert_eq!(phases.last(), Some(&"round_load"));
+    assert_eq!(profile.round_height, Some(0));
+    assert_eq!(profile.total_ms, profile.phases.iter().map(|phase| phase.duration_ms).sum::<u64>());
+}
+
+#[test]
+fn get_webhook_deliveries() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let client = Client::tracked(build_context().rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/admin/webhooks");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // No webhook is delivered while disabled
+    let mut req = client.get("/admin/webhooks");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let deliveries: Vec<WebhookDelivery> = response.into_json().unwrap();
+    assert!(deliveries.is_empty());
+}
+
+#[test]
+fn verification_quarantine() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let client = Client::tracked(build_context().rocket).expect("Invalid rocket instance");
+
+    // Provide invalid token
+    let mut req = client.get("/admin/verification_quarantine");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, "wrong token"));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // No verification crashed the verifier
+    let mut req = client.get("/admin/verification_quarantine");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let retries: Vec<VerificationRetries> = response.into_json().unwrap();
+    assert!(retries.is_empty());
+
+    // Only the quarantined contributions can be resolved
+    for decision in ["reject", "verify"] {
+        let mut req = client.post(format!(
+            "/admin/verification_quarantine/{}?chunk_id=0&contribution_id=1",
+            decision
+        ));
+        req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
+
+#[test]
+fn lifecycle_phases() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+
+    // Every route is mapped to its phases
+    for route in client.rocket().routes() {
+        let name = route.name.as_deref().unwrap();
+        assert!(lifecycle::endpoint_phases(name).is_some(), "{} has no lifecycle phases", name);
+    }
+
+    // Wrong, snapshot of the prelaunch state once a contributor is running
+    let mut req = client.post("/admin/prelaunch/snapshot");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let error: ErrorBody = response.into_json().unwrap();
+    assert_eq!(error.code, "error.invalid_lifecycle_phase");
+    let conflict = error.lifecycle.unwrap();
+    assert_eq!(conflict.endpoint, "/admin/prelaunch/snapshot");
+    assert_eq!(conflict.phase, LifecyclePhase::Running);
+    assert_eq!(conflict.allowed_phases, vec![LifecyclePhase::Prelaunch]);
+
+    // The endpoints of the running ceremony are available
+    let mut req = client.post("/contributor/heartbeat");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    assert_eq!(req.dispatch().status(), Status::Ok);
+    let mut req = client.get("/admin/lock_metrics");
+    req.add_header(Header::new(ACCESS_SECRET_HEADER, access_token));
+    assert_eq!(req.dispatch().status(), Status::Ok);
+}
+
+#[test]
+fn verifier_delegation() {
+    let access_token = "test-access_token";
+    std::env::set_var("ACCESS_SECRET", access_token);
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let delegate = |from_round: u64, to_round: u64, token: &str| {
+        let mut req = client.post(format!(
+            "/admin/delegate_verifier?verifier={}&from_round={}&to_round={}&duration_seconds=3600",
+            ctx.unknown_participant.keypair.pubkey(),
+            from_round,
+            to_round
+        ));
+        req.add_header(Header::new(ACCESS_SECRET_HEADER, token.to_owned()));
+        req.dispatch()
+    };
+
+    // Provide invalid token
+    let response = delegate(ROUND_HEIGHT, ROUND_HEIGHT, "wrong token");
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Delegations can't cover completed rounds only
+    let response = delegate(0, ROUND_HEIGHT - 1, access_token);
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = delegate(ROUND_HEIGHT, ROUND_HEIGHT, access_token);
+    assert_eq!(response.status(), Status::Ok);
+    let delegation: SignedVerifierDelegation = response.into_json().unwrap();
+    assert!(delegation.verify(&Production, ctx.coordinator.keypair.pubkey()));
+    assert_eq!(delegation.delegation.verifier, ctx.unknown_participant.keypair.pubkey());
+
+    // The delegated verifier is accepted by the verification endpoints
+    let mut req = client.get("/verifier/pending_verifications");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    req.add_header(Header::new(DELEGATION_HEADER, delegation.to_header().unwrap()));
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::UnprocessableEntity);
-    assert!(response.body().is_some());
-
-    let c = ContributionLocator::new(ROUND_HEIGHT, 0, 1, false);
-    let s = ContributionSignatureLocator::new(ROUND_HEIGHT, 0, 1, false);
-    let r = PostChunkRequest::new(ROUND_HEIGHT, c, s);
+    assert_eq!(response.status(), Status::Ok);
 
-    // Non-existing contributor key
-    req = client.post("/contributor/contribute_chunk");
-    req = set_request::<PostChunkRequest>(req, &ctx.unknown_participant.keypair, Some(&r));
+    // Wrong, delegation presented by another participant
+    let mut req = client.get("/verifier/pending_verifications");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    req.add_header(Header::new(DELEGATION_HEADER, delegation.to_header().unwrap()));
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
 
-    // Non-current-contributor
-    req = client.post("/contributor/contribute_chunk");
-    req = set_request(req, &ctx.contributors[1].keypair, Some(&r));
+    // Wrong, delegation for the next rounds only
+    let response = delegate(ROUND_HEIGHT + 1, ROUND_HEIGHT + 2, access_token);
+    let delegation: SignedVerifierDelegation = response.into_json().unwrap();
+    let mut req = client.get("/verifier/pending_verifications");
+    req = set_request::<()>(req, &ctx.unknown_participant.keypair, None);
+    req.add_header(Header::new(DELEGATION_HEADER, delegation.to_header().unwrap()));
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
-    assert!(response.body().is_some());
 }
 
 #[test]
-fn wrong_verify() {
+fn wrong_manual_contribution() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
 
-    // Wrong, request from non-coordinator participant
-    let mut req = client.get("/verify");
-    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    // Wrong, upload urls requested by a participant which is not an operator
+    let mut req = client.post("/operator/upload/chunk");
+    req = set_request::<u64>(req, &ctx.coordinator.keypair, Some(&ROUND_HEIGHT));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert!(response.body().is_some());
+
+    // Wrong, approval from the contributor itself which is not an operator
+    let approval = ManualContributionApproval {
+        contribution: ManualContribution {
+            participant: ctx.contributors[0]._inner.clone(),
+            round_height: ROUND_HEIGHT,
+            contribution_hash: String::from("00"),
+            reason: String::from("Computed on the audited machine"),
+        },
+        signature: String::from("00"),
+    };
+    let mut req = client.post("/operator/manual_contribution");
+    req = set_request::<ManualContributionApproval>(req, &ctx.contributors[0].keypair, Some(&approval));
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Unauthorized);
     assert!(response.body().is_some());
@@ -650,6 +2091,17 @@ fn wrong_post_contribution_info() {
     assert_eq!(response.status(), Status::UnprocessableEntity);
     assert!(response.body().is_some());
 
+    // Wrong request, body which isn't UTF-8
+    req = client.post("/contributor/contribution_info");
+    req = set_raw_request(
+        req,
+        &ctx.contributors[0].keypair,
+        Some(b"{\"full_name\": \"\xff\"}".to_vec()),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    assert!(response.into_string().unwrap().contains("not valid UTF-8"));
+
     // Non-existing contributor key
     let contrib_info = ContributionInfo::default();
     req = client.post("/contributor/contribution_info");
@@ -668,14 +2120,379 @@ fn wrong_post_contribution_info() {
     assert!(response.body().is_some());
 }
 
+/// Test the resumption of a contribution after a crash of the client.
+#[test]
+fn resume_session() {
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let keypair = &ctx.contributors[0].keypair;
+    let locked_locators = ctx.contributors[0].locked_locators.as_ref().unwrap();
+
+    // Wrong, participant not in the round
+    for participant in [&ctx.contributors[1], &ctx.unknown_participant] {
+        let mut req = client.post("/contributor/resume_session");
+        req = set_request::<()>(req, &participant.keypair, None);
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    // Resume the online flow
+    let mut req = client.post("/contributor/resume_session");
+    req = set_request::<()>(req, keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let session: ContributionSession = response.into_json().unwrap();
+    assert_eq!(session.round_height, ROUND_HEIGHT);
+    assert_eq!(session.chunk_id, 0);
+    assert_eq!(session.locked_locators.next_contribution(), locked_locators.next_contribution());
+    assert_eq!(
+        session.locked_locators.next_contribution_file_signature(),
+        locked_locators.next_contribution_file_signature()
+    );
+    assert!(session.deadline > ctx.time.time());
+    assert!(session.challenge_bundle.is_none());
+
+    // Resume the offline flow
+    req = client.get("/contributor/offline/challenge_bundle");
+    req = set_request::<()>(req, keypair, None);
+    let challenge_bundle: ChallengeBundle = req.dispatch().into_json().unwrap();
+    req = client.post("/contributor/resume_session");
+    req = set_request::<()>(req, keypair, None);
+    let session: ContributionSession = req.dispatch().into_json().unwrap();
+    assert_eq!(session.challenge_bundle, Some(challenge_bundle));
+}
+
+/// Test a contribution streamed to the coordinator instead of uploaded to S3:
+///
+/// - upload_contribution from a non-current contributor
+/// - upload_contribution of the wrong size
+/// - upload_contribution_range at the wrong offset or in another session, get_contribution_upload_progress and the
+///   remaining range
+/// - upload_contribution and contribute_chunk with its signature
+///
+#[test]
+fn spooled_contribution() {
+    use setup_utils::calculate_hash;
+
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let reqwest_client = reqwest::blocking::Client::new();
+    let keypair = &ctx.contributors[0].keypair;
+    let locked_locators = ctx.contributors[0].locked_locators.as_ref().unwrap();
+
+    // Get challenge
+    let mut req = client.post("/contributor/challenge");
+    req = set_request::<u64>(req, keypair, Some(&ROUND_HEIGHT));
+    let challenge_url: String = req.dispatch().into_json().unwrap();
+    let challenge = reqwest_client
+        .get(challenge_url)
+        .send()
+        .unwrap()
+        .bytes()
+        .unwrap()
+        .to_vec();
+    let challenge_hash = calculate_hash(challenge.as_ref());
+
+    let mut contribution: Vec<u8> = Vec::new();
+    contribution.write_all(challenge_hash.as_slice()).unwrap();
+    let entropy = RandomSource::Entropy(String::from("entropy"));
+    Computation::contribute_test_masp(&challenge, &mut contribution, &entropy);
+    let contrib_size = Object::anoma_contribution_file_size(ROUND_HEIGHT, 1);
+    contribution.resize(contrib_size as usize, 0);
+
+    let response_hash = calculate_hash(contribution.as_ref());
+    let contribution_state = ContributionState::new(challenge_hash.to_vec(), response_hash.to_vec(), None).unwrap();
+    let signature = Production
+        .sign(keypair.sigkey(), &contribution_state.signature_message().unwrap())
+        .unwrap();
+    let contribution_file_signature = ContributionFileSignature::new(signature, contribution_state).unwrap();
+
+    // Wrong, non-current contributor
+    req = client.post("/contributor/upload/contribution");
+    req = set_raw_request(req, &ctx.contributors[1].keypair, Some(contribution.clone()));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Wrong, contribution of the wrong size
+    req = client.post("/contributor/upload/contribution");
+    req = set_raw_request(req, keypair, Some(contribution[1..].to_vec()));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.into_string().unwrap().contains("ContributionFileSizeMismatch"));
+
+    // Upload the contribution in two ranges, resuming after the first one
+    let half = contribution.len() / 2;
+    req = client.post("/contributor/upload/contribution/range?offset=0");
+    req = set_raw_request(req, keypair, Some(contribution[..half].to_vec()));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let progress: UploadProgress = response.into_json().unwrap();
+    assert_eq!((progress.received, progress.hash), (half as u64, None));
+    let session = progress.session.unwrap();
+
+    req = client.post(format!("/contributor/upload/contribution/range?offset=1&session={}", session));
+    req = set_raw_request(req, keypair, Some(contribution[1..half].to_vec()));
+    assert_eq!(req.dispatch().status(), Status::Conflict);
+
+    req = client.post(format!("/contributor/upload/contribution/range?offset={}&session=other", half));
+    req = set_raw_request(req, keypair, Some(contribution[half..].to_vec()));
+    assert_eq!(req.dispatch().status(), Status::Conflict);
+
+    req = client.get("/contributor/upload/contribution/range");
+    req = set_raw_request(req, keypair, None);
+    let progress: UploadProgress = req.dispatch().into_json().unwrap();
+    assert_eq!(progress.received, half as u64);
+    assert_eq!(progress.session.as_ref(), Some(&session));
+
+    req = client.post(format!(
+        "/contributor/upload/contribution/range?offset={}&session={}",
+        progress.received, session
+    ));
+    req = set_raw_request(req, keypair, Some(contribution[half..].to_vec()));
+    let progress: UploadProgress = req.dispatch().into_json().unwrap();
+    assert_eq!(progress.received, contrib_size);
+    assert_eq!(progress.hash, Some(hex::encode(response_hash)));
+
+    // Stream the contribution
+    req = client.post("/contributor/upload/contribution");
+    req = set_raw_request(req, keypair, Some(contribution));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_json::<String>().unwrap(), hex::encode(response_hash));
+
+    // Contribute with the signature of the streamed contribution
+    let post_chunk = PostChunkRequest::spooled(
+        ROUND_HEIGHT,
+        locked_locators.next_contribution(),
+        locked_locators.next_contribution_file_signature(),
+        contribution_file_signature,
+    );
+    req = client.post("/contributor/contribute_chunk");
+    req = set_request::<PostChunkRequest>(req, keypair, Some(&post_chunk));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.body().is_none());
+}
+
+/// Test two ranges of the contribution uploaded concurrently at the same offset, e.g. by two clients of the same
+/// contributor, only one of them being appended.
+#[rocket::async_test]
+async fn spooled_contribution_racing_ranges() {
+    use setup_utils::calculate_hash;
+
+    let ctx = build_context();
+    let client = rocket::local::asynchronous::Client::tracked(ctx.rocket)
+        .await
+        .expect("Invalid rocket instance");
+    let keypair = &ctx.contributors[0].keypair;
+
+    let contrib_size = Object::anoma_contribution_file_size(ROUND_HEIGHT, 1) as usize;
+    let half = contrib_size / 2;
+    let contribution = vec![1u8; contrib_size];
+    let mut other_contribution = contribution.clone();
+    other_contribution[half..].fill(2);
+
+    let range = |offset: usize, session: Option<&str>, body: &[u8]| {
+        let uri = match session {
+            Some(session) => format!(
+                "/contributor/upload/contribution/range?offset={}&session={}",
+                offset, session
+            ),
+            None => format!("/contributor/upload/contribution/range?offset={}", offset),
+        };
+        let mut req = client.post(uri).body(body);
+        for header in signed_headers(keypair, Some(body)) {
+            req.add_header(header);
+        }
+        req.dispatch()
+    };
+
+    let response = range(0, None, &contribution[..half]).await;
+    assert_eq!(response.status(), Status::Ok);
+    let session = response.into_json::<UploadProgress>().await.unwrap().session.unwrap();
+
+    // Either range is appended, the other one is refused as it no longer starts at the end of the upload
+    let (first, second) = rocket::futures::join!(
+        range(half, Some(&session), &contribution[half..]),
+        range(half, Some(&session), &other_contribution[half..])
+    );
+    let mut statuses = vec![first.status(), second.status()];
+    statuses.sort_by_key(|status| status.code);
+    assert_eq!(statuses, vec![Status::Ok, Status::Conflict]);
+
+    let progress = if first.status() == Status::Ok {
+        first.into_json::<UploadProgress>().await.unwrap()
+    } else {
+        second.into_json::<UploadProgress>().await.unwrap()
+    };
+    assert_eq!(progress.received, contrib_size as u64);
+    let hash = progress.hash.unwrap();
+    assert!(
+        hash == hex::encode(calculate_hash(&contribution)) || hash == hex::encode(calculate_hash(&other_contribution))
+    );
+}
+
+/// Test a contribution computed on an air-gapped machine:
+///
+/// - post_response_bundle before switching to the offline flow
+/// - get_challenge_bundle
+/// - post_contribution_info without the offline record
+/// - post_response_bundle not matching the challenge bundle
+/// - post_response_bundle and post_contribution_info
+/// - get_contributions_info
+///
+#[test]
+fn offline_contribution() {
+    use setup_utils::calculate_hash;
+
+    let ctx = build_context();
+    let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
+    let reqwest_client = reqwest::blocking::Client::new();
+    let keypair = &ctx.contributors[0].keypair;
+
+    // Wrong, non-current contributor
+    let mut req = client.get("/contributor/offline/challenge_bundle");
+    req = set_request::<()>(req, &ctx.contributors[1].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // Get challenge
+    req = client.post("/contributor/challenge");
+    req = set_request::<u64>(req, keypair, Some(&ROUND_HEIGHT));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let challenge_url: String = response.into_json().unwrap();
+    let challenge = reqwest_client
+        .get(challenge_url)
+        .send()
+        .unwrap()
+        .bytes()
+        .unwrap()
+        .to_vec();
+    let challenge_hash = calculate_hash(challenge.as_ref());
+
+    // Compute the contribution and sign it, as done on the air-gapped machine
+    let mut contribution: Vec<u8> = Vec::new();
+    contribution.write_all(challenge_hash.as_slice()).unwrap();
+    let entropy = RandomSource::Entropy(String::from("entropy"));
+    Computation::contribute_test_masp(&challenge, &mut contribution, &entropy);
+    let contrib_size = Object::anoma_contribution_file_size(ROUND_HEIGHT, 1);
+    contribution.resize(contrib_size as usize, 0);
+
+    let response_hash = calculate_hash(contribution.as_ref());
+    let contribution_state = ContributionState::new(challenge_hash.to_vec(), response_hash.to_vec(), None).unwrap();
+    let signature = Production
+        .sign(keypair.sigkey(), &contribution_state.signature_message().unwrap())
+        .unwrap();
+    let contribution_file_signature = ContributionFileSignature::new(signature, contribution_state).unwrap();
+
+    // Wrong, response bundle before the challenge bundle
+    let mut response_bundle = ResponseBundle {
+        round_height: ROUND_HEIGHT,
+        chunk_id: 0,
+        contribution_id: 1,
+        contribution_file_signature: contribution_file_signature.clone(),
+    };
+    req = client.post("/contributor/offline/response_bundle");
+    req = set_request::<ResponseBundle>(req, keypair, Some(&response_bundle));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Get challenge bundle
+    req = client.get("/contributor/offline/challenge_bundle");
+    req = set_request::<()>(req, keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let challenge_bundle: ChallengeBundle = response.into_json().unwrap();
+    assert_eq!(challenge_bundle.round_height, ROUND_HEIGHT);
+    assert_eq!(challenge_bundle.contribution_id, 1);
+    assert_eq!(challenge_bundle.challenge_hash, hex::encode(challenge_hash));
+    assert!(challenge_bundle.deadline > time::OffsetDateTime::now_utc() + time::Duration::minutes(20));
+    assert!(response_bundle.answers(&challenge_bundle));
+
+    // Wrong, contribution info not recording the offline flow
+    let mut contrib_info = ContributionInfo::default();
+    contrib_info.full_name = Some(String::from("محمد بن موسى"));
+    contrib_info.language = Some(String::from("ar"));
+    contrib_info.public_key = keypair.pubkey().to_owned();
+    contrib_info.ceremony_round = ROUND_HEIGHT;
+    contrib_info.is_another_machine = true;
+    contrib_info.try_sign(keypair).unwrap();
+    req = client.post("/contributor/contribution_info");
+    req = set_request::<ContributionInfo>(req, keypair, Some(&contrib_info));
+    let response = req.dispatch();
+    assert!(response
+        .into_string()
+        .unwrap()
+        .contains("Offline contribution in info doesn't match"));
+
+    // Wrong, response to another contribution
+    response_bundle.contribution_id = 2;
+    req = client.post("/contributor/offline/response_bundle");
+    req = set_request::<ResponseBundle>(req, keypair, Some(&response_bundle));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Upload the contribution file
+    req = client.post("/upload/chunk");
+    req = set_request::<u64>(req, keypair, Some(&ROUND_HEIGHT));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let (chunk_url, sig_url): (String, String) = response.into_json().unwrap();
+    let response = reqwest_client.put(chunk_url).body(contribution).send().unwrap();
+    assert!(response.status().is_success());
+    let response = reqwest_client
+        .put(sig_url)
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .body(serde_json::to_vec(&contribution_file_signature).unwrap())
+        .send()
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // Post response bundle and contribution info
+    let response_bundle = ResponseBundle::new(&challenge_bundle, contribution_file_signature);
+    req = client.post("/contributor/offline/response_bundle");
+    req = set_request::<ResponseBundle>(req, keypair, Some(&response_bundle));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    contrib_info.contribution_file_hash = hex::encode(response_hash);
+    contrib_info.offline_contribution = Some(OfflineContributionInfo {
+        challenge_hash: challenge_bundle.challenge_hash.clone(),
+        deadline: chrono::Utc::now(),
+        challenge_bundle_downloaded: chrono::Utc::now(),
+        response_bundle_received: chrono::Utc::now(),
+    });
+    contrib_info.try_sign(keypair).unwrap();
+    req = client.post("/contributor/contribution_info");
+    req = set_request::<ContributionInfo>(req, keypair, Some(&contrib_info));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    // The contribution is recorded as offline in the summary
+    let response = client.get("/contribution_info").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let summary: Vec<TrimmedContributionInfo> = response.into_json().unwrap();
+    assert_eq!(summary.len(), 1);
+    assert!(summary[0].is_offline());
+    assert!(summary[0].is_another_machine());
+
+    // The right-to-left name is published as it was posted
+    assert_eq!(summary[0].full_name(), Some("محمد بن موسى"));
+    assert_eq!(summary[0].language(), Some("ar"));
+}
+
 /// Test a full contribution:
 ///
 /// - get_challenge_url
 /// - get_challenge
 /// - get_contribution_url
 /// - upload_chunk
-/// - post_contributor_info
+/// - post_contributor_info before the contribution
 /// - post_contribution_chunk
+/// - post_contributor_info with a wrong response hash
+/// - post_contributor_info
+/// - get_verification_status
 /// - verify_chunk
 /// - get_contributions_info
 /// - Update cohorts' tokens
@@ -695,14 +2512,19 @@ fn contribution() {
     let ctx = build_context();
     let client = Client::tracked(ctx.rocket).expect("Invalid rocket instance");
     let reqwest_client = reqwest::blocking::Client::new();
-    let start_time = std::time::Instant::now();
 
     // Remove tokens.zip file if present
     std::fs::remove_file(TOKENS_ZIP_FILE).ok();
 
+    // Wrong, challenge of a round other than the locked one
+    let mut req = client.post("/contributor/challenge");
+    req = set_request::<u64>(req, &ctx.contributors[0].keypair, Some(&(ROUND_HEIGHT + 1)));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+
     // Get challenge url
     let _locked_locators = ctx.contributors[0].locked_locators.as_ref().unwrap();
-    let mut req = client.post("/contributor/challenge");
+    req = client.post("/contributor/challenge");
     req = set_request::<u64>(req, &ctx.contributors[0].keypair, Some(&ROUND_HEIGHT));
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
@@ -763,10 +2585,11 @@ fn contribution() {
         .unwrap();
     assert!(response.status().is_success());
 
-    // Post contribution info
+    // Contribution info
     let mut contrib_info = ContributionInfo::default();
-    contrib_info.full_name = Some(String::from("Test Name"));
+    contrib_info.full_name = Some(String::from("山田 太郎"));
     contrib_info.email = Some(String::from("test@mail.dev"));
+    contrib_info.language = Some(String::from("ja"));
     contrib_info.public_key = ctx.contributors[0].keypair.pubkey().to_owned();
     contrib_info.ceremony_round = ctx.contributors[0]
         .locked_locators
@@ -774,13 +2597,23 @@ fn contribution() {
         .unwrap()
         .current_contribution()
         .round_height();
+    contrib_info.contribution_file_hash = hex::encode(response_hash);
     contrib_info.try_sign(&ctx.contributors[0].keypair).unwrap();
 
+    // Wrong, name spoofing the display of the following text
+    let mut wrong_contrib_info = contrib_info.clone();
+    wrong_contrib_info.full_name = Some(String::from("\u{202E}moc.liam@tset"));
+    wrong_contrib_info.try_sign(&ctx.contributors[0].keypair).unwrap();
+    req = client.post("/contributor/contribution_info");
+    req = set_request::<ContributionInfo>(req, &ctx.contributors[0].keypair, Some(&wrong_contrib_info));
+    let response = req.dispatch();
+    assert!(response.into_string().unwrap().contains("forbidden character"));
+
+    // Wrong, contribution info before the contribution
     req = client.post("/contributor/contribution_info");
     req = set_request::<ContributionInfo>(req, &ctx.contributors[0].keypair, Some(&contrib_info));
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::Ok);
-    assert!(response.body().is_none());
+    assert!(response.into_string().unwrap().contains("No contribution received"));
 
     // Contribute
     let post_chunk = PostChunkRequest::new(ROUND_HEIGHT, contribution_locator, contribution_file_signature_locator);
@@ -791,6 +2624,35 @@ fn contribution() {
     assert_eq!(response.status(), Status::Ok);
     assert!(response.body().is_none());
 
+    // Wrong, contribution info referencing another response file
+    let mut wrong_contrib_info = contrib_info.clone();
+    wrong_contrib_info.contribution_file_hash = hex::encode(calculate_hash(b"another response"));
+    wrong_contrib_info.try_sign(&ctx.contributors[0].keypair).unwrap();
+    req = client.post("/contributor/contribution_info");
+    req = set_request::<ContributionInfo>(req, &ctx.contributors[0].keypair, Some(&wrong_contrib_info));
+    let response = req.dispatch();
+    assert!(response
+        .into_string()
+        .unwrap()
+        .contains("doesn't match the contribution received"));
+
+    // Post contribution info
+    req = client.post("/contributor/contribution_info");
+    req = set_request::<ContributionInfo>(req, &ctx.contributors[0].keypair, Some(&contrib_info));
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.body().is_none());
+
+    // Get verification status of the pending contribution
+    req = client.get("/contributor/verification_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<VerificationStatus>().unwrap(),
+        VerificationStatus::Pending
+    );
+
     // Verify chunk
     req = client.get("/verify");
     req = set_request::<()>(req, &ctx.coordinator.keypair, None);
@@ -798,11 +2660,25 @@ fn contribution() {
     assert_eq!(response.status(), Status::Ok);
     assert!(response.body().is_none());
 
+    // Get verification status of the verified contribution
+    req = client.get("/contributor/verification_status");
+    req = set_request::<()>(req, &ctx.contributors[0].keypair, None);
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_json::<VerificationStatus>().unwrap(),
+        VerificationStatus::Succeeded
+    );
+
     // Get contributions info
     req = client.get("/contribution_info");
     let response = req.dispatch();
     assert_eq!(response.status(), Status::Ok);
     assert!(response.body().is_some());
+    assert_eq!(
+        response.content_type().unwrap().to_string(),
+        "application/json; charset=utf-8"
+    );
 
     let summary: Vec<TrimmedContributionInfo> = response.into_json().unwrap();
     assert_eq!(summary.len(), 1);
@@ -810,6 +2686,28 @@ fn contribution() {
     assert!(!summary[0].is_another_machine());
     assert!(!summary[0].is_own_seed_of_randomness());
     assert_eq!(summary[0].ceremony_round(), 1);
+    assert_eq!(summary[0].full_name(), Some("山田 太郎"));
+    assert_eq!(summary[0].language(), Some("ja"));
+
+    // The public transcript doesn't disclose the private contribution info
+    let response = client
+        .get(format!("/transcript?from_round={0}&to_round={0}", ROUND_HEIGHT))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let archive = response.into_bytes().unwrap();
+    let mut offset = 0;
+    let mut names = vec![];
+    while archive[offset..offset + 512].iter().any(|byte| *byte != 0) {
+        let header = &archive[offset..offset + 512];
+        let name = String::from_utf8_lossy(&header[..100]).trim_end_matches('\0').to_owned();
+        let size = u64::from_str_radix(std::str::from_utf8(&header[124..135]).unwrap(), 8).unwrap() as usize;
+        let content = &archive[offset + 512..offset + 512 + size];
+        assert!(!content.windows(b"test@mail.dev".len()).any(|w| w == b"test@mail.dev"));
+        names.push(name);
+        offset += 512 + (size + 511) / 512 * 512;
+    }
+    assert!(names.contains(&format!("round_{}/manifest.json", ROUND_HEIGHT)));
+    assert!(!names.iter().any(|name| name.contains("contributor_info")));
 
     // Update cohorts
     assert!(std::fs::metadata(TOKENS_ZIP_FILE).is_err());
@@ -829,11 +2727,12 @@ fn contribution() {
     let socket_address = SocketAddr::new(ctx.contributors[0].address, 8080);
 
     req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
+    req = set_request::<JoinQueueRequest>(
         req,
         &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP8SP4HrxTh9F86CY5pNWw8RF3jZa91q2i3yvE7ugpn9w2RzoZBZrdskgckmvJuVKq6ZWxfV8TepZYFd9SeARGHexi7tGGV2"),
+            None,
         )),
     );
     let response = req.dispatch();
@@ -844,11 +2743,12 @@ fn contribution() {
     let socket_address = SocketAddr::new(ctx.unknown_participant.address, 8080);
 
     req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
+    req = set_request::<JoinQueueRequest>(
         req,
         &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP7rdLh2njm5ewmCGxSLTW3GYmKP51fKjbRUvHDmntjEaQiq7iFux9tumgWEWVHwHQCs31oitpqBpMWpMydo1DnuFyLpsD6C"
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP7rdLh2njm5ewmCGxSLTW3GYmKP51fKjbRUvHDmntjEaQiq7iFux9tumgWEWVHwHQCs31oitpqBpMWpMydo1DnuFyLpsD6C"),
+            None,
         )),
     );
     let response = req.dispatch();
@@ -856,15 +2756,15 @@ fn contribution() {
     assert!(response.body().is_some());
 
     // Skip to second cohort and try joining the queue with expired token
-    let sleep_time = COHORT_TIME - start_time.elapsed().as_secs();
-    std::thread::sleep(std::time::Duration::from_secs(sleep_time));
+    ctx.time.advance(time::Duration::seconds(COHORT_TIME as i64 + 1));
 
     req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
+    req = set_request::<JoinQueueRequest>(
         req,
         &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP7sQsLG3oS7623phb2Zzc23GAdXjuby4XAbwbWbx1uNaYrZorVLio4ZSt3u95sgi4fsS8hiZ3XkEttBF6q4461dGpoWv7ek"),
+            None,
         )),
     );
     let response = req.dispatch();
@@ -881,7 +2781,7 @@ fn contribution() {
         Some(&(1, String::from("not_a_valid_url"))),
     );
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::InternalServerError);
+    assert_eq!(response.status(), Status::BadRequest);
     assert!(response.body().is_some());
 
     // Wrong round height
@@ -892,7 +2792,7 @@ fn contribution() {
         Some(&(2, String::from("https://namada.net"))),
     );
     let response = req.dispatch();
-    assert_eq!(response.status(), Status::InternalServerError);
+    assert_eq!(response.status(), Status::NotFound);
     assert!(response.body().is_some());
 
     // Try attestation with wrong participant
@@ -917,13 +2817,79 @@ fn contribution() {
     assert_eq!(response.status(), Status::Ok);
     assert!(response.body().is_none());
 
+    // Destruction attestation
+    let keypair = &ctx.contributors[0].keypair;
+    let mut attestation = DestructionAttestation {
+        round_height: 1,
+        contribution_hash: hex::encode(calculate_hash(b"another response")),
+        method: DestructionMethod::ProcessExited,
+        details: None,
+    };
+
+    // Wrong, another contribution
+    let signature = Production
+        .sign(keypair.sigkey(), &attestation.message().unwrap())
+        .unwrap();
+    req = client.post("/contributor/destruction_attestation");
+    req = set_request::<DestructionAttestationRequest>(
+        req,
+        keypair,
+        Some(&DestructionAttestationRequest {
+            attestation: attestation.clone(),
+            signature,
+        }),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Wrong, not signed by the contributor
+    attestation.contribution_hash = contrib_info.contribution_file_hash.clone();
+    let signature = Production
+        .sign(
+            ctx.unknown_participant.keypair.sigkey(),
+            &attestation.message().unwrap(),
+        )
+        .unwrap();
+    req = client.post("/contributor/destruction_attestation");
+    req = set_request::<DestructionAttestationRequest>(
+        req,
+        keypair,
+        Some(&DestructionAttestationRequest {
+            attestation: attestation.clone(),
+            signature,
+        }),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Ok destruction attestation, published in the summary
+    let signature = Production
+        .sign(keypair.sigkey(), &attestation.message().unwrap())
+        .unwrap();
+    req = client.post("/contributor/destruction_attestation");
+    req = set_request::<DestructionAttestationRequest>(
+        req,
+        keypair,
+        Some(&DestructionAttestationRequest {
+            attestation: attestation.clone(),
+            signature,
+        }),
+    );
+    let response = req.dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.get("/contribution_info").dispatch();
+    let summary: Vec<TrimmedContributionInfo> = response.into_json().unwrap();
+    assert_eq!(summary[0].destruction_attestation().unwrap().attestation, attestation);
+
     // Try joining the queue with correct token
     req = client.post("/contributor/join_queue").remote(socket_address);
-    req = set_request::<String>(
+    req = set_request::<JoinQueueRequest>(
         req,
         &ctx.unknown_participant.keypair,
-        Some(&format!(
-            "9nFeNpukSn1eVwNc2vkfP8TAaw6DXNAgCNpxiQc437BxT3iF2xUMdo6wYQjqwxHwAZjVhQzdH3QMpJSbXvaDcnkVu6Ktt22AfYDypK2h72vuQK9fGNp"
+        Some(&JoinQueueRequest::new(
+            String::from("9nFeNpukSn1eVwNc2vkfP8TAaw6DXNAgCNpxiQc437BxT3iF2xUMdo6wYQjqwxHwAZjVhQzdH3QMpJSbXvaDcnkVu6Ktt22AfYDypK2h72vuQK9fGNp"),
+            None,
         )),
     );
     let response = req.dispatch();
@@ -18,4 +18,5 @@ cargo test --test test_coordinator -- --test-threads=1
 # e2e test
 echo "Cleaning S3 bucket for e2e test..."
 aws s3 rm s3://$AWS_S3_BUCKET --recursive
-cargo test --test e2e -- --test-threads=1
+cargo build --bin phase2-coordinator --features parallel
+cargo test --test e2e -- --test-threads=1 --include-ignored